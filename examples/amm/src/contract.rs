@@ -303,6 +303,9 @@ impl Amm {
                     destination: chain_id.into(),
                     authenticated: true,
                     is_skippable: false,
+                    is_tracked: false,
+                    is_acknowledged: false,
+                    grant: Amount::ZERO,
                     message,
                 });
             }
@@ -346,6 +349,9 @@ impl Amm {
                     destination: chain_id.into(),
                     authenticated: true,
                     is_skippable: false,
+                    is_tracked: false,
+                    is_acknowledged: false,
+                    grant: Amount::ZERO,
                     message,
                 });
             }
@@ -165,6 +165,9 @@ impl CrowdFunding {
             destination: chain_id.into(),
             authenticated: true,
             is_skippable: false,
+            is_tracked: false,
+            is_acknowledged: false,
+            grant: Amount::ZERO,
             message,
         });
         Ok(())
@@ -264,6 +264,9 @@ impl MatchingEngine {
             destination: chain_id.into(),
             authenticated: true,
             is_skippable: false,
+            is_tracked: false,
+            is_acknowledged: false,
+            grant: Amount::ZERO,
             message,
         });
         Ok(())
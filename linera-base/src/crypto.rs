@@ -4,7 +4,9 @@
 
 use ed25519_dalek::{self as dalek, Signer, Verifier};
 use generic_array::typenum::Unsigned;
+use k256::ecdsa::{self as secp256k1, signature::DigestVerifier};
 use serde::{Deserialize, Serialize};
+use sha3::{digest::Digest, Keccak256};
 use std::{num::ParseIntError, str::FromStr};
 use thiserror::Error;
 
@@ -20,12 +22,54 @@ use {
     std::ops::RangeInclusive,
 };
 
+/// The length of a compressed secp256k1 public key.
+const SECP256K1_PUBLIC_KEY_LENGTH: usize = 33;
+
+/// The length of a recoverable secp256k1 signature: `r`, `s` and the recovery ID `v`.
+const SECP256K1_SIGNATURE_LENGTH: usize = 65;
+
+/// The prefix of the binary encoding of secp256k1 public keys.
+///
+/// Ed25519 public keys are encoded as their bytes without any tag, like before other signature
+/// schemes were supported, so that the encoding and the hashes of existing data don't change.
+/// This prefix is not the canonical encoding of any Edwards point, so no Ed25519 public key
+/// starts with it.
+const SECP256K1_PUBLIC_KEY_PREFIX: [u8; dalek::PUBLIC_KEY_LENGTH] =
+    [0xff; dalek::PUBLIC_KEY_LENGTH];
+
+/// The prefix of the binary encoding of secp256k1 signatures.
+///
+/// Like public keys, Ed25519 signatures are encoded without any tag. The high bits of the
+/// prefix's last byte are set, which is never the case for the `s` scalar of an Ed25519
+/// signature.
+const SECP256K1_SIGNATURE_PREFIX: [u8; dalek::SIGNATURE_LENGTH] = [0xff; dalek::SIGNATURE_LENGTH];
+
+/// The signature schemes that can be used by chain owners and validators.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Debug, Default)]
+pub enum SignatureScheme {
+    /// EdDSA over Curve25519.
+    #[default]
+    Ed25519,
+    /// ECDSA over secp256k1 with Keccak-256 digests, as used by Ethereum.
+    Secp256k1,
+}
+
 /// A signature key-pair.
-pub struct KeyPair(dalek::Keypair);
+pub struct KeyPair(KeyPairInner);
+
+enum KeyPairInner {
+    Ed25519(dalek::Keypair),
+    Secp256k1(secp256k1::SigningKey),
+}
 
 /// A signature public key.
 #[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash)]
-pub struct PublicKey(pub [u8; dalek::PUBLIC_KEY_LENGTH]);
+pub enum PublicKey {
+    /// An Ed25519 public key.
+    Ed25519([u8; dalek::PUBLIC_KEY_LENGTH]),
+    /// A secp256k1 public key, in compressed SEC1 encoding.
+    Secp256k1([u8; SECP256K1_PUBLIC_KEY_LENGTH]),
+}
 
 type HasherOutputSize = <sha3::Sha3_256 as sha3::digest::OutputSizeUser>::OutputSize;
 type HasherOutput = generic_array::GenericArray<u8, HasherOutputSize>;
@@ -37,7 +81,14 @@ pub struct CryptoHash(HasherOutput);
 
 /// A signature value.
 #[derive(Eq, PartialEq, Copy, Clone)]
-pub struct Signature(pub dalek::Signature);
+pub enum Signature {
+    /// An Ed25519 signature.
+    Ed25519(dalek::Signature),
+    /// A recoverable secp256k1 signature, laid out as `r`, `s` and `v` like in Ethereum.
+    /// Only the canonical form is valid: `s` must be in the lower half of the curve order, and
+    /// `v` must be the recovery ID of the signer's key.
+    Secp256k1([u8; SECP256K1_SIGNATURE_LENGTH]),
+}
 
 #[derive(Error, Debug)]
 /// Error type for cryptographic errors.
@@ -52,19 +103,79 @@ pub enum CryptoError {
     )]
     IncorrectHashSize(usize),
     #[error(
-        "Byte slice has length {0} but a `PublicKey` requires exactly {expected} bytes",
-        expected = dalek::PUBLIC_KEY_LENGTH,
+        "Byte slice has length {0} but a `PublicKey` requires {ed25519} bytes (Ed25519) \
+        or {secp256k1} bytes (secp256k1)",
+        ed25519 = dalek::PUBLIC_KEY_LENGTH,
+        secp256k1 = SECP256K1_PUBLIC_KEY_LENGTH,
     )]
     IncorrectPublicKeySize(usize),
+    #[error(
+        "Byte slice has length {0} but a `Signature` requires {ed25519} bytes (Ed25519) \
+        or {secp256k1} bytes (secp256k1)",
+        ed25519 = dalek::SIGNATURE_LENGTH,
+        secp256k1 = SECP256K1_SIGNATURE_LENGTH,
+    )]
+    IncorrectSignatureSize(usize),
+    #[error("Invalid {scheme:?} key: {error}")]
+    InvalidKey {
+        scheme: SignatureScheme,
+        error: String,
+    },
+    #[error("Unknown signature scheme: {0}")]
+    UnknownSignatureScheme(String),
     #[error("Could not parse integer")]
     ParseIntError(#[from] ParseIntError),
 }
 
+impl SignatureScheme {
+    /// Returns the name of the scheme, as accepted by `FromStr`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SignatureScheme::Ed25519 => "ed25519",
+            SignatureScheme::Secp256k1 => "secp256k1",
+        }
+    }
+}
+
+impl FromStr for SignatureScheme {
+    type Err = CryptoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ed25519" => Ok(SignatureScheme::Ed25519),
+            "secp256k1" => Ok(SignatureScheme::Secp256k1),
+            _ => Err(CryptoError::UnknownSignatureScheme(s.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for SignatureScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 impl PublicKey {
     #[cfg(any(test, feature = "test"))]
     pub fn debug(name: u8) -> PublicKey {
         let addr = [name; dalek::PUBLIC_KEY_LENGTH];
-        PublicKey(addr)
+        PublicKey::Ed25519(addr)
+    }
+
+    /// Returns the signature scheme this key belongs to.
+    pub fn scheme(&self) -> SignatureScheme {
+        match self {
+            PublicKey::Ed25519(_) => SignatureScheme::Ed25519,
+            PublicKey::Secp256k1(_) => SignatureScheme::Secp256k1,
+        }
+    }
+
+    /// Returns the encoding of the key, without the signature scheme.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            PublicKey::Ed25519(bytes) => bytes,
+            PublicKey::Secp256k1(bytes) => bytes,
+        }
     }
 }
 
@@ -88,23 +199,85 @@ impl From<Option<u64>> for Box<dyn CryptoRng> {
 }
 
 impl KeyPair {
-    /// Generates a new key-pair.
+    /// Generates a new Ed25519 key-pair.
     #[cfg(not(target_arch = "wasm32"))]
     pub fn generate() -> Self {
         let mut rng = rand07::rngs::OsRng;
         Self::generate_from(&mut rng)
     }
 
-    /// Generates a new key-pair from the given RNG. Use with care.
+    /// Generates a new Ed25519 key-pair from the given RNG. Use with care.
     #[cfg(not(target_arch = "wasm32"))]
     pub fn generate_from<R: CryptoRng>(rng: &mut R) -> Self {
-        let keypair = dalek::Keypair::generate(rng);
-        KeyPair(keypair)
+        Self::generate_with_scheme_from(SignatureScheme::Ed25519, rng)
+    }
+
+    /// Generates a new key-pair for the given signature scheme.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn generate_with_scheme(scheme: SignatureScheme) -> Self {
+        let mut rng = rand07::rngs::OsRng;
+        Self::generate_with_scheme_from(scheme, &mut rng)
+    }
+
+    /// Generates a new key-pair for the given signature scheme from the given RNG. Use with
+    /// care.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn generate_with_scheme_from<R: CryptoRng>(scheme: SignatureScheme, rng: &mut R) -> Self {
+        match scheme {
+            SignatureScheme::Ed25519 => {
+                KeyPair(KeyPairInner::Ed25519(dalek::Keypair::generate(rng)))
+            }
+            SignatureScheme::Secp256k1 => loop {
+                // Almost all 32-byte strings are valid secret keys.
+                let mut secret = [0u8; 32];
+                rng.fill_bytes(&mut secret);
+                if let Ok(key) = secp256k1::SigningKey::from_slice(&secret) {
+                    break KeyPair(KeyPairInner::Secp256k1(key));
+                }
+            },
+        }
+    }
+
+    /// Creates a key-pair from an existing secret key of the given signature scheme.
+    pub fn from_secret_key(scheme: SignatureScheme, secret: &[u8]) -> Result<Self, CryptoError> {
+        let invalid_key = |error: String| CryptoError::InvalidKey { scheme, error };
+        match scheme {
+            SignatureScheme::Ed25519 => {
+                let secret =
+                    dalek::SecretKey::from_bytes(secret).map_err(|e| invalid_key(e.to_string()))?;
+                let public = dalek::PublicKey::from(&secret);
+                Ok(KeyPair(KeyPairInner::Ed25519(dalek::Keypair {
+                    secret,
+                    public,
+                })))
+            }
+            SignatureScheme::Secp256k1 => {
+                let key = secp256k1::SigningKey::from_slice(secret)
+                    .map_err(|e| invalid_key(e.to_string()))?;
+                Ok(KeyPair(KeyPairInner::Secp256k1(key)))
+            }
+        }
+    }
+
+    /// Returns the signature scheme of the key-pair.
+    pub fn scheme(&self) -> SignatureScheme {
+        match &self.0 {
+            KeyPairInner::Ed25519(_) => SignatureScheme::Ed25519,
+            KeyPairInner::Secp256k1(_) => SignatureScheme::Secp256k1,
+        }
     }
 
     /// Obtains the public key of a key-pair.
     pub fn public(&self) -> PublicKey {
-        PublicKey(self.0.public.to_bytes())
+        match &self.0 {
+            KeyPairInner::Ed25519(keypair) => PublicKey::Ed25519(keypair.public.to_bytes()),
+            KeyPairInner::Secp256k1(key) => {
+                let point = key.verifying_key().to_encoded_point(true);
+                let mut bytes = [0u8; SECP256K1_PUBLIC_KEY_LENGTH];
+                bytes.copy_from_slice(point.as_bytes());
+                PublicKey::Secp256k1(bytes)
+            }
+        }
     }
 
     /// Copies the key-pair, **including the secret key**.
@@ -112,10 +285,125 @@ impl KeyPair {
     /// The `Clone` and `Copy` traits are deliberately not implemented for `KeyPair` to prevent
     /// accidental copies of secret keys.
     pub fn copy(&self) -> KeyPair {
-        KeyPair(dalek::Keypair {
-            secret: dalek::SecretKey::from_bytes(self.0.secret.as_bytes()).unwrap(),
-            public: self.0.public,
-        })
+        match &self.0 {
+            KeyPairInner::Ed25519(keypair) => KeyPair(KeyPairInner::Ed25519(dalek::Keypair {
+                secret: dalek::SecretKey::from_bytes(keypair.secret.as_bytes()).unwrap(),
+                public: keypair.public,
+            })),
+            KeyPairInner::Secp256k1(key) => KeyPair(KeyPairInner::Secp256k1(key.clone())),
+        }
+    }
+}
+
+/// (De)serializes the binary encoding of public keys and signatures: a newtype struct around
+/// a tuple of bytes, which are either the bytes of an Ed25519 value, or a prefix followed by
+/// the bytes of a secp256k1 value.
+mod prefixed_bytes {
+    use serde::{
+        de::{Error, SeqAccess, Visitor},
+        ser::SerializeTuple,
+        Deserializer, Serialize, Serializer,
+    };
+
+    struct Bytes<'a> {
+        prefix: &'a [u8],
+        bytes: &'a [u8],
+    }
+
+    impl Serialize for Bytes<'_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut tuple = serializer.serialize_tuple(self.prefix.len() + self.bytes.len())?;
+            for byte in self.prefix.iter().chain(self.bytes) {
+                tuple.serialize_element(byte)?;
+            }
+            tuple.end()
+        }
+    }
+
+    /// Serializes the `bytes` after the `prefix`, which is empty for Ed25519 values.
+    pub fn serialize<S>(
+        name: &'static str,
+        prefix: &[u8],
+        bytes: &[u8],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(name, &Bytes { prefix, bytes })
+    }
+
+    /// Deserializes either `prefix.len()` bytes that differ from the `prefix`, or the
+    /// `prefixed_length` bytes that follow it.
+    pub fn deserialize<'de, D>(
+        name: &'static str,
+        prefix: &'static [u8],
+        prefixed_length: usize,
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct BytesVisitor {
+            prefix: &'static [u8],
+            prefixed_length: usize,
+        }
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(
+                    formatter,
+                    "{} bytes, or a prefix followed by {} bytes",
+                    self.prefix.len(),
+                    self.prefixed_length
+                )
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                // Non-self-describing formats don't record the length of tuples, so only the
+                // bytes that are actually present are read.
+                let length = self.prefix.len() + self.prefixed_length;
+                deserializer.deserialize_tuple(length, self)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut bytes = Vec::with_capacity(self.prefix.len());
+                for index in 0..self.prefix.len() {
+                    let byte = seq
+                        .next_element()?
+                        .ok_or_else(|| A::Error::invalid_length(index, &self))?;
+                    bytes.push(byte);
+                }
+                if bytes != self.prefix {
+                    return Ok(bytes);
+                }
+                bytes.clear();
+                for index in 0..self.prefixed_length {
+                    let byte = seq.next_element()?.ok_or_else(|| {
+                        A::Error::invalid_length(self.prefix.len() + index, &self)
+                    })?;
+                    bytes.push(byte);
+                }
+                Ok(bytes)
+            }
+        }
+
+        let visitor = BytesVisitor {
+            prefix,
+            prefixed_length,
+        };
+        deserializer.deserialize_newtype_struct(name, visitor)
     }
 }
 
@@ -127,7 +415,14 @@ impl Serialize for PublicKey {
         if serializer.is_human_readable() {
             serializer.serialize_str(&self.to_string())
         } else {
-            serializer.serialize_newtype_struct("PublicKey", &self.0)
+            let prefix: &[u8] = match self {
+                PublicKey::Ed25519(bytes) if *bytes == SECP256K1_PUBLIC_KEY_PREFIX => {
+                    return Err(serde::ser::Error::custom("Invalid Ed25519 public key"));
+                }
+                PublicKey::Ed25519(_) => &[],
+                PublicKey::Secp256k1(_) => &SECP256K1_PUBLIC_KEY_PREFIX,
+            };
+            prefixed_bytes::serialize("PublicKey", prefix, self.as_bytes(), serializer)
         }
     }
 }
@@ -142,12 +437,13 @@ impl<'de> Deserialize<'de> for PublicKey {
             let value = Self::from_str(&s).map_err(serde::de::Error::custom)?;
             Ok(value)
         } else {
-            #[derive(Deserialize)]
-            #[serde(rename = "PublicKey")]
-            struct Foo([u8; dalek::PUBLIC_KEY_LENGTH]);
-
-            let value = Foo::deserialize(deserializer)?;
-            Ok(Self(value.0))
+            let bytes = prefixed_bytes::deserialize(
+                "PublicKey",
+                &SECP256K1_PUBLIC_KEY_PREFIX,
+                SECP256K1_PUBLIC_KEY_LENGTH,
+                deserializer,
+            )?;
+            PublicKey::try_from(bytes.as_slice()).map_err(serde::de::Error::custom)
         }
     }
 }
@@ -185,6 +481,23 @@ impl<'de> Deserialize<'de> for CryptoHash {
     }
 }
 
+/// The JSON representation of a [`KeyPair`], tagged with its signature scheme: the secret
+/// and public key for Ed25519, and only the secret key for secp256k1.
+#[derive(Serialize, Deserialize)]
+enum KeyPairRepr {
+    Ed25519(String),
+    Secp256k1(String),
+}
+
+/// The JSON representations of a [`KeyPair`] that can be read: untagged key-pairs are Ed25519
+/// key-pairs written before other signature schemes were supported.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AnyKeyPairRepr {
+    Tagged(KeyPairRepr),
+    Untagged(String),
+}
+
 impl Serialize for KeyPair {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -192,7 +505,11 @@ impl Serialize for KeyPair {
     {
         // This is only used for JSON configuration.
         assert!(serializer.is_human_readable());
-        serializer.serialize_str(&hex::encode(self.0.to_bytes()))
+        let repr = match &self.0 {
+            KeyPairInner::Ed25519(keypair) => KeyPairRepr::Ed25519(hex::encode(keypair.to_bytes())),
+            KeyPairInner::Secp256k1(key) => KeyPairRepr::Secp256k1(hex::encode(key.to_bytes())),
+        };
+        repr.serialize(serializer)
     }
 }
 
@@ -203,10 +520,22 @@ impl<'de> Deserialize<'de> for KeyPair {
     {
         // This is only used for JSON configuration.
         assert!(deserializer.is_human_readable());
-        let s = String::deserialize(deserializer)?;
-        let value = hex::decode(s).map_err(serde::de::Error::custom)?;
-        let key = dalek::Keypair::from_bytes(&value).map_err(serde::de::Error::custom)?;
-        Ok(KeyPair(key))
+        let repr = match AnyKeyPairRepr::deserialize(deserializer)? {
+            AnyKeyPairRepr::Tagged(repr) => repr,
+            AnyKeyPairRepr::Untagged(s) => KeyPairRepr::Ed25519(s),
+        };
+        match repr {
+            KeyPairRepr::Ed25519(s) => {
+                let value = hex::decode(s).map_err(serde::de::Error::custom)?;
+                let key = dalek::Keypair::from_bytes(&value).map_err(serde::de::Error::custom)?;
+                Ok(KeyPair(KeyPairInner::Ed25519(key)))
+            }
+            KeyPairRepr::Secp256k1(s) => {
+                let value = hex::decode(s).map_err(serde::de::Error::custom)?;
+                KeyPair::from_secret_key(SignatureScheme::Secp256k1, &value)
+                    .map_err(serde::de::Error::custom)
+            }
+        }
     }
 }
 
@@ -216,9 +545,13 @@ impl Serialize for Signature {
         S: serde::ser::Serializer,
     {
         if serializer.is_human_readable() {
-            serializer.serialize_str(&hex::encode(self.0.to_bytes()))
+            serializer.serialize_str(&hex::encode(self.to_bytes()))
         } else {
-            serializer.serialize_newtype_struct("Signature", &self.0)
+            let prefix: &[u8] = match self {
+                Signature::Ed25519(_) => &[],
+                Signature::Secp256k1(_) => &SECP256K1_SIGNATURE_PREFIX,
+            };
+            prefixed_bytes::serialize("Signature", prefix, &self.to_bytes(), serializer)
         }
     }
 }
@@ -231,20 +564,25 @@ impl<'de> Deserialize<'de> for Signature {
         if deserializer.is_human_readable() {
             let s = String::deserialize(deserializer)?;
             let value = hex::decode(s).map_err(serde::de::Error::custom)?;
-            let sig =
-                dalek::Signature::try_from(value.as_slice()).map_err(serde::de::Error::custom)?;
-            Ok(Signature(sig))
+            Signature::try_from(value.as_slice()).map_err(serde::de::Error::custom)
         } else {
-            #[derive(Deserialize)]
-            #[serde(rename = "Signature")]
-            struct Foo(dalek::Signature);
-
-            let value = Foo::deserialize(deserializer)?;
-            Ok(Self(value.0))
+            let bytes = prefixed_bytes::deserialize(
+                "Signature",
+                &SECP256K1_SIGNATURE_PREFIX,
+                SECP256K1_SIGNATURE_LENGTH,
+                deserializer,
+            )?;
+            Signature::try_from(bytes.as_slice()).map_err(serde::de::Error::custom)
         }
     }
 }
 
+impl std::hash::Hash for Signature {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.to_bytes().hash(state);
+    }
+}
+
 impl FromStr for PublicKey {
     type Err = CryptoError;
 
@@ -258,12 +596,55 @@ impl TryFrom<&[u8]> for PublicKey {
     type Error = CryptoError;
 
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
-        if value.len() != dalek::PUBLIC_KEY_LENGTH {
-            return Err(CryptoError::IncorrectPublicKeySize(value.len()));
+        match value.len() {
+            dalek::PUBLIC_KEY_LENGTH => {
+                if value == SECP256K1_PUBLIC_KEY_PREFIX {
+                    return Err(CryptoError::InvalidKey {
+                        scheme: SignatureScheme::Ed25519,
+                        error: "not the canonical encoding of a point".to_string(),
+                    });
+                }
+                let mut pubkey = [0u8; dalek::PUBLIC_KEY_LENGTH];
+                pubkey.copy_from_slice(value);
+                Ok(PublicKey::Ed25519(pubkey))
+            }
+            SECP256K1_PUBLIC_KEY_LENGTH => {
+                secp256k1::VerifyingKey::from_sec1_bytes(value).map_err(|error| {
+                    CryptoError::InvalidKey {
+                        scheme: SignatureScheme::Secp256k1,
+                        error: error.to_string(),
+                    }
+                })?;
+                let mut pubkey = [0u8; SECP256K1_PUBLIC_KEY_LENGTH];
+                pubkey.copy_from_slice(value);
+                Ok(PublicKey::Secp256k1(pubkey))
+            }
+            length => Err(CryptoError::IncorrectPublicKeySize(length)),
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for Signature {
+    type Error = CryptoError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        match value.len() {
+            dalek::SIGNATURE_LENGTH => {
+                let signature = dalek::Signature::try_from(value).map_err(|error| {
+                    CryptoError::InvalidSignature {
+                        error: error.to_string(),
+                        type_name: "Signature".to_string(),
+                    }
+                })?;
+                Ok(Signature::Ed25519(signature))
+            }
+            SECP256K1_SIGNATURE_LENGTH => {
+                let mut bytes = [0u8; SECP256K1_SIGNATURE_LENGTH];
+                bytes.copy_from_slice(value);
+                Ok(Signature::Secp256k1(bytes))
+            }
+            length => Err(CryptoError::IncorrectSignatureSize(length)),
         }
-        let mut pubkey = [0u8; dalek::PUBLIC_KEY_LENGTH];
-        pubkey.copy_from_slice(value);
-        Ok(PublicKey(pubkey))
     }
 }
 
@@ -318,14 +699,14 @@ impl From<CryptoHash> for [u64; 4] {
 
 impl std::fmt::Display for Signature {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        let s = hex::encode(self.0.to_bytes());
+        let s = hex::encode(self.to_bytes());
         write!(f, "{}", s)
     }
 }
 
 impl std::fmt::Display for PublicKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", hex::encode(&self.0[..]))
+        write!(f, "{}", hex::encode(self.as_bytes()))
     }
 }
 
@@ -338,13 +719,13 @@ impl std::fmt::Display for CryptoHash {
 
 impl std::fmt::Debug for Signature {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        write!(f, "{}", hex::encode(&self.0.to_bytes()[0..8]))
+        write!(f, "{}", hex::encode(&self.to_bytes()[0..8]))
     }
 }
 
 impl std::fmt::Debug for PublicKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        write!(f, "{}", hex::encode(&self.0[..8]))
+        write!(f, "{}", hex::encode(&self.as_bytes()[..8]))
     }
 }
 
@@ -412,8 +793,6 @@ impl CryptoHash {
     where
         T: BcsHashable,
     {
-        use sha3::digest::Digest;
-
         let mut hasher = sha3::Sha3_256::default();
         value.write(&mut hasher);
         CryptoHash(hasher.finalize())
@@ -431,47 +810,113 @@ impl Signature {
     {
         let mut message = Vec::new();
         value.write(&mut message);
-        let signature = secret.0.sign(&message);
-        Signature(signature)
+        match &secret.0 {
+            KeyPairInner::Ed25519(keypair) => Signature::Ed25519(keypair.sign(&message)),
+            KeyPairInner::Secp256k1(key) => {
+                let (signature, recovery_id) = key
+                    .sign_digest_recoverable(Keccak256::new_with_prefix(&message))
+                    .expect("Signing with a valid secp256k1 key should not fail");
+                let mut bytes = [0u8; SECP256K1_SIGNATURE_LENGTH];
+                bytes[..64].copy_from_slice(&signature.to_bytes());
+                bytes[64] = recovery_id.to_byte();
+                Signature::Secp256k1(bytes)
+            }
+        }
     }
 
-    fn check_internal<T>(&self, value: &T, author: PublicKey) -> Result<(), dalek::SignatureError>
-    where
-        T: BcsSignable,
-    {
-        let mut message = Vec::new();
-        value.write(&mut message);
-        let public_key = dalek::PublicKey::from_bytes(&author.0)?;
-        public_key.verify(&message, &self.0)
+    /// Returns the signature scheme of the signature.
+    pub fn scheme(&self) -> SignatureScheme {
+        match self {
+            Signature::Ed25519(_) => SignatureScheme::Ed25519,
+            Signature::Secp256k1(_) => SignatureScheme::Secp256k1,
+        }
+    }
+
+    /// Returns the encoding of the signature, without the signature scheme.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Signature::Ed25519(signature) => signature.to_bytes().to_vec(),
+            Signature::Secp256k1(bytes) => bytes.to_vec(),
+        }
+    }
+
+    fn check_internal(&self, message: &[u8], author: PublicKey) -> Result<(), String> {
+        match (self, author) {
+            (Signature::Ed25519(signature), PublicKey::Ed25519(bytes)) => {
+                let public_key = dalek::PublicKey::from_bytes(&bytes).map_err(|e| e.to_string())?;
+                public_key
+                    .verify(message, signature)
+                    .map_err(|e| e.to_string())
+            }
+            (Signature::Secp256k1(bytes), PublicKey::Secp256k1(key)) => {
+                let public_key =
+                    secp256k1::VerifyingKey::from_sec1_bytes(&key).map_err(|e| e.to_string())?;
+                let signature =
+                    secp256k1::Signature::from_slice(&bytes[..64]).map_err(|e| e.to_string())?;
+                // Reject the other encodings of the same signature, so that signatures, and the
+                // hashes of the values containing them, cannot be altered.
+                if signature.normalize_s().is_some() {
+                    return Err("secp256k1 signature must have a low `s` value".to_string());
+                }
+                let digest = Keccak256::new_with_prefix(message);
+                public_key
+                    .verify_digest(digest.clone(), &signature)
+                    .map_err(|e| e.to_string())?;
+                let recovery_id = secp256k1::RecoveryId::from_byte(bytes[64])
+                    .ok_or_else(|| format!("invalid secp256k1 recovery ID {}", bytes[64]))?;
+                let recovered =
+                    secp256k1::VerifyingKey::recover_from_digest(digest, &signature, recovery_id)
+                        .map_err(|e| e.to_string())?;
+                if recovered != public_key {
+                    return Err(format!(
+                        "secp256k1 recovery ID {} does not match the public key",
+                        bytes[64]
+                    ));
+                }
+                Ok(())
+            }
+            (signature, author) => Err(format!(
+                "{} signature cannot be checked with a {} public key",
+                signature.scheme(),
+                author.scheme()
+            )),
+        }
     }
 
     pub fn check<T>(&self, value: &T, author: PublicKey) -> Result<(), CryptoError>
     where
         T: BcsSignable + std::fmt::Debug,
     {
-        self.check_internal(value, author)
+        let mut message = Vec::new();
+        value.write(&mut message);
+        self.check_internal(&message, author)
             .map_err(|error| CryptoError::InvalidSignature {
-                error: error.to_string(),
+                error,
                 type_name: T::type_name().to_string(),
             })
     }
 
-    fn verify_batch_internal<'a, T, I>(value: &'a T, votes: I) -> Result<(), dalek::SignatureError>
+    fn verify_batch_internal<'a, I>(message: &[u8], votes: I) -> Result<(), String>
     where
-        T: BcsSignable,
         I: IntoIterator<Item = (&'a PublicKey, &'a Signature)>,
     {
-        let mut msg = Vec::new();
-        value.write(&mut msg);
         let mut messages: Vec<&[u8]> = Vec::new();
         let mut signatures: Vec<dalek::Signature> = Vec::new();
         let mut public_keys: Vec<dalek::PublicKey> = Vec::new();
         for (addr, sig) in votes.into_iter() {
-            messages.push(&msg);
-            signatures.push(sig.0);
-            public_keys.push(dalek::PublicKey::from_bytes(&addr.0)?);
+            match (sig, addr) {
+                (Signature::Ed25519(signature), PublicKey::Ed25519(bytes)) => {
+                    messages.push(message);
+                    signatures.push(*signature);
+                    public_keys
+                        .push(dalek::PublicKey::from_bytes(bytes).map_err(|e| e.to_string())?);
+                }
+                // Only Ed25519 supports batch verification.
+                (sig, addr) => sig.check_internal(message, *addr)?,
+            }
         }
         dalek::verify_batch(&messages[..], &signatures[..], &public_keys[..])
+            .map_err(|e| e.to_string())
     }
 
     pub fn verify_batch<'a, T, I>(value: &'a T, votes: I) -> Result<(), CryptoError>
@@ -479,7 +924,9 @@ impl Signature {
         T: BcsSignable,
         I: IntoIterator<Item = (&'a PublicKey, &'a Signature)>,
     {
-        Signature::verify_batch_internal(value, votes).map_err(|error| {
+        let mut message = Vec::new();
+        value.write(&mut message);
+        Signature::verify_batch_internal(&message, votes).map_err(|error| {
             CryptoError::InvalidSignature {
                 error: format!("batched {}", error),
                 type_name: T::type_name().to_string(),
@@ -534,3 +981,164 @@ fn test_signatures() {
     assert!(s.check(&foox, addr1).is_err());
     assert!(s.check(&bar, addr1).is_err());
 }
+
+#[test]
+#[allow(clippy::disallowed_names)]
+fn test_secp256k1_signatures() {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Foo(String);
+
+    impl BcsSignable for Foo {}
+
+    let key1 = KeyPair::generate_with_scheme(SignatureScheme::Secp256k1);
+    let addr1 = key1.public();
+    let key2 = KeyPair::generate_with_scheme(SignatureScheme::Secp256k1);
+    let addr2 = key2.public();
+    let key3 = KeyPair::generate();
+    let addr3 = key3.public();
+    assert_eq!(addr1.scheme(), SignatureScheme::Secp256k1);
+    assert_eq!(addr3.scheme(), SignatureScheme::Ed25519);
+
+    let foo = Foo("hello".into());
+    let foox = Foo("hellox".into());
+
+    let s = Signature::new(&foo, &key1);
+    assert!(s.check(&foo, addr1).is_ok());
+    assert!(s.check(&foo, addr2).is_err());
+    assert!(s.check(&foox, addr1).is_err());
+    assert!(s.check(&foo, addr3).is_err());
+
+    // Ed25519 and secp256k1 signatures can be verified in the same batch.
+    let s3 = Signature::new(&foo, &key3);
+    assert!(Signature::verify_batch(&foo, [(&addr1, &s), (&addr3, &s3)]).is_ok());
+    assert!(Signature::verify_batch(&foo, [(&addr2, &s), (&addr3, &s3)]).is_err());
+
+    // Keys and signatures survive both human-readable and binary serialization.
+    assert_eq!(PublicKey::from_str(&addr1.to_string()).unwrap(), addr1);
+    assert_eq!(
+        bcs::from_bytes::<PublicKey>(&bcs::to_bytes(&addr1).unwrap()).unwrap(),
+        addr1
+    );
+    assert_eq!(
+        bcs::from_bytes::<Signature>(&bcs::to_bytes(&s).unwrap()).unwrap(),
+        s
+    );
+    let json = serde_json::to_string(&s).unwrap();
+    assert_eq!(serde_json::from_str::<Signature>(&json).unwrap(), s);
+    let json = serde_json::to_string(&key1).unwrap();
+    let key1_copy = serde_json::from_str::<KeyPair>(&json).unwrap();
+    assert_eq!(key1_copy.public(), addr1);
+}
+
+#[test]
+#[allow(clippy::disallowed_names)]
+fn test_secp256k1_signature_malleability() {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Foo(String);
+
+    impl BcsSignable for Foo {}
+
+    let key = KeyPair::generate_with_scheme(SignatureScheme::Secp256k1);
+    let foo = Foo("hello".into());
+    let Signature::Secp256k1(bytes) = Signature::new(&foo, &key) else {
+        panic!("expected a secp256k1 signature");
+    };
+    assert!(Signature::Secp256k1(bytes)
+        .check(&foo, key.public())
+        .is_ok());
+
+    // Any other recovery ID is rejected.
+    for v in (0..=u8::MAX).filter(|v| *v != bytes[64]) {
+        let mut other_bytes = bytes;
+        other_bytes[64] = v;
+        assert!(Signature::Secp256k1(other_bytes)
+            .check(&foo, key.public())
+            .is_err());
+    }
+
+    // So is the high-`s` form of the same signature, with either recovery ID.
+    let signature = secp256k1::Signature::from_slice(&bytes[..64]).unwrap();
+    let (r, s) = signature.split_scalars();
+    let high_s = secp256k1::Signature::from_scalars(r, -s).unwrap();
+    let mut other_bytes = bytes;
+    other_bytes[..64].copy_from_slice(&high_s.to_bytes());
+    for v in [0, 1] {
+        other_bytes[64] = v;
+        assert!(Signature::Secp256k1(other_bytes)
+            .check(&foo, key.public())
+            .is_err());
+    }
+}
+
+#[test]
+#[allow(clippy::disallowed_names)]
+fn test_ed25519_encodings_are_untagged() {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Foo(String);
+
+    impl BcsSignable for Foo {}
+
+    let key = KeyPair::generate();
+    let PublicKey::Ed25519(key_bytes) = key.public() else {
+        panic!("expected an Ed25519 key");
+    };
+    let signature = Signature::new(&Foo("hello".into()), &key);
+    let Signature::Ed25519(dalek_signature) = signature else {
+        panic!("expected an Ed25519 signature");
+    };
+
+    // Ed25519 keys and signatures are encoded like in earlier versions.
+    let bytes = bcs::to_bytes(&key.public()).unwrap();
+    assert_eq!(bytes, bcs::to_bytes(&key_bytes).unwrap());
+    assert_eq!(bcs::from_bytes::<PublicKey>(&bytes).unwrap(), key.public());
+    let bytes = bcs::to_bytes(&signature).unwrap();
+    assert_eq!(bytes, bcs::to_bytes(&dalek_signature).unwrap());
+    assert_eq!(bcs::from_bytes::<Signature>(&bytes).unwrap(), signature);
+
+    // So the keys are hashed like in earlier versions.
+    let mut hasher = sha3::Sha3_256::default();
+    hasher.update(b"PublicKey::");
+    hasher.update(key_bytes);
+    assert_eq!(
+        CryptoHash::new(&key.public()),
+        CryptoHash(hasher.finalize())
+    );
+
+    // Encoded secp256k1 keys and signatures follow a prefix, and are read back within a
+    // larger value.
+    let key = KeyPair::generate_with_scheme(SignatureScheme::Secp256k1);
+    let signature = Signature::new(&Foo("hello".into()), &key);
+    let value = (key.public(), signature, 42u8);
+    let bytes = bcs::to_bytes(&value).unwrap();
+    assert_eq!(bytes[..32], SECP256K1_PUBLIC_KEY_PREFIX);
+    assert_eq!(
+        bcs::from_bytes::<(PublicKey, Signature, u8)>(&bytes).unwrap(),
+        value
+    );
+
+    // The prefix is not accepted as an Ed25519 key.
+    assert!(PublicKey::try_from(&SECP256K1_PUBLIC_KEY_PREFIX[..]).is_err());
+    assert!(bcs::to_bytes(&PublicKey::Ed25519(SECP256K1_PUBLIC_KEY_PREFIX)).is_err());
+}
+
+#[test]
+fn test_key_pair_json() {
+    for scheme in [SignatureScheme::Ed25519, SignatureScheme::Secp256k1] {
+        let key = KeyPair::generate_with_scheme(scheme);
+        let json = serde_json::to_value(&key).unwrap();
+        assert!(json.get(format!("{scheme:?}")).is_some());
+        let read_key = serde_json::from_value::<KeyPair>(json).unwrap();
+        assert_eq!(read_key.public(), key.public());
+    }
+
+    // Untagged key-pairs are Ed25519 key-pairs of earlier versions.
+    let key = KeyPair::generate();
+    let KeyPairInner::Ed25519(keypair) = &key.0 else {
+        panic!("expected an Ed25519 key-pair");
+    };
+    let json = serde_json::Value::String(hex::encode(keypair.to_bytes()));
+    let read_key = serde_json::from_value::<KeyPair>(json).unwrap();
+    assert_eq!(read_key.public(), key.public());
+    let json = serde_json::Value::String(hex::encode([1u8; 32]));
+    assert!(serde_json::from_value::<KeyPair>(json).is_err());
+}
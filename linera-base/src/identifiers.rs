@@ -452,6 +452,41 @@ impl<A> Debug for SessionId<A> {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "SessionId")]
+struct SerializableSessionId {
+    pub application_id: ApplicationId,
+    pub index: u64,
+}
+
+impl<A> Serialize for SessionId<A> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        SerializableSessionId::serialize(
+            &SerializableSessionId {
+                application_id: self.application_id.forget_abi(),
+                index: self.index,
+            },
+            serializer,
+        )
+    }
+}
+
+impl<'de, A> Deserialize<'de> for SessionId<A> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        let value = SerializableSessionId::deserialize(deserializer)?;
+        Ok(SessionId {
+            application_id: value.application_id.with_abi(),
+            index: value.index,
+        })
+    }
+}
+
 impl SessionId {
     pub fn with_abi<A>(self) -> SessionId<A> {
         SessionId {
@@ -484,7 +519,7 @@ impl From<PublicKey> for Owner {
 
 impl From<&PublicKey> for Owner {
     fn from(value: &PublicKey) -> Self {
-        Self(CryptoHash::new(value))
+        Self::from(*value)
     }
 }
 
@@ -567,7 +602,8 @@ doc_scalar!(
 
 #[cfg(test)]
 mod tests {
-    use super::ChainId;
+    use super::{ChainId, Owner};
+    use crate::crypto::PublicKey;
 
     /// Verifies that chain IDs that are explicitly used in some example and test scripts don't
     /// change.
@@ -586,4 +622,14 @@ mod tests {
             "9c8a838e8f7b63194f6c7585455667a8379d2b5db19a3300e9961f0b1e9091ea"
         );
     }
+
+    /// Verifies that the owners of Ed25519 keys are the same as before other signature schemes
+    /// were supported.
+    #[test]
+    fn ed25519_owners() {
+        assert_eq!(
+            &Owner::from(PublicKey::debug(1)).to_string(),
+            "223c6633dc798a3fcc7478ad62ea9d71d0da7978de000a82ac94de3c14d1577b"
+        );
+    }
 }
@@ -3,44 +3,57 @@
 
 use crate::{
     data_types::{
-        Block, BlockExecutionOutcome, ChainAndHeight, ChannelFullName, Event, IncomingMessage,
-        Medium, Origin, OutgoingMessage, Target,
+        ApplicationEvent, Block, BlockExecutionOutcome, ChainAndHeight, ChannelFullName,
+        Checkpoint, CheckpointChunk, Event, FailedOperation, IncomingMessage, Medium,
+        MessageAction, Origin, OutgoingMessage, Target, Transaction,
     },
-    inbox::{InboxError, InboxStateView},
+    inbox::{Cursor, InboxStateView, RemovedEvents},
     outbox::OutboxStateView,
     ChainError, ChainExecutionContext, ChainManager,
 };
 use async_graphql::SimpleObject;
+use async_lock::Mutex;
 use futures::stream::{self, StreamExt, TryStreamExt};
 use linera_base::{
-    crypto::CryptoHash,
+    crypto::{BcsHashable, CryptoHash},
     data_types::{Amount, ArithmeticError, BlockHeight, Timestamp},
     ensure,
-    identifiers::{ChainId, Destination, MessageId},
+    identifiers::{ChainId, Destination, MessageId, Owner},
 };
 use linera_execution::{
+    policy::ResourceControlPolicy,
     sub_assign_fees,
     system::{Account, SystemMessage},
-    ExecutionResult, ExecutionRuntimeContext, ExecutionStateView, GenericApplicationId, Message,
-    MessageContext, OperationContext, Query, QueryContext, RawExecutionResult, RawOutgoingMessage,
-    ResourceTracker, Response, UserApplicationDescription, UserApplicationId,
+    trace::{ExecutionTrace, TraceEvent},
+    ExecutionError, ExecutionResult, ExecutionRuntimeContext, ExecutionStateView,
+    GenericApplicationId, Message, MessageContext, MessageKind, MessageOutcome, OperationContext,
+    Query, QueryContext, RawExecutionResult, RawOutgoingMessage, ResourceTracker, Response,
+    UserApplicationDescription, UserApplicationId,
 };
 use linera_views::{
-    common::Context,
+    batch::Batch,
+    common::{Context, HasherOutput, KeyValueIterable, MIN_VIEW_TAG},
     log_view::LogView,
+    map_view::MapView,
+    memory::MemoryContext,
     reentrant_collection_view::ReentrantCollectionView,
     register_view::RegisterView,
     set_view::SetView,
-    views::{CryptoHashView, GraphQLView, RootView, View, ViewError},
+    views::{ClonableView, CryptoHashView, GraphQLView, HashableView, RootView, View, ViewError},
 };
 use once_cell::sync::Lazy;
 use prometheus::{register_histogram_vec, register_int_counter_vec, HistogramVec, IntCounterVec};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashSet},
+    sync::Arc,
     time::Instant,
 };
 
+#[cfg(test)]
+#[path = "unit_tests/chain_tests.rs"]
+mod chain_tests;
+
 pub static NUM_BLOCKS_EXECUTED: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "num_blocks_executed",
@@ -98,8 +111,14 @@ pub struct ChainStateView<C> {
     /// Number of outgoing messages in flight for each block height.
     /// We use a `RegisterView` to prioritize speed for small maps.
     pub outbox_counters: RegisterView<C, BTreeMap<BlockHeight, u32>>,
+    /// The timestamp of each block height in `outbox_counters`, to abandon the messages that
+    /// expire.
+    pub outbox_timestamps: RegisterView<C, BTreeMap<BlockHeight, Timestamp>>,
     /// Channels able to multicast messages to subscribers.
     pub channels: ReentrantCollectionView<C, ChannelFullName, ChannelStateView<C>>,
+    /// For each inbox, the number of events that cannot be skipped that the blocks removed,
+    /// as far back as needed to accept or refuse the next received events.
+    pub removed_inbox_events: MapView<C, Origin, Vec<RemovedEvents>>,
 }
 
 /// Block-chaining state.
@@ -142,8 +161,57 @@ impl ChainTipState {
     }
 }
 
+/// The number of concurrent queries to the in-memory storage of a chain restored from a
+/// checkpoint, before it is checked.
+const CHECKPOINT_MAX_STREAM_QUERIES: usize = 10;
+
+/// The hashes of the logical state of the views that a checkpoint commits to, besides the
+/// execution state and the tip.
+#[derive(Serialize, Deserialize)]
+struct CheckpointChainStateHashes {
+    confirmed_log: HasherOutput,
+    outboxes: HasherOutput,
+    outbox_counters: HasherOutput,
+    outbox_timestamps: HasherOutput,
+    channels: HasherOutput,
+    removed_inbox_events: HasherOutput,
+}
+
+impl BcsHashable for CheckpointChainStateHashes {}
+
+/// The indices in `ChainStateView` of the execution state, its hash, the tip state, the
+/// confirmed log, the outboxes, the outbox counters and timestamps, the channels, and the
+/// events removed from the inboxes.
+const CHECKPOINT_FIELD_INDICES: [i32; 9] = [0, 1, 2, 4, 7, 8, 9, 10, 11];
+
+/// Returns the suffix that the derived `View::load` appends to the chain's base key for the
+/// field with the given index.
+fn checkpoint_field_suffix(index: i32) -> Vec<u8> {
+    let mut suffix = vec![MIN_VIEW_TAG];
+    suffix.extend(index.to_le_bytes());
+    suffix
+}
+
+/// Loads a chain from the given chunks in memory, without touching the storage.
+async fn load_checkpoint_chunks<E>(
+    extra: E,
+    chunks: &[CheckpointChunk],
+) -> Result<ChainStateView<MemoryContext<E>>, ViewError>
+where
+    E: ExecutionRuntimeContext + Clone + Send + Sync + 'static,
+{
+    let guard = Arc::new(Mutex::new(BTreeMap::new())).lock_arc().await;
+    let context = MemoryContext::new(guard, CHECKPOINT_MAX_STREAM_QUERIES, extra);
+    let mut batch = Batch::new();
+    for (key, value) in chunks.iter().flat_map(|chunk| &chunk.entries) {
+        batch.put_key_value_bytes(key.clone(), value.clone());
+    }
+    context.write_batch(batch).await?;
+    ChainStateView::load(context).await
+}
+
 /// The state of a channel followed by subscribers.
-#[derive(Debug, View, GraphQLView)]
+#[derive(Debug, HashableView, GraphQLView)]
 pub struct ChannelStateView<C> {
     /// The current subscribers.
     pub subscribers: SetView<C, ChainId>,
@@ -209,6 +277,7 @@ where
             if *counter == 0 {
                 // Important for the test in `all_messages_delivered_up_to`.
                 self.outbox_counters.get_mut().remove(&update);
+                self.outbox_timestamps.get_mut().remove(&update);
             }
         }
         if outbox.queue.count() == 0 {
@@ -320,6 +389,8 @@ where
                 destination,
                 authenticated_signer,
                 is_skippable,
+                kind,
+                grant,
                 message,
             } = outgoing_message;
             // Skip events that do not belong to this origin OR have no effect on this
@@ -357,6 +428,8 @@ where
                 index,
                 authenticated_signer,
                 is_skippable,
+                kind,
+                grant,
                 timestamp,
                 message,
             });
@@ -374,13 +447,15 @@ where
         // Process the inbox events and update the inbox state.
         let mut inbox = self.inboxes.try_load_entry_mut(origin).await?;
         for event in events {
-            inbox.add_event(event).await.map_err(|error| match error {
-                InboxError::ViewError(error) => ChainError::ViewError(error),
-                error => ChainError::InternalError(format!(
+            inbox.receive_event(event).map_err(|error| {
+                ChainError::InternalError(format!(
                     "while processing messages in certified block: {error}"
-                )),
+                ))
             })?;
         }
+        drop(inbox);
+        let timestamp = *self.execution_state.system.timestamp.get();
+        self.accept_or_refuse_events(origin, timestamp).await?;
         // Remember the certificate for future validator/client synchronizations.
         self.received_log.push(ChainAndHeight {
             chain_id: origin.sender,
@@ -389,7 +464,78 @@ where
         Ok(())
     }
 
-    async fn execute_immediate_message(
+    /// Accepts or refuses the events received from `origin` that were sent no later than
+    /// `timestamp`. Each event is checked against the state of this chain after all its
+    /// blocks with an earlier timestamp than the event's, which is known to all validators as
+    /// soon as the chain has a block with a later or equal timestamp.
+    ///
+    /// Messages from the admin chain are always accepted so that the chain can follow
+    /// committee changes, and so are the messages returned to this chain by their recipients.
+    pub async fn accept_or_refuse_events(
+        &mut self,
+        origin: &Origin,
+        timestamp: Timestamp,
+    ) -> Result<(), ChainError> {
+        let chain_id = self.chain_id();
+        let admin_id = *self.execution_state.system.admin_id.get();
+        let removed_events = self
+            .removed_inbox_events
+            .get(origin)
+            .await?
+            .unwrap_or_default();
+        let mut inbox = self.inboxes.try_load_entry_mut(origin).await?;
+        while let Some(event) = inbox.received_events.front().await? {
+            if event.timestamp > timestamp {
+                break;
+            }
+            inbox.received_events.delete_front();
+            let is_accepted = if inbox.is_settled(&event)
+                || admin_id == Some(origin.sender)
+                || matches!(
+                    event.kind,
+                    MessageKind::Bounce | MessageKind::Acknowledgement(_)
+                ) {
+                true
+            } else {
+                let removed_events = removed_events
+                    .iter()
+                    .rev()
+                    .find(|removed_events| removed_events.timestamp < event.timestamp)
+                    .map_or(0, |removed_events| removed_events.count);
+                let pending_messages = inbox.pending_unskippable_events(removed_events);
+                self.execution_state
+                    .system
+                    .inbox_policy_at(event.timestamp)
+                    .await?
+                    .accepts(origin.sender, &event.message, event.grant, pending_messages)
+            };
+            if is_accepted {
+                inbox
+                    .add_event(event)
+                    .await
+                    .map_err(|error| ChainError::from((chain_id, origin.clone(), error)))?;
+            } else {
+                tracing::trace!("Refusing event {:?} from {:?}", event, origin);
+            }
+        }
+        Ok(())
+    }
+
+    /// Accepts or refuses the events received from all origins that were sent no later than
+    /// `timestamp`.
+    pub async fn accept_or_refuse_all_events(
+        &mut self,
+        timestamp: Timestamp,
+    ) -> Result<(), ChainError> {
+        for origin in self.inboxes.indices().await? {
+            self.accept_or_refuse_events(&origin, timestamp).await?;
+        }
+        Ok(())
+    }
+
+    /// Executes a system message that takes effect as soon as it is received, i.e. the
+    /// `OpenChain` message initializing this chain. Other messages are ignored.
+    pub async fn execute_immediate_message(
         &mut self,
         message_id: MessageId,
         message: &Message,
@@ -403,6 +549,10 @@ where
             admin_id,
         }) = message
         {
+            if self.is_active() {
+                // The chain was already initialized, e.g. from a checkpoint.
+                return Ok(());
+            }
             // Initialize ourself.
             self.execution_state.system.open_chain(
                 message_id,
@@ -425,7 +575,195 @@ where
         Ok(())
     }
 
-    /// Removes the incoming messages in the block from the inboxes.
+    /// Creates a checkpoint of the current state, together with the chunks of about
+    /// `chunk_size` bytes of storage that it commits to. Only the parts of the state that are
+    /// determined by the blocks of the chain are included, so that validators agree on them.
+    pub async fn checkpoint(
+        &self,
+        chunk_size: usize,
+    ) -> Result<(Checkpoint, Vec<CheckpointChunk>), ChainError> {
+        let chain_id = self.chain_id();
+        let epoch = self
+            .execution_state
+            .system
+            .epoch
+            .get()
+            .ok_or(ChainError::InactiveChain(chain_id))?;
+        let mut inbox_cursors = Vec::new();
+        let origins = self.inboxes.indices().await?;
+        let inboxes = self.inboxes.try_load_entries(&origins).await?;
+        for (origin, inbox) in origins.into_iter().zip(inboxes) {
+            let cursor = *inbox.next_cursor_to_remove.get();
+            if cursor != Cursor::default() {
+                inbox_cursors.push((origin, cursor));
+            }
+        }
+        let chunks = self.read_checkpoint_chunks(chunk_size).await?;
+        let tip = self.tip_state.get();
+        let checkpoint = Checkpoint {
+            chain_id,
+            epoch,
+            next_block_height: tip.next_block_height,
+            block_hash: tip.block_hash,
+            state_hash: *self.execution_state_hash.get(),
+            chain_state_hash: self.checkpoint_chain_state_hash().await?,
+            inbox_cursors,
+        };
+        Ok((checkpoint, chunks))
+    }
+
+    /// Replaces the parts of the state that a checkpoint commits to with the given chunks,
+    /// and skips the messages already consumed at that point.
+    ///
+    /// The chunks are first loaded in memory and checked against the checkpoint. Then they
+    /// are written to storage together with the rest of the chain, in a single batch.
+    pub async fn restore_checkpoint(
+        &mut self,
+        checkpoint: &Checkpoint,
+        chunks: Vec<CheckpointChunk>,
+        now: Timestamp,
+    ) -> Result<(), ChainError> {
+        let base_key = self.chain_base_key();
+        let key_prefixes = self
+            .checkpoint_base_keys()
+            .map(|key_prefix| key_prefix[base_key.len()..].to_vec());
+        ensure!(
+            chunks
+                .iter()
+                .flat_map(|chunk| &chunk.entries)
+                .all(|(key, _)| key_prefixes.iter().any(|prefix| key.starts_with(prefix))),
+            ChainError::InvalidCheckpointChunks
+        );
+        let staged_chain = load_checkpoint_chunks(self.context().extra().clone(), &chunks).await?;
+        ensure!(
+            staged_chain.matches_checkpoint(checkpoint).await?,
+            ChainError::InvalidCheckpointChunks
+        );
+        for (origin, cursor) in &checkpoint.inbox_cursors {
+            let removed_events = staged_chain
+                .removed_inbox_events
+                .get(origin)
+                .await?
+                .and_then(|removed_events| removed_events.last().copied())
+                .map_or(0, |removed_events| removed_events.count);
+            let mut inbox = self.inboxes.try_load_entry_mut(origin).await?;
+            inbox.restore(*cursor, removed_events);
+        }
+        self.manager.get_mut().reset(
+            staged_chain.execution_state.system.ownership.get(),
+            checkpoint.next_block_height,
+            now,
+        )?;
+        // Any pending change to the replaced views is overwritten by the chunks.
+        let mut batch = Batch::new();
+        self.flush(&mut batch)?;
+        for key_prefix in self.checkpoint_base_keys() {
+            batch.delete_key_prefix(key_prefix);
+        }
+        for chunk in chunks {
+            for (key, value) in chunk.entries {
+                batch.put_key_value_bytes([base_key.as_slice(), &key].concat(), value);
+            }
+        }
+        self.context()
+            .write_batch(batch)
+            .await
+            .map_err(ViewError::from)?;
+        self.reload_checkpoint_views().await?;
+        Ok(())
+    }
+
+    /// Returns the base key of the chain. `View::context` returns the context of the first
+    /// field instead, so the base key is recovered from the key of a register, which the
+    /// derived `load` obtains by appending a view tag and the index of the field.
+    fn chain_base_key(&self) -> Vec<u8> {
+        let mut base_key = self.execution_state_hash.context().base_key();
+        base_key.truncate(base_key.len() - checkpoint_field_suffix(1).len());
+        base_key
+    }
+
+    /// Returns the base keys of the views that are only modified by executing blocks.
+    fn checkpoint_base_keys(&self) -> [Vec<u8>; 9] {
+        let base_key = self.chain_base_key();
+        CHECKPOINT_FIELD_INDICES
+            .map(|index| [base_key.as_slice(), &checkpoint_field_suffix(index)].concat())
+    }
+
+    /// Loads the views with the base keys above again, after they were replaced in storage.
+    async fn reload_checkpoint_views(&mut self) -> Result<(), ViewError> {
+        let context = self.execution_state_hash.context().clone();
+        let [state, state_hash, tip, log, outboxes, counters, timestamps, channels, removed_events] =
+            self.checkpoint_base_keys()
+                .map(|key| context.clone_with_base_key(key));
+        self.execution_state = View::load(state).await?;
+        self.execution_state_hash = View::load(state_hash).await?;
+        self.tip_state = View::load(tip).await?;
+        self.confirmed_log = View::load(log).await?;
+        self.outboxes = View::load(outboxes).await?;
+        self.outbox_counters = View::load(counters).await?;
+        self.outbox_timestamps = View::load(timestamps).await?;
+        self.channels = View::load(channels).await?;
+        self.removed_inbox_events = View::load(removed_events).await?;
+        Ok(())
+    }
+
+    /// Returns the hash of the logical state of the views that a checkpoint commits to,
+    /// other than the execution state and the tip.
+    async fn checkpoint_chain_state_hash(&self) -> Result<CryptoHash, ViewError> {
+        let hashes = CheckpointChainStateHashes {
+            confirmed_log: self.confirmed_log.hash().await?,
+            outboxes: self.outboxes.hash().await?,
+            outbox_counters: self.outbox_counters.hash().await?,
+            outbox_timestamps: self.outbox_timestamps.hash().await?,
+            channels: self.channels.hash().await?,
+            removed_inbox_events: self.removed_inbox_events.hash().await?,
+        };
+        Ok(CryptoHash::new(&hashes))
+    }
+
+    /// Returns whether the logical state of this chain matches the checkpoint.
+    async fn matches_checkpoint(&self, checkpoint: &Checkpoint) -> Result<bool, ChainError> {
+        let tip = self.tip_state.get();
+        Ok(tip.next_block_height == checkpoint.next_block_height
+            && tip.block_hash == checkpoint.block_hash
+            && *self.execution_state.system.epoch.get() == Some(checkpoint.epoch)
+            && *self.execution_state_hash.get() == checkpoint.state_hash
+            && Some(self.execution_state.crypto_hash().await?) == checkpoint.state_hash
+            && self.checkpoint_chain_state_hash().await? == checkpoint.chain_state_hash)
+    }
+
+    async fn read_checkpoint_chunks(
+        &self,
+        chunk_size: usize,
+    ) -> Result<Vec<CheckpointChunk>, ViewError> {
+        let base_key = self.chain_base_key();
+        let mut chunks = Vec::new();
+        let mut chunk = CheckpointChunk::default();
+        let mut size = 0;
+        for key_prefix in self.checkpoint_base_keys() {
+            let key_values = self
+                .context()
+                .find_key_values_by_prefix(&key_prefix)
+                .await?;
+            for entry in key_values.iterator() {
+                let (key, value) = entry?;
+                let key = [&key_prefix[base_key.len()..], key].concat();
+                size += key.len() + value.len();
+                chunk.entries.push((key, value.to_vec()));
+                if size >= chunk_size {
+                    chunks.push(std::mem::take(&mut chunk));
+                    size = 0;
+                }
+            }
+        }
+        if !chunk.entries.is_empty() {
+            chunks.push(chunk);
+        }
+        Ok(chunks)
+    }
+
+    /// Removes the incoming messages in the block from the inboxes, after accepting or
+    /// refusing the events received up to the block's timestamp.
     pub async fn remove_events_from_inboxes(&mut self, block: &Block) -> Result<(), ChainError> {
         let chain_id = self.chain_id();
         let mut events_by_origin: BTreeMap<_, Vec<&Event>> = Default::default();
@@ -441,18 +779,53 @@ where
             let events = events_by_origin.entry(origin).or_default();
             events.push(event);
         }
+        for origin in events_by_origin.keys() {
+            self.accept_or_refuse_events(origin, block.timestamp)
+                .await?;
+        }
         let origins = events_by_origin.keys().copied();
         let inboxes = self.inboxes.try_load_entries_mut(origins).await?;
-        for ((origin, events), mut inbox) in events_by_origin.into_iter().zip(inboxes) {
+        for ((origin, events), mut inbox) in events_by_origin.iter().zip(inboxes) {
             tracing::trace!("Updating inbox {:?} in chain {:?}", origin, chain_id);
             for event in events {
                 // Mark the message as processed in the inbox.
                 inbox
                     .remove_event(event)
                     .await
-                    .map_err(|error| ChainError::from((chain_id, origin.clone(), error)))?;
+                    .map_err(|error| ChainError::from((chain_id, (*origin).clone(), error)))?;
             }
         }
+        for (origin, events) in events_by_origin {
+            let unskippable_events = events.iter().filter(|event| !event.is_skippable).count();
+            let mut removed_events = self
+                .removed_inbox_events
+                .get(origin)
+                .await?
+                .unwrap_or_default();
+            let count = removed_events
+                .last()
+                .map_or(0, |removed_events| removed_events.count)
+                .checked_add(unskippable_events as u64)
+                .ok_or(ArithmeticError::Overflow)?;
+            match removed_events.last_mut() {
+                _ if unskippable_events == 0 => {}
+                Some(last) if last.timestamp == block.timestamp => last.count = count,
+                _ => removed_events.push(RemovedEvents {
+                    timestamp: block.timestamp,
+                    count,
+                }),
+            }
+            // The next events are sent no earlier than the last ones removed: only the count
+            // before that is needed.
+            if let Some(last_event) = events.last() {
+                let earlier_counts = removed_events
+                    .iter()
+                    .take_while(|removed_events| removed_events.timestamp < last_event.timestamp)
+                    .count();
+                removed_events.drain(..earlier_counts.saturating_sub(1));
+            }
+            self.removed_inbox_events.insert(origin, removed_events)?;
+        }
         Ok(())
     }
 
@@ -466,47 +839,89 @@ where
         block: &Block,
         now: Timestamp,
     ) -> Result<BlockExecutionOutcome, ChainError> {
-        let start_time = Instant::now();
+        // The future is boxed, because it is too large to be nested in the callers' futures
+        // on the stack.
+        Box::pin(self.execute_block_with_trace(block, now, None)).await
+    }
 
+    /// Executes a new block like [`Self::execute_block`], recording the steps of the execution
+    /// in `trace` if one is provided. The trace is kept up to the point of failure if the
+    /// block fails.
+    ///
+    /// If the block has independent operations, an operation that fails because of the
+    /// transaction itself doesn't make the block fail: the execution state is restored to a
+    /// savepoint taken before the operation, and only the fees for the resources it used are
+    /// charged. The operations that failed are listed in the outcome. Acknowledged incoming
+    /// messages that fail are rolled back in the same way, and acknowledged as failed.
+    pub async fn execute_block_with_trace(
+        &mut self,
+        block: &Block,
+        now: Timestamp,
+        mut trace: Option<&mut ExecutionTrace>,
+    ) -> Result<BlockExecutionOutcome, ChainError> {
+        let start_time = Instant::now();
         assert_eq!(block.chain_id, self.chain_id());
         let chain_id = self.chain_id();
         ensure!(
             *self.execution_state.system.timestamp.get() <= block.timestamp,
             ChainError::InvalidBlockTimestamp
         );
+        block.check_previous_block_signatures(
+            self.execution_state.system.committees.get().values(),
+        )?;
         self.execution_state.system.timestamp.set(block.timestamp);
+        self.execution_state.system.remove_expired_committees();
         let Some((_, committee)) = self.execution_state.system.current_committee() else {
             return Err(ChainError::InactiveChain(chain_id));
         };
 
         let policy = committee.policy().clone();
+        let is_closed = *self.execution_state.system.closed.get();
+        ensure!(
+            !is_closed || block.operations.is_empty(),
+            ChainError::ClosedChain(chain_id)
+        );
+        let refused_messages = self.refused_messages(block, &policy);
+        let grants = block
+            .incoming_messages
+            .iter()
+            .try_fold(Amount::ZERO, |grants, message| {
+                grants.try_add(message.event.grant)
+            })?;
         let credit: Amount = block
             .incoming_messages
             .iter()
-            .filter_map(|msg| match &msg.event.message {
-                Message::System(SystemMessage::Credit { account, amount })
-                    if *account == Account::chain(chain_id) =>
-                {
-                    Some(amount)
-                }
+            .zip(&refused_messages)
+            .filter(|(msg, is_refused)| msg.action == MessageAction::Accept && !**is_refused)
+            .filter_map(|(msg, _)| match &msg.event.message {
+                Message::System(SystemMessage::Credit {
+                    account, amount, ..
+                }) if *account == Account::chain(chain_id) => Some(amount),
                 _ => None,
             })
             .sum();
         let balance = self.execution_state.system.balance.get_mut();
 
         balance.try_add_assign(credit)?;
-        sub_assign_fees(balance, policy.certificate_price())?;
-        sub_assign_fees(
-            balance,
-            policy.storage_bytes_written_price_raw(&block.incoming_messages)?,
-        )?;
-        sub_assign_fees(
-            balance,
-            policy.storage_bytes_written_price_raw(&block.operations)?,
-        )?;
+        // Closed chains swept or burnt their balances when closing: their blocks only return
+        // incoming messages to their senders, who already paid for them. The grants of these
+        // messages are burnt, so that no balance is left on the chain.
+        if !is_closed {
+            balance.try_add_assign(grants)?;
+            sub_assign_fees(balance, policy.certificate_price())?;
+            sub_assign_fees(
+                balance,
+                policy.storage_bytes_written_price_raw(&block.incoming_messages)?,
+            )?;
+            sub_assign_fees(
+                balance,
+                policy.storage_bytes_written_price_raw(&block.operations)?,
+            )?;
+        }
 
         let mut messages = Vec::new();
         let mut message_counts = Vec::new();
+        let mut events = Vec::new();
         let maximum_bytes_left_to_read = policy.maximum_bytes_read_per_block;
         let maximum_bytes_left_to_write = policy.maximum_bytes_written_per_block;
         let mut tracker = ResourceTracker {
@@ -517,8 +932,20 @@ where
             maximum_bytes_left_to_read,
             maximum_bytes_left_to_write,
         };
-        for (index, message) in block.incoming_messages.iter().enumerate() {
+        for (index, (message, is_refused)) in block
+            .incoming_messages
+            .iter()
+            .zip(refused_messages)
+            .enumerate()
+        {
             let index = u32::try_from(index).map_err(|_| ArithmeticError::Overflow)?;
+            if message.action == MessageAction::Reject || is_refused {
+                self.reject_message(&mut messages, block.height, message, is_refused)
+                    .await?;
+                message_counts
+                    .push(u32::try_from(messages.len()).map_err(|_| ArithmeticError::Overflow)?);
+                continue;
+            }
             // Execute the received message.
             let context = MessageContext {
                 chain_id,
@@ -530,20 +957,85 @@ where
                     index: message.event.index,
                 },
                 authenticated_signer: message.event.authenticated_signer,
+                is_bouncing: message.event.kind == MessageKind::Bounce,
+                is_acknowledged: message.event.kind == MessageKind::Acknowledged,
+                acknowledgement: match message.event.kind {
+                    MessageKind::Acknowledgement(outcome) => Some(outcome),
+                    _ => None,
+                },
+                random_seed: block.random_seed(Transaction::IncomingMessage(index)),
+            };
+            if let Some(trace) = trace.as_deref_mut() {
+                trace.record(TraceEvent::IncomingMessage {
+                    index,
+                    message_id: context.message_id,
+                });
+            }
+            // An acknowledged message that fails is acknowledged as such instead of making the
+            // block fail, so its changes must be undone.
+            let savepoint = if context.is_acknowledged {
+                Some((self.execution_state.clone_unchecked()?, tracker))
+            } else {
+                None
             };
-            let results = self
+            let result = self
                 .execution_state
-                .execute_message(&context, &message.event.message, &policy, &mut tracker)
-                .await
-                .map_err(|err| {
-                    ChainError::ExecutionError(err, ChainExecutionContext::IncomingMessage(index))
-                })?;
-            self.process_execution_results(&mut messages, context.height, results)
+                .execute_message_with_trace(
+                    &context,
+                    &message.event.message,
+                    &policy,
+                    &mut tracker,
+                    trace.as_deref_mut(),
+                )
+                .await;
+            let (results, outcome) = match (savepoint, result) {
+                (_, Ok(results)) => (results, MessageOutcome::Executed),
+                // User errors are only returned for acknowledged messages.
+                (
+                    Some((execution_state, previous_tracker)),
+                    Err(ExecutionError::UserError { code, message }),
+                ) => {
+                    tracing::debug!("Acknowledging a failed incoming message: {message}");
+                    let fees = tracker.fees_since(&previous_tracker, &policy)?;
+                    self.execution_state = execution_state;
+                    // The chain pays for the resources used, up to its whole balance.
+                    let balance = self.execution_state.system.balance.get_mut();
+                    let fees = fees.min(*balance);
+                    sub_assign_fees(balance, fees)?;
+                    (Vec::new(), MessageOutcome::Failed(code))
+                }
+                (_, Err(error)) => {
+                    return Err(ChainError::ExecutionError(
+                        error,
+                        ChainExecutionContext::IncomingMessage(index),
+                    ));
+                }
+            };
+            self.process_execution_results(
+                &mut messages,
+                &mut events,
+                context.height,
+                index,
+                results,
+            )
+            .await?;
+            if context.is_acknowledged {
+                // The acknowledgement is delivered to the sender like any other message.
+                self.return_to_sender(
+                    &mut messages,
+                    block.height,
+                    message.origin.sender,
+                    message.event.authenticated_signer,
+                    &message.event.message,
+                    MessageKind::Acknowledgement(outcome),
+                )
                 .await?;
+            }
             message_counts
                 .push(u32::try_from(messages.len()).map_err(|_| ArithmeticError::Overflow)?);
         }
         // Second, execute the operations in the block and remember the recipients to notify.
+        let mut failed_operations = Vec::new();
         for (index, operation) in block.operations.iter().enumerate() {
             let index = u32::try_from(index).map_err(|_| ArithmeticError::Overflow)?;
             let next_message_index =
@@ -554,19 +1046,74 @@ where
                 index,
                 authenticated_signer: block.authenticated_signer,
                 next_message_index,
+                random_seed: block.random_seed(Transaction::Operation(index)),
+            };
+            if let Some(trace) = trace.as_deref_mut() {
+                trace.record(TraceEvent::Operation { index });
+            }
+            // Outboxes and channels are only updated once an operation succeeded, so only
+            // the execution state needs a savepoint.
+            let savepoint = if block.independent_operations {
+                Some((self.execution_state.clone_unchecked()?, tracker))
+            } else {
+                None
             };
-            let results = self
+            let results = match self
                 .execution_state
-                .execute_operation(&context, operation, &policy, &mut tracker)
+                .execute_operation_with_trace(
+                    &context,
+                    operation,
+                    block.independent_operations,
+                    &policy,
+                    &mut tracker,
+                    trace.as_deref_mut(),
+                )
                 .await
-                .map_err(|err| {
-                    ChainError::ExecutionError(err, ChainExecutionContext::Operation(index))
-                })?;
-            self.process_execution_results(&mut messages, context.height, results)
-                .await?;
+            {
+                Ok(results) => results,
+                Err(error) => match (savepoint, error.transaction_failure()) {
+                    (Some((execution_state, previous_tracker)), Some(description)) => {
+                        let fees = tracker.fees_since(&previous_tracker, &policy)?;
+                        self.execution_state = execution_state;
+                        // The chain pays for the resources used, up to its whole balance.
+                        let balance = self.execution_state.system.balance.get_mut();
+                        let fees = fees.min(*balance);
+                        sub_assign_fees(balance, fees)?;
+                        failed_operations.push(FailedOperation {
+                            index,
+                            error: description,
+                            fees,
+                        });
+                        message_counts.push(
+                            u32::try_from(messages.len()).map_err(|_| ArithmeticError::Overflow)?,
+                        );
+                        continue;
+                    }
+                    _ => {
+                        return Err(ChainError::ExecutionError(
+                            error,
+                            ChainExecutionContext::Operation(index),
+                        ));
+                    }
+                },
+            };
+            let transaction_index = u32::try_from(block.incoming_messages.len())
+                .ok()
+                .and_then(|count| count.checked_add(index))
+                .ok_or(ArithmeticError::Overflow)?;
+            self.process_execution_results(
+                &mut messages,
+                &mut events,
+                context.height,
+                transaction_index,
+                results,
+            )
+            .await?;
             message_counts
                 .push(u32::try_from(messages.len()).map_err(|_| ArithmeticError::Overflow)?);
         }
+        // Finally, abandon the outgoing messages that expired.
+        self.expire_outgoing_messages(&policy).await?;
         let balance = self.execution_state.system.balance.get_mut();
         sub_assign_fees(balance, credit)?;
 
@@ -591,21 +1138,179 @@ where
         Ok(BlockExecutionOutcome {
             messages,
             message_counts,
+            events,
+            failed_operations,
             state_hash,
         })
     }
 
+    /// Returns, for each incoming message of the block, whether the chain refuses it because
+    /// it is closed or because the message expired. Closed chains refuse all messages except
+    /// the committee changes.
+    fn refused_messages(&self, block: &Block, policy: &ResourceControlPolicy) -> Vec<bool> {
+        let admin_id = *self.execution_state.system.admin_id.get();
+        let is_closed = *self.execution_state.system.closed.get();
+        block
+            .incoming_messages
+            .iter()
+            .map(|message| {
+                let IncomingMessage { origin, event, .. } = message;
+                let is_refused_when_closed = is_closed
+                    && (admin_id != Some(origin.sender)
+                        || !matches!(
+                            event.message,
+                            Message::System(SystemMessage::SetCommittees { .. })
+                        ));
+                is_refused_when_closed || self.has_expired(message, policy, block.timestamp)
+            })
+            .collect()
+    }
+
+    /// Returns whether an incoming message expired by the given time. Its sender keeps it in
+    /// its outbox until it is received, and it is then refused: tracked messages bounce and
+    /// acknowledged messages are acknowledged as rejected, so that the sender can safely
+    /// recover them. The messages that return something to this chain, and the ones from this
+    /// chain itself, never expire.
+    fn has_expired(
+        &self,
+        message: &IncomingMessage,
+        policy: &ResourceControlPolicy,
+        timestamp: Timestamp,
+    ) -> bool {
+        message.origin.sender != self.chain_id()
+            && !matches!(
+                message.event.kind,
+                MessageKind::Bounce | MessageKind::Acknowledgement(_)
+            )
+            && policy.has_message_expired(message.event.timestamp, timestamp)
+    }
+
+    /// Abandons the messages in outboxes that their recipients didn't receive before they
+    /// expired, as long as they are all simple ones. Subscribers of channels with abandoned
+    /// messages are removed. The messages to this chain itself never expire.
+    async fn expire_outgoing_messages(
+        &mut self,
+        policy: &ResourceControlPolicy,
+    ) -> Result<(), ChainError> {
+        let now = *self.execution_state.system.timestamp.get();
+        let Some(expired_height) = self
+            .outbox_timestamps
+            .get()
+            .iter()
+            .take_while(|(_, timestamp)| policy.has_message_expired(**timestamp, now))
+            .map(|(height, _)| *height)
+            .last()
+        else {
+            return Ok(());
+        };
+        let chain_id = self.chain_id();
+        for target in self.outboxes.indices().await? {
+            if target.recipient == chain_id {
+                continue;
+            }
+            let outbox = self.outboxes.try_load_entry(&target).await?;
+            let last_abandoned_height = outbox.last_abandonable_height(expired_height).await?;
+            drop(outbox);
+            let Some(last_abandoned_height) = last_abandoned_height else {
+                continue;
+            };
+            if let Medium::Channel(full_name) = &target.medium {
+                let mut channel = self.channels.try_load_entry_mut(full_name).await?;
+                channel.subscribers.remove(&target.recipient)?;
+            }
+            self.mark_messages_as_received(target, last_abandoned_height)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Skips the execution of an incoming message that the block rejects or that the chain
+    /// refuses. Tracked messages are bounced back to their sender and acknowledged messages are
+    /// acknowledged as rejected; other messages are simply dropped. Only rejectable messages
+    /// can be rejected by a block.
+    async fn reject_message(
+        &mut self,
+        messages: &mut Vec<OutgoingMessage>,
+        height: BlockHeight,
+        message: &IncomingMessage,
+        is_refused: bool,
+    ) -> Result<(), ChainError> {
+        let IncomingMessage { origin, event, .. } = message;
+        ensure!(
+            is_refused || event.is_rejectable(),
+            ChainError::UnrejectableMessage {
+                chain_id: self.chain_id(),
+                origin: origin.clone().into(),
+                event: event.clone(),
+            }
+        );
+        let kind = match event.kind {
+            MessageKind::Tracked => MessageKind::Bounce,
+            MessageKind::Acknowledged => MessageKind::Acknowledgement(MessageOutcome::Rejected),
+            _ => return Ok(()),
+        };
+        self.return_to_sender(
+            messages,
+            height,
+            origin.sender,
+            event.authenticated_signer,
+            &event.message,
+            kind,
+        )
+        .await
+    }
+
+    /// Sends a message back to the chain that sent it, with the given kind.
+    async fn return_to_sender(
+        &mut self,
+        messages: &mut Vec<OutgoingMessage>,
+        height: BlockHeight,
+        sender: ChainId,
+        authenticated_signer: Option<Owner>,
+        message: &Message,
+        kind: MessageKind,
+    ) -> Result<(), ChainError> {
+        let result = RawExecutionResult {
+            authenticated_signer,
+            messages: vec![RawOutgoingMessage {
+                destination: Destination::Recipient(sender),
+                authenticated: true,
+                is_skippable: false,
+                kind,
+                grant: Amount::ZERO,
+                message: message.clone(),
+            }],
+            ..RawExecutionResult::default()
+        };
+        self.process_raw_execution_result(
+            message.application_id(),
+            |message| message,
+            messages,
+            height,
+            result,
+        )
+        .await
+    }
+
     async fn process_execution_results(
         &mut self,
         messages: &mut Vec<OutgoingMessage>,
+        events: &mut Vec<ApplicationEvent>,
         height: BlockHeight,
+        transaction_index: u32,
         results: Vec<ExecutionResult>,
     ) -> Result<(), ChainError> {
         for result in results {
+            let application_id = result.application_id();
             match result {
                 ExecutionResult::System(result) => {
+                    events.extend(Self::application_events(
+                        application_id,
+                        transaction_index,
+                        &result,
+                    ));
                     self.process_raw_execution_result(
-                        GenericApplicationId::System,
+                        application_id,
                         Message::System,
                         messages,
                         height,
@@ -613,11 +1318,16 @@ where
                     )
                     .await?;
                 }
-                ExecutionResult::User(application_id, result) => {
+                ExecutionResult::User(user_application_id, result) => {
+                    events.extend(Self::application_events(
+                        application_id,
+                        transaction_index,
+                        &result,
+                    ));
                     self.process_raw_execution_result(
-                        GenericApplicationId::User(application_id),
+                        application_id,
                         |bytes| Message::User {
-                            application_id,
+                            application_id: user_application_id,
                             bytes,
                         },
                         messages,
@@ -631,6 +1341,24 @@ where
         Ok(())
     }
 
+    /// Returns the events of an execution result, tagged with their application and
+    /// transaction.
+    fn application_events<E>(
+        application_id: GenericApplicationId,
+        transaction_index: u32,
+        raw_result: &RawExecutionResult<E>,
+    ) -> impl Iterator<Item = ApplicationEvent> + '_ {
+        raw_result
+            .events
+            .iter()
+            .map(move |(topic, value)| ApplicationEvent {
+                application_id,
+                transaction_index,
+                topic: topic.clone(),
+                value: value.clone(),
+            })
+    }
+
     async fn process_raw_execution_result<E, F>(
         &mut self,
         application_id: GenericApplicationId,
@@ -647,10 +1375,13 @@ where
         // application.
         let mut recipients = HashSet::new();
         let mut channel_broadcasts = HashSet::new();
+        let first_message_index = messages.len();
         for RawOutgoingMessage {
             destination,
             authenticated,
             is_skippable,
+            kind,
+            grant,
             message,
         } in raw_result.messages
         {
@@ -659,9 +1390,16 @@ where
                     recipients.insert(*id);
                 }
                 Destination::Subscribers(name) => {
+                    ensure!(grant == Amount::ZERO, ChainError::GrantToSubscribers);
                     channel_broadcasts.insert(name.clone());
                 }
             }
+            self.execution_state
+                .system
+                .balance
+                .get_mut()
+                .try_sub_assign(grant)
+                .map_err(|_| ChainError::InsufficientBalance)?;
             let authenticated_signer = if authenticated {
                 raw_result.authenticated_signer
             } else {
@@ -671,20 +1409,31 @@ where
                 destination,
                 authenticated_signer,
                 is_skippable,
+                kind,
+                grant,
                 message: lift(message),
             });
         }
 
         // Update the (regular) outboxes.
+        let timestamp = *self.execution_state.system.timestamp.get();
         let outbox_counters = self.outbox_counters.get_mut();
+        let outbox_timestamps = self.outbox_timestamps.get_mut();
         let targets = recipients
             .into_iter()
             .map(Target::chain)
             .collect::<Vec<_>>();
         let outboxes = self.outboxes.try_load_entries_mut(&targets).await?;
-        for mut outbox in outboxes {
+        for (target, mut outbox) in targets.iter().zip(outboxes) {
             if outbox.schedule_message(height)? {
                 *outbox_counters.entry(height).or_default() += 1;
+                outbox_timestamps.entry(height).or_insert(timestamp);
+            }
+            let destination = Destination::Recipient(target.recipient);
+            if messages[first_message_index..].iter().any(|message| {
+                message.kind != MessageKind::Simple && message.destination == destination
+            }) {
+                outbox.add_returnable_height(height)?;
             }
         }
 
@@ -729,6 +1478,7 @@ where
         for mut outbox in outboxes {
             if outbox.schedule_message(height)? {
                 *outbox_counters.entry(height).or_default() += 1;
+                outbox_timestamps.entry(height).or_insert(timestamp);
             }
         }
         let full_names = raw_result
@@ -767,6 +1517,7 @@ where
         for (height, mut outbox) in heights.into_iter().zip(outboxes) {
             if outbox.schedule_message(height)? {
                 *outbox_counters.entry(height).or_default() += 1;
+                outbox_timestamps.entry(height).or_insert(timestamp);
             }
         }
         Ok(())
@@ -2,17 +2,17 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::ChainError;
+use crate::{inbox::Cursor, ChainError};
 use async_graphql::{Object, SimpleObject};
 use linera_base::{
     crypto::{BcsHashable, BcsSignable, CryptoHash, KeyPair, Signature},
-    data_types::{BlockHeight, RoundNumber, Timestamp},
+    data_types::{Amount, BlockHeight, RoundNumber, Timestamp, ValueHashAndRound},
     doc_scalar, ensure,
     identifiers::{ChainId, ChannelName, Destination, MessageId, Owner},
 };
 use linera_execution::{
     committee::{Committee, Epoch, ValidatorName},
-    BytecodeLocation, GenericApplicationId, Message, Operation,
+    BytecodeLocation, GenericApplicationId, Message, MessageKind, Operation,
 };
 use serde::{de::Deserializer, Deserialize, Serialize};
 use std::{
@@ -42,6 +42,9 @@ pub struct Block {
     pub incoming_messages: Vec<IncomingMessage>,
     /// The operations to execute.
     pub operations: Vec<Operation>,
+    /// Whether the operations are independent of each other. If so, an operation that fails is
+    /// rolled back and recorded in the execution outcome, instead of rejecting the whole block.
+    pub independent_operations: bool,
     /// The block height.
     pub height: BlockHeight,
     /// The timestamp when this block was created. This must be later than all messages received
@@ -53,6 +56,65 @@ pub struct Block {
     /// Certified hash (see `Certificate` below) of the previous block in the
     /// chain, if any.
     pub previous_block_hash: Option<CryptoHash>,
+    /// The canonical signatures of the certificate of the previous block (see
+    /// [`CertificateSignatures::new_canonical`]), from which the random seed of the block is
+    /// derived. They are only missing if the block was proposed before its previous block was
+    /// certified, like the blocks of a pipeline after the first one: such blocks have no
+    /// random seed.
+    pub previous_block_signatures: Option<CertificateSignatures>,
+}
+
+/// The signatures of a certificate, without the certified value.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct CertificateSignatures {
+    /// The round in which the value was certified.
+    pub round: RoundNumber,
+    /// Signatures on the value.
+    pub signatures: Vec<(ValidatorName, Signature)>,
+}
+
+impl CertificateSignatures {
+    /// Returns the canonical subset of the `signatures` of a certificate: the signatures of
+    /// the validators with the lowest names whose votes reach the quorum of the `committee`,
+    /// sorted by name. Validators check that the signatures of a block's previous block have
+    /// this form, so that a proposer can't add or reorder signatures to change the block's
+    /// random seed.
+    pub fn new_canonical(
+        round: RoundNumber,
+        signatures: &[(ValidatorName, Signature)],
+        committee: &Committee,
+    ) -> Self {
+        let mut signatures = signatures
+            .iter()
+            .filter(|(name, _)| committee.weight(name) > 0)
+            .cloned()
+            .collect::<Vec<_>>();
+        signatures.sort_by_key(|(name, _)| *name);
+        signatures.dedup_by_key(|(name, _)| *name);
+        let mut weight = 0;
+        if let Some(index) = signatures.iter().position(|(name, _)| {
+            weight += committee.weight(name);
+            weight >= committee.quorum_threshold()
+        }) {
+            signatures.truncate(index + 1);
+        }
+        CertificateSignatures { round, signatures }
+    }
+
+    /// Returns whether the signatures are sorted by name, and whether the quorum of the
+    /// `committee` is only reached with the last one, as in [`Self::new_canonical`].
+    fn is_canonical(&self, committee: &Committee) -> bool {
+        let Some(((last_name, _), others)) = self.signatures.split_last() else {
+            return false;
+        };
+        let weight = others
+            .iter()
+            .map(|(name, _)| committee.weight(name))
+            .sum::<u64>();
+        is_strictly_ordered(&self.signatures)
+            && weight < committee.quorum_threshold()
+            && weight + committee.weight(last_name) >= committee.quorum_threshold()
+    }
 }
 
 impl Block {
@@ -71,6 +133,81 @@ impl Block {
         }
         locations
     }
+
+    /// Returns the pseudo-random seed made available to the application executing the
+    /// `transaction` of this block.
+    ///
+    /// The seed is the hash of the chain ID, the block height, the previous block's hash and the
+    /// canonical signatures of its certificate, and the index of the transaction. All validators
+    /// therefore agree on it, and since the validators' signatures cannot be computed by anyone
+    /// else, it cannot be known before the previous block has been certified, nor steered by
+    /// choosing the content of the previous block. The signatures must be canonical, so the
+    /// proposer can only choose among the minimal quorums of validators that actually signed
+    /// the previous block. However, the owners of the chain know the seed when they propose the
+    /// block: it must not be used where the block proposer has an incentive to manipulate the
+    /// outcome.
+    ///
+    /// Blocks that have a previous block but not the signatures of its certificate have no
+    /// seed, since it could be computed before the previous block is certified.
+    pub fn random_seed(&self, transaction: Transaction) -> Option<CryptoHash> {
+        if self.previous_block_hash.is_some() && self.previous_block_signatures.is_none() {
+            return None;
+        }
+        Some(CryptoHash::new(&RandomSeedInput {
+            chain_id: self.chain_id,
+            height: self.height,
+            previous_block_hash: self.previous_block_hash,
+            previous_block_signatures: self.previous_block_signatures.clone(),
+            transaction,
+        }))
+    }
+
+    /// Verifies that the previous block's signatures, if any, are the canonical signatures of
+    /// a certificate of the previous block, with one of the `committees` known to the chain.
+    pub fn check_previous_block_signatures<'a>(
+        &self,
+        committees: impl IntoIterator<Item = &'a Committee>,
+    ) -> Result<(), ChainError> {
+        let Some(previous_signatures) = &self.previous_block_signatures else {
+            return Ok(());
+        };
+        let previous_block_hash = self
+            .previous_block_hash
+            .ok_or(ChainError::InvalidPreviousBlockSignatures)?;
+        let value = LiteValue {
+            value_hash: previous_block_hash,
+            chain_id: self.chain_id,
+        };
+        let is_certified = committees.into_iter().any(|committee| {
+            previous_signatures.is_canonical(committee)
+                && check_signatures(
+                    &value,
+                    previous_signatures.round,
+                    &previous_signatures.signatures,
+                    committee,
+                )
+                .is_ok()
+        });
+        ensure!(is_certified, ChainError::InvalidPreviousBlockSignatures);
+        Ok(())
+    }
+}
+
+/// The data from which the pseudo-random seed of a transaction is derived.
+#[derive(Serialize, Deserialize)]
+struct RandomSeedInput {
+    chain_id: ChainId,
+    height: BlockHeight,
+    previous_block_hash: Option<CryptoHash>,
+    previous_block_signatures: Option<CertificateSignatures>,
+    transaction: Transaction,
+}
+
+/// A transaction of a block, with its index among the incoming messages or the operations.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Transaction {
+    IncomingMessage(u32),
+    Operation(u32),
 }
 
 /// A chain ID with a block height.
@@ -95,6 +232,18 @@ pub struct IncomingMessage {
     /// The content of the message to be delivered to the inbox identified by
     /// `origin`.
     pub event: Event,
+    /// What to do with the message.
+    pub action: MessageAction,
+}
+
+/// What to do with a message picked from the inbox.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum MessageAction {
+    /// Execute the incoming message.
+    Accept,
+    /// Do not execute the incoming message. Tracked and acknowledged messages are returned to
+    /// their sender.
+    Reject,
 }
 
 /// A message together with non replayable information to ensure uniqueness in a
@@ -111,6 +260,10 @@ pub struct Event {
     pub authenticated_signer: Option<Owner>,
     /// Whether the message can be skipped.
     pub is_skippable: bool,
+    /// The kind of the message.
+    pub kind: MessageKind,
+    /// The tokens granted to the recipient for executing the message.
+    pub grant: Amount,
     /// The timestamp of the block that caused the message.
     pub timestamp: Timestamp,
     /// The message of the event (i.e. the actual payload of a message).
@@ -164,6 +317,9 @@ pub struct BlockProposal {
     pub signature: Signature,
     pub blobs: Vec<HashedValue>,
     pub validated: Option<Certificate>,
+    /// Signatures of other chain owners approving the block in this round, if the chain
+    /// requires a threshold of owners to approve it.
+    pub approvals: Vec<(Owner, Signature)>,
 }
 
 /// A message together with routing information.
@@ -175,30 +331,70 @@ pub struct OutgoingMessage {
     pub authenticated_signer: Option<Owner>,
     /// Whether the message can be skipped.
     pub is_skippable: bool,
+    /// The kind of the message.
+    pub kind: MessageKind,
+    /// The tokens granted to the recipient for executing the message.
+    pub grant: Amount,
     /// The message itself.
     pub message: Message,
 }
 
+/// An event emitted by an application during the execution of a block.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ApplicationEvent {
+    /// The application that emitted the event.
+    pub application_id: GenericApplicationId,
+    /// The index of the transaction (incoming message or operation) that emitted the event.
+    pub transaction_index: u32,
+    /// The topic of the event, chosen by the application.
+    #[serde(with = "serde_bytes")]
+    pub topic: Vec<u8>,
+    /// The value of the event, in the application's own serialization format.
+    #[serde(with = "serde_bytes")]
+    pub value: Vec<u8>,
+}
+
+/// An operation of a block with independent operations that failed and was rolled back.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct FailedOperation {
+    /// The index of the operation in the block.
+    pub index: u32,
+    /// A description of the error.
+    pub error: String,
+    /// The fees charged for the resources used before the operation failed.
+    pub fees: Amount,
+}
+
 /// A block, together with the messages and the state hash resulting from its execution.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, SimpleObject)]
 pub struct ExecutedBlock {
     pub block: Block,
+    /// The messages created by the transactions.
     pub messages: Vec<OutgoingMessage>,
     /// For each transaction, the cumulative number of messages created by this and all previous
     /// transactions, i.e. `message_counts[i]` is the index of the first message created by
     /// transaction `i + 1` or later.
     pub message_counts: Vec<u32>,
+    /// The events emitted by applications, in the order of their emission.
+    pub events: Vec<ApplicationEvent>,
+    /// The operations that failed and were rolled back, if the operations are independent.
+    pub failed_operations: Vec<FailedOperation>,
     pub state_hash: CryptoHash,
 }
 
-/// The messages and the state hash resulting from a block's execution.
+/// The messages, events and the state hash resulting from a block's execution.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, SimpleObject)]
 pub struct BlockExecutionOutcome {
+    /// The messages created by the transactions.
     pub messages: Vec<OutgoingMessage>,
     /// For each transaction, the cumulative number of messages created by this and all previous
     /// transactions, i.e. `message_counts[i]` is the index of the first message created by
     /// transaction `i + 1` or later.
     pub message_counts: Vec<u32>,
+    /// The events emitted by applications, in the order of their emission.
+    pub events: Vec<ApplicationEvent>,
+    /// The operations that failed and were rolled back, if the operations are independent.
+    pub failed_operations: Vec<FailedOperation>,
     pub state_hash: CryptoHash,
 }
 
@@ -262,9 +458,6 @@ pub struct LiteValue {
     pub chain_id: ChainId,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
-struct ValueHashAndRound(CryptoHash, RoundNumber);
-
 /// A vote on a statement from a validator.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Vote {
@@ -328,6 +521,54 @@ impl LiteVote {
     }
 }
 
+/// A commitment to the part of a chain state that only depends on its blocks, after a given
+/// height. It contains hashes of the logical state, not of its storage layout, so that
+/// validators agree on it. The state itself is transferred separately, in chunks.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// The chain whose state is committed to.
+    pub chain_id: ChainId,
+    /// The epoch of the chain at this height, whose committee certifies the checkpoint.
+    pub epoch: Epoch,
+    /// The height after the latest block included in the state.
+    pub next_block_height: BlockHeight,
+    /// The hash of the latest block included in the state.
+    pub block_hash: Option<CryptoHash>,
+    /// The hash of the execution state after the latest block.
+    pub state_hash: Option<CryptoHash>,
+    /// The hash of the rest of the state: the log of confirmed blocks, the outboxes and the
+    /// channels.
+    pub chain_state_hash: CryptoHash,
+    /// For each inbox that messages were consumed from, the cursor of the next message
+    /// to consume.
+    pub inbox_cursors: Vec<(Origin, Cursor)>,
+}
+
+/// A piece of the state committed to by a checkpoint, as key-value pairs. Keys are relative
+/// to the base key of the chain.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct CheckpointChunk {
+    pub entries: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// A vote on a checkpoint from a validator.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct CheckpointVote {
+    pub checkpoint: Checkpoint,
+    /// The hashes of the chunks of the state stored by this validator, in order. They are
+    /// not signed: the restored state is checked against the checkpoint instead.
+    pub chunk_hashes: Vec<CryptoHash>,
+    pub validator: ValidatorName,
+    pub signature: Signature,
+}
+
+/// A checkpoint signed by a quorum of validators.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct CertifiedCheckpoint {
+    pub checkpoint: Checkpoint,
+    pub signatures: Vec<(ValidatorName, Signature)>,
+}
+
 /// A certified statement from the committee, without the value.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "test"), derive(Eq, PartialEq))]
@@ -421,6 +662,14 @@ pub struct Certificate {
     signatures: Vec<(ValidatorName, Signature)>,
 }
 
+impl Event {
+    /// Returns whether a block may reject this message instead of executing it: skippable,
+    /// tracked and bouncing messages can be rejected.
+    pub fn is_rejectable(&self) -> bool {
+        self.is_skippable || self.kind != MessageKind::Simple
+    }
+}
+
 impl Origin {
     pub fn chain(sender: ChainId) -> Self {
         Self {
@@ -608,12 +857,16 @@ impl BlockExecutionOutcome {
         let BlockExecutionOutcome {
             messages,
             message_counts,
+            events,
+            failed_operations,
             state_hash,
         } = self;
         ExecutedBlock {
             block,
             messages,
             message_counts,
+            events,
+            failed_operations,
             state_hash,
         }
     }
@@ -687,8 +940,15 @@ impl BlockProposal {
             signature,
             blobs,
             validated,
+            approvals: Vec::new(),
         }
     }
+
+    /// Adds signatures of other chain owners approving the proposed block.
+    pub fn with_approvals(mut self, approvals: Vec<(Owner, Signature)>) -> Self {
+        self.approvals = approvals;
+        self
+    }
 }
 
 impl LiteVote {
@@ -711,6 +971,36 @@ impl LiteVote {
     }
 }
 
+impl CheckpointVote {
+    /// Uses the signing key to create a signed object.
+    pub fn new(checkpoint: Checkpoint, chunk_hashes: Vec<CryptoHash>, key_pair: &KeyPair) -> Self {
+        let signature = Signature::new(&checkpoint, key_pair);
+        Self {
+            checkpoint,
+            chunk_hashes,
+            validator: ValidatorName(key_pair.public()),
+            signature,
+        }
+    }
+
+    /// Verifies the signature in the vote.
+    pub fn check(&self) -> Result<(), ChainError> {
+        Ok(self.signature.check(&self.checkpoint, self.validator.0)?)
+    }
+}
+
+impl CertifiedCheckpoint {
+    /// Verifies that the checkpoint is signed by a quorum of the committee.
+    pub fn check(&self, committee: &Committee) -> Result<(), ChainError> {
+        check_quorum(&self.signatures, committee)?;
+        Signature::verify_batch(
+            &self.checkpoint,
+            self.signatures.iter().map(|(v, s)| (&v.0, s)),
+        )?;
+        Ok(())
+    }
+}
+
 pub struct SignatureAggregator<'a> {
     committee: &'a Committee,
     weight: u64,
@@ -820,6 +1110,12 @@ impl Certificate {
         &self.signatures
     }
 
+    /// Returns the round and the canonical signatures of the certificate for the `committee`
+    /// that signed it, without the value.
+    pub fn certificate_signatures(&self, committee: &Committee) -> CertificateSignatures {
+        CertificateSignatures::new_canonical(self.round, &self.signatures, committee)
+    }
+
     // Adds a signature to the certificate's list of signatures
     // It's the responsibility of the caller to not insert duplicates
     pub fn add_signature(
@@ -882,7 +1178,18 @@ fn check_signatures(
     signatures: &[(ValidatorName, Signature)],
     committee: &Committee,
 ) -> Result<(), ChainError> {
-    // Check the quorum.
+    check_quorum(signatures, committee)?;
+    // All that is left is checking signatures!
+    let hash_and_round = ValueHashAndRound(value.value_hash, round);
+    Signature::verify_batch(&hash_and_round, signatures.iter().map(|(v, s)| (&v.0, s)))?;
+    Ok(())
+}
+
+/// Verifies that the signers are distinct validators forming a quorum of the committee.
+fn check_quorum(
+    signatures: &[(ValidatorName, Signature)],
+    committee: &Committee,
+) -> Result<(), ChainError> {
     let mut weight = 0;
     let mut used_validators = HashSet::new();
     for (validator, _) in signatures {
@@ -901,18 +1208,23 @@ fn check_signatures(
         weight >= committee.quorum_threshold(),
         ChainError::CertificateRequiresQuorum
     );
-    // All that is left is checking signatures!
-    let hash_and_round = ValueHashAndRound(value.value_hash, round);
-    Signature::verify_batch(&hash_and_round, signatures.iter().map(|(v, s)| (&v.0, s)))?;
     Ok(())
 }
 
 impl BcsSignable for BlockAndRound {}
 
-impl BcsSignable for ValueHashAndRound {}
+impl BcsSignable for Checkpoint {}
+
+impl BcsHashable for CheckpointChunk {}
 
 impl BcsHashable for CertificateValue {}
 
+impl BcsHashable for RandomSeedInput {}
+
+doc_scalar!(
+    CertificateSignatures,
+    "The signatures of a certificate, without the certified value"
+);
 doc_scalar!(
     ChannelFullName,
     "A channel name together with its application id"
@@ -921,6 +1233,10 @@ doc_scalar!(
     Event,
     "A message together with non replayable information to ensure uniqueness in a particular inbox"
 );
+doc_scalar!(
+    MessageAction,
+    "What to do with a message picked from the inbox"
+);
 doc_scalar!(
     Medium,
     "The origin of a message coming from a particular chain. Used to identify each inbox."
@@ -4,7 +4,7 @@
 use crate::{data_types::Event, ChainError, Origin};
 use async_graphql::SimpleObject;
 use linera_base::{
-    data_types::{ArithmeticError, BlockHeight},
+    data_types::{ArithmeticError, BlockHeight, Timestamp},
     ensure,
     identifiers::ChainId,
 };
@@ -32,6 +32,8 @@ mod inbox_tests;
 /// The state of a inbox.
 /// * An inbox is used to track events received and executed locally.
 /// * An `Event` consists of a logical cursor `(height, index)` and some message content `message`.
+/// * Received events are first accepted or refused, according to the state of the chain at the
+/// time they were sent. Refused events are dropped.
 /// * On the surface, an inbox looks like a FIFO queue: the main APIs are `add_event` and
 /// `remove_event`.
 /// * However, events can also be removed before they are added. When this happens,
@@ -42,15 +44,31 @@ mod inbox_tests;
 /// However, the opposite is not true: every removed event must be eventually added.
 #[derive(Debug, View, GraphQLView)]
 pub struct InboxStateView<C> {
-    /// We have already added all the messages below this height and index.
+    /// We have already received all the messages below this height and index.
     pub next_cursor_to_add: RegisterView<C, Cursor>,
     /// We have already removed all the messages below this height and index.
     pub next_cursor_to_remove: RegisterView<C, Cursor>,
-    /// These events have been added and are waiting to be removed.
+    /// These events have been accepted and are waiting to be removed.
     pub added_events: QueueView<C, Event>,
     /// These events have been removed by anticipation and are waiting to be added.
     /// At least one of `added_events` and `removed_events` should be empty.
     pub removed_events: QueueView<C, Event>,
+    /// These events have been received and are waiting to be accepted or refused, once the
+    /// state of the chain at the time they were sent is known.
+    pub received_events: QueueView<C, Event>,
+    /// The number of events that cannot be skipped that were added, or removed by
+    /// anticipation.
+    pub added_unskippable_events: RegisterView<C, u64>,
+}
+
+/// The number of events from an origin that cannot be skipped that the blocks of a chain
+/// removed from the inbox, up to the blocks with a given timestamp.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize, SimpleObject)]
+pub struct RemovedEvents {
+    /// The timestamp of the latest blocks that removed events.
+    pub timestamp: Timestamp,
+    /// The number of events removed by these blocks and the previous ones.
+    pub count: u64,
 }
 
 #[derive(
@@ -83,6 +101,8 @@ pub(crate) enum InboxError {
     IncorrectOrder { event: Event, next_cursor: Cursor },
     #[error("{event:?} cannot be skipped: it must be received before the next messages from the same origin")]
     UnskippableEvent { event: Event },
+    #[error("{event:?} was refused by the inbox policy of the chain")]
+    RefusedEvent { event: Event },
 }
 
 impl From<&Event> for Cursor {
@@ -134,6 +154,11 @@ impl From<(ChainId, Origin, InboxError)> for ChainError {
                 origin: origin.into(),
                 event,
             },
+            InboxError::RefusedEvent { event } => ChainError::RefusedMessage {
+                chain_id,
+                origin: origin.into(),
+                event,
+            },
         }
     }
 }
@@ -184,9 +209,15 @@ where
             Some(previous_event) => {
                 // Rationale: If the two cursors are equal, then the events should match.
                 // Otherwise, at this point we know that `self.next_cursor_to_add >
-                // Cursor::from(&previous_event) > cursor`. Notably, `event` will never be
-                // added in the future. Therefore, we should fail instead of adding
-                // it to `self.removed_events`.
+                // Cursor::from(&previous_event) > cursor`. Notably, `event` was received
+                // and refused. Therefore, we should fail instead of adding it to
+                // `self.removed_events`.
+                ensure!(
+                    Cursor::from(&previous_event) == cursor,
+                    InboxError::RefusedEvent {
+                        event: event.clone()
+                    }
+                );
                 ensure!(
                     event == &previous_event,
                     InboxError::UnexpectedEvent {
@@ -197,6 +228,13 @@ where
                 self.added_events.delete_front();
                 tracing::trace!("Consuming event {:?}", event);
             }
+            None if cursor < *self.next_cursor_to_add.get() => {
+                // The event was received already. The chain accepts or refuses the received
+                // events before removing any event sent later, so this one was refused.
+                return Err(InboxError::RefusedEvent {
+                    event: event.clone(),
+                });
+            }
             None => {
                 tracing::trace!("Marking event as expected: {:?}", event);
                 self.removed_events.push_back(event.clone());
@@ -206,18 +244,44 @@ where
         Ok(())
     }
 
-    /// Pushes an event to the inbox. The verifications should not fail in production unless
-    /// many validators are faulty.
-    pub(crate) async fn add_event(&mut self, event: Event) -> Result<(), InboxError> {
+    /// Receives an event. It is only added to the inbox once it is accepted. The
+    /// verifications should not fail in production unless many validators are faulty.
+    pub(crate) fn receive_event(&mut self, event: Event) -> Result<(), InboxError> {
         // Record the latest cursor.
         let cursor = Cursor::from(&event);
         ensure!(
             cursor >= *self.next_cursor_to_add.get(),
             InboxError::IncorrectOrder {
-                event: event.clone(),
+                event,
                 next_cursor: *self.next_cursor_to_add.get(),
             }
         );
+        self.next_cursor_to_add.set(cursor.try_add_one()?);
+        self.received_events.push_back(event);
+        Ok(())
+    }
+
+    /// Returns the number of events that are pending, i.e. added but not removed yet, and
+    /// cannot be skipped, given the events that the chain had removed at some point.
+    pub(crate) fn pending_unskippable_events(&self, removed_events: u64) -> u64 {
+        self.added_unskippable_events
+            .get()
+            .saturating_sub(removed_events)
+    }
+
+    /// Returns whether the blocks of the chain have already gone past a received event:
+    /// then it must be added if and only if they removed it. These blocks were certified, so
+    /// there is no need to accept or refuse the event again.
+    pub(crate) fn is_settled(&self, event: &Event) -> bool {
+        self.removed_events.count() > 0 || Cursor::from(event) < *self.next_cursor_to_remove.get()
+    }
+
+    /// Adds an event that was received and accepted to the inbox, unless it was settled
+    /// already. The verifications should not fail in production unless many validators are
+    /// faulty.
+    pub(crate) async fn add_event(&mut self, event: Event) -> Result<(), InboxError> {
+        let cursor = Cursor::from(&event);
+        let is_skippable = event.is_skippable;
         // Find if the message was removed ahead of time.
         match self.removed_events.front().await? {
             Some(previous_event) => {
@@ -234,24 +298,57 @@ where
                     self.removed_events.delete_front();
                 } else {
                     // The receiver has already executed a later event from the same
-                    // sender ahead of time so we should skip this one.
+                    // sender ahead of time so this one was skipped or refused.
                     ensure!(
-                        cursor < Cursor::from(&previous_event) && event.is_skippable,
+                        cursor < Cursor::from(&previous_event),
                         InboxError::UnexpectedEvent {
                             previous_event,
                             event,
                         }
                     );
+                    tracing::trace!("Skipping previously skipped or refused event {:?}", event);
+                    return Ok(());
                 }
             }
+            None if cursor < *self.next_cursor_to_remove.get() => {
+                // The event was consumed before the inbox was restored from a checkpoint.
+                tracing::trace!("Skipping previously consumed event {:?}", event);
+                return Ok(());
+            }
             None => {
                 // Otherwise, schedule the message for execution.
                 self.added_events.push_back(event)
             }
         }
-        self.next_cursor_to_add.set(cursor.try_add_one()?);
+        if !is_skippable {
+            let added_unskippable_events = self.added_unskippable_events.get_mut();
+            *added_unskippable_events = added_unskippable_events
+                .checked_add(1)
+                .ok_or(ArithmeticError::Overflow)?;
+        }
         Ok(())
     }
+
+    /// Resets the inbox to the state it has after consuming all the events below
+    /// `next_cursor_to_remove`, but before receiving the events of the same block height.
+    /// The chain removed `removed_unskippable_events` events that cannot be skipped until
+    /// then: these were all the ones it added.
+    pub(crate) fn restore(
+        &mut self,
+        next_cursor_to_remove: Cursor,
+        removed_unskippable_events: u64,
+    ) {
+        self.added_events.clear();
+        self.removed_events.clear();
+        self.received_events.clear();
+        self.next_cursor_to_add.set(Cursor {
+            height: next_cursor_to_remove.height,
+            index: 0,
+        });
+        self.next_cursor_to_remove.set(next_cursor_to_remove);
+        self.added_unskippable_events
+            .set(removed_unskippable_events);
+    }
 }
 
 #[cfg(any(test, feature = "test"))]
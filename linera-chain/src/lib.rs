@@ -42,6 +42,8 @@ pub enum ChainError {
 
     #[error("The chain being queried is not active {0:?}")]
     InactiveChain(ChainId),
+    #[error("Closed chain {0:?} can only return incoming messages to their senders")]
+    ClosedChain(ChainId),
     #[error(
         "Cannot vote for block proposal of chain {chain_id:?} because a message \
          from origin {origin:?} at height {height:?} has not been received yet"
@@ -79,6 +81,21 @@ pub enum ChainError {
         origin: Box<Origin>,
         event: Event,
     },
+    #[error("Block proposed to {chain_id:?} is attempting to reject protected message {event:?}")]
+    UnrejectableMessage {
+        chain_id: ChainId,
+        origin: Box<Origin>,
+        event: Event,
+    },
+    #[error(
+        "Block proposed to {chain_id:?} is attempting to execute message {event:?}, which \
+         was refused by the inbox policy"
+    )]
+    RefusedMessage {
+        chain_id: ChainId,
+        origin: Box<Origin>,
+        event: Event,
+    },
     #[error(
         "Incoming message in block proposed to {chain_id:?} has timestamp {message_timestamp:},
         which is later than the block timestamp {block_timestamp:}."
@@ -103,10 +120,18 @@ pub enum ChainError {
     InvalidBlockHeight,
     #[error("Block timestamp must not be earlier than the parent block's.")]
     InvalidBlockTimestamp,
+    #[error("The block's previous block signatures don't certify its previous block")]
+    InvalidPreviousBlockSignatures,
     #[error("Cannot initiate a new block while the previous one is still pending confirmation")]
     PreviousBlockMustBeConfirmedFirst,
     #[error("Invalid block proposal")]
     InvalidBlockProposal,
+    #[error("Approvals of a block proposal must be from different owners")]
+    ApprovalOwnerReuse,
+    #[error(
+        "Block approvals have a total weight of {weight}, but the chain requires at least {threshold}"
+    )]
+    InsufficientApprovals { weight: u64, threshold: u64 },
     #[error("Round number should be at least {0:?}")]
     InsufficientRound(RoundNumber),
     #[error("Round number should be {0:?}")]
@@ -125,11 +150,15 @@ pub enum ChainError {
     InternalError(String),
     #[error("Insufficient balance to pay the fees")]
     InsufficientBalance,
+    #[error("Messages to the subscribers of a channel cannot grant tokens")]
+    GrantToSubscribers,
     #[error("Invalid owner weights: {0}")]
     OwnerWeightError(#[from] WeightedError),
+    #[error("The given chunks do not match the certified checkpoint")]
+    InvalidCheckpointChunks,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum ChainExecutionContext {
     Query,
     DescribeApplication,
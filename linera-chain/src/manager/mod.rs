@@ -63,10 +63,14 @@ impl ChainManager {
             ChainOwnership::Multi {
                 public_keys,
                 multi_leader_rounds,
+                approval_threshold,
+                timeout_config,
             } => {
                 *self = ChainManager::Multi(Box::new(MultiOwnerManager::new(
                     public_keys.clone(),
                     *multi_leader_rounds,
+                    *approval_threshold,
+                    *timeout_config,
                     height.0,
                     now,
                 )?));
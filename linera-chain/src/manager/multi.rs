@@ -64,18 +64,13 @@ use linera_base::{
     ensure,
     identifiers::{ChainId, Owner},
 };
-use linera_execution::committee::Epoch;
+use linera_execution::{committee::Epoch, ApprovalThreshold, TimeoutConfig};
 use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
 use rand_distr::{Distribution, WeightedAliasIndex};
 use serde::{Deserialize, Serialize};
-use std::{
-    collections::{BTreeMap, HashMap},
-    time::Duration,
-};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use tracing::error;
 
-const TIMEOUT: Duration = Duration::from_secs(10);
-
 /// The consensus state of a chain with multiple owners some of which are potentially faulty.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MultiOwnerManager {
@@ -84,6 +79,10 @@ pub struct MultiOwnerManager {
     /// The number of initial rounds in which all owners are allowed to propose blocks,
     /// i.e. the first round with only a single leader.
     pub multi_leader_rounds: RoundNumber,
+    /// The weight of the owners that must approve a block, if any, and which blocks need it.
+    pub approval_threshold: Option<ApprovalThreshold>,
+    /// The durations of the rounds.
+    pub timeout_config: TimeoutConfig,
     /// The seed for the pseudo-random number generator that determines the round leaders.
     pub seed: u64,
     /// The probability distribution for choosing a round leader.
@@ -107,17 +106,21 @@ impl MultiOwnerManager {
     pub fn new(
         public_keys: impl IntoIterator<Item = (Owner, (PublicKey, u64))>,
         multi_leader_rounds: RoundNumber,
+        approval_threshold: Option<ApprovalThreshold>,
+        timeout_config: TimeoutConfig,
         seed: u64,
         now: Timestamp,
     ) -> Result<Self, ChainError> {
         let public_keys: BTreeMap<Owner, (PublicKey, u64)> = public_keys.into_iter().collect();
         let weights = public_keys.values().map(|(_, weight)| *weight).collect();
         let distribution = WeightedAliasIndex::new(weights)?;
-        let round_timeout = now.saturating_add(TIMEOUT);
+        let round_timeout = now.saturating_add(timeout_config.round_duration(RoundNumber::ZERO));
 
         Ok(MultiOwnerManager {
             public_keys,
             multi_leader_rounds,
+            approval_threshold,
+            timeout_config,
             seed,
             distribution,
             proposed: None,
@@ -165,6 +168,7 @@ impl MultiOwnerManager {
             None => self.current_round(),
             Some(cert) => cert.round.try_add_one()?.max(self.current_round()),
         };
+        self.check_approvals(proposal)?;
         // In leader rotation mode, the round must equal the expected one exactly.
         // Only the first single-leader round can be entered at any time.
         if new_round > self.multi_leader_rounds {
@@ -207,6 +211,44 @@ impl MultiOwnerManager {
         Ok(Outcome::Accept)
     }
 
+    /// Verifies that the proposed block is approved by owners with a sufficient total weight, if
+    /// the chain requires it. The proposer counts as one of the approvers. Approvals are signed
+    /// for a specific round, so that they cannot be reused in later rounds, even when a
+    /// previously validated block is proposed again.
+    fn check_approvals(&self, proposal: &BlockProposal) -> Result<(), ChainError> {
+        let Some(threshold) = &self.approval_threshold else {
+            return Ok(());
+        };
+        if !threshold
+            .scope
+            .requires_approval(&proposal.content.block.operations)
+        {
+            return Ok(());
+        }
+        let mut approvers = HashSet::from([proposal.owner]);
+        let mut weight = self
+            .public_keys
+            .get(&proposal.owner)
+            .map_or(0, |(_, weight)| *weight);
+        for (owner, signature) in &proposal.approvals {
+            ensure!(approvers.insert(*owner), ChainError::ApprovalOwnerReuse);
+            let (public_key, owner_weight) = self
+                .public_keys
+                .get(owner)
+                .ok_or(ChainError::InvalidSigner)?;
+            signature.check(&proposal.content, *public_key)?;
+            weight = weight.saturating_add(*owner_weight);
+        }
+        ensure!(
+            weight >= threshold.weight,
+            ChainError::InsufficientApprovals {
+                weight,
+                threshold: threshold.weight,
+            }
+        );
+        Ok(())
+    }
+
     /// Checks if the current round has timed out, and signs a `LeaderTimeout`.
     pub fn vote_leader_timeout(
         &mut self,
@@ -324,11 +366,12 @@ impl MultiOwnerManager {
         }
     }
 
-    /// Resets the timer if `round` has just ended.
+    /// Resets the timer if `round` has just ended. The next round's duration depends on the
+    /// chain's timeout configuration.
     fn update_timeout(&mut self, round: RoundNumber, now: Timestamp) {
         if self.current_round() <= round {
-            let factor = round.0.saturating_add(2);
-            let timeout = TIMEOUT.saturating_mul(factor);
+            let next_round = round.try_add_one().unwrap_or(RoundNumber::MAX);
+            let timeout = self.timeout_config.round_duration(next_round);
             self.round_timeout = now.saturating_add(timeout);
         }
     }
@@ -392,6 +435,10 @@ pub struct MultiOwnerManagerInfo {
     /// The number of initial rounds in which all owners are allowed to propose blocks,
     /// i.e. the first round with only a single leader.
     pub multi_leader_rounds: RoundNumber,
+    /// The weight of the owners that must approve a block, if any, and which blocks need it.
+    pub approval_threshold: Option<ApprovalThreshold>,
+    /// The durations of the rounds.
+    pub timeout_config: TimeoutConfig,
     /// Latest authenticated block that we have received, if requested.
     pub requested_proposed: Option<BlockProposal>,
     /// Latest validated proposal that we have voted to confirm (or would have, if we are not a
@@ -420,6 +467,8 @@ impl From<&MultiOwnerManager> for MultiOwnerManagerInfo {
         MultiOwnerManagerInfo {
             public_keys: manager.public_keys.clone().into_iter().collect(),
             multi_leader_rounds: manager.multi_leader_rounds,
+            approval_threshold: manager.approval_threshold,
+            timeout_config: manager.timeout_config,
             requested_proposed: None,
             requested_locked: None,
             leader_timeout: manager.leader_timeout.clone(),
@@ -6,13 +6,17 @@ use linera_views::{
     common::Context,
     queue_view::QueueView,
     register_view::RegisterView,
-    views::{GraphQLView, View, ViewError},
+    set_view::SetView,
+    views::{GraphQLView, HashableView, ViewError},
 };
 
 #[cfg(any(test, feature = "test"))]
 use {
     async_lock::Mutex,
-    linera_views::memory::{MemoryContext, TEST_MEMORY_MAX_STREAM_QUERIES},
+    linera_views::{
+        memory::{MemoryContext, TEST_MEMORY_MAX_STREAM_QUERIES},
+        views::View,
+    },
     std::collections::BTreeMap,
     std::sync::Arc,
 };
@@ -28,13 +32,17 @@ mod outbox_tests;
 /// we just send the certified blocks over and let the receivers figure out what were the
 /// messages for them.
 /// * When marking block heights as received, messages at lower heights are also marked (ie. dequeued).
-#[derive(Debug, View, GraphQLView)]
+#[derive(Debug, HashableView, GraphQLView)]
 pub struct OutboxStateView<C> {
     /// The minimum block height accepted in the future.
     pub next_height_to_schedule: RegisterView<C, BlockHeight>,
     /// Keep sending these certified blocks of ours until they are acknowledged by
     /// receivers.
     pub queue: QueueView<C, BlockHeight>,
+    /// The heights in the queue with messages that can't simply be abandoned once they
+    /// expire, i.e. messages that are not simple ones: their recipients must return or
+    /// acknowledge them instead.
+    pub returnable_heights: SetView<C, BlockHeight>,
 }
 
 impl<C> OutboxStateView<C>
@@ -56,6 +64,12 @@ where
         Ok(true)
     }
 
+    /// Records that the messages at the given height can't simply be abandoned once they
+    /// expire.
+    pub(crate) fn add_returnable_height(&mut self, height: BlockHeight) -> Result<(), ViewError> {
+        self.returnable_heights.insert(&height)
+    }
+
     /// Marks all messages as received up to the given height.
     /// Returns true if a change was made.
     pub(crate) async fn mark_messages_as_received(
@@ -68,10 +82,28 @@ where
                 break;
             }
             self.queue.delete_front();
+            self.returnable_heights.remove(&h)?;
             updates.push(h);
         }
         Ok(updates)
     }
+
+    /// Returns the last height up to the given one whose messages can be abandoned, if any.
+    /// The messages that aren't simple ones are never abandoned, and neither are the messages
+    /// after them: the recipient returns or acknowledges them once they expire.
+    pub(crate) async fn last_abandonable_height(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Option<BlockHeight>, ViewError> {
+        let mut last_abandonable_height = None;
+        for h in self.queue.elements().await? {
+            if h > height || self.returnable_heights.contains(&h).await? {
+                break;
+            }
+            last_abandonable_height = Some(h);
+        }
+        Ok(last_abandonable_height)
+    }
 }
 
 #[cfg(any(test, feature = "test"))]
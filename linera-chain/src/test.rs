@@ -32,7 +32,9 @@ pub fn make_child_block(parent: &HashedValue) -> Block {
         chain_id: parent_value.chain_id(),
         incoming_messages: vec![],
         operations: vec![],
+        independent_operations: false,
         previous_block_hash: Some(parent.hash()),
+        previous_block_signatures: None,
         height: parent_value.height().try_add_one().unwrap(),
         authenticated_signer: None,
         timestamp: parent_block.timestamp,
@@ -46,7 +48,9 @@ pub fn make_first_block(chain_id: ChainId) -> Block {
         chain_id,
         incoming_messages: vec![],
         operations: vec![],
+        independent_operations: false,
         previous_block_hash: None,
+        previous_block_signatures: None,
         height: BlockHeight::ZERO,
         authenticated_signer: None,
         timestamp: Timestamp::default(),
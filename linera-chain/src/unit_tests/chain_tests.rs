@@ -0,0 +1,944 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::test::{make_first_block, BlockTestExt};
+use linera_base::{
+    crypto::{KeyPair, PublicKey},
+    identifiers::{BytecodeId, ChainDescription, Owner},
+};
+use linera_execution::{
+    committee::{Committee, Epoch, ValidatorName, ValidatorState},
+    policy::ResourceControlPolicy,
+    system::{Recipient, SystemOperation, UserData},
+    test_utils::TestApplication,
+    BytecodeLocation, ChainOwnership, InboxPolicy, Operation, Query, QueryContext, Response,
+    TestExecutionRuntimeContext,
+};
+use linera_views::memory::TEST_MEMORY_MAX_STREAM_QUERIES;
+
+type TestChain = ChainStateView<MemoryContext<TestExecutionRuntimeContext>>;
+
+/// Creates an active root chain in memory, owned by a new key, with the given balance.
+async fn make_chain(index: u32, balance: Amount) -> TestChain {
+    let description = ChainDescription::Root(index);
+    let guard = Arc::new(Mutex::new(BTreeMap::new())).lock_arc().await;
+    let extra = TestExecutionRuntimeContext::new(description.into());
+    let context = MemoryContext::new(guard, TEST_MEMORY_MAX_STREAM_QUERIES, extra);
+    let mut chain = TestChain::load(context).await.unwrap();
+    let key_pair = KeyPair::generate();
+    let committee = Committee::make_simple(vec![ValidatorName(key_pair.public())]);
+    let ownership = ChainOwnership::single(key_pair.public());
+    let system_state = &mut chain.execution_state.system;
+    system_state.description.set(Some(description));
+    system_state.epoch.set(Some(Epoch::ZERO));
+    system_state.admin_id.set(Some(ChainId::root(0)));
+    system_state
+        .committees
+        .get_mut()
+        .insert(Epoch::ZERO, committee);
+    system_state.ownership.set(ownership.clone());
+    system_state.balance.set(balance);
+    let state_hash = chain.execution_state.crypto_hash().await.unwrap();
+    chain.execution_state_hash.set(Some(state_hash));
+    chain
+        .manager
+        .get_mut()
+        .reset(&ownership, BlockHeight::ZERO, Timestamp::default())
+        .unwrap();
+    chain
+}
+
+/// Replaces the committee of the chain with one that has the given policy.
+fn set_policy(chain: &mut TestChain, policy: ResourceControlPolicy) {
+    let validators = [(
+        ValidatorName(KeyPair::generate().public()),
+        ValidatorState {
+            network_address: String::new(),
+            votes: 1,
+        },
+    )];
+    let committee = Committee::new(validators.into_iter().collect(), policy);
+    chain
+        .execution_state
+        .system
+        .committees
+        .get_mut()
+        .insert(Epoch::ZERO, committee);
+}
+
+/// Returns a message crediting `amount` to the chain `recipient`.
+fn make_outgoing_message(recipient: ChainId, kind: MessageKind, amount: Amount) -> OutgoingMessage {
+    OutgoingMessage {
+        destination: Destination::Recipient(recipient),
+        authenticated_signer: None,
+        is_skippable: false,
+        kind,
+        grant: Amount::ZERO,
+        message: Message::System(SystemMessage::Credit {
+            account: Account::chain(recipient),
+            amount,
+            source: None,
+        }),
+    }
+}
+
+/// Delivers the message to the chain's inbox, and returns it as an incoming message with
+/// the given action.
+async fn receive_message(
+    chain: &mut TestChain,
+    sender: ChainId,
+    message: OutgoingMessage,
+    action: MessageAction,
+) -> IncomingMessage {
+    let mut incoming_messages = receive_messages(chain, sender, vec![message], action).await;
+    incoming_messages.pop().unwrap()
+}
+
+/// Delivers the messages to the chain's inbox in a single block, and returns them as incoming
+/// messages with the given action.
+async fn receive_messages(
+    chain: &mut TestChain,
+    sender: ChainId,
+    messages: Vec<OutgoingMessage>,
+    action: MessageAction,
+) -> Vec<IncomingMessage> {
+    let timestamp = Timestamp::default();
+    receive_block_at(
+        chain,
+        sender,
+        BlockHeight::ZERO,
+        timestamp,
+        messages,
+        action,
+    )
+    .await
+}
+
+/// Delivers the messages of the sender's block at the given height and timestamp to the
+/// chain's inbox, and returns them as incoming messages with the given action.
+async fn receive_block_at(
+    chain: &mut TestChain,
+    sender: ChainId,
+    height: BlockHeight,
+    timestamp: Timestamp,
+    messages: Vec<OutgoingMessage>,
+    action: MessageAction,
+) -> Vec<IncomingMessage> {
+    let origin = Origin::chain(sender);
+    let certificate_hash = CryptoHash::from([0; 4]);
+    chain
+        .receive_block(
+            &origin,
+            height,
+            timestamp,
+            messages.clone(),
+            certificate_hash,
+            timestamp,
+        )
+        .await
+        .unwrap();
+    messages
+        .into_iter()
+        .zip(0..)
+        .map(|(message, index)| IncomingMessage {
+            origin: origin.clone(),
+            event: Event {
+                certificate_hash,
+                height,
+                index,
+                authenticated_signer: message.authenticated_signer,
+                is_skippable: message.is_skippable,
+                kind: message.kind,
+                grant: message.grant,
+                timestamp,
+                message: message.message,
+            },
+            action,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_rejected_tracked_message_bounces() {
+    let sender = ChainId::root(1);
+    let mut chain = make_chain(2, Amount::ZERO).await;
+    let chain_id = chain.chain_id();
+    let outgoing_message = make_outgoing_message(chain_id, MessageKind::Tracked, Amount::ONE);
+    let incoming_message = receive_message(
+        &mut chain,
+        sender,
+        outgoing_message.clone(),
+        MessageAction::Reject,
+    )
+    .await;
+
+    let block = make_first_block(chain_id).with_incoming_message(incoming_message);
+    chain.remove_events_from_inboxes(&block).await.unwrap();
+    let outcome = chain
+        .execute_block(&block, Timestamp::default())
+        .await
+        .unwrap();
+
+    // The credit is not executed, and returned to the sender instead.
+    assert_eq!(*chain.execution_state.system.balance.get(), Amount::ZERO);
+    assert_eq!(
+        outcome.messages,
+        vec![OutgoingMessage {
+            destination: Destination::Recipient(sender),
+            kind: MessageKind::Bounce,
+            ..outgoing_message
+        }]
+    );
+    assert_eq!(outcome.message_counts, vec![1]);
+    let outbox = chain
+        .outboxes
+        .try_load_entry(&Target::chain(sender))
+        .await
+        .unwrap();
+    assert_eq!(
+        outbox.queue.elements().await.unwrap(),
+        vec![BlockHeight::ZERO]
+    );
+}
+
+#[tokio::test]
+async fn test_simple_message_cannot_be_rejected() {
+    let sender = ChainId::root(1);
+    let mut chain = make_chain(2, Amount::ZERO).await;
+    let chain_id = chain.chain_id();
+    let outgoing_message = make_outgoing_message(chain_id, MessageKind::Simple, Amount::ONE);
+    let incoming_message =
+        receive_message(&mut chain, sender, outgoing_message, MessageAction::Reject).await;
+
+    let block = make_first_block(chain_id).with_incoming_message(incoming_message);
+    chain.remove_events_from_inboxes(&block).await.unwrap();
+    let result = chain.execute_block(&block, Timestamp::default()).await;
+    assert!(matches!(
+        result,
+        Err(ChainError::UnrejectableMessage { chain_id: id, .. }) if id == chain_id
+    ));
+}
+
+#[tokio::test]
+async fn test_bounced_message_is_executed_by_the_sender() {
+    let recipient = ChainId::root(1);
+    let mut chain = make_chain(2, Amount::ZERO).await;
+    let chain_id = chain.chain_id();
+    let bounced_message = OutgoingMessage {
+        destination: Destination::Recipient(chain_id),
+        kind: MessageKind::Bounce,
+        ..make_outgoing_message(recipient, MessageKind::Tracked, Amount::ONE)
+    };
+    let incoming_message = receive_message(
+        &mut chain,
+        recipient,
+        bounced_message,
+        MessageAction::Accept,
+    )
+    .await;
+
+    // The sender gets its tokens back.
+    let block = make_first_block(chain_id).with_incoming_message(incoming_message);
+    chain.remove_events_from_inboxes(&block).await.unwrap();
+    let outcome = chain
+        .execute_block(&block, Timestamp::default())
+        .await
+        .unwrap();
+    assert_eq!(*chain.execution_state.system.balance.get(), Amount::ONE);
+    assert!(outcome.messages.is_empty());
+}
+
+/// Registers a [`TestApplication`] owned by `owner` on the `chain`, and returns its ID.
+async fn register_test_application(chain: &mut TestChain, owner: Owner) -> UserApplicationId {
+    let chain_id = chain.chain_id();
+    let description = UserApplicationDescription {
+        bytecode_id: BytecodeId::new(MessageId {
+            chain_id,
+            height: BlockHeight::ZERO,
+            index: 0,
+        }),
+        bytecode_location: BytecodeLocation {
+            certificate_hash: CryptoHash::from([0; 4]),
+            operation_index: 0,
+        },
+        creation: MessageId {
+            chain_id,
+            height: BlockHeight::ZERO,
+            index: 1,
+        },
+        required_application_ids: vec![],
+        parameters: vec![],
+        storage_quota: None,
+    };
+    let application_id = chain
+        .execution_state
+        .system
+        .registry
+        .register_application(description)
+        .await
+        .unwrap();
+    chain
+        .execution_state
+        .context()
+        .extra()
+        .user_applications()
+        .insert(application_id, Arc::new(TestApplication { owner }));
+    application_id
+}
+
+/// Returns the state of the [`TestApplication`] on the `chain`.
+async fn test_application_state(
+    chain: &mut TestChain,
+    application_id: UserApplicationId,
+) -> Vec<u8> {
+    let context = QueryContext {
+        chain_id: chain.chain_id(),
+    };
+    let query = Query::User {
+        application_id,
+        bytes: vec![],
+    };
+    let response = chain
+        .execution_state
+        .query_application(&context, &query)
+        .await
+        .unwrap();
+    let Response::User(state) = response else {
+        panic!("Unexpected response: {response:?}");
+    };
+    state
+}
+
+#[tokio::test]
+async fn test_failing_independent_operation_is_rolled_back() {
+    let initial_balance = Amount::from_tokens(10);
+    let mut chain = make_chain(1, initial_balance).await;
+    let chain_id = chain.chain_id();
+    // Charge for every resource, so that the failing operation has fees.
+    set_policy(&mut chain, ResourceControlPolicy::all_categories());
+    let owner = Owner::from(PublicKey::debug(1));
+    let application_id = register_test_application(&mut chain, owner).await;
+    let user_operation = |bytes: &[u8]| Operation::User {
+        application_id,
+        bytes: bytes.to_vec(),
+    };
+
+    let block = Block {
+        independent_operations: true,
+        authenticated_signer: Some(owner),
+        ..make_first_block(chain_id)
+    }
+    .with_operation(user_operation(b"first"))
+    .with_operation(user_operation(b"failing"))
+    .with_simple_transfer(Recipient::root(2), Amount::ONE)
+    .with_operation(user_operation(b"last"));
+    let outcome = chain
+        .execute_block(&block, Timestamp::default())
+        .await
+        .unwrap();
+
+    // Only the changes of the failing operation are discarded.
+    let [failed_operation] = &outcome.failed_operations[..] else {
+        panic!(
+            "Unexpected failed operations: {:?}",
+            outcome.failed_operations
+        );
+    };
+    assert_eq!(failed_operation.index, 1);
+    assert!(failed_operation.error.contains("Failure requested"));
+    assert!(failed_operation.fees > Amount::ZERO);
+    assert_eq!(
+        test_application_state(&mut chain, application_id).await,
+        b"firstlast"
+    );
+    assert_eq!(outcome.message_counts, vec![0, 0, 1, 1]);
+    // The chain paid for the resources used by all operations, and for the transfer.
+    let balance = *chain.execution_state.system.balance.get();
+    let expected_fees = failed_operation.fees.try_add(Amount::ONE).unwrap();
+    assert!(balance < initial_balance.try_sub(expected_fees).unwrap());
+}
+
+#[tokio::test]
+async fn test_failing_acknowledged_message_is_rolled_back() {
+    let sender = ChainId::root(1);
+    let initial_balance = Amount::from_tokens(10);
+    let mut chain = make_chain(2, initial_balance).await;
+    let chain_id = chain.chain_id();
+    set_policy(&mut chain, ResourceControlPolicy::all_categories());
+    let owner = Owner::from(PublicKey::debug(1));
+    let application_id = register_test_application(&mut chain, owner).await;
+    let acknowledged_message = |bytes: &[u8]| OutgoingMessage {
+        destination: Destination::Recipient(chain_id),
+        authenticated_signer: Some(owner),
+        is_skippable: false,
+        kind: MessageKind::Acknowledged,
+        grant: Amount::ZERO,
+        message: Message::User {
+            application_id,
+            bytes: bytes.to_vec(),
+        },
+    };
+    let incoming_messages = receive_messages(
+        &mut chain,
+        sender,
+        vec![
+            acknowledged_message(b"first"),
+            acknowledged_message(b"failing"),
+        ],
+        MessageAction::Accept,
+    )
+    .await;
+
+    let block = incoming_messages
+        .into_iter()
+        .fold(make_first_block(chain_id), |block, message| {
+            block.with_incoming_message(message)
+        });
+    chain.remove_events_from_inboxes(&block).await.unwrap();
+    let outcome = chain
+        .execute_block(&block, Timestamp::default())
+        .await
+        .unwrap();
+
+    // The changes of the failing message are discarded, but its sender is told about the
+    // failure instead of the block failing.
+    assert_eq!(
+        test_application_state(&mut chain, application_id).await,
+        b"first"
+    );
+    assert_eq!(
+        outcome.messages,
+        vec![
+            OutgoingMessage {
+                destination: Destination::Recipient(sender),
+                kind: MessageKind::Acknowledgement(MessageOutcome::Executed),
+                ..acknowledged_message(b"first")
+            },
+            OutgoingMessage {
+                destination: Destination::Recipient(sender),
+                kind: MessageKind::Acknowledgement(MessageOutcome::Failed(1)),
+                ..acknowledged_message(b"failing")
+            },
+        ]
+    );
+    assert_eq!(outcome.message_counts, vec![1, 2]);
+    // The fees are still charged.
+    assert!(*chain.execution_state.system.balance.get() < initial_balance);
+}
+
+#[tokio::test]
+async fn test_rejected_acknowledged_messages_are_acknowledged() {
+    let sender = ChainId::root(1);
+    let mut chain = make_chain(2, Amount::ZERO).await;
+    let chain_id = chain.chain_id();
+    let message = make_outgoing_message(chain_id, MessageKind::Acknowledged, Amount::ONE);
+    let mut incoming_messages = receive_messages(
+        &mut chain,
+        sender,
+        vec![message.clone(), message.clone()],
+        MessageAction::Accept,
+    )
+    .await;
+    // The block rejects the first message and accepts the second one.
+    incoming_messages[0].action = MessageAction::Reject;
+
+    let block = incoming_messages
+        .into_iter()
+        .fold(make_first_block(chain_id), |block, message| {
+            block.with_incoming_message(message)
+        });
+    chain.remove_events_from_inboxes(&block).await.unwrap();
+    let outcome = chain
+        .execute_block(&block, Timestamp::default())
+        .await
+        .unwrap();
+
+    // Both messages are acknowledged in the outbox to the sender, but only the second one is
+    // executed.
+    assert_eq!(*chain.execution_state.system.balance.get(), Amount::ONE);
+    let acknowledgement = |outcome| OutgoingMessage {
+        destination: Destination::Recipient(sender),
+        kind: MessageKind::Acknowledgement(outcome),
+        ..message.clone()
+    };
+    assert_eq!(
+        outcome.messages,
+        vec![
+            acknowledgement(MessageOutcome::Rejected),
+            acknowledgement(MessageOutcome::Executed),
+        ]
+    );
+    assert_eq!(outcome.message_counts, vec![1, 2]);
+    let outbox = chain
+        .outboxes
+        .try_load_entry(&Target::chain(sender))
+        .await
+        .unwrap();
+    assert_eq!(
+        outbox.queue.elements().await.unwrap(),
+        vec![BlockHeight::ZERO]
+    );
+}
+
+#[tokio::test]
+async fn test_acknowledgements_are_received_and_executed() {
+    let recipient = ChainId::root(1);
+    let mut chain = make_chain(2, Amount::ZERO).await;
+    let chain_id = chain.chain_id();
+    let owner = Owner::from(PublicKey::debug(1));
+    let application_id = register_test_application(&mut chain, owner).await;
+    let acknowledgement = OutgoingMessage {
+        destination: Destination::Recipient(chain_id),
+        authenticated_signer: None,
+        is_skippable: false,
+        kind: MessageKind::Acknowledgement(MessageOutcome::Failed(1)),
+        grant: Amount::ZERO,
+        message: Message::User {
+            application_id,
+            bytes: b"key".to_vec(),
+        },
+    };
+    // Acknowledgements are received like other messages, and accepted even from denied
+    // senders.
+    chain.execution_state.system.inbox_policy.set(InboxPolicy {
+        denied_senders: [recipient].into_iter().collect(),
+        ..InboxPolicy::default()
+    });
+    let incoming_message = receive_message(
+        &mut chain,
+        recipient,
+        acknowledgement,
+        MessageAction::Accept,
+    )
+    .await;
+
+    let block = make_first_block(chain_id).with_incoming_message(incoming_message);
+    chain.remove_events_from_inboxes(&block).await.unwrap();
+    let outcome = chain
+        .execute_block(&block, Timestamp::default())
+        .await
+        .unwrap();
+
+    // The application handled the acknowledgement, which is not acknowledged in turn.
+    let [event] = &outcome.events[..] else {
+        panic!("Unexpected events: {:?}", outcome.events);
+    };
+    assert_eq!(event.value, b"Failed(1)");
+    assert!(outcome.messages.is_empty());
+}
+
+/// Returns the number of events from the `sender` that are in the inbox of the `chain`.
+async fn added_events(chain: &mut TestChain, sender: ChainId) -> usize {
+    let inbox = chain
+        .inboxes
+        .try_load_entry(&Origin::chain(sender))
+        .await
+        .unwrap();
+    inbox.added_events.count()
+}
+
+#[tokio::test]
+async fn test_inbox_policy_is_applied_when_receiving_messages() {
+    let denied_sender = ChainId::root(1);
+    let poor_sender = ChainId::root(3);
+    let busy_sender = ChainId::root(4);
+    let admin_id = ChainId::root(0);
+    let mut chain = make_chain(2, Amount::ZERO).await;
+    let chain_id = chain.chain_id();
+    chain.execution_state.system.inbox_policy.set(InboxPolicy {
+        denied_senders: [denied_sender].into_iter().collect(),
+        maximum_pending_messages: Some(1),
+        minimum_grant: Amount::ONE,
+        ..InboxPolicy::default()
+    });
+    let granted_message = |amount| OutgoingMessage {
+        grant: Amount::ONE,
+        ..make_outgoing_message(chain_id, MessageKind::Tracked, amount)
+    };
+    let two_tokens = Amount::from_tokens(2);
+
+    // The messages of the denied sender, the ones without a grant, and the ones beyond the
+    // pending messages of the busy sender are refused. The admin chain needs no grant.
+    receive_message(
+        &mut chain,
+        denied_sender,
+        granted_message(two_tokens),
+        MessageAction::Accept,
+    )
+    .await;
+    let poor_message = make_outgoing_message(chain_id, MessageKind::Tracked, two_tokens);
+    receive_message(&mut chain, poor_sender, poor_message, MessageAction::Accept).await;
+    let busy_messages = receive_messages(
+        &mut chain,
+        busy_sender,
+        vec![granted_message(two_tokens), granted_message(two_tokens)],
+        MessageAction::Accept,
+    )
+    .await;
+    let admin_message = make_outgoing_message(chain_id, MessageKind::Simple, Amount::ONE);
+    let admin_message =
+        receive_message(&mut chain, admin_id, admin_message, MessageAction::Accept).await;
+    assert_eq!(added_events(&mut chain, denied_sender).await, 0);
+    assert_eq!(added_events(&mut chain, poor_sender).await, 0);
+    assert_eq!(added_events(&mut chain, busy_sender).await, 1);
+    assert_eq!(added_events(&mut chain, admin_id).await, 1);
+
+    // The accepted messages are executed, and their grants go to the chain.
+    let block = make_first_block(chain_id)
+        .with_incoming_message(busy_messages[0].clone())
+        .with_incoming_message(admin_message);
+    chain.remove_events_from_inboxes(&block).await.unwrap();
+    chain
+        .execute_block(&block, Timestamp::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        *chain.execution_state.system.balance.get(),
+        Amount::from_tokens(4)
+    );
+
+    // A block cannot include a refused message.
+    let block = make_first_block(chain_id).with_incoming_message(busy_messages[1].clone());
+    let result = chain.remove_events_from_inboxes(&block).await;
+    assert!(matches!(
+        result,
+        Err(ChainError::RefusedMessage { chain_id: id, .. }) if id == chain_id
+    ));
+
+    // The busy sender's next message is checked against the state of the chain after the
+    // blocks before it, once the chain reaches its timestamp.
+    let timestamp = Timestamp::from(1);
+    let next_message = receive_block_at(
+        &mut chain,
+        busy_sender,
+        BlockHeight::from(1),
+        timestamp,
+        vec![granted_message(Amount::ONE)],
+        MessageAction::Accept,
+    )
+    .await;
+    assert_eq!(added_events(&mut chain, busy_sender).await, 0);
+    let block = Block {
+        timestamp,
+        ..make_first_block(chain_id)
+    }
+    .with_incoming_message(next_message[0].clone());
+    chain.remove_events_from_inboxes(&block).await.unwrap();
+    let removed_events = chain
+        .removed_inbox_events
+        .get(&Origin::chain(busy_sender))
+        .await
+        .unwrap();
+    assert_eq!(
+        removed_events,
+        Some(vec![
+            RemovedEvents {
+                timestamp: Timestamp::default(),
+                count: 1,
+            },
+            RemovedEvents {
+                timestamp,
+                count: 2,
+            },
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_inbox_policy_changes_apply_to_later_messages() {
+    let sender = ChainId::root(1);
+    let mut chain = make_chain(2, Amount::ZERO).await;
+    let chain_id = chain.chain_id();
+    // The sender was denied at the time 5.
+    let system = &mut chain.execution_state.system;
+    system.timestamp.set(Timestamp::from(10));
+    system
+        .previous_inbox_policies
+        .push((Timestamp::from(5), InboxPolicy::default()));
+    system.inbox_policy.set(InboxPolicy {
+        denied_senders: [sender].into_iter().collect(),
+        ..InboxPolicy::default()
+    });
+
+    let message = make_outgoing_message(chain_id, MessageKind::Simple, Amount::ONE);
+    for (height, timestamp) in [(0, 4), (1, 5), (2, 6)] {
+        receive_block_at(
+            &mut chain,
+            sender,
+            BlockHeight::from(height),
+            Timestamp::from(timestamp),
+            vec![message.clone()],
+            MessageAction::Accept,
+        )
+        .await;
+    }
+
+    // Only the messages sent before the blocks that changed the policy, or at the same time,
+    // are accepted.
+    let inbox = chain
+        .inboxes
+        .try_load_entry(&Origin::chain(sender))
+        .await
+        .unwrap();
+    let heights = inbox
+        .added_events
+        .elements()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|event| event.height)
+        .collect::<Vec<_>>();
+    assert_eq!(heights, vec![BlockHeight::ZERO, BlockHeight::from(1)]);
+}
+
+#[tokio::test]
+async fn test_closed_chain_returns_incoming_messages() {
+    let sender = ChainId::root(1);
+    let admin_id = ChainId::root(0);
+    let mut chain = make_chain(2, Amount::ZERO).await;
+    let chain_id = chain.chain_id();
+    chain.execution_state.system.closed.set(true);
+    set_policy(&mut chain, ResourceControlPolicy::all_categories());
+
+    // Closed chains don't execute operations anymore.
+    let block = make_first_block(chain_id).with_simple_transfer(Recipient::root(3), Amount::ONE);
+    let result = chain.execute_block(&block, Timestamp::default()).await;
+    assert!(matches!(result, Err(ChainError::ClosedChain(id)) if id == chain_id));
+
+    // Even accepted and simple messages are refused, and the tracked ones are returned to
+    // their senders, without fees. Only the committee changes from the admin chain are
+    // executed.
+    let tracked_message = make_outgoing_message(chain_id, MessageKind::Tracked, Amount::ONE);
+    let simple_message = make_outgoing_message(chain_id, MessageKind::Simple, Amount::ONE);
+    let admin_message = OutgoingMessage {
+        destination: Destination::Recipient(chain_id),
+        authenticated_signer: None,
+        is_skippable: false,
+        kind: MessageKind::Simple,
+        grant: Amount::ZERO,
+        message: Message::System(SystemMessage::SetCommittees {
+            epoch: Epoch::ZERO,
+            committees: chain.execution_state.system.committees.get().clone(),
+            expirations: BTreeMap::new(),
+        }),
+    };
+    let incoming_messages = receive_messages(
+        &mut chain,
+        sender,
+        vec![tracked_message.clone(), simple_message],
+        MessageAction::Accept,
+    )
+    .await;
+    let admin_incoming_message =
+        receive_message(&mut chain, admin_id, admin_message, MessageAction::Accept).await;
+    let block = incoming_messages
+        .into_iter()
+        .chain([admin_incoming_message])
+        .fold(make_first_block(chain_id), |block, message| {
+            block.with_incoming_message(message)
+        });
+    chain.remove_events_from_inboxes(&block).await.unwrap();
+    let outcome = chain
+        .execute_block(&block, Timestamp::default())
+        .await
+        .unwrap();
+
+    assert_eq!(*chain.execution_state.system.balance.get(), Amount::ZERO);
+    assert_eq!(
+        outcome.messages,
+        vec![OutgoingMessage {
+            destination: Destination::Recipient(sender),
+            kind: MessageKind::Bounce,
+            ..tracked_message
+        }]
+    );
+    assert_eq!(outcome.message_counts, vec![1, 1, 1]);
+}
+
+#[tokio::test]
+async fn test_expired_messages_are_abandoned_unless_returnable() {
+    let claim_target = ChainId::root(3);
+    let recipient = ChainId::root(2);
+    let mut chain = make_chain(1, Amount::from_tokens(10)).await;
+    let chain_id = chain.chain_id();
+    set_policy(
+        &mut chain,
+        ResourceControlPolicy {
+            message_expiry_micros: 1_000,
+            ..ResourceControlPolicy::default()
+        },
+    );
+
+    // A simple message, followed by a tracked one to another recipient.
+    let owner = Owner::from(PublicKey::debug(1));
+    let block = Block {
+        authenticated_signer: Some(owner),
+        ..make_first_block(chain_id)
+    }
+    .with_operation(SystemOperation::Claim {
+        owner,
+        target: claim_target,
+        recipient: Recipient::chain(chain_id),
+        amount: Amount::ONE,
+        user_data: UserData::default(),
+    });
+    let outcome = chain
+        .execute_block(&block, Timestamp::default())
+        .await
+        .unwrap();
+    let [claim] = &outcome.messages[..] else {
+        panic!("Unexpected messages: {:?}", outcome.messages);
+    };
+    assert_eq!(claim.kind, MessageKind::Simple);
+    let block = Block {
+        height: BlockHeight::from(1),
+        ..make_first_block(chain_id)
+    }
+    .with_simple_transfer(Recipient::chain(recipient), Amount::ONE);
+    let outcome = chain
+        .execute_block(&block, Timestamp::default())
+        .await
+        .unwrap();
+    let [transfer] = &outcome.messages[..] else {
+        panic!("Unexpected messages: {:?}", outcome.messages);
+    };
+    assert_eq!(transfer.kind, MessageKind::Tracked);
+
+    // Once they have expired, only the simple message is abandoned. The transfer is not
+    // refunded: its recipient will bounce it instead.
+    let block = Block {
+        height: BlockHeight::from(2),
+        ..make_first_block(chain_id)
+    }
+    .with_timestamp(1_001);
+    let outcome = chain
+        .execute_block(&block, Timestamp::default())
+        .await
+        .unwrap();
+    assert!(outcome.messages.is_empty());
+    assert_eq!(
+        *chain.execution_state.system.balance.get(),
+        Amount::from_tokens(9)
+    );
+    assert_eq!(
+        chain.outboxes.indices().await.unwrap(),
+        vec![Target::chain(recipient)]
+    );
+    assert_eq!(
+        *chain.outbox_counters.get(),
+        BTreeMap::from([(BlockHeight::from(1), 1)])
+    );
+    assert_eq!(
+        *chain.outbox_timestamps.get(),
+        BTreeMap::from([(BlockHeight::from(1), Timestamp::default())])
+    );
+}
+
+#[tokio::test]
+async fn test_expired_messages_are_refused() {
+    let sender = ChainId::root(1);
+    let mut chain = make_chain(2, Amount::ZERO).await;
+    let chain_id = chain.chain_id();
+    set_policy(
+        &mut chain,
+        ResourceControlPolicy {
+            message_expiry_micros: 1_000,
+            ..ResourceControlPolicy::default()
+        },
+    );
+    let tracked_message = make_outgoing_message(chain_id, MessageKind::Tracked, Amount::ONE);
+    let simple_message = make_outgoing_message(chain_id, MessageKind::Simple, Amount::ONE);
+    let incoming_messages = receive_messages(
+        &mut chain,
+        sender,
+        vec![tracked_message.clone(), simple_message],
+        MessageAction::Accept,
+    )
+    .await;
+
+    // Neither message is executed: the tracked one bounces, so that its sender gets the
+    // tokens back, and the simple one is dropped.
+    let block = incoming_messages
+        .into_iter()
+        .fold(make_first_block(chain_id), |block, message| {
+            block.with_incoming_message(message)
+        })
+        .with_timestamp(1_001);
+    chain.remove_events_from_inboxes(&block).await.unwrap();
+    let outcome = chain
+        .execute_block(&block, Timestamp::default())
+        .await
+        .unwrap();
+    assert_eq!(*chain.execution_state.system.balance.get(), Amount::ZERO);
+    assert_eq!(
+        outcome.messages,
+        vec![OutgoingMessage {
+            destination: Destination::Recipient(sender),
+            kind: MessageKind::Bounce,
+            ..tracked_message
+        }]
+    );
+    assert_eq!(outcome.message_counts, vec![1, 1]);
+}
+
+#[tokio::test]
+async fn test_restore_checkpoint_checks_the_chunks() {
+    let mut chain = make_chain(1, Amount::from_tokens(10)).await;
+    let block =
+        make_first_block(chain.chain_id()).with_simple_transfer(Recipient::root(2), Amount::ONE);
+    chain
+        .execute_block(&block, Timestamp::default())
+        .await
+        .unwrap();
+    chain.save().await.unwrap();
+    let (checkpoint, chunks) = chain.checkpoint(1_000).await.unwrap();
+    assert_eq!(checkpoint.state_hash, *chain.execution_state_hash.get());
+
+    // Chunks with keys outside of the checkpointed views are rejected.
+    let mut restored = make_chain(1, Amount::ZERO).await;
+    let mut tampered_chunks = chunks.clone();
+    tampered_chunks[0].entries.push((vec![255], vec![0]));
+    let result = restored
+        .restore_checkpoint(&checkpoint, tampered_chunks, Timestamp::default())
+        .await;
+    assert!(matches!(result, Err(ChainError::InvalidCheckpointChunks)));
+
+    // Chunks that don't add up to the logical state of the checkpoint are rejected, and
+    // nothing is written.
+    let base_key = chain.chain_base_key();
+    let hash_key = chain.execution_state_hash.context().base_key()[base_key.len()..].to_vec();
+    let tampered_chunks = chunks
+        .iter()
+        .map(|chunk| CheckpointChunk {
+            entries: chunk
+                .entries
+                .iter()
+                .filter(|(key, _)| !key.starts_with(&hash_key))
+                .cloned()
+                .collect(),
+        })
+        .collect();
+    let result = restored
+        .restore_checkpoint(&checkpoint, tampered_chunks, Timestamp::default())
+        .await;
+    assert!(matches!(result, Err(ChainError::InvalidCheckpointChunks)));
+    let context = restored.context().clone();
+    let reloaded = TestChain::load(context).await.unwrap();
+    assert_eq!(*reloaded.execution_state_hash.get(), None);
+
+    // The chunks of the checkpoint are restored.
+    restored
+        .restore_checkpoint(&checkpoint, chunks, Timestamp::default())
+        .await
+        .unwrap();
+    assert_eq!(*restored.execution_state_hash.get(), checkpoint.state_hash);
+    assert_eq!(
+        *restored.execution_state.system.balance.get(),
+        Amount::from_tokens(9)
+    );
+    assert_eq!(
+        restored.outboxes.indices().await.unwrap(),
+        vec![Target::chain(ChainId::root(2))]
+    );
+}
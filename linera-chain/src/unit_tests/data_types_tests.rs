@@ -3,9 +3,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use crate::test::{make_first_block, BlockTestExt};
-use linera_base::data_types::Amount;
-use linera_execution::system::Recipient;
+use crate::test::{make_child_block, make_first_block, BlockTestExt};
+use linera_base::{crypto::SignatureScheme, data_types::Amount, identifiers::ApplicationId};
+use linera_execution::{
+    committee::ValidatorChange,
+    system::{Account, AdminOperation, Recipient, SystemChannel, SystemOperation, UserData},
+    ApprovalScope, ApprovalThreshold, Bytecode, ChainOwnership, InboxPolicy, MessageOutcome,
+    SystemMessage, TimeoutBackoff, TimeoutConfig, UserApplicationDescription,
+};
+use linera_light_client::{BlockCertificate, ChainTip, LightClient, LightClientError};
+use std::collections::BTreeMap;
 
 #[test]
 fn test_signed_values() {
@@ -19,6 +26,8 @@ fn test_signed_values() {
         block,
         messages: Vec::new(),
         message_counts: vec![1],
+        events: Vec::new(),
+        failed_operations: Vec::new(),
         state_hash: CryptoHash::new(&Dummy),
     };
     let value = HashedValue::new_confirmed(executed_block);
@@ -47,6 +56,8 @@ fn test_certificates() {
         block,
         messages: Vec::new(),
         message_counts: vec![1],
+        events: Vec::new(),
+        failed_operations: Vec::new(),
         state_hash: CryptoHash::new(&Dummy),
     };
     let value = HashedValue::new_confirmed(executed_block);
@@ -73,6 +84,604 @@ fn test_certificates() {
     assert!(builder.append(v3.validator, v3.signature).is_err());
 }
 
+#[test]
+fn test_random_seeds() {
+    let key_pairs = (0..4).map(|_| KeyPair::generate()).collect::<Vec<_>>();
+    let committee = Committee::make_simple(
+        key_pairs
+            .iter()
+            .map(|key_pair| ValidatorName(key_pair.public()))
+            .collect(),
+    );
+    let first_block = make_first_block(ChainId::root(1));
+    let executed_block = ExecutedBlock {
+        block: first_block.clone(),
+        messages: Vec::new(),
+        message_counts: Vec::new(),
+        events: Vec::new(),
+        failed_operations: Vec::new(),
+        state_hash: CryptoHash::new(&Dummy),
+    };
+    let value = HashedValue::new_confirmed(executed_block);
+    let votes = key_pairs
+        .iter()
+        .map(|key_pair| LiteVote::new(value.lite(), RoundNumber(0), key_pair))
+        .collect::<Vec<_>>();
+    let certificate = Certificate::new(
+        value.clone(),
+        RoundNumber(0),
+        votes
+            .iter()
+            .map(|vote| (vote.validator, vote.signature))
+            .collect(),
+    );
+
+    // The canonical signatures are the first quorum of the certificate's signatures.
+    let signatures = certificate.certificate_signatures(&committee);
+    assert_eq!(signatures.signatures.len(), 3);
+    assert_eq!(signatures.signatures[..], certificate.signatures()[..3]);
+
+    let block = make_child_block(&value);
+    let certified_block = Block {
+        previous_block_signatures: Some(signatures.clone()),
+        ..block.clone()
+    };
+    assert!(certified_block
+        .check_previous_block_signatures([&committee])
+        .is_ok());
+
+    // Each transaction has its own seed, which depends on the previous block's signatures.
+    let seeds = [
+        certified_block.random_seed(Transaction::IncomingMessage(0)),
+        certified_block.random_seed(Transaction::Operation(0)),
+        certified_block.random_seed(Transaction::Operation(1)),
+        first_block.random_seed(Transaction::Operation(0)),
+    ]
+    .map(Option::unwrap);
+    assert_eq!(seeds.iter().collect::<HashSet<_>>().len(), seeds.len());
+
+    // Blocks proposed without the previous block's signatures have no seed.
+    assert_eq!(block.random_seed(Transaction::Operation(0)), None);
+
+    // Signatures that are not a minimal quorum sorted by name are rejected.
+    let mut unsorted_signatures = signatures.clone();
+    unsorted_signatures.signatures.reverse();
+    let larger_signatures = CertificateSignatures {
+        round: RoundNumber(0),
+        signatures: certificate.signatures().clone(),
+    };
+    let smaller_signatures = CertificateSignatures {
+        round: RoundNumber(0),
+        signatures: certificate.signatures()[..2].to_vec(),
+    };
+    for signatures in [unsorted_signatures, larger_signatures, smaller_signatures] {
+        let block = Block {
+            previous_block_signatures: Some(signatures),
+            ..block.clone()
+        };
+        assert!(matches!(
+            block.check_previous_block_signatures([&committee]),
+            Err(ChainError::InvalidPreviousBlockSignatures)
+        ));
+    }
+
+    // Signatures that don't certify the previous block are rejected.
+    let other_vote = LiteVote::new(value.lite(), RoundNumber(0), &KeyPair::generate());
+    let mut forged_signatures = signatures;
+    forged_signatures.signatures[0].1 = other_vote.signature;
+    let forged_block = Block {
+        previous_block_signatures: Some(forged_signatures),
+        ..block
+    };
+    assert!(matches!(
+        forged_block.check_previous_block_signatures([&committee]),
+        Err(ChainError::InvalidPreviousBlockSignatures)
+    ));
+}
+
+/// Returns the light client's identifier of the chain.
+fn light_chain_id(chain_id: ChainId) -> linera_light_client::ChainId {
+    let bytes: [u8; 32] = chain_id.0.as_bytes().as_slice().try_into().unwrap();
+    linera_light_client::ChainId(bytes.into())
+}
+
+/// Returns the light client's view of the committee, from its binary encoding.
+fn light_committee(committee: &Committee) -> linera_light_client::Committee {
+    linera_light_client::Committee::from_bcs(&bcs::to_bytes(committee).unwrap()).unwrap()
+}
+
+/// Returns a certificate for the block signed by the given validators.
+fn make_certificate(executed_block: ExecutedBlock, key_pairs: &[&KeyPair]) -> Certificate {
+    let value = HashedValue::new_confirmed(executed_block);
+    let signatures = key_pairs
+        .iter()
+        .map(|key_pair| {
+            let vote = LiteVote::new(value.lite(), RoundNumber(0), key_pair);
+            (vote.validator, vote.signature)
+        })
+        .collect();
+    Certificate::new(value, RoundNumber(0), signatures)
+}
+
+/// Returns the light client's view of the certificate, from its binary encoding.
+fn light_certificate(certificate: &Certificate) -> BlockCertificate {
+    BlockCertificate::from_bcs(&bcs::to_bytes(certificate).unwrap()).unwrap()
+}
+
+fn executed_block(block: Block, messages: Vec<OutgoingMessage>) -> ExecutedBlock {
+    ExecutedBlock {
+        block,
+        messages,
+        message_counts: Vec::new(),
+        events: Vec::new(),
+        failed_operations: Vec::new(),
+        state_hash: CryptoHash::new(&Dummy),
+    }
+}
+
+fn set_committees_message(
+    epoch: Epoch,
+    committees: BTreeMap<Epoch, Committee>,
+    expirations: BTreeMap<Epoch, Timestamp>,
+) -> OutgoingMessage {
+    OutgoingMessage {
+        destination: Destination::Subscribers(SystemChannel::Admin.name()),
+        authenticated_signer: None,
+        is_skippable: false,
+        kind: MessageKind::Simple,
+        grant: Amount::ZERO,
+        message: Message::System(SystemMessage::SetCommittees {
+            epoch,
+            committees,
+            expirations,
+        }),
+    }
+}
+
+#[test]
+fn test_light_client_decodes_certificates() {
+    let key1 = KeyPair::generate();
+    let key2 = KeyPair::generate_with_scheme(SignatureScheme::Secp256k1);
+    let committee0 = Committee::make_simple(vec![ValidatorName(key1.public())]);
+    let committee1 = Committee::make_simple(vec![
+        ValidatorName(key1.public()),
+        ValidatorName(key2.public()),
+    ]);
+    let committees = BTreeMap::from([
+        (Epoch::ZERO, committee0.clone()),
+        (Epoch::from(1), committee1.clone()),
+    ]);
+    let admin_id = ChainId::root(0);
+    let owner = Owner::from(key1.public());
+    let application_id = ApplicationId::default();
+    let account = Account {
+        chain_id: ChainId::root(1),
+        owner: Some(owner),
+    };
+    let ownership =
+        ChainOwnership::multiple([(key1.public(), 1), (key2.public(), 2)], RoundNumber(3))
+            .with_approval_threshold(Some(ApprovalThreshold {
+                weight: 2,
+                scope: ApprovalScope::TransfersAndOwnership,
+            }))
+            .with_timeout_config(TimeoutConfig {
+                backoff: TimeoutBackoff::Exponential { factor: 2 },
+                ..TimeoutConfig::default()
+            });
+
+    // A block using as many of the types of the protocol as possible, to check that the
+    // light client decodes their layout correctly.
+    let system_operations = vec![
+        SystemOperation::Transfer {
+            owner: Some(owner),
+            recipient: Recipient::Account(account),
+            amount: Amount::ONE,
+            user_data: UserData(Some([1; 32])),
+        },
+        SystemOperation::Claim {
+            owner,
+            target: ChainId::root(1),
+            recipient: Recipient::Burn,
+            amount: Amount::ONE,
+            user_data: UserData(None),
+        },
+        SystemOperation::OpenChain {
+            ownership: ownership.clone(),
+            admin_id,
+            epoch: Epoch::ZERO,
+            committees: committees.clone(),
+        },
+        SystemOperation::CloseChain {
+            recipient: Some(Recipient::root(1)),
+        },
+        SystemOperation::ChangeOwner {
+            new_public_key: key2.public(),
+        },
+        SystemOperation::ChangeMultipleOwners {
+            new_public_keys: vec![(key1.public(), 1)],
+            multi_leader_rounds: RoundNumber(2),
+            approval_threshold: None,
+            timeout_config: TimeoutConfig::default(),
+        },
+        SystemOperation::Subscribe {
+            chain_id: admin_id,
+            channel: SystemChannel::PublishedBytecodes,
+        },
+        SystemOperation::ChangeInboxPolicy {
+            policy: InboxPolicy {
+                allowed_senders: Some([admin_id].into()),
+                denied_senders: [ChainId::root(2)].into(),
+                allowed_applications: Some([GenericApplicationId::System].into()),
+                denied_applications: [GenericApplicationId::User(application_id)].into(),
+                maximum_pending_messages: Some(10),
+                minimum_grant: Amount::ONE,
+            },
+        },
+        SystemOperation::PublishBytecode {
+            contract: Bytecode::new(vec![1, 2, 3]),
+            service: Bytecode::new(vec![4]),
+        },
+        SystemOperation::CreateApplication {
+            bytecode_id: application_id.bytecode_id,
+            parameters: vec![5],
+            initialization_argument: vec![6, 7],
+            required_application_ids: vec![application_id],
+            storage_quota: Some(100),
+        },
+        SystemOperation::RequestApplication {
+            chain_id: admin_id,
+            application_id,
+        },
+        SystemOperation::Admin(AdminOperation::CreateCommittee {
+            epoch: Epoch::from(1),
+            committee: committee1.clone(),
+        }),
+        SystemOperation::Admin(AdminOperation::ChangeValidators {
+            changes: vec![
+                ValidatorChange::Join {
+                    name: ValidatorName(key2.public()),
+                    network_address: "address".to_string(),
+                    votes: 1,
+                },
+                ValidatorChange::RotateKey {
+                    name: ValidatorName(key1.public()),
+                    new_name: ValidatorName(key2.public()),
+                },
+                ValidatorChange::Exit {
+                    name: ValidatorName(key1.public()),
+                },
+            ],
+            grace_period_micros: 10,
+        }),
+    ];
+    let mut block = make_first_block(admin_id)
+        .with_operation(Operation::User {
+            application_id,
+            bytes: vec![8, 9],
+        })
+        .with_incoming_message(IncomingMessage {
+            origin: Origin::channel(
+                ChainId::root(1),
+                ChannelFullName {
+                    application_id: GenericApplicationId::User(application_id),
+                    name: ChannelName::from(vec![10]),
+                },
+            ),
+            event: Event {
+                certificate_hash: CryptoHash::new(&Dummy),
+                height: BlockHeight::from(2),
+                index: 3,
+                authenticated_signer: Some(owner),
+                is_skippable: true,
+                kind: MessageKind::Acknowledgement(MessageOutcome::Failed(4)),
+                grant: Amount::ONE,
+                timestamp: Timestamp::from(5),
+                message: Message::System(SystemMessage::Credit {
+                    account,
+                    amount: Amount::ONE,
+                    source: Some(owner),
+                }),
+            },
+            action: MessageAction::Reject,
+        })
+        .with_timestamp(20);
+    for operation in system_operations {
+        block = block.with_operation(operation);
+    }
+    block.authenticated_signer = Some(owner);
+    block.previous_block_hash = Some(CryptoHash::new(&Dummy));
+    block.height = BlockHeight::from(7);
+    block.previous_block_signatures = Some(CertificateSignatures {
+        round: RoundNumber(1),
+        signatures: vec![(ValidatorName(key2.public()), Signature::new(&Dummy, &key2))],
+    });
+    let simple_message = |destination: Destination, message: SystemMessage| OutgoingMessage {
+        destination,
+        authenticated_signer: Some(owner),
+        is_skippable: true,
+        kind: MessageKind::Tracked,
+        grant: Amount::ONE,
+        message: Message::System(message),
+    };
+    let expirations = BTreeMap::from([(Epoch::ZERO, Timestamp::from(10))]);
+    let messages = vec![
+        simple_message(
+            Destination::Recipient(ChainId::root(3)),
+            SystemMessage::OpenChain {
+                ownership: ChainOwnership::single(key1.public()),
+                admin_id,
+                epoch: Epoch::ZERO,
+                committees: committees.clone(),
+            },
+        ),
+        set_committees_message(
+            Epoch::ZERO,
+            BTreeMap::from([(Epoch::ZERO, committee0.clone())]),
+            BTreeMap::new(),
+        ),
+        simple_message(
+            Destination::Recipient(ChainId::root(3)),
+            SystemMessage::RegisterApplications {
+                applications: vec![UserApplicationDescription {
+                    bytecode_id: application_id.bytecode_id,
+                    bytecode_location: BytecodeLocation {
+                        certificate_hash: CryptoHash::new(&Dummy),
+                        operation_index: 1,
+                    },
+                    creation: MessageId::default(),
+                    parameters: vec![11],
+                    required_application_ids: vec![application_id],
+                    storage_quota: None,
+                }],
+            },
+        ),
+        set_committees_message(Epoch::from(1), committees, expirations.clone()),
+        simple_message(
+            Destination::Subscribers(ChannelName::from(vec![12])),
+            SystemMessage::Withdraw {
+                account,
+                amount: Amount::ONE,
+                recipient: Recipient::Burn,
+                user_data: UserData::default(),
+            },
+        ),
+        OutgoingMessage {
+            destination: Destination::Recipient(ChainId::root(4)),
+            authenticated_signer: None,
+            is_skippable: false,
+            kind: MessageKind::Bounce,
+            grant: Amount::ZERO,
+            message: Message::User {
+                application_id,
+                bytes: vec![13],
+            },
+        },
+    ];
+    let executed_block = ExecutedBlock {
+        block,
+        messages,
+        message_counts: vec![0, 1, 6],
+        events: vec![ApplicationEvent {
+            application_id: GenericApplicationId::User(application_id),
+            transaction_index: 1,
+            topic: vec![14],
+            value: vec![15, 16],
+        }],
+        failed_operations: vec![FailedOperation {
+            index: 2,
+            error: "error".to_string(),
+            fees: Amount::ONE,
+        }],
+        state_hash: CryptoHash::new(&Dummy),
+    };
+    let certificate = make_certificate(executed_block, &[&key1]);
+
+    let block_certificate = light_certificate(&certificate);
+    assert_eq!(
+        block_certificate.value_hash().as_bytes()[..],
+        certificate.hash().as_bytes()[..]
+    );
+    assert_eq!(block_certificate.chain_id(), light_chain_id(admin_id));
+    assert_eq!(block_certificate.height().0, 7);
+    assert_eq!(block_certificate.timestamp().0, 20);
+    assert_eq!(block_certificate.signatures().len(), 1);
+    // The last `SetCommittees` message of the block defines the committees.
+    let update = block_certificate.committee_update().unwrap();
+    assert_eq!(update.epoch.0, 1);
+    assert_eq!(
+        update.committees,
+        BTreeMap::from([
+            (linera_light_client::Epoch(0), light_committee(&committee0)),
+            (linera_light_client::Epoch(1), light_committee(&committee1)),
+        ])
+    );
+    assert_eq!(
+        update.expirations,
+        BTreeMap::from([(
+            linera_light_client::Epoch(0),
+            linera_light_client::Timestamp(10)
+        )])
+    );
+
+    // Lite certificates are decoded too.
+    let lite_certificate = linera_light_client::LiteCertificate::from_bcs(
+        &bcs::to_bytes(&certificate.lite_certificate()).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(lite_certificate.value_hash, block_certificate.value_hash());
+    assert_eq!(lite_certificate.chain_id, light_chain_id(admin_id));
+}
+
+#[test]
+fn test_light_client_block_chaining() {
+    let key1 = KeyPair::generate();
+    let key2 = KeyPair::generate();
+    let committee = Committee::make_simple(vec![
+        ValidatorName(key1.public()),
+        ValidatorName(key2.public()),
+    ]);
+    let admin_id = ChainId::root(0);
+    let chain_id = ChainId::root(1);
+    let mut client = LightClient::new(
+        light_chain_id(admin_id),
+        ChainTip::default(),
+        linera_light_client::Epoch(0),
+        BTreeMap::from([(linera_light_client::Epoch(0), light_committee(&committee))]),
+    );
+
+    let certificate0 = make_certificate(
+        executed_block(make_first_block(chain_id), Vec::new()),
+        &[&key1, &key2],
+    );
+    let block_certificate0 = light_certificate(&certificate0);
+    assert_eq!(
+        client.handle_confirmed_block(&block_certificate0),
+        Err(LightClientError::UnknownChain(light_chain_id(chain_id)))
+    );
+    client.follow_chain(light_chain_id(chain_id), ChainTip::default());
+    client.handle_confirmed_block(&block_certificate0).unwrap();
+    let tip = *client.tip(light_chain_id(chain_id)).unwrap();
+    assert_eq!(tip.next_block_height.0, 1);
+    assert_eq!(tip.block_hash, Some(block_certificate0.value_hash()));
+
+    // A certificate without a quorum is rejected.
+    let block1 = make_child_block(&certificate0.value).with_timestamp(123_456);
+    let certificate1 = make_certificate(executed_block(block1.clone(), Vec::new()), &[&key1]);
+    assert_eq!(
+        client.handle_confirmed_block(&light_certificate(&certificate1)),
+        Err(LightClientError::CertificateRequiresQuorum)
+    );
+
+    // The encoded block can't be altered: its hash wouldn't match the signatures anymore.
+    let certificate1 =
+        make_certificate(executed_block(block1.clone(), Vec::new()), &[&key1, &key2]);
+    let mut bytes = bcs::to_bytes(&certificate1).unwrap();
+    let timestamp = bcs::to_bytes(&Timestamp::from(123_456)).unwrap();
+    let position = bytes
+        .windows(timestamp.len())
+        .position(|window| window == timestamp)
+        .unwrap();
+    bytes[position] += 1;
+    assert_eq!(
+        client.handle_confirmed_block(&BlockCertificate::from_bcs(&bytes).unwrap()),
+        Err(LightClientError::InvalidSignature)
+    );
+
+    // A block that does not follow the tip is rejected.
+    let certificate = make_certificate(
+        executed_block(make_first_block(chain_id).with_timestamp(1), Vec::new()),
+        &[&key1, &key2],
+    );
+    assert!(matches!(
+        client.handle_confirmed_block(&light_certificate(&certificate)),
+        Err(LightClientError::UnexpectedBlockHeight { .. })
+    ));
+    let other_block = make_first_block(chain_id).with_timestamp(2);
+    let other_certificate =
+        make_certificate(executed_block(other_block, Vec::new()), &[&key1, &key2]);
+    let certificate = make_certificate(
+        executed_block(make_child_block(&other_certificate.value), Vec::new()),
+        &[&key1, &key2],
+    );
+    assert_eq!(
+        client.handle_confirmed_block(&light_certificate(&certificate)),
+        Err(LightClientError::UnexpectedPreviousBlockHash)
+    );
+
+    client
+        .handle_confirmed_block(&light_certificate(&certificate1))
+        .unwrap();
+    assert_eq!(
+        client
+            .tip(light_chain_id(chain_id))
+            .unwrap()
+            .next_block_height
+            .0,
+        2
+    );
+}
+
+#[test]
+fn test_light_client_committee_changes() {
+    let key1 = KeyPair::generate();
+    let key2 = KeyPair::generate();
+    let committee0 = Committee::make_simple(vec![ValidatorName(key1.public())]);
+    let committee1 = Committee::make_simple(vec![ValidatorName(key2.public())]);
+    let admin_id = ChainId::root(0);
+    let chain_id = ChainId::root(1);
+    let mut client = LightClient::new(
+        light_chain_id(admin_id),
+        ChainTip::default(),
+        linera_light_client::Epoch(0),
+        BTreeMap::from([(linera_light_client::Epoch(0), light_committee(&committee0))]),
+    );
+    client.follow_chain(light_chain_id(chain_id), ChainTip::default());
+
+    // Only the admin chain's `SetCommittees` messages change the committees.
+    let message = set_committees_message(
+        Epoch::from(1),
+        BTreeMap::from([(Epoch::from(1), committee1.clone())]),
+        BTreeMap::new(),
+    );
+    let certificate = make_certificate(
+        executed_block(make_first_block(chain_id), vec![message]),
+        &[&key1],
+    );
+    client
+        .handle_confirmed_block(&light_certificate(&certificate))
+        .unwrap();
+    assert_eq!(client.epoch(), linera_light_client::Epoch(0));
+
+    // The admin chain announces a new committee, with a grace period for the old one.
+    let message = set_committees_message(
+        Epoch::from(1),
+        BTreeMap::from([(Epoch::ZERO, committee0), (Epoch::from(1), committee1)]),
+        BTreeMap::from([(Epoch::ZERO, Timestamp::from(10))]),
+    );
+    let admin_certificate0 = make_certificate(
+        executed_block(make_first_block(admin_id), vec![message]),
+        &[&key1],
+    );
+    client
+        .handle_confirmed_block(&light_certificate(&admin_certificate0))
+        .unwrap();
+    assert_eq!(client.epoch(), linera_light_client::Epoch(1));
+    assert_eq!(client.committees().len(), 2);
+
+    // Both committees are trusted during the grace period.
+    let certificate1 = make_certificate(
+        executed_block(make_child_block(&certificate.value), Vec::new()),
+        &[&key1],
+    );
+    client
+        .handle_confirmed_block(&light_certificate(&certificate1))
+        .unwrap();
+    let block2 = make_child_block(&certificate1.value).with_epoch(1);
+    let certificate2 = make_certificate(executed_block(block2, Vec::new()), &[&key2]);
+    client
+        .handle_confirmed_block(&light_certificate(&certificate2))
+        .unwrap();
+
+    // The old committee is removed once the admin chain is past the grace period.
+    let admin_block1 = make_child_block(&admin_certificate0.value)
+        .with_epoch(1)
+        .with_timestamp(10);
+    let admin_certificate1 = make_certificate(executed_block(admin_block1, Vec::new()), &[&key2]);
+    client
+        .handle_confirmed_block(&light_certificate(&admin_certificate1))
+        .unwrap();
+    assert_eq!(
+        client.committees().keys().copied().collect::<Vec<_>>(),
+        vec![linera_light_client::Epoch(1)]
+    );
+    assert_eq!(
+        client.check_certificate(&light_certificate(&certificate1)),
+        Err(LightClientError::UnknownEpoch(linera_light_client::Epoch(
+            0
+        )))
+    );
+}
+
 #[derive(Serialize, Deserialize)]
 struct Dummy;
 
@@ -5,9 +5,9 @@
 use super::*;
 use linera_base::{
     crypto::{BcsSignable, CryptoHash},
-    data_types::Timestamp,
+    data_types::{Amount, Timestamp},
 };
-use linera_execution::{Message, UserApplicationId};
+use linera_execution::{Message, MessageKind, UserApplicationId};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -30,6 +30,8 @@ fn make_event(
         index,
         authenticated_signer: None,
         is_skippable: true,
+        kind: MessageKind::Simple,
+        grant: Amount::ZERO,
         timestamp: Timestamp::default(),
         message: Message::User {
             application_id: UserApplicationId::default(),
@@ -49,19 +51,51 @@ fn make_unskippable_event(
     event
 }
 
+/// Receives an event and accepts it. The inbox is left unchanged if this fails.
+async fn receive_and_add_event(
+    view: &mut InboxStateView<MemoryContext<()>>,
+    event: Event,
+) -> Result<(), InboxError> {
+    let next_cursor_to_add = *view.next_cursor_to_add.get();
+    view.receive_event(event)?;
+    let event = view
+        .received_events
+        .front()
+        .await?
+        .expect("a received event");
+    view.received_events.delete_front();
+    let result = view.add_event(event).await;
+    if result.is_err() {
+        view.next_cursor_to_add.set(next_cursor_to_add);
+    }
+    result
+}
+
+/// Receives an event and refuses it.
+async fn receive_and_refuse_event(
+    view: &mut InboxStateView<MemoryContext<()>>,
+    event: Event,
+) -> Result<(), InboxError> {
+    view.receive_event(event)?;
+    view.received_events.delete_front();
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_inbox_add_then_remove_skippable() {
     let hash = CryptoHash::new(&Dummy);
     let mut view = InboxStateView::new().await;
     // Add one event.
-    view.add_event(make_event(hash, 0, 0, [0])).await.unwrap();
+    receive_and_add_event(&mut view, make_event(hash, 0, 0, [0]))
+        .await
+        .unwrap();
     // Remove the same event
     view.remove_event(&make_event(hash, 0, 0, [0]))
         .await
         .unwrap();
     // Fail to add an old event.
     assert!(matches!(
-        view.add_event(make_event(hash, 0, 0, [0])).await,
+        receive_and_add_event(&mut view, make_event(hash, 0, 0, [0])).await,
         Err(InboxError::IncorrectOrder { .. })
     ));
     // Fail to remove an old event.
@@ -70,8 +104,12 @@ async fn test_inbox_add_then_remove_skippable() {
         Err(InboxError::IncorrectOrder { .. })
     ));
     // Add two more events.
-    view.add_event(make_event(hash, 0, 1, [1])).await.unwrap();
-    view.add_event(make_event(hash, 1, 0, [2])).await.unwrap();
+    receive_and_add_event(&mut view, make_event(hash, 0, 1, [1]))
+        .await
+        .unwrap();
+    receive_and_add_event(&mut view, make_event(hash, 1, 0, [2]))
+        .await
+        .unwrap();
     // Fail to remove non-matching event.
     assert!(matches!(
         view.remove_event(&make_event(hash, 0, 1, [0])).await,
@@ -101,7 +139,9 @@ async fn test_inbox_remove_then_add_skippable() {
         .await
         .unwrap();
     // Add the same event
-    view.add_event(make_event(hash, 0, 0, [0])).await.unwrap();
+    receive_and_add_event(&mut view, make_event(hash, 0, 0, [0]))
+        .await
+        .unwrap();
     // Fail to remove an old event.
     assert!(matches!(
         view.remove_event(&make_event(hash, 0, 0, [0])).await,
@@ -109,7 +149,7 @@ async fn test_inbox_remove_then_add_skippable() {
     ));
     // Fail to add an old event.
     assert!(matches!(
-        view.add_event(make_event(hash, 0, 0, [0])).await,
+        receive_and_add_event(&mut view, make_event(hash, 0, 0, [0])).await,
         Err(InboxError::IncorrectOrder { .. })
     ));
     // Remove two more events.
@@ -121,30 +161,29 @@ async fn test_inbox_remove_then_add_skippable() {
         .unwrap();
     // Fail to add non-matching event.
     assert!(matches!(
-        view.add_event(make_event(hash, 0, 1, [0])).await,
+        receive_and_add_event(&mut view, make_event(hash, 0, 1, [0])).await,
         Err(InboxError::UnexpectedEvent { .. })
     ));
     // Fail to add non-matching event (hash).
     assert!(matches!(
-        view.add_event(make_event(CryptoHash::new(&Dummy2), 0, 1, [1]))
-            .await,
+        receive_and_add_event(&mut view, make_event(CryptoHash::new(&Dummy2), 0, 1, [1])).await,
         Err(InboxError::UnexpectedEvent { .. })
     ));
     // NOT OK to forget about previous consumed events while backfilling.
     assert!(matches!(
-        view.add_event(make_event(hash, 1, 0, [2])).await,
+        receive_and_add_event(&mut view, make_event(hash, 1, 0, [2])).await,
         Err(InboxError::UnexpectedEvent { .. })
     ));
     // OK to backfill the two consumed events, with one skippable event in the middle.
-    view.add_event(make_event(hash, 0, 1, [1])).await.unwrap();
-    // Cannot add an unskippable event that was visibly skipped already.
-    assert!(matches!(
-        view.add_event(make_unskippable_event(hash, 1, 0, [2]))
-            .await,
-        Err(InboxError::UnexpectedEvent { .. })
-    ));
-    view.add_event(make_event(hash, 1, 0, [2])).await.unwrap();
-    view.add_event(make_event(hash, 1, 1, [3])).await.unwrap();
+    receive_and_add_event(&mut view, make_event(hash, 0, 1, [1]))
+        .await
+        .unwrap();
+    receive_and_add_event(&mut view, make_event(hash, 1, 0, [2]))
+        .await
+        .unwrap();
+    receive_and_add_event(&mut view, make_event(hash, 1, 1, [3]))
+        .await
+        .unwrap();
     // Inbox is empty again.
     assert_eq!(view.added_events.count(), 0);
     assert_eq!(view.removed_events.count(), 0);
@@ -155,7 +194,7 @@ async fn test_inbox_add_then_remove_unskippable() {
     let hash = CryptoHash::new(&Dummy);
     let mut view = InboxStateView::new().await;
     // Add one event.
-    view.add_event(make_unskippable_event(hash, 0, 0, [0]))
+    receive_and_add_event(&mut view, make_unskippable_event(hash, 0, 0, [0]))
         .await
         .unwrap();
     // Remove the same event
@@ -164,8 +203,7 @@ async fn test_inbox_add_then_remove_unskippable() {
         .unwrap();
     // Fail to add an old event.
     assert!(matches!(
-        view.add_event(make_unskippable_event(hash, 0, 0, [0]))
-            .await,
+        receive_and_add_event(&mut view, make_unskippable_event(hash, 0, 0, [0])).await,
         Err(InboxError::IncorrectOrder { .. })
     ));
     // Fail to remove an old event.
@@ -175,10 +213,10 @@ async fn test_inbox_add_then_remove_unskippable() {
         Err(InboxError::IncorrectOrder { .. })
     ));
     // Add two more events.
-    view.add_event(make_unskippable_event(hash, 0, 1, [1]))
+    receive_and_add_event(&mut view, make_unskippable_event(hash, 0, 1, [1]))
         .await
         .unwrap();
-    view.add_event(make_unskippable_event(hash, 1, 0, [2]))
+    receive_and_add_event(&mut view, make_unskippable_event(hash, 1, 0, [2]))
         .await
         .unwrap();
     // Fail to remove non-matching event.
@@ -217,7 +255,7 @@ async fn test_inbox_remove_then_add_unskippable() {
         .await
         .unwrap();
     // Add the same event
-    view.add_event(make_unskippable_event(hash, 0, 0, [0]))
+    receive_and_add_event(&mut view, make_unskippable_event(hash, 0, 0, [0]))
         .await
         .unwrap();
     // Fail to remove an old event.
@@ -228,8 +266,7 @@ async fn test_inbox_remove_then_add_unskippable() {
     ));
     // Fail to add an old event.
     assert!(matches!(
-        view.add_event(make_unskippable_event(hash, 0, 0, [0]))
-            .await,
+        receive_and_add_event(&mut view, make_unskippable_event(hash, 0, 0, [0])).await,
         Err(InboxError::IncorrectOrder { .. })
     ));
     // Remove two more events.
@@ -241,33 +278,32 @@ async fn test_inbox_remove_then_add_unskippable() {
         .unwrap();
     // Fail to add non-matching event.
     assert!(matches!(
-        view.add_event(make_unskippable_event(hash, 0, 1, [0]))
-            .await,
+        receive_and_add_event(&mut view, make_unskippable_event(hash, 0, 1, [0])).await,
         Err(InboxError::UnexpectedEvent { .. })
     ));
     // Fail to add non-matching event (hash).
     assert!(matches!(
-        view.add_event(make_unskippable_event(CryptoHash::new(&Dummy2), 0, 1, [1]))
-            .await,
+        receive_and_add_event(
+            &mut view,
+            make_unskippable_event(CryptoHash::new(&Dummy2), 0, 1, [1])
+        )
+        .await,
         Err(InboxError::UnexpectedEvent { .. })
     ));
     // NOT OK to forget about previous consumed events while backfilling.
     assert!(matches!(
-        view.add_event(make_unskippable_event(hash, 1, 1, [3]))
-            .await,
+        receive_and_add_event(&mut view, make_unskippable_event(hash, 1, 1, [3])).await,
         Err(InboxError::UnexpectedEvent { .. })
     ));
     // OK to add the two events.
-    view.add_event(make_unskippable_event(hash, 0, 1, [1]))
+    receive_and_add_event(&mut view, make_unskippable_event(hash, 0, 1, [1]))
         .await
         .unwrap();
-    // Cannot add an unskippable event that was visibly skipped already.
-    assert!(matches!(
-        view.add_event(make_unskippable_event(hash, 1, 0, [2]))
-            .await,
-        Err(InboxError::UnexpectedEvent { .. })
-    ));
-    view.add_event(make_unskippable_event(hash, 1, 1, [3]))
+    // An unskippable event that was visibly skipped already was refused.
+    receive_and_add_event(&mut view, make_unskippable_event(hash, 1, 0, [2]))
+        .await
+        .unwrap();
+    receive_and_add_event(&mut view, make_unskippable_event(hash, 1, 1, [3]))
         .await
         .unwrap();
     // Inbox is empty again.
@@ -280,10 +316,12 @@ async fn test_inbox_add_then_remove_mixed() {
     let hash = CryptoHash::new(&Dummy);
     let mut view = InboxStateView::new().await;
     // Add two events.
-    view.add_event(make_unskippable_event(hash, 0, 1, [1]))
+    receive_and_add_event(&mut view, make_unskippable_event(hash, 0, 1, [1]))
+        .await
+        .unwrap();
+    receive_and_add_event(&mut view, make_event(hash, 1, 0, [2]))
         .await
         .unwrap();
-    view.add_event(make_event(hash, 1, 0, [2])).await.unwrap();
     // Fail to remove non-matching event (skippability).
     assert!(matches!(
         view.remove_event(&make_event(hash, 0, 1, [1])).await,
@@ -309,3 +347,67 @@ async fn test_inbox_add_then_remove_mixed() {
     assert_eq!(view.added_events.count(), 0);
     assert_eq!(view.removed_events.count(), 0);
 }
+
+#[tokio::test]
+async fn test_inbox_remove_refused_unskippable() {
+    let hash = CryptoHash::new(&Dummy);
+    let mut view = InboxStateView::new().await;
+    // Receive three events, and refuse the first and the last ones.
+    receive_and_refuse_event(&mut view, make_unskippable_event(hash, 0, 0, [0]))
+        .await
+        .unwrap();
+    receive_and_add_event(&mut view, make_unskippable_event(hash, 0, 1, [1]))
+        .await
+        .unwrap();
+    receive_and_refuse_event(&mut view, make_unskippable_event(hash, 1, 0, [2]))
+        .await
+        .unwrap();
+    assert_eq!(*view.added_unskippable_events.get(), 1);
+    // Fail to remove refused events.
+    assert!(matches!(
+        view.remove_event(&make_unskippable_event(hash, 0, 0, [0]))
+            .await,
+        Err(InboxError::RefusedEvent { .. })
+    ));
+    view.remove_event(&make_unskippable_event(hash, 0, 1, [1]))
+        .await
+        .unwrap();
+    assert!(matches!(
+        view.remove_event(&make_unskippable_event(hash, 1, 0, [2]))
+            .await,
+        Err(InboxError::RefusedEvent { .. })
+    ));
+    // Inbox is empty again.
+    assert_eq!(view.added_events.count(), 0);
+    assert_eq!(view.removed_events.count(), 0);
+    assert_eq!(view.pending_unskippable_events(1), 0);
+}
+
+#[tokio::test]
+async fn test_inbox_settled_events_are_not_refused() {
+    let hash = CryptoHash::new(&Dummy);
+    let mut view = InboxStateView::new().await;
+    // Remove one event by anticipation.
+    view.remove_event(&make_unskippable_event(hash, 0, 1, [1]))
+        .await
+        .unwrap();
+    // Both the skipped event and the removed one were settled by the blocks.
+    let skipped_event = make_unskippable_event(hash, 0, 0, [0]);
+    let removed_event = make_unskippable_event(hash, 0, 1, [1]);
+    let next_event = make_unskippable_event(hash, 1, 0, [2]);
+    assert!(view.is_settled(&skipped_event));
+    assert!(view.is_settled(&removed_event));
+    receive_and_add_event(&mut view, skipped_event)
+        .await
+        .unwrap();
+    receive_and_add_event(&mut view, removed_event)
+        .await
+        .unwrap();
+    // Only the removed event counts, and the next one is not settled.
+    assert_eq!(*view.added_unskippable_events.get(), 1);
+    assert!(!view.is_settled(&next_event));
+    receive_and_add_event(&mut view, next_event).await.unwrap();
+    assert_eq!(view.pending_unskippable_events(1), 1);
+    assert_eq!(view.added_events.count(), 1);
+    assert_eq!(view.removed_events.count(), 0);
+}
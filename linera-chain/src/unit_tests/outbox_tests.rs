@@ -33,3 +33,38 @@ async fn test_outbox() {
     );
     assert_eq!(view.queue.count(), 0);
 }
+
+#[tokio::test]
+async fn test_outbox_last_abandonable_height() {
+    let mut view = OutboxStateView::new().await;
+    for height in 0..4 {
+        assert!(view.schedule_message(BlockHeight::from(height)).unwrap());
+    }
+    view.add_returnable_height(BlockHeight::from(2)).unwrap();
+
+    // Only the heights up to the given one can be abandoned.
+    assert_eq!(
+        view.last_abandonable_height(BlockHeight::ZERO)
+            .await
+            .unwrap(),
+        Some(BlockHeight::ZERO)
+    );
+    // Returnable messages are never abandoned, and neither are the following ones.
+    assert_eq!(
+        view.last_abandonable_height(BlockHeight::from(10))
+            .await
+            .unwrap(),
+        Some(BlockHeight::from(1))
+    );
+
+    view.mark_messages_as_received(BlockHeight::from(2))
+        .await
+        .unwrap();
+    assert_eq!(view.returnable_heights.indices().await.unwrap(), vec![]);
+    assert_eq!(
+        view.last_abandonable_height(BlockHeight::from(10))
+            .await
+            .unwrap(),
+        Some(BlockHeight::from(3))
+    );
+}
@@ -0,0 +1,93 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{ChainDescription, ChainId},
+};
+use linera_core::client::{
+    self,
+    client_test_utils::{MakeMemoryStoreClient, NodeProvider, StoreBuilder, TestBuilder},
+};
+use linera_execution::{
+    system::{Recipient, SystemOperation, UserData},
+    Operation,
+};
+use linera_storage::Store;
+use linera_views::views::ViewError;
+use std::time::Duration;
+use tokio::runtime;
+
+type ChainClient<B> =
+    client::ChainClient<NodeProvider<<B as StoreBuilder>::Store>, <B as StoreBuilder>::Store>;
+
+/// The number of blocks created in each iteration.
+const BLOCK_COUNT: u128 = 8;
+
+/// Creates root chain 1 with enough tokens for the transfers, and sets its pipeline depth.
+pub fn setup_pipelining_bench<B>(pipeline_depth: usize) -> ChainClient<B>
+where
+    B: StoreBuilder + Default,
+    ViewError: From<<B::Store as Store>::ContextError>,
+{
+    let store_builder = B::default();
+    // Criterion doesn't allow setup functions to be async, but it runs them inside an async
+    // context. But our setup uses async functions:
+    let handle = runtime::Handle::current();
+    let _guard = handle.enter();
+    futures::executor::block_on(async move {
+        let mut builder = TestBuilder::new(store_builder, 4, 1).await.unwrap();
+        let mut chain = builder
+            .add_initial_chain(ChainDescription::Root(1), Amount::from_tokens(BLOCK_COUNT))
+            .await
+            .unwrap();
+        chain.set_pipeline_depth(pipeline_depth);
+        chain
+    })
+}
+
+/// Creates `BLOCK_COUNT` blocks, each one with a transfer of one token to chain 2.
+pub async fn run_pipelining_bench<B>(mut chain: ChainClient<B>)
+where
+    B: StoreBuilder,
+    ViewError: From<<B::Store as Store>::ContextError>,
+{
+    let batches = (0..BLOCK_COUNT)
+        .map(|_| {
+            vec![Operation::System(SystemOperation::Transfer {
+                owner: None,
+                recipient: Recipient::chain(ChainId::root(2)),
+                amount: Amount::ONE,
+                user_data: UserData::default(),
+            })]
+        })
+        .collect();
+    let certificates = chain.execute_operation_batches(batches).await.unwrap();
+    assert_eq!(certificates.len() as u128, BLOCK_COUNT);
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pipelining");
+    for pipeline_depth in [1, 2, 4] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(pipeline_depth),
+            &pipeline_depth,
+            |b, &pipeline_depth| {
+                b.to_async(runtime::Runtime::new().unwrap()).iter_batched(
+                    || setup_pipelining_bench::<MakeMemoryStoreClient>(pipeline_depth),
+                    run_pipelining_bench::<MakeMemoryStoreClient>,
+                    BatchSize::PerIteration,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().measurement_time(Duration::from_secs(40));
+    targets = criterion_benchmark
+);
+criterion_main!(benches);
@@ -5,6 +5,7 @@
 use crate::{
     data_types::{BlockHeightRange, ChainInfo, ChainInfoQuery},
     local_node::{LocalNodeClient, LocalNodeError},
+    message_selection::MessageSelection,
     node::{NodeError, NotificationStream, ValidatorNode, ValidatorNodeProvider},
     notifier::Notifier,
     updater::{communicate_with_quorum, CommunicateAction, CommunicationError, ValidatorUpdater},
@@ -20,26 +21,27 @@ use futures::{
 };
 use linera_base::{
     abi::{Abi, ContractAbi},
-    crypto::{CryptoHash, KeyPair, PublicKey},
+    crypto::{CryptoHash, KeyPair, PublicKey, Signature},
     data_types::{Amount, ArithmeticError, BlockHeight, RoundNumber, Timestamp},
     ensure,
     identifiers::{ApplicationId, BytecodeId, ChainId, MessageId, Owner},
 };
 use linera_chain::{
     data_types::{
-        Block, BlockAndRound, BlockProposal, Certificate, CertificateValue, HashedValue,
-        IncomingMessage, LiteCertificate, LiteVote,
+        Block, BlockAndRound, BlockProposal, Certificate, CertificateSignatures, CertificateValue,
+        HashedValue, IncomingMessage, LiteCertificate, LiteVote, MessageAction,
     },
-    ChainError, ChainManagerInfo, ChainStateView,
+    ChainError, ChainExecutionContext, ChainManagerInfo, ChainStateView,
 };
 use linera_execution::{
-    committee::{Committee, Epoch, ValidatorName},
+    committee::{Committee, Epoch, ValidatorChange, ValidatorName},
     system::{
         Account, AdminOperation, Recipient, SystemChannel, SystemOperation, UserData,
         CREATE_APPLICATION_MESSAGE_INDEX, OPEN_CHAIN_MESSAGE_INDEX, PUBLISH_BYTECODE_MESSAGE_INDEX,
     },
-    Bytecode, ChainOwnership, Message, Operation, Query, Response, SystemMessage, SystemQuery,
-    SystemResponse, UserApplicationId,
+    Bytecode, ChainOwnership, ExecutionError, InboxPolicy, Message, Operation, Query,
+    RemoteQueryResponse, Response, SystemMessage, SystemQuery, SystemResponse, TimeoutConfig,
+    UserApplicationId,
 };
 use linera_storage::Store;
 use linera_views::views::ViewError;
@@ -134,11 +136,16 @@ impl<ValidatorNodeProvider: Clone> ChainClientBuilder<ValidatorNodeProvider> {
             validator_node_provider: self.validator_node_provider.clone(),
             admin_id,
             max_pending_messages: self.max_pending_messages,
+            message_selection: MessageSelection::default(),
+            pipeline_depth: 1,
+            independent_operations: false,
             received_certificate_trackers: HashMap::new(),
             block_hash,
             timestamp,
             next_block_height,
             pending_block: None,
+            approval_round: None,
+            pending_approvals: Vec::new(),
             cross_chain_delay: self.cross_chain_delay,
             cross_chain_retries: self.cross_chain_retries,
             node_client,
@@ -165,6 +172,10 @@ pub struct ChainClient<ValidatorNodeProvider, StorageClient> {
     next_block_height: BlockHeight,
     /// Pending block.
     pending_block: Option<Block>,
+    /// The round in which the pending block is to be proposed with the approvals, if any.
+    approval_round: Option<RoundNumber>,
+    /// Approvals of the pending block in `approval_round` by other owners of the chain.
+    pending_approvals: Vec<(Owner, Signature)>,
     /// Known key pairs from present and past identities.
     known_key_pairs: BTreeMap<Owner, KeyPair>,
     /// The id of the admin chain.
@@ -172,6 +183,12 @@ pub struct ChainClient<ValidatorNodeProvider, StorageClient> {
 
     /// Maximum number of pending messages processed at a time in a block.
     max_pending_messages: usize,
+    /// The order in which pending messages are included in a block.
+    message_selection: MessageSelection,
+    /// How many block proposals may be in flight at a time.
+    pipeline_depth: usize,
+    /// Whether the operations of new blocks may fail without making the whole block fail.
+    independent_operations: bool,
     /// Support synchronization of received certificates.
     received_certificate_trackers: HashMap<ValidatorName, u64>,
     /// How much time to wait between attempts when we wait for a cross-chain update.
@@ -239,6 +256,9 @@ pub enum ChainClientError {
 
     #[error("Leader timeout certificate does not match the expected one.")]
     UnexpectedLeaderTimeout,
+
+    #[error("Block approval from {0} is not valid for the pending block")]
+    InvalidBlockApproval(Owner),
 }
 
 impl<P, S> ChainClient<P, S> {
@@ -263,6 +283,39 @@ impl<P, S> ChainClient<P, S> {
     pub fn pending_block(&self) -> &Option<Block> {
         &self.pending_block
     }
+
+    /// Returns the order in which pending messages are included in new blocks.
+    pub fn message_selection(&self) -> &MessageSelection {
+        &self.message_selection
+    }
+
+    /// Sets the order in which pending messages are included in new blocks.
+    pub fn set_message_selection(&mut self, message_selection: MessageSelection) {
+        self.message_selection = message_selection;
+    }
+
+    /// Returns how many block proposals may be in flight at a time.
+    pub fn pipeline_depth(&self) -> usize {
+        self.pipeline_depth
+    }
+
+    /// Sets how many block proposals may be in flight at a time. With a depth of one, each block
+    /// is confirmed before the next one is proposed.
+    pub fn set_pipeline_depth(&mut self, pipeline_depth: usize) {
+        self.pipeline_depth = pipeline_depth;
+    }
+
+    /// Returns whether the operations of new blocks may fail without making the whole block
+    /// fail.
+    pub fn independent_operations(&self) -> bool {
+        self.independent_operations
+    }
+
+    /// Sets whether the operations of new blocks may fail without making the whole block fail.
+    /// A failing operation is then rolled back and only charged for the resources it used.
+    pub fn set_independent_operations(&mut self, independent_operations: bool) {
+        self.independent_operations = independent_operations;
+    }
 }
 
 enum ReceiveCertificateMode {
@@ -301,7 +354,8 @@ where
         Ok(response.info)
     }
 
-    /// Obtains up to `self.max_pending_messages` pending messages for the local chain.
+    /// Obtains up to `self.max_pending_messages` pending messages for the local chain, in the
+    /// order of `self.message_selection`.
     ///
     /// Messages known to be redundant are filtered out: A `RegisterApplications` message whose
     /// entries are already known never needs to be included in a block.
@@ -309,7 +363,10 @@ where
         let query = ChainInfoQuery::new(self.chain_id).with_pending_messages();
         let response = self.node_client.handle_chain_info_query(query).await?;
         let mut pending_messages = vec![];
-        for message in response.info.requested_pending_messages {
+        let messages = self
+            .message_selection
+            .order(response.info.requested_pending_messages);
+        for message in messages {
             if pending_messages.len() >= self.max_pending_messages {
                 tracing::warn!(
                     "Limiting block from {} to {} incoming messages",
@@ -702,7 +759,7 @@ where
     /// Broadcasts certified blocks and optionally one more block proposal.
     /// The corresponding block heights should be consecutive and increasing.
     async fn communicate_chain_updates(
-        &mut self,
+        &self,
         committee: &Committee,
         chain_id: ChainId,
         action: CommunicateAction,
@@ -733,7 +790,8 @@ where
         )
         .await;
         let (value, round) = match action {
-            CommunicateAction::SubmitBlockForConfirmation(proposal) => {
+            CommunicateAction::SubmitBlockForConfirmation(proposal)
+            | CommunicateAction::SubmitPipelinedBlock { proposal, .. } => {
                 let block = proposal.content.block;
                 let (executed_block, _) = self.node_client.stage_block_execution(block).await?;
                 (
@@ -767,6 +825,10 @@ where
                 });
                 (value, round)
             }
+            CommunicateAction::SendPipelinedProposal(_) => {
+                result?;
+                return Ok(None);
+            }
             CommunicateAction::AdvanceToNextBlockHeight(_) => {
                 return match result {
                     Ok(_) => Ok(None),
@@ -1162,6 +1224,30 @@ where
         Ok(certificate)
     }
 
+    /// Returns the time when the current round of this multi-owner chain times out, if we own
+    /// the chain, the round has a single leader other than us, and we are waiting for a block:
+    /// either we have a pending block or there are pending messages. After that time, we can
+    /// request a leader timeout, so that the next leader gets a chance to propose a block.
+    pub async fn leader_timeout_deadline(&mut self) -> Result<Option<Timestamp>, ChainClientError> {
+        let ChainManagerInfo::Multi(manager) = self.chain_info().await?.manager else {
+            return Ok(None);
+        };
+        let is_owner = manager
+            .public_keys
+            .keys()
+            .any(|owner| self.known_key_pairs.contains_key(owner));
+        let Some(leader) = manager.leader else {
+            return Ok(None);
+        };
+        if !is_owner || self.known_key_pairs.contains_key(&leader) {
+            return Ok(None);
+        }
+        if self.pending_block.is_none() && self.pending_messages().await?.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(manager.round_timeout))
+    }
+
     /// Executes (or retries) a regular block proposal. Updates local balance.
     /// If `with_confirmation` is false, we stop short of executing the finalized block.
     async fn propose_block(&mut self, block: Block) -> Result<Certificate, ChainClientError> {
@@ -1213,6 +1299,18 @@ where
             blobs,
             validated,
         );
+        // Approvals are only valid in the round they were signed for. The proposer's own
+        // signature already counts towards the approval threshold.
+        let approvals = if self.approval_round == Some(next_round) {
+            self.pending_approvals
+                .iter()
+                .filter(|(owner, _)| *owner != proposal.owner)
+                .cloned()
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let proposal = proposal.with_approvals(approvals);
         // Try to execute the block locally first.
         self.node_client
             .handle_block_proposal(proposal.clone())
@@ -1271,6 +1369,8 @@ where
         self.process_certificate(final_certificate.clone(), vec![])
             .await?;
         self.pending_block = None;
+        self.approval_round = None;
+        self.pending_approvals.clear();
         // Communicate the new certificate now.
         self.communicate_chain_updates(
             &committee,
@@ -1311,6 +1411,171 @@ where
         self.execute_operations(vec![operation]).await
     }
 
+    /// Executes one block for each batch of operations. Only the first block includes the
+    /// pending messages.
+    ///
+    /// If the chain has a single owner, the operations are not independent and the pipeline
+    /// depth is greater than one, a block may be proposed before the previous ones are
+    /// confirmed: validators vote for it as soon as they confirm the one before it. If a block fails, the blocks before it remain confirmed, it
+    /// becomes the pending block, and the blocks after it are dropped.
+    pub async fn execute_operation_batches(
+        &mut self,
+        batches: Vec<Vec<Operation>>,
+    ) -> Result<Vec<Certificate>, ChainClientError> {
+        let manager = self.chain_info().await?.manager;
+        if self.pipeline_depth <= 1
+            || batches.len() <= 1
+            || self.independent_operations
+            || !matches!(manager, ChainManagerInfo::Single(_))
+        {
+            let mut certificates = Vec::new();
+            for operations in batches {
+                certificates.push(self.execute_operations(operations).await?);
+            }
+            return Ok(certificates);
+        }
+        ensure!(
+            self.pending_block.is_none(),
+            ChainClientError::BlockProposalError("Client state already has a pending block")
+        );
+        self.prepare_chain().await?;
+        let mut incoming_messages = self.pending_messages().await?;
+        let mut blocks = Vec::<Block>::new();
+        for operations in batches {
+            let block = self
+                .new_block(std::mem::take(&mut incoming_messages), operations)
+                .await?;
+            let block = match blocks.last() {
+                None => self.reject_failing_messages(block).await?,
+                // The certificate of the previous block doesn't exist yet.
+                Some(previous_block) => Block {
+                    height: previous_block.height.try_add_one()?,
+                    timestamp: block.timestamp.max(previous_block.timestamp),
+                    previous_block_signatures: None,
+                    ..block
+                },
+            };
+            blocks.push(block);
+        }
+        // This also links each block to the previous one.
+        let executed_blocks = self
+            .node_client
+            .stage_pipelined_block_execution(blocks)
+            .await?;
+        let committee = self.local_committee().await?;
+        let mut blobs = Vec::new();
+        for executed_block in &executed_blocks {
+            let nodes = self.validator_node_provider.make_nodes(&committee)?;
+            let locations = executed_block.block.bytecode_locations();
+            blobs.push(
+                self.node_client
+                    .read_or_download_blobs(nodes, locations)
+                    .await?,
+            );
+        }
+        let key_pair = self.key_pair().await?;
+        let proposals = executed_blocks
+            .into_iter()
+            .zip(blobs)
+            .map(|(executed_block, blobs)| {
+                let content = BlockAndRound {
+                    block: executed_block.block,
+                    round: RoundNumber::default(),
+                };
+                BlockProposal::new(content, key_pair, blobs, None)
+            })
+            .collect();
+        self.propose_pipelined_blocks(&committee, proposals).await
+    }
+
+    /// Proposes consecutive blocks of a single-owner chain and confirms them in order. Each
+    /// proposal is sent to the validators up to `pipeline_depth - 1` blocks ahead of the block
+    /// being confirmed.
+    async fn propose_pipelined_blocks(
+        &mut self,
+        committee: &Committee,
+        proposals: Vec<BlockProposal>,
+    ) -> Result<Vec<Certificate>, ChainClientError> {
+        let mut certificates = Vec::<Certificate>::new();
+        for (index, proposal) in proposals.iter().enumerate() {
+            // Remember what we are trying to do, before sending the proposal to the validators.
+            self.pending_block = Some(proposal.content.block.clone());
+            let pipeline_end = (index + self.pipeline_depth).min(proposals.len());
+            let result = match certificates.last() {
+                None => {
+                    // Validators discard the proposals for later blocks when they receive one
+                    // for the next block, so the first one must arrive first.
+                    let action = CommunicateAction::SubmitBlockForConfirmation(proposal.clone());
+                    let result = self
+                        .communicate_chain_updates(committee, self.chain_id, action)
+                        .await;
+                    if result.is_ok() {
+                        self.send_pipelined_proposals(committee, &proposals[1..pipeline_end])
+                            .await;
+                    }
+                    result
+                }
+                Some(previous) => {
+                    let action = CommunicateAction::SubmitPipelinedBlock {
+                        previous: previous.clone(),
+                        proposal: proposal.clone(),
+                    };
+                    // Meanwhile, send the proposal that enters the pipeline, if any.
+                    let new_proposals = if index + self.pipeline_depth <= proposals.len() {
+                        &proposals[pipeline_end - 1..pipeline_end]
+                    } else {
+                        &[]
+                    };
+                    let (result, ()) = future::join(
+                        self.communicate_chain_updates(committee, self.chain_id, action),
+                        self.send_pipelined_proposals(committee, new_proposals),
+                    )
+                    .await;
+                    result
+                }
+            };
+            let certificate = result?.expect("a certificate");
+            ensure!(
+                matches!(
+                    certificate.value(), CertificateValue::ConfirmedBlock { executed_block, .. }
+                        if executed_block.block == proposal.content.block
+                ),
+                ChainClientError::BlockProposalError(
+                    "A different operation was executed in parallel (consider retrying the operation)"
+                )
+            );
+            self.process_certificate(certificate.clone(), vec![])
+                .await?;
+            certificates.push(certificate);
+        }
+        self.pending_block = None;
+        // Communicate the last certificate now.
+        self.communicate_chain_updates(
+            committee,
+            self.chain_id,
+            CommunicateAction::AdvanceToNextBlockHeight(self.next_block_height),
+        )
+        .await?;
+        Ok(certificates)
+    }
+
+    /// Sends proposals for later blocks to the validators, which keep them until they confirm
+    /// the previous blocks. Failures are only logged, since the proposals are sent again with
+    /// the previous certificates.
+    async fn send_pipelined_proposals(&self, committee: &Committee, proposals: &[BlockProposal]) {
+        future::join_all(proposals.iter().map(|proposal| async move {
+            let height = proposal.content.block.height;
+            let action = CommunicateAction::SendPipelinedProposal(proposal.clone());
+            if let Err(error) = self
+                .communicate_chain_updates(committee, self.chain_id, action)
+                .await
+            {
+                debug!("Failed to send the pipelined proposal at height {height}: {error}");
+            }
+        }))
+        .await;
+    }
+
     /// Executes a new block.
     ///
     /// This must be preceded by a call to `prepare_chain()`.
@@ -1319,19 +1584,173 @@ where
         incoming_messages: Vec<IncomingMessage>,
         operations: Vec<Operation>,
     ) -> Result<Certificate, ChainClientError> {
+        let block = self.new_block(incoming_messages, operations).await?;
+        let block = self.reject_failing_messages(block).await?;
+        let certificate = self.propose_block(block).await?;
+        Ok(certificate)
+    }
+
+    /// Executes the block locally and marks the incoming messages that fail to execute as
+    /// rejected, if they can be. Rejected tracked messages are returned to their sender.
+    ///
+    /// Other errors are left for `propose_block` to report.
+    async fn reject_failing_messages(
+        &mut self,
+        mut block: Block,
+    ) -> Result<Block, ChainClientError> {
+        loop {
+            let Err(LocalNodeError::WorkerError(WorkerError::ChainError(error))) =
+                self.node_client.stage_block_execution(block.clone()).await
+            else {
+                return Ok(block);
+            };
+            let ChainError::ExecutionError(error, ChainExecutionContext::IncomingMessage(index)) =
+                *error
+            else {
+                return Ok(block);
+            };
+            if matches!(
+                error,
+                ExecutionError::ViewError(_)
+                    | ExecutionError::InvalidBytecodeId(_)
+                    | ExecutionError::ApplicationBytecodeNotFound(_)
+            ) {
+                // The message may succeed once the local node has the missing data.
+                return Ok(block);
+            }
+            let Some(message) = usize::try_from(index)
+                .ok()
+                .and_then(|index| block.incoming_messages.get_mut(index))
+            else {
+                return Ok(block);
+            };
+            if message.action == MessageAction::Reject || !message.event.is_rejectable() {
+                return Ok(block);
+            }
+            info!(
+                "Rejecting incoming message from {:?} that failed to execute: {error}",
+                message.origin
+            );
+            message.action = MessageAction::Reject;
+        }
+    }
+
+    /// Creates the next block of the chain, with the given messages and operations.
+    async fn new_block(
+        &mut self,
+        incoming_messages: Vec<IncomingMessage>,
+        operations: Vec<Operation>,
+    ) -> Result<Block, ChainClientError> {
         let timestamp = self.next_timestamp(&incoming_messages).await;
-        let block = Block {
+        Ok(Block {
             epoch: self.epoch().await?,
             chain_id: self.chain_id,
             incoming_messages,
             operations,
+            independent_operations: self.independent_operations,
             previous_block_hash: self.block_hash,
+            previous_block_signatures: self.previous_block_signatures().await?,
             height: self.next_block_height,
             authenticated_signer: Some(self.identity().await?),
             timestamp,
+        })
+    }
+
+    /// Returns the canonical signatures of the certificate of the chain's latest block, if
+    /// any, and if its committee is still trusted by the chain.
+    async fn previous_block_signatures(
+        &mut self,
+    ) -> Result<Option<CertificateSignatures>, ChainClientError> {
+        let Some(hash) = self.block_hash else {
+            return Ok(None);
         };
-        let certificate = self.propose_block(block).await?;
-        Ok(certificate)
+        let certificate = self
+            .node_client
+            .storage_client()
+            .await
+            .read_certificate(hash)
+            .await
+            .map_err(LocalNodeError::from)?;
+        let committees = self.committees().await?;
+        Ok(committees
+            .get(&certificate.value().epoch())
+            .map(|committee| certificate.certificate_signatures(committee)))
+    }
+
+    /// Creates a block with the given operations and makes it the pending block, without
+    /// proposing it yet. Returns the block together with the round in which it will be
+    /// proposed. The other owners can then approve it with `approve_block`, and the block is
+    /// proposed with their approvals by `retry_pending_block`.
+    pub async fn stage_operations_for_approval(
+        &mut self,
+        operations: Vec<Operation>,
+    ) -> Result<BlockAndRound, ChainClientError> {
+        ensure!(
+            self.pending_block.is_none(),
+            ChainClientError::BlockProposalError("Client state already has a pending block")
+        );
+        self.prepare_chain().await?;
+        let query = ChainInfoQuery::new(self.chain_id).with_manager_values();
+        let response = self.node_client.handle_chain_info_query(query).await?;
+        let Some(round) = response.info.manager.next_round() else {
+            return Err(ChainClientError::BlockProposalError(
+                "Cannot propose a block; there is already a proposal in the current round",
+            ));
+        };
+        let messages = self.pending_messages().await?;
+        let block = self.new_block(messages, operations).await?;
+        let block = self.reject_failing_messages(block).await?;
+        self.pending_block = Some(block.clone());
+        self.approval_round = Some(round);
+        self.pending_approvals.clear();
+        Ok(BlockAndRound { block, round })
+    }
+
+    /// Signs the approval of a block proposed by another owner of this chain, in the given
+    /// round.
+    pub async fn approve_block(
+        &mut self,
+        content: &BlockAndRound,
+    ) -> Result<(Owner, Signature), ChainClientError> {
+        ensure!(
+            content.block.chain_id == self.chain_id,
+            ChainClientError::BlockProposalError("The block belongs to a different chain")
+        );
+        let key_pair = self.key_pair().await?;
+        let owner = Owner::from(key_pair.public());
+        Ok((owner, Signature::new(content, key_pair)))
+    }
+
+    /// Adds another owner's approval of the pending block, to be included in its proposal.
+    pub async fn add_block_approval(
+        &mut self,
+        owner: Owner,
+        signature: Signature,
+    ) -> Result<(), ChainClientError> {
+        let (Some(block), Some(round)) = (&self.pending_block, self.approval_round) else {
+            return Err(ChainClientError::BlockProposalError(
+                "Client state has no pending block to approve",
+            ));
+        };
+        let content = BlockAndRound {
+            block: block.clone(),
+            round,
+        };
+        let info = self.chain_info().await?;
+        let public_key = match info.manager {
+            ChainManagerInfo::Multi(manager) => {
+                manager.public_keys.get(&owner).map(|(key, _)| *key)
+            }
+            ChainManagerInfo::Single(_) | ChainManagerInfo::None => None,
+        }
+        .ok_or(ChainClientError::InvalidBlockApproval(owner))?;
+        signature
+            .check(&content, public_key)
+            .map_err(|_| ChainClientError::InvalidBlockApproval(owner))?;
+        self.pending_approvals
+            .retain(|(approver, _)| *approver != owner);
+        self.pending_approvals.push((owner, signature));
+        Ok(())
     }
 
     /// Returns a suitable timestamp for the next block.
@@ -1393,6 +1812,35 @@ where
         }
     }
 
+    /// Queries a user application on a chain tracked by the local node, which may be different
+    /// from the chain of this client.
+    ///
+    /// The response is annotated with the height of the next block of the queried chain.
+    pub async fn query_remote_application(
+        &self,
+        chain_id: ChainId,
+        application_id: UserApplicationId,
+        bytes: Vec<u8>,
+    ) -> Result<RemoteQueryResponse, ChainClientError> {
+        let query = Query::User {
+            application_id,
+            bytes,
+        };
+        let RemoteQueryResponse { response, height } = self
+            .node_client
+            .storage_client()
+            .await
+            .query_remote_application(chain_id, query, Vec::new())
+            .await
+            .map_err(|error| ChainError::ExecutionError(error, ChainExecutionContext::Query))?;
+        match response {
+            Response::User(response) => Ok(RemoteQueryResponse { response, height }),
+            _ => Err(ChainClientError::InternalError(
+                "Unexpected response for user query",
+            )),
+        }
+    }
+
     pub async fn local_balance(&mut self) -> Result<Amount, ChainClientError> {
         ensure!(
             self.chain_info().await?.next_block_height == self.next_block_height,
@@ -1405,7 +1853,9 @@ where
             chain_id: self.chain_id,
             incoming_messages,
             operations: Vec::new(),
+            independent_operations: self.independent_operations,
             previous_block_hash: self.block_hash,
+            previous_block_signatures: self.previous_block_signatures().await?,
             height: self.next_block_height,
             authenticated_signer: None,
             timestamp,
@@ -1471,6 +1921,30 @@ where
         self.local_balance().await
     }
 
+    /// Restores the local state of the chain from the latest checkpoint certified by the
+    /// validators, if it is ahead of the local state. Then synchronizes the chain as usual, so
+    /// that only the blocks after the checkpoint are downloaded and executed.
+    pub async fn synchronize_from_checkpoint(&mut self) -> Result<Amount, ChainClientError> {
+        let (committees, epoch) = self.known_committees().await?;
+        let committee = committees
+            .get(&epoch)
+            .ok_or(LocalNodeError::InactiveChain(self.chain_id))?;
+        let nodes: Vec<_> = self.validator_node_provider.make_nodes(committee)?;
+        let info = self
+            .node_client
+            .restore_from_checkpoint(nodes.clone(), self.chain_id, &committees)
+            .await?;
+        self.update_from_info(&info);
+        // Receive the messages first, so that the later blocks find them in the inboxes.
+        self.find_received_certificates().await?;
+        let info = self
+            .node_client
+            .synchronize_chain_state(nodes, self.chain_id)
+            .await?;
+        self.update_from_info(&info);
+        self.local_balance().await
+    }
+
     /// Retries the last pending block
     pub async fn retry_pending_block(&mut self) -> Result<Option<Certificate>, ChainClientError> {
         self.find_received_certificates().await?;
@@ -1489,6 +1963,8 @@ where
     /// Clears the information on any operation that previously failed.
     pub async fn clear_pending_block(&mut self) {
         self.pending_block = None;
+        self.approval_round = None;
+        self.pending_approvals.clear();
     }
 
     /// Processes confirmed operation for which this chain is a recipient.
@@ -1521,6 +1997,17 @@ where
         .await
     }
 
+    /// Changes the messages that the chain accepts in its inboxes.
+    pub async fn change_inbox_policy(
+        &mut self,
+        policy: InboxPolicy,
+    ) -> Result<Certificate, ChainClientError> {
+        self.execute_operation(Operation::System(SystemOperation::ChangeInboxPolicy {
+            policy,
+        }))
+        .await
+    }
+
     /// Adds another owner to the chain.
     ///
     /// If the chain is currently of type `Single`, the existing owner's weight is set to 100, and
@@ -1532,27 +2019,37 @@ where
     ) -> Result<Certificate, ChainClientError> {
         let info = self.prepare_chain().await?;
         let messages = self.pending_messages().await?;
-        let (new_public_keys, multi_leader_rounds) = match info.manager {
-            ChainManagerInfo::None => return Err(ChainError::InactiveChain(self.chain_id).into()),
-            ChainManagerInfo::Single(manager) => (
-                vec![(manager.public_key, 100), (new_public_key, new_weight)],
-                RoundNumber(2),
-            ),
-            ChainManagerInfo::Multi(manager) => (
-                manager
-                    .public_keys
-                    .values()
-                    .cloned()
-                    .chain(iter::once((new_public_key, new_weight)))
-                    .collect(),
-                manager.multi_leader_rounds,
-            ),
-        };
+        let manager = info.manager;
+        let (new_public_keys, multi_leader_rounds, approval_threshold, timeout_config) =
+            match manager {
+                ChainManagerInfo::None => {
+                    return Err(ChainError::InactiveChain(self.chain_id).into())
+                }
+                ChainManagerInfo::Single(manager) => (
+                    vec![(manager.public_key, 100), (new_public_key, new_weight)],
+                    RoundNumber(2),
+                    None,
+                    TimeoutConfig::default(),
+                ),
+                ChainManagerInfo::Multi(manager) => (
+                    manager
+                        .public_keys
+                        .values()
+                        .cloned()
+                        .chain(iter::once((new_public_key, new_weight)))
+                        .collect(),
+                    manager.multi_leader_rounds,
+                    manager.approval_threshold,
+                    manager.timeout_config,
+                ),
+            };
         self.execute_block(
             messages,
             vec![Operation::System(SystemOperation::ChangeMultipleOwners {
                 new_public_keys,
                 multi_leader_rounds,
+                approval_threshold,
+                timeout_config,
             })],
         )
         .await
@@ -1589,10 +2086,29 @@ where
         Ok((message_id, certificate))
     }
 
-    /// Closes the chain (and loses everything in it!!).
-    pub async fn close_chain(&mut self) -> Result<Certificate, ChainClientError> {
-        self.execute_operation(Operation::System(SystemOperation::CloseChain))
-            .await
+    /// Closes the chain. The pending messages are rejected if possible, so that tracked
+    /// messages are returned to their senders. If a recipient is given, the remaining
+    /// balances are transferred to it; otherwise they are lost.
+    ///
+    /// Returns the certificate of the block that closes the chain. The messages that didn't
+    /// fit in that block are returned to their senders in the following blocks, until the
+    /// inbox is empty.
+    pub async fn close_chain(
+        &mut self,
+        recipient: Option<Recipient>,
+    ) -> Result<Certificate, ChainClientError> {
+        self.prepare_chain().await?;
+        let mut messages = self.pending_messages().await?;
+        for message in &mut messages {
+            if message.event.is_rejectable() {
+                message.action = MessageAction::Reject;
+            }
+        }
+        let operation = Operation::System(SystemOperation::CloseChain { recipient });
+        let certificate = self.execute_block(messages, vec![operation]).await?;
+        // The closed chain refuses all other messages, and returns them to their senders.
+        self.process_inbox().await?;
+        Ok(certificate)
     }
 
     /// Publishes some bytecode.
@@ -1618,7 +2134,8 @@ where
         Ok((BytecodeId::new(message_id), certificate))
     }
 
-    /// Creates an application by instantiating some bytecode.
+    /// Creates an application by instantiating some bytecode, without limiting the storage
+    /// it may use.
     pub async fn create_application<A: Abi>(
         &mut self,
         bytecode_id: BytecodeId<A>,
@@ -1634,18 +2151,23 @@ where
                 parameters,
                 initialization_argument,
                 required_application_ids,
+                None,
             )
             .await?;
         Ok((app_id.with_abi(), cert))
     }
 
     /// Creates an application by instantiating some bytecode.
+    ///
+    /// If a `storage_quota` is provided, the application may not store more than that many
+    /// bytes of keys and values on each chain.
     pub async fn create_application_untyped(
         &mut self,
         bytecode_id: BytecodeId,
         parameters: Vec<u8>,
         initialization_argument: Vec<u8>,
         required_application_ids: Vec<UserApplicationId>,
+        storage_quota: Option<u64>,
     ) -> Result<(UserApplicationId, Certificate), ChainClientError> {
         let certificate = self
             .execute_operation(Operation::System(SystemOperation::CreateApplication {
@@ -1653,6 +2175,7 @@ where
                 parameters,
                 initialization_argument,
                 required_application_ids,
+                storage_quota,
             }))
             .await?;
         // The first message of the only operation created the application.
@@ -1690,6 +2213,29 @@ where
         .await
     }
 
+    /// Starts a new epoch with the committee obtained by applying the given changes to the
+    /// current one (admin chains only). The previous committees are removed once the grace
+    /// period is over.
+    pub async fn change_validators(
+        &mut self,
+        changes: Vec<ValidatorChange>,
+        grace_period: Duration,
+    ) -> Result<Certificate, ChainClientError> {
+        self.prepare_chain().await?;
+        let messages = self.pending_messages().await?;
+        let grace_period_micros = u64::try_from(grace_period.as_micros()).unwrap_or(u64::MAX);
+        self.execute_block(
+            messages,
+            vec![Operation::System(SystemOperation::Admin(
+                AdminOperation::ChangeValidators {
+                    changes,
+                    grace_period_micros,
+                },
+            ))],
+        )
+        .await
+    }
+
     /// Creates an empty block to process all incoming messages. This may require several blocks.
     pub async fn process_inbox(&mut self) -> Result<Vec<Certificate>, ChainClientError> {
         self.prepare_chain().await?;
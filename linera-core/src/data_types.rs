@@ -9,7 +9,10 @@ use linera_base::{
     identifiers::{ChainDescription, ChainId},
 };
 use linera_chain::{
-    data_types::{Certificate, ChainAndHeight, HashedValue, IncomingMessage, Medium},
+    data_types::{
+        Certificate, ChainAndHeight, CheckpointChunk, CheckpointVote, HashedValue, IncomingMessage,
+        Medium,
+    },
     ChainManagerInfo, ChainStateView,
 };
 use linera_execution::{
@@ -67,6 +70,10 @@ pub struct ChainInfoQuery {
     pub request_leader_timeout: bool,
     /// Query a value that contains a binary blob (e.g. bytecode) required by this chain.
     pub request_blob: Option<CryptoHash>,
+    /// Query the latest checkpoint of the chain state signed by the validator.
+    pub request_checkpoint: bool,
+    /// Query a chunk of the state committed to by the latest checkpoint.
+    pub request_checkpoint_chunk: Option<CryptoHash>,
 }
 
 impl ChainInfoQuery {
@@ -81,6 +88,8 @@ impl ChainInfoQuery {
             request_manager_values: false,
             request_leader_timeout: false,
             request_blob: None,
+            request_checkpoint: false,
+            request_checkpoint_chunk: None,
         }
     }
 
@@ -123,6 +132,16 @@ impl ChainInfoQuery {
         self.request_blob = Some(hash);
         self
     }
+
+    pub fn with_checkpoint(mut self) -> Self {
+        self.request_checkpoint = true;
+        self
+    }
+
+    pub fn with_checkpoint_chunk(mut self, hash: CryptoHash) -> Self {
+        self.request_checkpoint_chunk = Some(hash);
+        self
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -146,7 +165,7 @@ pub struct ChainInfo {
     pub next_block_height: BlockHeight,
     /// The hash of the current execution state.
     pub state_hash: Option<CryptoHash>,
-    /// The current committees.
+    /// The committees that the chain still trusts.
     pub requested_committees: Option<BTreeMap<Epoch, Committee>>,
     /// The received messages that are waiting be picked in the next block (if requested).
     pub requested_pending_messages: Vec<IncomingMessage>,
@@ -158,6 +177,10 @@ pub struct ChainInfo {
     pub requested_received_log: Vec<ChainAndHeight>,
     /// The requested blob, if any.
     pub requested_blob: Option<HashedValue>,
+    /// The latest checkpoint signed by the validator, if requested and available.
+    pub requested_checkpoint: Option<CheckpointVote>,
+    /// The requested checkpoint chunk, if any.
+    pub requested_checkpoint_chunk: Option<CheckpointChunk>,
 }
 
 /// The response to an `ChainInfoQuery`
@@ -237,6 +260,8 @@ where
             count_received_log: view.received_log.count(),
             requested_received_log: Vec::new(),
             requested_blob: None,
+            requested_checkpoint: None,
+            requested_checkpoint_chunk: None,
         }
     }
 }
@@ -7,6 +7,7 @@
 pub mod client;
 pub mod data_types;
 pub mod local_node;
+pub mod message_selection;
 pub mod node;
 pub mod notifier;
 pub mod tracker;
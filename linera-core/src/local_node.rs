@@ -10,21 +10,25 @@ use crate::{
 };
 use futures::{future, lock::Mutex};
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{ArithmeticError, BlockHeight},
     identifiers::{ChainId, MessageId},
 };
 use linera_chain::{
-    data_types::{Block, BlockProposal, Certificate, ExecutedBlock, HashedValue, LiteCertificate},
+    data_types::{
+        Block, BlockProposal, Certificate, CertifiedCheckpoint, CheckpointChunk, ExecutedBlock,
+        HashedValue, LiteCertificate,
+    },
     ChainManagerInfo,
 };
 use linera_execution::{
-    committee::ValidatorName, BytecodeLocation, Query, Response, UserApplicationDescription,
-    UserApplicationId,
+    committee::{Committee, Epoch, ValidatorName},
+    BytecodeLocation, Query, Response, UserApplicationDescription, UserApplicationId,
 };
 use linera_storage::Store;
 use linera_views::views::ViewError;
 use rand::prelude::SliceRandom;
-use std::{borrow::Cow, sync::Arc};
+use std::{borrow::Cow, collections::BTreeMap, sync::Arc};
 use thiserror::Error;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
@@ -174,6 +178,14 @@ where
         Ok((executed_block, info))
     }
 
+    pub(crate) async fn stage_pipelined_block_execution(
+        &self,
+        blocks: Vec<Block>,
+    ) -> Result<Vec<ExecutedBlock>, LocalNodeError> {
+        let mut node = self.node.lock().await;
+        Ok(node.state.stage_pipelined_block_execution(blocks).await?)
+    }
+
     async fn try_process_certificates<A>(
         &mut self,
         name: ValidatorName,
@@ -496,6 +508,123 @@ where
         Ok(())
     }
 
+    /// Restores the state of the chain from the latest checkpoint signed by a quorum of
+    /// validators, if it is ahead of the local state. If there is no such checkpoint, or if it
+    /// cannot be downloaded, the local state is left unchanged.
+    pub async fn restore_from_checkpoint<A>(
+        &mut self,
+        validators: Vec<(ValidatorName, A)>,
+        chain_id: ChainId,
+        committees: &BTreeMap<Epoch, Committee>,
+    ) -> Result<ChainInfo, LocalNodeError>
+    where
+        A: ValidatorNode + Send + Sync + 'static + Clone,
+    {
+        let local_info = self.local_chain_info(chain_id).await?;
+        let votes = future::join_all(validators.iter().cloned().map(
+            |(name, mut node)| async move {
+                let query = ChainInfoQuery::new(chain_id).with_checkpoint();
+                let response = node.handle_chain_info_query(query).await.ok()?;
+                response.check(name).ok()?;
+                let vote = response.info.requested_checkpoint?;
+                let is_valid = vote.validator == name
+                    && vote.checkpoint.chain_id == chain_id
+                    && vote.check().is_ok();
+                is_valid.then_some(vote)
+            },
+        ))
+        .await;
+        let votes = votes.into_iter().flatten().collect::<Vec<_>>();
+        // Group the signatures by checkpoint.
+        let mut certified_checkpoints: Vec<CertifiedCheckpoint> = Vec::new();
+        for vote in &votes {
+            let signature = (vote.validator, vote.signature);
+            match certified_checkpoints
+                .iter_mut()
+                .find(|certified| certified.checkpoint == vote.checkpoint)
+            {
+                Some(certified) => certified.signatures.push(signature),
+                None => certified_checkpoints.push(CertifiedCheckpoint {
+                    checkpoint: vote.checkpoint.clone(),
+                    signatures: vec![signature],
+                }),
+            }
+        }
+        // Pick the latest checkpoint signed by a quorum.
+        let Some((certified, committee)) = certified_checkpoints
+            .into_iter()
+            .filter(|certified| {
+                certified.checkpoint.next_block_height > local_info.next_block_height
+            })
+            .filter_map(|certified| {
+                let committee = committees.get(&certified.checkpoint.epoch)?;
+                certified
+                    .check(committee)
+                    .is_ok()
+                    .then_some((certified, committee))
+            })
+            .max_by_key(|(certified, _)| certified.checkpoint.next_block_height)
+        else {
+            return Ok(local_info);
+        };
+        // Each validator stores the state in its own chunks. Download them from one of the
+        // validators that signed the checkpoint, and try the next one if they don't match it.
+        let mut signers = validators
+            .into_iter()
+            .filter_map(|(name, node)| {
+                let vote = votes.iter().find(|vote| {
+                    vote.validator == name && vote.checkpoint == certified.checkpoint
+                })?;
+                Some((name, node, vote.chunk_hashes.clone()))
+            })
+            .collect::<Vec<_>>();
+        signers.shuffle(&mut rand::thread_rng());
+        for (name, mut node, chunk_hashes) in signers {
+            let Some(chunks) =
+                Self::try_download_checkpoint_chunks_from(name, &mut node, chain_id, &chunk_hashes)
+                    .await
+            else {
+                tracing::warn!("Failed to download checkpoint chunks from validator {name}");
+                continue;
+            };
+            let node = self.node.lock().await;
+            match node
+                .state
+                .restore_checkpoint(&certified, chunks, committee)
+                .await
+            {
+                Ok(response) => return Ok(response.info),
+                Err(error) => tracing::warn!(
+                    "Failed to restore checkpoint from the chunks of validator {name}: {error}"
+                ),
+            }
+        }
+        Ok(local_info)
+    }
+
+    async fn try_download_checkpoint_chunks_from<A>(
+        name: ValidatorName,
+        node: &mut A,
+        chain_id: ChainId,
+        chunk_hashes: &[CryptoHash],
+    ) -> Option<Vec<CheckpointChunk>>
+    where
+        A: ValidatorNode + Send + Sync + 'static + Clone,
+    {
+        let mut chunks = Vec::new();
+        for hash in chunk_hashes {
+            let query = ChainInfoQuery::new(chain_id).with_checkpoint_chunk(*hash);
+            let response = node.handle_chain_info_query(query).await.ok()?;
+            response.check(name).ok()?;
+            let chunk = response.info.requested_checkpoint_chunk?;
+            if CryptoHash::new(&chunk) != *hash {
+                return None;
+            }
+            chunks.push(chunk);
+        }
+        Some(chunks)
+    }
+
     pub async fn download_blob<A>(
         mut validators: Vec<(ValidatorName, A)>,
         chain_id: ChainId,
@@ -0,0 +1,114 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Strategies to order the pending messages of a chain in a new block.
+
+use linera_chain::data_types::{IncomingMessage, Origin};
+use linera_execution::GenericApplicationId;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    str::FromStr,
+};
+use thiserror::Error;
+
+#[cfg(test)]
+#[path = "unit_tests/message_selection_tests.rs"]
+mod message_selection_tests;
+
+/// The order in which a client includes the pending messages of its chain in a new block.
+///
+/// Messages from the same origin always keep the order of their inbox: the strategies only
+/// differ in how they interleave the origins. All of them are deterministic, i.e. the same
+/// pending messages are always put in the same order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum MessageSelection {
+    /// All the messages of an origin, then all the messages of the next origin.
+    #[default]
+    ByOrigin,
+    /// The oldest messages first, according to the timestamps of the blocks that sent them.
+    ByTimestamp,
+    /// The messages of the applications listed first go first, followed by the messages of
+    /// the applications that are not listed. Ties are broken by timestamp.
+    ByApplicationPriority(Vec<GenericApplicationId>),
+    /// One message from each origin in turn, so that every origin is served when the number
+    /// of messages in a block is limited.
+    RoundRobin,
+}
+
+impl MessageSelection {
+    /// Returns the given pending messages in the order of this strategy.
+    pub fn order(&self, messages: Vec<IncomingMessage>) -> Vec<IncomingMessage> {
+        let mut queues = BTreeMap::<Origin, VecDeque<IncomingMessage>>::new();
+        for message in messages {
+            queues
+                .entry(message.origin.clone())
+                .or_default()
+                .push_back(message);
+        }
+        match self {
+            MessageSelection::ByOrigin => queues.into_values().flatten().collect(),
+            MessageSelection::ByTimestamp => merge(queues, |message| message.event.timestamp),
+            MessageSelection::ByApplicationPriority(application_ids) => merge(queues, |message| {
+                let application_id = message.event.message.application_id();
+                let priority = application_ids
+                    .iter()
+                    .position(|id| *id == application_id)
+                    .unwrap_or(application_ids.len());
+                (priority, message.event.timestamp)
+            }),
+            MessageSelection::RoundRobin => {
+                let mut ordered = Vec::new();
+                while !queues.is_empty() {
+                    queues.retain(|_, queue| {
+                        ordered.extend(queue.pop_front());
+                        !queue.is_empty()
+                    });
+                }
+                ordered
+            }
+        }
+    }
+}
+
+impl FromStr for MessageSelection {
+    type Err = InvalidMessageSelection;
+
+    /// Parses the strategies that don't need any parameters.
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string {
+            "by-origin" => Ok(MessageSelection::ByOrigin),
+            "by-timestamp" => Ok(MessageSelection::ByTimestamp),
+            "round-robin" => Ok(MessageSelection::RoundRobin),
+            unknown => Err(InvalidMessageSelection(unknown.to_owned())),
+        }
+    }
+}
+
+/// Attempts to parse an unknown [`MessageSelection`] strategy.
+#[derive(Clone, Debug, Error)]
+#[error(
+    "{0:?} is not a valid message selection: expected \"by-origin\", \"by-timestamp\" or \"round-robin\""
+)]
+pub struct InvalidMessageSelection(String);
+
+/// Repeatedly takes the first message of the origin whose next message has the smallest key.
+/// Ties are broken by origin.
+fn merge<K: Ord>(
+    mut queues: BTreeMap<Origin, VecDeque<IncomingMessage>>,
+    key: impl Fn(&IncomingMessage) -> K,
+) -> Vec<IncomingMessage> {
+    let mut ordered = Vec::new();
+    while let Some(origin) = queues
+        .iter()
+        .filter_map(|(origin, queue)| Some((key(queue.front()?), origin)))
+        .min_by(|(key1, _), (key2, _)| key1.cmp(key2))
+        .map(|(_, origin)| origin.clone())
+    {
+        let queue = queues.get_mut(&origin).expect("origin should have a queue");
+        ordered.extend(queue.pop_front());
+        if queue.is_empty() {
+            queues.remove(&origin);
+        }
+    }
+    ordered
+}
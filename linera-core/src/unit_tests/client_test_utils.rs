@@ -160,6 +160,11 @@ where
         self.client.lock().await.fault_type
     }
 
+    async fn set_checkpoint_interval(&self, interval: u64) {
+        let mut validator = self.client.lock().await;
+        validator.state = validator.state.clone().with_checkpoint_interval(interval);
+    }
+
     /// Executes the future produced by `f` in a new thread in a new Tokio runtime.
     /// Returns the value that the future puts into the sender.
     async fn spawn_and_receive<F, R, T>(&self, f: F) -> T
@@ -389,7 +394,8 @@ where
             let store = store_builder.build().await?;
             let state = WorkerState::new(format!("Node {}", i), Some(key_pair), store.clone())
                 .with_allow_inactive_chains(false)
-                .with_allow_messages_from_deprecated_epochs(false);
+                .with_allow_messages_from_deprecated_epochs(false)
+                .with_proposal_pipeline_depth(10);
             let validator = LocalValidatorClient::new(name, state);
             if i < with_faulty_validators {
                 faulty_validators.insert(name);
@@ -435,6 +441,13 @@ where
         );
     }
 
+    /// Makes all validators sign a checkpoint of each chain every `interval` blocks.
+    pub async fn set_checkpoint_interval(&mut self, interval: u64) {
+        for validator in &self.validator_clients {
+            validator.set_checkpoint_interval(interval).await;
+        }
+    }
+
     pub async fn add_initial_chain(
         &mut self,
         description: ChainDescription,
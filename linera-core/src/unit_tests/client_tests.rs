@@ -22,19 +22,20 @@ use linera_base::{
     identifiers::{ChainDescription, ChainId, MessageId, Owner},
 };
 use linera_chain::{
-    data_types::{CertificateValue, ExecutedBlock},
+    data_types::{BlockAndRound, CertificateValue, ExecutedBlock, MessageAction},
     test::multi_manager,
     ChainError, ChainExecutionContext,
 };
 use linera_execution::{
-    committee::{Committee, Epoch},
+    committee::{Committee, Epoch, ValidatorChange},
     policy::ResourceControlPolicy,
     system::{Account, Recipient, SystemOperation, UserData},
-    ChainOwnership, ExecutionError, Operation, SystemExecutionError, SystemQuery, SystemResponse,
+    ApprovalScope, ApprovalThreshold, ChainOwnership, ExecutionError, Operation,
+    SystemExecutionError, SystemQuery, SystemResponse, TimeoutBackoff, TimeoutConfig,
 };
 use linera_storage::Store;
 use linera_views::views::ViewError;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use test_log::test;
 
 #[cfg(feature = "rocksdb")]
@@ -218,29 +219,37 @@ where
 
 #[test(tokio::test)]
 async fn test_memory_rotate_key_pair() -> Result<(), anyhow::Error> {
-    run_test_rotate_key_pair(MakeMemoryStoreClient::default()).await
+    run_test_rotate_key_pair(MakeMemoryStoreClient::default(), SignatureScheme::Ed25519).await
 }
 
 #[cfg(feature = "rocksdb")]
 #[test(tokio::test)]
 async fn test_rocks_db_rotate_key_pair() -> Result<(), anyhow::Error> {
     let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
-    run_test_rotate_key_pair(MakeRocksDbStore::default()).await
+    run_test_rotate_key_pair(MakeRocksDbStore::default(), SignatureScheme::Ed25519).await
 }
 
 #[cfg(feature = "aws")]
 #[test(tokio::test)]
 async fn test_dynamo_db_rotate_key_pair() -> Result<(), anyhow::Error> {
-    run_test_rotate_key_pair(MakeDynamoDbStore::default()).await
+    run_test_rotate_key_pair(MakeDynamoDbStore::default(), SignatureScheme::Ed25519).await
 }
 
 #[cfg(feature = "scylladb")]
 #[test(tokio::test)]
 async fn test_scylla_db_rotate_key_pair() -> Result<(), anyhow::Error> {
-    run_test_rotate_key_pair(MakeScyllaDbStore::default()).await
+    run_test_rotate_key_pair(MakeScyllaDbStore::default(), SignatureScheme::Ed25519).await
 }
 
-async fn run_test_rotate_key_pair<B>(store_builder: B) -> Result<(), anyhow::Error>
+#[test(tokio::test)]
+async fn test_memory_rotate_to_secp256k1_key_pair() -> Result<(), anyhow::Error> {
+    run_test_rotate_key_pair(MakeMemoryStoreClient::default(), SignatureScheme::Secp256k1).await
+}
+
+async fn run_test_rotate_key_pair<B>(
+    store_builder: B,
+    scheme: SignatureScheme,
+) -> Result<(), anyhow::Error>
 where
     B: StoreBuilder,
     ViewError: From<<B::Store as Store>::ContextError>,
@@ -251,7 +260,7 @@ where
     let mut sender = builder
         .add_initial_chain(ChainDescription::Root(1), Amount::from_tokens(4))
         .await?;
-    let new_key_pair = KeyPair::generate();
+    let new_key_pair = KeyPair::generate_with_scheme(scheme);
     let new_owner = Owner::from(new_key_pair.public());
     let certificate = sender.rotate_key_pair(new_key_pair).await.unwrap();
     assert_eq!(sender.next_block_height, BlockHeight::from(1));
@@ -530,6 +539,123 @@ where
     Ok(())
 }
 
+#[test(tokio::test)]
+async fn test_memory_approval_threshold() -> Result<(), anyhow::Error> {
+    run_test_approval_threshold(MakeMemoryStoreClient::default()).await
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_approval_threshold() -> Result<(), anyhow::Error> {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    run_test_approval_threshold(MakeRocksDbStore::default()).await
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_approval_threshold() -> Result<(), anyhow::Error> {
+    run_test_approval_threshold(MakeDynamoDbStore::default()).await
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_approval_threshold() -> Result<(), anyhow::Error> {
+    run_test_approval_threshold(MakeScyllaDbStore::default()).await
+}
+
+async fn run_test_approval_threshold<B>(store_builder: B) -> Result<(), anyhow::Error>
+where
+    B: StoreBuilder,
+    ViewError: From<<B::Store as Store>::ContextError>,
+{
+    let mut builder = TestBuilder::new(store_builder, 4, 0).await?;
+    let mut sender = builder
+        .add_initial_chain(ChainDescription::Root(1), Amount::from_tokens(4))
+        .await?;
+    let new_key_pair = KeyPair::generate();
+    let new_owner = Owner::from(new_key_pair.public());
+
+    // Require both owners to approve transfers.
+    let owner_change_op = SystemOperation::ChangeMultipleOwners {
+        new_public_keys: vec![
+            (sender.public_key().await.unwrap(), 100),
+            (new_key_pair.public(), 100),
+        ],
+        multi_leader_rounds: RoundNumber::MAX,
+        approval_threshold: Some(ApprovalThreshold {
+            weight: 200,
+            scope: ApprovalScope::TransfersAndOwnership,
+        }),
+        timeout_config: TimeoutConfig::default(),
+    };
+    sender
+        .execute_operation(owner_change_op.into())
+        .await
+        .unwrap();
+
+    // A transfer without the other owner's approval is rejected.
+    let transfer_op = SystemOperation::Transfer {
+        owner: None,
+        recipient: Recipient::Account(Account::chain(ChainId::root(2))),
+        amount: Amount::ONE,
+        user_data: UserData::default(),
+    };
+    let result = sender.execute_operation(transfer_op.clone().into()).await;
+    assert!(matches!(
+        result,
+        Err(ChainClientError::LocalNodeError(LocalNodeError::WorkerError(WorkerError::ChainError(error))))
+            if matches!(*error, ChainError::InsufficientApprovals { weight: 100, threshold: 200 })
+    ));
+    assert!(sender.pending_block.is_none());
+
+    // Stage the transfer, and let the other owner approve it.
+    let content = sender
+        .stage_operations_for_approval(vec![transfer_op.into()])
+        .await
+        .unwrap();
+    let mut client = builder
+        .make_client(
+            sender.chain_id,
+            new_key_pair,
+            sender.block_hash,
+            BlockHeight::from(1),
+        )
+        .await?;
+    client.synchronize_from_validators().await.unwrap();
+    let (owner, signature) = client.approve_block(&content).await.unwrap();
+    assert_eq!(owner, new_owner);
+
+    // An approval with an invalid signature is not accepted.
+    let other_signature = Signature::new(&content, &KeyPair::generate());
+    assert!(matches!(
+        sender.add_block_approval(owner, other_signature).await,
+        Err(ChainClientError::InvalidBlockApproval(_))
+    ));
+
+    // Neither is an approval of the same block in another round.
+    let other_round = BlockAndRound {
+        block: content.block.clone(),
+        round: content.round.try_add_one()?,
+    };
+    let (_, other_signature) = client.approve_block(&other_round).await.unwrap();
+    assert!(matches!(
+        sender.add_block_approval(owner, other_signature).await,
+        Err(ChainClientError::InvalidBlockApproval(_))
+    ));
+
+    // With the approval, the transfer goes through.
+    sender.add_block_approval(owner, signature).await.unwrap();
+    let certificate = sender.retry_pending_block().await.unwrap().unwrap();
+    assert_eq!(certificate.value().block(), Some(&content.block));
+    assert!(sender.pending_block.is_none());
+    assert!(sender.pending_approvals.is_empty());
+    assert_eq!(
+        sender.local_balance().await.unwrap(),
+        Amount::from_tokens(3)
+    );
+    Ok(())
+}
+
 #[test(tokio::test)]
 async fn test_memory_open_chain_then_close_it() -> Result<(), anyhow::Error> {
     run_test_open_chain_then_close_it(MakeMemoryStoreClient::default()).await
@@ -587,7 +713,7 @@ where
         Amount::ZERO
     );
     assert_eq!(client.local_balance().await.unwrap(), Amount::ZERO);
-    client.close_chain().await.unwrap();
+    client.close_chain(None).await.unwrap();
     Ok(())
 }
 
@@ -813,21 +939,17 @@ where
     let mut sender = builder
         .add_initial_chain(ChainDescription::Root(1), Amount::from_tokens(4))
         .await?;
-    let certificate = sender.close_chain().await.unwrap();
+    let certificate = sender.close_chain(None).await.unwrap();
     assert!(matches!(
         &certificate.value(),
         CertificateValue::ConfirmedBlock { executed_block: ExecutedBlock { block, .. }, .. } if matches!(
-            &block.operations[..], &[Operation::System(SystemOperation::CloseChain)]
+            &block.operations[..], &[Operation::System(SystemOperation::CloseChain { recipient: None })]
         ),
     ));
     assert_eq!(sender.next_block_height, BlockHeight::from(1));
     assert!(sender.pending_block.is_none());
-    assert!(matches!(
-        sender.key_pair().await,
-        Err(ChainClientError::LocalNodeError(
-            LocalNodeError::InactiveChain(_)
-        ))
-    ));
+    // Without a recipient, the balance was burnt.
+    assert_eq!(sender.local_balance().await.unwrap(), Amount::ZERO);
     assert_eq!(
         builder
             .check_that_validators_have_certificate(sender.chain_id, BlockHeight::ZERO, 3)
@@ -846,10 +968,114 @@ where
                 UserData::default()
             )
             .await,
-        Err(ChainClientError::LocalNodeError(
-            LocalNodeError::InactiveChain(_)
-        ))
+        Err(ChainClientError::LocalNodeError(LocalNodeError::WorkerError(WorkerError::ChainError(error))))
+            if matches!(*error, ChainError::ClosedChain(_))
     ));
+    assert!(sender.pending_block.is_none());
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_memory_close_chain_with_sweep() -> Result<(), anyhow::Error> {
+    run_test_close_chain_with_sweep(MakeMemoryStoreClient::default()).await
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_close_chain_with_sweep() -> Result<(), anyhow::Error> {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    run_test_close_chain_with_sweep(MakeRocksDbStore::default()).await
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_close_chain_with_sweep() -> Result<(), anyhow::Error> {
+    run_test_close_chain_with_sweep(MakeDynamoDbStore::default()).await
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_close_chain_with_sweep() -> Result<(), anyhow::Error> {
+    run_test_close_chain_with_sweep(MakeScyllaDbStore::default()).await
+}
+
+async fn run_test_close_chain_with_sweep<B>(store_builder: B) -> Result<(), anyhow::Error>
+where
+    B: StoreBuilder,
+    ViewError: From<<B::Store as Store>::ContextError>,
+{
+    let mut builder = TestBuilder::new(store_builder, 4, 1).await?;
+    let mut closing = builder
+        .add_initial_chain(ChainDescription::Root(1), Amount::from_tokens(4))
+        .await?;
+    let mut payer = builder
+        .add_initial_chain(ChainDescription::Root(2), Amount::from_tokens(3))
+        .await?;
+    let mut receiver = builder
+        .add_initial_chain(ChainDescription::Root(3), Amount::ZERO)
+        .await?;
+    // Two transfers are still pending when the chain is closed, but the closing block only
+    // has room for one of them.
+    closing.max_pending_messages = 1;
+    for _ in 0..2 {
+        let certificate = payer
+            .transfer_to_account(
+                None,
+                Amount::ONE,
+                Account::chain(ChainId::root(1)),
+                UserData::default(),
+            )
+            .await
+            .unwrap();
+        closing.receive_certificate(certificate).await?;
+    }
+    assert_eq!(payer.local_balance().await.unwrap(), Amount::ONE);
+    let certificate = closing
+        .close_chain(Some(Recipient::chain(ChainId::root(3))))
+        .await
+        .unwrap();
+    let executed_block = certificate.value().executed_block().unwrap();
+    assert_eq!(executed_block.block.incoming_messages.len(), 1);
+    assert_eq!(
+        executed_block.block.incoming_messages[0].action,
+        MessageAction::Reject
+    );
+    // The other transfer was returned in the next block.
+    assert_eq!(closing.next_block_height, BlockHeight::from(2));
+    let next_certificate = builder
+        .check_that_validators_have_certificate(closing.chain_id, BlockHeight::from(1), 3)
+        .await
+        .unwrap();
+    // The balance was swept to the recipient.
+    receiver.receive_certificate(certificate.clone()).await?;
+    receiver.process_inbox().await?;
+    assert_eq!(
+        receiver.local_balance().await.unwrap(),
+        Amount::from_tokens(4)
+    );
+    // The pending transfers were returned to the payer.
+    payer.receive_certificate(certificate).await?;
+    payer.receive_certificate(next_certificate).await?;
+    payer.process_inbox().await?;
+    assert_eq!(payer.local_balance().await.unwrap(), Amount::from_tokens(3));
+
+    // A transfer to the closed chain is returned to the payer as well.
+    let certificate = payer
+        .transfer_to_account(
+            None,
+            Amount::ONE,
+            Account::chain(ChainId::root(1)),
+            UserData::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(payer.local_balance().await.unwrap(), Amount::from_tokens(2));
+    closing.receive_certificate(certificate).await?;
+    for certificate in closing.process_inbox().await? {
+        payer.receive_certificate(certificate).await?;
+    }
+    payer.process_inbox().await?;
+    assert_eq!(payer.local_balance().await.unwrap(), Amount::from_tokens(3));
     Ok(())
 }
 
@@ -956,8 +1182,11 @@ where
         Amount::from_tokens(3)
     );
     assert_eq!(
-        client1.query_system_application(SystemQuery).await.unwrap(),
-        SystemResponse {
+        client1
+            .query_system_application(SystemQuery::Balance)
+            .await
+            .unwrap(),
+        SystemResponse::Balance {
             chain_id: ChainId::root(1),
             balance: Amount::from_tokens(3),
         }
@@ -977,8 +1206,11 @@ where
     assert!(client1.pending_block.is_none());
     assert_eq!(client1.local_balance().await.unwrap(), Amount::ZERO);
     assert_eq!(
-        client1.query_system_application(SystemQuery).await.unwrap(),
-        SystemResponse {
+        client1
+            .query_system_application(SystemQuery::Balance)
+            .await
+            .unwrap(),
+        SystemResponse::Balance {
             chain_id: ChainId::root(1),
             balance: Amount::ZERO,
         }
@@ -1006,8 +1238,11 @@ where
     // The local balance from the client is reflecting incoming messages but the
     // SystemResponse only reads the ChainState.
     assert_eq!(
-        client2.query_system_application(SystemQuery).await.unwrap(),
-        SystemResponse {
+        client2
+            .query_system_application(SystemQuery::Balance)
+            .await
+            .unwrap(),
+        SystemResponse::Balance {
             chain_id: ChainId::root(2),
             balance: Amount::ZERO,
         }
@@ -1036,8 +1271,11 @@ where
     );
     // Local balance from client2 is now consolidated.
     assert_eq!(
-        client2.query_system_application(SystemQuery).await.unwrap(),
-        SystemResponse {
+        client2
+            .query_system_application(SystemQuery::Balance)
+            .await
+            .unwrap(),
+        SystemResponse::Balance {
             chain_id: ChainId::root(2),
             balance: Amount::from_tokens(2),
         }
@@ -1375,6 +1613,99 @@ where
     Ok(())
 }
 
+#[test(tokio::test)]
+async fn test_memory_change_validators_with_grace_period() -> Result<(), anyhow::Error> {
+    run_test_change_validators_with_grace_period(MakeMemoryStoreClient::default()).await
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_change_validators_with_grace_period() -> Result<(), anyhow::Error> {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    run_test_change_validators_with_grace_period(MakeRocksDbStore::default()).await
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_change_validators_with_grace_period() -> Result<(), anyhow::Error> {
+    run_test_change_validators_with_grace_period(MakeDynamoDbStore::default()).await
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_change_validators_with_grace_period() -> Result<(), anyhow::Error> {
+    run_test_change_validators_with_grace_period(MakeScyllaDbStore::default()).await
+}
+
+async fn run_test_change_validators_with_grace_period<B>(
+    store_builder: B,
+) -> Result<(), anyhow::Error>
+where
+    B: StoreBuilder,
+    ViewError: From<<B::Store as Store>::ContextError>,
+{
+    let clock = store_builder.clock().clone();
+    // No validator is faulty: the one with more votes in the new epoch is needed for a quorum.
+    let mut builder = TestBuilder::new(store_builder, 4, 0).await?;
+    let mut admin = builder
+        .add_initial_chain(ChainDescription::Root(0), Amount::from_tokens(3))
+        .await?;
+    let mut user = builder
+        .add_initial_chain(ChainDescription::Root(1), Amount::from_tokens(3))
+        .await?;
+
+    // Start a new epoch where one validator has more votes, and keep trusting the previous
+    // one for a second.
+    let name = *builder
+        .initial_committee
+        .validators()
+        .keys()
+        .next()
+        .unwrap();
+    let changes = vec![ValidatorChange::ChangeVotes { name, votes: 2 }];
+    admin
+        .change_validators(changes, Duration::from_secs(1))
+        .await
+        .unwrap();
+    assert_eq!(admin.epoch().await.unwrap(), Epoch::from(1));
+    assert_eq!(
+        admin.epochs().await.unwrap(),
+        vec![Epoch::ZERO, Epoch::from(1)]
+    );
+
+    // The user chain is still at the epoch 0, but its transfers are accepted during the
+    // grace period.
+    let cert = user
+        .transfer_to_account(
+            None,
+            Amount::ONE,
+            Account::chain(ChainId::root(0)),
+            UserData(None),
+        )
+        .await
+        .unwrap();
+    admin.receive_certificate(cert).await.unwrap();
+
+    // Once the grace period is over, the epoch 0 is not trusted anymore, even though the
+    // admin chain didn't execute any block since.
+    clock.set(Timestamp::from(1_000_000));
+    assert_eq!(admin.epochs().await.unwrap(), vec![Epoch::from(1)]);
+    let cert = user
+        .transfer_to_account(
+            None,
+            Amount::ONE,
+            Account::chain(ChainId::root(0)),
+            UserData(None),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(
+        admin.receive_certificate(cert).await,
+        Err(ChainClientError::CommitteeDeprecationError)
+    ));
+    Ok(())
+}
+
 #[test(tokio::test)]
 pub async fn test_memory_insufficient_balance() -> Result<(), anyhow::Error> {
     run_test_insufficient_balance(MakeMemoryStoreClient::default()).await
@@ -1446,6 +1777,8 @@ where
     let owner_change_op = SystemOperation::ChangeMultipleOwners {
         new_public_keys: vec![(pub_key0, 100), (pub_key1, 100)],
         multi_leader_rounds: RoundNumber::ZERO,
+        approval_threshold: None,
+        timeout_config: TimeoutConfig::default(),
     }
     .into();
     client.execute_operation(owner_change_op).await.unwrap();
@@ -1479,3 +1812,329 @@ where
 
     Ok(())
 }
+
+#[test(tokio::test)]
+async fn test_memory_leader_rotation_with_faulty_owners() -> Result<(), anyhow::Error> {
+    run_test_leader_rotation_with_faulty_owners(MakeMemoryStoreClient::default()).await
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_leader_rotation_with_faulty_owners() -> Result<(), anyhow::Error> {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    run_test_leader_rotation_with_faulty_owners(MakeRocksDbStore::default()).await
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_leader_rotation_with_faulty_owners() -> Result<(), anyhow::Error> {
+    run_test_leader_rotation_with_faulty_owners(MakeDynamoDbStore::default()).await
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_leader_rotation_with_faulty_owners() -> Result<(), anyhow::Error> {
+    run_test_leader_rotation_with_faulty_owners(MakeScyllaDbStore::default()).await
+}
+
+/// Simulates a chain where two of three owners never propose a block: the honest owner times
+/// out their rounds until it becomes the leader itself, and then confirms a block.
+async fn run_test_leader_rotation_with_faulty_owners<B>(
+    store_builder: B,
+) -> Result<(), anyhow::Error>
+where
+    B: StoreBuilder,
+    ViewError: From<<B::Store as Store>::ContextError>,
+{
+    let clock = store_builder.clock().clone();
+    let mut builder = TestBuilder::new(store_builder, 4, 1).await?;
+    let description = ChainDescription::Root(1);
+    let chain_id = ChainId::from(description);
+    let mut client = builder
+        .add_initial_chain(description, Amount::from_tokens(3))
+        .await?;
+    let public_key = client.public_key().await.unwrap();
+    let owner = Owner::from(public_key);
+    let timeout_config = TimeoutConfig {
+        base_timeout_micros: 1_000_000,
+        backoff: TimeoutBackoff::Exponential { factor: 2 },
+        max_timeout_micros: 20_000_000,
+    };
+    let owner_change_op = SystemOperation::ChangeMultipleOwners {
+        new_public_keys: vec![
+            (public_key, 100),
+            (KeyPair::generate().public(), 100),
+            (KeyPair::generate().public(), 100),
+        ],
+        multi_leader_rounds: RoundNumber::ZERO,
+        approval_threshold: None,
+        timeout_config,
+    }
+    .into();
+    client.execute_operation(owner_change_op).await.unwrap();
+
+    let mut round = RoundNumber::ZERO;
+    let mut previous_timeout: Option<Timestamp> = None;
+    loop {
+        let manager = client.chain_info().await.unwrap().manager;
+        let manager = multi_manager(&manager);
+        assert_eq!(manager.current_round, round);
+        // Each round lasts longer than the previous one, up to the maximum.
+        if let Some(previous_timeout) = previous_timeout {
+            let duration = timeout_config.round_duration(round);
+            assert_eq!(
+                manager.round_timeout,
+                previous_timeout.saturating_add(duration)
+            );
+        }
+        if manager.leader == Some(owner) {
+            break;
+        }
+        // We can't propose a block in a faulty owner's round.
+        assert!(client
+            .execute_operations(transfer_to_root_2(1))
+            .await
+            .is_err());
+        assert!(client.pending_block.is_none());
+        clock.set(manager.round_timeout);
+        previous_timeout = Some(manager.round_timeout);
+        let certificate = client.request_leader_timeout().await.unwrap();
+        assert_eq!(certificate.round, round);
+        round = round.try_add_one()?;
+        assert!(
+            round < RoundNumber::from(50),
+            "the honest owner is never the leader"
+        );
+    }
+    builder
+        .check_that_validators_are_in_round(chain_id, BlockHeight::from(1), round, 3)
+        .await;
+
+    // In our own round, we can make progress.
+    let certificate = client
+        .execute_operations(transfer_to_root_2(1))
+        .await
+        .unwrap();
+    assert_eq!(certificate.round, round);
+    assert_eq!(certificate.value().height(), BlockHeight::from(1));
+    assert_eq!(
+        client.local_balance().await.unwrap(),
+        Amount::from_tokens(2)
+    );
+    Ok(())
+}
+
+/// Returns a batch with a transfer of the given amount of tokens to chain 2.
+fn transfer_to_root_2(tokens: u128) -> Vec<Operation> {
+    vec![Operation::System(SystemOperation::Transfer {
+        owner: None,
+        recipient: Recipient::chain(ChainId::root(2)),
+        amount: Amount::from_tokens(tokens),
+        user_data: UserData::default(),
+    })]
+}
+
+#[test(tokio::test)]
+async fn test_memory_pipelined_block_proposals() -> Result<(), anyhow::Error> {
+    run_test_pipelined_block_proposals(MakeMemoryStoreClient::default()).await
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_pipelined_block_proposals() -> Result<(), anyhow::Error> {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    run_test_pipelined_block_proposals(MakeRocksDbStore::default()).await
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_pipelined_block_proposals() -> Result<(), anyhow::Error> {
+    run_test_pipelined_block_proposals(MakeDynamoDbStore::default()).await
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_pipelined_block_proposals() -> Result<(), anyhow::Error> {
+    run_test_pipelined_block_proposals(MakeScyllaDbStore::default()).await
+}
+
+async fn run_test_pipelined_block_proposals<B>(store_builder: B) -> Result<(), anyhow::Error>
+where
+    B: StoreBuilder,
+    ViewError: From<<B::Store as Store>::ContextError>,
+{
+    let mut builder = TestBuilder::new(store_builder, 4, 1).await?;
+    let mut sender = builder
+        .add_initial_chain(ChainDescription::Root(1), Amount::from_tokens(10))
+        .await?;
+    sender.set_pipeline_depth(3);
+    let batches = (1..=4).map(transfer_to_root_2).collect();
+    let certificates = sender.execute_operation_batches(batches).await?;
+    assert_eq!(certificates.len(), 4);
+    for (height, certificate) in (0..).map(BlockHeight).zip(&certificates) {
+        assert_eq!(certificate.value().height(), height);
+        assert_eq!(
+            builder
+                .check_that_validators_have_certificate(sender.chain_id, height, 3)
+                .await
+                .unwrap()
+                .value,
+            certificate.value
+        );
+    }
+    assert_eq!(sender.next_block_height, BlockHeight::from(4));
+    assert!(sender.pending_block.is_none());
+    assert_eq!(sender.local_balance().await?, Amount::ZERO);
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_memory_pipelined_block_proposals_with_failure() -> Result<(), anyhow::Error> {
+    run_test_pipelined_block_proposals_with_failure(MakeMemoryStoreClient::default()).await
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_pipelined_block_proposals_with_failure() -> Result<(), anyhow::Error> {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    run_test_pipelined_block_proposals_with_failure(MakeRocksDbStore::default()).await
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_pipelined_block_proposals_with_failure() -> Result<(), anyhow::Error> {
+    run_test_pipelined_block_proposals_with_failure(MakeDynamoDbStore::default()).await
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_pipelined_block_proposals_with_failure() -> Result<(), anyhow::Error> {
+    run_test_pipelined_block_proposals_with_failure(MakeScyllaDbStore::default()).await
+}
+
+async fn run_test_pipelined_block_proposals_with_failure<B>(
+    store_builder: B,
+) -> Result<(), anyhow::Error>
+where
+    B: StoreBuilder,
+    ViewError: From<<B::Store as Store>::ContextError>,
+{
+    let mut builder = TestBuilder::new(store_builder, 4, 1).await?;
+    let mut sender = builder
+        .add_initial_chain(ChainDescription::Root(1), Amount::from_tokens(10))
+        .await?;
+    sender.set_pipeline_depth(3);
+    // Without a quorum, the first block fails and none of the others is proposed.
+    builder.set_fault_type(1..2, FaultType::Offline).await;
+    let batches = (1..=3).map(transfer_to_root_2).collect();
+    assert!(sender.execute_operation_batches(batches).await.is_err());
+    assert_eq!(sender.next_block_height, BlockHeight::ZERO);
+    assert_eq!(
+        sender.pending_block.as_ref().map(|block| block.height),
+        Some(BlockHeight::ZERO)
+    );
+    // Once the validator is back, the pending block goes through, and the chain can go on with
+    // different blocks.
+    builder.set_fault_type(1..2, FaultType::Honest).await;
+    sender.retry_pending_block().await?.unwrap();
+    let batches = vec![transfer_to_root_2(2), transfer_to_root_2(3)];
+    let certificates = sender.execute_operation_batches(batches).await?;
+    assert_eq!(certificates.len(), 2);
+    assert_eq!(sender.next_block_height, BlockHeight::from(3));
+    assert_eq!(sender.local_balance().await?, Amount::from_tokens(4));
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_memory_synchronize_from_checkpoint() -> Result<(), anyhow::Error> {
+    run_test_synchronize_from_checkpoint(MakeMemoryStoreClient::default()).await
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_synchronize_from_checkpoint() -> Result<(), anyhow::Error> {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    run_test_synchronize_from_checkpoint(MakeRocksDbStore::default()).await
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_synchronize_from_checkpoint() -> Result<(), anyhow::Error> {
+    run_test_synchronize_from_checkpoint(MakeDynamoDbStore::default()).await
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_synchronize_from_checkpoint() -> Result<(), anyhow::Error> {
+    run_test_synchronize_from_checkpoint(MakeScyllaDbStore::default()).await
+}
+
+async fn run_test_synchronize_from_checkpoint<B>(store_builder: B) -> Result<(), anyhow::Error>
+where
+    B: StoreBuilder,
+    ViewError: From<<B::Store as Store>::ContextError>,
+{
+    let mut builder = TestBuilder::new(store_builder, 4, 0).await?;
+    builder.set_checkpoint_interval(2).await;
+    let mut sender = builder
+        .add_initial_chain(ChainDescription::Root(1), Amount::from_tokens(4))
+        .await?;
+    let mut receiver = builder
+        .add_initial_chain(ChainDescription::Root(2), Amount::ZERO)
+        .await?;
+    // The receiver creates one block per transfer. Validators sign a checkpoint after the
+    // second one.
+    let mut certificates = Vec::new();
+    for _ in 0..3 {
+        let certificate = sender
+            .transfer(
+                None,
+                Amount::ONE,
+                Recipient::chain(receiver.chain_id),
+                UserData(None),
+            )
+            .await
+            .unwrap();
+        receiver.receive_certificate(certificate).await?;
+        certificates.extend(receiver.process_inbox().await?);
+    }
+    assert_eq!(certificates.len(), 3);
+    assert_eq!(receiver.next_block_height, BlockHeight::from(3));
+    // A new client starts from the checkpoint and only executes the last block.
+    let key_pair = receiver.key_pair().await?.copy();
+    let mut client = builder
+        .make_client(receiver.chain_id, key_pair, None, BlockHeight::ZERO)
+        .await?;
+    assert_eq!(
+        client.synchronize_from_checkpoint().await?,
+        Amount::from_tokens(3)
+    );
+    assert_eq!(client.next_block_height, BlockHeight::from(3));
+    assert_eq!(client.block_hash, receiver.block_hash);
+    let storage = client.node_client.storage_client().await;
+    for (height, certificate) in certificates.iter().enumerate() {
+        let result = storage.read_certificate(certificate.hash()).await;
+        if height < 2 {
+            assert!(matches!(result, Err(ViewError::NotFound(_))));
+        } else {
+            assert_eq!(result?.hash(), certificate.hash());
+        }
+    }
+    // The messages consumed before the checkpoint are not pending again, so the new client
+    // can go on with the chain.
+    let certificate = sender
+        .transfer(
+            None,
+            Amount::ONE,
+            Recipient::chain(client.chain_id),
+            UserData(None),
+        )
+        .await
+        .unwrap();
+    client.receive_certificate(certificate).await?;
+    let certificates = client.process_inbox().await?;
+    assert_eq!(certificates.len(), 1);
+    assert_eq!(client.next_block_height, BlockHeight::from(4));
+    assert_eq!(client.local_balance().await?, Amount::from_tokens(4));
+    Ok(())
+}
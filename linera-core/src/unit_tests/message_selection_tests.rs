@@ -0,0 +1,227 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use linera_base::{
+    crypto::{BcsSignable, CryptoHash},
+    data_types::{Amount, BlockHeight, Timestamp},
+    identifiers::{ChainId, MessageId},
+};
+use linera_chain::data_types::{Event, MessageAction};
+use linera_execution::{Message, MessageKind, UserApplicationId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Serialize, Deserialize)]
+struct Dummy;
+
+impl BcsSignable for Dummy {}
+
+fn make_message(sender: u32, height: u64, timestamp: u64, application: u64) -> IncomingMessage {
+    let application_id = UserApplicationId {
+        creation: MessageId {
+            chain_id: ChainId::root(0),
+            height: BlockHeight::from(application),
+            index: 0,
+        },
+        ..UserApplicationId::default()
+    };
+    IncomingMessage {
+        origin: Origin::chain(ChainId::root(sender)),
+        event: Event {
+            certificate_hash: CryptoHash::new(&Dummy),
+            height: BlockHeight::from(height),
+            index: 0,
+            authenticated_signer: None,
+            is_skippable: false,
+            kind: MessageKind::Simple,
+            grant: Amount::ZERO,
+            timestamp: Timestamp::from(timestamp),
+            message: Message::User {
+                application_id,
+                bytes: vec![],
+            },
+        },
+        action: MessageAction::Accept,
+    }
+}
+
+/// Returns the sender and height of each message, to compare orders concisely.
+fn summary(messages: &[IncomingMessage]) -> Vec<(ChainId, u64)> {
+    messages
+        .iter()
+        .map(|message| (message.origin.sender, message.event.height.0))
+        .collect()
+}
+
+/// Returns the pending messages of three origins, as listed by the worker.
+fn pending_messages() -> Vec<IncomingMessage> {
+    vec![
+        make_message(1, 0, 30, 0),
+        make_message(1, 1, 40, 1),
+        make_message(2, 0, 10, 1),
+        make_message(2, 1, 50, 0),
+        make_message(3, 0, 5, 0),
+    ]
+}
+
+#[test]
+fn test_by_origin() {
+    let ordered = MessageSelection::ByOrigin.order(pending_messages());
+    assert_eq!(summary(&ordered), summary(&pending_messages()));
+}
+
+#[test]
+fn test_by_timestamp() {
+    let ordered = MessageSelection::ByTimestamp.order(pending_messages());
+    let (chain1, chain2, chain3) = (ChainId::root(1), ChainId::root(2), ChainId::root(3));
+    assert_eq!(
+        summary(&ordered),
+        vec![
+            (chain3, 0),
+            (chain2, 0),
+            (chain1, 0),
+            (chain1, 1),
+            (chain2, 1)
+        ]
+    );
+}
+
+#[test]
+fn test_by_application_priority() {
+    let priority = make_message(0, 0, 0, 1).event.message.application_id();
+    let ordered = MessageSelection::ByApplicationPriority(vec![priority]).order(pending_messages());
+    let (chain1, chain2, chain3) = (ChainId::root(1), ChainId::root(2), ChainId::root(3));
+    // The second message of chain 1 has priority, but it must stay after the first one.
+    assert_eq!(
+        summary(&ordered),
+        vec![
+            (chain2, 0),
+            (chain3, 0),
+            (chain1, 0),
+            (chain1, 1),
+            (chain2, 1)
+        ]
+    );
+}
+
+#[test]
+fn test_round_robin() {
+    let ordered = MessageSelection::RoundRobin.order(pending_messages());
+    let (chain1, chain2, chain3) = (ChainId::root(1), ChainId::root(2), ChainId::root(3));
+    assert_eq!(
+        summary(&ordered),
+        vec![
+            (chain1, 0),
+            (chain2, 0),
+            (chain3, 0),
+            (chain1, 1),
+            (chain2, 1)
+        ]
+    );
+}
+
+#[test]
+fn test_round_robin_fairness_under_load() {
+    // A busy origin sends many messages, and two others send a few.
+    let mut messages = Vec::new();
+    for height in 0..1000 {
+        messages.push(make_message(1, height, height, 0));
+    }
+    for height in 0..5 {
+        messages.push(make_message(2, height, 2000 + height, 0));
+        messages.push(make_message(3, height, 3000 + height, 0));
+    }
+    messages.sort_by(|message1, message2| message1.origin.cmp(&message2.origin));
+    let max_pending_messages = 12;
+    for selection in [MessageSelection::ByOrigin, MessageSelection::ByTimestamp] {
+        let ordered = selection.order(messages.clone());
+        assert!(ordered[..max_pending_messages]
+            .iter()
+            .all(|message| message.origin.sender == ChainId::root(1)));
+    }
+    // With round-robin, every origin gets its share of a block.
+    let ordered = MessageSelection::RoundRobin.order(messages.clone());
+    for sender in 1..=3 {
+        let count = ordered[..max_pending_messages]
+            .iter()
+            .filter(|message| message.origin.sender == ChainId::root(sender))
+            .count();
+        assert_eq!(count, 4);
+    }
+    // All strategies keep every message, in the inbox order of each origin.
+    for selection in [
+        MessageSelection::ByOrigin,
+        MessageSelection::ByTimestamp,
+        MessageSelection::RoundRobin,
+    ] {
+        let ordered = selection.order(messages.clone());
+        assert_eq!(ordered.len(), messages.len());
+        for sender in 1..=3 {
+            let heights = ordered
+                .iter()
+                .filter(|message| message.origin.sender == ChainId::root(sender))
+                .map(|message| message.event.height)
+                .collect::<Vec<_>>();
+            assert!(heights.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+}
+
+#[test]
+fn test_round_robin_fairness_with_many_origins() {
+    // Origin `n` has `n` pending messages, and the busiest ones have the oldest messages.
+    let origins = 100;
+    let mut messages = Vec::new();
+    for sender in 1..=origins {
+        for height in 0..u64::from(sender) {
+            messages.push(make_message(sender, height, u64::from(origins - sender), 0));
+        }
+    }
+    let ordered = MessageSelection::RoundRobin.order(messages.clone());
+    assert_eq!(ordered.len(), messages.len());
+    // Every origin gets one message before any origin gets a second one.
+    let first_round = ordered[..origins as usize]
+        .iter()
+        .map(|message| message.origin.sender)
+        .collect::<BTreeSet<_>>();
+    assert_eq!(first_round.len(), origins as usize);
+    // In any prefix, the origins that still have messages left get shares that differs by at
+    // most one message.
+    for limit in [10, 150, 1000, 4000] {
+        let mut counts = BTreeMap::<ChainId, u64>::new();
+        for message in &ordered[..limit] {
+            *counts.entry(message.origin.sender).or_default() += 1;
+        }
+        let shares = (1..=origins)
+            .filter_map(|sender| {
+                let count = counts
+                    .get(&ChainId::root(sender))
+                    .copied()
+                    .unwrap_or_default();
+                (count < u64::from(sender)).then_some(count)
+            })
+            .collect::<Vec<_>>();
+        let fewest = shares.iter().min().copied().unwrap_or_default();
+        let most = shares.iter().max().copied().unwrap_or_default();
+        assert!(
+            most - fewest <= 1,
+            "Unfair share in the first {limit} messages"
+        );
+    }
+}
+
+#[test]
+fn test_parse_message_selection() {
+    assert_eq!(
+        "round-robin".parse::<MessageSelection>().unwrap(),
+        MessageSelection::RoundRobin
+    );
+    assert_eq!(
+        "by-timestamp".parse::<MessageSelection>().unwrap(),
+        MessageSelection::ByTimestamp
+    );
+    assert!("by-application-priority"
+        .parse::<MessageSelection>()
+        .is_err());
+}
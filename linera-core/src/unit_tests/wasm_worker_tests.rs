@@ -19,8 +19,8 @@ use linera_base::{
 };
 use linera_chain::{
     data_types::{
-        ChannelFullName, Event, ExecutedBlock, HashedValue, IncomingMessage, Origin,
-        OutgoingMessage,
+        ChannelFullName, Event, ExecutedBlock, HashedValue, IncomingMessage, MessageAction, Origin,
+        OutgoingMessage, Transaction,
     },
     test::{make_child_block, make_first_block, BlockTestExt},
 };
@@ -29,7 +29,7 @@ use linera_execution::{
     policy::ResourceControlPolicy,
     system::{SystemChannel, SystemMessage, SystemOperation},
     Bytecode, BytecodeLocation, ChainOwnership, ChannelSubscription, ExecutionStateView,
-    GenericApplicationId, Message, Operation, OperationContext, ResourceTracker,
+    GenericApplicationId, Message, MessageKind, Operation, OperationContext, ResourceTracker,
     SystemExecutionState, UserApplicationDescription, UserApplicationId, WasmApplication,
     WasmRuntime,
 };
@@ -149,9 +149,13 @@ where
             destination: Destination::Recipient(publisher_chain.into()),
             authenticated_signer: None,
             is_skippable: false,
+            kind: MessageKind::Simple,
+            grant: Amount::ZERO,
             message: Message::System(publish_message.clone()),
         }],
         message_counts: vec![1],
+        events: vec![],
+        failed_operations: vec![],
         state_hash: publisher_state_hash,
     });
     let publish_certificate = make_certificate(&committee, &worker, publish_block_proposal);
@@ -177,9 +181,12 @@ where
             index: 0,
             authenticated_signer: None,
             is_skippable: false,
+            kind: MessageKind::Simple,
+            grant: Amount::ZERO,
             timestamp: Timestamp::from(1),
             message: Message::System(publish_message),
         },
+        action: MessageAction::Accept,
     };
     let broadcast_block = make_child_block(&publish_certificate.value)
         .with_timestamp(1)
@@ -209,9 +216,13 @@ where
             destination: broadcast_channel,
             authenticated_signer: None,
             is_skippable: false,
+            kind: MessageKind::Simple,
+            grant: Amount::ZERO,
             message: Message::System(broadcast_message.clone()),
         }],
         message_counts: vec![1],
+        events: vec![],
+        failed_operations: vec![],
         state_hash: publisher_state_hash,
     });
     let broadcast_certificate = make_certificate(&committee, &worker, broadcast_block_proposal);
@@ -259,9 +270,13 @@ where
             destination: Destination::Recipient(publisher_chain.into()),
             authenticated_signer: None,
             is_skippable: false,
+            kind: MessageKind::Simple,
+            grant: Amount::ZERO,
             message: Message::System(subscribe_message.clone()),
         }],
         message_counts: vec![1],
+        events: vec![],
+        failed_operations: vec![],
         state_hash: creator_state.crypto_hash().await?,
     });
     let subscribe_certificate = make_certificate(&committee, &worker, subscribe_block_proposal);
@@ -287,9 +302,12 @@ where
             index: 0,
             authenticated_signer: None,
             is_skippable: false,
+            kind: MessageKind::Simple,
+            grant: Amount::ZERO,
             timestamp: Timestamp::from(2),
             message: subscribe_message.into(),
         },
+        action: MessageAction::Accept,
     };
     let accept_block = make_child_block(&broadcast_certificate.value)
         .with_timestamp(3)
@@ -302,11 +320,15 @@ where
             destination: Destination::Recipient(creator_chain.into()),
             authenticated_signer: None,
             is_skippable: false,
+            kind: MessageKind::Simple,
+            grant: Amount::ZERO,
             message: Message::System(SystemMessage::Notify {
                 id: creator_chain.into(),
             }),
         }],
         message_counts: vec![1],
+        events: vec![],
+        failed_operations: vec![],
         state_hash: publisher_state_hash,
     });
     let accept_certificate = make_certificate(&committee, &worker, accept_block_proposal);
@@ -331,6 +353,7 @@ where
         parameters: vec![],
         initialization_argument: initial_value_bytes.clone(),
         required_application_ids: vec![],
+        storage_quota: None,
     };
     let application_id = UserApplicationId {
         bytecode_id,
@@ -346,6 +369,7 @@ where
         creation: application_id.creation,
         required_application_ids: vec![],
         parameters: vec![],
+        storage_quota: None,
     };
     let publish_admin_channel = ChannelFullName {
         application_id: GenericApplicationId::System,
@@ -362,9 +386,12 @@ where
                 index: 0,
                 authenticated_signer: None,
                 is_skippable: false,
+                kind: MessageKind::Simple,
+                grant: Amount::ZERO,
                 timestamp: Timestamp::from(1),
                 message: Message::System(broadcast_message),
             },
+            action: MessageAction::Accept,
         });
     creator_system_state
         .registry
@@ -389,9 +416,13 @@ where
             destination: Destination::Recipient(creator_chain.into()),
             authenticated_signer: None,
             is_skippable: false,
+            kind: MessageKind::Simple,
+            grant: Amount::ZERO,
             message: Message::System(SystemMessage::ApplicationCreated),
         }],
         message_counts: vec![0, 1],
+        events: vec![],
+        failed_operations: vec![],
         state_hash: creator_state.crypto_hash().await?,
     });
     let create_certificate = make_certificate(&committee, &worker, create_block_proposal);
@@ -423,6 +454,7 @@ where
         height: run_block.height,
         index: 0,
         next_message_index: 0,
+        random_seed: run_block.random_seed(Transaction::Operation(0)),
     };
     let mut tracker = ResourceTracker::default();
     let policy = ResourceControlPolicy::default();
@@ -442,6 +474,8 @@ where
         block: run_block,
         messages: vec![],
         message_counts: vec![0],
+        events: vec![],
+        failed_operations: vec![],
         state_hash: creator_state.crypto_hash().await?,
     });
     let run_certificate = make_certificate(&committee, &worker, run_block_proposal);
@@ -20,9 +20,9 @@ use linera_base::{
 };
 use linera_chain::{
     data_types::{
-        Block, BlockProposal, Certificate, ChainAndHeight, ChannelFullName, Event, ExecutedBlock,
-        HashedValue, IncomingMessage, LiteVote, Medium, Origin, OutgoingMessage,
-        SignatureAggregator,
+        Block, BlockAndRound, BlockProposal, Certificate, ChainAndHeight, ChannelFullName, Event,
+        ExecutedBlock, FailedOperation, HashedValue, IncomingMessage, LiteVote, Medium,
+        MessageAction, Origin, OutgoingMessage, SignatureAggregator,
     },
     test::{make_child_block, make_first_block, multi_manager, BlockTestExt, VoteTestExt},
     ChainError, ChainExecutionContext, ChainManager,
@@ -30,16 +30,17 @@ use linera_chain::{
 use linera_execution::{
     committee::{Committee, Epoch, ValidatorName},
     system::{Account, AdminOperation, Recipient, SystemChannel, SystemMessage, SystemOperation},
-    ChainOwnership, ChannelSubscription, ExecutionError, ExecutionStateView, GenericApplicationId,
-    Message, Query, Response, SystemExecutionError, SystemExecutionState, SystemQuery,
-    SystemResponse,
+    ApprovalScope, ApprovalThreshold, ChainOwnership, ChannelSubscription, ExecutionError,
+    ExecutionStateView, GenericApplicationId, Message, MessageKind, MessageOutcome, Query,
+    Response, SystemExecutionError, SystemExecutionState, SystemQuery, SystemResponse,
+    TimeoutConfig,
 };
 use linera_storage::{DbStore, MemoryStoreClient, Store, TestClock};
 use linera_views::{
     common::KeyValueStoreClient,
     memory::TEST_MEMORY_MAX_STREAM_QUERIES,
     value_splitting::DatabaseConsistencyError,
-    views::{CryptoHashView, ViewError},
+    views::{CryptoHashView, RootView, ViewError},
 };
 use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, iter};
@@ -223,10 +224,7 @@ async fn make_transfer_certificate_for_epoch<S>(
     }
     .with_simple_transfer(recipient, amount);
     let messages = match recipient {
-        Recipient::Account(account) => vec![direct_outgoing_message(
-            account.chain_id,
-            SystemMessage::Credit { account, amount },
-        )],
+        Recipient::Account(account) => vec![credit_outgoing_message(account, amount)],
         Recipient::Burn => Vec::new(),
     };
     let state_hash = make_state_hash(system_state).await;
@@ -234,6 +232,8 @@ async fn make_transfer_certificate_for_epoch<S>(
         block,
         messages,
         message_counts,
+        events: vec![],
+        failed_operations: vec![],
         state_hash,
     });
     make_certificate(committee, worker, value)
@@ -244,6 +244,8 @@ fn direct_outgoing_message(recipient: ChainId, message: SystemMessage) -> Outgoi
         destination: Destination::Recipient(recipient),
         authenticated_signer: None,
         is_skippable: false,
+        kind: MessageKind::Simple,
+        grant: Amount::ZERO,
         message: Message::System(message),
     }
 }
@@ -253,14 +255,29 @@ fn channel_outgoing_message(name: ChannelName, message: SystemMessage) -> Outgoi
         destination: Destination::Subscribers(name),
         authenticated_signer: None,
         is_skippable: false,
+        kind: MessageKind::Simple,
+        grant: Amount::ZERO,
         message: Message::System(message),
     }
 }
 
+fn credit_outgoing_message(account: Account, amount: Amount) -> OutgoingMessage {
+    OutgoingMessage {
+        destination: Destination::Recipient(account.chain_id),
+        authenticated_signer: None,
+        is_skippable: false,
+        kind: MessageKind::Tracked,
+        grant: Amount::ZERO,
+        message: Message::System(SystemMessage::Credit {
+            account,
+            amount,
+            source: None,
+        }),
+    }
+}
+
 fn direct_credit_message(recipient: ChainId, amount: Amount) -> OutgoingMessage {
-    let account = Account::chain(recipient);
-    let message = SystemMessage::Credit { account, amount };
-    direct_outgoing_message(recipient, message)
+    credit_outgoing_message(Account::chain(recipient), amount)
 }
 
 /// Creates `count` key pairs and returns them, sorted by the `Owner` created from their public key.
@@ -486,6 +503,8 @@ where
             block,
             messages: vec![],
             message_counts: vec![],
+            events: vec![],
+            failed_operations: vec![],
             state_hash,
         });
         make_certificate(&committee, &worker, value)
@@ -752,6 +771,8 @@ where
                 direct_credit_message(ChainId::root(2), Amount::from_tokens(2)),
             ],
             message_counts: vec![1, 2],
+            events: vec![],
+            failed_operations: vec![],
             state_hash: make_state_hash(SystemExecutionState {
                 committees: [(epoch, committee.clone())].into_iter().collect(),
                 ownership: ChainOwnership::single(sender_key_pair.public()),
@@ -773,6 +794,8 @@ where
                 Amount::from_tokens(3),
             )],
             message_counts: vec![1],
+            events: vec![],
+            failed_operations: vec![],
             state_hash: make_state_hash(SystemExecutionState {
                 committees: [(epoch, committee.clone())].into_iter().collect(),
                 ownership: ChainOwnership::single(sender_key_pair.public()),
@@ -869,12 +892,16 @@ where
                     index: 0,
                     authenticated_signer: None,
                     is_skippable: false,
+                    kind: MessageKind::Tracked,
+                    grant: Amount::ZERO,
                     timestamp: Timestamp::from(0),
                     message: Message::System(SystemMessage::Credit {
                         account: Account::chain(ChainId::root(2)),
                         amount: Amount::ONE,
+                        source: None,
                     }),
                 },
+                action: MessageAction::Accept,
             })
             .with_incoming_message(IncomingMessage {
                 origin: Origin::chain(ChainId::root(1)),
@@ -884,12 +911,16 @@ where
                     index: 1,
                     authenticated_signer: None,
                     is_skippable: false,
+                    kind: MessageKind::Tracked,
+                    grant: Amount::ZERO,
                     timestamp: Timestamp::from(0),
                     message: Message::System(SystemMessage::Credit {
                         account: Account::chain(ChainId::root(2)),
                         amount: Amount::from_tokens(2),
+                        source: None,
                     }),
                 },
+                action: MessageAction::Accept,
             })
             .with_incoming_message(IncomingMessage {
                 origin: Origin::chain(ChainId::root(1)),
@@ -899,12 +930,16 @@ where
                     index: 0,
                     authenticated_signer: None,
                     is_skippable: false,
+                    kind: MessageKind::Tracked,
+                    grant: Amount::ZERO,
                     timestamp: Timestamp::from(0),
                     message: Message::System(SystemMessage::Credit {
                         account: Account::chain(ChainId::root(2)),
                         amount: Amount::from_tokens(2), // wrong
+                        source: None,
                     }),
                 },
+                action: MessageAction::Accept,
             })
             .into_simple_proposal(&recipient_key_pair);
         // Inconsistent received messages.
@@ -925,12 +960,16 @@ where
                     index: 1,
                     authenticated_signer: None,
                     is_skippable: false,
+                    kind: MessageKind::Tracked,
+                    grant: Amount::ZERO,
                     timestamp: Timestamp::from(0),
                     message: Message::System(SystemMessage::Credit {
                         account: Account::chain(ChainId::root(2)),
                         amount: Amount::from_tokens(2),
+                        source: None,
                     }),
                 },
+                action: MessageAction::Accept,
             })
             .into_simple_proposal(&recipient_key_pair);
         // Skipped message.
@@ -951,12 +990,16 @@ where
                     index: 0,
                     authenticated_signer: None,
                     is_skippable: false,
+                    kind: MessageKind::Tracked,
+                    grant: Amount::ZERO,
                     timestamp: Timestamp::from(0),
                     message: Message::System(SystemMessage::Credit {
                         account: Account::chain(ChainId::root(2)),
                         amount: Amount::from_tokens(3),
+                        source: None,
                     }),
                 },
+                action: MessageAction::Accept,
             })
             .with_incoming_message(IncomingMessage {
                 origin: Origin::chain(ChainId::root(1)),
@@ -966,12 +1009,16 @@ where
                     index: 0,
                     authenticated_signer: None,
                     is_skippable: false,
+                    kind: MessageKind::Tracked,
+                    grant: Amount::ZERO,
                     timestamp: Timestamp::from(0),
                     message: Message::System(SystemMessage::Credit {
                         account: Account::chain(ChainId::root(2)),
                         amount: Amount::ONE,
+                        source: None,
                     }),
                 },
+                action: MessageAction::Accept,
             })
             .with_incoming_message(IncomingMessage {
                 origin: Origin::chain(ChainId::root(1)),
@@ -981,12 +1028,16 @@ where
                     index: 1,
                     authenticated_signer: None,
                     is_skippable: false,
+                    kind: MessageKind::Tracked,
+                    grant: Amount::ZERO,
                     timestamp: Timestamp::from(0),
                     message: Message::System(SystemMessage::Credit {
                         account: Account::chain(ChainId::root(2)),
                         amount: Amount::from_tokens(2),
+                        source: None,
                     }),
                 },
+                action: MessageAction::Accept,
             })
             .into_simple_proposal(&recipient_key_pair);
         // Inconsistent order in received messages (heights).
@@ -1007,12 +1058,16 @@ where
                     index: 0,
                     authenticated_signer: None,
                     is_skippable: false,
+                    kind: MessageKind::Tracked,
+                    grant: Amount::ZERO,
                     timestamp: Timestamp::from(0),
                     message: Message::System(SystemMessage::Credit {
                         account: Account::chain(ChainId::root(2)),
                         amount: Amount::ONE,
+                        source: None,
                     }),
                 },
+                action: MessageAction::Accept,
             })
             .into_simple_proposal(&recipient_key_pair);
         // Taking the first message only is ok.
@@ -1027,6 +1082,8 @@ where
                 block: block_proposal.content.block,
                 messages: vec![direct_credit_message(ChainId::root(3), Amount::ONE)],
                 message_counts: vec![0, 1],
+                events: vec![],
+                failed_operations: vec![],
                 state_hash: make_state_hash(SystemExecutionState {
                     committees: [(epoch, committee.clone())].into_iter().collect(),
                     ownership: ChainOwnership::single(recipient_key_pair.public()),
@@ -1051,12 +1108,16 @@ where
                     index: 1,
                     authenticated_signer: None,
                     is_skippable: false,
+                    kind: MessageKind::Tracked,
+                    grant: Amount::ZERO,
                     timestamp: Timestamp::from(0),
                     message: Message::System(SystemMessage::Credit {
                         account: Account::chain(ChainId::root(2)),
                         amount: Amount::from_tokens(2),
+                        source: None,
                     }),
                 },
+                action: MessageAction::Accept,
             })
             .with_incoming_message(IncomingMessage {
                 origin: Origin::chain(ChainId::root(1)),
@@ -1066,12 +1127,16 @@ where
                     index: 0,
                     authenticated_signer: None,
                     is_skippable: false,
+                    kind: MessageKind::Tracked,
+                    grant: Amount::ZERO,
                     timestamp: Timestamp::from(0),
                     message: Message::System(SystemMessage::Credit {
                         account: Account::chain(ChainId::root(2)),
                         amount: Amount::from_tokens(3),
+                        source: None,
                     }),
                 },
+                action: MessageAction::Accept,
             })
             .into_simple_proposal(&recipient_key_pair);
         worker
@@ -1153,6 +1218,216 @@ where
         .is_none());
 }
 
+#[test(tokio::test)]
+async fn test_memory_stage_block_with_independent_operations() {
+    let store = MemoryStoreClient::make_test_store(None).await;
+    run_test_stage_block_with_independent_operations(store).await;
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_stage_block_with_independent_operations() {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    let store = RocksDbStore::make_test_store(None).await;
+    run_test_stage_block_with_independent_operations(store).await;
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_stage_block_with_independent_operations() {
+    let store = DynamoDbStore::make_test_store(None).await;
+    run_test_stage_block_with_independent_operations(store).await;
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_stage_block_with_independent_operations() {
+    let store = ScyllaDbStore::make_test_store(None).await;
+    run_test_stage_block_with_independent_operations(store).await;
+}
+
+async fn run_test_stage_block_with_independent_operations<S>(store: S)
+where
+    S: Store + Clone + Send + Sync + 'static,
+    ViewError: From<S::ContextError>,
+{
+    let sender_key_pair = KeyPair::generate();
+    let (_, mut worker) = init_worker_with_chains(
+        store,
+        vec![
+            (
+                ChainDescription::Root(1),
+                sender_key_pair.public(),
+                Amount::from_tokens(5),
+            ),
+            (ChainDescription::Root(2), PublicKey::debug(2), Amount::ZERO),
+        ],
+    )
+    .await;
+    let block = Block {
+        independent_operations: true,
+        ..make_first_block(ChainId::root(1))
+    }
+    .with_simple_transfer(Recipient::root(2), Amount::from_tokens(1000))
+    .with_simple_transfer(Recipient::root(2), Amount::from_tokens(2));
+    let (executed_block, response) = worker.stage_block_execution(block).await.unwrap();
+    let error = ExecutionError::SystemError(SystemExecutionError::InsufficientFunding {
+        current_balance: Amount::from_tokens(5),
+    });
+    assert_eq!(
+        executed_block.failed_operations,
+        vec![FailedOperation {
+            index: 0,
+            error: error.to_string(),
+            fees: Amount::ZERO,
+        }]
+    );
+    assert_eq!(
+        executed_block.messages,
+        vec![direct_credit_message(
+            ChainId::root(2),
+            Amount::from_tokens(2)
+        )]
+    );
+    assert_eq!(executed_block.message_counts, vec![0, 1]);
+    assert_eq!(response.info.system_balance, Amount::from_tokens(3));
+
+    // Without independent operations, the whole block fails.
+    let block = make_first_block(ChainId::root(1))
+        .with_simple_transfer(Recipient::root(2), Amount::from_tokens(1000))
+        .with_simple_transfer(Recipient::root(2), Amount::from_tokens(2));
+    assert!(matches!(
+        worker.stage_block_execution(block).await,
+        Err(
+            WorkerError::ChainError(error)
+        ) if matches!(
+            *error,
+            ChainError::ExecutionError(
+                ExecutionError::SystemError(SystemExecutionError::InsufficientFunding { .. }),
+                ChainExecutionContext::Operation(0)
+            )
+        )
+    ));
+}
+
+#[test(tokio::test)]
+async fn test_memory_pipelined_block_proposals() {
+    let store = MemoryStoreClient::make_test_store(None).await;
+    run_test_pipelined_block_proposals(store).await;
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_pipelined_block_proposals() {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    let store = RocksDbStore::make_test_store(None).await;
+    run_test_pipelined_block_proposals(store).await;
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_pipelined_block_proposals() {
+    let store = DynamoDbStore::make_test_store(None).await;
+    run_test_pipelined_block_proposals(store).await;
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_pipelined_block_proposals() {
+    let store = ScyllaDbStore::make_test_store(None).await;
+    run_test_pipelined_block_proposals(store).await;
+}
+
+async fn run_test_pipelined_block_proposals<S>(store: S)
+where
+    S: Store + Clone + Send + Sync + 'static,
+    ViewError: From<S::ContextError>,
+{
+    let sender_key_pair = KeyPair::generate();
+    let (committee, worker) = init_worker_with_chains(
+        store,
+        vec![(
+            ChainDescription::Root(1),
+            sender_key_pair.public(),
+            Amount::from_tokens(6),
+        )],
+    )
+    .await;
+    let mut worker = worker.with_proposal_pipeline_depth(1);
+    let blocks = (1..=3)
+        .map(|tokens| {
+            make_first_block(ChainId::root(1))
+                .with_simple_transfer(Recipient::root(2), Amount::from_tokens(tokens))
+        })
+        .enumerate()
+        .map(|(height, block)| Block {
+            height: BlockHeight::from(height as u64),
+            ..block
+        })
+        .collect::<Vec<_>>();
+    // Pipelined blocks cannot have independent operations.
+    let independent_blocks = blocks
+        .iter()
+        .map(|block| Block {
+            independent_operations: true,
+            ..block.clone()
+        })
+        .collect();
+    assert!(matches!(
+        worker
+            .stage_pipelined_block_execution(independent_blocks)
+            .await,
+        Err(WorkerError::PipelinedIndependentOperations)
+    ));
+    let executed_blocks = worker
+        .stage_pipelined_block_execution(blocks)
+        .await
+        .unwrap();
+    let values = executed_blocks
+        .into_iter()
+        .map(HashedValue::new_confirmed)
+        .collect::<Vec<_>>();
+    let proposals = values
+        .iter()
+        .map(|value| {
+            let block = value.inner().block().unwrap().clone();
+            block.into_simple_proposal(&sender_key_pair)
+        })
+        .collect::<Vec<_>>();
+
+    let (response, _) = worker
+        .handle_block_proposal(proposals[0].clone())
+        .await
+        .unwrap();
+    let vote = response.info.manager.pending().unwrap();
+    assert_eq!(vote.value.value_hash, values[0].hash());
+
+    // The next proposal is kept until the first block is confirmed.
+    let (response, _) = worker
+        .handle_block_proposal(proposals[1].clone())
+        .await
+        .unwrap();
+    assert_eq!(response.info.next_block_height, BlockHeight::ZERO);
+    let vote = response.info.manager.pending().unwrap();
+    assert_eq!(vote.value.value_hash, values[0].hash());
+
+    // Proposals beyond the pipeline depth are rejected.
+    assert!(worker
+        .handle_block_proposal(proposals[2].clone())
+        .await
+        .is_err());
+
+    // Confirming the first block yields the vote for the second one.
+    let certificate0 = make_certificate(&committee, &worker, values[0].clone());
+    let (response, _) = worker
+        .handle_certificate(certificate0, vec![], None)
+        .await
+        .unwrap();
+    assert_eq!(response.info.next_block_height, BlockHeight::from(1));
+    let vote = response.info.manager.pending().unwrap();
+    assert_eq!(vote.value.value_hash, values[1].hash());
+}
+
 #[test(tokio::test)]
 async fn test_memory_handle_block_proposal() {
     let store = MemoryStoreClient::make_test_store(None).await;
@@ -1536,12 +1811,16 @@ where
                 index: 0,
                 authenticated_signer: None,
                 is_skippable: false,
+                kind: MessageKind::Tracked,
+                grant: Amount::ZERO,
                 timestamp: Timestamp::from(0),
                 message: Message::System(SystemMessage::Credit {
                     account: Account::chain(ChainId::root(1)),
                     amount: Amount::from_tokens(995),
+                    source: None,
                 }),
             },
+            action: MessageAction::Accept,
         }],
         &committee,
         Amount::ZERO,
@@ -1586,6 +1865,8 @@ where
             index: 0,
             authenticated_signer: None,
             is_skippable: false,
+            kind: MessageKind::Tracked,
+            grant: Amount::ZERO,
             timestamp,
             message: Message::System(SystemMessage::Credit { amount, .. }),
         } if certificate_hash == CryptoHash::new(&Dummy)
@@ -1776,6 +2057,8 @@ where
             index: 0,
             authenticated_signer: None,
             is_skippable: false,
+            kind: MessageKind::Tracked,
+            grant: Amount::ZERO,
             timestamp,
             message: Message::System(SystemMessage::Credit { amount, .. })
         } if certificate_hash == certificate.hash()
@@ -1880,6 +2163,8 @@ where
             index: 0,
             authenticated_signer: None,
             is_skippable: false,
+            kind: MessageKind::Tracked,
+            grant: Amount::ZERO,
             timestamp,
             message: Message::System(SystemMessage::Credit { amount, .. })
         } if certificate_hash == certificate.hash()
@@ -1892,6 +2177,292 @@ where
     assert_eq!(chain.received_log.count(), 1);
 }
 
+#[test(tokio::test)]
+async fn test_memory_handle_cross_chain_request_to_closed_chain() {
+    let store = MemoryStoreClient::make_test_store(None).await;
+    run_test_handle_cross_chain_request_to_closed_chain(store).await;
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_handle_cross_chain_request_to_closed_chain() {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    let store = RocksDbStore::make_test_store(None).await;
+    run_test_handle_cross_chain_request_to_closed_chain(store).await;
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_handle_cross_chain_request_to_closed_chain() {
+    let store = DynamoDbStore::make_test_store(None).await;
+    run_test_handle_cross_chain_request_to_closed_chain(store).await;
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_handle_cross_chain_request_to_closed_chain() {
+    let store = ScyllaDbStore::make_test_store(None).await;
+    run_test_handle_cross_chain_request_to_closed_chain(store).await;
+}
+
+async fn run_test_handle_cross_chain_request_to_closed_chain<S>(store: S)
+where
+    S: Store + Clone + Send + Sync + 'static,
+    ViewError: From<S::ContextError>,
+{
+    let sender_key_pair = KeyPair::generate();
+    let (committee, mut worker) = init_worker_with_chains(
+        store,
+        vec![(ChainDescription::Root(2), PublicKey::debug(2), Amount::ONE)],
+    )
+    .await;
+    let mut chain = worker
+        .storage
+        .load_active_chain(ChainId::root(2))
+        .await
+        .unwrap();
+    chain.execution_state.system.closed.set(true);
+    chain.save().await.unwrap();
+    drop(chain);
+
+    // The first block only sends an untracked credit, the second one a tracked credit.
+    let certificate = make_transfer_certificate(
+        ChainDescription::Root(1),
+        &sender_key_pair,
+        Recipient::root(2),
+        Amount::ONE,
+        Vec::new(),
+        &committee,
+        Amount::ZERO,
+        &worker,
+        None,
+    )
+    .await;
+    let mut executed_block = certificate.value().executed_block().unwrap().clone();
+    executed_block.messages[0].kind = MessageKind::Simple;
+    let untracked_certificate = make_certificate(
+        &committee,
+        &worker,
+        HashedValue::new_confirmed(executed_block),
+    );
+    let tracked_certificate = make_transfer_certificate(
+        ChainDescription::Root(1),
+        &sender_key_pair,
+        Recipient::root(2),
+        Amount::from_tokens(2),
+        Vec::new(),
+        &committee,
+        Amount::ZERO,
+        &worker,
+        Some(&untracked_certificate),
+    )
+    .await;
+
+    // The untracked credit is dropped, but both blocks are confirmed.
+    let actions = worker
+        .handle_cross_chain_request(CrossChainRequest::UpdateRecipient {
+            height_map: vec![(
+                Medium::Direct,
+                vec![BlockHeight::ZERO, BlockHeight::from(1)],
+            )],
+            sender: ChainId::root(1),
+            recipient: ChainId::root(2),
+            certificates: vec![untracked_certificate, tracked_certificate.clone()],
+        })
+        .await
+        .unwrap();
+    assert!(matches!(
+        &actions.cross_chain_requests[..],
+        [CrossChainRequest::ConfirmUpdatedRecipient { latest_heights, .. }]
+            if latest_heights == &vec![(Medium::Direct, BlockHeight::from(1))]
+    ));
+
+    // Only the tracked credit is in the inbox, to be returned to the sender.
+    let chain = worker
+        .storage
+        .load_active_chain(ChainId::root(2))
+        .await
+        .unwrap();
+    assert_eq!(Amount::ONE, *chain.execution_state.system.balance.get());
+    let inbox = chain
+        .inboxes
+        .try_load_entry(&Origin::chain(ChainId::root(1)))
+        .await
+        .unwrap();
+    let events = inbox.added_events.elements().await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].certificate_hash, tracked_certificate.hash());
+    assert_eq!(events[0].kind, MessageKind::Tracked);
+    assert_eq!(
+        chain.received_log.read(..).await.unwrap(),
+        vec![ChainAndHeight {
+            chain_id: ChainId::root(1),
+            height: BlockHeight::from(1),
+        }]
+    );
+}
+
+#[test(tokio::test)]
+async fn test_memory_handle_certificate_returns_acknowledgements() {
+    let store = MemoryStoreClient::make_test_store(None).await;
+    run_test_handle_certificate_returns_acknowledgements(store).await;
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_handle_certificate_returns_acknowledgements() {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    let store = RocksDbStore::make_test_store(None).await;
+    run_test_handle_certificate_returns_acknowledgements(store).await;
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_handle_certificate_returns_acknowledgements() {
+    let store = DynamoDbStore::make_test_store(None).await;
+    run_test_handle_certificate_returns_acknowledgements(store).await;
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_handle_certificate_returns_acknowledgements() {
+    let store = ScyllaDbStore::make_test_store(None).await;
+    run_test_handle_certificate_returns_acknowledgements(store).await;
+}
+
+async fn run_test_handle_certificate_returns_acknowledgements<S>(store: S)
+where
+    S: Store + Clone + Send + Sync + 'static,
+    ViewError: From<S::ContextError>,
+{
+    let key_pair = KeyPair::generate();
+    let (committee, mut worker) = init_worker_with_chains(
+        store,
+        vec![
+            (ChainDescription::Root(1), PublicKey::debug(1), Amount::ZERO),
+            (ChainDescription::Root(2), key_pair.public(), Amount::ZERO),
+        ],
+    )
+    .await;
+    let credit = Message::System(SystemMessage::Credit {
+        account: Account::chain(ChainId::root(2)),
+        amount: Amount::ONE,
+        source: None,
+    });
+    let acknowledged_message = |index, action| IncomingMessage {
+        origin: Origin::chain(ChainId::root(1)),
+        event: Event {
+            certificate_hash: CryptoHash::new(&Dummy),
+            height: BlockHeight::ZERO,
+            index,
+            authenticated_signer: None,
+            is_skippable: false,
+            kind: MessageKind::Acknowledged,
+            grant: Amount::ZERO,
+            timestamp: Timestamp::from(0),
+            message: credit.clone(),
+        },
+        action,
+    };
+
+    // Chain 2 executes an acknowledged message from chain 1 and rejects another one.
+    let block = make_first_block(ChainId::root(2))
+        .with_incoming_message(acknowledged_message(0, MessageAction::Accept))
+        .with_incoming_message(acknowledged_message(1, MessageAction::Reject));
+    let (executed_block, _) = worker.stage_block_execution(block).await.unwrap();
+    // The acknowledgements are outgoing messages of the block, to chain 1.
+    let acknowledgement = |outcome| OutgoingMessage {
+        destination: Destination::Recipient(ChainId::root(1)),
+        authenticated_signer: None,
+        is_skippable: false,
+        kind: MessageKind::Acknowledgement(outcome),
+        grant: Amount::ZERO,
+        message: credit.clone(),
+    };
+    assert_eq!(
+        executed_block.messages,
+        vec![
+            acknowledgement(MessageOutcome::Executed),
+            acknowledgement(MessageOutcome::Rejected),
+        ]
+    );
+    assert_eq!(executed_block.message_counts, vec![1, 2]);
+    let certificate = make_certificate(
+        &committee,
+        &worker,
+        HashedValue::new_confirmed(executed_block),
+    );
+    let (_, mut actions) = worker
+        .handle_certificate(certificate.clone(), vec![], None)
+        .await
+        .unwrap();
+    let request = CrossChainRequest::UpdateRecipient {
+        height_map: vec![(Medium::Direct, vec![BlockHeight::ZERO])],
+        sender: ChainId::root(2),
+        recipient: ChainId::root(1),
+        certificates: vec![certificate.clone()],
+    };
+    assert_eq!(actions.cross_chain_requests, vec![request]);
+
+    // The acknowledgements are delivered to the inbox of chain 1, like other messages.
+    let mut actions = worker
+        .handle_cross_chain_request(actions.cross_chain_requests.pop().unwrap())
+        .await
+        .unwrap();
+    let origin = Origin::chain(ChainId::root(2));
+    assert_eq!(
+        actions.notifications,
+        vec![Notification {
+            chain_id: ChainId::root(1),
+            reason: NewIncomingMessage {
+                origin: origin.clone(),
+                height: BlockHeight::ZERO,
+            },
+        }]
+    );
+    let chain = worker
+        .storage
+        .load_active_chain(ChainId::root(1))
+        .await
+        .unwrap();
+    let inbox = chain.inboxes.try_load_entry(&origin).await.unwrap();
+    let kinds = inbox
+        .added_events
+        .elements()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|event| event.kind)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            MessageKind::Acknowledgement(MessageOutcome::Executed),
+            MessageKind::Acknowledgement(MessageOutcome::Rejected),
+        ]
+    );
+    drop(inbox);
+    drop(chain);
+
+    // Once chain 1 confirms the delivery, chain 2 doesn't send the acknowledgements anymore.
+    let confirmation = actions.cross_chain_requests.pop().unwrap();
+    assert!(matches!(
+        &confirmation,
+        CrossChainRequest::ConfirmUpdatedRecipient { latest_heights, .. }
+            if latest_heights == &vec![(Medium::Direct, BlockHeight::ZERO)]
+    ));
+    worker
+        .handle_cross_chain_request(confirmation)
+        .await
+        .unwrap();
+    let chain = worker
+        .storage
+        .load_active_chain(ChainId::root(2))
+        .await
+        .unwrap();
+    assert!(chain.outboxes.indices().await.unwrap().is_empty());
+}
+
 #[test(tokio::test)]
 async fn test_memory_handle_cross_chain_request_no_recipient_chain() {
     let store = MemoryStoreClient::make_test_store(None).await;
@@ -2083,20 +2654,20 @@ where
     .await;
     assert_eq!(
         worker
-            .query_application(ChainId::root(1), &Query::System(SystemQuery))
+            .query_application(ChainId::root(1), &Query::System(SystemQuery::Balance))
             .await
             .unwrap(),
-        Response::System(SystemResponse {
+        Response::System(SystemResponse::Balance {
             chain_id: ChainId::root(1),
             balance: Amount::from_tokens(5),
         })
     );
     assert_eq!(
         worker
-            .query_application(ChainId::root(2), &Query::System(SystemQuery))
+            .query_application(ChainId::root(2), &Query::System(SystemQuery::Balance))
             .await
             .unwrap(),
-        Response::System(SystemResponse {
+        Response::System(SystemResponse::Balance {
             chain_id: ChainId::root(2),
             balance: Amount::ZERO,
         })
@@ -2127,10 +2698,10 @@ where
     assert!(info.manager.pending().is_none());
     assert_eq!(
         worker
-            .query_application(ChainId::root(1), &Query::System(SystemQuery))
+            .query_application(ChainId::root(1), &Query::System(SystemQuery::Balance))
             .await
             .unwrap(),
-        Response::System(SystemResponse {
+        Response::System(SystemResponse::Balance {
             chain_id: ChainId::root(1),
             balance: Amount::ZERO,
         })
@@ -2150,12 +2721,16 @@ where
                 index: 0,
                 authenticated_signer: None,
                 is_skippable: false,
+                kind: MessageKind::Tracked,
+                grant: Amount::ZERO,
                 timestamp: Timestamp::from(0),
                 message: Message::System(SystemMessage::Credit {
                     account: Account::chain(ChainId::root(2)),
                     amount: Amount::from_tokens(5),
+                    source: None,
                 }),
             },
+            action: MessageAction::Accept,
         }],
         &committee,
         Amount::from_tokens(4),
@@ -2170,10 +2745,10 @@ where
 
     assert_eq!(
         worker
-            .query_application(ChainId::root(2), &Query::System(SystemQuery))
+            .query_application(ChainId::root(2), &Query::System(SystemQuery::Balance))
             .await
             .unwrap(),
-        Response::System(SystemResponse {
+        Response::System(SystemResponse::Balance {
             chain_id: ChainId::root(2),
             balance: Amount::from_tokens(4),
         })
@@ -2375,6 +2950,8 @@ where
                 ),
             ],
             message_counts: vec![2],
+            events: vec![],
+            failed_operations: vec![],
             state_hash: make_state_hash(SystemExecutionState {
                 committees: committees.clone(),
                 ownership: ChainOwnership::single(key_pair.public()),
@@ -2430,11 +3007,14 @@ where
                     SystemMessage::SetCommittees {
                         epoch: Epoch::from(1),
                         committees: committees2.clone(),
+                        expirations: BTreeMap::new(),
                     },
                 ),
                 direct_credit_message(user_id, Amount::from_tokens(2)),
             ],
             message_counts: vec![1, 2],
+            events: vec![],
+            failed_operations: vec![],
             state_hash: make_state_hash(SystemExecutionState {
                 epoch: Some(Epoch::from(1)),
                 description: Some(ChainDescription::Root(0)),
@@ -2467,18 +3047,23 @@ where
                         index: 1,
                         authenticated_signer: None,
                         is_skippable: false,
+                        kind: MessageKind::Simple,
+                        grant: Amount::ZERO,
                         timestamp: Timestamp::from(0),
                         message: Message::System(SystemMessage::Subscribe {
                             id: user_id,
                             subscription: admin_channel_subscription.clone(),
                         }),
                     },
+                    action: MessageAction::Accept,
                 }),
             messages: vec![direct_outgoing_message(
                 user_id,
                 SystemMessage::Notify { id: user_id },
             )],
             message_counts: vec![1],
+            events: vec![],
+            failed_operations: vec![],
             state_hash: make_state_hash(SystemExecutionState {
                 // The root chain knows both committees at the end.
                 committees: committees2.clone(),
@@ -2587,12 +3172,16 @@ where
                         index: 0,
                         authenticated_signer: None,
                         is_skippable: false,
+                        kind: MessageKind::Simple,
+                        grant: Amount::ZERO,
                         timestamp: Timestamp::from(0),
                         message: Message::System(SystemMessage::SetCommittees {
                             epoch: Epoch::from(1),
                             committees: committees2.clone(),
+                            expirations: BTreeMap::new(),
                         }),
                     },
+                    action: MessageAction::Accept,
                 })
                 .with_incoming_message(IncomingMessage {
                     origin: Origin::chain(admin_id),
@@ -2602,6 +3191,8 @@ where
                         index: 0,
                         authenticated_signer: None,
                         is_skippable: false,
+                        kind: MessageKind::Simple,
+                        grant: Amount::ZERO,
                         timestamp: Timestamp::from(0),
                         message: Message::System(SystemMessage::OpenChain {
                             ownership: ChainOwnership::single(key_pair.public()),
@@ -2610,6 +3201,7 @@ where
                             admin_id,
                         }),
                     },
+                    action: MessageAction::Accept,
                 })
                 .with_incoming_message(IncomingMessage {
                     origin: Origin::chain(admin_id),
@@ -2619,12 +3211,16 @@ where
                         index: 1,
                         authenticated_signer: None,
                         is_skippable: false,
+                        kind: MessageKind::Tracked,
+                        grant: Amount::ZERO,
                         timestamp: Timestamp::from(0),
                         message: Message::System(SystemMessage::Credit {
                             account: Account::chain(user_id),
                             amount: Amount::from_tokens(2),
+                            source: None,
                         }),
                     },
+                    action: MessageAction::Accept,
                 })
                 .with_incoming_message(IncomingMessage {
                     origin: Origin::chain(admin_id),
@@ -2634,12 +3230,17 @@ where
                         index: 0,
                         authenticated_signer: None,
                         is_skippable: false,
+                        kind: MessageKind::Simple,
+                        grant: Amount::ZERO,
                         timestamp: Timestamp::from(0),
                         message: Message::System(SystemMessage::Notify { id: user_id }),
                     },
+                    action: MessageAction::Accept,
                 }),
             messages: Vec::new(),
             message_counts: vec![0, 0, 0, 0],
+            events: vec![],
+            failed_operations: vec![],
             state_hash: make_state_hash(SystemExecutionState {
                 subscriptions: [ChannelSubscription {
                     chain_id: admin_id,
@@ -2773,6 +3374,8 @@ where
                 .with_simple_transfer(Recipient::chain(admin_id), Amount::ONE),
             messages: vec![direct_credit_message(admin_id, Amount::ONE)],
             message_counts: vec![1],
+            events: vec![],
+            failed_operations: vec![],
             state_hash: make_state_hash(SystemExecutionState {
                 committees: committees.clone(),
                 ownership: ChainOwnership::single(key_pair1.public()),
@@ -2802,9 +3405,12 @@ where
                 SystemMessage::SetCommittees {
                     epoch: Epoch::from(1),
                     committees: committees2.clone(),
+                    expirations: BTreeMap::new(),
                 },
             )],
             message_counts: vec![1],
+            events: vec![],
+            failed_operations: vec![],
             state_hash: make_state_hash(SystemExecutionState {
                 committees: committees2.clone(),
                 ownership: ChainOwnership::single(key_pair0.public()),
@@ -2920,6 +3526,8 @@ where
                 .with_simple_transfer(Recipient::chain(admin_id), Amount::ONE),
             messages: vec![direct_credit_message(admin_id, Amount::ONE)],
             message_counts: vec![1],
+            events: vec![],
+            failed_operations: vec![],
             state_hash: make_state_hash(SystemExecutionState {
                 committees: committees.clone(),
                 ownership: ChainOwnership::single(key_pair1.public()),
@@ -2953,6 +3561,7 @@ where
                     SystemMessage::SetCommittees {
                         epoch: Epoch::from(1),
                         committees: committees2.clone(),
+                        expirations: BTreeMap::new(),
                     },
                 ),
                 channel_outgoing_message(
@@ -2960,10 +3569,13 @@ where
                     SystemMessage::SetCommittees {
                         epoch: Epoch::from(1),
                         committees: committees3.clone(),
+                        expirations: BTreeMap::new(),
                     },
                 ),
             ],
             message_counts: vec![1, 2],
+            events: vec![],
+            failed_operations: vec![],
             state_hash: make_state_hash(SystemExecutionState {
                 committees: committees3.clone(),
                 ownership: ChainOwnership::single(key_pair0.public()),
@@ -3019,15 +3631,21 @@ where
                         index: 0,
                         authenticated_signer: None,
                         is_skippable: false,
+                        kind: MessageKind::Tracked,
+                        grant: Amount::ZERO,
                         timestamp: Timestamp::from(0),
                         message: Message::System(SystemMessage::Credit {
                             account: Account::chain(admin_id),
                             amount: Amount::ONE,
+                            source: None,
                         }),
                     },
+                    action: MessageAction::Accept,
                 }),
             messages: Vec::new(),
             message_counts: vec![0],
+            events: vec![],
+            failed_operations: vec![],
             state_hash: make_state_hash(SystemExecutionState {
                 committees: committees3.clone(),
                 ownership: ChainOwnership::single(key_pair0.public()),
@@ -3065,6 +3683,123 @@ where
     }
 }
 
+#[test(tokio::test)]
+async fn test_memory_expired_committees_are_not_trusted() {
+    let store = MemoryStoreClient::make_test_store(None).await;
+    run_test_expired_committees_are_not_trusted(store).await;
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_expired_committees_are_not_trusted() {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    let store = RocksDbStore::make_test_store(None).await;
+    run_test_expired_committees_are_not_trusted(store).await;
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_expired_committees_are_not_trusted() {
+    let store = DynamoDbStore::make_test_store(None).await;
+    run_test_expired_committees_are_not_trusted(store).await;
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_expired_committees_are_not_trusted() {
+    let store = ScyllaDbStore::make_test_store(None).await;
+    run_test_expired_committees_are_not_trusted(store).await;
+}
+
+async fn run_test_expired_committees_are_not_trusted<C>(store: DbStore<C, TestClock>)
+where
+    C: KeyValueStoreClient + Clone + Send + Sync + 'static,
+    ViewError: From<<C as KeyValueStoreClient>::Error>,
+    <C as KeyValueStoreClient>::Error:
+        From<bcs::Error> + From<DatabaseConsistencyError> + Send + Sync + serde::ser::StdError,
+{
+    let sender_key_pair = KeyPair::generate();
+    let sender = ChainId::root(0);
+    let recipient = ChainId::root(1);
+    let clock = store.clock.clone();
+    let (committee, mut worker) = init_worker_with_chain(
+        store,
+        ChainDescription::Root(1),
+        KeyPair::generate().public(),
+        Amount::ZERO,
+    )
+    .await;
+    // The recipient moved to the epoch 1, and trusts the epoch 0 until the time 1000.
+    let expiration = Timestamp::from(1_000);
+    let mut chain = worker.storage.load_active_chain(recipient).await.unwrap();
+    let system = &mut chain.execution_state.system;
+    system.epoch.set(Some(Epoch::from(1)));
+    system
+        .committees
+        .get_mut()
+        .insert(Epoch::from(1), committee.clone());
+    system
+        .committee_expirations
+        .set(BTreeMap::from([(Epoch::ZERO, expiration)]));
+    chain.save().await.unwrap();
+    drop(chain);
+    let certificate = make_transfer_certificate_for_epoch(
+        ChainDescription::Root(0),
+        &sender_key_pair,
+        Recipient::chain(recipient),
+        Amount::ONE,
+        Vec::new(),
+        Epoch::ZERO,
+        &committee,
+        Amount::ONE,
+        &worker,
+        None,
+    )
+    .await;
+    let request = CrossChainRequest::UpdateRecipient {
+        height_map: vec![(Medium::Direct, vec![BlockHeight::ZERO])],
+        sender,
+        recipient,
+        certificates: vec![certificate],
+    };
+    let query = ChainInfoQuery::new(recipient).with_committees();
+
+    // The chain hasn't removed the expired committee, but the worker doesn't trust it
+    // anymore: neither its certificates nor clients should rely on it.
+    clock.set(expiration);
+    let (response, _) = worker.handle_chain_info_query(query.clone()).await.unwrap();
+    let committees = response.info.requested_committees.unwrap();
+    assert_eq!(
+        committees.into_keys().collect::<Vec<_>>(),
+        vec![Epoch::from(1)]
+    );
+    worker
+        .handle_cross_chain_request(request.clone())
+        .await
+        .unwrap();
+    let chain = worker.storage.load_active_chain(recipient).await.unwrap();
+    assert!(chain.inboxes.indices().await.unwrap().is_empty());
+    drop(chain);
+
+    // Within the grace period, the committee is still trusted.
+    clock.set(expiration.saturating_sub_micros(1));
+    let (response, _) = worker.handle_chain_info_query(query).await.unwrap();
+    let committees = response.info.requested_committees.unwrap();
+    assert_eq!(
+        committees.into_keys().collect::<Vec<_>>(),
+        vec![Epoch::ZERO, Epoch::from(1)]
+    );
+    worker.handle_cross_chain_request(request).await.unwrap();
+    let mut chain = worker.storage.load_active_chain(recipient).await.unwrap();
+    assert_eq!(
+        chain
+            .next_block_height_to_receive(&Origin::chain(sender))
+            .await
+            .unwrap(),
+        BlockHeight::from(1)
+    );
+}
+
 #[test(tokio::test)]
 async fn test_cross_chain_helper() {
     // Make a committee and worker (only used for signing certificates)
@@ -3330,6 +4065,8 @@ where
     let block0 = make_first_block(chain_id).with_operation(SystemOperation::ChangeMultipleOwners {
         new_public_keys: vec![(pub_key0, 100), (pub_key1, 100)],
         multi_leader_rounds: RoundNumber::from(0),
+        approval_threshold: None,
+        timeout_config: TimeoutConfig::default(),
     });
     let (executed_block0, _) = worker.stage_block_execution(block0).await.unwrap();
     let value0 = HashedValue::new_confirmed(executed_block0);
@@ -3510,3 +4247,137 @@ where
     let manager = multi_manager(&response.info.manager);
     assert_eq!(manager.requested_locked, Some(certificate));
 }
+
+#[test(tokio::test)]
+async fn test_memory_approval_threshold() {
+    let store = MemoryStoreClient::make_test_store(None).await;
+    run_test_approval_threshold(store).await;
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_approval_threshold() {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    let store = RocksDbStore::make_test_store(None).await;
+    run_test_approval_threshold(store).await;
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_approval_threshold() {
+    let store = DynamoDbStore::make_test_store(None).await;
+    run_test_approval_threshold(store).await;
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_approval_threshold() {
+    let store = ScyllaDbStore::make_test_store(None).await;
+    run_test_approval_threshold(store).await;
+}
+
+async fn run_test_approval_threshold<C>(store: DbStore<C, TestClock>)
+where
+    C: KeyValueStoreClient + Clone + Send + Sync + 'static,
+    ViewError: From<<C as KeyValueStoreClient>::Error>,
+    <C as KeyValueStoreClient>::Error:
+        From<bcs::Error> + From<DatabaseConsistencyError> + Send + Sync + serde::ser::StdError,
+{
+    let chain_id = ChainId::root(0);
+    let key_pairs = generate_key_pairs(3);
+    let balances = vec![(
+        ChainDescription::Root(0),
+        key_pairs[0].public(),
+        Amount::from_tokens(2),
+    )];
+    let (committee, mut worker) = init_worker_with_chains(store, balances).await;
+
+    // Add two more owners, and require two of the three to approve transfers.
+    let block0 = make_first_block(chain_id).with_operation(SystemOperation::ChangeMultipleOwners {
+        new_public_keys: key_pairs.iter().map(|kp| (kp.public(), 100)).collect(),
+        multi_leader_rounds: RoundNumber::MAX,
+        approval_threshold: Some(ApprovalThreshold {
+            weight: 200,
+            scope: ApprovalScope::TransfersAndOwnership,
+        }),
+        timeout_config: TimeoutConfig::default(),
+    });
+    let (executed_block0, _) = worker.stage_block_execution(block0).await.unwrap();
+    let value0 = HashedValue::new_confirmed(executed_block0);
+    let certificate0 = make_certificate(&committee, &worker, value0.clone());
+    worker
+        .fully_handle_certificate(certificate0, vec![])
+        .await
+        .unwrap();
+
+    // Blocks without transfers don't need to be approved.
+    let block1 = make_child_block(&value0);
+    let proposal = block1.clone().into_simple_proposal(&key_pairs[0]);
+    worker.handle_block_proposal(proposal).await.unwrap();
+
+    // A transfer proposed by a single owner is rejected.
+    let block2 =
+        make_child_block(&value0).with_simple_transfer(Recipient::root(1), Amount::from_tokens(1));
+    let proposal = block2
+        .clone()
+        .into_proposal_with_round(&key_pairs[0], RoundNumber(1));
+    let result = worker.handle_block_proposal(proposal).await;
+    assert!(matches!(result, Err(WorkerError::ChainError(error))
+        if matches!(*error, ChainError::InsufficientApprovals { weight: 100, threshold: 200 })
+    ));
+
+    // Owners approve a block in a specific round.
+    let approve = |key_pair: &KeyPair, block: &Block, round: RoundNumber| {
+        let content = BlockAndRound {
+            block: block.clone(),
+            round,
+        };
+        (
+            Owner::from(key_pair.public()),
+            Signature::new(&content, key_pair),
+        )
+    };
+
+    // The proposer's own approval doesn't count twice.
+    let proposal = block2
+        .clone()
+        .into_proposal_with_round(&key_pairs[0], RoundNumber(1))
+        .with_approvals(vec![approve(&key_pairs[0], &block2, RoundNumber(1))]);
+    let result = worker.handle_block_proposal(proposal).await;
+    assert!(matches!(result, Err(WorkerError::ChainError(error))
+        if matches!(*error, ChainError::ApprovalOwnerReuse)
+    ));
+
+    // Approvals must be signatures of the proposed block.
+    let proposal = block2
+        .clone()
+        .into_proposal_with_round(&key_pairs[0], RoundNumber(1))
+        .with_approvals(vec![approve(&key_pairs[1], &block1, RoundNumber(1))]);
+    let result = worker.handle_block_proposal(proposal).await;
+    assert!(matches!(result, Err(WorkerError::ChainError(error))
+        if matches!(*error, ChainError::CryptoError(_))
+    ));
+
+    // Approvals from another round cannot be replayed.
+    let proposal = block2
+        .clone()
+        .into_proposal_with_round(&key_pairs[0], RoundNumber(1))
+        .with_approvals(vec![approve(&key_pairs[1], &block2, RoundNumber(0))]);
+    let result = worker.handle_block_proposal(proposal).await;
+    assert!(matches!(result, Err(WorkerError::ChainError(error))
+        if matches!(*error, ChainError::CryptoError(_))
+    ));
+
+    // With another owner's approval, the transfer is accepted.
+    let proposal = block2
+        .clone()
+        .into_proposal_with_round(&key_pairs[0], RoundNumber(1))
+        .with_approvals(vec![approve(&key_pairs[1], &block2, RoundNumber(1))]);
+    let (response, _) = worker.handle_block_proposal(proposal).await.unwrap();
+    let manager = multi_manager(&response.info.manager);
+    let (executed_block2, _) = worker.stage_block_execution(block2).await.unwrap();
+    assert_eq!(
+        manager.pending.as_ref().unwrap().value,
+        HashedValue::new_validated(executed_block2).lite()
+    );
+}
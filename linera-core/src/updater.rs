@@ -40,6 +40,11 @@ const MAX_TIMEOUT: Duration = Duration::from_secs(60 * 60 * 24); // 1 day.
 pub enum CommunicateAction {
     SubmitBlockForConfirmation(BlockProposal),
     SubmitBlockForValidation(BlockProposal),
+    SubmitPipelinedBlock {
+        previous: Certificate,
+        proposal: BlockProposal,
+    },
+    SendPipelinedProposal(BlockProposal),
     FinalizeBlock(Certificate),
     AdvanceToNextBlockHeight(BlockHeight),
     RequestLeaderTimeout {
@@ -436,9 +441,9 @@ where
     ) -> Result<Option<LiteVote>, NodeError> {
         let target_block_height = match &action {
             CommunicateAction::SubmitBlockForValidation(proposal)
-            | CommunicateAction::SubmitBlockForConfirmation(proposal) => {
-                proposal.content.block.height
-            }
+            | CommunicateAction::SubmitBlockForConfirmation(proposal)
+            | CommunicateAction::SendPipelinedProposal(proposal) => proposal.content.block.height,
+            CommunicateAction::SubmitPipelinedBlock { previous, .. } => previous.value().height(),
             CommunicateAction::FinalizeBlock(certificate) => certificate.value().height(),
             CommunicateAction::AdvanceToNextBlockHeight(height) => *height,
             CommunicateAction::RequestLeaderTimeout { height, .. } => *height,
@@ -461,6 +466,32 @@ where
                     }
                 }
             }
+            CommunicateAction::SubmitPipelinedBlock { previous, proposal } => {
+                // If the validator already has the proposal, it votes for it as soon as it
+                // confirms the previous block. Otherwise, we send the proposal again.
+                let info = self.send_certificate(previous, false).await?;
+                let info = if info.next_block_height == proposal.content.block.height
+                    && info.manager.pending().is_some()
+                {
+                    info
+                } else {
+                    self.send_block_proposal(proposal).await?
+                };
+                match info.manager.pending() {
+                    Some(vote) if vote.validator == self.name => {
+                        vote.check()?;
+                        return Ok(Some(vote.clone()));
+                    }
+                    Some(_) | None => {
+                        return Err(NodeError::MissingVoteInValidatorResponse);
+                    }
+                }
+            }
+            CommunicateAction::SendPipelinedProposal(proposal) => {
+                // The validator keeps the proposal until it confirms the previous block.
+                let response = self.node.handle_block_proposal(proposal).await?;
+                response.check(self.name)?;
+            }
             CommunicateAction::FinalizeBlock(certificate) => {
                 // The only cause for a retry here is the first certificate of a newly opened chain.
                 let retryable = target_block_height == BlockHeight::ZERO;
@@ -7,16 +7,17 @@ use async_trait::async_trait;
 use futures::{future, FutureExt};
 use linera_base::{
     crypto::{CryptoHash, KeyPair},
-    data_types::{ArithmeticError, BlockHeight, RoundNumber},
+    data_types::{ArithmeticError, BlockHeight, RoundNumber, Timestamp},
     doc_scalar, ensure,
-    identifiers::{ChainId, Owner},
+    identifiers::{ChainId, Destination, Owner},
 };
 use linera_chain::{
     data_types::{
-        Block, BlockAndRound, BlockProposal, Certificate, CertificateValue, ExecutedBlock,
-        HashedValue, IncomingMessage, LiteCertificate, Medium, Origin, Target,
+        Block, BlockAndRound, BlockProposal, Certificate, CertificateValue, CertifiedCheckpoint,
+        CheckpointChunk, CheckpointVote, ExecutedBlock, HashedValue, IncomingMessage,
+        LiteCertificate, Medium, MessageAction, Origin, Target,
     },
-    ChainManagerOutcome, ChainStateView,
+    ChainManager, ChainManagerOutcome, ChainStateView,
 };
 use linera_execution::{
     committee::{Committee, Epoch},
@@ -44,8 +45,7 @@ use tracing::{debug, error, instrument, trace, warn};
 
 #[cfg(any(test, feature = "test"))]
 use {
-    linera_base::identifiers::{Destination, MessageId},
-    linera_chain::data_types::ChannelFullName,
+    linera_base::identifiers::MessageId, linera_chain::data_types::ChannelFullName,
     linera_execution::ApplicationRegistryView,
 };
 
@@ -210,6 +210,10 @@ pub enum WorkerError {
     IncorrectMessages,
     #[error("The given message counts are not what we computed after executing the block")]
     IncorrectMessageCounts,
+    #[error("The given events are not what we computed after executing the block")]
+    IncorrectEvents,
+    #[error("The given failed operations are not what we computed after executing the block")]
+    IncorrectFailedOperations,
     #[error("The timestamp of a Tick operation is in the future.")]
     InvalidTimestamp,
     #[error("We don't have the value for the certificate.")]
@@ -220,6 +224,8 @@ pub enum WorkerError {
     UnneededValue { value_hash: CryptoHash },
     #[error("The following values containing application bytecode are missing: {0:?}.")]
     ApplicationBytecodesNotFound(Vec<BytecodeLocation>),
+    #[error("Pipelined blocks cannot have independent operations")]
+    PipelinedIndependentOperations,
 }
 
 impl From<linera_chain::ChainError> for WorkerError {
@@ -230,6 +236,9 @@ impl From<linera_chain::ChainError> for WorkerError {
 
 pub(crate) const DEFAULT_VALUE_CACHE_SIZE: usize = 1000;
 
+/// The approximate size in bytes of each chunk of state committed to by a checkpoint.
+pub(crate) const CHECKPOINT_CHUNK_SIZE: usize = 1_000_000;
+
 /// State of a worker in a validator or a local node.
 #[derive(Clone)]
 pub struct WorkerState<StorageClient> {
@@ -252,11 +261,19 @@ pub struct WorkerState<StorageClient> {
     /// One-shot channels to notify callers when messages of a particular chain have been
     /// delivered.
     delivery_notifiers: Arc<Mutex<DeliveryNotifiers>>,
+    /// How many blocks ahead of the next block height of a single-owner chain a proposal can be.
+    proposal_pipeline_depth: u64,
+    /// Proposals waiting for the previous blocks of their chains to be confirmed.
+    pipelined_proposals: Arc<Mutex<PipelinedProposals>>,
+    /// Every how many blocks a checkpoint of the chain state is signed, or 0 to never sign any.
+    checkpoint_interval: u64,
 }
 
 pub(crate) type DeliveryNotifiers =
     HashMap<ChainId, BTreeMap<BlockHeight, Vec<oneshot::Sender<()>>>>;
 
+type PipelinedProposals = HashMap<ChainId, BTreeMap<BlockHeight, BlockProposal>>;
+
 impl<StorageClient> WorkerState<StorageClient> {
     pub fn new(nickname: String, key_pair: Option<KeyPair>, storage: StorageClient) -> Self {
         let recent_values = Arc::new(Mutex::new(LruCache::new(
@@ -271,6 +288,9 @@ impl<StorageClient> WorkerState<StorageClient> {
             grace_period_micros: 0,
             recent_values,
             delivery_notifiers: Arc::default(),
+            proposal_pipeline_depth: 0,
+            pipelined_proposals: Arc::default(),
+            checkpoint_interval: 0,
         }
     }
 
@@ -289,6 +309,9 @@ impl<StorageClient> WorkerState<StorageClient> {
             grace_period_micros: 0,
             recent_values,
             delivery_notifiers,
+            proposal_pipeline_depth: 0,
+            pipelined_proposals: Arc::default(),
+            checkpoint_interval: 0,
         }
     }
 
@@ -311,6 +334,22 @@ impl<StorageClient> WorkerState<StorageClient> {
         self
     }
 
+    /// Returns an instance that accepts proposals for up to `depth` blocks ahead of the next
+    /// block height of a single-owner chain. Such proposals are kept until the previous block is
+    /// confirmed, and are voted for right after it.
+    pub fn with_proposal_pipeline_depth(mut self, depth: u64) -> Self {
+        self.proposal_pipeline_depth = depth;
+        self
+    }
+
+    /// Returns an instance that signs a checkpoint of the chain state every `interval` blocks,
+    /// so that new nodes can start from there instead of executing all the blocks. Only the
+    /// latest checkpoint of each chain is kept.
+    pub fn with_checkpoint_interval(mut self, interval: u64) -> Self {
+        self.checkpoint_interval = interval;
+        self
+    }
+
     pub fn nickname(&self) -> &str {
         &self.nickname
     }
@@ -404,6 +443,38 @@ where
         Ok((executed_block, response))
     }
 
+    /// Tries to execute consecutive blocks of a chain, each one after the previous ones, without
+    /// any verification other than block execution.
+    ///
+    /// The previous block hash of every block but the first is set to the hash the previous
+    /// block will have once it is confirmed. Fails if any block has independent operations.
+    pub async fn stage_pipelined_block_execution(
+        &mut self,
+        blocks: Vec<Block>,
+    ) -> Result<Vec<ExecutedBlock>, WorkerError> {
+        let Some(first_block) = blocks.first() else {
+            return Ok(Vec::new());
+        };
+        ensure!(
+            blocks.iter().all(|block| !block.independent_operations),
+            WorkerError::PipelinedIndependentOperations
+        );
+        let mut chain = self.storage.load_active_chain(first_block.chain_id).await?;
+        let now = self.storage.current_time();
+        let mut executed_blocks = Vec::<ExecutedBlock>::new();
+        for mut block in blocks {
+            if let Some(previous_block) = executed_blocks.last() {
+                let value = HashedValue::new_confirmed(previous_block.clone());
+                block.previous_block_hash = Some(value.hash());
+                block.previous_block_signatures = None;
+            }
+            let executed_block = chain.execute_block(&block, now).await?.with(block);
+            executed_blocks.push(executed_block);
+        }
+        // Do not save the new state.
+        Ok(executed_blocks)
+    }
+
     // Schedule a notification when cross-chain messages are delivered up to the given height.
     async fn register_delivery_notifier(
         &mut self,
@@ -486,6 +557,54 @@ where
         })
     }
 
+    /// Handles the proposal for the next block of the chain described by `info`, if it was
+    /// proposed before the previous block was confirmed. Returns the updated chain
+    /// information, including the vote, or `info` if there is no such proposal or it fails.
+    ///
+    /// Proposals with a timestamp in the future are kept for later instead, so that handling
+    /// a certificate never waits for the clock: the owner resends them as regular proposals.
+    async fn process_pipelined_proposal(&mut self, info: ChainInfoResponse) -> ChainInfoResponse {
+        let chain_id = info.info.chain_id;
+        let next_block_height = info.info.next_block_height;
+        let mut pipelined_proposals = self.pipelined_proposals.lock().await;
+        let Some(proposals) = pipelined_proposals.get_mut(&chain_id) else {
+            return info;
+        };
+        let Some(proposal) = proposals.get(&next_block_height) else {
+            return info;
+        };
+        if proposal.content.block.timestamp > self.storage.current_time() {
+            return info;
+        }
+        let mut later_proposals = proposals.split_off(&next_block_height);
+        pipelined_proposals.remove(&chain_id);
+        drop(pipelined_proposals);
+        let Some(proposal) = later_proposals.remove(&next_block_height) else {
+            return info;
+        };
+        match self.handle_block_proposal(proposal).await {
+            Ok((info, _)) => {
+                // Handling the proposal discarded the later ones.
+                if !later_proposals.is_empty() {
+                    let mut pipelined_proposals = self.pipelined_proposals.lock().await;
+                    let proposals = pipelined_proposals.entry(chain_id).or_default();
+                    for (height, proposal) in later_proposals {
+                        proposals.entry(height).or_insert(proposal);
+                    }
+                }
+                info
+            }
+            Err(error) => {
+                warn!(
+                    "[{}] Failed to process pipelined proposal for {chain_id:.8} at height \
+                     {next_block_height}: {error}",
+                    self.nickname
+                );
+                info
+            }
+        }
+    }
+
     /// Loads pending cross-chain requests.
     async fn create_network_actions(
         &self,
@@ -531,6 +650,8 @@ where
             block,
             messages,
             message_counts,
+            events,
+            failed_operations,
             state_hash,
         } = executed_block;
         let mut chain = self.storage.load_active_chain(block.chain_id).await?;
@@ -598,6 +719,14 @@ where
             *message_counts == verified_outcome.message_counts,
             WorkerError::IncorrectMessageCounts
         );
+        ensure!(
+            *events == verified_outcome.events,
+            WorkerError::IncorrectEvents
+        );
+        ensure!(
+            *failed_operations == verified_outcome.failed_operations,
+            WorkerError::IncorrectFailedOperations
+        );
         ensure!(
             *state_hash == verified_outcome.state_hash,
             WorkerError::IncorrectStateHash
@@ -618,6 +747,10 @@ where
         });
         // Persist chain.
         chain.save().await?;
+        let next_block_height = chain.tip_state.get().next_block_height;
+        if self.checkpoint_interval > 0 && next_block_height.0 % self.checkpoint_interval == 0 {
+            self.spawn_checkpoint(chain);
+        }
         // Notify the caller when cross-chain messages are delivered.
         self.register_delivery_notifier(
             block.chain_id,
@@ -630,6 +763,57 @@ where
         Ok((info, actions))
     }
 
+    /// Signs a checkpoint of the chain state in the background, and stores it together with
+    /// the chunks of state it commits to. The chain remains locked until then, so that the
+    /// checkpoint is taken at its current height.
+    fn spawn_checkpoint(&self, chain: ChainStateView<StorageClient::Context>) {
+        let Some(key_pair) = self.key_pair.clone() else {
+            return;
+        };
+        let storage = self.storage.clone();
+        let nickname = self.nickname.clone();
+        tokio::spawn(async move {
+            if let Err(error) = Self::create_checkpoint(&storage, &key_pair, &chain).await {
+                warn!(
+                    "[{nickname}] Failed to create a checkpoint for {:.8}: {error}",
+                    chain.chain_id()
+                );
+            }
+        });
+    }
+
+    async fn create_checkpoint(
+        storage: &StorageClient,
+        key_pair: &KeyPair,
+        chain: &ChainStateView<StorageClient::Context>,
+    ) -> Result<(), WorkerError> {
+        let (checkpoint, chunks) = chain.checkpoint(CHECKPOINT_CHUNK_SIZE).await?;
+        let chunk_hashes = chunks.iter().map(CryptoHash::new).collect();
+        let vote = CheckpointVote::new(checkpoint, chunk_hashes, key_pair);
+        storage.write_checkpoint(&vote, &chunks).await?;
+        Ok(())
+    }
+
+    /// Restores the state of a chain from a checkpoint certified by the given committee,
+    /// unless the chain is already at a later height. The blocks after the checkpoint must
+    /// then be processed as usual. Nothing is written if the chunks don't match the
+    /// checkpoint.
+    pub async fn restore_checkpoint(
+        &self,
+        certified: &CertifiedCheckpoint,
+        chunks: Vec<CheckpointChunk>,
+        committee: &Committee,
+    ) -> Result<ChainInfoResponse, WorkerError> {
+        let checkpoint = &certified.checkpoint;
+        certified.check(committee)?;
+        let mut chain = self.storage.load_chain(checkpoint.chain_id).await?;
+        if chain.tip_state.get().next_block_height < checkpoint.next_block_height {
+            let now = self.storage.current_time();
+            chain.restore_checkpoint(checkpoint, chunks, now).await?;
+        }
+        Ok(ChainInfoResponse::new(&chain, self.key_pair()))
+    }
+
     /// Returns an error if the block requires bytecode we don't have, or if unrelated bytecode
     /// blobs were provided.
     async fn check_no_missing_bytecode(
@@ -805,11 +989,16 @@ where
         // Only process certificates with relevant heights and epochs.
         let next_height_to_receive = chain.next_block_height_to_receive(origin).await?;
         let last_anticipated_block_height = chain.last_anticipated_block_height(origin).await?;
+        // The chain may not have removed the committees whose grace period is over yet.
+        let committees = chain
+            .execution_state
+            .system
+            .trusted_committees(self.storage.current_time());
         let helper = CrossChainUpdateHelper {
             nickname: &self.nickname,
             allow_messages_from_deprecated_epochs: self.allow_messages_from_deprecated_epochs,
             current_epoch: *chain.execution_state.system.epoch.get(),
-            committees: chain.execution_state.system.committees.get(),
+            committees: &committees,
         };
         let certificates = helper.select_certificates(
             origin,
@@ -822,6 +1011,32 @@ where
         else {
             return Ok(None);
         };
+        let certificates = if *chain.execution_state.system.closed.get() {
+            // Closed chains only receive the messages that they must return to their
+            // senders, and the committee changes that they need to produce blocks. The other
+            // blocks are dropped, but the update is confirmed anyway so that the sender
+            // doesn't keep retrying.
+            let admin_id = *chain.execution_state.system.admin_id.get();
+            let (certificates, dropped): (Vec<_>, Vec<_>) =
+                certificates.into_iter().partition(|certificate| {
+                    admin_id == Some(origin.sender)
+                        || has_tracked_messages(certificate, origin, recipient)
+                });
+            if !dropped.is_empty() {
+                warn!(
+                    "[{}] Dropping {} block(s) of messages to closed chain {recipient:?} \
+                    from {origin:?} up to height {last_updated_height}",
+                    self.nickname,
+                    dropped.len()
+                );
+            }
+            if certificates.is_empty() {
+                return Ok(Some(last_updated_height));
+            }
+            certificates
+        } else {
+            certificates
+        };
         // Process the received messages in certificates.
         self.storage.write_certificates(&certificates).await?;
         for certificate in certificates {
@@ -946,6 +1161,9 @@ where
         let origin = Origin { sender, medium };
 
         let mut chain = self.storage.load_active_chain(chain_id).await?;
+        chain
+            .accept_or_refuse_events(&origin, Timestamp::from(u64::MAX))
+            .await?;
         let mut inbox = chain.inboxes.try_load_entry_mut(&origin).await?;
 
         let certificate_hash = certificate.hash();
@@ -965,7 +1183,11 @@ where
 
         assert_eq!(event.message, outgoing_message.message);
 
-        Ok(Some(IncomingMessage { origin, event }))
+        Ok(Some(IncomingMessage {
+            origin,
+            event,
+            action: MessageAction::Accept,
+        }))
     }
 }
 
@@ -991,6 +1213,7 @@ where
             signature,
             blobs,
             validated,
+            approvals: _,
         } = &proposal;
         let chain_id = block.chain_id;
         let mut chain = self.storage.load_active_chain(chain_id).await?;
@@ -1018,9 +1241,28 @@ where
         if let Some(signer) = block.authenticated_signer {
             ensure!(signer == *owner, WorkerError::InvalidSigner(signer));
         }
+        // Keep proposals for later blocks of single-owner chains until the chain catches up.
+        let next_block_height = chain.tip_state.get().next_block_height;
+        if block.height > next_block_height
+            && block.height.0 - next_block_height.0 <= self.proposal_pipeline_depth
+            && matches!(chain.manager.get(), ChainManager::Single(_))
+            && !block.independent_operations
+        {
+            let mut pipelined_proposals = self.pipelined_proposals.lock().await;
+            let proposals = pipelined_proposals.entry(chain_id).or_default();
+            proposals.retain(|height, _| *height > next_block_height);
+            proposals.insert(block.height, proposal.clone());
+            return Ok((
+                ChainInfoResponse::new(&chain, self.key_pair()),
+                NetworkActions::default(),
+            ));
+        }
         // Check if the chain is ready for this new block proposal.
         // This should always pass for nodes without voting key.
         chain.tip_state.get().verify_block_chaining(block)?;
+        // A proposal for the next block starts over: the owner may propose different blocks
+        // after it.
+        self.pipelined_proposals.lock().await.remove(&chain_id);
         if chain.manager.get().check_proposed_block(&proposal)? == ChainManagerOutcome::Skip {
             // If we just processed the same pending block, return the chain info unchanged.
             return Ok((
@@ -1117,12 +1359,15 @@ where
                     + executed_block.block.operations.len())
                     as u64;
                 // Execute the confirmed block.
-                self.process_confirmed_block(
-                    certificate,
-                    &blobs,
-                    notify_when_messages_are_delivered,
-                )
-                .await?
+                let (info, actions) = self
+                    .process_confirmed_block(
+                        certificate,
+                        &blobs,
+                        notify_when_messages_are_delivered,
+                    )
+                    .await?;
+                // Vote for the next block if it was already proposed.
+                (self.process_pipelined_proposal(info).await, actions)
             }
             CertificateValue::LeaderTimeout { .. } => {
                 // Handle the leader timeout.
@@ -1168,7 +1413,11 @@ where
         }
         let mut info = ChainInfo::from(&chain);
         if query.request_committees {
-            info.requested_committees = Some(chain.execution_state.system.committees.get().clone());
+            let committees = chain
+                .execution_state
+                .system
+                .trusted_committees(self.storage.current_time());
+            info.requested_committees = Some(committees);
         }
         if let Some(next_block_height) = query.test_next_block_height {
             ensure!(
@@ -1180,6 +1429,11 @@ where
             );
         }
         if query.request_pending_messages {
+            // A block with a later timestamp than all the received messages would be able to
+            // execute the ones that are accepted. The chain is not saved afterwards.
+            chain
+                .accept_or_refuse_all_events(Timestamp::from(u64::MAX))
+                .await?;
             let mut messages = Vec::new();
             let origins = chain.inboxes.indices().await?;
             let inboxes = chain.inboxes.try_load_entries(&origins).await?;
@@ -1188,6 +1442,7 @@ where
                     messages.push(IncomingMessage {
                         origin: origin.clone(),
                         event: event.clone(),
+                        action: MessageAction::Accept,
                     });
                 }
             }
@@ -1214,6 +1469,16 @@ where
         if let Some(hash) = query.request_blob {
             info.requested_blob = Some(self.storage.read_value(hash).await?);
         }
+        if query.request_checkpoint {
+            info.requested_checkpoint = self.storage.read_checkpoint(query.chain_id).await?;
+        }
+        if let Some(hash) = query.request_checkpoint_chunk {
+            info.requested_checkpoint_chunk = Some(
+                self.storage
+                    .read_checkpoint_chunk(query.chain_id, hash)
+                    .await?,
+            );
+        }
         if query.request_manager_values {
             info.manager.add_values(chain.manager.get());
         }
@@ -1405,3 +1670,16 @@ impl<'a> CrossChainUpdateHelper<'a> {
         Ok(certificates)
     }
 }
+
+/// Returns whether the confirmed block of the certificate sends tracked messages to the
+/// recipient, through the given origin, i.e. messages that must be returned to the sender if
+/// the recipient rejects them.
+fn has_tracked_messages(certificate: &Certificate, origin: &Origin, recipient: ChainId) -> bool {
+    let Some(executed_block) = certificate.value().executed_block() else {
+        return false;
+    };
+    origin.medium == Medium::Direct
+        && executed_block.messages.iter().any(|message| {
+            message.destination == Destination::Recipient(recipient) && message.kind.is_tracked()
+        })
+}
@@ -11,7 +11,7 @@ use linera_base::{
 use linera_views::{
     common::Context,
     map_view::MapView,
-    views::{HashableView, ViewError},
+    views::{ClonableView, HashableView, ViewError},
 };
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -68,6 +68,9 @@ pub struct UserApplicationDescription {
     pub parameters: Vec<u8>,
     /// Required dependencies.
     pub required_application_ids: Vec<UserApplicationId>,
+    /// The maximum total size of the keys and values the application may store on each chain,
+    /// if limited.
+    pub storage_quota: Option<u64>,
 }
 
 impl From<&UserApplicationDescription> for UserApplicationId {
@@ -94,7 +97,7 @@ pub struct BytecodeLocation {
     pub operation_index: u32,
 }
 
-#[derive(Debug, HashableView)]
+#[derive(Debug, ClonableView, HashableView)]
 pub struct ApplicationRegistryView<C> {
     /// The application bytecodes that have been published.
     pub published_bytecodes: MapView<C, BytecodeId, BytecodeLocation>,
@@ -181,6 +184,7 @@ where
         application_id: UserApplicationId,
         parameters: Vec<u8>,
         required_application_ids: Vec<UserApplicationId>,
+        storage_quota: Option<u64>,
     ) -> Result<(), SystemExecutionError> {
         // Make sure that referenced applications ids have been registered.
         for required_id in &required_application_ids {
@@ -202,6 +206,7 @@ where
             parameters,
             creation,
             required_application_ids,
+            storage_quota,
         };
         self.known_applications
             .insert(&application_id, description)?;
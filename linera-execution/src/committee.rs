@@ -4,53 +4,11 @@
 
 use crate::policy::ResourceControlPolicy;
 use async_graphql::InputObject;
-use linera_base::{
-    crypto::{CryptoError, PublicKey},
-    data_types::ArithmeticError,
-};
+use linera_base::crypto::{CryptoError, PublicKey};
+pub use linera_base::data_types::Epoch;
 use serde::{Deserialize, Serialize};
 use std::{borrow::Cow, collections::BTreeMap, str::FromStr};
 
-/// A number identifying the configuration of the chain (aka the committee).
-#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Default, Debug)]
-pub struct Epoch(pub u32);
-
-impl Epoch {
-    pub const ZERO: Epoch = Epoch(0);
-}
-
-impl Serialize for Epoch {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::ser::Serializer,
-    {
-        if serializer.is_human_readable() {
-            serializer.serialize_str(&self.0.to_string())
-        } else {
-            serializer.serialize_newtype_struct("Epoch", &self.0)
-        }
-    }
-}
-
-impl<'de> Deserialize<'de> for Epoch {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::de::Deserializer<'de>,
-    {
-        if deserializer.is_human_readable() {
-            let s = String::deserialize(deserializer)?;
-            Ok(Epoch(u32::from_str(&s).map_err(serde::de::Error::custom)?))
-        } else {
-            #[derive(Deserialize)]
-            #[serde(rename = "Epoch")]
-            struct EpochDerived(u32);
-
-            let value = EpochDerived::deserialize(deserializer)?;
-            Ok(Self(value.0))
-        }
-    }
-}
-
 /// The identity of a validator.
 #[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Debug)]
 pub struct ValidatorName(pub PublicKey);
@@ -97,6 +55,26 @@ pub struct ValidatorState {
     pub votes: u64,
 }
 
+/// A change to the validators of a committee, recorded on the admin chain.
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+pub enum ValidatorChange {
+    /// Adds a new validator with the given network address and voting power.
+    Join {
+        name: ValidatorName,
+        network_address: String,
+        votes: u64,
+    },
+    /// Changes the voting power of a validator.
+    ChangeVotes { name: ValidatorName, votes: u64 },
+    /// Replaces the key of a validator, keeping its network address and voting power.
+    RotateKey {
+        name: ValidatorName,
+        new_name: ValidatorName,
+    },
+    /// Announces that a validator leaves the committee.
+    Exit { name: ValidatorName },
+}
+
 /// A set of validators (identified by their public keys) and their voting rights.
 #[derive(Eq, PartialEq, Hash, Clone, Debug, Default, InputObject)]
 pub struct Committee {
@@ -246,46 +224,12 @@ impl std::str::FromStr for ValidatorName {
     }
 }
 
-impl std::fmt::Display for Epoch {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        write!(f, "{}", self.0)
-    }
-}
-
-impl std::str::FromStr for Epoch {
-    type Err = CryptoError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Epoch(s.parse()?))
-    }
-}
-
-impl From<u32> for Epoch {
-    fn from(value: u32) -> Self {
-        Epoch(value)
-    }
-}
-
 impl From<PublicKey> for ValidatorName {
     fn from(value: PublicKey) -> Self {
         Self(value)
     }
 }
 
-impl Epoch {
-    #[inline]
-    pub fn try_add_one(self) -> Result<Self, ArithmeticError> {
-        let val = self.0.checked_add(1).ok_or(ArithmeticError::Overflow)?;
-        Ok(Self(val))
-    }
-
-    #[inline]
-    pub fn try_add_assign_one(&mut self) -> Result<(), ArithmeticError> {
-        self.0 = self.0.checked_add(1).ok_or(ArithmeticError::Overflow)?;
-        Ok(())
-    }
-}
-
 impl Committee {
     pub fn new(
         validators: BTreeMap<ValidatorName, ValidatorState>,
@@ -325,6 +269,44 @@ impl Committee {
         Committee::new(map, ResourceControlPolicy::default())
     }
 
+    /// Returns the committee obtained by applying the given changes in order, with the same
+    /// policy. Returns `None` if a change refers to an unknown validator, adds a known one,
+    /// or if no voting power is left.
+    pub fn with_changes(&self, changes: &[ValidatorChange]) -> Option<Committee> {
+        let mut validators = self.validators.clone();
+        for change in changes {
+            match change {
+                ValidatorChange::Join {
+                    name,
+                    network_address,
+                    votes,
+                } => {
+                    let state = ValidatorState {
+                        network_address: network_address.clone(),
+                        votes: *votes,
+                    };
+                    if validators.insert(*name, state).is_some() {
+                        return None;
+                    }
+                }
+                ValidatorChange::ChangeVotes { name, votes } => {
+                    validators.get_mut(name)?.votes = *votes;
+                }
+                ValidatorChange::RotateKey { name, new_name } => {
+                    let state = validators.remove(name)?;
+                    if validators.insert(*new_name, state).is_some() {
+                        return None;
+                    }
+                }
+                ValidatorChange::Exit { name } => {
+                    validators.remove(name)?;
+                }
+            }
+        }
+        let committee = Committee::new(validators, self.policy.clone());
+        (committee.total_votes > 0).then_some(committee)
+    }
+
     pub fn weight(&self, author: &ValidatorName) -> u64 {
         match self.validators.get(author) {
             Some(state) => state.votes,
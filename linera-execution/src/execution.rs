@@ -4,21 +4,25 @@
 use crate::{
     runtime::{ApplicationStatus, ExecutionRuntime, SessionManager},
     system::SystemExecutionStateView,
+    trace::{ExecutionTrace, TraceEvent},
     ContractRuntime, ExecutionError, ExecutionResult, ExecutionRuntimeContext, Message,
-    MessageContext, Operation, OperationContext, Query, QueryContext, RawExecutionResult,
-    RawOutgoingMessage, ResourceControlPolicy, ResourceTracker, Response, RuntimeLimits,
-    SystemMessage, UserApplicationDescription, UserApplicationId,
+    MessageContext, MessageKind, MessageOutcome, Operation, OperationContext, Query, QueryContext,
+    RawExecutionResult, RawOutgoingMessage, ResourceControlPolicy, ResourceTracker, Response,
+    RuntimeLimits, SystemMessage, UserApplicationDescription, UserApplicationId,
 };
 use linera_base::{
+    crypto::CryptoHash,
+    data_types::Amount,
     ensure,
     identifiers::{ChainId, Owner},
 };
 use linera_views::{
     common::Context,
     key_value_store_view::KeyValueStoreView,
+    map_view::MapView,
     reentrant_collection_view::ReentrantCollectionView,
     register_view::RegisterView,
-    views::{View, ViewError},
+    views::{ClonableView, View, ViewError},
 };
 use linera_views_derive::CryptoHashView;
 
@@ -32,7 +36,7 @@ use {
 };
 
 /// A view accessing the execution state of a chain.
-#[derive(Debug, CryptoHashView)]
+#[derive(Debug, ClonableView, CryptoHashView)]
 pub struct ExecutionStateView<C> {
     /// System application.
     pub system: SystemExecutionStateView<C>,
@@ -40,6 +44,10 @@ pub struct ExecutionStateView<C> {
     pub simple_users: ReentrantCollectionView<C, UserApplicationId, RegisterView<C, Vec<u8>>>,
     /// User applications (View based).
     pub view_users: ReentrantCollectionView<C, UserApplicationId, KeyValueStoreView<C>>,
+    /// The total size of the keys and values stored by each view-based user application with
+    /// a storage quota. The quota can only be set when the application is created, so the
+    /// total is tracked from the application's first write on every chain.
+    pub view_users_stored_bytes: MapView<C, UserApplicationId, u64>,
 }
 
 #[cfg(any(test, feature = "test"))]
@@ -59,7 +67,11 @@ where
             admin_id,
             subscriptions,
             committees,
+            committee_expirations,
             ownership,
+            closed,
+            inbox_policy,
+            previous_inbox_policies,
             balance,
             balances,
             timestamp,
@@ -83,7 +95,13 @@ where
                 .expect("serialization of subscription should not fail");
         }
         view.system.committees.set(committees);
+        view.system.committee_expirations.set(committee_expirations);
         view.system.ownership.set(ownership);
+        view.system.closed.set(closed);
+        view.system.inbox_policy.set(inbox_policy);
+        for previous_policy in previous_inbox_policies {
+            view.system.previous_inbox_policies.push(previous_policy);
+        }
         view.system.balance.set(balance);
         for (owner, balance) in balances {
             view.system
@@ -114,6 +132,7 @@ where
             height: application_description.creation.height,
             index: application_description.creation.index,
             next_message_index: 0,
+            random_seed: Some(CryptoHash::from([0; 4])),
         };
 
         let action = UserAction::Initialize(&context, initialization_argument);
@@ -131,8 +150,16 @@ where
 
         let mut tracker = ResourceTracker::default();
         let policy = ResourceControlPolicy::default();
-        self.run_user_action(application_id, chain_id, action, &policy, &mut tracker)
-            .await?;
+        self.run_user_action(
+            application_id,
+            chain_id,
+            action,
+            false,
+            &policy,
+            &mut tracker,
+            None,
+        )
+        .await?;
 
         Ok(())
     }
@@ -142,6 +169,8 @@ enum UserAction<'a> {
     Initialize(&'a OperationContext, Vec<u8>),
     Operation(&'a OperationContext, &'a [u8]),
     Message(&'a MessageContext, &'a [u8]),
+    BouncedMessage(&'a MessageContext, &'a [u8]),
+    Acknowledgement(&'a MessageContext, &'a [u8], MessageOutcome),
 }
 
 impl<'a> UserAction<'a> {
@@ -151,6 +180,19 @@ impl<'a> UserAction<'a> {
             Initialize(context, _) => context.authenticated_signer,
             Operation(context, _) => context.authenticated_signer,
             Message(context, _) => context.authenticated_signer,
+            BouncedMessage(context, _) => context.authenticated_signer,
+            Acknowledgement(context, _, _) => context.authenticated_signer,
+        }
+    }
+
+    fn random_seed(&self) -> Option<CryptoHash> {
+        use UserAction::*;
+        match self {
+            Initialize(context, _) => context.random_seed,
+            Operation(context, _) => context.random_seed,
+            Message(context, _) => context.random_seed,
+            BouncedMessage(context, _) => context.random_seed,
+            Acknowledgement(context, _, _) => context.random_seed,
         }
     }
 }
@@ -161,17 +203,28 @@ where
     ViewError: From<C::Error>,
     C::Extra: ExecutionRuntimeContext,
 {
+    /// Runs an action of a user application. Errors reported by the application are ignored
+    /// unless `reports_user_errors` is set.
+    #[allow(clippy::too_many_arguments)]
     async fn run_user_action(
         &mut self,
         application_id: UserApplicationId,
         chain_id: ChainId,
         action: UserAction<'_>,
+        reports_user_errors: bool,
         policy: &ResourceControlPolicy,
         tracker: &mut ResourceTracker,
+        mut trace: Option<&mut ExecutionTrace>,
     ) -> Result<Vec<ExecutionResult>, ExecutionError> {
         let balance = self.system.balance.get();
         let runtime_limits = tracker.limits(policy, balance);
         let initial_remaining_fuel = policy.remaining_fuel(*balance);
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.record(TraceEvent::UserAction {
+                application_id,
+                remaining_fuel: initial_remaining_fuel,
+            });
+        }
         // Try to load the application. This may fail if the corresponding
         // bytecode-publishing certificate doesn't exist yet on this validator.
         let description = self
@@ -185,6 +238,7 @@ where
             .get_user_application(&description)
             .await?;
         let signer = action.signer();
+        let random_seed = action.random_seed();
         // Create the execution runtime for this transaction.
         let mut session_manager = SessionManager::default();
         let mut results = Vec::new();
@@ -192,6 +246,7 @@ where
             id: application_id,
             parameters: description.parameters,
             signer,
+            storage_quota: description.storage_quota,
         }];
         let runtime = ExecutionRuntime::new(
             chain_id,
@@ -201,6 +256,8 @@ where
             &mut results,
             initial_remaining_fuel,
             runtime_limits,
+            random_seed,
+            trace,
         );
         // Make the call to user code.
         let call_result = match action {
@@ -217,19 +274,33 @@ where
                     .execute_message(context, &runtime, message)
                     .await
             }
+            UserAction::BouncedMessage(context, message) => {
+                application
+                    .handle_bounced_message(context, &runtime, message)
+                    .await
+            }
+            UserAction::Acknowledgement(context, message, outcome) => {
+                application
+                    .handle_acknowledgement(context, &runtime, message, outcome)
+                    .await
+            }
         };
-        // TODO(#989): Make user errors fail blocks again.
-        let mut result = if let Err(ExecutionError::UserError(message)) = &call_result {
-            tracing::error!("Ignoring error reported by user application: {message}");
-            RawExecutionResult::default()
-        } else {
-            call_result?
-        };
-        // Set the authenticated signer to be used in outgoing messages.
-        result.authenticated_signer = signer;
+        // Charge for the resources used, even if the application failed.
         let runtime_counts = runtime.runtime_counts();
         let balance = self.system.balance.get_mut();
         tracker.update_limits(balance, policy, runtime_counts)?;
+        // TODO(#989): Make user errors fail blocks again. Until then, they are only returned
+        // when they must be acknowledged to the sender of a message, or when the operation
+        // can fail on its own.
+        let mut result = match call_result {
+            Err(ExecutionError::UserError { message, .. }) if !reports_user_errors => {
+                tracing::error!("Ignoring error reported by user application: {message}");
+                RawExecutionResult::default()
+            }
+            call_result => call_result?,
+        };
+        // Set the authenticated signer to be used in outgoing messages.
+        result.authenticated_signer = signer;
 
         // Check that applications were correctly stacked and unstacked.
         assert_eq!(applications.len(), 1);
@@ -247,6 +318,8 @@ where
                 destination: message.destination.clone(),
                 authenticated: false,
                 is_skippable: true,
+                kind: MessageKind::Simple,
+                grant: Amount::ZERO,
                 message: SystemMessage::RegisterApplications {
                     applications: applications.clone(),
                 },
@@ -271,6 +344,25 @@ where
         operation: &Operation,
         policy: &ResourceControlPolicy,
         tracker: &mut ResourceTracker,
+    ) -> Result<Vec<ExecutionResult>, ExecutionError> {
+        self.execute_operation_with_trace(context, operation, false, policy, tracker, None)
+            .await
+    }
+
+    /// Executes an operation, recording the steps taken by user applications in `trace` if
+    /// one is provided.
+    ///
+    /// If the operation is `independent`, i.e. its failure doesn't make the block fail, the
+    /// errors reported by user applications are returned instead of being ignored, so that the
+    /// caller can discard the changes of the operation.
+    pub async fn execute_operation_with_trace(
+        &mut self,
+        context: &OperationContext,
+        operation: &Operation,
+        independent: bool,
+        policy: &ResourceControlPolicy,
+        tracker: &mut ResourceTracker,
+        trace: Option<&mut ExecutionTrace>,
     ) -> Result<Vec<ExecutionResult>, ExecutionError> {
         assert_eq!(context.chain_id, self.context().extra().chain_id());
         match operation {
@@ -286,8 +378,10 @@ where
                             application_id,
                             context.chain_id,
                             user_action,
+                            independent,
                             policy,
                             tracker,
+                            trace,
                         )
                         .await?,
                     );
@@ -302,8 +396,10 @@ where
                     *application_id,
                     context.chain_id,
                     UserAction::Operation(context, bytes),
+                    independent,
                     policy,
                     tracker,
+                    trace,
                 )
                 .await
             }
@@ -316,6 +412,20 @@ where
         message: &Message,
         policy: &ResourceControlPolicy,
         tracker: &mut ResourceTracker,
+    ) -> Result<Vec<ExecutionResult>, ExecutionError> {
+        self.execute_message_with_trace(context, message, policy, tracker, None)
+            .await
+    }
+
+    /// Executes an incoming message, recording the steps taken by user applications in
+    /// `trace` if one is provided.
+    pub async fn execute_message_with_trace(
+        &mut self,
+        context: &MessageContext,
+        message: &Message,
+        policy: &ResourceControlPolicy,
+        tracker: &mut ResourceTracker,
+        trace: Option<&mut ExecutionTrace>,
     ) -> Result<Vec<ExecutionResult>, ExecutionError> {
         assert_eq!(context.chain_id, self.context().extra().chain_id());
         match message {
@@ -327,12 +437,22 @@ where
                 application_id,
                 bytes,
             } => {
+                let action = if context.is_bouncing {
+                    UserAction::BouncedMessage(context, bytes)
+                } else if let Some(outcome) = context.acknowledgement {
+                    UserAction::Acknowledgement(context, bytes, outcome)
+                } else {
+                    UserAction::Message(context, bytes)
+                };
+                // The errors of acknowledged messages are reported to their sender.
                 self.run_user_action(
                     *application_id,
                     context.chain_id,
-                    UserAction::Message(context, bytes),
+                    action,
+                    context.is_acknowledged,
                     policy,
                     tracker,
+                    trace,
                 )
                 .await
             }
@@ -372,6 +492,7 @@ where
                     id: *application_id,
                     parameters: description.parameters,
                     signer: None,
+                    storage_quota: description.storage_quota,
                 }];
                 let runtime_limits = RuntimeLimits::default();
                 let remaining_fuel = 0;
@@ -383,6 +504,8 @@ where
                     &mut results,
                     remaining_fuel,
                     runtime_limits,
+                    None,
+                    None,
                 );
                 // Run the query.
                 let response = application.handle_query(context, &runtime, bytes).await?;
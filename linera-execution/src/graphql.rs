@@ -2,10 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    committee::{Committee, Epoch, ValidatorName, ValidatorState},
+    committee::{Committee, Epoch, ValidatorChange, ValidatorName, ValidatorState},
     system::{Recipient, UserData},
-    Bytecode, ChainOwnership, ChannelSubscription, ExecutionStateView, GenericApplicationId,
-    SystemExecutionStateView, UserApplicationDescription,
+    ApprovalThreshold, Bytecode, ChainOwnership, ChannelSubscription, ExecutionStateView,
+    GenericApplicationId, InboxPolicy, SystemExecutionStateView, TimeoutConfig,
+    UserApplicationDescription,
 };
 use async_graphql::{Error, Object};
 use linera_base::{
@@ -20,11 +21,19 @@ doc_scalar!(
     GenericApplicationId,
     "A unique identifier for a user application or for the system application"
 );
+doc_scalar!(
+    ApprovalThreshold,
+    "The total weight of chain owners that must approve a block, and which blocks need it"
+);
 doc_scalar!(Bytecode, "A WebAssembly module's bytecode");
 doc_scalar!(ChainOwnership, "Represents the owner(s) of a chain");
 doc_scalar!(
-    Epoch,
-    "A number identifying the configuration of the chain (aka the committee)"
+    TimeoutConfig,
+    "The durations of the rounds of a multi-owner chain"
+);
+doc_scalar!(
+    InboxPolicy,
+    "Restricts the messages that a chain accepts in its inboxes"
 );
 doc_scalar!(Recipient, "The recipient of a transfer");
 doc_scalar!(
@@ -32,6 +41,10 @@ doc_scalar!(
     "Description of the necessary information to run a user application"
 );
 doc_scalar!(UserData, "Optional user message attached to a transfer");
+doc_scalar!(
+    ValidatorChange,
+    "A change to the validators of a committee, recorded on the admin chain"
+);
 doc_scalar!(ValidatorName, "The identity of a validator");
 
 #[Object]
@@ -98,11 +111,26 @@ where
         self.committees.get()
     }
 
+    #[graphql(derived(name = "committee_expirations"))]
+    async fn _committee_expirations(&self) -> &BTreeMap<Epoch, Timestamp> {
+        self.committee_expirations.get()
+    }
+
     #[graphql(derived(name = "ownership"))]
     async fn _ownership(&self) -> &ChainOwnership {
         self.ownership.get()
     }
 
+    #[graphql(derived(name = "closed"))]
+    async fn _closed(&self) -> &bool {
+        self.closed.get()
+    }
+
+    #[graphql(derived(name = "inbox_policy"))]
+    async fn _inbox_policy(&self) -> &InboxPolicy {
+        self.inbox_policy.get()
+    }
+
     #[graphql(derived(name = "balance"))]
     async fn _balance(&self) -> &Amount {
         self.balance.get()
@@ -0,0 +1,71 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{GenericApplicationId, Message};
+use linera_base::{data_types::Amount, identifiers::ChainId};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+#[cfg(test)]
+#[path = "unit_tests/inbox_policy_tests.rs"]
+mod inbox_policy_tests;
+
+/// Restricts the messages that a chain accepts in its inboxes. The policy is applied when the
+/// messages are received, so refused messages never have to be executed or rejected by the
+/// blocks of the chain: they are dropped, whatever their kind.
+///
+/// For all validators to agree on it, a message is checked against the state of the chain
+/// before its first block with a timestamp at least the one of the block that sent the message.
+#[derive(PartialEq, Eq, Clone, Hash, Debug, Default, Serialize, Deserialize)]
+pub struct InboxPolicy {
+    /// If set, only messages sent by these chains are accepted.
+    pub allowed_senders: Option<BTreeSet<ChainId>>,
+    /// Messages sent by these chains are refused.
+    pub denied_senders: BTreeSet<ChainId>,
+    /// If set, only messages of these applications are accepted.
+    pub allowed_applications: Option<BTreeSet<GenericApplicationId>>,
+    /// Messages of these applications are refused.
+    pub denied_applications: BTreeSet<GenericApplicationId>,
+    /// If set, at most this many messages from the same origin can be pending, i.e. accepted
+    /// but not executed yet. The following ones are refused. Messages that can be skipped are
+    /// not counted.
+    pub maximum_pending_messages: Option<u64>,
+    /// The minimum amount of tokens that a message must grant to the chain.
+    pub minimum_grant: Amount,
+}
+
+impl InboxPolicy {
+    /// Returns whether a `message` from the `sender` chain that grants `grant` tokens is
+    /// accepted, given the number of messages from the same origin that are pending.
+    pub fn accepts(
+        &self,
+        sender: ChainId,
+        message: &Message,
+        grant: Amount,
+        pending_messages: u64,
+    ) -> bool {
+        let application_id = message.application_id();
+        if let Some(allowed_senders) = &self.allowed_senders {
+            if !allowed_senders.contains(&sender) {
+                return false;
+            }
+        }
+        if self.denied_senders.contains(&sender) {
+            return false;
+        }
+        if let Some(allowed_applications) = &self.allowed_applications {
+            if !allowed_applications.contains(&application_id) {
+                return false;
+            }
+        }
+        if self.denied_applications.contains(&application_id) {
+            return false;
+        }
+        if let Some(maximum) = self.maximum_pending_messages {
+            if pending_messages >= maximum {
+                return false;
+            }
+        }
+        grant >= self.minimum_grant
+    }
+}
@@ -7,19 +7,30 @@ mod applications;
 pub mod committee;
 mod execution;
 mod graphql;
+mod inbox_policy;
 mod ownership;
 pub mod policy;
+pub mod profiler;
 mod runtime;
 pub mod system;
+#[cfg(any(test, feature = "test"))]
+pub mod test_utils;
+pub mod trace;
 mod wasm;
-use crate::policy::{PricingError, ResourceControlPolicy};
+use crate::{
+    policy::{PricingError, ResourceControlPolicy},
+    trace::LogLevel,
+};
 
 pub use applications::{
     ApplicationRegistryView, BytecodeLocation, GenericApplicationId, UserApplicationDescription,
     UserApplicationId,
 };
 pub use execution::ExecutionStateView;
-pub use ownership::ChainOwnership;
+pub use inbox_policy::InboxPolicy;
+pub use ownership::{
+    ApprovalScope, ApprovalThreshold, ChainOwnership, TimeoutBackoff, TimeoutConfig,
+};
 pub use system::{
     SystemExecutionError, SystemExecutionStateView, SystemMessage, SystemOperation, SystemQuery,
     SystemResponse,
@@ -30,7 +41,7 @@ pub use system::{
 ))]
 pub use wasm::test as wasm_test;
 #[cfg(any(feature = "wasmer", feature = "wasmtime"))]
-pub use wasm::{WasmApplication, WasmExecutionError};
+pub use wasm::{instrument_for_profiling, WasmApplication, WasmExecutionError};
 #[cfg(any(test, feature = "test"))]
 pub use {applications::ApplicationRegistry, system::SystemExecutionState};
 
@@ -41,7 +52,7 @@ use dashmap::DashMap;
 use derive_more::Display;
 use linera_base::{
     abi::Abi,
-    crypto::CryptoHash,
+    crypto::{BcsHashable, CryptoHash},
     data_types::{Amount, ArithmeticError, BlockHeight, Timestamp},
     doc_scalar, hex_debug,
     identifiers::{BytecodeId, ChainId, ChannelName, Destination, MessageId, Owner, SessionId},
@@ -71,6 +82,12 @@ pub struct RuntimeLimits {
     pub maximum_bytes_left_to_read: u64,
     /// The maximum size of write allowed per block
     pub maximum_bytes_left_to_write: u64,
+    /// The maximum number of pages of linear memory of a Wasm contract
+    pub maximum_wasm_memory_pages: u64,
+    /// The maximum depth of nested calls to other applications and sessions
+    pub maximum_call_depth: u64,
+    /// The maximum number of sessions alive at the same time
+    pub maximum_live_sessions: u64,
 }
 
 /// The entries of the runtime related to storage
@@ -112,6 +129,9 @@ impl Default for RuntimeLimits {
             max_budget_bytes_written: u64::MAX / 2,
             maximum_bytes_left_to_read: u64::MAX / 2,
             maximum_bytes_left_to_write: u64::MAX / 2,
+            maximum_wasm_memory_pages: u64::MAX,
+            maximum_call_depth: u64::MAX,
+            maximum_live_sessions: u64::MAX,
         }
     }
 }
@@ -148,6 +168,29 @@ impl ResourceTracker {
         Ok(())
     }
 
+    /// Computes the fees for the resources used since the tracker was in the `previous`
+    /// state.
+    pub fn fees_since(
+        &self,
+        previous: &ResourceTracker,
+        policy: &ResourceControlPolicy,
+    ) -> Result<Amount, PricingError> {
+        let fees = [
+            policy.fuel_price(self.used_fuel.saturating_sub(previous.used_fuel))?,
+            policy.storage_num_reads_price(&self.num_reads.saturating_sub(previous.num_reads))?,
+            policy
+                .storage_bytes_read_price(&self.bytes_read.saturating_sub(previous.bytes_read))?,
+            policy.storage_bytes_written_price(
+                &self.bytes_written.saturating_sub(previous.bytes_written),
+            )?,
+        ];
+        let mut total = Amount::ZERO;
+        for fee in fees {
+            total = total.try_add(fee)?;
+        }
+        Ok(total)
+    }
+
     /// Obtain the limits for the running of the system
     pub fn limits(&self, policy: &ResourceControlPolicy, balance: &Amount) -> RuntimeLimits {
         let max_budget_num_reads =
@@ -162,6 +205,9 @@ impl ResourceTracker {
             max_budget_bytes_written,
             maximum_bytes_left_to_read: self.maximum_bytes_left_to_read,
             maximum_bytes_left_to_write: self.maximum_bytes_left_to_write,
+            maximum_wasm_memory_pages: policy.maximum_wasm_memory_pages,
+            maximum_call_depth: policy.maximum_call_depth,
+            maximum_live_sessions: policy.maximum_live_sessions,
         }
     }
 }
@@ -179,6 +225,10 @@ pub struct RuntimeCounts {
     pub bytes_written: u64,
 }
 
+/// The maximum number of nested remote queries, i.e. of services waiting for a query to
+/// another chain while handling a remote query themselves.
+pub const MAXIMUM_REMOTE_QUERY_DEPTH: usize = 8;
+
 /// An implementation of [`UserApplication`]
 pub type UserApplicationCode = Arc<dyn UserApplication + Send + Sync + 'static>;
 
@@ -190,8 +240,8 @@ pub enum ExecutionError {
     ArithmeticError(#[from] ArithmeticError),
     #[error(transparent)]
     SystemError(#[from] SystemExecutionError),
-    #[error("User application reported an error: {0}")]
-    UserError(String),
+    #[error("User application reported an error with code {code}: {message}")]
+    UserError { code: u32, message: String },
     #[cfg(any(feature = "wasmer", feature = "wasmtime"))]
     #[error(transparent)]
     WasmError(#[from] WasmExecutionError),
@@ -224,6 +274,25 @@ pub enum ExecutionError {
     ExcessiveRead,
     #[error("Excessive writings to storage")]
     ExcessiveWrite,
+    #[error("Wasm application uses more than the maximum of {0} memory pages")]
+    ExcessiveMemory(u64),
+    #[error("Nested application calls exceed the maximum depth of {0}")]
+    ExcessiveCallDepth(u64),
+    #[error("Too many live sessions: the maximum is {0}")]
+    ExcessiveSessions(u64),
+    #[error("Application storage would exceed the quota of {0} bytes")]
+    ExcessiveStorage(u64),
+
+    #[error("Chain {0} is not tracked by the local node")]
+    UntrackedChain(ChainId),
+    #[error("Chain {0} is already being queried by one of the services querying it")]
+    RemoteQueryCycle(ChainId),
+    #[error("Nested remote queries exceed the maximum depth of {MAXIMUM_REMOTE_QUERY_DEPTH}")]
+    ExcessiveRemoteQueryDepth,
+    #[error("Chain {0} is busy and cannot be queried")]
+    RemoteChainBusy(ChainId),
+    #[error("The block has no random seed, because it lacks the signatures of its previous block")]
+    MissingRandomSeed,
 
     #[error("Bytecode ID {0:?} is invalid")]
     InvalidBytecodeId(BytecodeId),
@@ -240,6 +309,26 @@ impl From<ViewError> for ExecutionError {
     }
 }
 
+impl ExecutionError {
+    /// Returns a description of the error if it was caused by the transaction being executed,
+    /// or `None` if it was caused by the node executing it, for instance a storage failure.
+    ///
+    /// The description doesn't depend on the Wasm runtime used by the node, so that all the
+    /// validators agree on it.
+    pub fn transaction_failure(&self) -> Option<String> {
+        match self {
+            ExecutionError::ViewError(_)
+            | ExecutionError::SystemError(SystemExecutionError::ViewError(_))
+            | ExecutionError::UntrackedChain(_)
+            | ExecutionError::RemoteChainBusy(_)
+            | ExecutionError::ApplicationBytecodeNotFound(_) => None,
+            #[cfg(any(feature = "wasmer", feature = "wasmtime"))]
+            ExecutionError::WasmError(error) => error.transaction_failure(),
+            error => Some(error.to_string()),
+        }
+    }
+}
+
 /// The public entry points provided by an application.
 #[async_trait]
 pub trait UserApplication {
@@ -267,6 +356,25 @@ pub trait UserApplication {
         message: &[u8],
     ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError>;
 
+    /// Handles a tracked message sent by this application that was rejected by its
+    /// recipient and returned to this chain.
+    async fn handle_bounced_message(
+        &self,
+        context: &MessageContext,
+        runtime: &dyn ContractRuntime,
+        message: &[u8],
+    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError>;
+
+    /// Handles the acknowledgement of a message sent by this application, with the outcome
+    /// of its execution by the recipient.
+    async fn handle_acknowledgement(
+        &self,
+        context: &MessageContext,
+        runtime: &dyn ContractRuntime,
+        message: &[u8],
+        outcome: MessageOutcome,
+    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError>;
+
     /// Executes a call from another application.
     ///
     /// When an application is executing an operation or a message it may call other applications,
@@ -334,6 +442,17 @@ pub trait ExecutionRuntimeContext {
         &self,
         description: &UserApplicationDescription,
     ) -> Result<UserApplicationCode, ExecutionError>;
+
+    /// Queries an application, or the system, on another chain tracked by the local node.
+    ///
+    /// Fails if the queried chain is already being queried by one of the services waiting for
+    /// this query, or if the remote queries are nested more than
+    /// [`MAXIMUM_REMOTE_QUERY_DEPTH`] times.
+    async fn query_remote_application(
+        &self,
+        chain_id: ChainId,
+        query: Query,
+    ) -> Result<RemoteQueryResponse<Response>, ExecutionError>;
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -348,6 +467,8 @@ pub struct OperationContext {
     pub index: u32,
     /// The index of the next message to be created.
     pub next_message_index: u32,
+    /// The pseudo-random seed of the current transaction, if the block has one.
+    pub random_seed: Option<CryptoHash>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -363,6 +484,16 @@ pub struct MessageContext {
     /// The id of the message (based on the operation height and index in the remote
     /// certificate).
     pub message_id: MessageId,
+    /// Whether the message is a tracked message that was rejected by its recipient and
+    /// returned to the sender.
+    pub is_bouncing: bool,
+    /// Whether the sender expects an acknowledgement with the outcome of the execution.
+    pub is_acknowledged: bool,
+    /// The outcome of the execution by the recipient, if the message is an acknowledgement
+    /// of a message sent by this chain.
+    pub acknowledgement: Option<MessageOutcome>,
+    /// The pseudo-random seed of the current transaction, if the block has one.
+    pub random_seed: Option<CryptoHash>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -432,6 +563,31 @@ pub trait ServiceRuntime: BaseRuntime {
         queried_id: UserApplicationId,
         argument: &[u8],
     ) -> Result<Vec<u8>, ExecutionError>;
+
+    /// Queries an application on another chain tracked by the local node.
+    async fn try_query_remote_application(
+        &self,
+        chain_id: ChainId,
+        queried_id: UserApplicationId,
+        argument: &[u8],
+    ) -> Result<RemoteQueryResponse, ExecutionError>;
+
+    /// Queries the system state of another chain tracked by the local node.
+    async fn try_query_remote_system(
+        &self,
+        chain_id: ChainId,
+        query: SystemQuery,
+    ) -> Result<RemoteQueryResponse<SystemResponse>, ExecutionError>;
+}
+
+/// The response of an application on another chain.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RemoteQueryResponse<Response = Vec<u8>> {
+    /// The response of the queried application.
+    pub response: Response,
+    /// The height of the next block of the queried chain: the response reflects the state of
+    /// the chain after executing all the blocks below this height.
+    pub height: BlockHeight,
 }
 
 /// The result of calling into an application or a session.
@@ -453,6 +609,16 @@ pub trait ContractRuntime: BaseRuntime {
     /// Sets the amount of execution fuel remaining before execution is aborted.
     fn set_remaining_fuel(&self, remaining_fuel: u64);
 
+    /// Returns the limits enforced while executing the transaction.
+    fn runtime_limits(&self) -> RuntimeLimits;
+
+    /// Returns the pseudo-random seed of the current transaction.
+    ///
+    /// The seed is the same for all the validators, but it is known by the chain owners before
+    /// they propose the block. Blocks proposed before their previous block was certified have
+    /// no random seed.
+    fn random_seed(&self) -> Result<CryptoHash, ExecutionError>;
+
     /// Reads the application state and prevents further reading/loading until the state is saved.
     async fn try_read_and_lock_my_state(&self) -> Result<Vec<u8>, ExecutionError>;
 
@@ -465,6 +631,12 @@ pub trait ContractRuntime: BaseRuntime {
     /// Writes the batch and then unlock
     async fn write_batch_and_unlock(&self, batch: Batch) -> Result<(), ExecutionError>;
 
+    /// Emits an event with the given topic, to be recorded in the block's execution outcome.
+    fn emit_event(&self, topic: Vec<u8>, value: Vec<u8>) -> Result<(), ExecutionError>;
+
+    /// Logs a message from the application.
+    fn log(&self, level: LogLevel, message: String);
+
     /// Calls another application. Forwarded sessions will now be visible to
     /// `callee_id` (but not to the caller any more).
     async fn try_call_application(
@@ -551,10 +723,57 @@ pub struct RawOutgoingMessage<Message> {
     pub authenticated: bool,
     /// Whether the message can be skipped by the receiver.
     pub is_skippable: bool,
+    /// The kind of message being sent.
+    pub kind: MessageKind,
+    /// The tokens granted to the recipient for executing the message. They are taken from
+    /// the sender's balance. Only messages to a single recipient can grant tokens.
+    pub grant: Amount,
     /// The message itself.
     pub message: Message,
 }
 
+/// The kind of a message, which determines whether and how it is returned to its sender.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum MessageKind {
+    /// The message is dropped if the recipient rejects it, provided it is skippable.
+    #[default]
+    Simple,
+    /// The message is returned to the sender as a bounced message if the recipient
+    /// rejects it.
+    Tracked,
+    /// A tracked message that was rejected and is being returned to its sender. It is
+    /// dropped if it is rejected again.
+    Bounce,
+    /// The recipient returns the message to the sender as an acknowledgement, with the
+    /// outcome of its execution, whether it is executed or rejected, once the block that
+    /// handles it is confirmed.
+    Acknowledged,
+    /// An acknowledged message that is being returned to its sender with the outcome of its
+    /// execution. It is dropped if it is rejected.
+    Acknowledgement(MessageOutcome),
+}
+
+impl MessageKind {
+    /// Whether the recipient must return the message to its sender, either as a bounced
+    /// message or as an acknowledgement.
+    pub fn is_tracked(&self) -> bool {
+        matches!(self, MessageKind::Tracked | MessageKind::Acknowledged)
+    }
+}
+
+/// The outcome of the execution of an acknowledged message by its recipient.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum MessageOutcome {
+    /// The message was executed successfully.
+    Executed,
+    /// The recipient application returned an error with the given code while executing the
+    /// message.
+    Failed(u32),
+    /// The message was rejected by the receiving chain, or it expired before the receiving
+    /// chain executed it, and it was not executed.
+    Rejected,
+}
+
 /// Externally visible results of an execution. These results are meant in the context of
 /// the application that created them.
 #[derive(Debug)]
@@ -569,6 +788,8 @@ pub struct RawExecutionResult<Message> {
     pub subscribe: Vec<(ChannelName, ChainId)>,
     /// Unsubscribe chains to channels.
     pub unsubscribe: Vec<(ChannelName, ChainId)>,
+    /// Events emitted by the application, as pairs of topic and value.
+    pub events: Vec<(Vec<u8>, Vec<u8>)>,
 }
 
 /// The identifier of a channel, relative to a particular application.
@@ -614,6 +835,7 @@ impl<Message> Default for RawExecutionResult<Message> {
             messages: Vec::new(),
             subscribe: Vec::new(),
             unsubscribe: Vec::new(),
+            events: Vec::new(),
         }
     }
 }
@@ -637,7 +859,7 @@ pub struct TestExecutionRuntimeContext {
 
 #[cfg(any(test, feature = "test"))]
 impl TestExecutionRuntimeContext {
-    fn new(chain_id: ChainId) -> Self {
+    pub fn new(chain_id: ChainId) -> Self {
         Self {
             chain_id,
             user_applications: Arc::default(),
@@ -669,6 +891,14 @@ impl ExecutionRuntimeContext for TestExecutionRuntimeContext {
             })?
             .clone())
     }
+
+    async fn query_remote_application(
+        &self,
+        chain_id: ChainId,
+        _query: Query,
+    ) -> Result<RemoteQueryResponse<Response>, ExecutionError> {
+        Err(ExecutionError::UntrackedChain(chain_id))
+    }
 }
 
 impl From<SystemOperation> for Operation {
@@ -785,8 +1015,7 @@ pub struct Bytecode {
 
 impl Bytecode {
     /// Creates a new [`Bytecode`] instance using the provided `bytes`.
-    #[cfg(any(feature = "wasmer", feature = "wasmtime"))]
-    pub(crate) fn new(bytes: Vec<u8>) -> Self {
+    pub fn new(bytes: Vec<u8>) -> Self {
         Bytecode { bytes }
     }
 
@@ -797,6 +1026,8 @@ impl Bytecode {
     }
 }
 
+impl BcsHashable for Bytecode {}
+
 impl AsRef<[u8]> for Bytecode {
     fn as_ref(&self) -> &[u8] {
         self.bytes.as_ref()
@@ -893,3 +1124,7 @@ doc_scalar!(
     Message,
     "An message to be sent and possibly executed in the receiver's block."
 );
+doc_scalar!(
+    MessageKind,
+    "The kind of a message, which determines whether and how it is returned to its sender"
+);
@@ -1,9 +1,10 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::{Operation, SystemOperation};
 use linera_base::{crypto::PublicKey, data_types::RoundNumber, identifiers::Owner};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, time::Duration};
 
 /// Represents the owner(s) of a chain.
 #[derive(PartialEq, Eq, Clone, Hash, Debug, Default, Serialize, Deserialize)]
@@ -20,9 +21,134 @@ pub enum ChainOwnership {
         /// The number of initial rounds in which all owners are allowed to propose blocks,
         /// i.e. the first round with only a single leader.
         multi_leader_rounds: RoundNumber,
+        /// If set, some blocks must be approved by owners with a sufficient total weight.
+        approval_threshold: Option<ApprovalThreshold>,
+        /// How long each round lasts before validators vote to time out the round leader.
+        timeout_config: TimeoutConfig,
     },
 }
 
+/// The durations of the rounds of a multi-owner chain. When a round times out, validators
+/// vote to start the next one, so that another owner can propose a block.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Serialize, Deserialize)]
+pub struct TimeoutConfig {
+    /// The duration of the first round, in microseconds.
+    pub base_timeout_micros: u64,
+    /// How the duration grows from one round to the next.
+    pub backoff: TimeoutBackoff,
+    /// The maximum duration of a round, in microseconds.
+    pub max_timeout_micros: u64,
+}
+
+/// How the durations of consecutive rounds grow.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Serialize, Deserialize)]
+pub enum TimeoutBackoff {
+    /// Round `n` lasts `n + 1` times as long as the first one.
+    Linear,
+    /// Each round lasts `factor` times as long as the previous one. With a factor of one,
+    /// all rounds have the same duration.
+    Exponential { factor: u32 },
+}
+
+impl Default for TimeoutConfig {
+    /// Rounds last 10 seconds, 20 seconds, 30 seconds etc.
+    fn default() -> Self {
+        TimeoutConfig {
+            base_timeout_micros: 10_000_000,
+            backoff: TimeoutBackoff::Linear,
+            max_timeout_micros: u64::MAX,
+        }
+    }
+}
+
+impl TimeoutConfig {
+    /// Returns the duration of the given round.
+    pub fn round_duration(&self, round: RoundNumber) -> Duration {
+        let factor = match self.backoff {
+            TimeoutBackoff::Linear => u64::from(round.0).saturating_add(1),
+            TimeoutBackoff::Exponential { factor } => {
+                u64::from(factor).checked_pow(round.0).unwrap_or(u64::MAX)
+            }
+        };
+        let micros = self
+            .base_timeout_micros
+            .saturating_mul(factor)
+            .min(self.max_timeout_micros);
+        Duration::from_micros(micros)
+    }
+
+    /// Returns whether rounds have a positive duration that never decreases.
+    pub fn is_valid(&self) -> bool {
+        let backoff_is_valid = match self.backoff {
+            TimeoutBackoff::Linear => true,
+            TimeoutBackoff::Exponential { factor } => factor > 0,
+        };
+        self.base_timeout_micros > 0
+            && backoff_is_valid
+            && self.max_timeout_micros >= self.base_timeout_micros
+    }
+}
+
+/// A requirement that blocks of a multi-owner chain be approved by a weighted threshold of
+/// its owners, rather than by the proposer alone.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Serialize, Deserialize)]
+pub struct ApprovalThreshold {
+    /// The total weight of the owners that must approve a block, including the proposer.
+    pub weight: u64,
+    /// Which blocks require approval.
+    pub scope: ApprovalScope,
+}
+
+/// The blocks that require approval from a threshold of owners.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Serialize, Deserialize)]
+pub enum ApprovalScope {
+    /// Every block must be approved.
+    AllBlocks,
+    /// Only blocks that transfer tokens, open or close chains, or change the chain's owners,
+    /// inbox policy or committees must be approved. This includes all blocks with user
+    /// operations or new applications, since applications can transfer their own tokens on
+    /// behalf of the chain.
+    TransfersAndOwnership,
+}
+
+impl ApprovalScope {
+    /// Returns whether a block with the given operations requires approval.
+    pub fn requires_approval(&self, operations: &[Operation]) -> bool {
+        match self {
+            ApprovalScope::AllBlocks => true,
+            ApprovalScope::TransfersAndOwnership => {
+                operations.iter().any(|operation| match operation {
+                    Operation::User { .. } => true,
+                    Operation::System(operation) => {
+                        Self::system_operation_requires_approval(operation)
+                    }
+                })
+            }
+        }
+    }
+
+    /// Returns whether the system operation can move tokens, or change who controls the
+    /// chain and what it accepts.
+    fn system_operation_requires_approval(operation: &SystemOperation) -> bool {
+        match operation {
+            SystemOperation::Transfer { .. }
+            | SystemOperation::Claim { .. }
+            | SystemOperation::OpenChain { .. }
+            | SystemOperation::CloseChain { .. }
+            | SystemOperation::ChangeOwner { .. }
+            | SystemOperation::ChangeMultipleOwners { .. }
+            | SystemOperation::ChangeInboxPolicy { .. }
+            | SystemOperation::Admin(_) => true,
+            // Initializing an application runs its code, like a user operation.
+            SystemOperation::CreateApplication { .. } => true,
+            SystemOperation::Subscribe { .. }
+            | SystemOperation::Unsubscribe { .. }
+            | SystemOperation::PublishBytecode { .. }
+            | SystemOperation::RequestApplication { .. } => false,
+        }
+    }
+}
+
 impl ChainOwnership {
     pub fn single(public_key: PublicKey) -> Self {
         ChainOwnership::Single {
@@ -41,6 +167,62 @@ impl ChainOwnership {
                 .map(|(key, weight)| (Owner::from(key), (key, weight)))
                 .collect(),
             multi_leader_rounds,
+            approval_threshold: None,
+            timeout_config: TimeoutConfig::default(),
+        }
+    }
+
+    /// Sets the durations of the rounds, if this is a multi-owner chain.
+    pub fn with_timeout_config(mut self, config: TimeoutConfig) -> Self {
+        if let ChainOwnership::Multi { timeout_config, .. } = &mut self {
+            *timeout_config = config;
+        }
+        self
+    }
+
+    /// Requires the given threshold of owners to approve blocks, if this is a multi-owner chain.
+    pub fn with_approval_threshold(mut self, threshold: Option<ApprovalThreshold>) -> Self {
+        if let ChainOwnership::Multi {
+            approval_threshold, ..
+        } = &mut self
+        {
+            *approval_threshold = threshold;
+        }
+        self
+    }
+
+    /// Returns the approval threshold of the chain, if any.
+    pub fn approval_threshold(&self) -> Option<&ApprovalThreshold> {
+        match self {
+            ChainOwnership::Multi {
+                approval_threshold, ..
+            } => approval_threshold.as_ref(),
+            ChainOwnership::None | ChainOwnership::Single { .. } => None,
+        }
+    }
+
+    /// Returns the durations of the rounds, if this is a multi-owner chain.
+    pub fn timeout_config(&self) -> Option<&TimeoutConfig> {
+        match self {
+            ChainOwnership::Multi { timeout_config, .. } => Some(timeout_config),
+            ChainOwnership::None | ChainOwnership::Single { .. } => None,
+        }
+    }
+
+    /// Returns whether the approval threshold, if any, can be reached by the owners.
+    pub fn is_approval_threshold_reachable(&self) -> bool {
+        match self {
+            ChainOwnership::Multi {
+                public_keys,
+                approval_threshold: Some(threshold),
+                ..
+            } => {
+                let total_weight = public_keys
+                    .values()
+                    .fold(0u64, |total, (_, weight)| total.saturating_add(*weight));
+                threshold.weight <= total_weight
+            }
+            _ => true,
         }
     }
 
@@ -67,3 +249,58 @@ impl ChainOwnership {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        system::{AdminOperation, SystemChannel},
+        InboxPolicy,
+    };
+    use linera_base::identifiers::ChainId;
+
+    #[test]
+    fn test_default_timeouts_grow_linearly() {
+        let config = TimeoutConfig::default();
+        for round in 0..5 {
+            assert_eq!(
+                config.round_duration(RoundNumber(round)),
+                Duration::from_secs(10 * (u64::from(round) + 1))
+            );
+        }
+    }
+
+    #[test]
+    fn test_exponential_timeouts_are_capped() {
+        let config = TimeoutConfig {
+            base_timeout_micros: 1_000_000,
+            backoff: TimeoutBackoff::Exponential { factor: 2 },
+            max_timeout_micros: 5_000_000,
+        };
+        let durations = (0..5)
+            .map(|round| config.round_duration(RoundNumber(round)).as_secs())
+            .collect::<Vec<_>>();
+        assert_eq!(durations, vec![1, 2, 4, 5, 5]);
+    }
+
+    #[test]
+    fn test_transfers_and_ownership_scope() {
+        let scope = ApprovalScope::TransfersAndOwnership;
+        let subscribe = Operation::System(SystemOperation::Subscribe {
+            chain_id: ChainId::root(0),
+            channel: SystemChannel::Admin,
+        });
+        let change_inbox_policy = Operation::System(SystemOperation::ChangeInboxPolicy {
+            policy: InboxPolicy::default(),
+        });
+        let change_validators =
+            Operation::System(SystemOperation::Admin(AdminOperation::ChangeValidators {
+                changes: Vec::new(),
+                grace_period_micros: 0,
+            }));
+        assert!(!scope.requires_approval(&[subscribe.clone()]));
+        assert!(scope.requires_approval(&[subscribe.clone(), change_inbox_policy]));
+        assert!(scope.requires_approval(&[subscribe, change_validators]));
+        assert!(ApprovalScope::AllBlocks.requires_approval(&[]));
+    }
+}
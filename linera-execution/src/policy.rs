@@ -4,7 +4,7 @@
 //! This module contains types related to fees and pricing.
 
 use async_graphql::InputObject;
-use linera_base::data_types::{Amount, ArithmeticError};
+use linera_base::data_types::{Amount, ArithmeticError, Timestamp};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -28,6 +28,17 @@ pub struct ResourceControlPolicy {
     pub maximum_bytes_written_per_block: u64,
     /// The cost to store and send cross-chain messages, per byte.
     pub messages: Amount,
+    /// The maximum number of 64 KiB pages of linear memory a Wasm contract may use
+    pub maximum_wasm_memory_pages: u64,
+    /// The maximum depth of nested calls to other applications and sessions
+    pub maximum_call_depth: u64,
+    /// The maximum number of sessions alive at the same time during a transaction
+    pub maximum_live_sessions: u64,
+    /// The time after which the messages that their recipients haven't executed expire, in
+    /// microseconds. The recipients then refuse them: tracked messages bounce and acknowledged
+    /// messages are acknowledged as rejected. The senders only stop sending the expired
+    /// messages that are simple ones.
+    pub message_expiry_micros: u64,
 }
 
 impl Default for ResourceControlPolicy {
@@ -41,6 +52,10 @@ impl Default for ResourceControlPolicy {
             maximum_bytes_read_per_block: u64::MAX / 2,
             maximum_bytes_written_per_block: u64::MAX / 2,
             messages: Amount::default(),
+            maximum_wasm_memory_pages: u64::MAX,
+            maximum_call_depth: u64::MAX,
+            maximum_live_sessions: u64::MAX,
+            message_expiry_micros: u64::MAX,
         }
     }
 }
@@ -84,6 +99,11 @@ impl ResourceControlPolicy {
         Ok(self.fuel.try_mul(u128::from(fuel))?)
     }
 
+    /// Returns whether the messages of a block with the given timestamp have expired by `now`.
+    pub fn has_message_expired(&self, timestamp: Timestamp, now: Timestamp) -> bool {
+        timestamp.saturating_add_micros(self.message_expiry_micros) < now
+    }
+
     /// Returns how much fuel can be paid with the given balance.
     pub fn remaining_fuel(&self, balance: Amount) -> u64 {
         u64::try_from(balance.saturating_div(self.fuel)).unwrap_or(u64::MAX)
@@ -104,6 +124,10 @@ impl ResourceControlPolicy {
             maximum_bytes_read_per_block: u64::MAX / 2,
             maximum_bytes_written_per_block: u64::MAX / 2,
             messages: Amount::ZERO,
+            maximum_wasm_memory_pages: u64::MAX,
+            maximum_call_depth: u64::MAX,
+            maximum_live_sessions: u64::MAX,
+            message_expiry_micros: u64::MAX,
         }
     }
 
@@ -122,6 +146,10 @@ impl ResourceControlPolicy {
             maximum_bytes_read_per_block: u64::MAX,
             maximum_bytes_written_per_block: u64::MAX,
             messages: Amount::ZERO,
+            maximum_wasm_memory_pages: u64::MAX,
+            maximum_call_depth: u64::MAX,
+            maximum_live_sessions: u64::MAX,
+            message_expiry_micros: u64::MAX,
         }
     }
 
@@ -137,6 +165,10 @@ impl ResourceControlPolicy {
             maximum_bytes_read_per_block: u64::MAX,
             maximum_bytes_written_per_block: u64::MAX,
             messages: Amount::from_atto(1),
+            maximum_wasm_memory_pages: u64::MAX,
+            maximum_call_depth: u64::MAX,
+            maximum_live_sessions: u64::MAX,
+            message_expiry_micros: u64::MAX,
         }
     }
 }
@@ -0,0 +1,182 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Attribution of the fuel consumed by applications to the functions of their contracts.
+//!
+//! Contracts are instrumented so that the runtime is notified whenever one of their functions is
+//! entered or exited. The fuel consumed between two notifications is attributed to the stack of
+//! functions being executed at that point. The collected samples can be written in the "folded
+//! stacks" format, which can be turned into a flamegraph by tools like [`inferno`] or
+//! [`flamegraph.pl`].
+//!
+//! [`inferno`]: https://github.com/jonhoo/inferno
+//! [`flamegraph.pl`]: https://github.com/brendangregg/FlameGraph
+
+use std::{
+    collections::BTreeMap,
+    io,
+    sync::{Arc, Mutex},
+};
+
+/// The name of the module from which instrumented contracts import the profiling functions.
+pub const PROFILER_MODULE: &str = "linera:profiler";
+
+/// The name of the function called by instrumented contracts when a function is entered.
+pub const PROFILER_ENTER_FUNCTION: &str = "enter";
+
+/// The name of the function called by instrumented contracts when a function is exited.
+pub const PROFILER_EXIT_FUNCTION: &str = "exit";
+
+/// A collector of the fuel consumed by each stack of function calls.
+///
+/// Cloning a [`FuelProfiler`] returns a handle to the same collected samples.
+#[derive(Clone, Debug, Default)]
+pub struct FuelProfiler {
+    samples: Arc<Mutex<BTreeMap<String, u64>>>,
+}
+
+impl FuelProfiler {
+    /// Returns the fuel consumed by each stack of function calls, keyed by the names of the
+    /// functions in the stack separated by semicolons.
+    pub fn samples(&self) -> BTreeMap<String, u64> {
+        self.lock().clone()
+    }
+
+    /// Returns the total fuel attributed to functions so far.
+    pub fn total_fuel(&self) -> u64 {
+        self.lock().values().sum()
+    }
+
+    /// Discards the samples collected so far.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Writes the collected samples in the folded stacks format, with one line per stack of
+    /// function calls followed by the fuel it consumed.
+    pub fn write_folded_stacks(&self, mut writer: impl io::Write) -> io::Result<()> {
+        for (stack, fuel) in self.lock().iter() {
+            writeln!(writer, "{stack} {fuel}")?;
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, u64>> {
+        self.samples
+            .lock()
+            .expect("fuel profiler should not panic while holding its lock")
+    }
+}
+
+/// A [`FuelProfiler`] associated with an instrumented module.
+#[derive(Clone, Debug)]
+pub struct ModuleProfiler {
+    profiler: FuelProfiler,
+    function_names: Arc<[String]>,
+}
+
+impl ModuleProfiler {
+    /// Creates a [`ModuleProfiler`] for a module whose functions have the given names, indexed
+    /// by their index in the original module.
+    pub fn new(profiler: FuelProfiler, function_names: Arc<[String]>) -> Self {
+        ModuleProfiler {
+            profiler,
+            function_names,
+        }
+    }
+
+    /// Starts profiling a new instance of the module.
+    pub fn start(&self) -> InstanceProfiler {
+        InstanceProfiler {
+            profiler: self.profiler.clone(),
+            function_names: self.function_names.clone(),
+            stack: Vec::new(),
+            last_fuel_consumed: None,
+        }
+    }
+}
+
+/// The profiling state of a single instance of an instrumented module.
+///
+/// Each instance has its own fuel counter, so calls to other applications are profiled
+/// separately.
+#[derive(Debug)]
+pub struct InstanceProfiler {
+    profiler: FuelProfiler,
+    function_names: Arc<[String]>,
+    stack: Vec<u32>,
+    last_fuel_consumed: Option<u64>,
+}
+
+impl InstanceProfiler {
+    /// Records that the instance entered the function with index `function`, after consuming
+    /// `fuel_consumed` in total.
+    pub fn enter(&mut self, function: u32, fuel_consumed: u64) {
+        self.attribute_fuel(fuel_consumed);
+        self.stack.push(function);
+    }
+
+    /// Records that the instance exited the function with index `function`, after consuming
+    /// `fuel_consumed` in total.
+    pub fn exit(&mut self, function: u32, fuel_consumed: u64) {
+        self.attribute_fuel(fuel_consumed);
+        if let Some(position) = self.stack.iter().rposition(|entry| *entry == function) {
+            self.stack.truncate(position);
+        }
+    }
+
+    /// Attributes the fuel consumed since the previous notification to the current stack.
+    fn attribute_fuel(&mut self, fuel_consumed: u64) {
+        let previous_fuel_consumed = self.last_fuel_consumed.replace(fuel_consumed);
+        let fuel = fuel_consumed.saturating_sub(previous_fuel_consumed.unwrap_or(fuel_consumed));
+        if fuel == 0 || self.stack.is_empty() {
+            return;
+        }
+        let stack = self
+            .stack
+            .iter()
+            .map(|function| self.function_name(*function))
+            .collect::<Vec<_>>()
+            .join(";");
+        *self.profiler.lock().entry(stack).or_default() += fuel;
+    }
+
+    fn function_name(&self, function: u32) -> String {
+        usize::try_from(function)
+            .ok()
+            .and_then(|index| self.function_names.get(index))
+            .cloned()
+            .unwrap_or_else(|| format!("function#{function}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FuelProfiler, ModuleProfiler};
+    use std::sync::Arc;
+
+    /// Tests that fuel is attributed to the stack of functions that consumed it.
+    #[test]
+    fn attributes_fuel_to_call_stacks() {
+        let profiler = FuelProfiler::default();
+        let function_names: Arc<[String]> = vec!["main".to_owned(), "helper".to_owned()].into();
+        let mut instance = ModuleProfiler::new(profiler.clone(), function_names).start();
+
+        instance.enter(0, 100);
+        instance.enter(1, 110);
+        instance.exit(1, 135);
+        instance.enter(1, 140);
+        instance.exit(1, 145);
+        instance.exit(0, 150);
+
+        let samples = profiler.samples();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples["main"], 20);
+        assert_eq!(samples["main;helper"], 30);
+        assert_eq!(profiler.total_fuel(), 50);
+
+        let mut folded_stacks = Vec::new();
+        profiler.write_folded_stacks(&mut folded_stacks).unwrap();
+        assert_eq!(folded_stacks, b"main 20\nmain;helper 30\n");
+    }
+}
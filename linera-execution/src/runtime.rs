@@ -2,20 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    execution::ExecutionStateView, BaseRuntime, CallResult, ContractRuntime, ExecutionError,
-    ExecutionResult, ExecutionRuntimeContext, RuntimeCounts, RuntimeLimits, ServiceRuntime,
-    SessionId, UserApplicationCode, UserApplicationDescription, UserApplicationId,
+    execution::ExecutionStateView,
+    trace::{ExecutionTrace, LogLevel, TraceEvent},
+    BaseRuntime, CallResult, ContractRuntime, ExecutionError, ExecutionResult,
+    ExecutionRuntimeContext, Query, RawExecutionResult, RemoteQueryResponse, Response,
+    RuntimeCounts, RuntimeLimits, ServiceRuntime, SessionId, SystemQuery, SystemResponse,
+    UserApplicationCode, UserApplicationDescription, UserApplicationId,
 };
 use async_lock::{Mutex, MutexGuard, MutexGuardArc, RwLockWriteGuardArc};
 use async_trait::async_trait;
 use custom_debug_derive::Debug;
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{ArithmeticError, Timestamp},
     ensure, hex_debug,
     identifiers::{ChainId, Owner},
 };
 use linera_views::{
-    batch::Batch,
+    batch::{Batch, WriteOperation},
     common::Context,
     key_value_store_view::KeyValueStoreView,
     register_view::RegisterView,
@@ -45,6 +49,8 @@ pub(crate) struct ExecutionRuntime<'a, C, const WRITABLE: bool> {
     runtime_limits: RuntimeLimits,
     /// The current chain ID.
     chain_id: ChainId,
+    /// The pseudo-random seed of the current transaction, if executing a block.
+    random_seed: Option<CryptoHash>,
     /// The current stack of application descriptions.
     applications: Arc<Mutex<&'a mut Vec<ApplicationStatus>>>,
     /// The storage view on the execution state.
@@ -59,6 +65,8 @@ pub(crate) struct ExecutionRuntime<'a, C, const WRITABLE: bool> {
     active_sessions: Arc<Mutex<ActiveSessions>>,
     /// Accumulate the externally visible results (e.g. cross-chain messages) of applications.
     execution_results: Arc<Mutex<&'a mut Vec<ExecutionResult>>>,
+    /// The trace recording the steps of the execution, if tracing is enabled.
+    trace: Arc<Mutex<Option<&'a mut ExecutionTrace>>>,
 }
 
 /// The runtime status of an application.
@@ -70,6 +78,8 @@ pub(crate) struct ApplicationStatus {
     pub(crate) parameters: Vec<u8>,
     /// The authenticated signer for the execution thread, if any.
     pub(crate) signer: Option<Owner>,
+    /// The storage quota from the application description, if any.
+    pub(crate) storage_quota: Option<u64>,
 }
 
 type ActiveViewUserStates<C> =
@@ -101,6 +111,7 @@ where
     ViewError: From<C::Error>,
     C::Extra: ExecutionRuntimeContext,
 {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         chain_id: ChainId,
         applications: &'a mut Vec<ApplicationStatus>,
@@ -109,6 +120,8 @@ where
         execution_results: &'a mut Vec<ExecutionResult>,
         fuel: u64,
         runtime_limits: RuntimeLimits,
+        random_seed: Option<CryptoHash>,
+        trace: Option<&'a mut ExecutionTrace>,
     ) -> Self {
         assert_eq!(chain_id, execution_state.context().extra().chain_id());
         let remaining_fuel = Arc::new(AtomicU64::new(fuel));
@@ -122,6 +135,7 @@ where
             bytes_written,
             runtime_limits,
             chain_id,
+            random_seed,
             applications: Arc::new(Mutex::new(applications)),
             execution_state: Arc::new(Mutex::new(execution_state)),
             session_manager: Arc::new(Mutex::new(session_manager)),
@@ -129,6 +143,7 @@ where
             active_view_user_states: Arc::default(),
             active_sessions: Arc::default(),
             execution_results: Arc::new(Mutex::new(execution_results)),
+            trace: Arc::new(Mutex::new(trace)),
         }
     }
 
@@ -172,6 +187,14 @@ where
             .expect("single-threaded execution should not lock `execution_results`")
     }
 
+    /// Returns the storage quota of the current application, if any.
+    fn storage_quota(&self) -> Option<u64> {
+        self.applications_mut()
+            .last()
+            .expect("at least one application description should be present in the stack")
+            .storage_quota
+    }
+
     async fn load_application(
         &self,
         id: UserApplicationId,
@@ -213,15 +236,33 @@ where
         Ok(())
     }
 
+    /// Checks that a new nested call would not exceed the maximum call depth.
+    fn check_call_depth(&self) -> Result<(), ExecutionError> {
+        let maximum_call_depth = self.runtime_limits.maximum_call_depth;
+        // The stack of applications includes the application that started the transaction.
+        let depth = self.applications_mut().len() as u64;
+        ensure!(
+            depth <= maximum_call_depth,
+            ExecutionError::ExcessiveCallDepth(maximum_call_depth)
+        );
+        Ok(())
+    }
+
     fn make_sessions(
         &self,
         new_sessions: Vec<Vec<u8>>,
         creator_id: UserApplicationId,
         receiver_id: UserApplicationId,
-    ) -> Vec<SessionId> {
+    ) -> Result<Vec<SessionId>, ExecutionError> {
         let mut manager = self.session_manager_mut();
         let manager = manager.deref_mut();
         let states = &mut manager.states;
+        let maximum_live_sessions = self.runtime_limits.maximum_live_sessions;
+        ensure!(
+            (states.len() as u64).saturating_add(new_sessions.len() as u64)
+                <= maximum_live_sessions,
+            ExecutionError::ExcessiveSessions(maximum_live_sessions)
+        );
         let counter = manager.counters.entry(creator_id).or_default();
         let mut session_ids = Vec::new();
         for data in new_sessions {
@@ -237,7 +278,7 @@ where
             };
             states.insert(id, Arc::new(Mutex::new(state)));
         }
-        session_ids
+        Ok(session_ids)
     }
 
     fn try_load_session(
@@ -310,6 +351,17 @@ where
 }
 
 impl<'a, C, const W: bool> ExecutionRuntime<'a, C, W> {
+    /// Records an event in the execution trace, if tracing is enabled.
+    fn record(&self, event: impl FnOnce() -> TraceEvent) {
+        let mut trace = self
+            .trace
+            .try_lock()
+            .expect("single-threaded execution should not lock `trace`");
+        if let Some(trace) = trace.as_mut() {
+            trace.record(event());
+        }
+    }
+
     fn increment_num_reads(&self) -> Result<(), ExecutionError> {
         self.num_reads.fetch_add(1, Ordering::Relaxed);
         let bytes = self.num_reads.load(Ordering::Acquire);
@@ -392,6 +444,10 @@ where
             .await?
             .get()
             .to_vec();
+        self.record(|| TraceEvent::ReadState {
+            application_id: self.application_id(),
+            size: state.len() as u64,
+        });
         Ok(state)
     }
 
@@ -429,6 +485,11 @@ where
         if let Some(value) = &result {
             self.increment_bytes_read(value.len() as u64)?;
         }
+        self.record(|| TraceEvent::ReadKey {
+            application_id: self.application_id(),
+            key,
+            value: result.clone(),
+        });
         Ok(result)
     }
 
@@ -448,6 +509,11 @@ where
             read_size += key.len();
         }
         self.increment_bytes_read(read_size as u64)?;
+        self.record(|| TraceEvent::FindKeys {
+            application_id: self.application_id(),
+            key_prefix,
+            count: keys.len() as u64,
+        });
         Ok(keys)
     }
 
@@ -467,10 +533,94 @@ where
             read_size += key.len() + value.len();
         }
         self.increment_bytes_read(read_size as u64)?;
+        self.record(|| TraceEvent::FindKeyValues {
+            application_id: self.application_id(),
+            key_prefix,
+            count: key_values.len() as u64,
+        });
         Ok(key_values)
     }
 }
 
+impl<'a, C, const W: bool> ExecutionRuntime<'a, C, W>
+where
+    C: Context + Clone + Send + Sync + 'static,
+    ViewError: From<C::Error>,
+{
+    /// Computes the total size of the keys and values stored in `view` once `batch` is
+    /// written, given the current total `stored_bytes`.
+    ///
+    /// Reading the sizes of the entries replaced or deleted by the batch is charged like any
+    /// other read of the application.
+    async fn stored_bytes_after_batch(
+        &self,
+        view: &KeyValueStoreView<C>,
+        mut stored_bytes: u64,
+        batch: &Batch,
+    ) -> Result<u64, ExecutionError> {
+        // The sizes of the entries already modified by the batch, or `None` if they were deleted.
+        let mut updated_entries = BTreeMap::<Vec<u8>, Option<u64>>::new();
+        for operation in &batch.operations {
+            match operation {
+                WriteOperation::Put { key, value } => {
+                    let previous_size = self.entry_size(view, &updated_entries, key).await?;
+                    let size = (key.len() + value.len()) as u64;
+                    stored_bytes = stored_bytes
+                        .saturating_sub(previous_size)
+                        .saturating_add(size);
+                    updated_entries.insert(key.clone(), Some(size));
+                }
+                WriteOperation::Delete { key } => {
+                    let previous_size = self.entry_size(view, &updated_entries, key).await?;
+                    stored_bytes = stored_bytes.saturating_sub(previous_size);
+                    updated_entries.insert(key.clone(), None);
+                }
+                WriteOperation::DeletePrefix { key_prefix } => {
+                    self.increment_num_reads()?;
+                    for (suffix, value) in view.find_key_values_by_prefix(key_prefix).await? {
+                        let mut key = key_prefix.clone();
+                        key.extend(suffix);
+                        let size = (key.len() + value.len()) as u64;
+                        self.increment_bytes_read(size)?;
+                        if let btree_map::Entry::Vacant(entry) = updated_entries.entry(key) {
+                            stored_bytes = stored_bytes.saturating_sub(size);
+                            entry.insert(None);
+                        }
+                    }
+                    for (key, size) in updated_entries.iter_mut() {
+                        if key.starts_with(key_prefix) {
+                            if let Some(size) = size.take() {
+                                stored_bytes = stored_bytes.saturating_sub(size);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(stored_bytes)
+    }
+
+    /// Returns the size of the entry at `key`, taking into account the `updated_entries` that
+    /// haven't been written to the `view` yet.
+    async fn entry_size(
+        &self,
+        view: &KeyValueStoreView<C>,
+        updated_entries: &BTreeMap<Vec<u8>, Option<u64>>,
+        key: &[u8],
+    ) -> Result<u64, ExecutionError> {
+        if let Some(size) = updated_entries.get(key) {
+            return Ok(size.unwrap_or_default());
+        }
+        self.increment_num_reads()?;
+        let size = view
+            .get(key)
+            .await?
+            .map_or(0, |value| (key.len() + value.len()) as u64);
+        self.increment_bytes_read(size)?;
+        Ok(size)
+    }
+}
+
 #[async_trait]
 impl<'a, C> ServiceRuntime for ExecutionRuntime<'a, C, false>
 where
@@ -494,11 +644,47 @@ where
             id: queried_id,
             parameters: description.parameters,
             signer: None,
+            storage_quota: description.storage_quota,
         });
         let value = code.handle_query(&query_context, self, argument).await?;
         self.applications_mut().pop();
         Ok(value)
     }
+
+    async fn try_query_remote_application(
+        &self,
+        chain_id: ChainId,
+        queried_id: UserApplicationId,
+        argument: &[u8],
+    ) -> Result<RemoteQueryResponse, ExecutionError> {
+        let runtime_context = self.execution_state_mut().context().extra().clone();
+        let query = Query::User {
+            application_id: queried_id,
+            bytes: argument.to_vec(),
+        };
+        let RemoteQueryResponse { response, height } = runtime_context
+            .query_remote_application(chain_id, query)
+            .await?;
+        match response {
+            Response::User(response) => Ok(RemoteQueryResponse { response, height }),
+            Response::System(_) => unreachable!("cannot get a system response for a user query"),
+        }
+    }
+
+    async fn try_query_remote_system(
+        &self,
+        chain_id: ChainId,
+        query: SystemQuery,
+    ) -> Result<RemoteQueryResponse<SystemResponse>, ExecutionError> {
+        let runtime_context = self.execution_state_mut().context().extra().clone();
+        let RemoteQueryResponse { response, height } = runtime_context
+            .query_remote_application(chain_id, Query::System(query))
+            .await?;
+        match response {
+            Response::System(response) => Ok(RemoteQueryResponse { response, height }),
+            Response::User(_) => unreachable!("cannot get a user response for a system query"),
+        }
+    }
 }
 
 #[async_trait]
@@ -527,6 +713,18 @@ where
 
     fn set_remaining_fuel(&self, remaining_fuel: u64) {
         self.remaining_fuel.store(remaining_fuel, Ordering::Release);
+        self.record(|| TraceEvent::Fuel {
+            application_id: self.application_id(),
+            remaining_fuel,
+        });
+    }
+
+    fn runtime_limits(&self) -> RuntimeLimits {
+        self.runtime_limits
+    }
+
+    fn random_seed(&self) -> Result<CryptoHash, ExecutionError> {
+        self.random_seed.ok_or(ExecutionError::MissingRandomSeed)
     }
 
     async fn try_read_and_lock_my_state(&self) -> Result<Vec<u8>, ExecutionError> {
@@ -536,6 +734,10 @@ where
             .try_load_entry_mut(&self.application_id())
             .await?;
         let state = view.get().to_vec();
+        self.record(|| TraceEvent::ReadState {
+            application_id: self.application_id(),
+            size: state.len() as u64,
+        });
         // Remember the view. This will prevent reentrancy.
         self.active_simple_user_states_mut()
             .insert(self.application_id(), view);
@@ -549,6 +751,16 @@ where
             .remove(&self.application_id())
         {
             Some(mut view) => {
+                if let Some(storage_quota) = self.storage_quota() {
+                    ensure!(
+                        state.len() as u64 <= storage_quota,
+                        ExecutionError::ExcessiveStorage(storage_quota)
+                    );
+                }
+                self.record(|| TraceEvent::WriteState {
+                    application_id: self.application_id(),
+                    size: state.len() as u64,
+                });
                 // Set the state.
                 view.set(state);
                 Ok(())
@@ -565,15 +777,76 @@ where
     async fn write_batch_and_unlock(&self, batch: Batch) -> Result<(), ExecutionError> {
         let size = batch.size() as u64;
         self.increment_bytes_written(size)?;
-        // Write the batch and make the view available again.
-        match self
-            .active_view_user_states_mut()
-            .await
-            .remove(&self.application_id())
-        {
-            Some(mut view) => Ok(view.write_batch(batch).await?),
-            None => Err(ExecutionError::ApplicationStateNotLocked),
+        self.record(|| TraceEvent::WriteBatch {
+            application_id: self.application_id(),
+            size,
+        });
+        let application_id = self.application_id();
+        let mut active_view_user_states = self.active_view_user_states_mut().await;
+        let view = active_view_user_states
+            .get(&application_id)
+            .ok_or(ExecutionError::ApplicationStateNotLocked)?;
+        // Check the storage quota of the application, if any, before writing the batch. The
+        // view remains locked if the quota is exceeded.
+        let stored_bytes = match self.storage_quota() {
+            None => None,
+            Some(storage_quota) => {
+                let stored_bytes = self
+                    .execution_state_mut()
+                    .view_users_stored_bytes
+                    .get(&application_id)
+                    .await?
+                    .unwrap_or_default();
+                let stored_bytes = self
+                    .stored_bytes_after_batch(view, stored_bytes, &batch)
+                    .await?;
+                ensure!(
+                    stored_bytes <= storage_quota,
+                    ExecutionError::ExcessiveStorage(storage_quota)
+                );
+                Some(stored_bytes)
+            }
+        };
+        // Make the view available again.
+        let mut view = active_view_user_states
+            .remove(&application_id)
+            .expect("the view was found above");
+        drop(active_view_user_states);
+        view.write_batch(batch).await?;
+        if let Some(stored_bytes) = stored_bytes {
+            self.execution_state_mut()
+                .view_users_stored_bytes
+                .insert(&application_id, stored_bytes)?;
+        }
+        Ok(())
+    }
+
+    fn emit_event(&self, topic: Vec<u8>, value: Vec<u8>) -> Result<(), ExecutionError> {
+        // Events are stored in the block, so they are charged like written bytes.
+        let size = (topic.len() + value.len()) as u64;
+        self.increment_bytes_written(size)?;
+        let result = RawExecutionResult {
+            events: vec![(topic, value)],
+            ..RawExecutionResult::default()
+        };
+        self.execution_results_mut()
+            .push(ExecutionResult::User(self.application_id(), result));
+        Ok(())
+    }
+
+    fn log(&self, level: LogLevel, message: String) {
+        match level {
+            LogLevel::Trace => tracing::trace!("{message}"),
+            LogLevel::Debug => tracing::debug!("{message}"),
+            LogLevel::Info => tracing::info!("{message}"),
+            LogLevel::Warn => tracing::warn!("{message}"),
+            LogLevel::Error => tracing::error!("{message}"),
         }
+        self.record(|| TraceEvent::Log {
+            application_id: self.application_id(),
+            level,
+            message,
+        });
     }
 
     async fn try_call_application(
@@ -583,11 +856,19 @@ where
         argument: &[u8],
         forwarded_sessions: Vec<SessionId>,
     ) -> Result<CallResult, ExecutionError> {
+        self.check_call_depth()?;
         let caller = self
             .applications_mut()
             .last()
             .expect("caller must exist")
             .clone();
+        self.record(|| TraceEvent::ApplicationCall {
+            caller_id: caller.id,
+            callee_id,
+            authenticated,
+            argument: argument.to_vec(),
+            forwarded_sessions: forwarded_sessions.clone(),
+        });
         // Load the application.
         let (code, description) = self.load_application(callee_id).await?;
         // Change the owners of forwarded sessions.
@@ -608,6 +889,7 @@ where
             parameters: description.parameters,
             // Allow further nested calls to be authenticated if this one is.
             signer: authenticated_signer,
+            storage_quota: description.storage_quota,
         });
         let raw_result = code
             .handle_application_call(&callee_context, self, argument, forwarded_sessions)
@@ -621,7 +903,7 @@ where
                 .with_authenticated_signer(authenticated_signer),
         ));
         let sessions =
-            self.make_sessions(raw_result.create_sessions, callee_id, self.application_id());
+            self.make_sessions(raw_result.create_sessions, callee_id, self.application_id())?;
         let result = CallResult {
             value: raw_result.value,
             sessions,
@@ -636,12 +918,20 @@ where
         argument: &[u8],
         forwarded_sessions: Vec<SessionId>,
     ) -> Result<CallResult, ExecutionError> {
+        self.check_call_depth()?;
         let callee_id = session_id.application_id;
         let caller = self
             .applications_mut()
             .last()
             .expect("caller must exist")
             .clone();
+        self.record(|| TraceEvent::SessionCall {
+            caller_id: caller.id,
+            session_id,
+            authenticated,
+            argument: argument.to_vec(),
+            forwarded_sessions: forwarded_sessions.clone(),
+        });
         // Load the application.
         let (code, description) = self.load_application(callee_id).await?;
         // Change the owners of forwarded sessions.
@@ -664,6 +954,7 @@ where
             parameters: description.parameters,
             // Allow further nested calls to be authenticated if this one is.
             signer: authenticated_signer,
+            storage_quota: description.storage_quota,
         });
         let raw_result = code
             .handle_session_call(
@@ -694,7 +985,7 @@ where
             inner_result.create_sessions,
             callee_id,
             self.application_id(),
-        );
+        )?;
         let result = CallResult {
             value: inner_result.value,
             sessions,
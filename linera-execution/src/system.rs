@@ -3,10 +3,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    committee::{Committee, Epoch},
-    ApplicationRegistryView, Bytecode, BytecodeLocation, ChainOwnership, ChannelName,
-    ChannelSubscription, Destination, MessageContext, OperationContext, QueryContext,
-    RawExecutionResult, RawOutgoingMessage, UserApplicationDescription, UserApplicationId,
+    committee::{Committee, Epoch, ValidatorChange},
+    ApplicationRegistryView, ApprovalThreshold, Bytecode, BytecodeLocation, ChainOwnership,
+    ChannelName, ChannelSubscription, Destination, InboxPolicy, MessageContext, MessageKind,
+    OperationContext, QueryContext, RawExecutionResult, RawOutgoingMessage, TimeoutConfig,
+    UserApplicationDescription, UserApplicationId,
 };
 use async_graphql::Enum;
 use custom_debug_derive::Debug;
@@ -18,10 +19,11 @@ use linera_base::{
 };
 use linera_views::{
     common::Context,
+    log_view::LogView,
     map_view::MapView,
     register_view::RegisterView,
     set_view::SetView,
-    views::{HashableView, View, ViewError},
+    views::{ClonableView, HashableView, View, ViewError},
 };
 use serde::{Deserialize, Serialize};
 use std::{
@@ -45,7 +47,7 @@ pub static CREATE_APPLICATION_MESSAGE_INDEX: u32 = 0;
 pub static PUBLISH_BYTECODE_MESSAGE_INDEX: u32 = 0;
 
 /// A view accessing the execution state of the system of a chain.
-#[derive(Debug, HashableView)]
+#[derive(Debug, ClonableView, HashableView)]
 pub struct SystemExecutionStateView<C> {
     /// How the chain was created. May be unknown for inactive chains.
     pub description: RegisterView<C, Option<ChainDescription>>,
@@ -60,8 +62,18 @@ pub struct SystemExecutionStateView<C> {
     /// small. Plus, currently, we would create the `BTreeMap` anyway in various places
     /// (e.g. the `OpenChain` operation).
     pub committees: RegisterView<C, BTreeMap<Epoch, Committee>>,
+    /// The times after which the committees of past epochs are no longer trusted.
+    pub committee_expirations: RegisterView<C, BTreeMap<Epoch, Timestamp>>,
     /// Ownership of the chain.
     pub ownership: RegisterView<C, ChainOwnership>,
+    /// Whether the chain was closed. Closed chains only return incoming messages to their
+    /// senders.
+    pub closed: RegisterView<C, bool>,
+    /// The messages that the chain accepts in its inboxes.
+    pub inbox_policy: RegisterView<C, InboxPolicy>,
+    /// The inbox policies that were replaced, each with the timestamp of the first block
+    /// that replaced it: messages are checked against the policy at the time they were sent.
+    pub previous_inbox_policies: LogView<C, (Timestamp, InboxPolicy)>,
     /// Balance of the chain (unattributed).
     pub balance: RegisterView<C, Amount>,
     /// Balances attributed to a given owner.
@@ -81,7 +93,11 @@ pub struct SystemExecutionState {
     pub admin_id: Option<ChainId>,
     pub subscriptions: BTreeSet<ChannelSubscription>,
     pub committees: BTreeMap<Epoch, Committee>,
+    pub committee_expirations: BTreeMap<Epoch, Timestamp>,
     pub ownership: ChainOwnership,
+    pub closed: bool,
+    pub inbox_policy: InboxPolicy,
+    pub previous_inbox_policies: Vec<(Timestamp, InboxPolicy)>,
     pub balance: Amount,
     pub balances: BTreeMap<Owner, Amount>,
     pub timestamp: Timestamp,
@@ -117,14 +133,21 @@ pub enum SystemOperation {
         epoch: Epoch,
         committees: BTreeMap<Epoch, Committee>,
     },
-    /// Closes the chain.
-    CloseChain,
+    /// Closes the chain. If a recipient is given, the remaining balances are transferred
+    /// first: the unattributed balance to the recipient, and the balance of each owner to the
+    /// same owner on the recipient's chain; otherwise they are burnt. The chain then only
+    /// returns incoming messages to their senders.
+    CloseChain { recipient: Option<Recipient> },
     /// Changes the authentication key of the chain.
     ChangeOwner { new_public_key: PublicKey },
     /// Changes the authentication key of the chain.
     ChangeMultipleOwners {
         new_public_keys: Vec<(PublicKey, u64)>,
         multi_leader_rounds: RoundNumber,
+        /// If set, some blocks must be approved by owners with a sufficient total weight.
+        approval_threshold: Option<ApprovalThreshold>,
+        /// How long each round lasts before validators vote to time out the round leader.
+        timeout_config: TimeoutConfig,
     },
     /// Subscribes to a system channel.
     Subscribe {
@@ -136,6 +159,9 @@ pub enum SystemOperation {
         chain_id: ChainId,
         channel: SystemChannel,
     },
+    /// Changes the messages that the chain accepts in its inboxes. This only applies to
+    /// messages received afterwards.
+    ChangeInboxPolicy { policy: InboxPolicy },
     /// Publishes a new application bytecode.
     PublishBytecode {
         contract: Bytecode,
@@ -151,6 +177,9 @@ pub enum SystemOperation {
         #[debug(with = "hex_debug")]
         initialization_argument: Vec<u8>,
         required_application_ids: Vec<UserApplicationId>,
+        /// The maximum total size of the keys and values the application may store on each
+        /// chain, if limited.
+        storage_quota: Option<u64>,
     },
     /// Requests a message from another chain to register a user application on this chain.
     RequestApplication {
@@ -172,13 +201,25 @@ pub enum AdminOperation {
     /// blocks from the retired epoch will not be accepted until they are followed (hence
     /// re-certified) by a block certified by a recent committee.
     RemoveCommittee { epoch: Epoch },
+    /// Starts a new epoch with the committee obtained by applying the given changes to the
+    /// current one. The committees of previous epochs are still trusted during the grace
+    /// period, so that chains have time to migrate, and are removed automatically afterwards.
+    ChangeValidators {
+        changes: Vec<ValidatorChange>,
+        grace_period_micros: u64,
+    },
 }
 
 /// A system message meant to be executed on a remote chain.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub enum SystemMessage {
-    /// Credits `amount` units of value to the account.
-    Credit { account: Account, amount: Amount },
+    /// Credits `amount` units of value to the account. If the message is bounced, the
+    /// amount is refunded to the `source` owner (or the chain balance) on the sender chain.
+    Credit {
+        account: Account,
+        amount: Amount,
+        source: Option<Owner>,
+    },
     /// Withdraws `amount` units of value from the account and starts a transfer to credit
     /// the recipient. The message must be properly authenticated. Receiver chains may
     /// refuse it depending on their configuration.
@@ -195,10 +236,12 @@ pub enum SystemMessage {
         epoch: Epoch,
         committees: BTreeMap<Epoch, Committee>,
     },
-    /// Sets the current epoch and the recognized committees.
+    /// Sets the current epoch and the recognized committees, together with the times after
+    /// which the committees of past epochs are removed.
     SetCommittees {
         epoch: Epoch,
         committees: BTreeMap<Epoch, Committee>,
+        expirations: BTreeMap<Epoch, Timestamp>,
     },
     /// Subscribes to a channel.
     Subscribe {
@@ -264,14 +307,62 @@ impl SystemMessage {
 }
 
 /// A query to the system state.
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
-pub struct SystemQuery;
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum SystemQuery {
+    /// Queries the chain ID and the unattributed balance of the chain.
+    Balance,
+    /// Queries the balances attributed to each owner.
+    Balances,
+    /// Queries the current ownership of the chain.
+    Ownership,
+    /// Queries the committees that the chain trusts, indexed by epoch.
+    Committees,
+    /// Queries the channels that the chain is subscribed to.
+    Subscriptions,
+    /// Queries the published bytecodes and the applications known by the chain.
+    Registry,
+}
+
+impl FromStr for SystemQuery {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "balance" => Ok(SystemQuery::Balance),
+            "balances" => Ok(SystemQuery::Balances),
+            "ownership" => Ok(SystemQuery::Ownership),
+            "committees" => Ok(SystemQuery::Committees),
+            "subscriptions" => Ok(SystemQuery::Subscriptions),
+            "registry" => Ok(SystemQuery::Registry),
+            _ => anyhow::bail!(
+                "Expecting one of `balance`, `balances`, `ownership`, `committees`, \
+                `subscriptions` or `registry`"
+            ),
+        }
+    }
+}
 
 /// The response to a system query.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
-pub struct SystemResponse {
-    pub chain_id: ChainId,
-    pub balance: Amount,
+pub enum SystemResponse {
+    /// The chain ID and the unattributed balance of the chain.
+    Balance { chain_id: ChainId, balance: Amount },
+    /// The balances attributed to each owner.
+    Balances(BTreeMap<Owner, Amount>),
+    /// The current ownership of the chain.
+    Ownership(ChainOwnership),
+    /// The committees that the chain trusts, indexed by epoch.
+    Committees {
+        current_epoch: Option<Epoch>,
+        committees: BTreeMap<Epoch, Committee>,
+    },
+    /// The channels that the chain is subscribed to.
+    Subscriptions(Vec<ChannelSubscription>),
+    /// The published bytecodes and the applications known by the chain.
+    Registry {
+        published_bytecodes: BTreeMap<BytecodeId, BytecodeLocation>,
+        known_applications: BTreeMap<UserApplicationId, UserApplicationDescription>,
+    },
 }
 
 /// The channels available in the system application.
@@ -396,6 +487,10 @@ pub enum SystemExecutionError {
     InvalidNewChainAdminId(ChainId),
     #[error("Invalid committees")]
     InvalidCommittees,
+    #[error("The approval threshold exceeds the total weight of the chain owners")]
+    UnreachableApprovalThreshold,
+    #[error("Invalid round timeout configuration")]
+    InvalidTimeoutConfig,
     #[error("{epoch:?} is not recognized by chain {chain_id:}")]
     InvalidEpoch { chain_id: ChainId, epoch: Epoch },
     #[error("Transfer must have positive amount")]
@@ -406,6 +501,8 @@ pub enum SystemExecutionError {
         "The transferred amount must be not exceed the current chain balance: {current_balance}"
     )]
     InsufficientFunding { current_balance: Amount },
+    #[error("Cannot transfer the balances of a closed chain to itself")]
+    InvalidClosingRecipient,
     #[error("Claim must have positive amount")]
     IncorrectClaimAmount,
     #[error("Claim must be authenticated by the right signer")]
@@ -416,6 +513,8 @@ pub enum SystemExecutionError {
     InvalidCommitteeCreation,
     #[error("Failed to remove committee")]
     InvalidCommitteeRemoval,
+    #[error("Failed to apply the validator changes to the current committee")]
+    InvalidValidatorChanges,
     #[error(
         "Attempted to subscribe to the admin channel ({1}) of this chain's ({0}) admin chain {1}"
     )]
@@ -488,6 +587,16 @@ where
                 epoch,
             } => {
                 let child_id = ChainId::child(context.next_message_id());
+                ensure!(
+                    ownership.is_approval_threshold_reachable(),
+                    SystemExecutionError::UnreachableApprovalThreshold
+                );
+                ensure!(
+                    ownership
+                        .timeout_config()
+                        .map_or(true, TimeoutConfig::is_valid),
+                    SystemExecutionError::InvalidTimeoutConfig
+                );
                 ensure!(
                     self.admin_id.get().as_ref() == Some(admin_id),
                     SystemExecutionError::InvalidNewChainAdminId(child_id)
@@ -507,6 +616,8 @@ where
                     destination: Destination::Recipient(child_id),
                     authenticated: false,
                     is_skippable: false,
+                    kind: MessageKind::Simple,
+                    grant: Amount::ZERO,
                     message: SystemMessage::OpenChain {
                         ownership: ownership.clone(),
                         committees: committees.clone(),
@@ -522,6 +633,8 @@ where
                     destination: Destination::Recipient(*admin_id),
                     authenticated: false,
                     is_skippable: false,
+                    kind: MessageKind::Simple,
+                    grant: Amount::ZERO,
                     message: SystemMessage::Subscribe {
                         id: child_id,
                         subscription,
@@ -535,31 +648,72 @@ where
             ChangeMultipleOwners {
                 new_public_keys,
                 multi_leader_rounds,
+                approval_threshold,
+                timeout_config,
             } => {
-                self.ownership.set(ChainOwnership::multiple(
+                let ownership = ChainOwnership::multiple(
                     new_public_keys.iter().map(|(key, weight)| (*key, *weight)),
                     *multi_leader_rounds,
-                ));
+                )
+                .with_approval_threshold(*approval_threshold)
+                .with_timeout_config(*timeout_config);
+                ensure!(
+                    ownership.is_approval_threshold_reachable(),
+                    SystemExecutionError::UnreachableApprovalThreshold
+                );
+                ensure!(
+                    ownership
+                        .timeout_config()
+                        .map_or(true, TimeoutConfig::is_valid),
+                    SystemExecutionError::InvalidTimeoutConfig
+                );
+                self.ownership.set(ownership);
             }
-            CloseChain => {
-                self.ownership.set(ChainOwnership::default());
-                // Unsubscribe to all channels.
-                self.subscriptions
-                    .for_each_index(|subscription| {
-                        let message = RawOutgoingMessage {
-                            destination: Destination::Recipient(subscription.chain_id),
-                            authenticated: false,
-                            is_skippable: false,
-                            message: SystemMessage::Unsubscribe {
-                                id: context.chain_id,
-                                subscription,
-                            },
-                        };
-                        result.messages.push(message);
-                        Ok(())
-                    })
-                    .await?;
-                self.subscriptions.clear();
+            ChangeInboxPolicy { policy } => {
+                // Only the policy before the first change at a given time is ever needed.
+                let timestamp = *self.timestamp.get();
+                let last_change = match self.previous_inbox_policies.count().checked_sub(1) {
+                    Some(index) => self.previous_inbox_policies.get(index).await?,
+                    None => None,
+                };
+                if last_change.map_or(true, |(changed, _)| changed < timestamp) {
+                    let previous_policy = self.inbox_policy.get().clone();
+                    self.previous_inbox_policies
+                        .push((timestamp, previous_policy));
+                }
+                self.inbox_policy.set(policy.clone());
+            }
+            CloseChain { recipient } => {
+                // Without a recipient, the remaining balances are burnt.
+                let recipient = recipient.as_ref().unwrap_or(&Recipient::Burn);
+                self.sweep_balances(context, recipient, &mut result).await?;
+                // The owners keep the chain, so that they can still return the incoming
+                // messages to their senders.
+                self.closed.set(true);
+                // Unsubscribe to all channels, except the admin channel: the chain needs the
+                // current committees to keep producing blocks.
+                let admin_subscription = self.admin_id.get().map(|admin_id| ChannelSubscription {
+                    chain_id: admin_id,
+                    name: SystemChannel::Admin.name(),
+                });
+                for subscription in self.subscriptions.indices().await? {
+                    if Some(&subscription) == admin_subscription.as_ref() {
+                        continue;
+                    }
+                    self.subscriptions.remove(&subscription)?;
+                    let message = RawOutgoingMessage {
+                        destination: Destination::Recipient(subscription.chain_id),
+                        authenticated: false,
+                        is_skippable: false,
+                        kind: MessageKind::Simple,
+                        grant: Amount::ZERO,
+                        message: SystemMessage::Unsubscribe {
+                            id: context.chain_id,
+                            subscription,
+                        },
+                    };
+                    result.messages.push(message);
+                }
             }
             Transfer {
                 owner,
@@ -593,9 +747,12 @@ where
                         destination: Destination::Recipient(account.chain_id),
                         authenticated: false,
                         is_skippable: false,
+                        kind: MessageKind::Tracked,
+                        grant: Amount::ZERO,
                         message: SystemMessage::Credit {
                             amount: *amount,
                             account: *account,
+                            source: *owner,
                         },
                     };
                     result.messages.push(message);
@@ -620,6 +777,8 @@ where
                     destination: Destination::Recipient(*target),
                     authenticated: true,
                     is_skippable: false,
+                    kind: MessageKind::Simple,
+                    grant: Amount::ZERO,
                     message: SystemMessage::Withdraw {
                         amount: *amount,
                         account: Account {
@@ -645,32 +804,39 @@ where
                         );
                         self.committees.get_mut().insert(*epoch, committee.clone());
                         self.epoch.set(Some(*epoch));
-                        let message = RawOutgoingMessage {
-                            destination: Destination::Subscribers(SystemChannel::Admin.name()),
-                            authenticated: false,
-                            is_skippable: false,
-                            message: SystemMessage::SetCommittees {
-                                epoch: *epoch,
-                                committees: self.committees.get().clone(),
-                            },
-                        };
-                        result.messages.push(message);
+                        result.messages.push(self.set_committees_message());
                     }
                     AdminOperation::RemoveCommittee { epoch } => {
                         ensure!(
                             self.committees.get_mut().remove(epoch).is_some(),
                             SystemExecutionError::InvalidCommitteeRemoval
                         );
-                        let message = RawOutgoingMessage {
-                            destination: Destination::Subscribers(SystemChannel::Admin.name()),
-                            authenticated: false,
-                            is_skippable: false,
-                            message: SystemMessage::SetCommittees {
-                                epoch: self.epoch.get().expect("chain is active"),
-                                committees: self.committees.get().clone(),
-                            },
-                        };
-                        result.messages.push(message);
+                        self.committee_expirations.get_mut().remove(epoch);
+                        result.messages.push(self.set_committees_message());
+                    }
+                    AdminOperation::ChangeValidators {
+                        changes,
+                        grace_period_micros,
+                    } => {
+                        let (epoch, committee) = self.current_committee().expect("chain is active");
+                        let committee = committee
+                            .with_changes(changes)
+                            .ok_or(SystemExecutionError::InvalidValidatorChanges)?;
+                        let new_epoch = epoch.try_add_one()?;
+                        let expiration = self
+                            .timestamp
+                            .get()
+                            .saturating_add_micros(*grace_period_micros);
+                        for old_epoch in self.committees.get().keys() {
+                            self.committee_expirations
+                                .get_mut()
+                                .entry(*old_epoch)
+                                .or_insert(expiration);
+                        }
+                        self.committees.get_mut().insert(new_epoch, committee);
+                        self.epoch.set(Some(new_epoch));
+                        self.remove_expired_committees();
+                        result.messages.push(self.set_committees_message());
                     }
                 }
             }
@@ -698,6 +864,8 @@ where
                     destination: Destination::Recipient(*chain_id),
                     authenticated: false,
                     is_skippable: false,
+                    kind: MessageKind::Simple,
+                    grant: Amount::ZERO,
                     message: SystemMessage::Subscribe {
                         id: context.chain_id,
                         subscription,
@@ -719,6 +887,8 @@ where
                     destination: Destination::Recipient(*chain_id),
                     authenticated: false,
                     is_skippable: false,
+                    kind: MessageKind::Simple,
+                    grant: Amount::ZERO,
                     message: SystemMessage::Unsubscribe {
                         id: context.chain_id,
                         subscription,
@@ -733,6 +903,8 @@ where
                     destination: Destination::Recipient(context.chain_id),
                     authenticated: false,
                     is_skippable: false,
+                    kind: MessageKind::Simple,
+                    grant: Amount::ZERO,
                     message: SystemMessage::BytecodePublished {
                         operation_index: context.index,
                     },
@@ -744,6 +916,7 @@ where
                 parameters,
                 initialization_argument,
                 required_application_ids,
+                storage_quota,
             } => {
                 let id = UserApplicationId {
                     bytecode_id: *bytecode_id,
@@ -754,6 +927,7 @@ where
                         id,
                         parameters.clone(),
                         required_application_ids.clone(),
+                        *storage_quota,
                     )
                     .await?;
                 // Send a message to ourself to increment the message ID.
@@ -761,6 +935,8 @@ where
                     destination: Destination::Recipient(context.chain_id),
                     authenticated: false,
                     is_skippable: false,
+                    kind: MessageKind::Simple,
+                    grant: Amount::ZERO,
                     message: SystemMessage::ApplicationCreated,
                 };
                 result.messages.push(message);
@@ -774,6 +950,8 @@ where
                     destination: Destination::Recipient(*chain_id),
                     authenticated: false,
                     is_skippable: false,
+                    kind: MessageKind::Simple,
+                    grant: Amount::ZERO,
                     message: SystemMessage::RequestApplication(*application_id),
                 };
                 result.messages.push(message);
@@ -799,18 +977,22 @@ where
     ) -> Result<RawExecutionResult<SystemMessage>, SystemExecutionError> {
         let mut result = RawExecutionResult::default();
         use SystemMessage::*;
+        if context.is_bouncing {
+            // Only credits are tracked: refund the amount to its source.
+            if let Credit { amount, source, .. } = message {
+                self.credit(source.as_ref(), *amount).await?;
+            }
+            return Ok(result);
+        }
+        if context.acknowledgement.is_some() {
+            // System messages are never acknowledged.
+            return Ok(result);
+        }
         match message {
-            Credit { amount, account } if context.chain_id == account.chain_id => {
-                match &account.owner {
-                    None => {
-                        let new_balance = self.balance.get().saturating_add(*amount);
-                        self.balance.set(new_balance);
-                    }
-                    Some(owner) => {
-                        let balance = self.balances.get_mut_or_default(owner).await?;
-                        *balance = balance.saturating_add(*amount);
-                    }
-                }
+            Credit {
+                amount, account, ..
+            } if context.chain_id == account.chain_id => {
+                self.credit(account.owner.as_ref(), *amount).await?;
             }
             Withdraw {
                 amount,
@@ -831,9 +1013,12 @@ where
                             destination: Destination::Recipient(account.chain_id),
                             authenticated: false,
                             is_skippable: false,
+                            kind: MessageKind::Tracked,
+                            grant: Amount::ZERO,
                             message: SystemMessage::Credit {
                                 amount: *amount,
                                 account: *account,
+                                source: Some(*owner),
                             },
                         };
                         result.messages.push(message);
@@ -842,13 +1027,19 @@ where
                     tracing::info!("Withdrawal request was skipped due to lack of funds.");
                 }
             }
-            SetCommittees { epoch, committees } => {
+            SetCommittees {
+                epoch,
+                committees,
+                expirations,
+            } => {
                 ensure!(
                     *epoch >= self.epoch.get().expect("chain is active"),
                     SystemExecutionError::CannotRewindEpoch
                 );
                 self.epoch.set(Some(*epoch));
                 self.committees.set(committees.clone());
+                self.committee_expirations.set(expirations.clone());
+                self.remove_expired_committees();
             }
             Subscribe { id, subscription } if subscription.chain_id == context.chain_id => {
                 // Notify the subscriber about this block, so that it is included in the
@@ -857,6 +1048,8 @@ where
                     destination: Destination::Recipient(*id),
                     authenticated: false,
                     is_skippable: false,
+                    kind: MessageKind::Simple,
+                    grant: Amount::ZERO,
                     message: SystemMessage::Notify { id: *id },
                 };
                 result.messages.push(message);
@@ -867,6 +1060,8 @@ where
                     destination: Destination::Recipient(*id),
                     authenticated: false,
                     is_skippable: false,
+                    kind: MessageKind::Simple,
+                    grant: Amount::ZERO,
                     message: SystemMessage::Notify { id: *id },
                 };
                 result.messages.push(message);
@@ -885,6 +1080,8 @@ where
                     destination: Destination::Subscribers(SystemChannel::PublishedBytecodes.name()),
                     authenticated: false,
                     is_skippable: false,
+                    kind: MessageKind::Simple,
+                    grant: Amount::ZERO,
                     message: SystemMessage::BytecodeLocations { locations },
                 };
                 result.messages.push(message);
@@ -926,6 +1123,8 @@ where
                             destination: Destination::Recipient(context.message_id.chain_id),
                             authenticated: false,
                             is_skippable: true,
+                            kind: MessageKind::Simple,
+                            grant: Amount::ZERO,
                             message: SystemMessage::RegisterApplications { applications },
                         };
                         result.messages.push(message);
@@ -941,6 +1140,149 @@ where
         Ok(result)
     }
 
+    /// Credits `amount` to the given owner's balance, or to the chain balance if `owner` is
+    /// `None`.
+    async fn credit(
+        &mut self,
+        owner: Option<&Owner>,
+        amount: Amount,
+    ) -> Result<(), SystemExecutionError> {
+        match owner {
+            None => {
+                let new_balance = self.balance.get().saturating_add(amount);
+                self.balance.set(new_balance);
+            }
+            Some(owner) => {
+                let balance = self.balances.get_mut_or_default(owner).await?;
+                *balance = balance.saturating_add(amount);
+            }
+        }
+        Ok(())
+    }
+
+    /// Transfers the unattributed balance to the recipient and the balance of each owner to
+    /// the same owner on the recipient's chain, before the chain is closed. The balances are
+    /// burnt if the recipient is `Burn`.
+    async fn sweep_balances(
+        &mut self,
+        context: &OperationContext,
+        recipient: &Recipient,
+        result: &mut RawExecutionResult<SystemMessage>,
+    ) -> Result<(), SystemExecutionError> {
+        if let Recipient::Account(account) = recipient {
+            ensure!(
+                account.chain_id != context.chain_id,
+                SystemExecutionError::InvalidClosingRecipient
+            );
+        }
+        let mut balances = vec![(None, std::mem::take(self.balance.get_mut()))];
+        self.balances
+            .for_each_index_value(|owner, balance| {
+                balances.push((Some(owner), balance));
+                Ok(())
+            })
+            .await?;
+        self.balances.clear();
+        let Recipient::Account(account) = recipient else {
+            return Ok(());
+        };
+        for (owner, amount) in balances {
+            if amount == Amount::ZERO {
+                continue;
+            }
+            let account = match owner {
+                None => *account,
+                Some(owner) => Account::owner(account.chain_id, owner),
+            };
+            // A closed chain drops the messages returned to it, so the credit is not tracked.
+            let message = RawOutgoingMessage {
+                destination: Destination::Recipient(account.chain_id),
+                authenticated: false,
+                is_skippable: false,
+                kind: MessageKind::Simple,
+                grant: Amount::ZERO,
+                message: SystemMessage::Credit {
+                    amount,
+                    account,
+                    source: owner,
+                },
+            };
+            result.messages.push(message);
+        }
+        Ok(())
+    }
+
+    /// Returns the inbox policy of the chain after all its blocks with a timestamp earlier
+    /// than `timestamp`.
+    pub async fn inbox_policy_at(&self, timestamp: Timestamp) -> Result<InboxPolicy, ViewError> {
+        let mut policy = self.inbox_policy.get().clone();
+        for index in (0..self.previous_inbox_policies.count()).rev() {
+            match self.previous_inbox_policies.get(index).await? {
+                Some((changed, previous_policy)) if changed >= timestamp => {
+                    policy = previous_policy;
+                }
+                _ => break,
+            }
+        }
+        Ok(policy)
+    }
+
+    /// Returns whether the grace period of the committee of the given epoch is over at the
+    /// given time. The committee of the current epoch never expires.
+    pub fn is_committee_expired(&self, epoch: Epoch, timestamp: Timestamp) -> bool {
+        Some(epoch) != *self.epoch.get()
+            && self
+                .committee_expirations
+                .get()
+                .get(&epoch)
+                .is_some_and(|expiration| *expiration <= timestamp)
+    }
+
+    /// Returns the committees that are still trusted at the given time, even if the chain
+    /// didn't remove the expired ones yet.
+    pub fn trusted_committees(&self, timestamp: Timestamp) -> BTreeMap<Epoch, Committee> {
+        self.committees
+            .get()
+            .iter()
+            .filter(|(epoch, _)| !self.is_committee_expired(**epoch, timestamp))
+            .map(|(epoch, committee)| (*epoch, committee.clone()))
+            .collect()
+    }
+
+    /// Removes the committees of past epochs whose grace period is over at the time of the
+    /// current block.
+    pub fn remove_expired_committees(&mut self) {
+        let timestamp = *self.timestamp.get();
+        let expired_epochs = self
+            .committee_expirations
+            .get()
+            .keys()
+            .filter(|epoch| self.is_committee_expired(**epoch, timestamp))
+            .copied()
+            .collect::<Vec<_>>();
+        for epoch in expired_epochs {
+            self.committee_expirations.get_mut().remove(&epoch);
+            self.committees.get_mut().remove(&epoch);
+        }
+    }
+
+    /// Returns the message notifying the subscribers of the admin chain about the current
+    /// epoch and committees.
+    fn set_committees_message(&self) -> RawOutgoingMessage<SystemMessage> {
+        RawOutgoingMessage {
+            destination: Destination::Subscribers(SystemChannel::Admin.name()),
+            authenticated: false,
+            is_skippable: false,
+            kind: MessageKind::Simple,
+            grant: Amount::ZERO,
+            message: SystemMessage::SetCommittees {
+                epoch: self.epoch.get().expect("chain is active"),
+                committees: self.committees.get().clone(),
+                expirations: self.committee_expirations.get().clone(),
+            },
+        }
+    }
+
     /// Initializes the system application state on a newly opened chain.
     #[allow(clippy::too_many_arguments)]
     pub fn open_chain(
@@ -974,11 +1316,46 @@ where
     pub async fn handle_query(
         &mut self,
         context: &QueryContext,
-        _query: &SystemQuery,
+        query: &SystemQuery,
     ) -> Result<SystemResponse, SystemExecutionError> {
-        let response = SystemResponse {
-            chain_id: context.chain_id,
-            balance: *self.balance.get(),
+        let response = match query {
+            SystemQuery::Balance => SystemResponse::Balance {
+                chain_id: context.chain_id,
+                balance: *self.balance.get(),
+            },
+            SystemQuery::Balances => {
+                let mut balances = BTreeMap::new();
+                self.balances
+                    .for_each_index_value(|owner, balance| {
+                        balances.insert(owner, balance);
+                        Ok(())
+                    })
+                    .await?;
+                SystemResponse::Balances(balances)
+            }
+            SystemQuery::Ownership => SystemResponse::Ownership(self.ownership.get().clone()),
+            SystemQuery::Committees => SystemResponse::Committees {
+                current_epoch: *self.epoch.get(),
+                committees: self.committees.get().clone(),
+            },
+            SystemQuery::Subscriptions => {
+                SystemResponse::Subscriptions(self.subscriptions.indices().await?)
+            }
+            SystemQuery::Registry => {
+                let published_bytecodes = self.registry.bytecode_locations().await?;
+                let mut known_applications = BTreeMap::new();
+                self.registry
+                    .known_applications
+                    .for_each_index_value(|id, description| {
+                        known_applications.insert(id, description);
+                        Ok(())
+                    })
+                    .await?;
+                SystemResponse::Registry {
+                    published_bytecodes: published_bytecodes.into_iter().collect(),
+                    known_applications,
+                }
+            }
         };
         Ok(response)
     }
@@ -987,7 +1364,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{ExecutionStateView, TestExecutionRuntimeContext};
+    use crate::{committee::ValidatorName, ExecutionStateView, TestExecutionRuntimeContext};
     use linera_base::{
         crypto::{BcsSignable, KeyPair},
         data_types::BlockHeight,
@@ -1013,6 +1390,7 @@ mod tests {
             height: BlockHeight::from(7),
             index: 2,
             next_message_index: 3,
+            random_seed: Some(CryptoHash::new(&Dummy)),
         };
         let state = SystemExecutionState {
             description: Some(description),
@@ -1067,6 +1445,7 @@ mod tests {
             parameters: vec![],
             initialization_argument: vec![],
             required_application_ids: vec![],
+            storage_quota: None,
         };
         let (result, new_application) = view
             .system
@@ -1118,4 +1497,50 @@ mod tests {
             }
         );
     }
+
+    #[tokio::test]
+    async fn change_validators_starts_new_epoch() {
+        let (mut view, mut context) = new_view_and_context().await;
+        context.chain_id = ChainId::root(0);
+        let validator = ValidatorName(KeyPair::generate().public());
+        let new_validator = ValidatorName(KeyPair::generate().public());
+        let committee = Committee::make_simple(vec![validator]);
+        view.system
+            .committees
+            .set(BTreeMap::from([(Epoch(1), committee)]));
+        view.system.timestamp.set(Timestamp::from(1_000));
+        let operation = SystemOperation::Admin(AdminOperation::ChangeValidators {
+            changes: vec![ValidatorChange::RotateKey {
+                name: validator,
+                new_name: new_validator,
+            }],
+            grace_period_micros: 500,
+        });
+        let (result, _) = view
+            .system
+            .execute_operation(&context, &operation)
+            .await
+            .unwrap();
+        assert_eq!(*view.system.epoch.get(), Some(Epoch(2)));
+        let committees = view.system.committees.get().clone();
+        assert_eq!(committees[&Epoch(2)].weight(&validator), 0);
+        assert_eq!(committees[&Epoch(2)].weight(&new_validator), 1);
+        assert_eq!(
+            result.messages[0].message,
+            SystemMessage::SetCommittees {
+                epoch: Epoch(2),
+                committees,
+                expirations: BTreeMap::from([(Epoch(1), Timestamp::from(1_500))]),
+            }
+        );
+
+        // The previous committee is removed once the grace period is over.
+        view.system.timestamp.set(Timestamp::from(1_500));
+        view.system.remove_expired_committees();
+        assert_eq!(
+            view.system.committees.get().keys().collect::<Vec<_>>(),
+            vec![&Epoch(2)]
+        );
+        assert!(view.system.committee_expirations.get().is_empty());
+    }
 }
@@ -0,0 +1,181 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers for tests that execute user applications without a WebAssembly runtime.
+
+use crate::{
+    ApplicationCallResult, CalleeContext, ContractRuntime, ExecutionError, MessageContext,
+    MessageOutcome, OperationContext, QueryContext, RawExecutionResult, ServiceRuntime,
+    SessionCallResult, UserApplication,
+};
+use async_trait::async_trait;
+use linera_base::identifiers::{Owner, SessionId};
+use linera_views::batch::Batch;
+
+/// A native application for tests, whose operations and messages must be signed by `owner`.
+///
+/// Operations and messages append their bytes to the view state of the application, which
+/// queries return. Operations are also emitted as events, and acknowledgements emit their
+/// outcome.
+pub struct TestApplication {
+    pub owner: Owner,
+}
+
+impl TestApplication {
+    /// The prefix of the operations and messages that make the application fail, with error
+    /// code [`Self::FAILURE_CODE`], after they modified its state.
+    pub const FAILURE: &'static [u8] = b"fail";
+    /// The error code of the failures requested with [`Self::FAILURE`].
+    pub const FAILURE_CODE: u32 = 1;
+    /// The key of the application state.
+    const STATE_KEY: [u8; 1] = [0];
+
+    /// Appends the `bytes` to the application state, and fails if they start with
+    /// [`Self::FAILURE`].
+    async fn append_to_state(
+        runtime: &dyn ContractRuntime,
+        bytes: &[u8],
+    ) -> Result<(), ExecutionError> {
+        runtime.lock_view_user_state().await?;
+        let state = runtime.read_key_bytes(Self::STATE_KEY.to_vec()).await?;
+        let mut state = state.unwrap_or_default();
+        state.extend(bytes);
+        let mut batch = Batch::new();
+        batch.put_key_value_bytes(Self::STATE_KEY.to_vec(), state);
+        runtime.write_batch_and_unlock(batch).await?;
+        if bytes.starts_with(Self::FAILURE) {
+            return Err(ExecutionError::UserError {
+                code: Self::FAILURE_CODE,
+                message: "Failure requested".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UserApplication for TestApplication {
+    /// Saves the initialization `argument` as the simple state of the application.
+    async fn initialize(
+        &self,
+        context: &OperationContext,
+        runtime: &dyn ContractRuntime,
+        argument: &[u8],
+    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError> {
+        assert_eq!(context.authenticated_signer, Some(self.owner));
+        runtime.try_read_and_lock_my_state().await?;
+        runtime.save_and_unlock_my_state(argument.to_vec())?;
+        Ok(RawExecutionResult::default())
+    }
+
+    /// Extends the application state with the `operation` bytes, and emits them as an event.
+    ///
+    /// Calls itself during the operation, opening a session. The session is intentionally
+    /// leaked if the operation is empty.
+    async fn execute_operation(
+        &self,
+        context: &OperationContext,
+        runtime: &dyn ContractRuntime,
+        operation: &[u8],
+    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError> {
+        // Who we are.
+        assert_eq!(context.authenticated_signer, Some(self.owner));
+        let app_id = runtime.application_id();
+        // Modify our state.
+        Self::append_to_state(runtime, operation).await?;
+        runtime.emit_event(b"operation".to_vec(), operation.to_vec())?;
+        // Call ourselves after the state => ok.
+        let call_result = runtime
+            .try_call_application(/* authenticate */ true, app_id, &[], vec![])
+            .await?;
+        assert_eq!(call_result.value, Vec::<u8>::new());
+        assert_eq!(call_result.sessions.len(), 1);
+        if !operation.is_empty() {
+            // Call the session to close it.
+            let session_id = call_result.sessions[0];
+            runtime
+                .try_call_session(/* authenticate */ false, session_id, &[], vec![])
+                .await?;
+        }
+        Ok(RawExecutionResult::default())
+    }
+
+    /// Extends the application state with the `message` bytes.
+    async fn execute_message(
+        &self,
+        context: &MessageContext,
+        runtime: &dyn ContractRuntime,
+        message: &[u8],
+    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError> {
+        // Who we are.
+        assert_eq!(context.authenticated_signer, Some(self.owner));
+        Self::append_to_state(runtime, message).await?;
+        Ok(RawExecutionResult::default())
+    }
+
+    async fn handle_bounced_message(
+        &self,
+        _context: &MessageContext,
+        _runtime: &dyn ContractRuntime,
+        _message: &[u8],
+    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError> {
+        Ok(RawExecutionResult::default())
+    }
+
+    /// Emits the `outcome` as an event.
+    async fn handle_acknowledgement(
+        &self,
+        _context: &MessageContext,
+        runtime: &dyn ContractRuntime,
+        _message: &[u8],
+        outcome: MessageOutcome,
+    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError> {
+        runtime.emit_event(b"outcome".to_vec(), format!("{outcome:?}").into_bytes())?;
+        Ok(RawExecutionResult::default())
+    }
+
+    /// Creates a session.
+    async fn handle_application_call(
+        &self,
+        context: &CalleeContext,
+        _runtime: &dyn ContractRuntime,
+        _argument: &[u8],
+        _forwarded_sessions: Vec<SessionId>,
+    ) -> Result<ApplicationCallResult, ExecutionError> {
+        assert_eq!(context.authenticated_signer, Some(self.owner));
+        Ok(ApplicationCallResult {
+            create_sessions: vec![vec![1]],
+            ..ApplicationCallResult::default()
+        })
+    }
+
+    /// Closes the session.
+    async fn handle_session_call(
+        &self,
+        context: &CalleeContext,
+        _runtime: &dyn ContractRuntime,
+        _session_state: &mut Vec<u8>,
+        _argument: &[u8],
+        _forwarded_sessions: Vec<SessionId>,
+    ) -> Result<SessionCallResult, ExecutionError> {
+        assert_eq!(context.authenticated_signer, None);
+        Ok(SessionCallResult {
+            inner: ApplicationCallResult::default(),
+            close_session: true,
+        })
+    }
+
+    /// Returns the application state.
+    async fn handle_query(
+        &self,
+        _context: &QueryContext,
+        runtime: &dyn ServiceRuntime,
+        _argument: &[u8],
+    ) -> Result<Vec<u8>, ExecutionError> {
+        runtime.lock_view_user_state().await?;
+        let state = runtime.read_key_bytes(Self::STATE_KEY.to_vec()).await?;
+        let state = state.unwrap_or_default();
+        runtime.unlock_view_user_state().await?;
+        Ok(state)
+    }
+}
@@ -0,0 +1,237 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recording of the steps taken while executing transactions, to help debugging failing
+//! applications.
+
+use crate::UserApplicationId;
+use linera_base::identifiers::{MessageId, SessionId};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// The events recorded while executing transactions with tracing enabled.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ExecutionTrace {
+    /// The recorded events, in the order in which they happened.
+    pub events: Vec<TraceEvent>,
+}
+
+/// A step of the execution of a transaction.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum TraceEvent {
+    /// The execution of an incoming message of the block started.
+    IncomingMessage { index: u32, message_id: MessageId },
+    /// The execution of an operation of the block started.
+    Operation { index: u32 },
+    /// A user application started executing a transaction.
+    UserAction {
+        application_id: UserApplicationId,
+        remaining_fuel: u64,
+    },
+    /// An application called another application.
+    ApplicationCall {
+        caller_id: UserApplicationId,
+        callee_id: UserApplicationId,
+        authenticated: bool,
+        #[serde(with = "serde_bytes")]
+        argument: Vec<u8>,
+        forwarded_sessions: Vec<SessionId>,
+    },
+    /// An application called a session.
+    SessionCall {
+        caller_id: UserApplicationId,
+        session_id: SessionId,
+        authenticated: bool,
+        #[serde(with = "serde_bytes")]
+        argument: Vec<u8>,
+        forwarded_sessions: Vec<SessionId>,
+    },
+    /// An application read its simple state.
+    ReadState {
+        application_id: UserApplicationId,
+        size: u64,
+    },
+    /// An application saved its simple state.
+    WriteState {
+        application_id: UserApplicationId,
+        size: u64,
+    },
+    /// An application read a key of its view state.
+    ReadKey {
+        application_id: UserApplicationId,
+        #[serde(with = "serde_bytes")]
+        key: Vec<u8>,
+        value: Option<Vec<u8>>,
+    },
+    /// An application searched the keys of its view state with a given prefix.
+    FindKeys {
+        application_id: UserApplicationId,
+        #[serde(with = "serde_bytes")]
+        key_prefix: Vec<u8>,
+        count: u64,
+    },
+    /// An application searched the key-value pairs of its view state with a given prefix.
+    FindKeyValues {
+        application_id: UserApplicationId,
+        #[serde(with = "serde_bytes")]
+        key_prefix: Vec<u8>,
+        count: u64,
+    },
+    /// An application wrote a batch to its view state.
+    WriteBatch {
+        application_id: UserApplicationId,
+        size: u64,
+    },
+    /// An application logged a message.
+    Log {
+        application_id: UserApplicationId,
+        level: LogLevel,
+        message: String,
+    },
+    /// The remaining fuel was updated after running an application.
+    Fuel {
+        application_id: UserApplicationId,
+        remaining_fuel: u64,
+    },
+}
+
+/// The level of a message logged by an application.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl ExecutionTrace {
+    /// Appends an event to the trace.
+    pub fn record(&mut self, event: TraceEvent) {
+        self.events.push(event);
+    }
+}
+
+impl Display for ExecutionTrace {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        for event in &self.events {
+            writeln!(formatter, "{event}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for TraceEvent {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        use TraceEvent::*;
+        match self {
+            IncomingMessage { index, message_id } => write!(
+                formatter,
+                "incoming message #{index} ({}:{}:{})",
+                message_id.chain_id, message_id.height, message_id.index
+            ),
+            Operation { index } => write!(formatter, "operation #{index}"),
+            UserAction {
+                application_id,
+                remaining_fuel,
+            } => write!(
+                formatter,
+                "  {application_id:?}: start with {remaining_fuel} fuel"
+            ),
+            ApplicationCall {
+                caller_id,
+                callee_id,
+                authenticated,
+                argument,
+                forwarded_sessions,
+            } => write!(
+                formatter,
+                "  {caller_id:?}: call application {callee_id:?} (authenticated: {authenticated}, \
+                {} argument bytes, {} forwarded sessions)",
+                argument.len(),
+                forwarded_sessions.len()
+            ),
+            SessionCall {
+                caller_id,
+                session_id,
+                authenticated,
+                argument,
+                forwarded_sessions,
+            } => write!(
+                formatter,
+                "  {caller_id:?}: call session {session_id:?} (authenticated: {authenticated}, \
+                {} argument bytes, {} forwarded sessions)",
+                argument.len(),
+                forwarded_sessions.len()
+            ),
+            ReadState {
+                application_id,
+                size,
+            } => write!(
+                formatter,
+                "  {application_id:?}: read {size} bytes of state"
+            ),
+            WriteState {
+                application_id,
+                size,
+            } => write!(
+                formatter,
+                "  {application_id:?}: save {size} bytes of state"
+            ),
+            ReadKey {
+                application_id,
+                key,
+                value,
+            } => match value {
+                Some(value) => write!(
+                    formatter,
+                    "  {application_id:?}: read key of {} bytes, found {} bytes",
+                    key.len(),
+                    value.len()
+                ),
+                None => write!(
+                    formatter,
+                    "  {application_id:?}: read key of {} bytes, not found",
+                    key.len()
+                ),
+            },
+            FindKeys {
+                application_id,
+                key_prefix,
+                count,
+            } => write!(
+                formatter,
+                "  {application_id:?}: find keys with a prefix of {} bytes, found {count}",
+                key_prefix.len()
+            ),
+            FindKeyValues {
+                application_id,
+                key_prefix,
+                count,
+            } => write!(
+                formatter,
+                "  {application_id:?}: find key-values with a prefix of {} bytes, found {count}",
+                key_prefix.len()
+            ),
+            WriteBatch {
+                application_id,
+                size,
+            } => write!(
+                formatter,
+                "  {application_id:?}: write batch of {size} bytes"
+            ),
+            Log {
+                application_id,
+                level,
+                message,
+            } => write!(formatter, "  {application_id:?}: [{level:?}] {message}"),
+            Fuel {
+                application_id,
+                remaining_fuel,
+            } => write!(
+                formatter,
+                "  {application_id:?}: {remaining_fuel} fuel remaining"
+            ),
+        }
+    }
+}
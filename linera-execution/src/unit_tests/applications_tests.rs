@@ -38,6 +38,7 @@ fn app_description(index: u32, deps: Vec<u32>) -> UserApplicationDescription {
         creation: message_id(index),
         parameters: vec![],
         required_application_ids: deps.into_iter().map(app_id).collect(),
+        storage_quota: None,
     }
 }
 
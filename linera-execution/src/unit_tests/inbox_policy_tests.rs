@@ -0,0 +1,125 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::InboxPolicy;
+use crate::{
+    system::{Account, SystemMessage},
+    GenericApplicationId, Message, UserApplicationId,
+};
+use linera_base::{
+    data_types::{Amount, BlockHeight},
+    identifiers::{BytecodeId, ChainId, MessageId},
+};
+use std::collections::BTreeSet;
+
+fn credit(amount: Amount) -> Message {
+    Message::System(SystemMessage::Credit {
+        account: Account::chain(ChainId::root(0)),
+        amount,
+        source: None,
+    })
+}
+
+fn user_message(index: u32) -> (GenericApplicationId, Message) {
+    let message_id = MessageId {
+        chain_id: ChainId::root(0),
+        height: BlockHeight::ZERO,
+        index,
+    };
+    let application_id = UserApplicationId {
+        bytecode_id: BytecodeId::new(message_id),
+        creation: message_id,
+    };
+    let message = Message::User {
+        application_id,
+        bytes: vec![],
+    };
+    (GenericApplicationId::User(application_id), message)
+}
+
+#[test]
+fn test_default_policy_accepts_everything() {
+    let policy = InboxPolicy::default();
+    assert!(policy.accepts(
+        ChainId::root(1),
+        &credit(Amount::ZERO),
+        Amount::ZERO,
+        u64::MAX
+    ));
+    assert!(policy.accepts(ChainId::root(1), &user_message(0).1, Amount::ZERO, 0));
+}
+
+#[test]
+fn test_senders() {
+    let allowed = ChainId::root(1);
+    let denied = ChainId::root(2);
+    let other = ChainId::root(3);
+    let message = credit(Amount::ONE);
+    let policy = InboxPolicy {
+        denied_senders: BTreeSet::from([denied]),
+        ..InboxPolicy::default()
+    };
+    assert!(policy.accepts(allowed, &message, Amount::ZERO, 0));
+    assert!(!policy.accepts(denied, &message, Amount::ZERO, 0));
+    assert!(policy.accepts(other, &message, Amount::ZERO, 0));
+
+    let policy = InboxPolicy {
+        allowed_senders: Some(BTreeSet::from([allowed, denied])),
+        ..policy
+    };
+    assert!(policy.accepts(allowed, &message, Amount::ZERO, 0));
+    assert!(!policy.accepts(denied, &message, Amount::ZERO, 0));
+    assert!(!policy.accepts(other, &message, Amount::ZERO, 0));
+}
+
+#[test]
+fn test_applications() {
+    let sender = ChainId::root(1);
+    let (allowed_id, allowed_message) = user_message(1);
+    let (denied_id, denied_message) = user_message(2);
+    let (_, other_message) = user_message(3);
+    let policy = InboxPolicy {
+        denied_applications: BTreeSet::from([denied_id]),
+        ..InboxPolicy::default()
+    };
+    assert!(policy.accepts(sender, &allowed_message, Amount::ZERO, 0));
+    assert!(!policy.accepts(sender, &denied_message, Amount::ZERO, 0));
+    assert!(policy.accepts(sender, &other_message, Amount::ZERO, 0));
+    assert!(policy.accepts(sender, &credit(Amount::ONE), Amount::ZERO, 0));
+
+    let policy = InboxPolicy {
+        allowed_applications: Some(BTreeSet::from([allowed_id, denied_id])),
+        ..policy
+    };
+    assert!(policy.accepts(sender, &allowed_message, Amount::ZERO, 0));
+    assert!(!policy.accepts(sender, &denied_message, Amount::ZERO, 0));
+    assert!(!policy.accepts(sender, &other_message, Amount::ZERO, 0));
+    assert!(!policy.accepts(sender, &credit(Amount::ONE), Amount::ZERO, 0));
+}
+
+#[test]
+fn test_maximum_pending_messages() {
+    let sender = ChainId::root(1);
+    let message = credit(Amount::ONE);
+    let policy = InboxPolicy {
+        maximum_pending_messages: Some(2),
+        ..InboxPolicy::default()
+    };
+    assert!(policy.accepts(sender, &message, Amount::ZERO, 0));
+    assert!(policy.accepts(sender, &message, Amount::ZERO, 1));
+    assert!(!policy.accepts(sender, &message, Amount::ZERO, 2));
+}
+
+#[test]
+fn test_minimum_grant() {
+    let sender = ChainId::root(1);
+    let policy = InboxPolicy {
+        minimum_grant: Amount::from_tokens(2),
+        ..InboxPolicy::default()
+    };
+    let message = user_message(0).1;
+    assert!(!policy.accepts(sender, &message, Amount::ONE, 0));
+    assert!(policy.accepts(sender, &message, Amount::from_tokens(2), 0));
+    // The amount of a transfer is not a grant.
+    assert!(!policy.accepts(sender, &credit(Amount::from_tokens(2)), Amount::ZERO, 0));
+}
@@ -86,7 +86,14 @@ where
     /// Executes the `future`, polling it as requested by the [`PollSender`] until it completes.
     fn run_poll_loop(mut self, future: Future) {
         while let Ok(request) = self.poll_request_receiver.recv() {
-            let response = future.poll(&self.context.application, &mut self.context.store);
+            let mut response = future.poll(&self.context.application, &mut self.context.store);
+            // The runtimes refuse to grow the memory beyond its limit, which usually makes the
+            // application fail: report the cause instead of the resulting trap.
+            if let Poll::Ready(Err(_)) = response {
+                if let Some(error) = self.context.memory_limit_error() {
+                    response = Poll::Ready(Err(error));
+                }
+            }
             let mut finished = response.is_ready();
 
             if request
@@ -9,8 +9,8 @@ use super::{
     ExecutionError,
 };
 use crate::{
-    ApplicationCallResult, CalleeContext, MessageContext, OperationContext, QueryContext,
-    RawExecutionResult, SessionCallResult, SessionId,
+    ApplicationCallResult, CalleeContext, MessageContext, MessageOutcome, OperationContext,
+    QueryContext, RawExecutionResult, SessionCallResult, SessionId,
 };
 
 /// Types that are specific to the context of an application ready to be executedy by a WebAssembly
@@ -30,6 +30,10 @@ pub trait ApplicationRuntimeContext: Sized {
 
     /// Persists the remaining fuel after execution.
     fn persist_remaining_fuel(context: &mut WasmRuntimeContext<Self>) -> Result<(), ()>;
+
+    /// Returns `true` if the runtime refused to grow the application's linear memory because
+    /// of its limit.
+    fn memory_growth_refused(context: &mut WasmRuntimeContext<Self>) -> bool;
 }
 
 /// Common interface to calling a user contract in a WebAssembly module.
@@ -61,6 +65,24 @@ pub trait Contract: ApplicationRuntimeContext {
         > + Send
         + Unpin;
 
+    /// The WIT type for the resource representing the guest future
+    /// [`handle_bounced_message`][crate::Contract::handle_bounced_message] method.
+    type HandleBouncedMessage: GuestFutureInterface<
+            Self,
+            Output = RawExecutionResult<Vec<u8>>,
+            Parameters = (MessageContext, Vec<u8>),
+        > + Send
+        + Unpin;
+
+    /// The WIT type for the resource representing the guest future
+    /// [`handle_acknowledgement`][crate::Contract::handle_acknowledgement] method.
+    type HandleAcknowledgement: GuestFutureInterface<
+            Self,
+            Output = RawExecutionResult<Vec<u8>>,
+            Parameters = (MessageContext, Vec<u8>, MessageOutcome),
+        > + Send
+        + Unpin;
+
     /// The WIT type for the resource representing the guest future
     /// [`handle_application_call`][crate::Contract::handle_application_call] method.
     type HandleApplicationCall: GuestFutureInterface<
@@ -133,6 +155,37 @@ pub trait Contract: ApplicationRuntimeContext {
         future: &Self::ExecuteMessage,
     ) -> Result<Self::PollExecutionResult, Self::Error>;
 
+    /// Creates a new future for the user contract to handle a bounced message.
+    fn handle_bounced_message_new(
+        &self,
+        store: &mut Self::Store,
+        context: MessageContext,
+        message: Vec<u8>,
+    ) -> Result<Self::HandleBouncedMessage, Self::Error>;
+
+    /// Polls a user contract future that's handling a bounced message.
+    fn handle_bounced_message_poll(
+        &self,
+        store: &mut Self::Store,
+        future: &Self::HandleBouncedMessage,
+    ) -> Result<Self::PollExecutionResult, Self::Error>;
+
+    /// Creates a new future for the user contract to handle the acknowledgement of a message.
+    fn handle_acknowledgement_new(
+        &self,
+        store: &mut Self::Store,
+        context: MessageContext,
+        message: Vec<u8>,
+        outcome: MessageOutcome,
+    ) -> Result<Self::HandleAcknowledgement, Self::Error>;
+
+    /// Polls a user contract future that's handling the acknowledgement of a message.
+    fn handle_acknowledgement_poll(
+        &self,
+        store: &mut Self::Store,
+        future: &Self::HandleAcknowledgement,
+    ) -> Result<Self::PollExecutionResult, Self::Error>;
+
     /// Creates a new future for the user contract to handle a call from another contract.
     fn handle_application_call_new(
         &self,
@@ -195,6 +248,12 @@ pub trait Service: ApplicationRuntimeContext {
     ) -> Result<Self::PollApplicationQueryResult, Self::Error>;
 }
 
+/// The maximum number of elements in each table of a contract instance.
+pub(crate) const MAXIMUM_TABLE_ELEMENTS: u32 = 100_000;
+
+/// The size of a page of Wasm linear memory, in bytes.
+pub(crate) const WASM_PAGE_SIZE: u64 = 0x1_0000;
+
 /// Wrapper around all types necessary to call an asynchronous method of a Wasm application.
 pub struct WasmRuntimeContext<A>
 where
@@ -209,11 +268,25 @@ where
     /// The application's memory state.
     pub(crate) store: A::Store,
 
+    /// The maximum number of pages of linear memory the application may use, if limited.
+    pub(crate) memory_limit: Option<u64>,
+
     /// Guard type to clean up any host state after the call to the Wasm application finishes.
-    #[cfg_attr(all(not(feature = "wasmer"), feature = "wasmtime"), allow(dead_code))]
     pub(crate) extra: A::Extra,
 }
 
+impl<A> WasmRuntimeContext<A>
+where
+    A: ApplicationRuntimeContext,
+{
+    /// Returns the error to report if the application failed after the runtime refused to grow
+    /// its linear memory beyond its limit.
+    pub(crate) fn memory_limit_error(&mut self) -> Option<ExecutionError> {
+        let maximum_pages = self.memory_limit?;
+        A::memory_growth_refused(self).then_some(ExecutionError::ExcessiveMemory(maximum_pages))
+    }
+}
+
 impl<A> WasmRuntimeContext<A>
 where
     A: Contract + Send + Unpin + 'static,
@@ -278,6 +351,51 @@ where
         GuestFutureActor::<A::ExecuteMessage, A>::spawn((*context, message.to_owned()), self)
     }
 
+    /// Calls the guest Wasm module's implementation of
+    /// [`UserApplication::handle_bounced_message`][`linera_execution::UserApplication::handle_bounced_message`].
+    ///
+    /// This method returns a [`Future`][`std::future::Future`], and is equivalent to
+    ///
+    /// ```ignore
+    /// pub async fn handle_bounced_message(
+    ///     mut self,
+    ///     context: &MessageContext,
+    ///     message: &[u8],
+    /// ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError>
+    /// ```
+    pub fn handle_bounced_message(
+        self,
+        context: &MessageContext,
+        message: &[u8],
+    ) -> PollSender<RawExecutionResult<Vec<u8>>> {
+        GuestFutureActor::<A::HandleBouncedMessage, A>::spawn((*context, message.to_owned()), self)
+    }
+
+    /// Calls the guest Wasm module's implementation of
+    /// [`UserApplication::handle_acknowledgement`][`linera_execution::UserApplication::handle_acknowledgement`].
+    ///
+    /// This method returns a [`Future`][`std::future::Future`], and is equivalent to
+    ///
+    /// ```ignore
+    /// pub async fn handle_acknowledgement(
+    ///     mut self,
+    ///     context: &MessageContext,
+    ///     message: &[u8],
+    ///     outcome: MessageOutcome,
+    /// ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError>
+    /// ```
+    pub fn handle_acknowledgement(
+        self,
+        context: &MessageContext,
+        message: &[u8],
+        outcome: MessageOutcome,
+    ) -> PollSender<RawExecutionResult<Vec<u8>>> {
+        GuestFutureActor::<A::HandleAcknowledgement, A>::spawn(
+            (*context, message.to_owned(), outcome),
+            self,
+        )
+    }
+
     /// Calls the guest Wasm module's implementation of
     /// [`UserApplication::handle_application_call`][`linera_execution::UserApplication::handle_application_call`].
     ///
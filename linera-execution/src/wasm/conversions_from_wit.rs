@@ -10,12 +10,12 @@
 
 use super::{contract, contract_system_api, service_system_api};
 use crate::{
-    ApplicationCallResult, ChannelName, Destination, RawExecutionResult, RawOutgoingMessage,
-    SessionCallResult, SessionId, UserApplicationId,
+    trace::LogLevel, ApplicationCallResult, ChannelName, Destination, MessageKind,
+    RawExecutionResult, RawOutgoingMessage, SessionCallResult, SessionId, UserApplicationId,
 };
 use linera_base::{
     crypto::CryptoHash,
-    data_types::BlockHeight,
+    data_types::{Amount, BlockHeight},
     identifiers::{BytecodeId, ChainId, MessageId},
 };
 
@@ -48,6 +48,14 @@ impl From<contract::OutgoingMessage> for RawOutgoingMessage<Vec<u8>> {
             destination: message.destination.into(),
             authenticated: message.authenticated,
             is_skippable: message.is_skippable,
+            kind: if message.is_acknowledged {
+                MessageKind::Acknowledged
+            } else if message.is_tracked {
+                MessageKind::Tracked
+            } else {
+                MessageKind::Simple
+            },
+            grant: message.grant.into(),
             message: message.message,
         }
     }
@@ -78,6 +86,7 @@ impl From<contract::ExecutionResult> for RawExecutionResult<Vec<u8>> {
             messages,
             subscribe,
             unsubscribe,
+            events: Vec::new(),
         }
     }
 }
@@ -112,6 +121,13 @@ impl From<contract::ChainId> for ChainId {
     }
 }
 
+impl From<contract::Amount> for Amount {
+    fn from(guest: contract::Amount) -> Self {
+        let value = ((guest.upper_half as u128) << 64) | (guest.lower_half as u128);
+        Amount::from_atto(value)
+    }
+}
+
 impl From<contract_system_api::SessionId> for SessionId {
     fn from(guest: contract_system_api::SessionId) -> Self {
         SessionId {
@@ -130,6 +146,18 @@ impl From<contract_system_api::ApplicationId> for UserApplicationId {
     }
 }
 
+impl From<contract_system_api::LogLevel> for LogLevel {
+    fn from(level: contract_system_api::LogLevel) -> Self {
+        match level {
+            contract_system_api::LogLevel::Trace => LogLevel::Trace,
+            contract_system_api::LogLevel::Debug => LogLevel::Debug,
+            contract_system_api::LogLevel::Info => LogLevel::Info,
+            contract_system_api::LogLevel::Warn => LogLevel::Warn,
+            contract_system_api::LogLevel::Error => LogLevel::Error,
+        }
+    }
+}
+
 impl From<contract_system_api::MessageId> for BytecodeId {
     fn from(guest: contract_system_api::MessageId) -> Self {
         BytecodeId::new(guest.into())
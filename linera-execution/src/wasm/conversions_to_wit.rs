@@ -10,8 +10,8 @@
 
 use super::{contract, contract_system_api, service, service_system_api};
 use crate::{
-    CallResult, CalleeContext, MessageContext, MessageId, OperationContext, QueryContext,
-    SessionId, UserApplicationId,
+    CallResult, CalleeContext, MessageContext, MessageId, MessageOutcome, OperationContext,
+    QueryContext, RemoteQueryResponse, SessionId, UserApplicationId,
 };
 use linera_base::{crypto::CryptoHash, data_types::Amount, identifiers::ChainId};
 
@@ -37,6 +37,16 @@ impl From<MessageContext> for contract::MessageContext {
     }
 }
 
+impl From<MessageOutcome> for contract::MessageOutcome {
+    fn from(host: MessageOutcome) -> Self {
+        match host {
+            MessageOutcome::Executed => contract::MessageOutcome::Executed,
+            MessageOutcome::Failed(code) => contract::MessageOutcome::Failed(code),
+            MessageOutcome::Rejected => contract::MessageOutcome::Rejected,
+        }
+    }
+}
+
 impl From<MessageId> for service_system_api::MessageId {
     fn from(host: MessageId) -> Self {
         service_system_api::MessageId {
@@ -234,3 +244,12 @@ impl From<Amount> for contract_system_api::Amount {
         }
     }
 }
+
+impl From<RemoteQueryResponse> for service_system_api::RemoteQueryResponse {
+    fn from(host: RemoteQueryResponse) -> Self {
+        service_system_api::RemoteQueryResponse {
+            response: host.response,
+            height: host.height.0,
+        }
+    }
+}
@@ -0,0 +1,390 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! An optional on-disk cache of compiled WebAssembly modules.
+//!
+//! Compiling a module is expensive, and the in-memory [`ModuleCache`][super::module_cache]
+//! is lost whenever the process restarts. When enabled, this cache stores the serialized
+//! compiled modules in a directory, so that they can be reloaded instead of being recompiled.
+//!
+//! Entries are keyed by the hash of the bytecode, the runtime, the kind of module, the version
+//! of Linera and of the compilation engine, and the hash of the compiler configuration, which
+//! includes the fuel costs compiled into contracts. Each entry carries a checksum of the serialized module,
+//! which is verified before the module is handed to the runtime. The total size of the
+//! directory is bounded by evicting the oldest entries first.
+//!
+//! Deserializing a compiled module is inherently unsafe, so the cache directory must only be
+//! writable by the validator itself.
+
+use crate::Bytecode;
+use linera_base::crypto::{BcsHashable, CryptoHash};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display, Formatter},
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    time::SystemTime,
+};
+use tempfile::NamedTempFile;
+
+/// The file extension of cache entries.
+const ENTRY_EXTENSION: &str = "module";
+
+/// The process-wide on-disk cache, if it was enabled.
+static DISK_MODULE_CACHE: OnceCell<DiskModuleCache> = OnceCell::new();
+
+/// A directory storing serialized compiled modules.
+#[derive(Debug)]
+pub struct DiskModuleCache {
+    directory: PathBuf,
+    max_size: u64,
+}
+
+/// The kind of application module being compiled.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ModuleKind {
+    Contract,
+    Service,
+}
+
+/// The settings a runtime compiles modules with, besides the version of its engine.
+///
+/// Anything that changes the compiled code, such as the instrumentation for fuel metering, must
+/// be reflected here, so that modules compiled with other settings aren't reused.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct CompilerConfiguration {
+    /// The name of the compiler backend.
+    pub compiler: String,
+    /// Whether NaN values are canonicalized to make floating point operations deterministic.
+    pub canonicalize_nans: bool,
+    /// Whether the consumed fuel is metered.
+    pub fuel_metering: bool,
+    /// The costs of a sample of operators, if fuel is metered with a custom cost function.
+    pub operator_costs: Vec<u64>,
+}
+
+impl BcsHashable for CompilerConfiguration {}
+
+/// The key identifying a compiled module in the cache.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DiskCacheKey {
+    /// The name of the runtime that compiled the module.
+    runtime: String,
+    /// The version of Linera that compiled the module.
+    linera_version: String,
+    /// The version of the runtime's compilation engine.
+    engine_version: String,
+    /// The hash of the [`CompilerConfiguration`] used to compile the module.
+    configuration_hash: CryptoHash,
+    /// Whether the module is a contract or a service.
+    kind: ModuleKind,
+    /// The hash of the bytecode that was compiled.
+    bytecode_hash: CryptoHash,
+}
+
+/// An entry of the cache, as written to disk.
+#[derive(Debug, Deserialize, Serialize)]
+struct DiskCacheEntry {
+    key: DiskCacheKey,
+    checksum: CryptoHash,
+    module: SerializedModule,
+}
+
+/// The serialized bytes of a compiled module.
+#[derive(Debug, Deserialize, Serialize)]
+struct SerializedModule {
+    #[serde(with = "serde_bytes")]
+    bytes: Vec<u8>,
+}
+
+impl BcsHashable for SerializedModule {}
+
+impl DiskModuleCache {
+    /// Creates a cache in `directory`, creating the directory if needed. The total size of the
+    /// cached entries is kept below `max_size` bytes.
+    pub fn new(directory: impl Into<PathBuf>, max_size: u64) -> Result<Self, io::Error> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+        Ok(DiskModuleCache {
+            directory,
+            max_size,
+        })
+    }
+
+    /// Enables the process-wide on-disk cache.
+    ///
+    /// Returns an error if the cache was already enabled.
+    pub fn enable(directory: impl Into<PathBuf>, max_size: u64) -> Result<(), io::Error> {
+        let cache = Self::new(directory, max_size)?;
+        DISK_MODULE_CACHE.set(cache).map_err(|cache| {
+            io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "the on-disk module cache is already enabled, refusing to use {}",
+                    cache.directory.display()
+                ),
+            )
+        })
+    }
+
+    /// Returns the process-wide on-disk cache, if it was enabled.
+    pub fn global() -> Option<&'static Self> {
+        DISK_MODULE_CACHE.get()
+    }
+
+    /// Returns the serialized module stored for `key`, if there is one and it is intact.
+    ///
+    /// Corrupted or mismatched entries are removed.
+    pub fn load(&self, key: &DiskCacheKey) -> Option<Vec<u8>> {
+        let path = self.entry_path(key);
+        let contents = match fs::read(&path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return None,
+            Err(error) => {
+                tracing::warn!("Failed to read cached module {}: {error}", path.display());
+                return None;
+            }
+        };
+        match bcs::from_bytes::<DiskCacheEntry>(&contents) {
+            Ok(entry) if entry.key == *key && entry.checksum == CryptoHash::new(&entry.module) => {
+                Some(entry.module.bytes)
+            }
+            _ => {
+                tracing::warn!("Removing corrupted cached module {}", path.display());
+                self.remove(key);
+                None
+            }
+        }
+    }
+
+    /// Stores the serialized `module` for `key`, then evicts the oldest entries if the cache
+    /// grew larger than its maximum size.
+    ///
+    /// Failures are logged and otherwise ignored, since the module can always be recompiled.
+    pub fn store(&self, key: &DiskCacheKey, module: Vec<u8>) {
+        if let Err(error) = self.try_store(key, module) {
+            tracing::warn!("Failed to store compiled module in the on-disk cache: {error}");
+        }
+        if let Err(error) = self.evict() {
+            tracing::warn!("Failed to evict entries from the on-disk module cache: {error}");
+        }
+    }
+
+    /// Removes the entry for `key`, for instance because the runtime refused to load it.
+    pub fn remove(&self, key: &DiskCacheKey) {
+        let path = self.entry_path(key);
+        if let Err(error) = fs::remove_file(&path) {
+            if error.kind() != io::ErrorKind::NotFound {
+                tracing::warn!("Failed to remove cached module {}: {error}", path.display());
+            }
+        }
+    }
+
+    /// Writes the entry to a uniquely named temporary file and moves it in place, so that
+    /// concurrent readers never observe a partially written entry and concurrent writers do
+    /// not interfere with each other.
+    fn try_store(&self, key: &DiskCacheKey, bytes: Vec<u8>) -> Result<(), io::Error> {
+        let module = SerializedModule { bytes };
+        let entry = DiskCacheEntry {
+            key: key.clone(),
+            checksum: CryptoHash::new(&module),
+            module,
+        };
+        let contents =
+            bcs::to_bytes(&entry).map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+        let mut temporary_file = NamedTempFile::new_in(&self.directory)?;
+        temporary_file.write_all(&contents)?;
+        temporary_file
+            .persist(self.entry_path(key))
+            .map_err(|error| error.error)?;
+        Ok(())
+    }
+
+    /// Removes the least recently written entries until the total size of the cache is at
+    /// most `max_size`.
+    fn evict(&self) -> Result<(), io::Error> {
+        let mut entries = Vec::new();
+        let mut total_size = 0;
+        for dir_entry in fs::read_dir(&self.directory)? {
+            let dir_entry = dir_entry?;
+            let path = dir_entry.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some(ENTRY_EXTENSION) {
+                continue;
+            }
+            let metadata = dir_entry.metadata()?;
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            total_size += metadata.len();
+            entries.push((modified, metadata.len(), path));
+        }
+        entries.sort();
+        for (_modified, size, path) in entries {
+            if total_size <= self.max_size {
+                break;
+            }
+            fs::remove_file(&path)?;
+            total_size -= size;
+        }
+        Ok(())
+    }
+
+    /// Returns the path of the file storing the entry for `key`.
+    fn entry_path(&self, key: &DiskCacheKey) -> PathBuf {
+        self.directory.join(format!("{key}.{ENTRY_EXTENSION}"))
+    }
+}
+
+impl DiskCacheKey {
+    /// Creates the key for the `bytecode` of a module of the given `kind`, compiled by
+    /// `runtime` with a compilation engine of version `engine_version` and the given
+    /// `configuration`.
+    pub fn new(
+        runtime: &str,
+        engine_version: &str,
+        configuration: &CompilerConfiguration,
+        kind: ModuleKind,
+        bytecode: &Bytecode,
+    ) -> Self {
+        DiskCacheKey {
+            runtime: runtime.to_owned(),
+            linera_version: env!("CARGO_PKG_VERSION").to_owned(),
+            engine_version: engine_version.to_owned(),
+            configuration_hash: CryptoHash::new(configuration),
+            kind,
+            bytecode_hash: CryptoHash::new(bytecode),
+        }
+    }
+}
+
+impl Display for DiskCacheKey {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            ModuleKind::Contract => "contract",
+            ModuleKind::Service => "service",
+        };
+        write!(
+            formatter,
+            "{}-{}-{}-{}-{}-{}",
+            self.runtime,
+            self.linera_version,
+            self.engine_version,
+            self.configuration_hash,
+            kind,
+            self.bytecode_hash
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompilerConfiguration, DiskCacheKey, DiskModuleCache, ModuleKind};
+    use crate::Bytecode;
+    use std::fs;
+
+    fn key(bytes: &[u8]) -> DiskCacheKey {
+        DiskCacheKey::new(
+            "test",
+            "1.0",
+            &CompilerConfiguration::default(),
+            ModuleKind::Contract,
+            &Bytecode::new(bytes.to_vec()),
+        )
+    }
+
+    /// Tests that a stored module is loaded back, and that keys differing only by their kind,
+    /// engine version or compiler configuration do not share entries.
+    #[test]
+    fn stored_modules_are_loaded_back() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache = DiskModuleCache::new(directory.path(), 1024).unwrap();
+        let contract_key = key(b"bytecode");
+
+        assert_eq!(cache.load(&contract_key), None);
+        cache.store(&contract_key, vec![1, 2, 3]);
+        assert_eq!(cache.load(&contract_key), Some(vec![1, 2, 3]));
+
+        let bytecode = Bytecode::new(b"bytecode".to_vec());
+        let configuration = CompilerConfiguration::default();
+        let service_key = DiskCacheKey::new(
+            "test",
+            "1.0",
+            &configuration,
+            ModuleKind::Service,
+            &bytecode,
+        );
+        let upgraded_key = DiskCacheKey::new(
+            "test",
+            "2.0",
+            &configuration,
+            ModuleKind::Contract,
+            &bytecode,
+        );
+        let metered_configuration = CompilerConfiguration {
+            fuel_metering: true,
+            operator_costs: vec![0, 1],
+            ..CompilerConfiguration::default()
+        };
+        let metered_key = DiskCacheKey::new(
+            "test",
+            "1.0",
+            &metered_configuration,
+            ModuleKind::Contract,
+            &bytecode,
+        );
+        assert_eq!(cache.load(&service_key), None);
+        assert_eq!(cache.load(&upgraded_key), None);
+        assert_eq!(cache.load(&metered_key), None);
+    }
+
+    /// Tests that a corrupted entry is discarded instead of being returned.
+    #[test]
+    fn corrupted_entries_are_removed() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache = DiskModuleCache::new(directory.path(), 1024).unwrap();
+        let key = key(b"bytecode");
+        cache.store(&key, vec![1, 2, 3]);
+
+        let path = cache.entry_path(&key);
+        let mut contents = fs::read(&path).unwrap();
+        let last = contents.len() - 1;
+        contents[last] ^= 0xff;
+        fs::write(&path, contents).unwrap();
+
+        assert_eq!(cache.load(&key), None);
+        assert!(!path.exists());
+    }
+
+    /// Tests that concurrent writers of the same entry do not interfere with each other.
+    #[test]
+    fn concurrent_stores_do_not_conflict() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache = DiskModuleCache::new(directory.path(), 1 << 20).unwrap();
+        let key = key(b"bytecode");
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| cache.store(&key, vec![7; 4096]));
+            }
+        });
+
+        assert_eq!(cache.load(&key), Some(vec![7; 4096]));
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 1);
+    }
+
+    /// Tests that the oldest entries are evicted when the cache exceeds its maximum size.
+    #[test]
+    fn oldest_entries_are_evicted() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache = DiskModuleCache::new(directory.path(), 1024).unwrap();
+        let old_key = key(b"old");
+        let new_key = key(b"new");
+
+        cache.store(&old_key, vec![0; 600]);
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        cache.store(&new_key, vec![0; 600]);
+
+        assert_eq!(cache.load(&old_key), None);
+        assert_eq!(cache.load(&new_key), Some(vec![0; 600]));
+    }
+}
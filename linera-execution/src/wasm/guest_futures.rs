@@ -13,18 +13,44 @@ use super::{
         ExecutionError,
     },
     contract::{
-        ExecuteMessage, ExecuteOperation, HandleApplicationCall, HandleSessionCall, Initialize,
-        PollApplicationCallResult, PollExecutionResult, PollSessionCallResult,
+        ExecuteMessage, ExecuteOperation, HandleAcknowledgement, HandleApplicationCall,
+        HandleBouncedMessage, HandleSessionCall, Initialize, PollApplicationCallResult,
+        PollExecutionResult, PollSessionCallResult, UserError,
     },
     service::{HandleQuery, PollApplicationQueryResult},
 };
 use crate::{
-    ApplicationCallResult, CalleeContext, MessageContext, OperationContext, QueryContext,
-    RawExecutionResult, SessionCallResult,
+    ApplicationCallResult, CalleeContext, MessageContext, MessageOutcome, OperationContext,
+    QueryContext, RawExecutionResult, SessionCallResult,
 };
 use linera_base::identifiers::SessionId;
 use std::task::Poll;
 
+/// An error reported by a guest Wasm module when one of its futures fails.
+trait GuestError {
+    /// Converts the error into the [`ExecutionError`] reported by the user application.
+    fn into_execution_error(self) -> ExecutionError;
+}
+
+/// Errors reported with only a message have no error code.
+impl GuestError for String {
+    fn into_execution_error(self) -> ExecutionError {
+        ExecutionError::UserError {
+            code: 0,
+            message: self,
+        }
+    }
+}
+
+impl GuestError for UserError {
+    fn into_execution_error(self) -> ExecutionError {
+        ExecutionError::UserError {
+            code: self.code,
+            message: self.message,
+        }
+    }
+}
+
 /// Implements [`GuestFutureInterface`] for a `future` type implemented by a guest Wasm module.
 ///
 /// The future is then polled by calling the guest `poll_func`. The return type of that function is
@@ -68,8 +94,8 @@ macro_rules! impl_guest_future_interface {
                         Ok($poll_type::Ready(Ok(result))) => {
                             Poll::Ready(Ok(result.into()))
                         }
-                        Ok($poll_type::Ready(Err(message))) => {
-                            Poll::Ready(Err(ExecutionError::UserError(message).into()))
+                        Ok($poll_type::Ready(Err(error))) => {
+                            Poll::Ready(Err(error.into_execution_error()))
                         }
                         Ok($poll_type::Pending) => Poll::Pending,
                         Err(error) => Poll::Ready(Err(error.into())),
@@ -105,6 +131,26 @@ impl_guest_future_interface! {
         output_type = RawExecutionResult<Vec<u8>>,
     },
 
+    HandleBouncedMessage: {
+        application_trait = Contract,
+        new_function = handle_bounced_message_new(context: MessageContext, message: Vec<u8>),
+        poll_function = handle_bounced_message_poll,
+        poll_type = PollExecutionResult,
+        output_type = RawExecutionResult<Vec<u8>>,
+    },
+
+    HandleAcknowledgement: {
+        application_trait = Contract,
+        new_function = handle_acknowledgement_new(
+            context: MessageContext,
+            message: Vec<u8>,
+            outcome: MessageOutcome,
+        ),
+        poll_function = handle_acknowledgement_poll,
+        poll_type = PollExecutionResult,
+        output_type = RawExecutionResult<Vec<u8>>,
+    },
+
     HandleApplicationCall: {
         application_trait = Contract,
         new_function = handle_application_call_new(
@@ -13,7 +13,9 @@
 mod async_boundary;
 mod async_determinism;
 mod common;
+mod disk_module_cache;
 mod module_cache;
+mod profiling;
 mod runtime_actor;
 mod sanitizer;
 #[macro_use]
@@ -25,15 +27,22 @@ mod wasmer;
 #[path = "wasmtime.rs"]
 mod wasmtime;
 
-use self::{runtime_actor::RuntimeActor, sanitizer::sanitize};
+pub use self::profiling::instrument_for_profiling;
+
+use self::{disk_module_cache::DiskModuleCache, runtime_actor::RuntimeActor, sanitizer::sanitize};
 use crate::{
+    profiler::{FuelProfiler, ModuleProfiler},
     ApplicationCallResult, Bytecode, CalleeContext, ContractRuntime, ExecutionError,
-    MessageContext, OperationContext, QueryContext, RawExecutionResult, ServiceRuntime,
-    SessionCallResult, SessionId, UserApplication, WasmRuntime,
+    MessageContext, MessageOutcome, OperationContext, QueryContext, RawExecutionResult,
+    ServiceRuntime, SessionCallResult, SessionId, UserApplication, WasmRuntime,
 };
 use async_trait::async_trait;
 use futures::future;
-use std::{path::Path, sync::Arc};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use thiserror::Error;
 
 /// A user application in a compiled WebAssembly module.
@@ -42,11 +51,13 @@ pub enum WasmApplication {
     Wasmer {
         contract: (::wasmer::Engine, ::wasmer::Module),
         service: Arc<::wasmer::Module>,
+        profiler: Option<ModuleProfiler>,
     },
     #[cfg(feature = "wasmtime")]
     Wasmtime {
         contract: Arc<::wasmtime::Module>,
         service: Arc<::wasmtime::Module>,
+        profiler: Option<ModuleProfiler>,
     },
 }
 
@@ -76,6 +87,40 @@ impl WasmApplication {
         }
     }
 
+    /// Creates a new [`WasmApplication`] using the WebAssembly module with the provided bytecodes,
+    /// with the contract instrumented so that the fuel it consumes is attributed to its functions
+    /// by the `profiler`.
+    pub async fn new_with_fuel_profiler(
+        contract_bytecode: Bytecode,
+        service_bytecode: Bytecode,
+        runtime: WasmRuntime,
+        profiler: FuelProfiler,
+    ) -> Result<Self, WasmExecutionError> {
+        let (contract_bytecode, function_names) = instrument_for_profiling(contract_bytecode)
+            .map_err(WasmExecutionError::LoadContractModule)?;
+        let mut application = Self::new(contract_bytecode, service_bytecode, runtime).await?;
+        let module_profiler = ModuleProfiler::new(profiler, function_names);
+        match &mut application {
+            #[cfg(feature = "wasmer")]
+            WasmApplication::Wasmer { profiler, .. } => *profiler = Some(module_profiler),
+            #[cfg(feature = "wasmtime")]
+            WasmApplication::Wasmtime { profiler, .. } => *profiler = Some(module_profiler),
+        }
+        Ok(application)
+    }
+
+    /// Enables the on-disk cache of compiled modules in `directory`, so that applications
+    /// don't need to be recompiled after a restart. The total size of the cache is kept below
+    /// `max_size` bytes.
+    ///
+    /// The cache can only be enabled once per process, before any application is loaded.
+    pub fn enable_disk_module_cache(
+        directory: impl Into<PathBuf>,
+        max_size: u64,
+    ) -> Result<(), WasmExecutionError> {
+        DiskModuleCache::enable(directory, max_size).map_err(WasmExecutionError::DiskModuleCache)
+    }
+
     /// Creates a new [`WasmApplication`] using the WebAssembly module in `bytecode_file`.
     pub async fn from_files(
         contract_bytecode_file: impl AsRef<Path>,
@@ -120,6 +165,29 @@ pub enum WasmExecutionError {
     MissingRuntimeResponse,
     #[error("Execution of guest future was aborted")]
     Aborted,
+    #[error("Failed to enable the on-disk cache of compiled modules")]
+    DiskModuleCache(#[source] io::Error),
+}
+
+#[cfg(any(feature = "wasmer", feature = "wasmtime"))]
+impl WasmExecutionError {
+    /// Returns a description of the error if it was caused by the application trapping, or
+    /// `None` if it was caused by the node executing it.
+    ///
+    /// The description doesn't mention the Wasm runtime, so that all the validators agree on it.
+    pub(crate) fn transaction_failure(&self) -> Option<String> {
+        match self {
+            #[cfg(feature = "wasmer")]
+            WasmExecutionError::ExecuteModuleInWasmer(_) => {
+                Some("Failed to execute Wasm module".to_owned())
+            }
+            #[cfg(feature = "wasmtime")]
+            WasmExecutionError::ExecuteModuleInWasmtime(_) => {
+                Some("Failed to execute Wasm module".to_owned())
+            }
+            _ => None,
+        }
+    }
 }
 
 #[async_trait]
@@ -134,15 +202,25 @@ impl UserApplication for WasmApplication {
 
         let wasm_result_receiver = match self {
             #[cfg(feature = "wasmtime")]
-            WasmApplication::Wasmtime { contract, .. } => {
-                Self::prepare_contract_runtime_with_wasmtime(contract, runtime_requests)?
-                    .initialize(context, argument)
-            }
+            WasmApplication::Wasmtime {
+                contract, profiler, ..
+            } => Self::prepare_contract_runtime_with_wasmtime(
+                contract,
+                runtime_requests,
+                runtime.runtime_limits(),
+                profiler.as_ref(),
+            )?
+            .initialize(context, argument),
             #[cfg(feature = "wasmer")]
-            WasmApplication::Wasmer { contract, .. } => {
-                Self::prepare_contract_runtime_with_wasmer(contract, runtime_requests)?
-                    .initialize(context, argument)
-            }
+            WasmApplication::Wasmer {
+                contract, profiler, ..
+            } => Self::prepare_contract_runtime_with_wasmer(
+                contract,
+                runtime_requests,
+                runtime.runtime_limits(),
+                profiler.as_ref(),
+            )?
+            .initialize(context, argument),
         };
 
         let (runtime_result, wasm_result) =
@@ -162,15 +240,25 @@ impl UserApplication for WasmApplication {
 
         let wasm_result_receiver = match self {
             #[cfg(feature = "wasmtime")]
-            WasmApplication::Wasmtime { contract, .. } => {
-                Self::prepare_contract_runtime_with_wasmtime(contract, runtime_requests)?
-                    .execute_operation(context, operation)
-            }
+            WasmApplication::Wasmtime {
+                contract, profiler, ..
+            } => Self::prepare_contract_runtime_with_wasmtime(
+                contract,
+                runtime_requests,
+                runtime.runtime_limits(),
+                profiler.as_ref(),
+            )?
+            .execute_operation(context, operation),
             #[cfg(feature = "wasmer")]
-            WasmApplication::Wasmer { contract, .. } => {
-                Self::prepare_contract_runtime_with_wasmer(contract, runtime_requests)?
-                    .execute_operation(context, operation)
-            }
+            WasmApplication::Wasmer {
+                contract, profiler, ..
+            } => Self::prepare_contract_runtime_with_wasmer(
+                contract,
+                runtime_requests,
+                runtime.runtime_limits(),
+                profiler.as_ref(),
+            )?
+            .execute_operation(context, operation),
         };
 
         let (runtime_result, wasm_result) =
@@ -190,15 +278,102 @@ impl UserApplication for WasmApplication {
 
         let wasm_result_receiver = match self {
             #[cfg(feature = "wasmtime")]
-            WasmApplication::Wasmtime { contract, .. } => {
-                Self::prepare_contract_runtime_with_wasmtime(contract, runtime_requests)?
-                    .execute_message(context, message)
-            }
+            WasmApplication::Wasmtime {
+                contract, profiler, ..
+            } => Self::prepare_contract_runtime_with_wasmtime(
+                contract,
+                runtime_requests,
+                runtime.runtime_limits(),
+                profiler.as_ref(),
+            )?
+            .execute_message(context, message),
             #[cfg(feature = "wasmer")]
-            WasmApplication::Wasmer { contract, .. } => {
-                Self::prepare_contract_runtime_with_wasmer(contract, runtime_requests)?
-                    .execute_message(context, message)
-            }
+            WasmApplication::Wasmer {
+                contract, profiler, ..
+            } => Self::prepare_contract_runtime_with_wasmer(
+                contract,
+                runtime_requests,
+                runtime.runtime_limits(),
+                profiler.as_ref(),
+            )?
+            .execute_message(context, message),
+        };
+
+        let (runtime_result, wasm_result) =
+            future::join(runtime_actor.run(), wasm_result_receiver).await;
+
+        runtime_result?;
+        wasm_result
+    }
+
+    async fn handle_bounced_message(
+        &self,
+        context: &MessageContext,
+        runtime: &dyn ContractRuntime,
+        message: &[u8],
+    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError> {
+        let (runtime_actor, runtime_requests) = RuntimeActor::new(runtime);
+
+        let wasm_result_receiver = match self {
+            #[cfg(feature = "wasmtime")]
+            WasmApplication::Wasmtime {
+                contract, profiler, ..
+            } => Self::prepare_contract_runtime_with_wasmtime(
+                contract,
+                runtime_requests,
+                runtime.runtime_limits(),
+                profiler.as_ref(),
+            )?
+            .handle_bounced_message(context, message),
+            #[cfg(feature = "wasmer")]
+            WasmApplication::Wasmer {
+                contract, profiler, ..
+            } => Self::prepare_contract_runtime_with_wasmer(
+                contract,
+                runtime_requests,
+                runtime.runtime_limits(),
+                profiler.as_ref(),
+            )?
+            .handle_bounced_message(context, message),
+        };
+
+        let (runtime_result, wasm_result) =
+            future::join(runtime_actor.run(), wasm_result_receiver).await;
+
+        runtime_result?;
+        wasm_result
+    }
+
+    async fn handle_acknowledgement(
+        &self,
+        context: &MessageContext,
+        runtime: &dyn ContractRuntime,
+        message: &[u8],
+        outcome: MessageOutcome,
+    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError> {
+        let (runtime_actor, runtime_requests) = RuntimeActor::new(runtime);
+
+        let wasm_result_receiver = match self {
+            #[cfg(feature = "wasmtime")]
+            WasmApplication::Wasmtime {
+                contract, profiler, ..
+            } => Self::prepare_contract_runtime_with_wasmtime(
+                contract,
+                runtime_requests,
+                runtime.runtime_limits(),
+                profiler.as_ref(),
+            )?
+            .handle_acknowledgement(context, message, outcome),
+            #[cfg(feature = "wasmer")]
+            WasmApplication::Wasmer {
+                contract, profiler, ..
+            } => Self::prepare_contract_runtime_with_wasmer(
+                contract,
+                runtime_requests,
+                runtime.runtime_limits(),
+                profiler.as_ref(),
+            )?
+            .handle_acknowledgement(context, message, outcome),
         };
 
         let (runtime_result, wasm_result) =
@@ -219,15 +394,25 @@ impl UserApplication for WasmApplication {
 
         let wasm_result_receiver = match self {
             #[cfg(feature = "wasmtime")]
-            WasmApplication::Wasmtime { contract, .. } => {
-                Self::prepare_contract_runtime_with_wasmtime(contract, runtime_requests)?
-                    .handle_application_call(context, argument, forwarded_sessions)
-            }
+            WasmApplication::Wasmtime {
+                contract, profiler, ..
+            } => Self::prepare_contract_runtime_with_wasmtime(
+                contract,
+                runtime_requests,
+                runtime.runtime_limits(),
+                profiler.as_ref(),
+            )?
+            .handle_application_call(context, argument, forwarded_sessions),
             #[cfg(feature = "wasmer")]
-            WasmApplication::Wasmer { contract, .. } => {
-                Self::prepare_contract_runtime_with_wasmer(contract, runtime_requests)?
-                    .handle_application_call(context, argument, forwarded_sessions)
-            }
+            WasmApplication::Wasmer {
+                contract, profiler, ..
+            } => Self::prepare_contract_runtime_with_wasmer(
+                contract,
+                runtime_requests,
+                runtime.runtime_limits(),
+                profiler.as_ref(),
+            )?
+            .handle_application_call(context, argument, forwarded_sessions),
         };
 
         let (runtime_result, wasm_result) =
@@ -249,15 +434,35 @@ impl UserApplication for WasmApplication {
 
         let wasm_result_receiver = match self {
             #[cfg(feature = "wasmtime")]
-            WasmApplication::Wasmtime { contract, .. } => {
-                Self::prepare_contract_runtime_with_wasmtime(contract, runtime_requests)?
-                    .handle_session_call(context, &*session_state, argument, forwarded_sessions)
-            }
+            WasmApplication::Wasmtime {
+                contract, profiler, ..
+            } => Self::prepare_contract_runtime_with_wasmtime(
+                contract,
+                runtime_requests,
+                runtime.runtime_limits(),
+                profiler.as_ref(),
+            )?
+            .handle_session_call(
+                context,
+                &*session_state,
+                argument,
+                forwarded_sessions,
+            ),
             #[cfg(feature = "wasmer")]
-            WasmApplication::Wasmer { contract, .. } => {
-                Self::prepare_contract_runtime_with_wasmer(contract, runtime_requests)?
-                    .handle_session_call(context, &*session_state, argument, forwarded_sessions)
-            }
+            WasmApplication::Wasmer {
+                contract, profiler, ..
+            } => Self::prepare_contract_runtime_with_wasmer(
+                contract,
+                runtime_requests,
+                runtime.runtime_limits(),
+                profiler.as_ref(),
+            )?
+            .handle_session_call(
+                context,
+                &*session_state,
+                argument,
+                forwarded_sessions,
+            ),
         };
 
         let (runtime_result, wasm_result) =
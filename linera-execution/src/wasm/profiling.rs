@@ -0,0 +1,533 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Instrumentation of WebAssembly bytecodes so that the fuel they consume can be attributed to
+//! their functions by a [`FuelProfiler`][`crate::profiler::FuelProfiler`].
+//!
+//! Two functions are imported from the [`PROFILER_MODULE`], and every function of the module is
+//! changed to call the first one with its index when it is entered, and the second one with its
+//! index when it is exited. The body of each function is wrapped in a block, and `return`
+//! instructions are replaced by branches to the end of that block, so that the exit is always
+//! reported.
+//!
+//! Importing functions shifts the indices of the functions defined in the module, so every
+//! reference to them is updated. The name section is dropped because of that, but the function
+//! names it contains are returned separately, indexed by the original function indices.
+
+use super::sanitizer::Sanitizer;
+use crate::{
+    profiler::{PROFILER_ENTER_FUNCTION, PROFILER_EXIT_FUNCTION, PROFILER_MODULE},
+    Bytecode,
+};
+use anyhow::{ensure, Context};
+use std::{mem, sync::Arc};
+use wasm_encoder::{
+    BlockType, CodeSection, ConstExpr, ElementSection, Elements, Encode, EntityType, ExportKind,
+    ExportSection, Function, Instruction, RawSection, SectionId, StartSection, TypeSection,
+};
+use wasmparser::{
+    BinaryReaderError, CustomSectionReader, ElementItems, ElementKind, ElementSectionReader,
+    ExportSectionReader, ExternalKind, FunctionBody, FunctionSectionReader, ImportSectionReader,
+    Name, NameSectionReader, Operator, Parser, Payload, Type, TypeRef, TypeSectionReader,
+};
+
+/// Instruments the input `bytecode` so that its functions report when they are entered and
+/// exited.
+///
+/// Returns the instrumented bytecode and the names of the functions of the original module,
+/// indexed by their original index.
+pub fn instrument_for_profiling(
+    bytecode: Bytecode,
+) -> Result<(Bytecode, Arc<[String]>), anyhow::Error> {
+    let mut instrumenter = Instrumenter::new(bytecode.as_ref());
+    let instrumented_bytecode = instrumenter.instrument()?;
+
+    Ok((
+        Bytecode::new(instrumented_bytecode),
+        instrumenter.function_names(),
+    ))
+}
+
+/// Instrumenter of WebAssembly bytecodes for profiling.
+///
+/// See the module level documentation for more information.
+struct Instrumenter<'bytecode> {
+    bytecode: &'bytecode [u8],
+    output: wasm_encoder::Module,
+    body_block_types: Vec<BlockType>,
+    hook_type: Option<u32>,
+    imports_written: bool,
+    imported_functions: u32,
+    defined_function_types: Vec<u32>,
+    instrumented_functions: u32,
+    code_section: Option<CodeSection>,
+    function_names: Vec<Option<String>>,
+}
+
+impl<'bytecode> Instrumenter<'bytecode> {
+    /// Creates a new [`Instrumenter`] for the WebAssembly `bytecode`.
+    fn new(bytecode: &'bytecode [u8]) -> Self {
+        Instrumenter {
+            bytecode,
+            output: wasm_encoder::Module::new(),
+            body_block_types: Vec::new(),
+            hook_type: None,
+            imports_written: false,
+            imported_functions: 0,
+            defined_function_types: Vec::new(),
+            instrumented_functions: 0,
+            code_section: None,
+            function_names: Vec::new(),
+        }
+    }
+
+    /// Instruments the input `bytecode`, and returns the instrumented bytecode.
+    fn instrument(&mut self) -> Result<Vec<u8>, anyhow::Error> {
+        for payload in Parser::new(0).parse_all(self.bytecode) {
+            let payload = payload?;
+
+            if !matches!(payload, Payload::CodeSectionEntry(_)) {
+                if let Some(code_section) = self.code_section.take() {
+                    self.output.section(&code_section);
+                }
+            }
+
+            if self.hook_type.is_some()
+                && !self.imports_written
+                && !matches!(
+                    payload,
+                    Payload::ImportSection(_) | Payload::CustomSection(_)
+                )
+            {
+                self.write_import_section(None)?;
+            }
+
+            match payload {
+                Payload::Version { .. } | Payload::End(_) => {}
+                Payload::TypeSection(reader) => self.write_type_section(reader)?,
+                Payload::ImportSection(reader) => self.write_import_section(Some(reader))?,
+                Payload::FunctionSection(reader) => self.write_function_section(reader)?,
+                Payload::ExportSection(reader) => self.write_export_section(reader)?,
+                Payload::StartSection { func, .. } => {
+                    let function_index = self.shift(func);
+                    self.output.section(&StartSection { function_index });
+                }
+                Payload::ElementSection(reader) => self.write_element_section(reader)?,
+                Payload::CodeSectionStart { .. } => self.code_section = Some(CodeSection::new()),
+                Payload::CodeSectionEntry(body) => {
+                    let function = self.instrument_function(body)?;
+                    self.code_section
+                        .as_mut()
+                        .context("Function body outside of the code section")?
+                        .function(&function);
+                }
+                Payload::CustomSection(reader) if reader.name() == "name" => {
+                    self.read_function_names(reader);
+                }
+                payload => {
+                    if let Some((id, range)) = payload.as_section() {
+                        self.output.section(&RawSection {
+                            id,
+                            data: &self.bytecode[range],
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(mem::replace(&mut self.output, wasm_encoder::Module::new()).finish())
+    }
+
+    /// Returns the names of the functions of the original module, indexed by their original
+    /// index.
+    fn function_names(&self) -> Arc<[String]> {
+        self.function_names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                name.clone()
+                    .unwrap_or_else(|| format!("function#{index}"))
+                    .replace(';', ":")
+            })
+            .collect()
+    }
+
+    /// Copies the type section, adding the type of the profiling functions and the types of the
+    /// blocks wrapping functions that return multiple values.
+    fn write_type_section(&mut self, reader: TypeSectionReader<'_>) -> Result<(), anyhow::Error> {
+        let mut function_types = Vec::new();
+        for parsed_type in reader {
+            #[allow(unreachable_patterns)]
+            match parsed_type? {
+                Type::Func(function_type) => function_types.push(function_type),
+                _ => anyhow::bail!("Unsupported type in Wasm module"),
+            }
+        }
+
+        let mut section = TypeSection::new();
+        let hook_type = u32::try_from(function_types.len())?;
+        let mut multi_value_types = Vec::new();
+        for function_type in &function_types {
+            section.function(
+                function_type
+                    .params()
+                    .iter()
+                    .map(|param| Sanitizer::convert_type(*param)),
+                function_type
+                    .results()
+                    .iter()
+                    .map(|result| Sanitizer::convert_type(*result)),
+            );
+            let block_type = match function_type.results() {
+                [] => BlockType::Empty,
+                [result] => BlockType::Result(Sanitizer::convert_type(*result)),
+                results => {
+                    multi_value_types.push(results);
+                    BlockType::FunctionType(hook_type + u32::try_from(multi_value_types.len())?)
+                }
+            };
+            self.body_block_types.push(block_type);
+        }
+
+        section.function([wasm_encoder::ValType::I32], []);
+        for results in multi_value_types {
+            section.function(
+                [],
+                results
+                    .iter()
+                    .map(|result| Sanitizer::convert_type(*result)),
+            );
+        }
+
+        self.output.section(&section);
+        self.hook_type = Some(hook_type);
+        Ok(())
+    }
+
+    /// Copies the import section, adding the imports of the profiling functions.
+    fn write_import_section(
+        &mut self,
+        reader: Option<ImportSectionReader<'_>>,
+    ) -> Result<(), anyhow::Error> {
+        let hook_type = self
+            .hook_type
+            .context("Missing type section in Wasm module")?;
+        let mut data = Vec::new();
+
+        match reader {
+            Some(reader) => {
+                for import in reader.clone() {
+                    let import = import?;
+                    ensure!(
+                        import.module != PROFILER_MODULE,
+                        "Wasm module is already instrumented for profiling"
+                    );
+                    if let TypeRef::Func(_) = import.ty {
+                        self.function_names
+                            .push(Some(format!("{}::{}", import.module, import.name)));
+                    }
+                }
+                self.imported_functions = u32::try_from(self.function_names.len())?;
+
+                (reader.count() + 2).encode(&mut data);
+                data.extend_from_slice(
+                    &self.bytecode[reader.original_position()..reader.range().end],
+                );
+            }
+            None => 2_u32.encode(&mut data),
+        }
+
+        for function in [PROFILER_ENTER_FUNCTION, PROFILER_EXIT_FUNCTION] {
+            PROFILER_MODULE.encode(&mut data);
+            function.encode(&mut data);
+            EntityType::Function(hook_type).encode(&mut data);
+        }
+
+        self.output.section(&RawSection {
+            id: SectionId::Import as u8,
+            data: &data,
+        });
+        self.imports_written = true;
+        Ok(())
+    }
+
+    /// Copies the function section, keeping track of the types of the defined functions.
+    fn write_function_section(
+        &mut self,
+        reader: FunctionSectionReader<'_>,
+    ) -> Result<(), anyhow::Error> {
+        for type_index in reader.clone() {
+            self.defined_function_types.push(type_index?);
+            self.function_names.push(None);
+        }
+
+        self.output.section(&RawSection {
+            id: SectionId::Function as u8,
+            data: &self.bytecode[reader.range()],
+        });
+        Ok(())
+    }
+
+    /// Copies the export section, updating the indices of the exported functions.
+    ///
+    /// The export names are used as the names of the functions missing from the name section.
+    fn write_export_section(
+        &mut self,
+        reader: ExportSectionReader<'_>,
+    ) -> Result<(), anyhow::Error> {
+        let mut section = ExportSection::new();
+
+        for export in reader {
+            let export = export?;
+            let (kind, index) = match export.kind {
+                ExternalKind::Func => {
+                    if let Some(name) = usize::try_from(export.index)
+                        .ok()
+                        .and_then(|index| self.function_names.get_mut(index))
+                    {
+                        name.get_or_insert_with(|| export.name.to_owned());
+                    }
+                    (ExportKind::Func, self.shift(export.index))
+                }
+                ExternalKind::Table => (ExportKind::Table, export.index),
+                ExternalKind::Memory => (ExportKind::Memory, export.index),
+                ExternalKind::Global => (ExportKind::Global, export.index),
+                ExternalKind::Tag => (ExportKind::Tag, export.index),
+            };
+            section.export(export.name, kind, index);
+        }
+
+        self.output.section(&section);
+        Ok(())
+    }
+
+    /// Copies the element section, updating the indices of the referenced functions.
+    fn write_element_section(
+        &mut self,
+        reader: ElementSectionReader<'_>,
+    ) -> Result<(), anyhow::Error> {
+        let mut section = ElementSection::new();
+
+        for element in reader {
+            let element = element?;
+            let element_type = wasm_encoder::RefType {
+                nullable: element.ty.nullable,
+                heap_type: Sanitizer::convert_heap_type(element.ty.heap_type),
+            };
+            let functions;
+            let expressions;
+            let elements = match element.items {
+                ElementItems::Functions(reader) => {
+                    functions = reader
+                        .into_iter()
+                        .map(|function| function.map(|function| self.shift(function)))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Elements::Functions(&functions)
+                }
+                ElementItems::Expressions(reader) => {
+                    expressions = reader
+                        .into_iter()
+                        .map(|expression| {
+                            expression.and_then(|expression| self.convert_const_expr(expression))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Elements::Expressions(&expressions)
+                }
+            };
+            match element.kind {
+                ElementKind::Passive => {
+                    section.passive(element_type, elements);
+                }
+                ElementKind::Declared => {
+                    section.declared(element_type, elements);
+                }
+                ElementKind::Active {
+                    table_index,
+                    offset_expr,
+                } => {
+                    let offset = self.convert_const_expr(offset_expr)?;
+                    section.active(Some(table_index), &offset, element_type, elements);
+                }
+            }
+        }
+
+        self.output.section(&section);
+        Ok(())
+    }
+
+    /// Instruments the next function of the code section.
+    fn instrument_function(&mut self, body: FunctionBody<'_>) -> Result<Function, anyhow::Error> {
+        let defined_index = self.instrumented_functions;
+        self.instrumented_functions += 1;
+
+        let function_index = self.imported_functions + defined_index;
+        let block_type = usize::try_from(defined_index)
+            .ok()
+            .and_then(|index| self.defined_function_types.get(index))
+            .and_then(|type_index| {
+                self.body_block_types
+                    .get(usize::try_from(*type_index).ok()?)
+            })
+            .copied()
+            .context("Missing type of function in Wasm module")?;
+
+        let locals = Sanitizer::convert_locals(body.get_locals_reader()?)?;
+        let mut function = Function::new(locals);
+        let mut depth = 0;
+
+        self.call_hook(&mut function, self.enter_hook(), function_index);
+        function.instruction(&Instruction::Block(block_type));
+
+        for operator in body.get_operators_reader()? {
+            let operator = operator?;
+            match &operator {
+                Operator::Block { .. }
+                | Operator::Loop { .. }
+                | Operator::If { .. }
+                | Operator::Try { .. } => depth += 1,
+                Operator::End | Operator::Delegate { .. } if depth > 0 => depth -= 1,
+                Operator::End => {
+                    function.instruction(&Instruction::End);
+                    self.call_hook(&mut function, self.exit_hook(), function_index);
+                    function.instruction(&Instruction::End);
+                    continue;
+                }
+                Operator::Return => {
+                    function.instruction(&Instruction::Br(depth));
+                    continue;
+                }
+                Operator::ReturnCall { .. } | Operator::ReturnCallIndirect { .. } => {
+                    self.call_hook(&mut function, self.exit_hook(), function_index);
+                }
+                _ => {}
+            }
+            function.instruction(&self.convert_operator(operator)?);
+        }
+
+        Ok(function)
+    }
+
+    /// Adds a call to a profiling `hook` with the `function_index` to the `function`.
+    fn call_hook(&self, function: &mut Function, hook: u32, function_index: u32) {
+        function.instruction(&Instruction::I32Const(function_index as i32));
+        function.instruction(&Instruction::Call(hook));
+    }
+
+    /// Converts a constant expression, updating the indices of the referenced functions.
+    fn convert_const_expr(
+        &self,
+        expression: wasmparser::ConstExpr<'_>,
+    ) -> Result<ConstExpr, BinaryReaderError> {
+        let mut bytes = Vec::new();
+        for operator in expression.get_operators_reader() {
+            match self.convert_operator(operator?)? {
+                Instruction::End => {}
+                instruction => instruction.encode(&mut bytes),
+            }
+        }
+        Ok(ConstExpr::raw(bytes))
+    }
+
+    /// Converts an instruction, updating the index of the function it references.
+    fn convert_operator<'op>(
+        &self,
+        operator: Operator<'op>,
+    ) -> Result<Instruction<'op>, BinaryReaderError> {
+        Ok(match Sanitizer::convert_operator(operator)? {
+            Instruction::Call(function) => Instruction::Call(self.shift(function)),
+            Instruction::ReturnCall(function) => Instruction::ReturnCall(self.shift(function)),
+            Instruction::RefFunc(function) => Instruction::RefFunc(self.shift(function)),
+            instruction => instruction,
+        })
+    }
+
+    /// Reads the names of the functions from the name section.
+    fn read_function_names(&mut self, reader: CustomSectionReader<'_>) {
+        for name in NameSectionReader::new(reader.data(), reader.data_offset()) {
+            let Ok(Name::Function(names)) = name else {
+                continue;
+            };
+            for naming in names.into_iter().flatten() {
+                if let Some(function_name) = usize::try_from(naming.index)
+                    .ok()
+                    .and_then(|index| self.function_names.get_mut(index))
+                {
+                    *function_name = Some(naming.name.to_owned());
+                }
+            }
+        }
+    }
+
+    /// Returns the new index of a function, after the profiling functions were imported.
+    fn shift(&self, function_index: u32) -> u32 {
+        if function_index >= self.imported_functions {
+            function_index + 2
+        } else {
+            function_index
+        }
+    }
+
+    /// Returns the index of the imported function called when a function is entered.
+    fn enter_hook(&self) -> u32 {
+        self.imported_functions
+    }
+
+    /// Returns the index of the imported function called when a function is exited.
+    fn exit_hook(&self) -> u32 {
+        self.imported_functions + 1
+    }
+}
+
+#[cfg(all(test, feature = "wasmer"))]
+mod tests {
+    use super::instrument_for_profiling;
+    use crate::Bytecode;
+
+    /// Tests if a bytecode is properly instrumented.
+    ///
+    /// Checks that the instrumented bytecode is still valid, and that the names of the functions
+    /// are collected from the imports, the exports and the name section.
+    #[test]
+    fn instruments_bytecode() {
+        let wat = r#"
+            (module
+              (type (;0;) (func (param i32) (result i32)))
+              (type (;1;) (func (result i32 i32)))
+              (import "env" "double" (func $double (type 0)))
+              (func $increment (type 0) (param i32) (result i32)
+                local.get 0
+                i32.const 0
+                i32.eq
+                if
+                  i32.const 1
+                  return
+                end
+                local.get 0
+                call $double
+                i32.const 1
+                i32.add
+              )
+              (func (type 1) (result i32 i32)
+                i32.const 1
+                call $increment
+                i32.const 2
+              )
+              (table (;0;) 2 2 funcref)
+              (memory (;0;) 16)
+              (export "memory" (memory 0))
+              (export "pair" (func 2))
+              (elem (;0;) (i32.const 0) func $increment 2)
+            )
+        "#;
+
+        let input = Bytecode::new(wasmer::wat2wasm(wat.as_bytes()).unwrap().into_owned());
+        let (output, function_names) = instrument_for_profiling(input.clone()).unwrap();
+
+        assert_ne!(input, output);
+        assert_eq!(&*function_names, ["double", "increment", "pair"]);
+
+        // Check that the instrumented output can be used by a runtime
+        let store = wasmer::Store::default();
+        wasmer::Module::new(&store, &output).unwrap();
+    }
+}
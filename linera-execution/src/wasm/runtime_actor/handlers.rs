@@ -83,6 +83,9 @@ where
                 remaining_fuel,
                 response_sender,
             } => response_sender.respond(self.set_remaining_fuel(remaining_fuel)),
+            ContractRequest::RandomSeed { response_sender } => {
+                response_sender.respond(self.random_seed()?)
+            }
             ContractRequest::TryReadAndLockMyState { response_sender } => {
                 response_sender.respond(match self.try_read_and_lock_my_state().await {
                     Ok(bytes) => Some(bytes),
@@ -101,6 +104,16 @@ where
                 batch,
                 response_sender,
             } => response_sender.respond(self.write_batch_and_unlock(batch).await?),
+            ContractRequest::EmitEvent {
+                topic,
+                value,
+                response_sender,
+            } => response_sender.respond(self.emit_event(topic, value)?),
+            ContractRequest::Log {
+                level,
+                message,
+                response_sender,
+            } => response_sender.respond(self.log(level, message)),
             ContractRequest::TryCallApplication {
                 authenticated,
                 callee_id,
@@ -140,6 +153,20 @@ where
                 argument,
                 response_sender,
             } => response_sender.respond(self.try_query_application(queried_id, &argument).await?),
+            ServiceRequest::TryQueryRemoteApplication {
+                chain_id,
+                queried_id,
+                argument,
+                response_sender,
+            } => response_sender.respond(
+                self.try_query_remote_application(chain_id, queried_id, &argument)
+                    .await?,
+            ),
+            ServiceRequest::TryQueryRemoteSystem {
+                chain_id,
+                query,
+                response_sender,
+            } => response_sender.respond(self.try_query_remote_system(chain_id, query).await?),
         }
 
         Ok(())
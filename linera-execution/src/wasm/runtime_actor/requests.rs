@@ -3,8 +3,12 @@
 
 //! Different request types for different runtimes.
 
-use crate::{CallResult, UserApplicationId};
+use crate::{
+    trace::LogLevel, CallResult, RemoteQueryResponse, SystemQuery, SystemResponse,
+    UserApplicationId,
+};
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{Amount, Timestamp},
     identifiers::{ChainId, SessionId},
 };
@@ -131,6 +135,11 @@ pub enum ContractRequest {
         response_sender: oneshot::Sender<()>,
     },
 
+    /// Requests the pseudo-random seed of the current block.
+    RandomSeed {
+        response_sender: oneshot::Sender<CryptoHash>,
+    },
+
     /// Requests to read the application state and prevent further reading/loading until the state
     /// is saved or unlocked.
     TryReadAndLockMyState {
@@ -156,6 +165,20 @@ pub enum ContractRequest {
         response_sender: oneshot::Sender<()>,
     },
 
+    /// Requests to emit an event to be recorded in the block.
+    EmitEvent {
+        topic: Vec<u8>,
+        value: Vec<u8>,
+        response_sender: oneshot::Sender<()>,
+    },
+
+    /// Requests to log a message from the application.
+    Log {
+        level: LogLevel,
+        message: String,
+        response_sender: oneshot::Sender<()>,
+    },
+
     /// Requests to call another application.
     TryCallApplication {
         authenticated: bool,
@@ -192,6 +215,10 @@ impl Debug for ContractRequest {
                 .field("remaining_fuel", remaining_fuel)
                 .finish_non_exhaustive(),
 
+            ContractRequest::RandomSeed { .. } => formatter
+                .debug_struct("ContractRequest::RandomSeed")
+                .finish_non_exhaustive(),
+
             ContractRequest::TryReadAndLockMyState { .. } => formatter
                 .debug_struct("ContractRequest::TryReadAndLockMyState")
                 .finish_non_exhaustive(),
@@ -210,6 +237,18 @@ impl Debug for ContractRequest {
                 .field("batch", &"Batch")
                 .finish_non_exhaustive(),
 
+            ContractRequest::EmitEvent { topic, value, .. } => formatter
+                .debug_struct("ContractRequest::EmitEvent")
+                .field("topic", topic)
+                .field("value", value)
+                .finish_non_exhaustive(),
+
+            ContractRequest::Log { level, message, .. } => formatter
+                .debug_struct("ContractRequest::Log")
+                .field("level", level)
+                .field("message", message)
+                .finish_non_exhaustive(),
+
             ContractRequest::TryCallApplication {
                 authenticated,
                 callee_id,
@@ -252,6 +291,21 @@ pub enum ServiceRequest {
         argument: Vec<u8>,
         response_sender: oneshot::Sender<Vec<u8>>,
     },
+
+    /// Requests to query an application on another chain.
+    TryQueryRemoteApplication {
+        chain_id: ChainId,
+        queried_id: UserApplicationId,
+        argument: Vec<u8>,
+        response_sender: oneshot::Sender<RemoteQueryResponse>,
+    },
+
+    /// Requests to query the system state of another chain.
+    TryQueryRemoteSystem {
+        chain_id: ChainId,
+        query: SystemQuery,
+        response_sender: oneshot::Sender<RemoteQueryResponse<SystemResponse>>,
+    },
 }
 
 impl Debug for ServiceRequest {
@@ -271,6 +325,26 @@ impl Debug for ServiceRequest {
                 .field("queried_id", queried_id)
                 .field("argument", argument)
                 .finish_non_exhaustive(),
+
+            ServiceRequest::TryQueryRemoteApplication {
+                chain_id,
+                queried_id,
+                argument,
+                ..
+            } => formatter
+                .debug_struct("ServiceRequest::TryQueryRemoteApplication")
+                .field("chain_id", chain_id)
+                .field("queried_id", queried_id)
+                .field("argument", argument)
+                .finish_non_exhaustive(),
+
+            ServiceRequest::TryQueryRemoteSystem {
+                chain_id, query, ..
+            } => formatter
+                .debug_struct("ServiceRequest::TryQueryRemoteSystem")
+                .field("chain_id", chain_id)
+                .field("query", query)
+                .finish_non_exhaustive(),
         }
     }
 }
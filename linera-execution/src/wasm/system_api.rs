@@ -70,6 +70,16 @@ macro_rules! impl_contract_system_api {
                     .map_err(|oneshot::RecvError| WasmExecutionError::MissingRuntimeResponse.into())
             }
 
+            fn random_seed(&mut self) -> Result<contract_system_api::CryptoHash, Self::Error> {
+                self.runtime
+                    .send_request(|response_sender| ContractRequest::RandomSeed {
+                        response_sender,
+                    })?
+                    .recv()
+                    .map(|random_seed| random_seed.into())
+                    .map_err(|oneshot::RecvError| WasmExecutionError::MissingRuntimeResponse.into())
+            }
+
             fn load(&mut self) -> Result<Vec<u8>, Self::Error> {
                 self.runtime
                     .send_request(|response_sender| {
@@ -190,14 +200,25 @@ macro_rules! impl_contract_system_api {
                 message: &str,
                 level: contract_system_api::LogLevel,
             ) -> Result<(), Self::Error> {
-                match level {
-                    contract_system_api::LogLevel::Trace => tracing::trace!("{message}"),
-                    contract_system_api::LogLevel::Debug => tracing::debug!("{message}"),
-                    contract_system_api::LogLevel::Info => tracing::info!("{message}"),
-                    contract_system_api::LogLevel::Warn => tracing::warn!("{message}"),
-                    contract_system_api::LogLevel::Error => tracing::error!("{message}"),
-                }
-                Ok(())
+                self.runtime
+                    .send_request(|response_sender| ContractRequest::Log {
+                        level: level.into(),
+                        message: message.to_owned(),
+                        response_sender,
+                    })?
+                    .recv()
+                    .map_err(|oneshot::RecvError| WasmExecutionError::MissingRuntimeResponse.into())
+            }
+
+            fn emit_event(&mut self, topic: &[u8], value: &[u8]) -> Result<(), Self::Error> {
+                self.runtime
+                    .send_request(|response_sender| ContractRequest::EmitEvent {
+                        topic: topic.to_owned(),
+                        value: value.to_owned(),
+                        response_sender,
+                    })?
+                    .recv()
+                    .map_err(|oneshot::RecvError| WasmExecutionError::MissingRuntimeResponse.into())
             }
         }
     };
@@ -215,6 +236,9 @@ macro_rules! impl_service_system_api {
             type Lock = Mutex<oneshot::Receiver<()>>;
             type Unlock = Mutex<oneshot::Receiver<()>>;
             type TryQueryApplication = Mutex<oneshot::Receiver<Vec<u8>>>;
+            type TryQueryRemoteApplication = Mutex<oneshot::Receiver<RemoteQueryResponse>>;
+            type TryQueryRemoteSystem =
+                Mutex<oneshot::Receiver<RemoteQueryResponse<SystemResponse>>>;
 
             fn error_to_trap(&mut self, error: Self::Error) -> $trap {
                 error.into()
@@ -379,6 +403,83 @@ macro_rules! impl_service_system_api {
                 }
             }
 
+            fn try_query_remote_application_new(
+                &mut self,
+                chain_id: service_system_api::ChainId,
+                application: service_system_api::ApplicationId,
+                argument: &[u8],
+            ) -> Result<Self::TryQueryRemoteApplication, Self::Error> {
+                let argument = Vec::from(argument);
+
+                Ok(Mutex::new(self.runtime.send_request(
+                    |response_sender| ServiceRequest::TryQueryRemoteApplication {
+                        chain_id: chain_id.into(),
+                        queried_id: application.into(),
+                        argument,
+                        response_sender,
+                    },
+                )?))
+            }
+
+            fn try_query_remote_application_poll(
+                &mut self,
+                future: &Self::TryQueryRemoteApplication,
+            ) -> Result<service_system_api::PollRemoteQuery, Self::Error> {
+                use service_system_api::PollRemoteQuery;
+                let receiver = future
+                    .try_lock()
+                    .expect("Unexpected reentrant locking of `oneshot::Receiver`");
+                match receiver.try_recv() {
+                    Ok(result) => Ok(PollRemoteQuery::Ready(Ok(result.into()))),
+                    Err(oneshot::TryRecvError::Empty) => Ok(PollRemoteQuery::Pending),
+                    Err(oneshot::TryRecvError::Disconnected) => {
+                        Err(WasmExecutionError::MissingRuntimeResponse.into())
+                    }
+                }
+            }
+
+            fn try_query_remote_system_new(
+                &mut self,
+                chain_id: service_system_api::ChainId,
+                query: &[u8],
+            ) -> Result<Self::TryQueryRemoteSystem, Self::Error> {
+                let query =
+                    serde_json::from_slice(query).map_err(|_| ExecutionError::InvalidQuery)?;
+
+                Ok(Mutex::new(self.runtime.send_request(
+                    |response_sender| ServiceRequest::TryQueryRemoteSystem {
+                        chain_id: chain_id.into(),
+                        query,
+                        response_sender,
+                    },
+                )?))
+            }
+
+            fn try_query_remote_system_poll(
+                &mut self,
+                future: &Self::TryQueryRemoteSystem,
+            ) -> Result<service_system_api::PollRemoteQuery, Self::Error> {
+                use service_system_api::PollRemoteQuery;
+                let receiver = future
+                    .try_lock()
+                    .expect("Unexpected reentrant locking of `oneshot::Receiver`");
+                match receiver.try_recv() {
+                    Ok(RemoteQueryResponse { response, height }) => {
+                        let response = serde_json::to_vec(&response)
+                            .expect("`SystemResponse` can be serialized to JSON");
+                        Ok(PollRemoteQuery::Ready(Ok(RemoteQueryResponse {
+                            response,
+                            height,
+                        }
+                        .into())))
+                    }
+                    Err(oneshot::TryRecvError::Empty) => Ok(PollRemoteQuery::Pending),
+                    Err(oneshot::TryRecvError::Disconnected) => {
+                        Err(WasmExecutionError::MissingRuntimeResponse.into())
+                    }
+                }
+            }
+
             fn log(
                 &mut self,
                 message: &str,
@@ -37,28 +37,77 @@ mod guest_futures;
 use super::{
     async_determinism::{HostFutureQueue, QueuedHostFutureFactory},
     common::{self, ApplicationRuntimeContext, WasmRuntimeContext},
+    disk_module_cache::{CompilerConfiguration, DiskCacheKey, DiskModuleCache, ModuleKind},
     module_cache::ModuleCache,
     runtime_actor::{BaseRequest, ContractRequest, SendRequestExt, ServiceRequest},
     WasmApplication, WasmExecutionError,
 };
 use crate::{
-    Bytecode, CalleeContext, ContractRuntime, ExecutionError, MessageContext, OperationContext,
-    QueryContext, ServiceRuntime,
+    profiler::{
+        InstanceProfiler, ModuleProfiler, PROFILER_ENTER_FUNCTION, PROFILER_EXIT_FUNCTION,
+        PROFILER_MODULE,
+    },
+    Bytecode, CalleeContext, ContractRuntime, ExecutionError, MessageContext, MessageOutcome,
+    OperationContext, QueryContext, RemoteQueryResponse, RuntimeLimits, ServiceRuntime,
+    SystemResponse,
 };
 use bytes::Bytes;
 use futures::{channel::mpsc, TryFutureExt};
 use linera_base::identifiers::SessionId;
 use linera_views::{batch::Batch, views::ViewError};
 use once_cell::sync::Lazy;
-use std::{marker::PhantomData, mem, sync::Arc};
+use std::{marker::PhantomData, mem, ptr::NonNull, sync::Arc};
 use tokio::sync::Mutex;
 use wasmer::{
-    imports, wasmparser::Operator, CompilerConfig, Engine, EngineBuilder, Instance, Module,
-    RuntimeError, Singlepass, Store,
+    imports,
+    vm::{
+        MemoryError, MemoryStyle, TableStyle, VMMemory, VMMemoryDefinition, VMTable,
+        VMTableDefinition,
+    },
+    wasmparser::Operator,
+    AsStoreMut, BaseTunables, CompilerConfig, Engine, EngineBuilder, ExternType, Function,
+    FunctionEnv, FunctionEnvMut, Imports, Instance, MemoryType, Module, Pages, RuntimeError,
+    Singlepass, Store, TableType, Target, Tunables,
 };
 use wasmer_middlewares::metering::{self, Metering, MeteringPoints};
 use wit_bindgen_host_wasmer_rust::Le;
 
+/// The version of Wasmer compiling the modules, used to key the on-disk module cache.
+const ENGINE_VERSION: &str = wasmer::VERSION;
+
+/// The configuration of [`CachedContractModule::create_compilation_engine`], used to key the
+/// on-disk module cache.
+static CONTRACT_COMPILER_CONFIGURATION: Lazy<CompilerConfiguration> =
+    Lazy::new(|| CompilerConfiguration {
+        compiler: "singlepass".to_owned(),
+        canonicalize_nans: true,
+        fuel_metering: true,
+        operator_costs: [
+            Operator::Unreachable,
+            Operator::Nop,
+            Operator::Else,
+            Operator::End,
+            Operator::Return,
+            Operator::Drop,
+            Operator::Select,
+            Operator::I32Eqz,
+            Operator::I32Add,
+            Operator::I64Mul,
+            Operator::F32Div,
+            Operator::F64Sqrt,
+        ]
+        .iter()
+        .map(WasmApplication::operation_cost)
+        .collect(),
+    });
+
+/// The configuration of [`SERVICE_ENGINE`], used to key the on-disk module cache.
+static SERVICE_COMPILER_CONFIGURATION: Lazy<CompilerConfiguration> =
+    Lazy::new(|| CompilerConfiguration {
+        compiler: "singlepass".to_owned(),
+        ..CompilerConfiguration::default()
+    });
+
 /// An [`Engine`] instance configured to run application services.
 static SERVICE_ENGINE: Lazy<Engine> = Lazy::new(|| {
     let compiler_config = Singlepass::default();
@@ -121,6 +170,21 @@ impl ApplicationRuntimeContext for Contract {
             .recv()
             .map_err(|_| ())
     }
+
+    fn memory_growth_refused(context: &mut WasmRuntimeContext<Self>) -> bool {
+        // The tunables can't observe `memory.grow`, but the growth can only have been refused
+        // if the memory reached its limit.
+        let Some(maximum_pages) = context.memory_limit else {
+            return false;
+        };
+        context
+            .extra
+            .instance
+            .exports
+            .get_memory("memory")
+            .map(|memory| u64::from(memory.view(&context.store).size().0) >= maximum_pages)
+            .unwrap_or(false)
+    }
 }
 
 /// Type representing the [Wasmer](https://wasmer.io/) service runtime.
@@ -138,6 +202,10 @@ impl ApplicationRuntimeContext for Service {
     fn persist_remaining_fuel(_context: &mut WasmRuntimeContext<Self>) -> Result<(), ()> {
         Ok(())
     }
+
+    fn memory_growth_refused(_context: &mut WasmRuntimeContext<Self>) -> bool {
+        false
+    }
 }
 
 impl WasmApplication {
@@ -155,22 +223,41 @@ impl WasmApplication {
 
         let mut service_cache = SERVICE_CACHE.lock().await;
         let service = service_cache
-            .get_or_insert_with(service_bytecode, |bytecode| {
-                Module::new(&*SERVICE_ENGINE, bytecode)
-                    .map_err(wit_bindgen_host_wasmer_rust::anyhow::Error::from)
-            })
+            .get_or_insert_with(service_bytecode, compile_service_module)
             .map_err(WasmExecutionError::LoadServiceModule)?;
 
-        Ok(WasmApplication::Wasmer { contract, service })
+        Ok(WasmApplication::Wasmer {
+            contract,
+            service,
+            profiler: None,
+        })
     }
 
     /// Prepares a runtime instance to call into the Wasm contract.
     pub fn prepare_contract_runtime_with_wasmer(
         (contract_engine, contract_module): &(Engine, Module),
         runtime: mpsc::UnboundedSender<ContractRequest>,
-    ) -> Result<WasmRuntimeContext<Contract>, WasmExecutionError> {
-        let mut store = Store::new(contract_engine);
+        runtime_limits: RuntimeLimits,
+        profiler: Option<&ModuleProfiler>,
+    ) -> Result<WasmRuntimeContext<Contract>, ExecutionError> {
+        let maximum_memory_pages = runtime_limits.maximum_wasm_memory_pages;
+        let initial_memory_pages = contract_module
+            .exports()
+            .filter_map(|export| match export.ty() {
+                ExternType::Memory(memory) => Some(u64::from(memory.minimum.0)),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+        if initial_memory_pages > maximum_memory_pages {
+            return Err(ExecutionError::ExcessiveMemory(maximum_memory_pages));
+        }
+
+        let tunables = ContractTunables::new(maximum_memory_pages);
+        let mut store = Store::new_with_tunables(contract_engine, tunables);
         let mut imports = imports! {};
+        let profiler_env = profiler
+            .map(|profiler| add_profiler_to_imports(&mut store, &mut imports, profiler.start()));
         let (future_queue, queued_future_factory) = HostFutureQueue::new();
         let contract_system_api =
             ContractSystemApi::new(runtime.clone(), queued_future_factory.clone());
@@ -186,11 +273,15 @@ impl WasmApplication {
 
         system_api_setup(&instance, &store).map_err(WasmExecutionError::LoadContractModule)?;
         views_api_setup(&instance, &store).map_err(WasmExecutionError::LoadContractModule)?;
+        if let Some(profiler_env) = profiler_env {
+            profiler_env.as_mut(&mut store).instance = Some(instance.clone());
+        }
 
         Ok(WasmRuntimeContext {
             application,
             future_queue: Some(future_queue),
             store,
+            memory_limit: Some(maximum_memory_pages),
             extra: WasmerContractExtra { runtime, instance },
         })
     }
@@ -220,6 +311,7 @@ impl WasmApplication {
             application,
             future_queue: None,
             store,
+            memory_limit: None,
             extra: (),
         })
     }
@@ -246,6 +338,8 @@ impl common::Contract for Contract {
     type Initialize = contract::Initialize;
     type ExecuteOperation = contract::ExecuteOperation;
     type ExecuteMessage = contract::ExecuteMessage;
+    type HandleBouncedMessage = contract::HandleBouncedMessage;
+    type HandleAcknowledgement = contract::HandleAcknowledgement;
     type HandleApplicationCall = contract::HandleApplicationCall;
     type HandleSessionCall = contract::HandleSessionCall;
     type PollExecutionResult = contract::PollExecutionResult;
@@ -303,6 +397,52 @@ impl common::Contract for Contract {
         contract::Contract::execute_message_poll(&self.contract, store, future)
     }
 
+    fn handle_bounced_message_new(
+        &self,
+        store: &mut Store,
+        context: MessageContext,
+        message: Vec<u8>,
+    ) -> Result<contract::HandleBouncedMessage, RuntimeError> {
+        contract::Contract::handle_bounced_message_new(
+            &self.contract,
+            store,
+            context.into(),
+            &message,
+        )
+    }
+
+    fn handle_bounced_message_poll(
+        &self,
+        store: &mut Store,
+        future: &contract::HandleBouncedMessage,
+    ) -> Result<contract::PollExecutionResult, RuntimeError> {
+        contract::Contract::handle_bounced_message_poll(&self.contract, store, future)
+    }
+
+    fn handle_acknowledgement_new(
+        &self,
+        store: &mut Store,
+        context: MessageContext,
+        message: Vec<u8>,
+        outcome: MessageOutcome,
+    ) -> Result<contract::HandleAcknowledgement, RuntimeError> {
+        contract::Contract::handle_acknowledgement_new(
+            &self.contract,
+            store,
+            context.into(),
+            &message,
+            outcome.into(),
+        )
+    }
+
+    fn handle_acknowledgement_poll(
+        &self,
+        store: &mut Store,
+        future: &contract::HandleAcknowledgement,
+    ) -> Result<contract::PollExecutionResult, RuntimeError> {
+        contract::Contract::handle_acknowledgement_poll(&self.contract, store, future)
+    }
+
     fn handle_application_call_new(
         &self,
         store: &mut Store,
@@ -499,6 +639,65 @@ pub struct WasmerContractExtra {
     instance: Instance,
 }
 
+/// The state of the functions called by a contract instrumented for profiling.
+struct ProfilerEnv {
+    profiler: InstanceProfiler,
+    instance: Option<Instance>,
+}
+
+impl ProfilerEnv {
+    /// Returns the fuel consumed by the instance of the profiler `env` so far.
+    ///
+    /// Wasmer only keeps track of the remaining fuel, but the profiler only needs the
+    /// differences between the readings.
+    fn fuel_consumed(env: &mut FunctionEnvMut<ProfilerEnv>) -> u64 {
+        let Some(instance) = env.data().instance.clone() else {
+            return 0;
+        };
+        match metering::get_remaining_points(&mut env.as_store_mut(), &instance) {
+            MeteringPoints::Exhausted => u64::MAX,
+            MeteringPoints::Remaining(fuel) => u64::MAX - fuel,
+        }
+    }
+}
+
+/// Adds the functions called by a contract instrumented for profiling to the `imports`, reporting
+/// the fuel consumed by the contract to the `profiler`.
+///
+/// The returned environment must be updated with the contract instance once it is created.
+fn add_profiler_to_imports(
+    store: &mut Store,
+    imports: &mut Imports,
+    profiler: InstanceProfiler,
+) -> FunctionEnv<ProfilerEnv> {
+    let env = FunctionEnv::new(
+        store,
+        ProfilerEnv {
+            profiler,
+            instance: None,
+        },
+    );
+    let enter = Function::new_typed_with_env(
+        store,
+        &env,
+        |mut env: FunctionEnvMut<ProfilerEnv>, function: u32| {
+            let fuel_consumed = ProfilerEnv::fuel_consumed(&mut env);
+            env.data_mut().profiler.enter(function, fuel_consumed);
+        },
+    );
+    let exit = Function::new_typed_with_env(
+        store,
+        &env,
+        |mut env: FunctionEnvMut<ProfilerEnv>, function: u32| {
+            let fuel_consumed = ProfilerEnv::fuel_consumed(&mut env);
+            env.data_mut().profiler.exit(function, fuel_consumed);
+        },
+    );
+    imports.define(PROFILER_MODULE, PROFILER_ENTER_FUNCTION, enter);
+    imports.define(PROFILER_MODULE, PROFILER_EXIT_FUNCTION, exit);
+    env
+}
+
 /// A guard to unsure that the [`ContractRuntime`] trait object isn't called after it's no longer
 /// borrowed.
 pub struct RuntimeGuard<'runtime, S> {
@@ -531,6 +730,130 @@ impl From<wasmer::RuntimeError> for ExecutionError {
     }
 }
 
+/// Compiles a service module from its `bytecode`, reusing a previously compiled module from the
+/// on-disk cache if it is enabled.
+fn compile_service_module(bytecode: Bytecode) -> Result<Module, anyhow::Error> {
+    let Some(disk_cache) = DiskModuleCache::global() else {
+        return Ok(Module::new(&*SERVICE_ENGINE, bytecode)?);
+    };
+    let key = DiskCacheKey::new(
+        "wasmer",
+        ENGINE_VERSION,
+        &SERVICE_COMPILER_CONFIGURATION,
+        ModuleKind::Service,
+        &bytecode,
+    );
+    if let Some(serialized_module) = disk_cache.load(&key) {
+        let store = Store::new(&*SERVICE_ENGINE);
+        // SAFETY: The entry was written by `Module::serialize` below and its checksum was
+        // verified, and Wasmer checks that it is compatible with the engine.
+        match unsafe { Module::deserialize(&store, serialized_module) } {
+            Ok(module) => return Ok(module),
+            Err(error) => {
+                tracing::warn!("Failed to load cached service module: {error}");
+                disk_cache.remove(&key);
+            }
+        }
+    }
+    let module = Module::new(&*SERVICE_ENGINE, bytecode)?;
+    disk_cache.store(&key, module.serialize()?.to_vec());
+    Ok(module)
+}
+
+/// [`Tunables`] preventing the memory and the tables of a contract from growing beyond their
+/// limits, by lowering the maximum size declared by the module if needed.
+struct ContractTunables {
+    base: BaseTunables,
+    maximum_memory_pages: Pages,
+}
+
+impl ContractTunables {
+    /// Creates [`ContractTunables`] allowing at most `maximum_memory_pages` of linear memory.
+    fn new(maximum_memory_pages: u64) -> Self {
+        let maximum_memory_pages = u32::try_from(maximum_memory_pages)
+            .map(Pages)
+            .unwrap_or_else(|_| Pages::max_value())
+            .min(Pages::max_value());
+        ContractTunables {
+            base: BaseTunables::for_target(&Target::default()),
+            maximum_memory_pages,
+        }
+    }
+
+    /// Returns the memory type `memory` with its maximum lowered to the limit.
+    fn limit_memory(&self, memory: &MemoryType) -> Result<MemoryType, MemoryError> {
+        if memory.minimum > self.maximum_memory_pages {
+            return Err(MemoryError::Generic(
+                "Initial memory exceeds the allowed limit".to_owned(),
+            ));
+        }
+        let mut memory = *memory;
+        memory.maximum = Some(memory.maximum.map_or(self.maximum_memory_pages, |maximum| {
+            maximum.min(self.maximum_memory_pages)
+        }));
+        Ok(memory)
+    }
+
+    /// Returns the table type `table` with its maximum lowered to the limit.
+    fn limit_table(&self, table: &TableType) -> Result<TableType, String> {
+        let maximum_elements = common::MAXIMUM_TABLE_ELEMENTS;
+        if table.minimum > maximum_elements {
+            return Err("Initial table size exceeds the allowed limit".to_owned());
+        }
+        let mut table = *table;
+        table.maximum = Some(
+            table
+                .maximum
+                .map_or(maximum_elements, |maximum| maximum.min(maximum_elements)),
+        );
+        Ok(table)
+    }
+}
+
+impl Tunables for ContractTunables {
+    fn memory_style(&self, memory: &MemoryType) -> MemoryStyle {
+        self.base.memory_style(memory)
+    }
+
+    fn table_style(&self, table: &TableType) -> TableStyle {
+        self.base.table_style(table)
+    }
+
+    fn create_host_memory(
+        &self,
+        memory: &MemoryType,
+        style: &MemoryStyle,
+    ) -> Result<VMMemory, MemoryError> {
+        self.base
+            .create_host_memory(&self.limit_memory(memory)?, style)
+    }
+
+    unsafe fn create_vm_memory(
+        &self,
+        memory: &MemoryType,
+        style: &MemoryStyle,
+        vm_definition_location: NonNull<VMMemoryDefinition>,
+    ) -> Result<VMMemory, MemoryError> {
+        self.base
+            .create_vm_memory(&self.limit_memory(memory)?, style, vm_definition_location)
+    }
+
+    fn create_host_table(&self, table: &TableType, style: &TableStyle) -> Result<VMTable, String> {
+        self.base
+            .create_host_table(&self.limit_table(table)?, style)
+    }
+
+    unsafe fn create_vm_table(
+        &self,
+        table: &TableType,
+        style: &TableStyle,
+        vm_definition_location: NonNull<VMTableDefinition>,
+    ) -> Result<VMTable, String> {
+        self.base
+            .create_vm_table(&self.limit_table(table)?, style, vm_definition_location)
+    }
+}
+
 /// Serialized bytes of a compiled contract bytecode.
 ///
 /// Each [`Module`] needs to be compiled with a separate [`Engine`] instance, otherwise Wasmer
@@ -541,8 +864,39 @@ pub struct CachedContractModule {
 }
 
 impl CachedContractModule {
-    /// Creates a new [`CachedContractModule`] by compiling a `contract_bytecode`.
+    /// Creates a new [`CachedContractModule`] by compiling a `contract_bytecode`, or by loading
+    /// it from the on-disk cache if it is enabled.
     pub fn new(contract_bytecode: Bytecode) -> Result<Self, anyhow::Error> {
+        let Some(disk_cache) = DiskModuleCache::global() else {
+            return Self::compile(contract_bytecode);
+        };
+        let key = DiskCacheKey::new(
+            "wasmer",
+            ENGINE_VERSION,
+            &CONTRACT_COMPILER_CONFIGURATION,
+            ModuleKind::Contract,
+            &contract_bytecode,
+        );
+        if let Some(serialized_module) = disk_cache.load(&key) {
+            let cached_module = CachedContractModule {
+                compiled_bytecode: serialized_module.into(),
+            };
+            // Make sure that the cached module can still be loaded by this version of Wasmer.
+            match cached_module.create_execution_instance() {
+                Ok(_) => return Ok(cached_module),
+                Err(error) => {
+                    tracing::warn!("Failed to load cached contract module: {error}");
+                    disk_cache.remove(&key);
+                }
+            }
+        }
+        let cached_module = Self::compile(contract_bytecode)?;
+        disk_cache.store(&key, cached_module.compiled_bytecode.to_vec());
+        Ok(cached_module)
+    }
+
+    /// Compiles a `contract_bytecode` into a new [`CachedContractModule`].
+    fn compile(contract_bytecode: Bytecode) -> Result<Self, anyhow::Error> {
         let module = Module::new(&Self::create_compilation_engine(), contract_bytecode)?;
         let compiled_bytecode = module.serialize()?;
         Ok(CachedContractModule { compiled_bytecode })
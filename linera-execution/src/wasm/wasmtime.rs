@@ -41,20 +41,32 @@ use self::{
 use super::{
     async_determinism::{HostFutureQueue, QueuedHostFutureFactory},
     common::{self, ApplicationRuntimeContext, WasmRuntimeContext},
+    disk_module_cache::{CompilerConfiguration, DiskCacheKey, DiskModuleCache, ModuleKind},
     module_cache::ModuleCache,
     runtime_actor::{BaseRequest, ContractRequest, SendRequestExt, ServiceRequest},
     WasmApplication, WasmExecutionError,
 };
 use crate::{
-    Bytecode, CalleeContext, ExecutionError, MessageContext, OperationContext, QueryContext,
-    SessionId,
+    profiler::{
+        InstanceProfiler, ModuleProfiler, PROFILER_ENTER_FUNCTION, PROFILER_EXIT_FUNCTION,
+        PROFILER_MODULE,
+    },
+    Bytecode, CalleeContext, ExecutionError, MessageContext, MessageOutcome, OperationContext,
+    QueryContext, RemoteQueryResponse, RuntimeLimits, SessionId, SystemResponse,
 };
 use futures::{channel::mpsc, TryFutureExt};
 use linera_views::{batch::Batch, views::ViewError};
 use once_cell::sync::Lazy;
-use std::error::Error;
+use std::{
+    collections::hash_map::DefaultHasher,
+    error::Error,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
 use tokio::sync::Mutex;
-use wasmtime::{Config, Engine, Linker, Module, Store, Trap};
+use wasmtime::{
+    Caller, Config, Engine, Instance, Linker, MemoryType, Module, ResourceLimiter, Store, Trap,
+};
 use wit_bindgen_host_wasmtime_rust::Le;
 
 /// An [`Engine`] instance configured to run application contracts.
@@ -70,6 +82,28 @@ static CONTRACT_ENGINE: Lazy<Engine> = Lazy::new(|| {
 /// An [`Engine`] instance configured to run application services.
 static SERVICE_ENGINE: Lazy<Engine> = Lazy::new(Engine::default);
 
+/// The version of [`CONTRACT_ENGINE`], used to key the on-disk module cache.
+static CONTRACT_ENGINE_VERSION: Lazy<String> = Lazy::new(|| engine_version(&CONTRACT_ENGINE));
+
+/// The version of [`SERVICE_ENGINE`], used to key the on-disk module cache.
+static SERVICE_ENGINE_VERSION: Lazy<String> = Lazy::new(|| engine_version(&SERVICE_ENGINE));
+
+/// The configuration of [`CONTRACT_ENGINE`], used to key the on-disk module cache.
+static CONTRACT_COMPILER_CONFIGURATION: Lazy<CompilerConfiguration> =
+    Lazy::new(|| CompilerConfiguration {
+        compiler: "cranelift".to_owned(),
+        canonicalize_nans: true,
+        fuel_metering: true,
+        operator_costs: Vec::new(),
+    });
+
+/// The configuration of [`SERVICE_ENGINE`], used to key the on-disk module cache.
+static SERVICE_COMPILER_CONFIGURATION: Lazy<CompilerConfiguration> =
+    Lazy::new(|| CompilerConfiguration {
+        compiler: "cranelift".to_owned(),
+        ..CompilerConfiguration::default()
+    });
+
 /// A cache of compiled contract modules.
 static CONTRACT_CACHE: Lazy<Mutex<ModuleCache<Module>>> = Lazy::new(Mutex::default);
 
@@ -87,7 +121,7 @@ pub struct Contract {
 impl ApplicationRuntimeContext for Contract {
     type Store = Store<ContractState>;
     type Error = Trap;
-    type Extra = ();
+    type Extra = Instance;
 
     fn configure_initial_fuel(context: &mut WasmRuntimeContext<Self>) {
         let runtime = &context.store.data().system_api.runtime;
@@ -131,6 +165,10 @@ impl ApplicationRuntimeContext for Contract {
             .recv()
             .map_err(|_| ())
     }
+
+    fn memory_growth_refused(context: &mut WasmRuntimeContext<Self>) -> bool {
+        context.store.data().limiter.memory_growth_refused
+    }
 }
 
 /// Type representing the [Wasmtime](https://wasmtime.dev/) runtime for services.
@@ -148,6 +186,10 @@ impl ApplicationRuntimeContext for Service {
     fn persist_remaining_fuel(_context: &mut WasmRuntimeContext<Self>) -> Result<(), ()> {
         Ok(())
     }
+
+    fn memory_growth_refused(_context: &mut WasmRuntimeContext<Self>) -> bool {
+        false
+    }
 }
 
 impl WasmApplication {
@@ -159,36 +201,70 @@ impl WasmApplication {
         let mut contract_cache = CONTRACT_CACHE.lock().await;
         let contract = contract_cache
             .get_or_insert_with(contract_bytecode, |bytecode| {
-                Module::new(&CONTRACT_ENGINE, bytecode)
+                compile_module(
+                    &CONTRACT_ENGINE,
+                    &CONTRACT_ENGINE_VERSION,
+                    &CONTRACT_COMPILER_CONFIGURATION,
+                    ModuleKind::Contract,
+                    bytecode,
+                )
             })
             .map_err(WasmExecutionError::LoadContractModule)?;
 
         let mut service_cache = SERVICE_CACHE.lock().await;
         let service = service_cache
             .get_or_insert_with(service_bytecode, |bytecode| {
-                Module::new(&SERVICE_ENGINE, bytecode)
+                compile_module(
+                    &SERVICE_ENGINE,
+                    &SERVICE_ENGINE_VERSION,
+                    &SERVICE_COMPILER_CONFIGURATION,
+                    ModuleKind::Service,
+                    bytecode,
+                )
             })
             .map_err(WasmExecutionError::LoadServiceModule)?;
 
-        Ok(WasmApplication::Wasmtime { contract, service })
+        Ok(WasmApplication::Wasmtime {
+            contract,
+            service,
+            profiler: None,
+        })
     }
 
     /// Prepares a runtime instance to call into the Wasm contract.
     pub fn prepare_contract_runtime_with_wasmtime(
         contract_module: &Module,
         runtime: mpsc::UnboundedSender<ContractRequest>,
-    ) -> Result<WasmRuntimeContext<Contract>, WasmExecutionError> {
+        runtime_limits: RuntimeLimits,
+        profiler: Option<&ModuleProfiler>,
+    ) -> Result<WasmRuntimeContext<Contract>, ExecutionError> {
+        let maximum_memory_pages = runtime_limits.maximum_wasm_memory_pages;
+        let initial_memory_pages = contract_module
+            .exports()
+            .filter_map(|export| export.ty().memory().map(MemoryType::minimum))
+            .max()
+            .unwrap_or(0);
+        if initial_memory_pages > maximum_memory_pages {
+            return Err(ExecutionError::ExcessiveMemory(maximum_memory_pages));
+        }
+
         let mut linker = Linker::new(&CONTRACT_ENGINE);
 
         contract_system_api::add_to_linker(&mut linker, ContractState::system_api)
             .map_err(WasmExecutionError::LoadContractModule)?;
         view_system_api::add_to_linker(&mut linker, ContractState::views_api)
             .map_err(WasmExecutionError::LoadContractModule)?;
+        if let Some(profiler) = profiler {
+            add_profiler_to_linker(&mut linker, profiler.start())
+                .map_err(WasmExecutionError::LoadContractModule)?;
+        }
 
         let (future_queue, queued_future_factory) = HostFutureQueue::new();
-        let state = ContractState::new(runtime, queued_future_factory);
+        let limiter = ContractLimiter::new(maximum_memory_pages);
+        let state = ContractState::new(runtime, queued_future_factory, limiter);
         let mut store = Store::new(&CONTRACT_ENGINE, state);
-        let (contract, _instance) = contract::Contract::instantiate(
+        store.limiter(|state| &mut state.limiter);
+        let (contract, instance) = contract::Contract::instantiate(
             &mut store,
             contract_module,
             &mut linker,
@@ -201,7 +277,8 @@ impl WasmApplication {
             application,
             future_queue: Some(future_queue),
             store,
-            extra: (),
+            memory_limit: Some(maximum_memory_pages),
+            extra: instance,
         })
     }
 
@@ -232,17 +309,129 @@ impl WasmApplication {
             application,
             future_queue: None,
             store,
+            memory_limit: None,
             extra: (),
         })
     }
 }
 
+/// Adds the functions called by a contract instrumented for profiling to the `linker`, reporting
+/// the fuel consumed by the contract to the `profiler`.
+fn add_profiler_to_linker(
+    linker: &mut Linker<ContractState>,
+    profiler: InstanceProfiler,
+) -> Result<(), anyhow::Error> {
+    let profiler = Arc::new(std::sync::Mutex::new(profiler));
+    let enter_profiler = profiler.clone();
+    linker.func_wrap(
+        PROFILER_MODULE,
+        PROFILER_ENTER_FUNCTION,
+        move |caller: Caller<'_, ContractState>, function: u32| {
+            let fuel_consumed = caller.fuel_consumed().unwrap_or(0);
+            enter_profiler
+                .lock()
+                .expect("profiler should not panic while holding its lock")
+                .enter(function, fuel_consumed);
+        },
+    )?;
+    linker.func_wrap(
+        PROFILER_MODULE,
+        PROFILER_EXIT_FUNCTION,
+        move |caller: Caller<'_, ContractState>, function: u32| {
+            let fuel_consumed = caller.fuel_consumed().unwrap_or(0);
+            profiler
+                .lock()
+                .expect("profiler should not panic while holding its lock")
+                .exit(function, fuel_consumed);
+        },
+    )?;
+    Ok(())
+}
+
+/// Compiles a module from its `bytecode`, reusing a previously compiled module from the on-disk
+/// cache if it is enabled.
+fn compile_module(
+    engine: &Engine,
+    engine_version: &str,
+    configuration: &CompilerConfiguration,
+    kind: ModuleKind,
+    bytecode: Bytecode,
+) -> Result<Module, anyhow::Error> {
+    let Some(disk_cache) = DiskModuleCache::global() else {
+        return Module::new(engine, bytecode);
+    };
+    let key = DiskCacheKey::new("wasmtime", engine_version, configuration, kind, &bytecode);
+    if let Some(serialized_module) = disk_cache.load(&key) {
+        // SAFETY: The entry was written by `Module::serialize` below and its checksum was
+        // verified, and Wasmtime checks that it is compatible with the `engine`.
+        match unsafe { Module::deserialize(engine, serialized_module) } {
+            Ok(module) => return Ok(module),
+            Err(error) => {
+                tracing::warn!("Failed to load cached {kind:?} module: {error}");
+                disk_cache.remove(&key);
+            }
+        }
+    }
+    let module = Module::new(engine, bytecode)?;
+    disk_cache.store(&key, module.serialize()?);
+    Ok(module)
+}
+
+/// Returns the version of the `engine` used to key the on-disk module cache.
+///
+/// Serialized modules start with a header recording the Wasmtime version and the engine's
+/// configuration, so the serialization of an empty module identifies which compiled modules
+/// the `engine` is able to load.
+fn engine_version(engine: &Engine) -> String {
+    const EMPTY_MODULE: &[u8] = b"\0asm\x01\0\0\0";
+    let serialized_module = Module::new(engine, EMPTY_MODULE)
+        .and_then(|module| module.serialize())
+        .expect("Failed to serialize an empty module");
+    let mut hasher = DefaultHasher::new();
+    serialized_module.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
 /// Data stored by the runtime that's necessary for handling calls to and from the Wasm module.
 pub struct ContractState {
     data: ContractData,
     system_api: ContractSystemApi,
     system_tables: ContractSystemApiTables<ContractSystemApi>,
     views_tables: ViewSystemApiTables<ContractSystemApi>,
+    limiter: ContractLimiter,
+}
+
+/// A [`ResourceLimiter`] preventing the memory and the tables of a contract from growing
+/// beyond their limits.
+pub struct ContractLimiter {
+    maximum_memory_bytes: usize,
+    memory_growth_refused: bool,
+}
+
+impl ContractLimiter {
+    /// Creates a [`ContractLimiter`] allowing at most `maximum_memory_pages` of linear memory.
+    pub fn new(maximum_memory_pages: u64) -> Self {
+        let maximum_memory_bytes = maximum_memory_pages
+            .checked_mul(common::WASM_PAGE_SIZE)
+            .and_then(|bytes| usize::try_from(bytes).ok())
+            .unwrap_or(usize::MAX);
+        ContractLimiter {
+            maximum_memory_bytes,
+            memory_growth_refused: false,
+        }
+    }
+}
+
+impl ResourceLimiter for ContractLimiter {
+    fn memory_growing(&mut self, _current: usize, desired: usize, _maximum: Option<usize>) -> bool {
+        let allowed = desired <= self.maximum_memory_bytes;
+        self.memory_growth_refused |= !allowed;
+        allowed
+    }
+
+    fn table_growing(&mut self, _current: u32, desired: u32, _maximum: Option<u32>) -> bool {
+        desired <= common::MAXIMUM_TABLE_ELEMENTS
+    }
 }
 
 /// Data stored by the runtime that's necessary for handling queries to and from the Wasm module.
@@ -260,12 +449,14 @@ impl ContractState {
     pub fn new(
         runtime: mpsc::UnboundedSender<ContractRequest>,
         queued_future_factory: QueuedHostFutureFactory,
+        limiter: ContractLimiter,
     ) -> Self {
         Self {
             data: ContractData::default(),
             system_api: ContractSystemApi::new(runtime, queued_future_factory),
             system_tables: ContractSystemApiTables::default(),
             views_tables: ViewSystemApiTables::default(),
+            limiter,
         }
     }
 
@@ -338,6 +529,8 @@ impl common::Contract for Contract {
     type Initialize = contract::Initialize;
     type ExecuteOperation = contract::ExecuteOperation;
     type ExecuteMessage = contract::ExecuteMessage;
+    type HandleBouncedMessage = contract::HandleBouncedMessage;
+    type HandleAcknowledgement = contract::HandleAcknowledgement;
     type HandleApplicationCall = contract::HandleApplicationCall;
     type HandleSessionCall = contract::HandleSessionCall;
     type PollExecutionResult = contract::PollExecutionResult;
@@ -395,6 +588,52 @@ impl common::Contract for Contract {
         contract::Contract::execute_message_poll(&self.contract, store, future)
     }
 
+    fn handle_bounced_message_new(
+        &self,
+        store: &mut Store<ContractState>,
+        context: MessageContext,
+        message: Vec<u8>,
+    ) -> Result<contract::HandleBouncedMessage, Trap> {
+        contract::Contract::handle_bounced_message_new(
+            &self.contract,
+            store,
+            context.into(),
+            &message,
+        )
+    }
+
+    fn handle_bounced_message_poll(
+        &self,
+        store: &mut Store<ContractState>,
+        future: &contract::HandleBouncedMessage,
+    ) -> Result<contract::PollExecutionResult, Trap> {
+        contract::Contract::handle_bounced_message_poll(&self.contract, store, future)
+    }
+
+    fn handle_acknowledgement_new(
+        &self,
+        store: &mut Store<ContractState>,
+        context: MessageContext,
+        message: Vec<u8>,
+        outcome: MessageOutcome,
+    ) -> Result<contract::HandleAcknowledgement, Trap> {
+        contract::Contract::handle_acknowledgement_new(
+            &self.contract,
+            store,
+            context.into(),
+            &message,
+            outcome.into(),
+        )
+    }
+
+    fn handle_acknowledgement_poll(
+        &self,
+        store: &mut Store<ContractState>,
+        future: &contract::HandleAcknowledgement,
+    ) -> Result<contract::PollExecutionResult, Trap> {
+        contract::Contract::handle_acknowledgement_poll(&self.contract, store, future)
+    }
+
     fn handle_application_call_new(
         &self,
         store: &mut Store<ContractState>,
@@ -520,7 +759,7 @@ impl_view_system_api_for_service!(ServiceSystemApi, wasmtime::Trap);
 impl From<ExecutionError> for wasmtime::Trap {
     fn from(error: ExecutionError) -> Self {
         match error {
-            ExecutionError::UserError(message) => wasmtime::Trap::new(message),
+            ExecutionError::UserError { message, .. } => wasmtime::Trap::new(message),
             _ => {
                 let boxed_error: Box<dyn Error + Send + Sync + 'static> = Box::new(error);
                 wasmtime::Trap::from(boxed_error)
@@ -532,7 +771,10 @@ impl From<ExecutionError> for wasmtime::Trap {
 impl From<wasmtime::Trap> for ExecutionError {
     fn from(trap: wasmtime::Trap) -> Self {
         if trap.trap_code().is_none() {
-            ExecutionError::UserError(trap.display_reason().to_string())
+            ExecutionError::UserError {
+                code: 0,
+                message: trap.display_reason().to_string(),
+            }
         } else {
             ExecutionError::WasmError(WasmExecutionError::ExecuteModuleInWasmtime(trap))
         }
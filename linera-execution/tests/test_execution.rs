@@ -6,14 +6,18 @@
 mod utils;
 
 use self::utils::create_dummy_user_application_description;
-use async_trait::async_trait;
 use linera_base::{
-    crypto::PublicKey,
+    crypto::{CryptoHash, PublicKey},
     data_types::BlockHeight,
-    identifiers::{ChainDescription, ChainId, Owner, SessionId},
+    identifiers::{ChainDescription, ChainId, MessageId, Owner, SessionId},
 };
-use linera_execution::{policy::ResourceControlPolicy, *};
-use linera_views::{batch::Batch, common::Context, memory::MemoryContext, views::View};
+use linera_execution::{
+    policy::ResourceControlPolicy,
+    test_utils::TestApplication,
+    trace::{ExecutionTrace, TraceEvent},
+    *,
+};
+use linera_views::{common::Context, memory::MemoryContext, views::View};
 use std::sync::Arc;
 
 #[tokio::test]
@@ -41,6 +45,7 @@ async fn test_missing_bytecode_for_user_application() -> anyhow::Result<()> {
         index: 0,
         authenticated_signer: None,
         next_message_index: 0,
+        random_seed: Some(CryptoHash::from([0; 4])),
     };
     let mut tracker = ResourceTracker::default();
     let policy = ResourceControlPolicy::default();
@@ -63,130 +68,6 @@ async fn test_missing_bytecode_for_user_application() -> anyhow::Result<()> {
     Ok(())
 }
 
-struct TestApplication {
-    owner: Owner,
-}
-
-#[async_trait]
-impl UserApplication for TestApplication {
-    /// Nothing needs to be done during initialization.
-    async fn initialize(
-        &self,
-        context: &OperationContext,
-        _runtime: &dyn ContractRuntime,
-        _argument: &[u8],
-    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError> {
-        assert_eq!(context.authenticated_signer, Some(self.owner));
-        Ok(RawExecutionResult::default())
-    }
-
-    /// Extend the application state with the `operation` bytes.
-    ///
-    /// Calls itself during the operation, opening a session. The session is intentionally
-    /// leaked if the operation is empty.
-    async fn execute_operation(
-        &self,
-        context: &OperationContext,
-        runtime: &dyn ContractRuntime,
-        operation: &[u8],
-    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError> {
-        // Who we are.
-        assert_eq!(context.authenticated_signer, Some(self.owner));
-        let app_id = runtime.application_id();
-        // Modify our state.
-        let chosen_key = vec![0];
-        runtime.lock_view_user_state().await?;
-        let state = runtime.read_key_bytes(chosen_key.clone()).await?;
-        let mut state = state.unwrap_or_default();
-        state.extend(operation);
-        let mut batch = Batch::new();
-        batch.put_key_value_bytes(chosen_key, state);
-        runtime
-            .write_batch_and_unlock(batch)
-            .await
-            .expect("State is locked at the start of the operation");
-        // Call ourselves after the state => ok.
-        let call_result = runtime
-            .try_call_application(/* authenticate */ true, app_id, &[], vec![])
-            .await?;
-        assert_eq!(call_result.value, Vec::<u8>::new());
-        assert_eq!(call_result.sessions.len(), 1);
-        if !operation.is_empty() {
-            // Call the session to close it.
-            let session_id = call_result.sessions[0];
-            runtime
-                .try_call_session(/* authenticate */ false, session_id, &[], vec![])
-                .await?;
-        }
-        Ok(RawExecutionResult::default())
-    }
-
-    /// Attempts to call ourself while the state is locked.
-    async fn execute_message(
-        &self,
-        context: &MessageContext,
-        runtime: &dyn ContractRuntime,
-        _message: &[u8],
-    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError> {
-        // Who we are.
-        assert_eq!(context.authenticated_signer, Some(self.owner));
-        let app_id = runtime.application_id();
-        runtime.lock_view_user_state().await?;
-        // Call ourselves while the state is locked => not ok.
-        runtime
-            .try_call_application(/* authenticate */ true, app_id, &[], vec![])
-            .await?;
-        runtime.unlock_view_user_state().await?;
-        Ok(RawExecutionResult::default())
-    }
-
-    /// Creates a session.
-    async fn handle_application_call(
-        &self,
-        context: &CalleeContext,
-        _runtime: &dyn ContractRuntime,
-        _argument: &[u8],
-        _forwarded_sessions: Vec<SessionId>,
-    ) -> Result<ApplicationCallResult, ExecutionError> {
-        assert_eq!(context.authenticated_signer, Some(self.owner));
-        Ok(ApplicationCallResult {
-            create_sessions: vec![vec![1]],
-            ..ApplicationCallResult::default()
-        })
-    }
-
-    /// Closes the session.
-    async fn handle_session_call(
-        &self,
-        context: &CalleeContext,
-        _runtime: &dyn ContractRuntime,
-        _session_state: &mut Vec<u8>,
-        _argument: &[u8],
-        _forwarded_sessions: Vec<SessionId>,
-    ) -> Result<SessionCallResult, ExecutionError> {
-        assert_eq!(context.authenticated_signer, None);
-        Ok(SessionCallResult {
-            inner: ApplicationCallResult::default(),
-            close_session: true,
-        })
-    }
-
-    /// Returns the application state.
-    async fn handle_query(
-        &self,
-        _context: &QueryContext,
-        runtime: &dyn ServiceRuntime,
-        _argument: &[u8],
-    ) -> Result<Vec<u8>, ExecutionError> {
-        let chosen_key = vec![0];
-        runtime.lock_view_user_state().await?;
-        let state = runtime.read_key_bytes(chosen_key).await?;
-        let state = state.unwrap_or_default();
-        runtime.unlock_view_user_state().await?;
-        Ok(state)
-    }
-}
-
 #[tokio::test]
 async fn test_simple_user_operation() -> anyhow::Result<()> {
     let owner = Owner::from(PublicKey::debug(0));
@@ -212,6 +93,7 @@ async fn test_simple_user_operation() -> anyhow::Result<()> {
         index: 0,
         authenticated_signer: Some(owner),
         next_message_index: 0,
+        random_seed: Some(CryptoHash::from([0; 4])),
     };
     let mut tracker = ResourceTracker::default();
     let policy = ResourceControlPolicy::default();
@@ -227,9 +109,14 @@ async fn test_simple_user_operation() -> anyhow::Result<()> {
         )
         .await
         .unwrap();
+    let event_result = RawExecutionResult {
+        events: vec![(b"operation".to_vec(), vec![1])],
+        ..RawExecutionResult::default()
+    };
     assert_eq!(
         result,
         vec![
+            ExecutionResult::User(app_id, event_result),
             ExecutionResult::User(
                 app_id,
                 RawExecutionResult::default().with_authenticated_signer(Some(owner))
@@ -241,6 +128,8 @@ async fn test_simple_user_operation() -> anyhow::Result<()> {
             )
         ]
     );
+    // The storage of applications without a quota isn't tracked.
+    assert_eq!(view.view_users_stored_bytes.get(&app_id).await?, None);
 
     let context = QueryContext {
         chain_id: ChainId::root(0),
@@ -260,6 +149,74 @@ async fn test_simple_user_operation() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_simple_user_operation_with_trace() -> anyhow::Result<()> {
+    let owner = Owner::from(PublicKey::debug(0));
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view =
+        ExecutionStateView::<MemoryContext<TestExecutionRuntimeContext>>::from_system_state(state)
+            .await;
+    let app_desc = create_dummy_user_application_description();
+    let app_id = view
+        .system
+        .registry
+        .register_application(app_desc.clone())
+        .await?;
+    view.context()
+        .extra()
+        .user_applications()
+        .insert(app_id, Arc::new(TestApplication { owner }));
+
+    let context = OperationContext {
+        chain_id: ChainId::root(0),
+        height: BlockHeight(0),
+        index: 0,
+        authenticated_signer: Some(owner),
+        next_message_index: 0,
+        random_seed: Some(CryptoHash::from([0; 4])),
+    };
+    let mut tracker = ResourceTracker::default();
+    let policy = ResourceControlPolicy::default();
+    let mut trace = ExecutionTrace::default();
+    view.execute_operation_with_trace(
+        &context,
+        &Operation::User {
+            application_id: app_id,
+            bytes: vec![1],
+        },
+        false,
+        &policy,
+        &mut tracker,
+        Some(&mut trace),
+    )
+    .await?;
+
+    let session_id = SessionId {
+        application_id: app_id,
+        index: 0,
+    };
+    assert!(matches!(
+        trace.events.as_slice(),
+        [
+            TraceEvent::UserAction { application_id, .. },
+            TraceEvent::ReadKey { key, value: None, .. },
+            TraceEvent::WriteBatch { .. },
+            TraceEvent::ApplicationCall { callee_id, authenticated: true, .. },
+            TraceEvent::SessionCall { session_id: called_session, authenticated: false, .. },
+        ] if *application_id == app_id
+            && *key == vec![0]
+            && *callee_id == app_id
+            && *called_session == session_id
+    ));
+    // Traces can be saved and loaded again, e.g. to replay blocks.
+    let bytes = bcs::to_bytes(&trace)?;
+    assert_eq!(bcs::from_bytes::<ExecutionTrace>(&bytes)?, trace);
+    let json = serde_json::to_string(&trace)?;
+    assert_eq!(serde_json::from_str::<ExecutionTrace>(&json)?, trace);
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_simple_user_operation_with_leaking_session() -> anyhow::Result<()> {
     let owner = Owner::from(PublicKey::debug(0));
@@ -285,6 +242,7 @@ async fn test_simple_user_operation_with_leaking_session() -> anyhow::Result<()>
         index: 0,
         authenticated_signer: Some(owner),
         next_message_index: 0,
+        random_seed: Some(CryptoHash::from([0; 4])),
     };
     let mut tracker = ResourceTracker::default();
     let policy = ResourceControlPolicy::default();
@@ -303,3 +261,326 @@ async fn test_simple_user_operation_with_leaking_session() -> anyhow::Result<()>
     assert!(matches!(result, Err(ExecutionError::SessionWasNotClosed)));
     Ok(())
 }
+
+#[tokio::test]
+async fn test_simple_user_operation_exceeding_limits() -> anyhow::Result<()> {
+    let owner = Owner::from(PublicKey::debug(0));
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view =
+        ExecutionStateView::<MemoryContext<TestExecutionRuntimeContext>>::from_system_state(state)
+            .await;
+    let app_desc = create_dummy_user_application_description();
+    let app_id = view
+        .system
+        .registry
+        .register_application(app_desc.clone())
+        .await?;
+    view.context()
+        .extra()
+        .user_applications()
+        .insert(app_id, Arc::new(TestApplication { owner }));
+
+    let context = OperationContext {
+        chain_id: ChainId::root(0),
+        height: BlockHeight(0),
+        index: 0,
+        authenticated_signer: Some(owner),
+        next_message_index: 0,
+        random_seed: Some(CryptoHash::from([0; 4])),
+    };
+    let operation = Operation::User {
+        application_id: app_id,
+        bytes: vec![1],
+    };
+
+    let policy = ResourceControlPolicy {
+        maximum_call_depth: 0,
+        ..ResourceControlPolicy::default()
+    };
+    let result = view
+        .execute_operation(
+            &context,
+            &operation,
+            &policy,
+            &mut ResourceTracker::default(),
+        )
+        .await;
+    assert!(matches!(result, Err(ExecutionError::ExcessiveCallDepth(0))));
+
+    let policy = ResourceControlPolicy {
+        maximum_live_sessions: 0,
+        ..ResourceControlPolicy::default()
+    };
+    let result = view
+        .execute_operation(
+            &context,
+            &operation,
+            &policy,
+            &mut ResourceTracker::default(),
+        )
+        .await;
+    assert!(matches!(result, Err(ExecutionError::ExcessiveSessions(0))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_simple_user_operation_exceeding_storage_quota() -> anyhow::Result<()> {
+    let owner = Owner::from(PublicKey::debug(0));
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view =
+        ExecutionStateView::<MemoryContext<TestExecutionRuntimeContext>>::from_system_state(state)
+            .await;
+    let app_desc = UserApplicationDescription {
+        storage_quota: Some(2),
+        ..create_dummy_user_application_description()
+    };
+    let app_id = view
+        .system
+        .registry
+        .register_application(app_desc.clone())
+        .await?;
+    view.context()
+        .extra()
+        .user_applications()
+        .insert(app_id, Arc::new(TestApplication { owner }));
+
+    let context = OperationContext {
+        chain_id: ChainId::root(0),
+        height: BlockHeight(0),
+        index: 0,
+        authenticated_signer: Some(owner),
+        next_message_index: 0,
+        random_seed: Some(CryptoHash::from([0; 4])),
+    };
+    let policy = ResourceControlPolicy::default();
+
+    // The key and the one-byte value fit in the quota.
+    view.execute_operation(
+        &context,
+        &Operation::User {
+            application_id: app_id,
+            bytes: vec![1],
+        },
+        &policy,
+        &mut ResourceTracker::default(),
+    )
+    .await?;
+    assert_eq!(view.view_users_stored_bytes.get(&app_id).await?, Some(2));
+
+    // Extending the value exceeds the quota.
+    let result = view
+        .execute_operation(
+            &context,
+            &Operation::User {
+                application_id: app_id,
+                bytes: vec![2],
+            },
+            &policy,
+            &mut ResourceTracker::default(),
+        )
+        .await;
+    assert!(matches!(result, Err(ExecutionError::ExcessiveStorage(2))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_simple_user_state_exceeding_storage_quota() -> anyhow::Result<()> {
+    let owner = Owner::from(PublicKey::debug(0));
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view =
+        ExecutionStateView::<MemoryContext<TestExecutionRuntimeContext>>::from_system_state(state)
+            .await;
+    let app_desc = create_dummy_user_application_description();
+    view.system
+        .registry
+        .published_bytecodes
+        .insert(&app_desc.bytecode_id, app_desc.bytecode_location)?;
+
+    let context = OperationContext {
+        chain_id: ChainId::root(0),
+        height: BlockHeight(0),
+        index: 0,
+        authenticated_signer: Some(owner),
+        next_message_index: 0,
+        random_seed: Some(CryptoHash::from([0; 4])),
+    };
+    let app_id = UserApplicationId {
+        bytecode_id: app_desc.bytecode_id,
+        creation: MessageId {
+            chain_id: context.chain_id,
+            height: context.height,
+            index: context.next_message_index,
+        },
+    };
+    view.context()
+        .extra()
+        .user_applications()
+        .insert(app_id, Arc::new(TestApplication { owner }));
+    let policy = ResourceControlPolicy::default();
+    let create_application = |initialization_argument| {
+        Operation::System(SystemOperation::CreateApplication {
+            bytecode_id: app_desc.bytecode_id,
+            parameters: vec![],
+            initialization_argument,
+            required_application_ids: vec![],
+            storage_quota: Some(2),
+        })
+    };
+
+    // The initial state doesn't fit in the quota.
+    let result = view
+        .execute_operation(
+            &context,
+            &create_application(vec![1, 2, 3]),
+            &policy,
+            &mut ResourceTracker::default(),
+        )
+        .await;
+    assert!(matches!(result, Err(ExecutionError::ExcessiveStorage(2))));
+
+    // The initial state fits in the quota.
+    view.execute_operation(
+        &context,
+        &create_application(vec![1, 2]),
+        &policy,
+        &mut ResourceTracker::default(),
+    )
+    .await?;
+    let state = view.simple_users.try_load_entry(&app_id).await?;
+    assert_eq!(state.get(), &vec![1, 2]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_emit_event() -> anyhow::Result<()> {
+    let owner = Owner::from(PublicKey::debug(0));
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view =
+        ExecutionStateView::<MemoryContext<TestExecutionRuntimeContext>>::from_system_state(state)
+            .await;
+    let app_desc = create_dummy_user_application_description();
+    let app_id = view
+        .system
+        .registry
+        .register_application(app_desc.clone())
+        .await?;
+    view.context()
+        .extra()
+        .user_applications()
+        .insert(app_id, Arc::new(TestApplication { owner }));
+
+    let context = OperationContext {
+        chain_id: ChainId::root(0),
+        height: BlockHeight(0),
+        index: 0,
+        authenticated_signer: Some(owner),
+        next_message_index: 0,
+        random_seed: Some(CryptoHash::from([0; 4])),
+    };
+    let mut tracker = ResourceTracker::default();
+    let policy = ResourceControlPolicy::default();
+    let result = view
+        .execute_operation(
+            &context,
+            &Operation::User {
+                application_id: app_id,
+                bytes: vec![1, 2, 3],
+            },
+            &policy,
+            &mut tracker,
+        )
+        .await
+        .unwrap();
+    let event_result = RawExecutionResult {
+        events: vec![(b"operation".to_vec(), vec![1, 2, 3])],
+        ..RawExecutionResult::default()
+    };
+    assert_eq!(result[0], ExecutionResult::User(app_id, event_result));
+    // The event is charged like the written state: one key byte and three value bytes.
+    assert_eq!(tracker.bytes_written, 4 + 12);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_acknowledged_message() -> anyhow::Result<()> {
+    let owner = Owner::from(PublicKey::debug(0));
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view =
+        ExecutionStateView::<MemoryContext<TestExecutionRuntimeContext>>::from_system_state(state)
+            .await;
+    let app_desc = create_dummy_user_application_description();
+    let app_id = view
+        .system
+        .registry
+        .register_application(app_desc.clone())
+        .await?;
+    view.context()
+        .extra()
+        .user_applications()
+        .insert(app_id, Arc::new(TestApplication { owner }));
+
+    let mut context = MessageContext {
+        chain_id: ChainId::root(0),
+        authenticated_signer: Some(owner),
+        height: BlockHeight(0),
+        certificate_hash: CryptoHash::from([0; 4]),
+        message_id: MessageId {
+            chain_id: ChainId::root(1),
+            height: BlockHeight(0),
+            index: 0,
+        },
+        is_bouncing: false,
+        is_acknowledged: false,
+        acknowledgement: None,
+        random_seed: Some(CryptoHash::from([0; 4])),
+    };
+    let message = Message::User {
+        application_id: app_id,
+        bytes: TestApplication::FAILURE.to_vec(),
+    };
+    let policy = ResourceControlPolicy::default();
+
+    // The error is ignored, unless the message is acknowledged.
+    let result = view
+        .execute_message(&context, &message, &policy, &mut ResourceTracker::default())
+        .await;
+    assert!(result.is_ok());
+    context.is_acknowledged = true;
+    let result = view
+        .execute_message(&context, &message, &policy, &mut ResourceTracker::default())
+        .await;
+    assert!(matches!(
+        result,
+        Err(ExecutionError::UserError {
+            code: TestApplication::FAILURE_CODE,
+            ..
+        })
+    ));
+
+    // The acknowledgement is handled by the sender application.
+    context.is_acknowledged = false;
+    context.acknowledgement = Some(MessageOutcome::Failed(1));
+    let result = view
+        .execute_message(&context, &message, &policy, &mut ResourceTracker::default())
+        .await?;
+    let event_result = RawExecutionResult {
+        events: vec![(b"outcome".to_vec(), b"Failed(1)".to_vec())],
+        ..RawExecutionResult::default()
+    };
+    assert_eq!(
+        result,
+        vec![
+            ExecutionResult::User(app_id, event_result),
+            ExecutionResult::User(
+                app_id,
+                RawExecutionResult::default().with_authenticated_signer(Some(owner))
+            ),
+        ]
+    );
+    Ok(())
+}
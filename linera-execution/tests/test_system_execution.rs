@@ -4,16 +4,18 @@
 #![allow(clippy::field_reassign_with_default)]
 
 use linera_base::{
-    crypto::{BcsSignable, CryptoHash},
+    crypto::{BcsSignable, CryptoHash, PublicKey},
     data_types::{Amount, BlockHeight},
-    identifiers::{ChainDescription, ChainId, MessageId},
+    identifiers::{ChainDescription, ChainId, MessageId, Owner},
 };
 use linera_execution::{
+    committee::{Committee, Epoch},
     policy::ResourceControlPolicy,
-    system::{Account, Recipient, UserData},
-    ExecutionResult, ExecutionStateView, Message, MessageContext, Operation, OperationContext,
-    Query, QueryContext, RawExecutionResult, ResourceTracker, Response, SystemExecutionState,
-    SystemMessage, SystemOperation, SystemQuery, SystemResponse, TestExecutionRuntimeContext,
+    system::{Account, Recipient, SystemChannel, UserData},
+    ChainOwnership, ChannelSubscription, ExecutionResult, ExecutionStateView, Message,
+    MessageContext, Operation, OperationContext, Query, QueryContext, RawExecutionResult,
+    ResourceTracker, Response, SystemExecutionState, SystemMessage, SystemOperation, SystemQuery,
+    SystemResponse, TestExecutionRuntimeContext,
 };
 use linera_views::memory::MemoryContext;
 use serde::{Deserialize, Serialize};
@@ -38,6 +40,7 @@ async fn test_simple_system_operation() -> anyhow::Result<()> {
         index: 0,
         authenticated_signer: None,
         next_message_index: 0,
+        random_seed: Some(CryptoHash::new(&Dummy)),
     };
     let mut tracker = ResourceTracker::default();
     let policy = ResourceControlPolicy::default();
@@ -73,6 +76,7 @@ async fn test_simple_system_message() -> anyhow::Result<()> {
     let message = SystemMessage::Credit {
         amount: Amount::from_tokens(4),
         account: Account::chain(ChainId::root(0)),
+        source: None,
     };
     let context = MessageContext {
         chain_id: ChainId::root(0),
@@ -84,6 +88,10 @@ async fn test_simple_system_message() -> anyhow::Result<()> {
             index: 0,
         },
         authenticated_signer: None,
+        is_bouncing: false,
+        is_acknowledged: false,
+        acknowledgement: None,
+        random_seed: Some(CryptoHash::new(&Dummy)),
     };
     let mut tracker = ResourceTracker::default();
     let policy = ResourceControlPolicy::default();
@@ -99,6 +107,53 @@ async fn test_simple_system_message() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_bounced_credit_is_refunded_to_source() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view =
+        ExecutionStateView::<MemoryContext<TestExecutionRuntimeContext>>::from_system_state(state)
+            .await;
+    let owner = Owner::from(PublicKey::debug(1));
+    // A credit sent by `owner` to another chain, and returned to us.
+    let message = SystemMessage::Credit {
+        amount: Amount::from_tokens(4),
+        account: Account::chain(ChainId::root(1)),
+        source: Some(owner),
+    };
+    let context = MessageContext {
+        chain_id: ChainId::root(0),
+        height: BlockHeight(1),
+        certificate_hash: CryptoHash::new(&Dummy),
+        message_id: MessageId {
+            chain_id: ChainId::root(1),
+            height: BlockHeight(0),
+            index: 0,
+        },
+        authenticated_signer: None,
+        is_bouncing: true,
+        is_acknowledged: false,
+        acknowledgement: None,
+        random_seed: Some(CryptoHash::new(&Dummy)),
+    };
+    let mut tracker = ResourceTracker::default();
+    let policy = ResourceControlPolicy::default();
+    let results = view
+        .execute_message(&context, &Message::System(message), &policy, &mut tracker)
+        .await
+        .unwrap();
+    assert_eq!(view.system.balance.get(), &Amount::ZERO);
+    assert_eq!(
+        view.system.balances.get(&owner).await?,
+        Some(Amount::from_tokens(4))
+    );
+    assert_eq!(
+        results,
+        vec![ExecutionResult::System(RawExecutionResult::default())]
+    );
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_simple_system_query() -> anyhow::Result<()> {
     let mut state = SystemExecutionState::default();
@@ -111,15 +166,89 @@ async fn test_simple_system_query() -> anyhow::Result<()> {
         chain_id: ChainId::root(0),
     };
     let response = view
-        .query_application(&context, &Query::System(SystemQuery))
+        .query_application(&context, &Query::System(SystemQuery::Balance))
         .await
         .unwrap();
     assert_eq!(
         response,
-        Response::System(SystemResponse {
+        Response::System(SystemResponse::Balance {
             chain_id: ChainId::root(0),
             balance: Amount::from_tokens(4)
         })
     );
     Ok(())
 }
+
+#[tokio::test]
+async fn test_detailed_system_queries() -> anyhow::Result<()> {
+    let owner = Owner::from(PublicKey::debug(1));
+    let committee = Committee::make_simple(Vec::new());
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    state.epoch = Some(Epoch::ZERO);
+    state.committees = [(Epoch::ZERO, committee.clone())].into_iter().collect();
+    state.ownership = ChainOwnership::single(PublicKey::debug(1));
+    state.balances = [(owner, Amount::from_tokens(2))].into_iter().collect();
+    let subscription = ChannelSubscription {
+        chain_id: ChainId::root(1),
+        name: SystemChannel::Admin.name(),
+    };
+    state.subscriptions = [subscription.clone()].into_iter().collect();
+    let mut view =
+        ExecutionStateView::<MemoryContext<TestExecutionRuntimeContext>>::from_system_state(state)
+            .await;
+    let context = QueryContext {
+        chain_id: ChainId::root(0),
+    };
+
+    let response = view
+        .query_application(&context, &Query::System(SystemQuery::Balances))
+        .await?;
+    assert_eq!(
+        response,
+        Response::System(SystemResponse::Balances(
+            [(owner, Amount::from_tokens(2))].into_iter().collect()
+        ))
+    );
+
+    let response = view
+        .query_application(&context, &Query::System(SystemQuery::Ownership))
+        .await?;
+    assert_eq!(
+        response,
+        Response::System(SystemResponse::Ownership(ChainOwnership::single(
+            PublicKey::debug(1)
+        )))
+    );
+
+    let response = view
+        .query_application(&context, &Query::System(SystemQuery::Committees))
+        .await?;
+    assert_eq!(
+        response,
+        Response::System(SystemResponse::Committees {
+            current_epoch: Some(Epoch::ZERO),
+            committees: [(Epoch::ZERO, committee)].into_iter().collect(),
+        })
+    );
+
+    let response = view
+        .query_application(&context, &Query::System(SystemQuery::Subscriptions))
+        .await?;
+    assert_eq!(
+        response,
+        Response::System(SystemResponse::Subscriptions(vec![subscription]))
+    );
+
+    let response = view
+        .query_application(&context, &Query::System(SystemQuery::Registry))
+        .await?;
+    assert_eq!(
+        response,
+        Response::System(SystemResponse::Registry {
+            published_bytecodes: Default::default(),
+            known_applications: Default::default(),
+        })
+    );
+    Ok(())
+}
@@ -29,6 +29,7 @@ pub fn create_dummy_user_application_description() -> UserApplicationDescription
         },
         required_application_ids: vec![],
         parameters: vec![],
+        storage_quota: None,
     }
 }
 
@@ -8,32 +8,46 @@ mod utils;
 use self::utils::create_dummy_user_application_description;
 use counter::CounterAbi;
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{Amount, BlockHeight},
     identifiers::{ChainDescription, ChainId},
 };
 use linera_execution::{
-    policy::ResourceControlPolicy, ExecutionResult, ExecutionRuntimeContext, ExecutionStateView,
-    Operation, OperationContext, Query, QueryContext, RawExecutionResult, ResourceTracker,
-    Response, SystemExecutionState, TestExecutionRuntimeContext, WasmApplication, WasmRuntime,
+    policy::ResourceControlPolicy, profiler::FuelProfiler, Bytecode, ExecutionError,
+    ExecutionResult, ExecutionRuntimeContext, ExecutionStateView, Operation, OperationContext,
+    Query, QueryContext, RawExecutionResult, ResourceTracker, Response, SystemExecutionState,
+    TestExecutionRuntimeContext, UserApplicationId, WasmApplication, WasmRuntime,
 };
 use linera_views::{memory::MemoryContext, views::View};
 use serde_json::json;
 use std::sync::Arc;
 use test_case::test_case;
 
-/// Test if the "counter" example application in `linera-sdk` compiled to a Wasm module can be
-/// called correctly and consume the expected amount of fuel.
-///
-/// To update the bytecode files, run `linera-execution/update_wasm_fixtures.sh`.
-#[cfg_attr(feature = "wasmer", test_case(WasmRuntime::Wasmer, 30_463; "wasmer"))]
-#[cfg_attr(feature = "wasmer", test_case(WasmRuntime::WasmerWithSanitizer, 30_844; "wasmer_with_sanitizer"))]
-#[cfg_attr(feature = "wasmtime", test_case(WasmRuntime::Wasmtime, 30_844; "wasmtime"))]
-#[cfg_attr(feature = "wasmtime", test_case(WasmRuntime::WasmtimeWithSanitizer, 30_844; "wasmtime_with_sanitizer"))]
-#[test_log::test(tokio::test(flavor = "multi_thread"))]
-async fn test_fuel_for_counter_wasm_application(
+/// Creates an execution state with the "counter" example application registered and loaded
+/// with the given runtime.
+async fn create_view_with_counter_application(
     wasm_runtime: WasmRuntime,
-    expected_fuel: u64,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<(
+    ExecutionStateView<MemoryContext<TestExecutionRuntimeContext>>,
+    UserApplicationId<CounterAbi>,
+)> {
+    let application = WasmApplication::from_files(
+        "tests/fixtures/counter_contract.wasm",
+        "tests/fixtures/counter_service.wasm",
+        wasm_runtime,
+    )
+    .await?;
+    create_view_with_application(application).await
+}
+
+/// Creates an execution state with the given `application` registered as the "counter" example
+/// application.
+async fn create_view_with_application(
+    application: WasmApplication,
+) -> anyhow::Result<(
+    ExecutionStateView<MemoryContext<TestExecutionRuntimeContext>>,
+    UserApplicationId<CounterAbi>,
+)> {
     let state = SystemExecutionState {
         description: Some(ChainDescription::Root(0)),
         ..Default::default()
@@ -48,26 +62,40 @@ async fn test_fuel_for_counter_wasm_application(
         .register_application(app_desc.clone())
         .await?;
 
-    let application = WasmApplication::from_files(
-        "tests/fixtures/counter_contract.wasm",
-        "tests/fixtures/counter_service.wasm",
-        wasm_runtime,
-    )
-    .await?;
     view.context()
         .extra
         .user_applications()
         .insert(app_id, Arc::new(application));
 
-    let app_id = app_id.with_abi::<CounterAbi>();
+    Ok((view, app_id.with_abi::<CounterAbi>()))
+}
 
-    let context = OperationContext {
+fn create_operation_context() -> OperationContext {
+    OperationContext {
         chain_id: ChainId::root(0),
         height: BlockHeight(0),
         index: 0,
         authenticated_signer: None,
         next_message_index: 0,
-    };
+        random_seed: Some(CryptoHash::from([0; 4])),
+    }
+}
+
+/// Test if the "counter" example application in `linera-sdk` compiled to a Wasm module can be
+/// called correctly and consume the expected amount of fuel.
+///
+/// To update the bytecode files, run `linera-execution/update_wasm_fixtures.sh`.
+#[cfg_attr(feature = "wasmer", test_case(WasmRuntime::Wasmer, 34_937; "wasmer"))]
+#[cfg_attr(feature = "wasmer", test_case(WasmRuntime::WasmerWithSanitizer, 35_341; "wasmer_with_sanitizer"))]
+#[cfg_attr(feature = "wasmtime", test_case(WasmRuntime::Wasmtime, 35_341; "wasmtime"))]
+#[cfg_attr(feature = "wasmtime", test_case(WasmRuntime::WasmtimeWithSanitizer, 35_341; "wasmtime_with_sanitizer"))]
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn test_fuel_for_counter_wasm_application(
+    wasm_runtime: WasmRuntime,
+    expected_fuel: u64,
+) -> anyhow::Result<()> {
+    let (mut view, app_id) = create_view_with_counter_application(wasm_runtime).await?;
+    let context = create_operation_context();
     let increments = [2_u64, 9, 7, 1000];
     let policy = ResourceControlPolicy {
         fuel: Amount::from_atto(1),
@@ -115,3 +143,73 @@ async fn test_fuel_for_counter_wasm_application(
     );
     Ok(())
 }
+
+/// Test if the "counter" example application fails to execute if its memory exceeds the limit
+/// set by the policy.
+#[cfg_attr(feature = "wasmer", test_case(WasmRuntime::Wasmer; "wasmer"))]
+#[cfg_attr(feature = "wasmer", test_case(WasmRuntime::WasmerWithSanitizer; "wasmer_with_sanitizer"))]
+#[cfg_attr(feature = "wasmtime", test_case(WasmRuntime::Wasmtime; "wasmtime"))]
+#[cfg_attr(feature = "wasmtime", test_case(WasmRuntime::WasmtimeWithSanitizer; "wasmtime_with_sanitizer"))]
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn test_memory_limit_for_counter_wasm_application(
+    wasm_runtime: WasmRuntime,
+) -> anyhow::Result<()> {
+    let (mut view, app_id) = create_view_with_counter_application(wasm_runtime).await?;
+    let context = create_operation_context();
+    let policy = ResourceControlPolicy {
+        maximum_wasm_memory_pages: 1,
+        ..ResourceControlPolicy::default()
+    };
+    let mut tracker = ResourceTracker::default();
+
+    let result = view
+        .execute_operation(
+            &context,
+            &Operation::user(app_id, &1_u64).unwrap(),
+            &policy,
+            &mut tracker,
+        )
+        .await;
+    assert!(matches!(result, Err(ExecutionError::ExcessiveMemory(1))));
+    Ok(())
+}
+
+/// Test if the fuel consumed by the "counter" example application is attributed to the
+/// functions of its contract when it is instrumented for profiling.
+#[cfg_attr(feature = "wasmer", test_case(WasmRuntime::Wasmer; "wasmer"))]
+#[cfg_attr(feature = "wasmtime", test_case(WasmRuntime::Wasmtime; "wasmtime"))]
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn test_fuel_profiler_for_counter_wasm_application(
+    wasm_runtime: WasmRuntime,
+) -> anyhow::Result<()> {
+    let profiler = FuelProfiler::default();
+    let application = WasmApplication::new_with_fuel_profiler(
+        Bytecode::load_from_file("tests/fixtures/counter_contract.wasm").await?,
+        Bytecode::load_from_file("tests/fixtures/counter_service.wasm").await?,
+        wasm_runtime,
+        profiler.clone(),
+    )
+    .await?;
+    let (mut view, app_id) = create_view_with_application(application).await?;
+    let context = create_operation_context();
+    let policy = ResourceControlPolicy {
+        fuel: Amount::from_atto(1),
+        ..ResourceControlPolicy::default()
+    };
+    let mut tracker = ResourceTracker::default();
+    *view.system.balance.get_mut() = Amount::from_tokens(1);
+
+    view.execute_operation(
+        &context,
+        &Operation::user(app_id, &5_u64).unwrap(),
+        &policy,
+        &mut tracker,
+    )
+    .await?;
+
+    let samples = profiler.samples();
+    assert!(!samples.is_empty());
+    assert!(profiler.total_fuel() > 0);
+    assert!(profiler.total_fuel() <= tracker.used_fuel);
+    Ok(())
+}
@@ -0,0 +1,220 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{Object, OneofObject, SimpleObject};
+use axum::Router;
+use linera_base::{crypto::CryptoHash, data_types::BlockHeight, doc_scalar, identifiers::ChainId};
+use linera_chain::data_types::{ApplicationEvent, HashedValue};
+use linera_execution::GenericApplicationId;
+use linera_indexer::{
+    common::IndexerError,
+    plugin::{load, route, sdl, Plugin},
+};
+use linera_views::{
+    common::{Context, ContextFromDb, KeyValueStoreClient},
+    map_view::MapView,
+    views::{RootView, ViewError},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::{Ordering, PartialOrd},
+    sync::Arc,
+};
+use tokio::sync::Mutex;
+use tracing::info;
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct EventKey {
+    pub chain_id: ChainId,
+    pub height: BlockHeight,
+    pub index: usize,
+}
+
+doc_scalar!(EventKey, "An event key to index application events");
+
+impl PartialOrd for EventKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(
+            self.height
+                .cmp(&other.height)
+                .then_with(|| self.index.cmp(&other.index)),
+        )
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, SimpleObject, Debug)]
+pub struct ChainEvent {
+    key: EventKey,
+    previous_event: Option<EventKey>,
+    index: u64,
+    block: CryptoHash,
+    content: ApplicationEvent,
+}
+
+#[derive(RootView)]
+pub struct Events<C> {
+    last: MapView<C, ChainId, EventKey>,
+    count: MapView<C, ChainId, u64>,
+    /// ChainEvent MapView indexed by their key
+    events: MapView<C, EventKey, ChainEvent>,
+}
+
+#[derive(OneofObject)]
+pub enum EventKeyKind {
+    Key(EventKey),
+    Last(ChainId),
+}
+
+/// Implements helper functions on the `RootView`
+impl<C> Events<C>
+where
+    C: Context + Send + Sync + 'static + Clone,
+    ViewError: From<C::Error>,
+{
+    /// Registers an event and update count and last entries for this chain ID
+    async fn register_event(
+        &mut self,
+        key: EventKey,
+        block: CryptoHash,
+        content: ApplicationEvent,
+    ) -> Result<(), IndexerError> {
+        let last_event = self.last.get(&key.chain_id).await?;
+        match last_event {
+            Some(last_key) if last_key >= key => Ok(()),
+            previous_event => {
+                let index = self.count.get(&key.chain_id).await?.unwrap_or(0);
+                let event = ChainEvent {
+                    key: key.clone(),
+                    previous_event,
+                    index,
+                    block,
+                    content,
+                };
+                info!("register event for {:?}:\n{:?}", key.chain_id, event);
+                self.events.insert(&key, event.clone())?;
+                self.count.insert(&key.chain_id, index + 1)?;
+                Ok(self.last.insert(&key.chain_id, key.clone())?)
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct EventsPlugin<C>(Arc<Mutex<Events<C>>>);
+
+static NAME: &str = "events";
+
+/// Implements `Plugin`
+#[async_trait::async_trait]
+impl<DB> Plugin<DB> for EventsPlugin<ContextFromDb<(), DB>>
+where
+    DB: KeyValueStoreClient + Clone + Send + Sync + 'static,
+    DB::Error: From<bcs::Error> + Send + Sync + std::error::Error + 'static,
+    ViewError: From<DB::Error>,
+{
+    fn name(&self) -> String {
+        NAME.to_string()
+    }
+
+    async fn load(store: DB) -> Result<Self, IndexerError>
+    where
+        Self: Sized,
+    {
+        Ok(Self(load(store, NAME).await?))
+    }
+
+    async fn register(&self, value: &HashedValue) -> Result<(), IndexerError> {
+        let mut plugin = self.0.lock().await;
+        let Some(executed_block) = value.inner().executed_block() else {
+            return Ok(());
+        };
+        let chain_id = value.inner().chain_id();
+        for (index, content) in executed_block.events.iter().enumerate() {
+            let key = EventKey {
+                chain_id,
+                height: executed_block.block.height,
+                index,
+            };
+            plugin
+                .register_event(key, value.hash(), content.clone())
+                .await?;
+        }
+        Ok(plugin.save().await?)
+    }
+
+    fn sdl(&self) -> String {
+        sdl(self.clone())
+    }
+
+    fn route(&self, app: Router) -> Router {
+        route(&self.name(), self.clone(), app)
+    }
+}
+
+/// Implements `ObjectType`
+#[Object]
+impl<C> EventsPlugin<C>
+where
+    C: Context + Send + Sync + 'static + Clone,
+    ViewError: From<C::Error>,
+{
+    /// Gets the event associated to its key
+    pub async fn event(&self, key: EventKeyKind) -> Result<Option<ChainEvent>, IndexerError> {
+        let plugin = self.0.lock().await;
+        let key = match key {
+            EventKeyKind::Last(chain_id) => match plugin.last.get(&chain_id).await? {
+                None => return Ok(None),
+                Some(key) => key,
+            },
+            EventKeyKind::Key(key) => key,
+        };
+        Ok(plugin.events.get(&key).await?)
+    }
+
+    /// Gets the events in downward order from an event key or from the last block of a chain,
+    /// optionally only keeping the events of one application
+    pub async fn events(
+        &self,
+        from: EventKeyKind,
+        application_id: Option<GenericApplicationId>,
+        limit: Option<u32>,
+    ) -> Result<Vec<ChainEvent>, IndexerError> {
+        let plugin = self.0.lock().await;
+        let mut key = match from {
+            EventKeyKind::Last(chain_id) => match plugin.last.get(&chain_id).await? {
+                None => return Ok(Vec::new()),
+                Some(key) => Some(key),
+            },
+            EventKeyKind::Key(key) => Some(key),
+        };
+        let mut result = Vec::new();
+        let limit = limit.unwrap_or(20) as usize;
+        while result.len() < limit {
+            let Some(next_key) = key else { break };
+            let Some(event) = plugin.events.get(&next_key).await? else {
+                break;
+            };
+            key = event.previous_event.clone();
+            if application_id.map_or(true, |id| id == event.content.application_id) {
+                result.push(event);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Gets the number of events registered for a chain
+    pub async fn count(&self, chain_id: ChainId) -> Result<u64, IndexerError> {
+        let plugin = self.0.lock().await;
+        Ok(plugin
+            .count
+            .get(&chain_id)
+            .await
+            .map(|opt| opt.unwrap_or(0))?)
+    }
+
+    /// Gets the key of the last event registered for a chain
+    pub async fn last(&self, chain_id: ChainId) -> Result<Option<EventKey>, IndexerError> {
+        let plugin = self.0.lock().await;
+        Ok(plugin.last.get(&chain_id).await?)
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The hashes, public keys and signatures of the Linera protocol, with the same binary
+//! encoding and the same verification rules as in `linera-base`.
+
+use crate::LightClientError;
+use core::fmt;
+use ed25519_dalek::Verifier;
+use k256::ecdsa::{self as secp256k1, signature::DigestVerifier};
+use sha3::{Digest, Keccak256, Sha3_256};
+
+/// The length of an Ed25519 public key.
+pub(crate) const ED25519_PUBLIC_KEY_LENGTH: usize = 32;
+
+/// The length of a compressed secp256k1 public key.
+pub(crate) const SECP256K1_PUBLIC_KEY_LENGTH: usize = 33;
+
+/// The length of an Ed25519 signature.
+pub(crate) const ED25519_SIGNATURE_LENGTH: usize = 64;
+
+/// The length of a recoverable secp256k1 signature: `r`, `s` and the recovery ID `v`.
+pub(crate) const SECP256K1_SIGNATURE_LENGTH: usize = 65;
+
+/// The byte that the binary encoding of secp256k1 public keys and signatures is prefixed
+/// with, as many times as the length of the corresponding Ed25519 encoding.
+pub(crate) const SECP256K1_PREFIX_BYTE: u8 = 0xff;
+
+/// A Sha3-256 value.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash)]
+pub struct CryptoHash([u8; 32]);
+
+/// A signature public key.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Debug)]
+pub enum PublicKey {
+    /// An Ed25519 public key.
+    Ed25519([u8; ED25519_PUBLIC_KEY_LENGTH]),
+    /// A secp256k1 public key, in compressed SEC1 encoding.
+    Secp256k1([u8; SECP256K1_PUBLIC_KEY_LENGTH]),
+}
+
+/// A signature value.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Signature {
+    /// An Ed25519 signature.
+    Ed25519([u8; ED25519_SIGNATURE_LENGTH]),
+    /// A recoverable secp256k1 signature, laid out as `r`, `s` and `v` like in Ethereum.
+    Secp256k1([u8; SECP256K1_SIGNATURE_LENGTH]),
+}
+
+impl CryptoHash {
+    /// Returns the hash of a value of the given type, from its BCS encoding, like
+    /// `CryptoHash::new` in `linera-base`.
+    pub(crate) fn from_bcs(type_name: &str, bytes: &[u8]) -> Self {
+        let mut hasher = Sha3_256::new();
+        hasher.update(type_name);
+        hasher.update("::");
+        hasher.update(bytes);
+        CryptoHash(hasher.finalize().into())
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for CryptoHash {
+    fn from(bytes: [u8; 32]) -> Self {
+        CryptoHash(bytes)
+    }
+}
+
+impl fmt::Display for CryptoHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for CryptoHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in &self.0[..8] {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl Signature {
+    /// Verifies that the signature was created by the given public key for the message,
+    /// i.e. the type name and BCS encoding of the signed value.
+    pub(crate) fn check(&self, message: &[u8], author: &PublicKey) -> Result<(), LightClientError> {
+        match (self, author) {
+            (Signature::Ed25519(bytes), PublicKey::Ed25519(key)) => {
+                let public_key = ed25519_dalek::PublicKey::from_bytes(key)
+                    .map_err(|_| LightClientError::InvalidSignature)?;
+                let signature = ed25519_dalek::Signature::from_bytes(bytes)
+                    .map_err(|_| LightClientError::InvalidSignature)?;
+                public_key
+                    .verify(message, &signature)
+                    .map_err(|_| LightClientError::InvalidSignature)
+            }
+            (Signature::Secp256k1(bytes), PublicKey::Secp256k1(key)) => {
+                let public_key = secp256k1::VerifyingKey::from_sec1_bytes(key)
+                    .map_err(|_| LightClientError::InvalidSignature)?;
+                let signature = secp256k1::Signature::from_slice(&bytes[..64])
+                    .map_err(|_| LightClientError::InvalidSignature)?;
+                // Like `linera-base`, only accept the canonical form of the signature.
+                if signature.normalize_s().is_some() {
+                    return Err(LightClientError::InvalidSignature);
+                }
+                let digest = Keccak256::new_with_prefix(message);
+                public_key
+                    .verify_digest(digest.clone(), &signature)
+                    .map_err(|_| LightClientError::InvalidSignature)?;
+                let recovery_id = secp256k1::RecoveryId::from_byte(bytes[64])
+                    .ok_or(LightClientError::InvalidSignature)?;
+                let recovered =
+                    secp256k1::VerifyingKey::recover_from_digest(digest, &signature, recovery_id)
+                        .map_err(|_| LightClientError::InvalidSignature)?;
+                if recovered != public_key {
+                    return Err(LightClientError::InvalidSignature);
+                }
+                Ok(())
+            }
+            _ => Err(LightClientError::InvalidSignature),
+        }
+    }
+}
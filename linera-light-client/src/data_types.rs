@@ -0,0 +1,222 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The identifiers, committees and certificates that the light client works with.
+
+use crate::{
+    crypto::{CryptoHash, PublicKey, Signature},
+    decoder::{self, Reader},
+    LightClientError,
+};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::fmt;
+
+/// The unique identifier of a chain.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Debug)]
+pub struct ChainId(pub CryptoHash);
+
+/// The height of a block in a chain.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Default, Debug)]
+pub struct BlockHeight(pub u64);
+
+/// A number identifying the configuration of the chain, i.e. its committee.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Default, Debug)]
+pub struct Epoch(pub u32);
+
+/// The round in which a block was certified.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Default, Debug)]
+pub struct RoundNumber(pub u32);
+
+/// A timestamp, in microseconds since the Unix epoch.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Default, Debug)]
+pub struct Timestamp(pub u64);
+
+impl fmt::Display for ChainId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Display for BlockHeight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Display for Epoch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// The voting rights of the validators of a committee.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Committee {
+    /// The voting rights of each validator.
+    votes: BTreeMap<PublicKey, u64>,
+}
+
+impl Committee {
+    pub fn new(votes: BTreeMap<PublicKey, u64>) -> Self {
+        Committee { votes }
+    }
+
+    /// Decodes the BCS encoding of a `Committee` of `linera-execution`, e.g. from the
+    /// genesis configuration.
+    pub fn from_bcs(bytes: &[u8]) -> Result<Self, LightClientError> {
+        let mut reader = Reader::new(bytes);
+        let committee = decoder::committee(&mut reader)?;
+        reader.finish()?;
+        Ok(committee)
+    }
+
+    /// Returns the voting rights of the validator, or 0 if it is not in the committee.
+    pub fn weight(&self, validator: &PublicKey) -> u64 {
+        self.votes.get(validator).copied().unwrap_or(0)
+    }
+
+    /// Returns the total voting rights of the signatures of a certificate.
+    pub fn quorum_threshold(&self) -> u64 {
+        let total_votes = self.votes.values().sum::<u64>();
+        2 * total_votes / 3 + 1
+    }
+}
+
+/// The committees announced by a block of the admin chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitteeUpdate {
+    /// The new current epoch.
+    pub epoch: Epoch,
+    /// The trusted committees, indexed by epoch.
+    pub committees: BTreeMap<Epoch, Committee>,
+    /// The times after which the committees of past epochs are no longer trusted.
+    pub expirations: BTreeMap<Epoch, Timestamp>,
+}
+
+/// The parts of a confirmed block that the light client follows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BlockSummary {
+    pub(crate) chain_id: ChainId,
+    pub(crate) epoch: Epoch,
+    pub(crate) height: BlockHeight,
+    pub(crate) timestamp: Timestamp,
+    pub(crate) previous_block_hash: Option<CryptoHash>,
+    /// The last committees announced by the block's `SetCommittees` messages, if any.
+    pub(crate) committee_update: Option<CommitteeUpdate>,
+}
+
+/// A certificate for a confirmed block.
+///
+/// It can only be obtained by decoding a full certificate, so that the hash of the value is
+/// always computed from the block, and the block's fields are the certified ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockCertificate {
+    value_hash: CryptoHash,
+    round: RoundNumber,
+    signatures: Vec<(PublicKey, Signature)>,
+    block: BlockSummary,
+}
+
+impl BlockCertificate {
+    /// Decodes the BCS encoding of a `Certificate` of `linera-chain` for a confirmed block,
+    /// and computes the hash of its value.
+    pub fn from_bcs(bytes: &[u8]) -> Result<Self, LightClientError> {
+        let mut reader = Reader::new(bytes);
+        let block = decoder::confirmed_block_value(&mut reader)?;
+        let value_hash = CryptoHash::from_bcs("CertificateValue", reader.consumed());
+        let round = RoundNumber(reader.u32()?);
+        let signatures = decoder::signatures(&mut reader)?;
+        reader.finish()?;
+        Ok(BlockCertificate {
+            value_hash,
+            round,
+            signatures,
+            block,
+        })
+    }
+
+    /// The hash of the certified value, computed from the full block.
+    pub fn value_hash(&self) -> CryptoHash {
+        self.value_hash
+    }
+
+    /// The round in which the value was certified.
+    pub fn round(&self) -> RoundNumber {
+        self.round
+    }
+
+    /// The signatures of the validators on the value hash and round.
+    pub fn signatures(&self) -> &[(PublicKey, Signature)] {
+        &self.signatures
+    }
+
+    /// The chain of the block.
+    pub fn chain_id(&self) -> ChainId {
+        self.block.chain_id
+    }
+
+    /// The epoch of the committee that certified the block.
+    pub fn epoch(&self) -> Epoch {
+        self.block.epoch
+    }
+
+    /// The height of the block.
+    pub fn height(&self) -> BlockHeight {
+        self.block.height
+    }
+
+    /// The timestamp of the block.
+    pub fn timestamp(&self) -> Timestamp {
+        self.block.timestamp
+    }
+
+    /// The hash of the previous block of the chain, if any.
+    pub fn previous_block_hash(&self) -> Option<CryptoHash> {
+        self.block.previous_block_hash
+    }
+
+    /// The last committees announced by the `SetCommittees` messages of the block, if any.
+    pub fn committee_update(&self) -> Option<&CommitteeUpdate> {
+        self.block.committee_update.as_ref()
+    }
+}
+
+/// A certificate without the certified value, only its hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiteCertificate {
+    /// The hash of the certified value.
+    pub value_hash: CryptoHash,
+    /// The chain of the certified value.
+    pub chain_id: ChainId,
+    /// The round in which the value was certified.
+    pub round: RoundNumber,
+    /// The signatures of the validators on the value hash and round.
+    pub signatures: Vec<(PublicKey, Signature)>,
+}
+
+impl LiteCertificate {
+    /// Decodes the BCS encoding of a `LiteCertificate` of `linera-chain`.
+    pub fn from_bcs(bytes: &[u8]) -> Result<Self, LightClientError> {
+        let mut reader = Reader::new(bytes);
+        let value_hash = decoder::crypto_hash(&mut reader)?;
+        let chain_id = ChainId(decoder::crypto_hash(&mut reader)?);
+        let round = RoundNumber(reader.u32()?);
+        let signatures = decoder::signatures(&mut reader)?;
+        reader.finish()?;
+        Ok(LiteCertificate {
+            value_hash,
+            chain_id,
+            round,
+            signatures,
+        })
+    }
+}
+
+/// The last verified block of a chain.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ChainTip {
+    /// The height of the next block.
+    pub next_block_height: BlockHeight,
+    /// The hash of the last block, if any.
+    pub block_hash: Option<CryptoHash>,
+}
@@ -0,0 +1,676 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decoding of the BCS encoding of certificates.
+//!
+//! The light client doesn't depend on the crates defining the blocks, so it walks their
+//! encoding itself: it reads the fields it follows and skips over the others. The layout
+//! must therefore be kept in sync with the definitions of the types in `linera-base`,
+//! `linera-execution` and `linera-chain`, i.e. with `linera-rpc/tests/staged/formats.yaml`.
+//!
+//! Encodings that are not canonical are not rejected here, but their hash never matches a
+//! signed one.
+
+use crate::{
+    crypto::{
+        CryptoHash, PublicKey, Signature, ED25519_PUBLIC_KEY_LENGTH, ED25519_SIGNATURE_LENGTH,
+        SECP256K1_PREFIX_BYTE, SECP256K1_PUBLIC_KEY_LENGTH, SECP256K1_SIGNATURE_LENGTH,
+    },
+    data_types::{
+        BlockHeight, BlockSummary, ChainId, Committee, CommitteeUpdate, Epoch, Timestamp,
+    },
+    LightClientError,
+};
+use alloc::{collections::BTreeMap, vec::Vec};
+
+type Result<T> = core::result::Result<T, LightClientError>;
+
+/// Reads BCS-encoded values from a byte slice.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes, position: 0 }
+    }
+
+    /// Returns the bytes that were read so far.
+    pub(crate) fn consumed(&self) -> &'a [u8] {
+        &self.bytes[..self.position]
+    }
+
+    /// Checks that all the bytes were read.
+    pub(crate) fn finish(&self) -> Result<()> {
+        if self.position == self.bytes.len() {
+            Ok(())
+        } else {
+            Err(LightClientError::InvalidEncoding)
+        }
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(LightClientError::InvalidEncoding)?;
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    fn bool(&mut self) -> Result<bool> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(LightClientError::InvalidEncoding),
+        }
+    }
+
+    /// Reads a ULEB128-encoded integer, used for lengths and variant indices.
+    fn uleb128(&mut self) -> Result<u32> {
+        let mut value = 0u64;
+        for shift in (0..32).step_by(7) {
+            let byte = self.u8()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return u32::try_from(value).map_err(|_| LightClientError::InvalidEncoding);
+            }
+        }
+        Err(LightClientError::InvalidEncoding)
+    }
+
+    fn variant(&mut self) -> Result<u32> {
+        self.uleb128()
+    }
+
+    fn length(&mut self) -> Result<usize> {
+        usize::try_from(self.uleb128()?).map_err(|_| LightClientError::InvalidEncoding)
+    }
+
+    fn option<T>(&mut self, read: impl FnOnce(&mut Self) -> Result<T>) -> Result<Option<T>> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(read(self)?)),
+            _ => Err(LightClientError::InvalidEncoding),
+        }
+    }
+
+    fn seq<T>(&mut self, mut read: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let length = self.length()?;
+        let mut values = Vec::new();
+        for _ in 0..length {
+            values.push(read(self)?);
+        }
+        Ok(values)
+    }
+
+    /// Skips a sequence of bytes or a string.
+    fn skip_bytes(&mut self) -> Result<()> {
+        let length = self.length()?;
+        self.take(length)?;
+        Ok(())
+    }
+
+    fn skip_amount(&mut self) -> Result<()> {
+        self.take(16)?;
+        Ok(())
+    }
+}
+
+/// Reads a `CertificateValue`, which must be a confirmed block.
+pub(crate) fn confirmed_block_value(reader: &mut Reader) -> Result<BlockSummary> {
+    match reader.variant()? {
+        1 => {}
+        0 | 2 => return Err(LightClientError::NotAConfirmedBlock),
+        _ => return Err(LightClientError::InvalidEncoding),
+    }
+    // The block.
+    let chain_id = chain_id(reader)?;
+    let epoch = Epoch(reader.u32()?);
+    reader.seq(skip_incoming_message)?;
+    reader.seq(skip_operation)?;
+    reader.bool()?;
+    let height = BlockHeight(reader.u64()?);
+    let timestamp = Timestamp(reader.u64()?);
+    reader.option(crypto_hash)?;
+    let previous_block_hash = reader.option(crypto_hash)?;
+    reader.option(|reader| {
+        reader.u32()?;
+        signatures(reader)
+    })?;
+    // The outcome of its execution.
+    let committee_update = reader.seq(outgoing_message)?.into_iter().flatten().last();
+    reader.seq(Reader::u32)?;
+    reader.seq(skip_application_event)?;
+    reader.seq(skip_failed_operation)?;
+    crypto_hash(reader)?;
+    Ok(BlockSummary {
+        chain_id,
+        epoch,
+        height,
+        timestamp,
+        previous_block_hash,
+        committee_update,
+    })
+}
+
+pub(crate) fn crypto_hash(reader: &mut Reader) -> Result<CryptoHash> {
+    Ok(CryptoHash::from(reader.array::<32>()?))
+}
+
+fn chain_id(reader: &mut Reader) -> Result<ChainId> {
+    Ok(ChainId(crypto_hash(reader)?))
+}
+
+pub(crate) fn public_key(reader: &mut Reader) -> Result<PublicKey> {
+    let bytes = reader.array::<ED25519_PUBLIC_KEY_LENGTH>()?;
+    if bytes != [SECP256K1_PREFIX_BYTE; ED25519_PUBLIC_KEY_LENGTH] {
+        return Ok(PublicKey::Ed25519(bytes));
+    }
+    Ok(PublicKey::Secp256k1(
+        reader.array::<SECP256K1_PUBLIC_KEY_LENGTH>()?,
+    ))
+}
+
+fn signature(reader: &mut Reader) -> Result<Signature> {
+    let bytes = reader.array::<ED25519_SIGNATURE_LENGTH>()?;
+    if bytes != [SECP256K1_PREFIX_BYTE; ED25519_SIGNATURE_LENGTH] {
+        return Ok(Signature::Ed25519(bytes));
+    }
+    Ok(Signature::Secp256k1(
+        reader.array::<SECP256K1_SIGNATURE_LENGTH>()?,
+    ))
+}
+
+/// Reads the signatures of a certificate.
+pub(crate) fn signatures(reader: &mut Reader) -> Result<Vec<(PublicKey, Signature)>> {
+    reader.seq(|reader| Ok((public_key(reader)?, signature(reader)?)))
+}
+
+/// Reads a `Committee`, keeping the voting rights of its validators.
+pub(crate) fn committee(reader: &mut Reader) -> Result<Committee> {
+    let votes = reader
+        .seq(|reader| {
+            let name = public_key(reader)?;
+            reader.skip_bytes()?;
+            Ok((name, reader.u64()?))
+        })?
+        .into_iter()
+        .collect();
+    skip_resource_control_policy(reader)?;
+    Ok(Committee::new(votes))
+}
+
+fn committees(reader: &mut Reader) -> Result<BTreeMap<Epoch, Committee>> {
+    Ok(reader
+        .seq(|reader| Ok((Epoch(reader.u32()?), committee(reader)?)))?
+        .into_iter()
+        .collect())
+}
+
+/// Reads an `OutgoingMessage`, returning the committees if it is a `SetCommittees` message.
+fn outgoing_message(reader: &mut Reader) -> Result<Option<CommitteeUpdate>> {
+    skip_destination(reader)?;
+    reader.option(crypto_hash)?;
+    reader.bool()?;
+    skip_message_kind(reader)?;
+    reader.skip_amount()?;
+    message(reader)
+}
+
+/// Reads a `Message`, returning the committees if it is a `SetCommittees` message.
+fn message(reader: &mut Reader) -> Result<Option<CommitteeUpdate>> {
+    match reader.variant()? {
+        0 => system_message(reader),
+        1 => {
+            skip_application_id(reader)?;
+            reader.skip_bytes()?;
+            Ok(None)
+        }
+        _ => Err(LightClientError::InvalidEncoding),
+    }
+}
+
+/// Reads a `SystemMessage`, returning the committees if it is a `SetCommittees` message.
+fn system_message(reader: &mut Reader) -> Result<Option<CommitteeUpdate>> {
+    match reader.variant()? {
+        // Credit
+        0 => {
+            skip_account(reader)?;
+            reader.skip_amount()?;
+            reader.option(crypto_hash)?;
+        }
+        // Withdraw
+        1 => {
+            skip_account(reader)?;
+            reader.skip_amount()?;
+            skip_recipient(reader)?;
+            skip_user_data(reader)?;
+        }
+        // OpenChain
+        2 => {
+            skip_chain_ownership(reader)?;
+            chain_id(reader)?;
+            reader.u32()?;
+            committees(reader)?;
+        }
+        // SetCommittees
+        3 => {
+            let epoch = Epoch(reader.u32()?);
+            let committees = committees(reader)?;
+            let expirations = reader
+                .seq(|reader| Ok((Epoch(reader.u32()?), Timestamp(reader.u64()?))))?
+                .into_iter()
+                .collect();
+            return Ok(Some(CommitteeUpdate {
+                epoch,
+                committees,
+                expirations,
+            }));
+        }
+        // Subscribe, Unsubscribe
+        4 | 5 => {
+            chain_id(reader)?;
+            chain_id(reader)?;
+            reader.skip_bytes()?;
+        }
+        // BytecodePublished
+        6 => {
+            reader.u32()?;
+        }
+        // ApplicationCreated
+        7 => {}
+        // BytecodeLocations
+        8 => {
+            reader.seq(|reader| {
+                skip_message_id(reader)?;
+                skip_bytecode_location(reader)
+            })?;
+        }
+        // RegisterApplications
+        9 => {
+            reader.seq(skip_user_application_description)?;
+        }
+        // Notify
+        10 => {
+            chain_id(reader)?;
+        }
+        // RequestApplication
+        11 => skip_application_id(reader)?,
+        _ => return Err(LightClientError::InvalidEncoding),
+    }
+    Ok(None)
+}
+
+fn skip_incoming_message(reader: &mut Reader) -> Result<()> {
+    // The origin.
+    chain_id(reader)?;
+    skip_medium(reader)?;
+    // The event.
+    crypto_hash(reader)?;
+    reader.u64()?;
+    reader.u32()?;
+    reader.option(crypto_hash)?;
+    reader.bool()?;
+    skip_message_kind(reader)?;
+    reader.skip_amount()?;
+    reader.u64()?;
+    message(reader)?;
+    // The action.
+    skip_unit_variant(reader, 2)
+}
+
+fn skip_operation(reader: &mut Reader) -> Result<()> {
+    match reader.variant()? {
+        0 => skip_system_operation(reader),
+        1 => {
+            skip_application_id(reader)?;
+            reader.skip_bytes()
+        }
+        _ => Err(LightClientError::InvalidEncoding),
+    }
+}
+
+fn skip_system_operation(reader: &mut Reader) -> Result<()> {
+    match reader.variant()? {
+        // Transfer
+        0 => {
+            reader.option(crypto_hash)?;
+            skip_recipient(reader)?;
+            reader.skip_amount()?;
+            skip_user_data(reader)
+        }
+        // Claim
+        1 => {
+            crypto_hash(reader)?;
+            chain_id(reader)?;
+            skip_recipient(reader)?;
+            reader.skip_amount()?;
+            skip_user_data(reader)
+        }
+        // OpenChain
+        2 => {
+            skip_chain_ownership(reader)?;
+            chain_id(reader)?;
+            reader.u32()?;
+            committees(reader)?;
+            Ok(())
+        }
+        // CloseChain
+        3 => {
+            reader.option(skip_recipient)?;
+            Ok(())
+        }
+        // ChangeOwner
+        4 => {
+            public_key(reader)?;
+            Ok(())
+        }
+        // ChangeMultipleOwners
+        5 => {
+            reader.seq(|reader| {
+                public_key(reader)?;
+                reader.u64()
+            })?;
+            reader.u32()?;
+            reader.option(skip_approval_threshold)?;
+            skip_timeout_config(reader)
+        }
+        // Subscribe, Unsubscribe
+        6 | 7 => {
+            chain_id(reader)?;
+            skip_unit_variant(reader, 2)
+        }
+        // ChangeInboxPolicy
+        8 => skip_inbox_policy(reader),
+        // PublishBytecode
+        9 => {
+            reader.skip_bytes()?;
+            reader.skip_bytes()
+        }
+        // CreateApplication
+        10 => {
+            skip_message_id(reader)?;
+            reader.skip_bytes()?;
+            reader.skip_bytes()?;
+            reader.seq(skip_application_id)?;
+            reader.option(Reader::u64)?;
+            Ok(())
+        }
+        // RequestApplication
+        11 => {
+            chain_id(reader)?;
+            skip_application_id(reader)
+        }
+        // Admin
+        12 => skip_admin_operation(reader),
+        _ => Err(LightClientError::InvalidEncoding),
+    }
+}
+
+fn skip_admin_operation(reader: &mut Reader) -> Result<()> {
+    match reader.variant()? {
+        // CreateCommittee
+        0 => {
+            reader.u32()?;
+            committee(reader)?;
+        }
+        // RemoveCommittee
+        1 => {
+            reader.u32()?;
+        }
+        // ChangeValidators
+        2 => {
+            reader.seq(skip_validator_change)?;
+            reader.u64()?;
+        }
+        _ => return Err(LightClientError::InvalidEncoding),
+    }
+    Ok(())
+}
+
+fn skip_validator_change(reader: &mut Reader) -> Result<()> {
+    match reader.variant()? {
+        // Join
+        0 => {
+            public_key(reader)?;
+            reader.skip_bytes()?;
+            reader.u64()?;
+        }
+        // ChangeVotes
+        1 => {
+            public_key(reader)?;
+            reader.u64()?;
+        }
+        // RotateKey
+        2 => {
+            public_key(reader)?;
+            public_key(reader)?;
+        }
+        // Exit
+        3 => {
+            public_key(reader)?;
+        }
+        _ => return Err(LightClientError::InvalidEncoding),
+    }
+    Ok(())
+}
+
+fn skip_resource_control_policy(reader: &mut Reader) -> Result<()> {
+    // The prices of certificates, fuel, storage reads, bytes read and bytes written.
+    for _ in 0..5 {
+        reader.skip_amount()?;
+    }
+    // The maximum bytes read and written per block.
+    reader.u64()?;
+    reader.u64()?;
+    // The price of messages.
+    reader.skip_amount()?;
+    // The maximum Wasm memory pages, call depth, live sessions and the message expiry.
+    for _ in 0..4 {
+        reader.u64()?;
+    }
+    Ok(())
+}
+
+fn skip_chain_ownership(reader: &mut Reader) -> Result<()> {
+    match reader.variant()? {
+        // None
+        0 => Ok(()),
+        // Single
+        1 => {
+            crypto_hash(reader)?;
+            public_key(reader)?;
+            Ok(())
+        }
+        // Multi
+        2 => {
+            reader.seq(|reader| {
+                crypto_hash(reader)?;
+                public_key(reader)?;
+                reader.u64()
+            })?;
+            reader.u32()?;
+            reader.option(skip_approval_threshold)?;
+            skip_timeout_config(reader)
+        }
+        _ => Err(LightClientError::InvalidEncoding),
+    }
+}
+
+fn skip_approval_threshold(reader: &mut Reader) -> Result<()> {
+    reader.u64()?;
+    skip_unit_variant(reader, 2)
+}
+
+fn skip_timeout_config(reader: &mut Reader) -> Result<()> {
+    reader.u64()?;
+    match reader.variant()? {
+        // Linear
+        0 => {}
+        // Exponential
+        1 => {
+            reader.u32()?;
+        }
+        _ => return Err(LightClientError::InvalidEncoding),
+    }
+    reader.u64()?;
+    Ok(())
+}
+
+fn skip_inbox_policy(reader: &mut Reader) -> Result<()> {
+    reader.option(|reader| reader.seq(chain_id))?;
+    reader.seq(chain_id)?;
+    reader.option(|reader| reader.seq(skip_generic_application_id))?;
+    reader.seq(skip_generic_application_id)?;
+    reader.option(Reader::u64)?;
+    reader.skip_amount()
+}
+
+fn skip_medium(reader: &mut Reader) -> Result<()> {
+    match reader.variant()? {
+        // Direct
+        0 => Ok(()),
+        // Channel
+        1 => {
+            skip_generic_application_id(reader)?;
+            reader.skip_bytes()
+        }
+        _ => Err(LightClientError::InvalidEncoding),
+    }
+}
+
+fn skip_destination(reader: &mut Reader) -> Result<()> {
+    match reader.variant()? {
+        // Recipient
+        0 => {
+            chain_id(reader)?;
+            Ok(())
+        }
+        // Subscribers
+        1 => reader.skip_bytes(),
+        _ => Err(LightClientError::InvalidEncoding),
+    }
+}
+
+fn skip_message_kind(reader: &mut Reader) -> Result<()> {
+    match reader.variant()? {
+        // Simple, Tracked, Bounce, Acknowledged
+        0..=3 => Ok(()),
+        // Acknowledgement
+        4 => match reader.variant()? {
+            // Executed, Rejected
+            0 | 2 => Ok(()),
+            // Failed
+            1 => {
+                reader.u32()?;
+                Ok(())
+            }
+            _ => Err(LightClientError::InvalidEncoding),
+        },
+        _ => Err(LightClientError::InvalidEncoding),
+    }
+}
+
+fn skip_account(reader: &mut Reader) -> Result<()> {
+    chain_id(reader)?;
+    reader.option(crypto_hash)?;
+    Ok(())
+}
+
+fn skip_recipient(reader: &mut Reader) -> Result<()> {
+    match reader.variant()? {
+        // Burn
+        0 => Ok(()),
+        // Account
+        1 => skip_account(reader),
+        _ => Err(LightClientError::InvalidEncoding),
+    }
+}
+
+fn skip_user_data(reader: &mut Reader) -> Result<()> {
+    reader.option(Reader::array::<32>)?;
+    Ok(())
+}
+
+fn skip_message_id(reader: &mut Reader) -> Result<()> {
+    chain_id(reader)?;
+    reader.u64()?;
+    reader.u32()?;
+    Ok(())
+}
+
+fn skip_application_id(reader: &mut Reader) -> Result<()> {
+    // The bytecode ID, then the creation.
+    skip_message_id(reader)?;
+    skip_message_id(reader)
+}
+
+fn skip_generic_application_id(reader: &mut Reader) -> Result<()> {
+    match reader.variant()? {
+        // System
+        0 => Ok(()),
+        // User
+        1 => skip_application_id(reader),
+        _ => Err(LightClientError::InvalidEncoding),
+    }
+}
+
+fn skip_bytecode_location(reader: &mut Reader) -> Result<()> {
+    crypto_hash(reader)?;
+    reader.u32()?;
+    Ok(())
+}
+
+fn skip_user_application_description(reader: &mut Reader) -> Result<()> {
+    skip_message_id(reader)?;
+    skip_bytecode_location(reader)?;
+    skip_message_id(reader)?;
+    reader.skip_bytes()?;
+    reader.seq(skip_application_id)?;
+    reader.option(Reader::u64)?;
+    Ok(())
+}
+
+fn skip_application_event(reader: &mut Reader) -> Result<()> {
+    skip_generic_application_id(reader)?;
+    reader.u32()?;
+    reader.skip_bytes()?;
+    reader.skip_bytes()
+}
+
+fn skip_failed_operation(reader: &mut Reader) -> Result<()> {
+    reader.u32()?;
+    reader.skip_bytes()?;
+    reader.skip_amount()
+}
+
+/// Skips an enum whose variants have no fields.
+fn skip_unit_variant(reader: &mut Reader, variant_count: u32) -> Result<()> {
+    if reader.variant()? < variant_count {
+        Ok(())
+    } else {
+        Err(LightClientError::InvalidEncoding)
+    }
+}
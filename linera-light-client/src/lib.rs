@@ -0,0 +1,305 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A light client that verifies certificates without access to the storage or to the state
+//! of the chains.
+//!
+//! It only keeps the committees announced by the admin chain and the tips of the chains
+//! that it follows. It is `no_std` and only needs an allocator, performs no I/O and does not
+//! need an async runtime, so that it can also be compiled to Wasm, e.g. to be used in
+//! browsers.
+//!
+//! Certificates are given in the binary (BCS) encoding used by validators. The light client
+//! computes the hash of the certified value from the encoded block itself, and reads the
+//! height, the previous block and the `SetCommittees` messages of the block from it, so
+//! that all of them are covered by the validators' signatures.
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod crypto;
+mod data_types;
+mod decoder;
+
+pub use crate::{
+    crypto::{CryptoHash, PublicKey, Signature},
+    data_types::{
+        BlockCertificate, BlockHeight, ChainId, ChainTip, Committee, CommitteeUpdate, Epoch,
+        LiteCertificate, RoundNumber, Timestamp,
+    },
+};
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+use core::fmt;
+
+#[cfg(test)]
+#[path = "unit_tests/light_client_tests.rs"]
+mod light_client_tests;
+
+/// Returns early with an error if the condition is not met.
+macro_rules! ensure {
+    ($cond:expr, $e:expr) => {
+        if !($cond) {
+            return Err($e);
+        }
+    };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LightClientError {
+    InvalidEncoding,
+    NotAConfirmedBlock,
+    InvalidSignature,
+    UnknownEpoch(Epoch),
+    UnknownChain(ChainId),
+    CertificateValidatorReuse,
+    InvalidSigner,
+    CertificateRequiresQuorum,
+    UnexpectedBlockHeight {
+        expected_block_height: BlockHeight,
+        found_block_height: BlockHeight,
+    },
+    UnexpectedPreviousBlockHash,
+    BlockHeightOverflow,
+}
+
+impl fmt::Display for LightClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LightClientError::InvalidEncoding => {
+                write!(f, "The certificate is not a valid BCS encoding")
+            }
+            LightClientError::NotAConfirmedBlock => {
+                write!(f, "The certificate does not certify a confirmed block")
+            }
+            LightClientError::InvalidSignature => {
+                write!(f, "A signature of the certificate is not valid")
+            }
+            LightClientError::UnknownEpoch(epoch) => {
+                write!(f, "No trusted committee for epoch {}", epoch)
+            }
+            LightClientError::UnknownChain(chain_id) => {
+                write!(f, "Chain {} is not followed by the light client", chain_id)
+            }
+            LightClientError::CertificateValidatorReuse => write!(
+                f,
+                "Signatures in a certificate must be from different validators"
+            ),
+            LightClientError::InvalidSigner => {
+                write!(f, "The signature was not created by a valid entity")
+            }
+            LightClientError::CertificateRequiresQuorum => {
+                write!(f, "Signatures in a certificate must form a quorum")
+            }
+            LightClientError::UnexpectedBlockHeight {
+                expected_block_height,
+                found_block_height,
+            } => write!(
+                f,
+                "Was expecting block height {} but found {} instead",
+                expected_block_height, found_block_height
+            ),
+            LightClientError::UnexpectedPreviousBlockHash => write!(
+                f,
+                "The previous block hash of a new block should match the last block of the chain"
+            ),
+            LightClientError::BlockHeightOverflow => {
+                write!(f, "The block height of the chain overflowed")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LightClientError {}
+
+/// Tracks the committees from the blocks of the admin chain, and verifies the certificates
+/// and the chaining of the blocks of the chains it follows.
+#[derive(Debug, Clone)]
+pub struct LightClient {
+    /// The admin chain, whose `SetCommittees` messages define the trusted committees.
+    admin_id: ChainId,
+    /// The current epoch.
+    epoch: Epoch,
+    /// The trusted committees, indexed by epoch.
+    committees: BTreeMap<Epoch, Committee>,
+    /// The times after which the committees of past epochs are no longer trusted.
+    expirations: BTreeMap<Epoch, Timestamp>,
+    /// The tips of the followed chains, including the admin chain.
+    tips: BTreeMap<ChainId, ChainTip>,
+}
+
+impl LightClient {
+    /// Creates a light client trusting the given committees, e.g. from the genesis
+    /// configuration, and following the admin chain from the given tip.
+    pub fn new(
+        admin_id: ChainId,
+        admin_tip: ChainTip,
+        epoch: Epoch,
+        committees: BTreeMap<Epoch, Committee>,
+    ) -> Self {
+        LightClient {
+            admin_id,
+            epoch,
+            committees,
+            expirations: BTreeMap::new(),
+            tips: BTreeMap::from([(admin_id, admin_tip)]),
+        }
+    }
+
+    pub fn admin_id(&self) -> ChainId {
+        self.admin_id
+    }
+
+    pub fn epoch(&self) -> Epoch {
+        self.epoch
+    }
+
+    pub fn committees(&self) -> &BTreeMap<Epoch, Committee> {
+        &self.committees
+    }
+
+    /// Returns the last verified block of the chain, if the chain is followed.
+    pub fn tip(&self, chain_id: ChainId) -> Option<&ChainTip> {
+        self.tips.get(&chain_id)
+    }
+
+    /// Starts following a chain from the given tip, e.g. `ChainTip::default()` to verify
+    /// all its blocks.
+    pub fn follow_chain(&mut self, chain_id: ChainId, tip: ChainTip) {
+        self.tips.insert(chain_id, tip);
+    }
+
+    /// Returns the trusted committee of the given epoch.
+    pub fn committee(&self, epoch: Epoch) -> Result<&Committee, LightClientError> {
+        self.committees
+            .get(&epoch)
+            .ok_or(LightClientError::UnknownEpoch(epoch))
+    }
+
+    /// Verifies that distinct validators forming a quorum of the committee of the given
+    /// epoch signed the value hash and round, e.g. those of a lite certificate.
+    pub fn check_signatures(
+        &self,
+        epoch: Epoch,
+        value_hash: CryptoHash,
+        round: RoundNumber,
+        signatures: &[(PublicKey, Signature)],
+    ) -> Result<(), LightClientError> {
+        let committee = self.committee(epoch)?;
+        let mut weight = 0;
+        let mut used_validators = BTreeSet::new();
+        for (validator, _) in signatures {
+            ensure!(
+                used_validators.insert(*validator),
+                LightClientError::CertificateValidatorReuse
+            );
+            let voting_rights = committee.weight(validator);
+            ensure!(voting_rights > 0, LightClientError::InvalidSigner);
+            weight += voting_rights;
+        }
+        ensure!(
+            weight >= committee.quorum_threshold(),
+            LightClientError::CertificateRequiresQuorum
+        );
+        // The signed value is a `ValueHashAndRound`, prefixed with its type name like all
+        // the values signed in the Linera protocol.
+        let mut message = Vec::from(&b"ValueHashAndRound::"[..]);
+        message.extend_from_slice(value_hash.as_bytes());
+        message.extend_from_slice(&round.0.to_le_bytes());
+        for (validator, signature) in signatures {
+            signature.check(&message, validator)?;
+        }
+        Ok(())
+    }
+
+    /// Verifies the signatures of a certificate, using the committee of its epoch.
+    pub fn check_certificate(
+        &self,
+        certificate: &BlockCertificate,
+    ) -> Result<(), LightClientError> {
+        self.check_signatures(
+            certificate.epoch(),
+            certificate.value_hash(),
+            certificate.round(),
+            certificate.signatures(),
+        )
+    }
+
+    /// Verifies the signatures of a lite certificate, using the committee of the given
+    /// epoch.
+    pub fn check_lite_certificate(
+        &self,
+        epoch: Epoch,
+        certificate: &LiteCertificate,
+    ) -> Result<(), LightClientError> {
+        self.check_signatures(
+            epoch,
+            certificate.value_hash,
+            certificate.round,
+            &certificate.signatures,
+        )
+    }
+
+    /// Verifies the certificate of the next confirmed block of a followed chain, and makes
+    /// it the new tip of the chain. The blocks of the admin chain also update the trusted
+    /// committees.
+    pub fn handle_confirmed_block(
+        &mut self,
+        certificate: &BlockCertificate,
+    ) -> Result<(), LightClientError> {
+        self.check_certificate(certificate)?;
+        let chain_id = certificate.chain_id();
+        let height = certificate.height();
+        let tip = self
+            .tips
+            .get_mut(&chain_id)
+            .ok_or(LightClientError::UnknownChain(chain_id))?;
+        ensure!(
+            height == tip.next_block_height,
+            LightClientError::UnexpectedBlockHeight {
+                expected_block_height: tip.next_block_height,
+                found_block_height: height,
+            }
+        );
+        ensure!(
+            certificate.previous_block_hash() == tip.block_hash,
+            LightClientError::UnexpectedPreviousBlockHash
+        );
+        let next_height = height
+            .0
+            .checked_add(1)
+            .ok_or(LightClientError::BlockHeightOverflow)?;
+        tip.next_block_height = BlockHeight(next_height);
+        tip.block_hash = Some(certificate.value_hash());
+        if chain_id != self.admin_id {
+            return Ok(());
+        }
+        if let Some(update) = certificate.committee_update() {
+            self.epoch = update.epoch;
+            self.committees = update.committees.clone();
+            self.expirations = update.expirations.clone();
+        }
+        self.remove_expired_committees(certificate.timestamp());
+        Ok(())
+    }
+
+    /// Removes the committees of past epochs whose grace period is over at the given time.
+    fn remove_expired_committees(&mut self, timestamp: Timestamp) {
+        let current_epoch = self.epoch;
+        let committees = &mut self.committees;
+        self.expirations.retain(|epoch, expiration| {
+            if *expiration > timestamp || *epoch == current_epoch {
+                return true;
+            }
+            committees.remove(epoch);
+            false
+        });
+    }
+}
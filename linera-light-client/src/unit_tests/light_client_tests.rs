@@ -0,0 +1,137 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::decoder::Reader;
+use alloc::vec;
+use linera_base::{
+    crypto::{self as base, KeyPair, SignatureScheme},
+    data_types as base_data_types, identifiers as base_identifiers,
+};
+
+fn public_key(key_pair: &KeyPair) -> PublicKey {
+    let bytes = bcs::to_bytes(&key_pair.public()).unwrap();
+    decoder::public_key(&mut Reader::new(&bytes)).unwrap()
+}
+
+fn make_committee(key_pairs: &[&KeyPair]) -> Committee {
+    Committee::new(
+        key_pairs
+            .iter()
+            .map(|key_pair| (public_key(key_pair), 1))
+            .collect(),
+    )
+}
+
+/// Returns the BCS encoding of a `LiteCertificate` signed by the given key-pairs.
+fn make_lite_certificate(value_hash: base::CryptoHash, key_pairs: &[&KeyPair]) -> Vec<u8> {
+    let round = base_data_types::RoundNumber(0);
+    let hash_and_round = base_data_types::ValueHashAndRound(value_hash, round);
+    let signatures = key_pairs
+        .iter()
+        .map(|key_pair| {
+            let signature = base::Signature::new(&hash_and_round, key_pair);
+            (key_pair.public(), signature)
+        })
+        .collect::<Vec<_>>();
+    let chain_id = base_identifiers::ChainId::root(1);
+    bcs::to_bytes(&(value_hash, chain_id, round, signatures)).unwrap()
+}
+
+#[test]
+fn test_lite_certificates() {
+    let key1 = KeyPair::generate();
+    let key2 = KeyPair::generate_with_scheme(SignatureScheme::Secp256k1);
+    let key3 = KeyPair::generate();
+    let committee = make_committee(&[&key1, &key2]);
+    let admin_id = ChainId(CryptoHash::from([0; 32]));
+    let client = LightClient::new(
+        admin_id,
+        ChainTip::default(),
+        Epoch(0),
+        BTreeMap::from([(Epoch(0), committee)]),
+    );
+    let value_hash = base::CryptoHash::new(&key1.public());
+    let other_hash = base::CryptoHash::new(&key2.public());
+
+    let certificate =
+        LiteCertificate::from_bcs(&make_lite_certificate(value_hash, &[&key1, &key2])).unwrap();
+    assert_eq!(
+        certificate.value_hash.as_bytes()[..],
+        value_hash.as_bytes()[..]
+    );
+    client
+        .check_lite_certificate(Epoch(0), &certificate)
+        .unwrap();
+    assert_eq!(
+        client.check_lite_certificate(Epoch(1), &certificate),
+        Err(LightClientError::UnknownEpoch(Epoch(1)))
+    );
+
+    // A certificate without a quorum is rejected.
+    let certificate =
+        LiteCertificate::from_bcs(&make_lite_certificate(value_hash, &[&key1])).unwrap();
+    assert_eq!(
+        client.check_lite_certificate(Epoch(0), &certificate),
+        Err(LightClientError::CertificateRequiresQuorum)
+    );
+
+    // Signatures must be from distinct validators of the committee.
+    let mut certificate =
+        LiteCertificate::from_bcs(&make_lite_certificate(value_hash, &[&key1, &key2])).unwrap();
+    certificate.signatures[1] = certificate.signatures[0];
+    assert_eq!(
+        client.check_lite_certificate(Epoch(0), &certificate),
+        Err(LightClientError::CertificateValidatorReuse)
+    );
+    let certificate =
+        LiteCertificate::from_bcs(&make_lite_certificate(value_hash, &[&key1, &key3])).unwrap();
+    assert_eq!(
+        client.check_lite_certificate(Epoch(0), &certificate),
+        Err(LightClientError::InvalidSigner)
+    );
+
+    // Signatures on another value are rejected, for both signature schemes.
+    for index in 0..2 {
+        let mut certificate =
+            LiteCertificate::from_bcs(&make_lite_certificate(value_hash, &[&key1, &key2])).unwrap();
+        let other_certificate =
+            LiteCertificate::from_bcs(&make_lite_certificate(other_hash, &[&key1, &key2])).unwrap();
+        certificate.signatures[index] = other_certificate.signatures[index];
+        assert_eq!(
+            client.check_lite_certificate(Epoch(0), &certificate),
+            Err(LightClientError::InvalidSignature)
+        );
+    }
+}
+
+#[test]
+fn test_invalid_encodings() {
+    let key = KeyPair::generate();
+    let bytes = make_lite_certificate(base::CryptoHash::new(&key.public()), &[&key]);
+    assert!(LiteCertificate::from_bcs(&bytes).is_ok());
+    assert_eq!(
+        LiteCertificate::from_bcs(&bytes[..bytes.len() - 1]),
+        Err(LightClientError::InvalidEncoding)
+    );
+    let mut extended_bytes = bytes.clone();
+    extended_bytes.push(0);
+    assert_eq!(
+        LiteCertificate::from_bcs(&extended_bytes),
+        Err(LightClientError::InvalidEncoding)
+    );
+
+    // Only certificates for confirmed blocks are accepted, e.g. not for leader timeouts.
+    let chain_id = base_identifiers::ChainId::root(0);
+    let mut timeout = vec![2];
+    timeout.extend(bcs::to_bytes(&(chain_id, 0u64, 0u32, 0u32)).unwrap());
+    timeout.push(0);
+    assert_eq!(
+        BlockCertificate::from_bcs(&timeout),
+        Err(LightClientError::NotAConfirmedBlock)
+    );
+    assert_eq!(
+        BlockCertificate::from_bcs(&[3]),
+        Err(LightClientError::InvalidEncoding)
+    );
+}
@@ -5,15 +5,20 @@
 // Needed for arg_enum!
 #![allow(clippy::useless_vec)]
 
-use linera_base::identifiers::{ChainDescription, Destination};
+use linera_base::{
+    crypto::{PublicKey, Signature},
+    identifiers::{ChainDescription, Destination},
+};
 use linera_chain::{
-    data_types::{CertificateValue, HashedValue, Medium},
+    data_types::{CertificateValue, HashedValue, Medium, MessageAction},
     ChainManagerInfo,
 };
 use linera_core::{data_types::CrossChainRequest, node::NodeError};
 use linera_execution::{
+    committee::ValidatorChange,
     system::{AdminOperation, Recipient, SystemChannel, SystemMessage, SystemOperation},
-    ChainOwnership, GenericApplicationId, Message, Operation,
+    ApprovalScope, ChainOwnership, GenericApplicationId, Message, MessageKind, MessageOutcome,
+    Operation,
 };
 use linera_rpc::RpcMessage;
 use serde_reflection::{Registry, Result, Samples, Tracer, TracerConfig};
@@ -26,22 +31,31 @@ fn get_registry() -> Result<Registry> {
             .record_samples_for_newtype_structs(true)
             .record_samples_for_tuple_structs(true),
     );
-    let samples = Samples::new();
+    let mut samples = Samples::new();
     // 1. Record samples for types with custom deserializers.
+    let public_key = PublicKey::try_from(&[0u8; 32][..]).expect("Invalid Ed25519 public key");
+    let signature = Signature::try_from(&[0u8; 64][..]).expect("Invalid Ed25519 signature");
+    tracer.trace_value(&mut samples, &public_key)?;
+    tracer.trace_value(&mut samples, &signature)?;
     // 2. Trace the main entry point(s) + every enum separately.
     tracer.trace_type::<Recipient>(&samples)?;
     tracer.trace_type::<SystemChannel>(&samples)?;
     tracer.trace_type::<SystemOperation>(&samples)?;
     tracer.trace_type::<AdminOperation>(&samples)?;
+    tracer.trace_type::<ValidatorChange>(&samples)?;
     tracer.trace_type::<SystemMessage>(&samples)?;
     tracer.trace_type::<Operation>(&samples)?;
     tracer.trace_type::<Message>(&samples)?;
+    tracer.trace_type::<MessageKind>(&samples)?;
+    tracer.trace_type::<MessageOutcome>(&samples)?;
     tracer.trace_type::<HashedValue>(&samples)?;
     tracer.trace_type::<CertificateValue>(&samples)?;
     tracer.trace_type::<Medium>(&samples)?;
+    tracer.trace_type::<MessageAction>(&samples)?;
     tracer.trace_type::<Destination>(&samples)?;
     tracer.trace_type::<ChainDescription>(&samples)?;
     tracer.trace_type::<ChainOwnership>(&samples)?;
+    tracer.trace_type::<ApprovalScope>(&samples)?;
     tracer.trace_type::<GenericApplicationId>(&samples)?;
     tracer.trace_type::<ChainManagerInfo>(&samples)?;
     tracer.trace_type::<CrossChainRequest>(&samples)?;
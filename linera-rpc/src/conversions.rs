@@ -5,7 +5,6 @@ use crate::{
     grpc_network::{grpc, grpc::ChainInfoResult},
     HandleCertificateRequest, HandleLiteCertificateRequest,
 };
-use ed25519::signature::Signature as edSignature;
 use linera_base::{
     crypto::{CryptoError, CryptoHash, PublicKey, Signature},
     data_types::{BlockHeight, RoundNumber},
@@ -30,8 +29,6 @@ pub enum ProtoConversionError {
     BincodeError(#[from] bincode::Error),
     #[error("Conversion failed due to missing field")]
     MissingField,
-    #[error("Signature error: {0}")]
-    SignatureError(#[from] ed25519_dalek::SignatureError),
     #[error("Cryptographic error: {0}")]
     CryptoError(#[from] CryptoError),
     #[error("Inconsistent outer/inner chain ids")]
@@ -110,6 +107,7 @@ impl TryFrom<BlockProposal> for grpc::BlockProposal {
                 .validated
                 .map(|cert| bincode::serialize(&cert))
                 .transpose()?,
+            approvals: bincode::serialize(&block_proposal.approvals)?,
         })
     }
 }
@@ -132,6 +130,7 @@ impl TryFrom<grpc::BlockProposal> for BlockProposal {
                 .validated
                 .map(|bytes| bincode::deserialize(&bytes))
                 .transpose()?,
+            approvals: bincode::deserialize(&block_proposal.approvals)?,
         })
     }
 }
@@ -279,6 +278,10 @@ impl TryFrom<grpc::ChainInfoQuery> for ChainInfoQuery {
             .request_blob
             .map(|bytes| bincode::deserialize(&bytes))
             .transpose()?;
+        let request_checkpoint_chunk = chain_info_query
+            .request_checkpoint_chunk
+            .map(|bytes| bincode::deserialize(&bytes))
+            .transpose()?;
 
         Ok(Self {
             request_committees: chain_info_query.request_committees,
@@ -291,6 +294,8 @@ impl TryFrom<grpc::ChainInfoQuery> for ChainInfoQuery {
             request_manager_values: chain_info_query.request_manager_values,
             request_leader_timeout: chain_info_query.request_leader_timeout,
             request_blob,
+            request_checkpoint: chain_info_query.request_checkpoint,
+            request_checkpoint_chunk,
         })
     }
 }
@@ -307,6 +312,10 @@ impl TryFrom<ChainInfoQuery> for grpc::ChainInfoQuery {
             .request_blob
             .map(|hash| bincode::serialize(&hash))
             .transpose()?;
+        let request_checkpoint_chunk = chain_info_query
+            .request_checkpoint_chunk
+            .map(|hash| bincode::serialize(&hash))
+            .transpose()?;
 
         Ok(Self {
             chain_id: Some(chain_info_query.chain_id.into()),
@@ -319,6 +328,8 @@ impl TryFrom<ChainInfoQuery> for grpc::ChainInfoQuery {
             request_manager_values: chain_info_query.request_manager_values,
             request_leader_timeout: chain_info_query.request_leader_timeout,
             request_blob,
+            request_checkpoint: chain_info_query.request_checkpoint,
+            request_checkpoint_chunk,
         })
     }
 }
@@ -342,7 +353,7 @@ impl TryFrom<grpc::ChainId> for ChainId {
 impl From<PublicKey> for grpc::PublicKey {
     fn from(public_key: PublicKey) -> Self {
         Self {
-            bytes: public_key.0.to_vec(),
+            bytes: public_key.as_bytes().to_vec(),
         }
     }
 }
@@ -358,7 +369,7 @@ impl TryFrom<grpc::PublicKey> for PublicKey {
 impl From<ValidatorName> for grpc::PublicKey {
     fn from(validator_name: ValidatorName) -> Self {
         Self {
-            bytes: validator_name.0 .0.to_vec(),
+            bytes: validator_name.0.as_bytes().to_vec(),
         }
     }
 }
@@ -374,7 +385,7 @@ impl TryFrom<grpc::PublicKey> for ValidatorName {
 impl From<Signature> for grpc::Signature {
     fn from(signature: Signature) -> Self {
         Self {
-            bytes: signature.0.as_bytes().to_vec(),
+            bytes: signature.to_bytes(),
         }
     }
 }
@@ -383,9 +394,7 @@ impl TryFrom<grpc::Signature> for Signature {
     type Error = ProtoConversionError;
 
     fn try_from(signature: grpc::Signature) -> Result<Self, Self::Error> {
-        Ok(Self(ed25519_dalek::Signature::from_bytes(
-            &signature.bytes,
-        )?))
+        Ok(Signature::try_from(signature.bytes.as_slice())?)
     }
 }
 
@@ -447,7 +456,7 @@ impl TryFrom<grpc::Owner> for Owner {
 pub mod tests {
     use super::*;
     use linera_base::{
-        crypto::{BcsSignable, CryptoHash, KeyPair},
+        crypto::{BcsSignable, CryptoHash, KeyPair, SignatureScheme},
         data_types::{Amount, Timestamp},
     };
     use linera_chain::{
@@ -485,6 +494,8 @@ pub mod tests {
     pub fn test_public_key() {
         let public_key = KeyPair::generate().public();
         round_trip_check::<_, grpc::PublicKey>(public_key);
+        let public_key = KeyPair::generate_with_scheme(SignatureScheme::Secp256k1).public();
+        round_trip_check::<_, grpc::PublicKey>(public_key);
     }
 
     #[test]
@@ -492,6 +503,9 @@ pub mod tests {
         let key_pair = KeyPair::generate();
         let signature = Signature::new(&Foo("test".into()), &key_pair);
         round_trip_check::<_, grpc::Signature>(signature);
+        let key_pair = KeyPair::generate_with_scheme(SignatureScheme::Secp256k1);
+        let signature = Signature::new(&Foo("test".into()), &key_pair);
+        round_trip_check::<_, grpc::Signature>(signature);
     }
 
     #[test]
@@ -539,6 +553,8 @@ pub mod tests {
             count_received_log: 0,
             requested_received_log: vec![],
             requested_blob: None,
+            requested_checkpoint: None,
+            requested_checkpoint_chunk: None,
         };
 
         let chain_info_response_none = ChainInfoResponse {
@@ -574,6 +590,8 @@ pub mod tests {
             request_manager_values: false,
             request_leader_timeout: false,
             request_blob: None,
+            request_checkpoint: true,
+            request_checkpoint_chunk: Some(CryptoHash::new(&Foo("chunk".into()))),
         };
         round_trip_check::<_, grpc::ChainInfoQuery>(chain_info_query_some);
     }
@@ -608,6 +626,8 @@ pub mod tests {
                 block: get_block(),
                 messages: vec![],
                 message_counts: vec![],
+                events: vec![],
+                failed_operations: vec![],
                 state_hash: CryptoHash::new(&Foo("test".into())),
             }),
             RoundNumber(3),
@@ -620,6 +640,8 @@ pub mod tests {
             block: get_block(),
             messages: vec![],
             message_counts: vec![],
+            events: vec![],
+            failed_operations: vec![],
             state_hash: CryptoHash::new(&Foo("also test".into())),
         })];
         let request = HandleCertificateRequest {
@@ -669,6 +691,8 @@ pub mod tests {
                 block: get_block(),
                 messages: vec![],
                 message_counts: vec![],
+                events: vec![],
+                failed_operations: vec![],
                 state_hash: CryptoHash::new(&Foo("execution state".into())),
             })],
             validated: Some(Certificate::new(
@@ -676,6 +700,8 @@ pub mod tests {
                     block: get_block(),
                     messages: vec![],
                     message_counts: vec![],
+                    events: vec![],
+                    failed_operations: vec![],
                     state_hash: CryptoHash::new(&Foo("validated".into())),
                 }),
                 RoundNumber(3),
@@ -684,6 +710,10 @@ pub mod tests {
                     Signature::new(&Foo("signed".into()), &key_pair),
                 )],
             )),
+            approvals: vec![(
+                Owner::from(key_pair.public()),
+                Signature::new(&Foo("approved".into()), &key_pair),
+            )],
         };
 
         round_trip_check::<_, grpc::BlockProposal>(block_proposal);
@@ -0,0 +1,122 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Profiling of the fuel consumed by the functions called by the tests.
+
+use crate::mock_system_api::Resources;
+use anyhow::{Context, Result};
+use linera_execution::{
+    instrument_for_profiling,
+    profiler::{
+        FuelProfiler, InstanceProfiler, ModuleProfiler, PROFILER_ENTER_FUNCTION,
+        PROFILER_EXIT_FUNCTION, PROFILER_MODULE,
+    },
+    Bytecode,
+};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+};
+use wasmtime::{Caller, Engine, Linker, Module, Store};
+
+/// The fuel given to each test, which is large enough to never run out.
+const TEST_FUEL: u64 = 1 << 62;
+
+/// A profiler of the fuel consumed by the tests of an instrumented test module.
+pub struct TestProfiler {
+    profiler: FuelProfiler,
+    module_profiler: ModuleProfiler,
+    instance_profiler: Arc<Mutex<Option<InstanceProfiler>>>,
+    output_path: PathBuf,
+}
+
+impl TestProfiler {
+    /// Loads the test module in `module_path`, instrumented so that the fuel consumed by the
+    /// tests is written to `output_path`.
+    pub async fn load_test_module(
+        module_path: &Path,
+        engine: &Engine,
+        output_path: PathBuf,
+    ) -> Result<(Self, Module)> {
+        let bytecode = Bytecode::load_from_file(module_path).await?;
+        let (bytecode, function_names) = instrument_for_profiling(bytecode)?;
+        let module = Module::new(engine, bytecode)?;
+        let profiler = FuelProfiler::default();
+        let test_profiler = TestProfiler {
+            module_profiler: ModuleProfiler::new(profiler.clone(), function_names),
+            profiler,
+            instance_profiler: Arc::default(),
+            output_path,
+        };
+
+        Ok((test_profiler, module))
+    }
+
+    /// Adds the functions called by the instrumented test module to the `linker`.
+    pub fn add_to_linker(&self, linker: &mut Linker<Resources>) -> Result<()> {
+        let instance_profiler = self.instance_profiler.clone();
+        linker.func_wrap(
+            PROFILER_MODULE,
+            PROFILER_ENTER_FUNCTION,
+            move |caller: Caller<'_, Resources>, function: u32| {
+                let fuel_consumed = caller.fuel_consumed().unwrap_or(0);
+                if let Some(profiler) = &mut *lock(&instance_profiler) {
+                    profiler.enter(function, fuel_consumed);
+                }
+            },
+        )?;
+
+        let instance_profiler = self.instance_profiler.clone();
+        linker.func_wrap(
+            PROFILER_MODULE,
+            PROFILER_EXIT_FUNCTION,
+            move |caller: Caller<'_, Resources>, function: u32| {
+                let fuel_consumed = caller.fuel_consumed().unwrap_or(0);
+                if let Some(profiler) = &mut *lock(&instance_profiler) {
+                    profiler.exit(function, fuel_consumed);
+                }
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// Prepares the `store` used to run a test, and starts profiling the test.
+    pub fn start_test(&self, store: &mut Store<Resources>) -> Result<()> {
+        store.add_fuel(TEST_FUEL)?;
+        *lock(&self.instance_profiler) = Some(self.module_profiler.start());
+        Ok(())
+    }
+
+    /// Writes the fuel consumed by each stack of function calls to the output file, in the folded
+    /// stacks format.
+    pub fn write(&self) -> Result<()> {
+        let file = File::create(&self.output_path).with_context(|| {
+            format!(
+                "Failed to create fuel profile file {}",
+                self.output_path.display()
+            )
+        })?;
+        let mut writer = BufWriter::new(file);
+        self.profiler.write_folded_stacks(&mut writer)?;
+        writer.flush()?;
+
+        eprintln!(
+            "fuel profile written to {} ({} fuel in total)",
+            self.output_path.display(),
+            self.profiler.total_fuel()
+        );
+        Ok(())
+    }
+}
+
+/// Locks the profiler of the test being executed.
+fn lock(
+    instance_profiler: &Mutex<Option<InstanceProfiler>>,
+) -> MutexGuard<'_, Option<InstanceProfiler>> {
+    instance_profiler
+        .lock()
+        .expect("profiler should not panic while holding its lock")
+}
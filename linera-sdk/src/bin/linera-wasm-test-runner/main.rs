@@ -22,14 +22,23 @@
 //! runner = "/path/to/linera-wasm-test-runner"
 //! ```
 //!
+//! # Profiling
+//!
+//! Passing `--fuel-profile <FILE>` instruments the test module so that the fuel consumed by the
+//! tests is attributed to the functions that consumed it. The result is written to `FILE` in the
+//! folded stacks format, which can be turned into a flamegraph with tools like
+//! [`inferno-flamegraph`](https://github.com/jonhoo/inferno).
+//!
 //! [webassembly_test]: https://docs.rs/webassembly-test/latest/webassembly_test/
 
 #[cfg(target_arch = "wasm32")]
 compile_error!("The test runner is meant to be compiled for the host target");
 
+mod fuel_profiler;
 mod mock_system_api;
 
 use anyhow::Result;
+use fuel_profiler::TestProfiler;
 use mock_system_api::Resources;
 use std::{
     path::{Path, PathBuf},
@@ -45,6 +54,11 @@ use wasmtime::*;
 )]
 struct Options {
     module_path: PathBuf,
+
+    /// Write the fuel consumed by each stack of function calls to this file, in the folded
+    /// stacks format used by flamegraph tools.
+    #[structopt(long)]
+    fuel_profile: Option<PathBuf>,
 }
 
 /// Loads a test Wasm module and runs all test functions annotated by [`webassembly-test`].
@@ -58,9 +72,18 @@ async fn main() -> Result<ExitCode> {
     let mut engine_config = Config::default();
     engine_config.wasm_backtrace_details(WasmBacktraceDetails::Enable);
     engine_config.async_support(true);
+    engine_config.consume_fuel(options.fuel_profile.is_some());
     let engine = Engine::new(&engine_config)?;
     let mut linker = Linker::new(&engine);
-    let test_module = load_test_module(&options.module_path, &engine)?;
+    let (profiler, test_module) = match options.fuel_profile {
+        Some(output_path) => {
+            let (profiler, test_module) =
+                TestProfiler::load_test_module(&options.module_path, &engine, output_path).await?;
+            profiler.add_to_linker(&mut linker)?;
+            (Some(profiler), test_module)
+        }
+        None => (None, load_test_module(&options.module_path, &engine)?),
+    };
     let tests: Vec<_> = test_module.exports().filter_map(Test::new).collect();
 
     mock_system_api::add_to_linker(&mut linker)?;
@@ -70,7 +93,12 @@ async fn main() -> Result<ExitCode> {
     eprintln!("\nrunning {} tests", tests.len());
 
     for test in tests {
-        test.run(&mut report, &linker, &test_module).await?;
+        test.run(&mut report, &linker, &test_module, profiler.as_ref())
+            .await?;
+    }
+
+    if let Some(profiler) = profiler {
+        profiler.write()?;
     }
 
     Ok(report.print())
@@ -105,12 +133,14 @@ impl<'a> Test<'a> {
 
     /// Runs a test function exported from the Wasm module and reports its result.
     ///
-    /// The test is executed in a clean Wasm environment.
+    /// The test is executed in a clean Wasm environment, and profiled if a `profiler` is
+    /// provided.
     pub async fn run(
         self,
         report: &mut TestReport,
         linker: &Linker<Resources>,
         test_module: &Module,
+        profiler: Option<&TestProfiler>,
     ) -> Result<()> {
         eprint!("test {} ...", self.name);
 
@@ -118,6 +148,9 @@ impl<'a> Test<'a> {
             report.ignore();
         } else {
             let mut store = Store::new(linker.engine(), Resources::default());
+            if let Some(profiler) = profiler {
+                profiler.start_test(&mut store)?;
+            }
             let instance = linker.instantiate_async(&mut store, test_module).await?;
 
             let function = instance.get_typed_func::<(), (), _>(&mut store, self.function)?;
@@ -318,6 +318,35 @@ pub fn add_to_linker(linker: &mut Linker<Resources>) -> Result<()> {
             })
         },
     )?;
+    linker.func_wrap1_async(
+        "contract_system_api",
+        "random-seed: func() -> record { part1: u64, part2: u64, part3: u64, part4: u64 }",
+        move |mut caller: Caller<'_, Resources>, return_offset: i32| {
+            Box::new(async move {
+                let function = get_function(
+                    &mut caller,
+                    "mocked-random-seed: \
+                        func() -> record { part1: u64, part2: u64, part3: u64, part4: u64 }",
+                )
+                .expect(
+                    "Missing `mocked-random-seed` function in the module. \
+                    Please ensure `linera_sdk::test::mock_random_seed` was called",
+                );
+
+                let (result_offset,) = function
+                    .typed::<(), (i32,), _>(&mut caller)
+                    .expect("Incorrect `mocked-random-seed` function signature")
+                    .call_async(&mut caller, ())
+                    .await
+                    .expect(
+                        "Failed to call `mocked-random-seed` function. \
+                        Please ensure `linera_sdk::test::mock_random_seed` was called",
+                    );
+
+                copy_memory_slices(&mut caller, result_offset, return_offset, 32);
+            })
+        },
+    )?;
     linker.func_wrap3_async(
         "contract_system_api",
         "log: func(message: string, level: enum { trace, debug, info, warn, error }) -> unit",
@@ -454,6 +483,61 @@ pub fn add_to_linker(linker: &mut Linker<Resources>) -> Result<()> {
             })
         },
     )?;
+    linker.func_wrap4_async(
+        "contract_system_api",
+        "emit-event: func(topic: list<u8>, value: list<u8>) -> unit",
+        move |mut caller: Caller<'_, Resources>,
+              topic_address: i32,
+              topic_length: i32,
+              value_address: i32,
+              value_length: i32| {
+            Box::new(async move {
+                let function = get_function(
+                    &mut caller,
+                    "mocked-emit-event: func(topic: list<u8>, value: list<u8>) -> unit",
+                )
+                .expect(
+                    "Missing `mocked-emit-event` function in the module. \
+                    Please ensure `linera_sdk` is compiled with the `test` feature enabled",
+                );
+
+                let alloc_function = get_function(&mut caller, "cabi_realloc").expect(
+                    "Missing `cabi_realloc` function in the module. \
+                    Please ensure `linera_sdk` is compiled in with the module",
+                );
+                let alloc_function = alloc_function
+                    .typed::<(i32, i32, i32, i32), i32, _>(&mut caller)
+                    .expect("Incorrect `cabi_realloc` function signature");
+
+                let new_topic_address = alloc_function
+                    .call_async(&mut caller, (0, 0, 1, topic_length))
+                    .await
+                    .expect("Failed to call `cabi_realloc` function");
+                copy_memory_slices(&mut caller, topic_address, new_topic_address, topic_length);
+
+                let new_value_address = alloc_function
+                    .call_async(&mut caller, (0, 0, 1, value_length))
+                    .await
+                    .expect("Failed to call `cabi_realloc` function");
+                copy_memory_slices(&mut caller, value_address, new_value_address, value_length);
+
+                function
+                    .typed::<(i32, i32, i32, i32), (), _>(&mut caller)
+                    .expect("Incorrect `mocked-emit-event` function signature")
+                    .call_async(
+                        &mut caller,
+                        (
+                            new_topic_address,
+                            topic_length,
+                            new_value_address,
+                            value_length,
+                        ),
+                    )
+                    .await
+                    .expect("Failed to call `mocked-emit-event` function");
+            })
+        },
+    )?;
 
     linker.func_wrap1_async(
         "service_system_api",
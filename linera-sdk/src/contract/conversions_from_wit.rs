@@ -13,7 +13,7 @@ use linera_base::{
     identifiers::{ApplicationId, BytecodeId, ChainId, MessageId, Owner, SessionId},
 };
 
-use crate::{CalleeContext, MessageContext, OperationContext};
+use crate::{CalleeContext, MessageContext, MessageOutcome, OperationContext};
 use std::task::Poll;
 
 impl From<wit_types::OperationContext> for OperationContext {
@@ -38,6 +38,16 @@ impl From<wit_types::MessageContext> for MessageContext {
     }
 }
 
+impl From<wit_types::MessageOutcome> for MessageOutcome {
+    fn from(outcome: wit_types::MessageOutcome) -> Self {
+        match outcome {
+            wit_types::MessageOutcome::Executed => MessageOutcome::Executed,
+            wit_types::MessageOutcome::Failed(code) => MessageOutcome::Failed(code),
+            wit_types::MessageOutcome::Rejected => MessageOutcome::Rejected,
+        }
+    }
+}
+
 impl From<wit_types::MessageId> for MessageId {
     fn from(message_id: wit_types::MessageId) -> Self {
         MessageId {
@@ -8,6 +8,7 @@ use super::{contract_system_api as wit_system_api, wit_types};
 use crate::{ApplicationCallResult, ExecutionResult, OutgoingMessage, SessionCallResult};
 use linera_base::{
     crypto::CryptoHash,
+    data_types::Amount,
     identifiers::{ApplicationId, ChannelName, Destination, MessageId, SessionId},
 };
 use serde::{de::DeserializeOwned, Serialize};
@@ -136,6 +137,9 @@ where
             destination: message.destination.into(),
             authenticated: message.authenticated,
             is_skippable: message.is_skippable,
+            is_tracked: message.is_tracked,
+            is_acknowledged: message.is_acknowledged,
+            grant: message.grant.into(),
             // TODO(#743): Do we need explicit error handling?
             message: bcs::to_bytes(&message.message).expect("message serialization failed"),
         }
@@ -194,17 +198,26 @@ impl From<ChannelName> for wit_types::ChannelName {
     }
 }
 
-impl<Message> From<Poll<Result<ExecutionResult<Message>, String>>>
+impl From<Amount> for wit_types::Amount {
+    fn from(amount: Amount) -> Self {
+        wit_types::Amount {
+            lower_half: amount.lower_half(),
+            upper_half: amount.upper_half(),
+        }
+    }
+}
+
+impl<Message> From<Poll<Result<ExecutionResult<Message>, wit_types::UserError>>>
     for wit_types::PollExecutionResult
 where
     Message: DeserializeOwned + Serialize + Debug,
 {
-    fn from(poll: Poll<Result<ExecutionResult<Message>, String>>) -> Self {
+    fn from(poll: Poll<Result<ExecutionResult<Message>, wit_types::UserError>>) -> Self {
         use wit_types::PollExecutionResult;
         match poll {
             Poll::Pending => PollExecutionResult::Pending,
             Poll::Ready(Ok(result)) => PollExecutionResult::Ready(Ok(result.into())),
-            Poll::Ready(Err(message)) => PollExecutionResult::Ready(Err(message)),
+            Poll::Ready(Err(error)) => PollExecutionResult::Ready(Err(error)),
         }
     }
 }
@@ -14,7 +14,7 @@ use crate::{
     SessionCallResult, SessionId, SimpleStateStorage, ViewStateStorage,
 };
 use async_trait::async_trait;
-use futures::FutureExt;
+use futures::{FutureExt, TryFutureExt};
 use linera_views::views::RootView;
 use serde::{de::DeserializeOwned, Serialize};
 use std::{future::Future, marker::PhantomData, mem, pin::Pin};
@@ -31,10 +31,10 @@ pub trait ContractStateStorage<Application> {
     /// Executes an `operation` with the `Application` state.
     ///
     /// The state is only stored back in storage if the `operation` succeeds. Otherwise, the error
-    /// is returned as a [`String`].
+    /// is returned.
     async fn execute_with_state<Operation, Success, Error>(
         operation: Operation,
-    ) -> Result<Success, String>
+    ) -> Result<Success, Error>
     where
         Operation: for<'app> FnOnce(
             &'app mut Application,
@@ -44,13 +44,11 @@ pub trait ContractStateStorage<Application> {
         Application: Send,
         Operation: Send,
         Success: Send + 'static,
-        Error: ToString + 'static,
+        Error: Send + 'static,
     {
         let mut application = Self::load_and_lock().await;
 
-        let result = operation(&mut application)
-            .await
-            .map_err(|error| error.to_string());
+        let result = operation(&mut application).await;
 
         if result.is_ok() {
             Self::store_and_unlock(application).await;
@@ -75,7 +73,9 @@ where
     Application: Contract + Default + DeserializeOwned + Serialize + Send + 'static,
 {
     async fn load_and_lock() -> Application {
-        system_api::load_and_lock().expect("Failed to lock contract state")
+        system_api::load_and_lock()
+            .await
+            .expect("Failed to lock contract state")
     }
 
     async fn store_and_unlock(state: Application) {
@@ -127,12 +127,21 @@ where
     }
 }
 
+/// Converts an error returned by the `Application` into the error reported to the host,
+/// together with its code.
+fn user_error<Application: Contract>(error: Application::Error) -> wit_types::UserError {
+    wit_types::UserError {
+        code: Application::error_code(&error),
+        message: error.to_string(),
+    }
+}
+
 /// Future implementation exported from the guest to allow the host to call
 /// [`Contract::initialize`].
 ///
 /// Loads the `Application` state and calls its [`initialize`][Contract::initialize] method.
 pub struct Initialize<Application: Contract> {
-    future: ExportedFuture<Result<ExecutionResult<Application::Message>, String>>,
+    future: ExportedFuture<Result<ExecutionResult<Application::Message>, wit_types::UserError>>,
     _application: PhantomData<Application>,
 }
 
@@ -152,6 +161,7 @@ where
                         let argument = serde_json::from_slice(&bytes)?;
                         application.initialize(&context.into(), argument).await
                     }
+                    .map_err(user_error::<Application>)
                     .boxed()
                 },
             )),
@@ -173,7 +183,7 @@ where
 /// Loads the `Application` state and calls its
 /// [`execute_operation`][Contract::execute_operation] method.
 pub struct ExecuteOperation<Application: Contract> {
-    future: ExportedFuture<Result<ExecutionResult<Application::Message>, String>>,
+    future: ExportedFuture<Result<ExecutionResult<Application::Message>, wit_types::UserError>>,
     _application: PhantomData<Application>,
 }
 
@@ -195,6 +205,7 @@ where
                             .execute_operation(&context.into(), operation)
                             .await
                     }
+                    .map_err(user_error::<Application>)
                     .boxed()
                 },
             )),
@@ -216,7 +227,7 @@ where
 /// Loads the `Application` state and calls its [`execute_message`][Contract::execute_message]
 /// method.
 pub struct ExecuteMessage<Application: Contract> {
-    future: ExportedFuture<Result<ExecutionResult<Application::Message>, String>>,
+    future: ExportedFuture<Result<ExecutionResult<Application::Message>, wit_types::UserError>>,
     _application: PhantomData<Application>,
 }
 
@@ -236,6 +247,99 @@ where
                         let message: Application::Message = bcs::from_bytes(&message)?;
                         application.execute_message(&context.into(), message).await
                     }
+                    .map_err(user_error::<Application>)
+                    .boxed()
+                },
+            )),
+            _application: PhantomData,
+        }
+    }
+
+    /// Polls the future export from the guest.
+    ///
+    /// This is called from the host.
+    pub fn poll(&self) -> wit_types::PollExecutionResult {
+        self.future.poll()
+    }
+}
+
+/// Future implementation exported from the guest to allow the host to call
+/// [`Contract::handle_bounced_message`].
+///
+/// Loads the `Application` state and calls its [`handle_bounced_message`][Contract::handle_bounced_message]
+/// method.
+pub struct HandleBouncedMessage<Application: Contract> {
+    future: ExportedFuture<Result<ExecutionResult<Application::Message>, wit_types::UserError>>,
+    _application: PhantomData<Application>,
+}
+
+impl<Application> HandleBouncedMessage<Application>
+where
+    Application: Contract,
+{
+    /// Creates the exported future that the host can poll.
+    ///
+    /// This is called from the host.
+    pub fn new(context: wit_types::MessageContext, message: Vec<u8>) -> Self {
+        ContractLogger::install();
+        HandleBouncedMessage {
+            future: ExportedFuture::new(Application::Storage::execute_with_state(
+                move |application| {
+                    async move {
+                        let message: Application::Message = bcs::from_bytes(&message)?;
+                        application
+                            .handle_bounced_message(&context.into(), message)
+                            .await
+                    }
+                    .map_err(user_error::<Application>)
+                    .boxed()
+                },
+            )),
+            _application: PhantomData,
+        }
+    }
+
+    /// Polls the future export from the guest.
+    ///
+    /// This is called from the host.
+    pub fn poll(&self) -> wit_types::PollExecutionResult {
+        self.future.poll()
+    }
+}
+
+/// Future implementation exported from the guest to allow the host to call
+/// [`Contract::handle_acknowledgement`].
+///
+/// Loads the `Application` state and calls its [`handle_acknowledgement`][Contract::handle_acknowledgement]
+/// method.
+pub struct HandleAcknowledgement<Application: Contract> {
+    future: ExportedFuture<Result<ExecutionResult<Application::Message>, wit_types::UserError>>,
+    _application: PhantomData<Application>,
+}
+
+impl<Application> HandleAcknowledgement<Application>
+where
+    Application: Contract,
+{
+    /// Creates the exported future that the host can poll.
+    ///
+    /// This is called from the host.
+    pub fn new(
+        context: wit_types::MessageContext,
+        message: Vec<u8>,
+        outcome: wit_types::MessageOutcome,
+    ) -> Self {
+        ContractLogger::install();
+        HandleAcknowledgement {
+            future: ExportedFuture::new(Application::Storage::execute_with_state(
+                move |application| {
+                    async move {
+                        let message: Application::Message = bcs::from_bytes(&message)?;
+                        application
+                            .handle_acknowledgement(&context.into(), message, outcome.into())
+                            .await
+                    }
+                    .map_err(user_error::<Application>)
                     .boxed()
                 },
             )),
@@ -298,6 +402,7 @@ where
                             .handle_application_call(&context.into(), argument, forwarded_sessions)
                             .await
                     }
+                    .map_err(|error| error.to_string())
                     .boxed()
                 },
             )),
@@ -367,6 +472,7 @@ where
                             )
                             .await
                     }
+                    .map_err(|error| error.to_string())
                     .boxed()
                 },
             )),
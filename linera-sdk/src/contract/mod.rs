@@ -6,6 +6,7 @@
 mod conversions_from_wit;
 mod conversions_to_wit;
 pub mod exported_futures;
+#[cfg_attr(not(target_arch = "wasm32"), path = "native_system_api.rs")]
 pub mod system_api;
 pub mod wit_types;
 
@@ -28,6 +29,8 @@ macro_rules! contract {
             type Initialize = Initialize;
             type ExecuteOperation = ExecuteOperation;
             type ExecuteMessage = ExecuteMessage;
+            type HandleBouncedMessage = HandleBouncedMessage;
+            type HandleAcknowledgement = HandleAcknowledgement;
             type HandleApplicationCall = HandleApplicationCall;
             type HandleSessionCall = HandleSessionCall;
         }
@@ -53,6 +56,21 @@ macro_rules! contract {
             ) -> PollExecutionResult
         }
 
+        $crate::instance_exported_future! {
+            contract::HandleBouncedMessage<$application>(
+                context: $crate::contract::wit_types::MessageContext,
+                message: Vec<u8>,
+            ) -> PollExecutionResult
+        }
+
+        $crate::instance_exported_future! {
+            contract::HandleAcknowledgement<$application>(
+                context: $crate::contract::wit_types::MessageContext,
+                message: Vec<u8>,
+                outcome: $crate::contract::wit_types::MessageOutcome,
+            ) -> PollExecutionResult
+        }
+
         $crate::instance_exported_future! {
             contract::HandleApplicationCall<$application>(
                 context: $crate::contract::wit_types::CalleeContext,
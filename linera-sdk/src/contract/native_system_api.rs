@@ -0,0 +1,203 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Functions and types to interface with the system API available to application contracts
+//! executed natively, outside of a WebAssembly virtual machine.
+
+use crate::{
+    native::{self, runtime},
+    views::ViewStorageContext,
+};
+use futures::FutureExt;
+use linera_base::{
+    crypto::CryptoHash,
+    data_types::{Amount, Timestamp},
+    identifiers::{ApplicationId, ChainId, SessionId},
+};
+use linera_execution::ExecutionError;
+use linera_views::views::{RootView, View, ViewError};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt;
+
+/// Loads the application state and locks it for writes.
+pub(crate) async fn load_and_lock<State>() -> Option<State>
+where
+    State: Default + DeserializeOwned,
+{
+    let state_bytes = runtime::call_async(|runtime| {
+        async move {
+            match runtime.contract().try_read_and_lock_my_state().await {
+                Ok(state_bytes) => Ok(Some(state_bytes)),
+                Err(ExecutionError::ViewError(ViewError::NotFound(_))) => Ok(None),
+                Err(error) => Err(error),
+            }
+        }
+        .boxed()
+    })
+    .await;
+    state_bytes.map(deserialize_state)
+}
+
+/// Deserializes the application state or creates a new one if the `bytes` vector is empty.
+fn deserialize_state<State>(bytes: Vec<u8>) -> State
+where
+    State: Default + DeserializeOwned,
+{
+    if bytes.is_empty() {
+        State::default()
+    } else {
+        bcs::from_bytes(&bytes).expect("Invalid application state")
+    }
+}
+
+/// Saves the application state and unlocks it.
+pub(crate) async fn store_and_unlock<State>(state: State)
+where
+    State: Serialize,
+{
+    let state_bytes = bcs::to_bytes(&state).expect("State serialization failed");
+    // Like in WebAssembly, a failure to save the state is not reported to the application.
+    runtime::with_runtime(|runtime| {
+        let _ = runtime.contract().save_and_unlock_my_state(state_bytes);
+    });
+}
+
+/// Loads the application state and locks it for writes.
+pub(crate) async fn load_and_lock_view<State: View<ViewStorageContext>>() -> Option<State> {
+    let is_locked = runtime::call_async(|runtime| {
+        async move {
+            match runtime.contract().lock_view_user_state().await {
+                Ok(()) => Ok(true),
+                Err(ExecutionError::ViewError(ViewError::TryLockError(_))) => Ok(false),
+                Err(error) => Err(error),
+            }
+        }
+        .boxed()
+    })
+    .await;
+    if is_locked {
+        Some(load_view_using::<State>().await)
+    } else {
+        None
+    }
+}
+
+/// Helper function to load the application state or create a new one if it doesn't exist.
+pub(crate) async fn load_view_using<State: View<ViewStorageContext>>() -> State {
+    let context = ViewStorageContext::default();
+    let r = State::load(context).await;
+    r.expect("Failed to load application state")
+}
+
+/// Saves the application state and unlocks it.
+pub(crate) async fn store_and_unlock_view<State: RootView<ViewStorageContext>>(mut state: State) {
+    state.save().await.expect("save operation failed");
+}
+
+/// Retrieves the current chain ID.
+pub fn current_chain_id() -> ChainId {
+    runtime::with_runtime(|runtime| runtime.contract().chain_id())
+}
+
+/// Retrieves the current application ID.
+pub fn current_application_id() -> ApplicationId {
+    runtime::with_runtime(|runtime| runtime.contract().application_id())
+}
+
+/// Retrieves the current application parameters.
+pub(crate) fn current_application_parameters() -> Vec<u8> {
+    runtime::with_runtime(|runtime| runtime.contract().application_parameters())
+}
+
+/// Retrieves the current system balance.
+pub fn current_system_balance() -> Amount {
+    runtime::with_runtime(|runtime| runtime.contract().read_system_balance())
+}
+
+/// Retrieves the current system time, i.e. the timestamp of the block in which this is called.
+pub fn current_system_time() -> Timestamp {
+    runtime::with_runtime(|runtime| runtime.contract().read_system_timestamp())
+}
+
+/// Retrieves the pseudo-random seed of the transaction in which this is called.
+///
+/// All validators agree on the seed, which is derived from the signatures of the certificate of
+/// the chain's previous block, and differs for each transaction of the block. It cannot be
+/// predicted before the previous block of the chain is certified. However, the owners of the
+/// chain know it when they propose the block. It must therefore not be used to decide outcomes
+/// that the chain owners have an incentive to manipulate.
+///
+/// Blocks proposed before the previous block was certified have no seed, and the transaction
+/// fails if it calls this.
+pub fn current_random_seed() -> CryptoHash {
+    // Without a seed, the execution is aborted as soon as control returns to its executor, so
+    // whatever is done with the placeholder is discarded.
+    runtime::try_with_runtime(|runtime| runtime.contract().random_seed())
+        .unwrap_or(CryptoHash::from([0; 4]))
+}
+
+/// Calls another application without persisting the current application's state.
+///
+/// Use the `call_application` method generated by the [`linera-sdk::contract`] macro in order to
+/// guarantee the state is up-to-date in reentrant calls.
+pub(crate) async fn call_application_without_persisting_state(
+    authenticated: bool,
+    application: ApplicationId,
+    argument: &[u8],
+    forwarded_sessions: Vec<SessionId>,
+) -> (Vec<u8>, Vec<SessionId>) {
+    let argument = argument.to_vec();
+    let call_result = runtime::call_async(move |runtime| {
+        async move {
+            runtime
+                .contract()
+                .try_call_application(authenticated, application, &argument, forwarded_sessions)
+                .await
+        }
+        .boxed()
+    })
+    .await;
+    (call_result.value, call_result.sessions)
+}
+
+/// Calls another application's session without persisting the current application's state.
+///
+/// Use the `call_session` method generated by the [`linera-sdk::contract`] macro in order to
+/// guarantee the state is up-to-date in reentrant calls.
+pub(crate) async fn call_session_without_persisting_state(
+    authenticated: bool,
+    session: SessionId,
+    argument: &[u8],
+    forwarded_sessions: Vec<SessionId>,
+) -> (Vec<u8>, Vec<SessionId>) {
+    let argument = argument.to_vec();
+    let call_result = runtime::call_async(move |runtime| {
+        async move {
+            runtime
+                .contract()
+                .try_call_session(authenticated, session, &argument, forwarded_sessions)
+                .await
+        }
+        .boxed()
+    })
+    .await;
+    (call_result.value, call_result.sessions)
+}
+
+/// Requests the host to log a message.
+///
+/// Useful for debugging locally, but may be ignored by validators.
+pub fn log(message: &fmt::Arguments<'_>, level: log::Level) {
+    let message = message.to_string();
+    runtime::with_runtime(|runtime| runtime.contract().log(native::log_level(level), message));
+}
+
+/// Emits an event with the given `topic`, to be recorded in the current block.
+///
+/// Events are included in the certified execution outcome of the block, so they can be
+/// inspected by clients and indexers.
+pub fn emit_event(topic: &[u8], value: &impl Serialize) {
+    let value = bcs::to_bytes(value).expect("Failed to serialize event value");
+    let topic = topic.to_vec();
+    runtime::try_with_runtime(|runtime| runtime.contract().emit_event(topic, value));
+}
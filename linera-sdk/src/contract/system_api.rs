@@ -7,6 +7,7 @@ use super::contract_system_api as wit;
 use crate::views::ViewStorageContext;
 use futures::future;
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{Amount, Timestamp},
     identifiers::{ApplicationId, ChainId, SessionId},
 };
@@ -15,7 +16,7 @@ use serde::{de::DeserializeOwned, Serialize};
 use std::fmt;
 
 /// Loads the application state and locks it for writes.
-pub(crate) fn load_and_lock<State>() -> Option<State>
+pub(crate) async fn load_and_lock<State>() -> Option<State>
 where
     State: Default + DeserializeOwned,
 {
@@ -90,11 +91,25 @@ pub fn current_system_time() -> Timestamp {
     wit::read_system_timestamp().into()
 }
 
+/// Retrieves the pseudo-random seed of the transaction in which this is called.
+///
+/// All validators agree on the seed, which is derived from the signatures of the certificate of
+/// the chain's previous block, and differs for each transaction of the block. It cannot be
+/// predicted before the previous block of the chain is certified. However, the owners of the
+/// chain know it when they propose the block. It must therefore not be used to decide outcomes
+/// that the chain owners have an incentive to manipulate.
+///
+/// Blocks proposed before the previous block was certified have no seed, and the transaction
+/// fails if it calls this.
+pub fn current_random_seed() -> CryptoHash {
+    wit::random_seed().into()
+}
+
 /// Calls another application without persisting the current application's state.
 ///
 /// Use the `call_application` method generated by the [`linera-sdk::contract`] macro in order to
 /// guarantee the state is up-to-date in reentrant calls.
-pub(crate) fn call_application_without_persisting_state(
+pub(crate) async fn call_application_without_persisting_state(
     authenticated: bool,
     application: ApplicationId,
     argument: &[u8],
@@ -118,7 +133,7 @@ pub(crate) fn call_application_without_persisting_state(
 ///
 /// Use the `call_session` method generated by the [`linera-sdk::contract`] macro in order to
 /// guarantee the state is up-to-date in reentrant calls.
-pub(crate) fn call_session_without_persisting_state(
+pub(crate) async fn call_session_without_persisting_state(
     authenticated: bool,
     session: SessionId,
     argument: &[u8],
@@ -138,3 +153,12 @@ pub(crate) fn call_session_without_persisting_state(
 pub fn log(message: &fmt::Arguments<'_>, level: log::Level) {
     wit::log(&message.to_string(), level.into());
 }
+
+/// Emits an event with the given `topic`, to be recorded in the current block.
+///
+/// Events are included in the certified execution outcome of the block, so they can be
+/// inspected by clients and indexers.
+pub fn emit_event(topic: &[u8], value: &impl Serialize) {
+    let value = bcs::to_bytes(value).expect("Failed to serialize event value");
+    wit::emit_event(topic, &value);
+}
@@ -43,6 +43,8 @@ mod exported_future;
 mod extensions;
 pub mod graphql;
 mod log;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod native;
 pub mod service;
 #[cfg(feature = "test")]
 #[cfg_attr(not(target_arch = "wasm32"), path = "./test/integration/mod.rs")]
@@ -57,7 +59,7 @@ use crate::{
 use async_trait::async_trait;
 use linera_base::{
     abi::{ContractAbi, ServiceAbi, WithContractAbi, WithServiceAbi},
-    data_types::BlockHeight,
+    data_types::{Amount, BlockHeight},
     identifiers::{ApplicationId, ChainId, ChannelName, Destination, MessageId, Owner, SessionId},
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -152,6 +154,50 @@ pub trait Contract: WithContractAbi + ContractAbi + Send + Sized {
         message: Self::Message,
     ) -> Result<ExecutionResult<Self::Message>, Self::Error>;
 
+    /// Handles a tracked message that was sent by this application and rejected by the
+    /// receiving chain.
+    ///
+    /// Tracked messages are created with [`ExecutionResult::with_tracked_message`]. If the
+    /// receiving chain rejects one, for instance because its execution failed, the message is
+    /// returned to the sender chain with its original payload, so that the application can undo
+    /// its effects, e.g. refund the tokens it carried.
+    ///
+    /// By default, bounced messages are ignored.
+    async fn handle_bounced_message(
+        &mut self,
+        _context: &MessageContext,
+        _message: Self::Message,
+    ) -> Result<ExecutionResult<Self::Message>, Self::Error> {
+        Ok(ExecutionResult::default())
+    }
+
+    /// Handles the acknowledgement of a message that was sent by this application.
+    ///
+    /// Acknowledged messages are created with [`ExecutionResult::with_acknowledged_message`].
+    /// Once the receiving chain has executed or rejected one, the message is returned to the
+    /// sender chain with its original payload and the [`MessageOutcome`] of its execution, so
+    /// that the application can react to it, e.g. undo its effects if the execution failed.
+    ///
+    /// By default, acknowledgements are ignored.
+    async fn handle_acknowledgement(
+        &mut self,
+        _context: &MessageContext,
+        _message: Self::Message,
+        _outcome: MessageOutcome,
+    ) -> Result<ExecutionResult<Self::Message>, Self::Error> {
+        Ok(ExecutionResult::default())
+    }
+
+    /// Returns the code of an error returned by this application.
+    ///
+    /// If the execution of an acknowledged message fails, the code of the error is sent back to
+    /// the sender application in [`MessageOutcome::Failed`].
+    ///
+    /// By default, all errors have the code `0`.
+    fn error_code(_error: &Self::Error) -> u32 {
+        0
+    }
+
     /// Handles a call from another application.
     ///
     /// Cross-application calls allow applications to interact inside a chain. An
@@ -237,6 +283,7 @@ pub trait Contract: WithContractAbi + ContractAbi + Send + Sized {
                     &call_bytes,
                     forwarded_sessions,
                 )
+                .await
             })
             .await;
         let response = bcs::from_bytes(&response_bytes)?;
@@ -262,6 +309,7 @@ pub trait Contract: WithContractAbi + ContractAbi + Send + Sized {
                     &call_bytes,
                     forwarded_sessions,
                 )
+                .await
             })
             .await;
         let response = bcs::from_bytes(&response_bytes)?;
@@ -324,6 +372,53 @@ pub trait Service: WithServiceAbi + ServiceAbi {
         Ok(response)
     }
 
+    /// Queries an application on another chain tracked by the local node.
+    ///
+    /// The response is annotated with the height of the next block of the queried chain, so
+    /// that callers can tell how recent the state it was computed from is.
+    async fn query_remote_application<A: ServiceAbi + Send>(
+        chain_id: ChainId,
+        application: ApplicationId<A>,
+        query: &A::Query,
+    ) -> Result<RemoteQueryResponse<A::QueryResponse>, Self::Error>
+    where
+        Self::Error: From<String>,
+    {
+        let query_bytes = serde_json::to_vec(&query)?;
+        let (response_bytes, height) = crate::service::system_api::query_remote_application(
+            chain_id,
+            application.forget_abi(),
+            &query_bytes,
+        )
+        .await
+        .map_err(String::from)?;
+        let response = serde_json::from_slice(&response_bytes)?;
+        Ok(RemoteQueryResponse { response, height })
+    }
+
+    /// Queries the system state of another chain tracked by the local node.
+    ///
+    /// The `query` and the response are the JSON encodings of the `SystemQuery` and
+    /// `SystemResponse` types of the execution layer, e.g. `"Balances"` for the balances of
+    /// the chain's owners.
+    async fn query_remote_system<Query, Response>(
+        chain_id: ChainId,
+        query: &Query,
+    ) -> Result<RemoteQueryResponse<Response>, Self::Error>
+    where
+        Query: Serialize + Sync,
+        Response: DeserializeOwned,
+        Self::Error: From<String>,
+    {
+        let query_bytes = serde_json::to_vec(query)?;
+        let (response_bytes, height) =
+            crate::service::system_api::query_remote_system(chain_id, &query_bytes)
+                .await
+                .map_err(String::from)?;
+        let response = serde_json::from_slice(&response_bytes)?;
+        Ok(RemoteQueryResponse { response, height })
+    }
+
     /// Retrieves the parameters of the application.
     fn parameters() -> Result<Self::Parameters, Self::Error> {
         let bytes = crate::service::system_api::current_application_parameters();
@@ -359,6 +454,18 @@ pub struct MessageContext {
     pub message_id: MessageId,
 }
 
+/// The outcome of the execution of an acknowledged message by the receiving chain.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum MessageOutcome {
+    /// The message was executed successfully.
+    Executed,
+    /// The application returned an error while executing the message, with the code given by
+    /// [`Contract::error_code`].
+    Failed(u32),
+    /// The message was rejected by the receiving chain and was not executed.
+    Rejected,
+}
+
 /// The context of the execution of an application's cross-application call or session call handler.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CalleeContext {
@@ -378,6 +485,16 @@ pub struct QueryContext {
     pub chain_id: ChainId,
 }
 
+/// The response of an application on another chain.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RemoteQueryResponse<Response> {
+    /// The response of the queried application.
+    pub response: Response,
+    /// The height of the next block of the queried chain: the response reflects the state of
+    /// the chain after executing all the blocks below this height.
+    pub height: BlockHeight,
+}
+
 /// A message together with routing information.
 #[derive(Debug, Deserialize, Serialize)]
 #[cfg_attr(any(test, feature = "test"), derive(Eq, PartialEq))]
@@ -388,6 +505,14 @@ pub struct OutgoingMessage<Message> {
     pub authenticated: bool,
     /// Whether the message can skipped by the receiving chain.
     pub is_skippable: bool,
+    /// Whether the message is returned to the sender if the receiving chain rejects it.
+    pub is_tracked: bool,
+    /// Whether the message is returned to the sender with the outcome of its execution once
+    /// the receiving chain executes or rejects it.
+    pub is_acknowledged: bool,
+    /// The tokens granted to the receiving chain for executing the message, taken from the
+    /// balance of this chain. Chains can refuse the messages that don't grant them enough.
+    pub grant: Amount,
     /// The message itself.
     pub message: Message,
 }
@@ -425,6 +550,68 @@ impl<Message: Serialize + Debug + DeserializeOwned> ExecutionResult<Message> {
             destination,
             authenticated: false,
             is_skippable,
+            is_tracked: false,
+            is_acknowledged: false,
+            grant: Amount::ZERO,
+            message,
+        });
+        self
+    }
+
+    /// Adds a tracked message to the execution result. If the receiving chain rejects it, the
+    /// message is returned to this application by [`Contract::handle_bounced_message`].
+    pub fn with_tracked_message(
+        mut self,
+        destination: impl Into<Destination>,
+        message: Message,
+    ) -> Self {
+        self.messages.push(OutgoingMessage {
+            destination: destination.into(),
+            authenticated: false,
+            is_skippable: false,
+            is_tracked: true,
+            is_acknowledged: false,
+            grant: Amount::ZERO,
+            message,
+        });
+        self
+    }
+
+    /// Adds an acknowledged message to the execution result. Once the receiving chain executes
+    /// or rejects it, the message is returned to this application with the outcome of its
+    /// execution by [`Contract::handle_acknowledgement`].
+    pub fn with_acknowledged_message(
+        mut self,
+        destination: impl Into<Destination>,
+        message: Message,
+    ) -> Self {
+        self.messages.push(OutgoingMessage {
+            destination: destination.into(),
+            authenticated: false,
+            is_skippable: false,
+            is_tracked: false,
+            is_acknowledged: true,
+            grant: Amount::ZERO,
+            message,
+        });
+        self
+    }
+
+    /// Adds a message to the execution result that grants some tokens to the receiving chain,
+    /// e.g. to pay for the minimum grant required by its inbox policy.
+    pub fn with_granted_message(
+        mut self,
+        recipient: ChainId,
+        message: Message,
+        grant: Amount,
+    ) -> Self {
+        self.messages.push(OutgoingMessage {
+            destination: Destination::Recipient(recipient),
+            authenticated: false,
+            is_skippable: false,
+            is_tracked: false,
+            is_acknowledged: false,
+            grant,
             message,
         });
         self
@@ -442,6 +629,9 @@ impl<Message: Serialize + Debug + DeserializeOwned> ExecutionResult<Message> {
             destination,
             authenticated: true,
             is_skippable,
+            is_tracked: false,
+            is_acknowledged: false,
+            grant: Amount::ZERO,
             message,
         });
         self
@@ -0,0 +1,142 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Conversions between the types of the SDK and the types of [`linera_execution`], used when
+//! executing applications natively.
+
+use crate::{
+    ApplicationCallResult, CalleeContext, ExecutionResult, MessageContext, MessageOutcome,
+    OperationContext, OutgoingMessage, QueryContext,
+};
+use linera_execution::{trace::LogLevel, MessageKind, RawExecutionResult, RawOutgoingMessage};
+use serde::Serialize;
+
+impl From<linera_execution::OperationContext> for OperationContext {
+    fn from(context: linera_execution::OperationContext) -> Self {
+        OperationContext {
+            chain_id: context.chain_id,
+            authenticated_signer: context.authenticated_signer,
+            height: context.height,
+            index: context.index,
+        }
+    }
+}
+
+impl From<linera_execution::MessageContext> for MessageContext {
+    fn from(context: linera_execution::MessageContext) -> Self {
+        MessageContext {
+            chain_id: context.chain_id,
+            authenticated_signer: context.authenticated_signer,
+            height: context.height,
+            message_id: context.message_id,
+        }
+    }
+}
+
+impl From<linera_execution::MessageOutcome> for MessageOutcome {
+    fn from(outcome: linera_execution::MessageOutcome) -> Self {
+        match outcome {
+            linera_execution::MessageOutcome::Executed => MessageOutcome::Executed,
+            linera_execution::MessageOutcome::Failed(code) => MessageOutcome::Failed(code),
+            linera_execution::MessageOutcome::Rejected => MessageOutcome::Rejected,
+        }
+    }
+}
+
+impl From<linera_execution::CalleeContext> for CalleeContext {
+    fn from(context: linera_execution::CalleeContext) -> Self {
+        CalleeContext {
+            chain_id: context.chain_id,
+            authenticated_signer: context.authenticated_signer,
+            authenticated_caller_id: context.authenticated_caller_id,
+        }
+    }
+}
+
+impl From<linera_execution::QueryContext> for QueryContext {
+    fn from(context: linera_execution::QueryContext) -> Self {
+        QueryContext {
+            chain_id: context.chain_id,
+        }
+    }
+}
+
+impl<Message> From<OutgoingMessage<Message>> for RawOutgoingMessage<Vec<u8>>
+where
+    Message: Serialize,
+{
+    fn from(message: OutgoingMessage<Message>) -> Self {
+        RawOutgoingMessage {
+            destination: message.destination,
+            authenticated: message.authenticated,
+            is_skippable: message.is_skippable,
+            kind: if message.is_acknowledged {
+                MessageKind::Acknowledged
+            } else if message.is_tracked {
+                MessageKind::Tracked
+            } else {
+                MessageKind::Simple
+            },
+            grant: message.grant,
+            message: bcs::to_bytes(&message.message).expect("message serialization failed"),
+        }
+    }
+}
+
+impl<Message> From<ExecutionResult<Message>> for RawExecutionResult<Vec<u8>>
+where
+    Message: Serialize,
+{
+    fn from(result: ExecutionResult<Message>) -> Self {
+        RawExecutionResult {
+            authenticated_signer: None,
+            messages: result
+                .messages
+                .into_iter()
+                .map(RawOutgoingMessage::from)
+                .collect(),
+            subscribe: result.subscribe,
+            unsubscribe: result.unsubscribe,
+            events: Vec::new(),
+        }
+    }
+}
+
+impl<Message, Value, SessionState> From<ApplicationCallResult<Message, Value, SessionState>>
+    for linera_execution::ApplicationCallResult
+where
+    Message: Serialize,
+    Value: Serialize,
+    SessionState: Serialize,
+{
+    fn from(result: ApplicationCallResult<Message, Value, SessionState>) -> Self {
+        let value = bcs::to_bytes(&result.value)
+            .expect("failed to serialize Value for ApplicationCallResult");
+
+        let create_sessions = result
+            .create_sessions
+            .into_iter()
+            .map(|state| {
+                bcs::to_bytes(&state)
+                    .expect("failed to serialize session state for ApplicationCallResult")
+            })
+            .collect();
+
+        linera_execution::ApplicationCallResult {
+            value,
+            execution_result: result.execution_result.into(),
+            create_sessions,
+        }
+    }
+}
+
+/// Converts the level of a message logged by an application.
+pub(crate) fn log_level(level: log::Level) -> LogLevel {
+    match level {
+        log::Level::Trace => LogLevel::Trace,
+        log::Level::Debug => LogLevel::Debug,
+        log::Level::Info => LogLevel::Info,
+        log::Level::Warn => LogLevel::Warn,
+        log::Level::Error => LogLevel::Error,
+    }
+}
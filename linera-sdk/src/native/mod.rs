@@ -0,0 +1,289 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Execution of applications compiled natively, without a WebAssembly virtual machine.
+//!
+//! A [`NativeApplication`] wraps the [`Contract`] and [`Service`] types of an application linked
+//! into the current binary, so that it can be executed by [`linera_execution`] like any other
+//! [`UserApplication`]. This allows the application to be tested without building it for
+//! WebAssembly first, and to be inspected with the usual native debugging and code coverage
+//! tools.
+//!
+//! The system APIs used by natively executed applications forward the calls to the runtime of
+//! the application currently being executed. If a system API call fails, the execution of the
+//! application is aborted and the error returned by the runtime is reported, as if the
+//! application had trapped inside a WebAssembly virtual machine.
+
+mod conversions;
+pub(crate) mod runtime;
+
+pub(crate) use self::conversions::log_level;
+use crate::{
+    contract::exported_futures::ContractStateStorage,
+    service::exported_futures::ServiceStateStorage, Contract, Service,
+};
+use async_trait::async_trait;
+use futures::{FutureExt, TryFutureExt};
+use linera_base::identifiers::SessionId;
+use linera_execution::{
+    ApplicationCallResult, CalleeContext, ContractRuntime, ExecutionError, MessageContext,
+    MessageOutcome, OperationContext, QueryContext, RawExecutionResult, ServiceRuntime,
+    SessionCallResult, UserApplication,
+};
+use std::{fmt, marker::PhantomData};
+
+/// Converts an error returned by the contract `A` into the [`ExecutionError`] reported to the
+/// runtime, together with its code.
+fn user_error<A: Contract>(error: A::Error) -> ExecutionError {
+    ExecutionError::UserError {
+        code: A::error_code(&error),
+        message: error.to_string(),
+    }
+}
+
+/// A user application with a contract and a service compiled natively.
+pub struct NativeApplication<A, S> {
+    _application: PhantomData<fn() -> (A, S)>,
+}
+
+impl<A, S> NativeApplication<A, S>
+where
+    A: Contract,
+    S: Service,
+{
+    /// Creates a [`NativeApplication`] executing the contract `A` and the service `S`.
+    pub fn new() -> Self {
+        NativeApplication {
+            _application: PhantomData,
+        }
+    }
+}
+
+impl<A, S> Default for NativeApplication<A, S>
+where
+    A: Contract,
+    S: Service,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A, S> fmt::Debug for NativeApplication<A, S>
+where
+    A: Contract,
+    S: Service,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("NativeApplication")
+            .field("contract", &std::any::type_name::<A>())
+            .field("service", &std::any::type_name::<S>())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<A, S> UserApplication for NativeApplication<A, S>
+where
+    A: Contract + 'static,
+    S: Service + 'static,
+{
+    async fn initialize(
+        &self,
+        context: &OperationContext,
+        runtime: &dyn ContractRuntime,
+        argument: &[u8],
+    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError> {
+        let context = crate::OperationContext::from(*context);
+        let argument = argument.to_vec();
+        runtime::execute_contract(runtime, async move {
+            A::Storage::execute_with_state(move |application| {
+                async move {
+                    let argument = serde_json::from_slice(&argument)?;
+                    application.initialize(&context, argument).await
+                }
+                .map_err(user_error::<A>)
+                .boxed()
+            })
+            .await
+            .map(RawExecutionResult::<Vec<u8>>::from)
+        })
+        .await?
+    }
+
+    async fn execute_operation(
+        &self,
+        context: &OperationContext,
+        runtime: &dyn ContractRuntime,
+        operation: &[u8],
+    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError> {
+        let context = crate::OperationContext::from(*context);
+        let operation = operation.to_vec();
+        runtime::execute_contract(runtime, async move {
+            A::Storage::execute_with_state(move |application| {
+                async move {
+                    let operation: A::Operation = bcs::from_bytes(&operation)?;
+                    application.execute_operation(&context, operation).await
+                }
+                .map_err(user_error::<A>)
+                .boxed()
+            })
+            .await
+            .map(RawExecutionResult::<Vec<u8>>::from)
+        })
+        .await?
+    }
+
+    async fn execute_message(
+        &self,
+        context: &MessageContext,
+        runtime: &dyn ContractRuntime,
+        message: &[u8],
+    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError> {
+        let context = crate::MessageContext::from(*context);
+        let message = message.to_vec();
+        runtime::execute_contract(runtime, async move {
+            A::Storage::execute_with_state(move |application| {
+                async move {
+                    let message: A::Message = bcs::from_bytes(&message)?;
+                    application.execute_message(&context, message).await
+                }
+                .map_err(user_error::<A>)
+                .boxed()
+            })
+            .await
+            .map(RawExecutionResult::<Vec<u8>>::from)
+        })
+        .await?
+    }
+
+    async fn handle_bounced_message(
+        &self,
+        context: &MessageContext,
+        runtime: &dyn ContractRuntime,
+        message: &[u8],
+    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError> {
+        let context = crate::MessageContext::from(*context);
+        let message = message.to_vec();
+        runtime::execute_contract(runtime, async move {
+            A::Storage::execute_with_state(move |application| {
+                async move {
+                    let message: A::Message = bcs::from_bytes(&message)?;
+                    application.handle_bounced_message(&context, message).await
+                }
+                .map_err(user_error::<A>)
+                .boxed()
+            })
+            .await
+            .map(RawExecutionResult::<Vec<u8>>::from)
+        })
+        .await?
+    }
+
+    async fn handle_acknowledgement(
+        &self,
+        context: &MessageContext,
+        runtime: &dyn ContractRuntime,
+        message: &[u8],
+        outcome: MessageOutcome,
+    ) -> Result<RawExecutionResult<Vec<u8>>, ExecutionError> {
+        let context = crate::MessageContext::from(*context);
+        let outcome = crate::MessageOutcome::from(outcome);
+        let message = message.to_vec();
+        runtime::execute_contract(runtime, async move {
+            A::Storage::execute_with_state(move |application| {
+                async move {
+                    let message: A::Message = bcs::from_bytes(&message)?;
+                    application
+                        .handle_acknowledgement(&context, message, outcome)
+                        .await
+                }
+                .map_err(user_error::<A>)
+                .boxed()
+            })
+            .await
+            .map(RawExecutionResult::<Vec<u8>>::from)
+        })
+        .await?
+    }
+
+    async fn handle_application_call(
+        &self,
+        context: &CalleeContext,
+        runtime: &dyn ContractRuntime,
+        argument: &[u8],
+        forwarded_sessions: Vec<SessionId>,
+    ) -> Result<ApplicationCallResult, ExecutionError> {
+        let context = crate::CalleeContext::from(*context);
+        let argument = argument.to_vec();
+        runtime::execute_contract(runtime, async move {
+            A::Storage::execute_with_state(move |application| {
+                async move {
+                    let argument: A::ApplicationCall = bcs::from_bytes(&argument)?;
+                    application
+                        .handle_application_call(&context, argument, forwarded_sessions)
+                        .await
+                }
+                .map_err(user_error::<A>)
+                .boxed()
+            })
+            .await
+            .map(ApplicationCallResult::from)
+        })
+        .await?
+    }
+
+    async fn handle_session_call(
+        &self,
+        context: &CalleeContext,
+        runtime: &dyn ContractRuntime,
+        session_state: &mut Vec<u8>,
+        argument: &[u8],
+        forwarded_sessions: Vec<SessionId>,
+    ) -> Result<SessionCallResult, ExecutionError> {
+        let context = crate::CalleeContext::from(*context);
+        let session = session_state.clone();
+        let argument = argument.to_vec();
+        let result = runtime::execute_contract(runtime, async move {
+            A::Storage::execute_with_state(move |application| {
+                async move {
+                    let session: A::SessionState = bcs::from_bytes(&session)?;
+                    let argument: A::SessionCall = bcs::from_bytes(&argument)?;
+                    application
+                        .handle_session_call(&context, session, argument, forwarded_sessions)
+                        .await
+                }
+                .map_err(user_error::<A>)
+                .boxed()
+            })
+            .await
+            .map(|result| {
+                let new_state = result
+                    .new_state
+                    .map(|state| bcs::to_bytes(&state).expect("session type serialization failed"));
+                (ApplicationCallResult::from(result.inner), new_state)
+            })
+        })
+        .await?;
+        let (inner, new_state) = result?;
+        let close_session = new_state.is_none();
+        *session_state = new_state.unwrap_or_default();
+        Ok(SessionCallResult {
+            inner,
+            close_session,
+        })
+    }
+
+    async fn handle_query(
+        &self,
+        context: &QueryContext,
+        runtime: &dyn ServiceRuntime,
+        argument: &[u8],
+    ) -> Result<Vec<u8>, ExecutionError> {
+        let context = crate::QueryContext::from(*context);
+        let future = S::Storage::execute_query(context, argument.to_vec());
+        let result = runtime::execute_service(runtime, future).await?;
+        result.map_err(|message| ExecutionError::UserError { code: 0, message })
+    }
+}
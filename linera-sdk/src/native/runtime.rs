@@ -0,0 +1,292 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Access to the runtime of the application being executed natively.
+//!
+//! The system APIs don't receive the runtime as a parameter, so the execution of the
+//! application currently being polled is made available through a thread-local variable. It is
+//! set every time the future executing an application is polled, and restored afterwards, so
+//! that applications called by other applications use their own runtime.
+//!
+//! The runtime is only lent to the system APIs for the duration of a synchronous call.
+//! Asynchronous system API calls are handed over to the executor of the application, which
+//! owns the borrow of the runtime and drives the calls next to the application's future. If a
+//! call fails, the executor stops polling the application and reports the error, as if the
+//! application had trapped inside a WebAssembly virtual machine.
+
+use futures::{
+    channel::oneshot,
+    future::{self, BoxFuture},
+    stream::{FuturesUnordered, StreamExt},
+    FutureExt,
+};
+use linera_execution::{ContractRuntime, ExecutionError, ServiceRuntime};
+use std::{
+    any::Any,
+    cell::Cell,
+    future::Future,
+    mem,
+    panic::{self, AssertUnwindSafe},
+    sync::Mutex,
+    task::Poll,
+};
+
+thread_local! {
+    /// The execution of the application currently being polled on this thread.
+    static CURRENT_EXECUTION: Cell<Option<*const NativeExecution<'static>>> = Cell::new(None);
+}
+
+/// The runtime of an application executed natively.
+#[derive(Clone, Copy)]
+pub(crate) enum NativeRuntime<'runtime> {
+    Contract(&'runtime dyn ContractRuntime),
+    Service(&'runtime dyn ServiceRuntime),
+}
+
+impl<'runtime> NativeRuntime<'runtime> {
+    /// Returns the runtime of the contract being executed.
+    pub(crate) fn contract(self) -> &'runtime dyn ContractRuntime {
+        match self {
+            NativeRuntime::Contract(runtime) => runtime,
+            NativeRuntime::Service(_) => panic!("Contract system API called from a service"),
+        }
+    }
+
+    /// Returns the runtime of the service being executed.
+    pub(crate) fn service(self) -> &'runtime dyn ServiceRuntime {
+        match self {
+            NativeRuntime::Service(runtime) => runtime,
+            NativeRuntime::Contract(_) => panic!("Service system API called from a contract"),
+        }
+    }
+}
+
+/// An asynchronous system API call, to be driven by the executor of the application.
+type PendingCall = Box<
+    dyn for<'runtime> FnOnce(
+            NativeRuntime<'runtime>,
+        ) -> BoxFuture<'runtime, Result<(), ExecutionError>>
+        + Send,
+>;
+
+/// The state of the execution of an application.
+struct NativeExecution<'runtime> {
+    runtime: NativeRuntime<'runtime>,
+    pending_calls: Mutex<Vec<PendingCall>>,
+    error: Mutex<Option<ExecutionError>>,
+}
+
+impl NativeExecution<'_> {
+    /// Records the failure of a system API call. Only the first error is kept.
+    fn fail(&self, error: ExecutionError) {
+        self.error
+            .lock()
+            .expect("Recording an error never panics")
+            .get_or_insert(error);
+    }
+
+    /// Returns the error of a failed system API call, if any.
+    fn take_error(&self) -> Option<ExecutionError> {
+        self.error
+            .lock()
+            .expect("Recording an error never panics")
+            .take()
+    }
+
+    /// Returns the asynchronous system API calls made since the last time this was called.
+    fn take_pending_calls(&self) -> Vec<PendingCall> {
+        mem::take(
+            &mut *self
+                .pending_calls
+                .lock()
+                .expect("Scheduling a call never panics"),
+        )
+    }
+}
+
+/// Executes the `future` of a contract, with the system APIs using the `runtime`.
+pub(crate) async fn execute_contract<Output>(
+    runtime: &dyn ContractRuntime,
+    future: impl Future<Output = Output> + Send,
+) -> Result<Output, ExecutionError> {
+    execute(NativeRuntime::Contract(runtime), future).await
+}
+
+/// Executes the `future` of a service, with the system APIs using the `runtime`.
+pub(crate) async fn execute_service<Output>(
+    runtime: &dyn ServiceRuntime,
+    future: impl Future<Output = Output> + Send,
+) -> Result<Output, ExecutionError> {
+    execute(NativeRuntime::Service(runtime), future).await
+}
+
+/// Executes the `future` with the system APIs using the `runtime`, driving the asynchronous
+/// system API calls it makes.
+///
+/// The execution stops with an error as soon as a system API call fails. A panic of the
+/// application is reported as an error too, like a trap of a WebAssembly application.
+async fn execute<Output>(
+    runtime: NativeRuntime<'_>,
+    future: impl Future<Output = Output> + Send,
+) -> Result<Output, ExecutionError> {
+    let execution = NativeExecution {
+        runtime,
+        pending_calls: Mutex::new(Vec::new()),
+        error: Mutex::new(None),
+    };
+    let mut application = Box::pin(future);
+    let mut running_calls = FuturesUnordered::<BoxFuture<'_, _>>::new();
+
+    future::poll_fn(|context| loop {
+        let poll = {
+            let _guard = ExecutionGuard::enter(&execution);
+            panic::catch_unwind(AssertUnwindSafe(|| application.as_mut().poll(context)))
+        };
+        if let Some(error) = execution.take_error() {
+            return Poll::Ready(Err(error));
+        }
+        match poll {
+            Ok(Poll::Ready(output)) => return Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => {}
+            Err(panic) => {
+                return Poll::Ready(Err(ExecutionError::UserError {
+                    code: 0,
+                    message: panic_message(panic),
+                }));
+            }
+        }
+
+        running_calls.extend(
+            execution
+                .take_pending_calls()
+                .into_iter()
+                .map(|call| call(execution.runtime)),
+        );
+        let mut completed_call = false;
+        while let Poll::Ready(Some(result)) = running_calls.poll_next_unpin(context) {
+            if let Err(error) = result {
+                return Poll::Ready(Err(error));
+            }
+            completed_call = true;
+        }
+        if !completed_call {
+            return Poll::Pending;
+        }
+    })
+    .await
+}
+
+/// Restores the previous execution of the thread when dropped.
+struct ExecutionGuard {
+    previous: Option<*const NativeExecution<'static>>,
+}
+
+impl ExecutionGuard {
+    /// Makes `execution` the current execution of the thread.
+    fn enter(execution: &NativeExecution<'_>) -> Self {
+        let pointer = (execution as *const NativeExecution<'_>).cast::<NativeExecution<'static>>();
+        let previous = CURRENT_EXECUTION.with(|current| current.replace(Some(pointer)));
+        ExecutionGuard { previous }
+    }
+}
+
+impl Drop for ExecutionGuard {
+    fn drop(&mut self) {
+        CURRENT_EXECUTION.with(|current| current.set(self.previous));
+    }
+}
+
+/// Runs `action` with the execution currently being polled.
+fn with_current_execution<Output>(
+    action: impl for<'execution> FnOnce(&'execution NativeExecution<'execution>) -> Output,
+) -> Output {
+    let execution = CURRENT_EXECUTION
+        .with(Cell::get)
+        .expect("System APIs can only be called while executing an application");
+    // SAFETY: The pointer is only set while the referenced execution is borrowed by the future
+    // being polled, and the higher-ranked `action` can't keep the reference, nor the runtime it
+    // contains, after it returns.
+    action(unsafe { &*execution })
+}
+
+/// Calls a synchronous system API with the runtime of the application being executed.
+pub(crate) fn with_runtime<Output>(
+    call: impl for<'runtime> FnOnce(NativeRuntime<'runtime>) -> Output,
+) -> Output {
+    with_current_execution(|execution| call(execution.runtime))
+}
+
+/// Calls a synchronous system API that may fail. A failure aborts the execution of the
+/// application once control returns to its executor, and [`None`] is returned meanwhile.
+pub(crate) fn try_with_runtime<Output>(
+    call: impl for<'runtime> FnOnce(NativeRuntime<'runtime>) -> Result<Output, ExecutionError>,
+) -> Option<Output> {
+    with_current_execution(|execution| match call(execution.runtime) {
+        Ok(output) => Some(output),
+        Err(error) => {
+            execution.fail(error);
+            None
+        }
+    })
+}
+
+/// Calls an asynchronous system API, driven by the executor of the application being executed.
+///
+/// If the call fails, the execution of the application is aborted, so the returned future never
+/// completes.
+pub(crate) async fn call_async<Output>(
+    call: impl for<'runtime> FnOnce(
+            NativeRuntime<'runtime>,
+        ) -> BoxFuture<'runtime, Result<Output, ExecutionError>>
+        + Send
+        + 'static,
+) -> Output
+where
+    Output: Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    let pending_call = pending_call(move |runtime| {
+        call(runtime)
+            .map(move |result| {
+                // The application may have been dropped already, in which case there is no one
+                // to notify.
+                let _ = sender.send(result?);
+                Ok(())
+            })
+            .boxed()
+    });
+    with_current_execution(|execution| {
+        execution
+            .pending_calls
+            .lock()
+            .expect("Scheduling a call never panics")
+            .push(pending_call)
+    });
+    match receiver.await {
+        Ok(output) => output,
+        // The call failed, and the executor stopped polling the application.
+        Err(_) => future::pending().await,
+    }
+}
+
+/// Boxes an asynchronous system API `call`.
+fn pending_call(
+    call: impl for<'runtime> FnOnce(
+            NativeRuntime<'runtime>,
+        ) -> BoxFuture<'runtime, Result<(), ExecutionError>>
+        + Send
+        + 'static,
+) -> PendingCall {
+    Box::new(call)
+}
+
+/// Extracts the message of a `panic`.
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    match panic.downcast::<String>() {
+        Ok(message) => *message,
+        Err(panic) => match panic.downcast::<&'static str>() {
+            Ok(message) => (*message).to_owned(),
+            Err(_) => "Application panicked".to_owned(),
+        },
+    }
+}
@@ -4,7 +4,7 @@
 //! Conversions from types generated by [`wit-bindgen-guest-rust`] to types declared in [`linera-sdk`].
 
 use super::{
-    service_system_api::{self as wit_system_api, PollLoad, PollLock},
+    service_system_api::{self as wit_system_api, PollLoad, PollLock, PollRemoteQuery},
     wit_types,
 };
 use crate::QueryContext;
@@ -81,6 +81,18 @@ impl From<PollLoad> for Poll<Result<Vec<u8>, String>> {
     }
 }
 
+impl From<PollRemoteQuery> for Poll<Result<(Vec<u8>, BlockHeight), String>> {
+    fn from(poll_remote_query: PollRemoteQuery) -> Self {
+        match poll_remote_query {
+            PollRemoteQuery::Ready(Ok(response)) => {
+                Poll::Ready(Ok((response.response, BlockHeight(response.height))))
+            }
+            PollRemoteQuery::Ready(Err(error)) => Poll::Ready(Err(error)),
+            PollRemoteQuery::Pending => Poll::Pending,
+        }
+    }
+}
+
 impl From<PollLock> for Poll<Result<(), ViewError>> {
     fn from(poll_lock: PollLock) -> Self {
         match poll_lock {
@@ -10,61 +10,63 @@
 use crate::{
     service::{system_api, wit_types},
     views::ViewStorageContext,
-    ExportedFuture, Service, ServiceLogger, SimpleStateStorage, ViewStateStorage,
+    ExportedFuture, QueryContext, Service, ServiceLogger, SimpleStateStorage, ViewStateStorage,
 };
+use async_trait::async_trait;
 use linera_views::views::RootView;
 use serde::{de::DeserializeOwned, Serialize};
 use std::{marker::PhantomData, sync::Arc};
 
 /// The storage APIs used by a service.
+#[async_trait]
 pub trait ServiceStateStorage {
+    /// Loads the application state and runs the given query, returning the serialized response.
+    async fn execute_query(context: QueryContext, argument: Vec<u8>) -> Result<Vec<u8>, String>;
+
     /// Loads the application state and run the given query.
     fn handle_query(
         context: wit_types::QueryContext,
         argument: Vec<u8>,
-    ) -> ExportedFuture<Result<Vec<u8>, String>>;
+    ) -> ExportedFuture<Result<Vec<u8>, String>> {
+        ExportedFuture::new(Self::execute_query(context.into(), argument))
+    }
 }
 
+#[async_trait]
 impl<Application> ServiceStateStorage for SimpleStateStorage<Application>
 where
-    Application: Service + Default + DeserializeOwned + Serialize,
+    Application: Service + Default + DeserializeOwned + Serialize + Send + Sync + 'static,
 {
-    fn handle_query(
-        context: wit_types::QueryContext,
-        argument: Vec<u8>,
-    ) -> ExportedFuture<Result<Vec<u8>, String>> {
-        ExportedFuture::new(async move {
-            let application: Arc<Application> = Arc::new(system_api::load().await);
-            let argument: Application::Query =
-                serde_json::from_slice(&argument).map_err(|e| e.to_string())?;
-            let query_response = application
-                .handle_query(&context.into(), argument)
-                .await
-                .map_err(|error| error.to_string())?;
-            serde_json::to_vec(&query_response).map_err(|e| e.to_string())
-        })
+    async fn execute_query(context: QueryContext, argument: Vec<u8>) -> Result<Vec<u8>, String> {
+        let application: Arc<Application> = Arc::new(system_api::load().await);
+        let argument: Application::Query =
+            serde_json::from_slice(&argument).map_err(|e| e.to_string())?;
+        let query_response = application
+            .handle_query(&context, argument)
+            .await
+            .map_err(|error| error.to_string())?;
+        serde_json::to_vec(&query_response).map_err(|e| e.to_string())
     }
 }
 
+#[async_trait]
 impl<Application> ServiceStateStorage for ViewStateStorage<Application>
 where
-    Application: Service + RootView<ViewStorageContext>,
+    Application: Service + RootView<ViewStorageContext> + Send + Sync + 'static,
 {
-    fn handle_query(
-        context: wit_types::QueryContext,
-        argument: Vec<u8>,
-    ) -> ExportedFuture<Result<Vec<u8>, String>> {
-        ExportedFuture::new(async move {
-            let application: Arc<Application> = Arc::new(system_api::lock_and_load_view().await);
-            let argument: Application::Query =
-                serde_json::from_slice(&argument).map_err(|e| e.to_string())?;
-            let result = application.handle_query(&context.into(), argument).await;
-            if result.is_ok() {
-                system_api::unlock_view().await;
-            }
-            let query_response = result.map_err(|error| error.to_string())?;
-            serde_json::to_vec(&query_response).map_err(|e| e.to_string())
-        })
+    async fn execute_query(context: QueryContext, argument: Vec<u8>) -> Result<Vec<u8>, String> {
+        let application: Arc<Application> = Arc::new(system_api::lock_and_load_view().await);
+        let argument: Application::Query =
+            serde_json::from_slice(&argument).map_err(|e| e.to_string())?;
+        let result = application
+            .handle_query(&context, argument)
+            .await
+            .map_err(|error| error.to_string());
+        if result.is_ok() {
+            system_api::unlock_view().await;
+        }
+        let query_response = result?;
+        serde_json::to_vec(&query_response).map_err(|e| e.to_string())
     }
 }
 
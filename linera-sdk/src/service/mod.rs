@@ -6,6 +6,7 @@
 mod conversions_from_wit;
 mod conversions_to_wit;
 pub mod exported_futures;
+#[cfg_attr(not(target_arch = "wasm32"), path = "native_system_api.rs")]
 pub mod system_api;
 pub mod wit_types;
 
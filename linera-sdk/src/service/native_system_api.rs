@@ -0,0 +1,144 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Functions and types to interface with the system API available to application services
+//! executed natively, outside of a WebAssembly virtual machine.
+
+use crate::{native::runtime, views::ViewStorageContext};
+use futures::FutureExt;
+use linera_base::{
+    data_types::{Amount, BlockHeight, Timestamp},
+    identifiers::{ApplicationId, ChainId},
+};
+use linera_views::views::View;
+use serde::de::DeserializeOwned;
+use std::fmt;
+
+/// Loads the application state, without locking it for writes.
+pub(crate) async fn load<State>() -> State
+where
+    State: Default + DeserializeOwned,
+{
+    let bytes = runtime::call_async(|runtime| runtime.service().try_read_my_state()).await;
+    if bytes.is_empty() {
+        State::default()
+    } else {
+        bcs::from_bytes(&bytes).expect("Invalid application state")
+    }
+}
+
+/// Loads the application state (and locks it for writes).
+pub(crate) async fn lock_and_load_view<State: View<ViewStorageContext>>() -> State {
+    runtime::call_async(|runtime| runtime.service().lock_view_user_state()).await;
+    load_view_using::<State>().await
+}
+
+/// Unlocks the service state previously loaded.
+pub(crate) async fn unlock_view() {
+    runtime::call_async(|runtime| runtime.service().unlock_view_user_state()).await;
+}
+
+/// Helper function to load the service state or create a new one if it doesn't exist.
+pub(crate) async fn load_view_using<State: View<ViewStorageContext>>() -> State {
+    let context = ViewStorageContext::default();
+    State::load(context)
+        .await
+        .expect("Failed to load application state")
+}
+
+/// Retrieves the current chain ID.
+pub fn current_chain_id() -> ChainId {
+    runtime::with_runtime(|runtime| runtime.service().chain_id())
+}
+
+/// Retrieves the current application ID.
+pub fn current_application_id() -> ApplicationId {
+    runtime::with_runtime(|runtime| runtime.service().application_id())
+}
+
+/// Retrieves the current application parameters.
+pub(crate) fn current_application_parameters() -> Vec<u8> {
+    runtime::with_runtime(|runtime| runtime.service().application_parameters())
+}
+
+/// Retrieves the current system balance.
+pub fn current_system_balance() -> Amount {
+    runtime::with_runtime(|runtime| runtime.service().read_system_balance())
+}
+
+/// Retrieves the current system time, i.e. the timestamp of the latest block in this chain.
+pub fn current_system_time() -> Timestamp {
+    runtime::with_runtime(|runtime| runtime.service().read_system_timestamp())
+}
+
+/// Queries another application.
+pub(crate) async fn query_application(
+    application: ApplicationId,
+    argument: &[u8],
+) -> Result<Vec<u8>, String> {
+    let argument = argument.to_vec();
+    let response = runtime::call_async(move |runtime| {
+        async move {
+            runtime
+                .service()
+                .try_query_application(application, &argument)
+                .await
+        }
+        .boxed()
+    })
+    .await;
+    Ok(response)
+}
+
+/// Queries an application on another chain tracked by the local node.
+///
+/// Returns the response together with the height of the next block of the queried chain.
+pub(crate) async fn query_remote_application(
+    chain_id: ChainId,
+    application: ApplicationId,
+    argument: &[u8],
+) -> Result<(Vec<u8>, BlockHeight), String> {
+    let argument = argument.to_vec();
+    let remote_response = runtime::call_async(move |runtime| {
+        async move {
+            runtime
+                .service()
+                .try_query_remote_application(chain_id, application, &argument)
+                .await
+        }
+        .boxed()
+    })
+    .await;
+    Ok((remote_response.response, remote_response.height))
+}
+
+/// Queries the system state of another chain tracked by the local node.
+///
+/// Returns the JSON-encoded response together with the height of the next block of the
+/// queried chain.
+pub(crate) async fn query_remote_system(
+    chain_id: ChainId,
+    query: &[u8],
+) -> Result<(Vec<u8>, BlockHeight), String> {
+    let query = serde_json::from_slice(query).map_err(|error| error.to_string())?;
+    let remote_response = runtime::call_async(move |runtime| {
+        async move {
+            runtime
+                .service()
+                .try_query_remote_system(chain_id, query)
+                .await
+        }
+        .boxed()
+    })
+    .await;
+    let response =
+        serde_json::to_vec(&remote_response.response).map_err(|error| error.to_string())?;
+    Ok((response, remote_response.height))
+}
+
+/// Requests the host to log a message.
+///
+/// Useful for debugging locally, but may be ignored by validators.
+pub fn log(message: &fmt::Arguments<'_>, level: log::Level) {
+    log::log!(level, "{message}");
+}
@@ -7,7 +7,7 @@ use super::service_system_api as wit;
 use crate::views::ViewStorageContext;
 use futures::future;
 use linera_base::{
-    data_types::{Amount, Timestamp},
+    data_types::{Amount, BlockHeight, Timestamp},
     identifiers::{ApplicationId, ChainId},
 };
 use linera_views::views::{View, ViewError};
@@ -94,6 +94,33 @@ pub(crate) async fn query_application(
     future::poll_fn(|_context| future.poll().into()).await
 }
 
+/// Queries an application on another chain tracked by the local node.
+///
+/// Returns the response together with the height of the next block of the queried chain.
+pub(crate) async fn query_remote_application(
+    chain_id: ChainId,
+    application: ApplicationId,
+    argument: &[u8],
+) -> Result<(Vec<u8>, BlockHeight), String> {
+    let future =
+        wit::TryQueryRemoteApplication::new(chain_id.0.into(), application.into(), argument);
+
+    future::poll_fn(|_context| future.poll().into()).await
+}
+
+/// Queries the system state of another chain tracked by the local node.
+///
+/// Returns the JSON-encoded response together with the height of the next block of the
+/// queried chain.
+pub(crate) async fn query_remote_system(
+    chain_id: ChainId,
+    query: &[u8],
+) -> Result<(Vec<u8>, BlockHeight), String> {
+    let future = wit::TryQueryRemoteSystem::new(chain_id.0.into(), query);
+
+    future::poll_fn(|_context| future.poll().into()).await
+}
+
 /// Requests the host to log a message.
 ///
 /// Useful for debugging locally, but may be ignored by validators.
@@ -14,6 +14,7 @@ use linera_base::{
 use linera_chain::data_types::{
     Block, Certificate, HashedValue, IncomingMessage, LiteVote, SignatureAggregator,
 };
+use linera_core::worker::WorkerError;
 use linera_execution::{system::SystemOperation, Operation};
 use std::mem;
 
@@ -60,7 +61,10 @@ impl BlockBuilder {
                 chain_id,
                 incoming_messages: vec![],
                 operations: vec![],
+                independent_operations: false,
                 previous_block_hash,
+                previous_block_signatures: previous_block
+                    .map(|certificate| certificate.certificate_signatures(validator.committee())),
                 height,
                 authenticated_signer: Some(owner),
                 timestamp: Timestamp::from(0),
@@ -145,8 +149,8 @@ impl BlockBuilder {
     }
 
     /// Signs the prepared [`Block`] with the [`TestValidator`]'s keys and returns the resulting
-    /// [`Certificate`].
-    pub(crate) async fn sign(mut self) -> (Certificate, Vec<MessageId>) {
+    /// [`Certificate`], or the error if the block fails to execute.
+    pub(crate) async fn try_sign(mut self) -> Result<(Certificate, Vec<MessageId>), WorkerError> {
         self.collect_incoming_messages().await;
 
         let (executed_block, _) = self
@@ -154,8 +158,7 @@ impl BlockBuilder {
             .worker()
             .await
             .stage_block_execution(self.block)
-            .await
-            .expect("Failed to execute block");
+            .await?;
 
         let message_ids = (0..executed_block.messages.len() as u32)
             .map(|index| MessageId {
@@ -174,7 +177,7 @@ impl BlockBuilder {
             .expect("Failed to sign block")
             .expect("Committee has more than one test validator");
 
-        (certificate, message_ids)
+        Ok((certificate, message_ids))
     }
 
     /// Collects and adds the previously requested messages to this block.
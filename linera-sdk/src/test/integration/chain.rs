@@ -6,7 +6,7 @@
 //! This allows manipulating a test microchain.
 
 use super::{BlockBuilder, TestValidator};
-use crate::{ContractAbi, ServiceAbi};
+use crate::{native::NativeApplication, Contract, ContractAbi, ServiceAbi};
 use cargo_toml::Manifest;
 use linera_base::{
     crypto::{KeyPair, PublicKey},
@@ -14,7 +14,10 @@ use linera_base::{
     identifiers::{ApplicationId, BytecodeId, ChainDescription, ChainId, MessageId},
 };
 use linera_chain::data_types::Certificate;
-use linera_core::{data_types::ChainInfoQuery, worker::ValidatorWorker};
+use linera_core::{
+    data_types::ChainInfoQuery,
+    worker::{ValidatorWorker, WorkerError},
+};
 use linera_execution::{
     system::{SystemChannel, SystemExecutionError, SystemMessage, SystemOperation},
     Bytecode, Message, Query, Response,
@@ -75,6 +78,19 @@ impl ActiveChain {
     /// The `block_builder` parameter is a closure that should use the [`BlockBuilder`] parameter
     /// to provide the block's contents.
     pub async fn add_block(&self, block_builder: impl FnOnce(&mut BlockBuilder)) -> Vec<MessageId> {
+        self.try_add_block(block_builder)
+            .await
+            .expect("Failed to execute block")
+    }
+
+    /// Tries to add a block to this microchain.
+    ///
+    /// Like [`ActiveChain::add_block`], but returns the error if the block fails to execute,
+    /// leaving the microchain unchanged.
+    pub async fn try_add_block(
+        &self,
+        block_builder: impl FnOnce(&mut BlockBuilder),
+    ) -> Result<Vec<MessageId>, WorkerError> {
         let mut tip = self.tip.lock().await;
         let mut block = BlockBuilder::new(
             self.description.into(),
@@ -85,7 +101,7 @@ impl ActiveChain {
 
         block_builder(&mut block);
 
-        let (certificate, message_ids) = block.sign().await;
+        let (certificate, message_ids) = block.try_sign().await?;
 
         self.validator
             .worker()
@@ -96,7 +112,7 @@ impl ActiveChain {
 
         *tip = Some(certificate);
 
-        message_ids
+        Ok(message_ids)
     }
 
     /// Receives all queued messages in all inboxes of this microchain.
@@ -144,6 +160,40 @@ impl ActiveChain {
         Self::build_bytecodes_in(&repository_path).await;
         let (contract, service) = self.find_bytecodes_in(&repository_path).await;
 
+        self.publish_bytecodes(contract, service).await.with_abi()
+    }
+
+    /// Publishes an application compiled natively to this microchain.
+    ///
+    /// Empty bytecodes are published to obtain a bytecode ID, and applications created from it
+    /// execute the `Application` contract and the `Service` linked into the test binary, without
+    /// building them for WebAssembly. This allows debugging the application and measuring its
+    /// code coverage with native tools.
+    pub async fn publish_native_bytecode<Application, Service>(
+        &self,
+    ) -> BytecodeId<Application::Abi>
+    where
+        Application: Contract + 'static,
+        Service: crate::Service + 'static,
+    {
+        let bytecode_id = self
+            .publish_bytecodes(Bytecode::new(vec![]), Bytecode::new(vec![]))
+            .await;
+
+        self.validator
+            .worker()
+            .await
+            .storage_client()
+            .register_native_application(
+                bytecode_id,
+                Arc::new(NativeApplication::<Application, Service>::new()),
+            );
+
+        bytecode_id.with_abi()
+    }
+
+    /// Publishes the `contract` and `service` bytecodes to this microchain.
+    async fn publish_bytecodes(&self, contract: Bytecode, service: Bytecode) -> BytecodeId {
         let publish_messages = self
             .add_block(|block| {
                 block.with_system_operation(SystemOperation::PublishBytecode { contract, service });
@@ -157,7 +207,7 @@ impl ActiveChain {
         })
         .await;
 
-        BytecodeId::new(publish_messages[0]).with_abi()
+        BytecodeId::new(publish_messages[0])
     }
 
     /// Compiles the crate in the `repository` path.
@@ -334,6 +384,7 @@ impl ActiveChain {
                     parameters,
                     initialization_argument,
                     required_application_ids,
+                    storage_quota: None,
                 });
             })
             .await;
@@ -461,6 +512,23 @@ impl ActiveChain {
         application_id: ApplicationId<Abi>,
         query: Abi::Query,
     ) -> Abi::QueryResponse
+    where
+        Abi: ServiceAbi,
+    {
+        self.try_query(application_id, query)
+            .await
+            .expect("Failed to query application")
+    }
+
+    /// Tries to execute a `query` on an `application`'s state on this microchain.
+    ///
+    /// Returns the deserialized response from the `application`, or the error that made the
+    /// query fail.
+    pub async fn try_query<Abi>(
+        &self,
+        application_id: ApplicationId<Abi>,
+        query: Abi::Query,
+    ) -> Result<Abi::QueryResponse, WorkerError>
     where
         Abi: ServiceAbi,
     {
@@ -477,12 +545,11 @@ impl ActiveChain {
                     bytes: query_bytes,
                 },
             )
-            .await
-            .expect("Failed to query application");
+            .await?;
 
         match response {
             Response::User(bytes) => {
-                serde_json::from_slice(&bytes).expect("Failed to deserialize query response")
+                Ok(serde_json::from_slice(&bytes).expect("Failed to deserialize query response"))
             }
             Response::System(_) => unreachable!("User query returned a system response"),
         }
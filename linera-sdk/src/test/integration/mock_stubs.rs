@@ -9,6 +9,7 @@
 //! called.
 
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{Amount, Timestamp},
     identifiers::{ApplicationId, ChainId},
 };
@@ -48,11 +49,21 @@ pub fn mock_system_timestamp(_system_timestamp: impl Into<Option<Timestamp>>) {
     unreachable!("{ERROR_MESSAGE}");
 }
 
+/// Sets the mocked random seed.
+pub fn mock_random_seed(_random_seed: impl Into<Option<CryptoHash>>) {
+    unreachable!("{ERROR_MESSAGE}");
+}
+
 /// Returns all messages logged so far.
 pub fn log_messages() -> Vec<(log::Level, String)> {
     unreachable!("{ERROR_MESSAGE}");
 }
 
+/// Returns all events emitted so far, as pairs of topic and serialized value.
+pub fn emitted_events() -> Vec<(Vec<u8>, Vec<u8>)> {
+    unreachable!("{ERROR_MESSAGE}");
+}
+
 /// Sets the mocked application state.
 pub fn mock_application_state(_state: impl Into<Option<Vec<u8>>>) {
     unreachable!("{ERROR_MESSAGE}");
@@ -21,4 +21,7 @@ mod validator;
 #[cfg(feature = "test")]
 pub use self::mock_stubs::*;
 #[cfg(any(feature = "wasmer", feature = "wasmtime"))]
-pub use self::{block::BlockBuilder, chain::ActiveChain, validator::TestValidator};
+pub use {
+    self::{block::BlockBuilder, chain::ActiveChain, validator::TestValidator},
+    linera_execution::profiler::FuelProfiler,
+};
@@ -7,7 +7,7 @@
 //! it, and blocks can be added to each microchain individually.
 
 use super::ActiveChain;
-use crate::ContractAbi;
+use crate::{Contract, ContractAbi};
 use dashmap::DashMap;
 use linera_base::{
     crypto::KeyPair,
@@ -17,6 +17,7 @@ use linera_base::{
 use linera_core::worker::WorkerState;
 use linera_execution::{
     committee::{Committee, ValidatorName},
+    profiler::FuelProfiler,
     WasmRuntime,
 };
 use linera_storage::{MemoryStoreClient, Store, WallClock};
@@ -47,27 +48,13 @@ pub struct TestValidator {
 
 impl Default for TestValidator {
     fn default() -> Self {
-        let key_pair = KeyPair::generate();
-        let committee = Committee::make_simple(vec![ValidatorName(key_pair.public())]);
         let store = MemoryStoreClient::new(
             Some(WasmRuntime::default()),
             TEST_MEMORY_MAX_STREAM_QUERIES,
             WallClock,
         );
 
-        let worker = WorkerState::new(
-            "Single validator node".to_string(),
-            Some(key_pair.copy()),
-            store,
-        );
-
-        TestValidator {
-            key_pair,
-            committee,
-            worker: Arc::new(Mutex::new(worker)),
-            root_chain_counter: Arc::default(),
-            chains: Arc::default(),
-        }
+        TestValidator::with_store(store)
     }
 }
 
@@ -84,6 +71,48 @@ impl Clone for TestValidator {
 }
 
 impl TestValidator {
+    /// Creates a new [`TestValidator`] that attributes the fuel consumed by the contracts of
+    /// its applications to their functions using the `profiler`.
+    ///
+    /// ```rust
+    /// # use linera_sdk::test::*;
+    /// let profiler = FuelProfiler::default();
+    /// let validator = TestValidator::with_fuel_profiler(profiler.clone());
+    /// // Add blocks executing operations of the application being profiled...
+    /// let mut folded_stacks = Vec::new();
+    /// profiler.write_folded_stacks(&mut folded_stacks).unwrap();
+    /// ```
+    pub fn with_fuel_profiler(profiler: FuelProfiler) -> Self {
+        let store = MemoryStoreClient::new_with_fuel_profiler(
+            Some(WasmRuntime::default()),
+            TEST_MEMORY_MAX_STREAM_QUERIES,
+            WallClock,
+            profiler,
+        );
+
+        TestValidator::with_store(store)
+    }
+
+    /// Creates a new [`TestValidator`] using the provided `store`.
+    fn with_store(store: MemoryStoreClient<WallClock>) -> Self {
+        let key_pair = KeyPair::generate();
+        let committee = Committee::make_simple(vec![ValidatorName(key_pair.public())]);
+
+        let worker = WorkerState::new(
+            "Single validator node".to_string(),
+            Some(key_pair.copy()),
+            store,
+        );
+
+        TestValidator {
+            key_pair,
+            committee,
+            worker: Arc::new(Mutex::new(worker)),
+            root_chain_counter: Arc::default(),
+            chains: Arc::default(),
+        }
+    }
+
     /// Creates a new [`TestValidator`] with a single microchain with the bytecode of the crate
     /// calling this method published on it.
     ///
@@ -122,6 +151,59 @@ impl TestValidator {
         (validator, application_id)
     }
 
+    /// Creates a new [`TestValidator`] with a single microchain with the natively compiled
+    /// `Application` contract and `Service` published on it.
+    ///
+    /// Returns the new [`TestValidator`] and the [`BytecodeId`] of the published bytecode.
+    pub async fn with_native_bytecode<Application, Service>(
+    ) -> (TestValidator, BytecodeId<Application::Abi>)
+    where
+        Application: Contract + 'static,
+        Service: crate::Service + 'static,
+    {
+        let validator = TestValidator::default();
+        let publisher = validator.new_chain().await;
+
+        let bytecode_id = publisher
+            .publish_native_bytecode::<Application, Service>()
+            .await;
+
+        (validator, bytecode_id)
+    }
+
+    /// Creates a new [`TestValidator`] with the natively compiled `Application` contract and
+    /// `Service` created as an application on a chain.
+    ///
+    /// The bytecode is first published on one microchain, then the application is created on
+    /// another microchain. The application is executed natively, so it can be debugged without
+    /// building it for WebAssembly.
+    ///
+    /// Returns the new [`TestValidator`] and the [`ApplicationId`] of the created application.
+    pub async fn with_native_application<Application, Service>(
+        parameters: <Application::Abi as ContractAbi>::Parameters,
+        initialization_argument: <Application::Abi as ContractAbi>::InitializationArgument,
+    ) -> (TestValidator, ApplicationId<Application::Abi>)
+    where
+        Application: Contract + 'static,
+        Service: crate::Service + 'static,
+    {
+        let (validator, bytecode_id) =
+            TestValidator::with_native_bytecode::<Application, Service>().await;
+
+        let mut creator = validator.new_chain().await;
+
+        let application_id = creator
+            .create_application::<Application::Abi>(
+                bytecode_id,
+                parameters,
+                initialization_argument,
+                vec![],
+            )
+            .await;
+
+        (validator, application_id)
+    }
+
     /// Returns the locked [`WorkerState`] of this validator.
     pub(crate) async fn worker(&self) -> MutexGuard<WorkerState<MemoryStoreClient<WallClock>>> {
         self.worker.lock().await
@@ -19,6 +19,7 @@ mod conversions_to_wit;
 use self::mock_system_api as wit;
 use futures::FutureExt;
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{Amount, Timestamp},
     identifiers::{ApplicationId, ChainId},
 };
@@ -33,7 +34,9 @@ static mut MOCK_APPLICATION_ID: Option<ApplicationId> = None;
 static mut MOCK_APPLICATION_PARAMETERS: Option<Vec<u8>> = None;
 static mut MOCK_SYSTEM_BALANCE: Option<Amount> = None;
 static mut MOCK_SYSTEM_TIMESTAMP: Option<Timestamp> = None;
+static mut MOCK_RANDOM_SEED: Option<CryptoHash> = None;
 static mut MOCK_LOG_COLLECTOR: Vec<(log::Level, String)> = Vec::new();
+static mut MOCK_EVENT_COLLECTOR: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
 static mut MOCK_APPLICATION_STATE: Option<Vec<u8>> = None;
 static mut MOCK_APPLICATION_STATE_LOCKED: bool = false;
 static mut MOCK_KEY_VALUE_STORE: Option<MemoryContext<()>> = None;
@@ -66,11 +69,21 @@ pub fn mock_system_timestamp(system_timestamp: impl Into<Option<Timestamp>>) {
     unsafe { MOCK_SYSTEM_TIMESTAMP = system_timestamp.into() };
 }
 
+/// Sets the mocked random seed.
+pub fn mock_random_seed(random_seed: impl Into<Option<CryptoHash>>) {
+    unsafe { MOCK_RANDOM_SEED = random_seed.into() };
+}
+
 /// Returns all messages logged so far.
 pub fn log_messages() -> Vec<(log::Level, String)> {
     unsafe { MOCK_LOG_COLLECTOR.clone() }
 }
 
+/// Returns all events emitted so far, as pairs of topic and serialized value.
+pub fn emitted_events() -> Vec<(Vec<u8>, Vec<u8>)> {
+    unsafe { MOCK_EVENT_COLLECTOR.clone() }
+}
+
 /// Sets the mocked application state.
 pub fn mock_application_state(state: impl Into<Option<Vec<u8>>>) {
     unsafe { MOCK_APPLICATION_STATE = state.into() };
@@ -144,10 +157,23 @@ impl wit::MockSystemApi for MockSystemApi {
             .micros()
     }
 
+    fn mocked_random_seed() -> wit::CryptoHash {
+        unsafe { MOCK_RANDOM_SEED }
+            .expect(
+                "Unexpected call to the `random_seed` system API. \
+                Please call `mock_random_seed` first",
+            )
+            .into()
+    }
+
     fn mocked_log(message: String, level: wit::LogLevel) {
         unsafe { MOCK_LOG_COLLECTOR.push((level.into(), message)) }
     }
 
+    fn mocked_emit_event(topic: Vec<u8>, value: Vec<u8>) {
+        unsafe { MOCK_EVENT_COLLECTOR.push((topic, value)) }
+    }
+
     fn mocked_load() -> Vec<u8> {
         unsafe { MOCK_APPLICATION_STATE.clone() }.expect(
             "Unexpected call to the `load` system API. \
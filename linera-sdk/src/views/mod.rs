@@ -4,6 +4,7 @@
 //! Helper types for using [`linera_views`] to store application state.
 
 mod conversions_from_wit;
+#[cfg_attr(not(target_arch = "wasm32"), path = "native_system_api.rs")]
 mod system_api;
 
 pub use self::system_api::ViewStorageContext;
@@ -0,0 +1,86 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Functions and types to interface with the system API available to application views
+//! executed natively, outside of a WebAssembly virtual machine.
+
+use crate::native::runtime::{self, NativeRuntime};
+use async_trait::async_trait;
+use linera_views::{
+    batch::Batch,
+    common::{ContextFromDb, KeyValueStoreClient},
+    views::ViewError,
+};
+
+/// A type to interface with the key value storage provided to applications.
+#[derive(Default, Clone)]
+pub struct KeyValueStore;
+
+#[async_trait]
+impl KeyValueStoreClient for KeyValueStore {
+    // The KeyValueStoreClient of the system_api does not have limits
+    // on the size of its values.
+    const MAX_VALUE_SIZE: usize = usize::MAX;
+    type Error = ViewError;
+    type Keys = Vec<Vec<u8>>;
+    type KeyValues = Vec<(Vec<u8>, Vec<u8>)>;
+
+    fn max_stream_queries(&self) -> usize {
+        1
+    }
+
+    async fn read_key_bytes(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+        let key = key.to_vec();
+        Ok(runtime::call_async(move |runtime| match runtime {
+            NativeRuntime::Contract(runtime) => runtime.read_key_bytes(key),
+            NativeRuntime::Service(runtime) => runtime.read_key_bytes(key),
+        })
+        .await)
+    }
+
+    async fn read_multi_key_bytes(
+        &self,
+        keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<Option<Vec<u8>>>, Self::Error> {
+        let mut results = Vec::new();
+        for key in keys {
+            let value = self.read_key_bytes(&key).await?;
+            results.push(value);
+        }
+        Ok(results)
+    }
+
+    async fn find_keys_by_prefix(&self, key_prefix: &[u8]) -> Result<Self::Keys, ViewError> {
+        let key_prefix = key_prefix.to_vec();
+        Ok(runtime::call_async(move |runtime| match runtime {
+            NativeRuntime::Contract(runtime) => runtime.find_keys_by_prefix(key_prefix),
+            NativeRuntime::Service(runtime) => runtime.find_keys_by_prefix(key_prefix),
+        })
+        .await)
+    }
+
+    async fn find_key_values_by_prefix(
+        &self,
+        key_prefix: &[u8],
+    ) -> Result<Self::KeyValues, ViewError> {
+        let key_prefix = key_prefix.to_vec();
+        Ok(runtime::call_async(move |runtime| match runtime {
+            NativeRuntime::Contract(runtime) => runtime.find_key_values_by_prefix(key_prefix),
+            NativeRuntime::Service(runtime) => runtime.find_key_values_by_prefix(key_prefix),
+        })
+        .await)
+    }
+
+    async fn write_batch(&self, batch: Batch, _base_key: &[u8]) -> Result<(), ViewError> {
+        runtime::call_async(move |runtime| runtime.contract().write_batch_and_unlock(batch)).await;
+        Ok(())
+    }
+
+    async fn clear_journal(&self, _base_key: &[u8]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Implementation of [`linera_views::common::Context`] to be used for data storage
+/// by Linera applications.
+pub type ViewStorageContext = ContextFromDb<(), KeyValueStore>;
@@ -0,0 +1,389 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Integration tests for applications executed natively, without a WebAssembly virtual
+//! machine.
+
+#![cfg(all(
+    any(feature = "wasmer", feature = "wasmtime"),
+    not(target_arch = "wasm32")
+))]
+
+use async_trait::async_trait;
+use linera_chain::ChainError;
+use linera_core::worker::WorkerError;
+use linera_execution::{ExecutionError, MAXIMUM_REMOTE_QUERY_DEPTH};
+use linera_sdk::{
+    base::{
+        ApplicationId, ChainId, ContractAbi, MessageId, ServiceAbi, SessionId, WithContractAbi,
+        WithServiceAbi,
+    },
+    service,
+    test::{ActiveChain, TestValidator},
+    ApplicationCallResult, CalleeContext, Contract, ExecutionResult, MessageContext,
+    OperationContext, QueryContext, Service, SessionCallResult, SimpleStateStorage,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// A counter executed natively.
+#[derive(Default, Deserialize, Serialize)]
+pub struct NativeCounter {
+    value: u64,
+}
+
+/// The ABI of the [`NativeCounter`] application.
+pub struct NativeCounterAbi;
+
+impl ContractAbi for NativeCounterAbi {
+    type InitializationArgument = u64;
+    type Parameters = ();
+    type Operation = CounterOperation;
+    type ApplicationCall = u64;
+    type Message = ();
+    type SessionCall = ();
+    type Response = u64;
+    type SessionState = ();
+}
+
+impl ServiceAbi for NativeCounterAbi {
+    type Query = CounterQuery;
+    type QueryResponse = u64;
+    type Parameters = ();
+}
+
+/// The queries of the [`NativeCounter`] application.
+#[derive(Debug, Deserialize, Serialize)]
+pub enum CounterQuery {
+    /// The chains to query the counter on, one after the other, before returning the value of
+    /// the counter on the last one.
+    Value(Vec<ChainId>),
+    /// Queries the system state of another chain, and returns the height of its next block.
+    NextBlockHeight(ChainId),
+}
+
+/// The operations of the [`NativeCounter`] application.
+#[derive(Debug, Deserialize, Serialize)]
+pub enum CounterOperation {
+    /// Increments the counter.
+    Increment(u64),
+    /// Increments the counter, then increments the counter of another application.
+    IncrementWith(u64, ApplicationId),
+}
+
+impl WithContractAbi for NativeCounter {
+    type Abi = NativeCounterAbi;
+}
+
+impl WithServiceAbi for NativeCounter {
+    type Abi = NativeCounterAbi;
+}
+
+#[async_trait]
+impl Contract for NativeCounter {
+    type Error = Error;
+    type Storage = SimpleStateStorage<Self>;
+
+    async fn initialize(
+        &mut self,
+        _context: &OperationContext,
+        value: u64,
+    ) -> Result<ExecutionResult<Self::Message>, Self::Error> {
+        self.value = value;
+        Ok(ExecutionResult::default())
+    }
+
+    async fn execute_operation(
+        &mut self,
+        _context: &OperationContext,
+        operation: CounterOperation,
+    ) -> Result<ExecutionResult<Self::Message>, Self::Error> {
+        match operation {
+            CounterOperation::Increment(increment) => self.value += increment,
+            CounterOperation::IncrementWith(increment, application_id) => {
+                self.value += increment;
+                self.call_application(
+                    true,
+                    application_id.with_abi::<NativeCounterAbi>(),
+                    &increment,
+                    vec![],
+                )
+                .await?;
+            }
+        }
+        Ok(ExecutionResult::default())
+    }
+
+    async fn execute_message(
+        &mut self,
+        _context: &MessageContext,
+        _message: (),
+    ) -> Result<ExecutionResult<Self::Message>, Self::Error> {
+        Err(Error::NotSupported)
+    }
+
+    async fn handle_application_call(
+        &mut self,
+        _context: &CalleeContext,
+        increment: u64,
+        _forwarded_sessions: Vec<SessionId>,
+    ) -> Result<ApplicationCallResult<Self::Message, Self::Response, Self::SessionState>, Self::Error>
+    {
+        self.value += increment;
+        Ok(ApplicationCallResult {
+            value: self.value,
+            ..ApplicationCallResult::default()
+        })
+    }
+
+    async fn handle_session_call(
+        &mut self,
+        _context: &CalleeContext,
+        _state: Self::SessionState,
+        _call: (),
+        _forwarded_sessions: Vec<SessionId>,
+    ) -> Result<SessionCallResult<Self::Message, Self::Response, Self::SessionState>, Self::Error>
+    {
+        Err(Error::NotSupported)
+    }
+}
+
+#[async_trait]
+impl Service for NativeCounter {
+    type Error = Error;
+    type Storage = SimpleStateStorage<Self>;
+
+    async fn handle_query(
+        self: Arc<Self>,
+        _context: &QueryContext,
+        query: CounterQuery,
+    ) -> Result<u64, Self::Error> {
+        let mut chain_ids = match query {
+            CounterQuery::Value(chain_ids) => chain_ids,
+            CounterQuery::NextBlockHeight(chain_id) => {
+                let remote_response =
+                    Self::query_remote_system::<_, serde_json::Value>(chain_id, &"Balance").await?;
+                let queried_id = &remote_response.response["Balance"]["chain_id"];
+                assert_eq!(queried_id, &serde_json::to_value(chain_id)?);
+                return Ok(remote_response.height.0);
+            }
+        };
+        if chain_ids.is_empty() {
+            return Ok(self.value);
+        }
+        let chain_id = chain_ids.remove(0);
+        let application_id = service::system_api::current_application_id();
+        let remote_response = Self::query_remote_application(
+            chain_id,
+            application_id.with_abi::<NativeCounterAbi>(),
+            &CounterQuery::Value(chain_ids),
+        )
+        .await?;
+        Ok(remote_response.response)
+    }
+}
+
+/// An error that can occur during the execution of the [`NativeCounter`].
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The counter only supports operations and application calls.
+    #[error("Counter application only supports operations and application calls")]
+    NotSupported,
+
+    /// Failed to deserialize BCS bytes
+    #[error("Failed to deserialize BCS bytes")]
+    BcsError(#[from] bcs::Error),
+
+    /// Failed to deserialize JSON string
+    #[error("Failed to deserialize JSON string")]
+    JsonError(#[from] serde_json::Error),
+
+    /// Failed to query another application
+    #[error("Failed to query another application: {0}")]
+    QueryError(String),
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::QueryError(message)
+    }
+}
+
+/// Tests that a natively executed contract updates its state, and that the natively executed
+/// service reads it back.
+#[tokio::test(flavor = "multi_thread")]
+async fn native_operation_and_query() {
+    let (validator, application_id) =
+        TestValidator::with_native_application::<NativeCounter, NativeCounter>((), 42).await;
+    let chain = validator.get_chain(&application_id.creation.chain_id);
+
+    chain
+        .add_block(|block| {
+            block.with_operation(application_id, CounterOperation::Increment(15));
+        })
+        .await;
+
+    assert_eq!(
+        chain
+            .query(application_id, CounterQuery::Value(vec![]))
+            .await,
+        57
+    );
+}
+
+/// Tests that a failing system API call aborts the natively executed contract with an error,
+/// instead of a panic, and that the block is rejected without changing the state.
+#[tokio::test(flavor = "multi_thread")]
+async fn native_failing_system_api_call() {
+    let (validator, application_id) =
+        TestValidator::with_native_application::<NativeCounter, NativeCounter>((), 42).await;
+    let chain = validator.get_chain(&application_id.creation.chain_id);
+
+    let unknown_application_id = ApplicationId {
+        bytecode_id: application_id.bytecode_id.forget_abi(),
+        creation: MessageId {
+            index: application_id.creation.index + 1,
+            ..application_id.creation
+        },
+    };
+    let result = chain
+        .try_add_block(|block| {
+            block.with_operation(
+                application_id,
+                CounterOperation::IncrementWith(15, unknown_application_id),
+            );
+        })
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(
+        chain
+            .query(application_id, CounterQuery::Value(vec![]))
+            .await,
+        42
+    );
+}
+
+/// Creates `count` new chains on the `validator`, with the `application_id` registered on them.
+async fn chains_with_application(
+    validator: &TestValidator,
+    application_id: ApplicationId<NativeCounterAbi>,
+    count: usize,
+) -> Vec<ActiveChain> {
+    let mut chains = Vec::with_capacity(count);
+    for _ in 0..count {
+        let chain = validator.new_chain().await;
+        chain.register_application(application_id).await;
+        chains.push(chain);
+    }
+    chains
+}
+
+/// Returns the [`ExecutionError`] that made a query fail.
+fn execution_error(error: WorkerError) -> ExecutionError {
+    match error {
+        WorkerError::ChainError(error) => match *error {
+            ChainError::ExecutionError(error, _) => error,
+            error => panic!("Unexpected chain error: {error}"),
+        },
+        error => panic!("Unexpected worker error: {error}"),
+    }
+}
+
+/// Tests that a service queries the state of the same application on another chain.
+#[tokio::test(flavor = "multi_thread")]
+async fn native_remote_query() {
+    let (validator, application_id) =
+        TestValidator::with_native_application::<NativeCounter, NativeCounter>((), 42).await;
+    let creator_id = application_id.creation.chain_id;
+    let chains = chains_with_application(&validator, application_id, 1).await;
+
+    assert_eq!(
+        chains[0]
+            .query(application_id, CounterQuery::Value(vec![creator_id]))
+            .await,
+        42
+    );
+}
+
+/// Tests that a service queries the system state of another chain.
+#[tokio::test(flavor = "multi_thread")]
+async fn native_remote_system_query() {
+    let (validator, application_id) =
+        TestValidator::with_native_application::<NativeCounter, NativeCounter>((), 42).await;
+    let creator = validator.get_chain(&application_id.creation.chain_id);
+    let chains = chains_with_application(&validator, application_id, 1).await;
+
+    let height = chains[0]
+        .query(application_id, CounterQuery::NextBlockHeight(creator.id()))
+        .await;
+    assert_eq!(height, creator.get_tip_height().await.0 + 1);
+}
+
+/// Tests that remote queries that would query a chain that is waiting for them fail instead of
+/// deadlocking.
+#[tokio::test(flavor = "multi_thread")]
+async fn native_remote_query_cycle() {
+    let (validator, application_id) =
+        TestValidator::with_native_application::<NativeCounter, NativeCounter>((), 42).await;
+    let creator = validator.get_chain(&application_id.creation.chain_id);
+    let chains = chains_with_application(&validator, application_id, 1).await;
+
+    let error = creator
+        .try_query(application_id, CounterQuery::Value(vec![creator.id()]))
+        .await
+        .expect_err("Querying the queried chain should fail");
+    assert!(matches!(
+        execution_error(error),
+        ExecutionError::RemoteQueryCycle(chain_id) if chain_id == creator.id()
+    ));
+
+    let error = creator
+        .try_query(
+            application_id,
+            CounterQuery::Value(vec![chains[0].id(), creator.id()]),
+        )
+        .await
+        .expect_err("Querying a chain waiting for the query should fail");
+    assert!(matches!(
+        execution_error(error),
+        ExecutionError::RemoteQueryCycle(chain_id) if chain_id == creator.id()
+    ));
+}
+
+/// Tests that remote queries can only be nested up to [`MAXIMUM_REMOTE_QUERY_DEPTH`] times.
+#[tokio::test(flavor = "multi_thread")]
+async fn native_remote_query_depth() {
+    let (validator, application_id) =
+        TestValidator::with_native_application::<NativeCounter, NativeCounter>((), 42).await;
+    let creator_id = application_id.creation.chain_id;
+    let chains =
+        chains_with_application(&validator, application_id, MAXIMUM_REMOTE_QUERY_DEPTH).await;
+    let (first, others) = chains.split_first().expect("Missing chains");
+
+    let mut path = others
+        .iter()
+        .map(|chain| chain.id())
+        .chain([creator_id])
+        .collect::<Vec<_>>();
+    assert_eq!(path.len(), MAXIMUM_REMOTE_QUERY_DEPTH);
+    assert_eq!(
+        first
+            .query(application_id, CounterQuery::Value(path.clone()))
+            .await,
+        42
+    );
+
+    path.insert(0, first.id());
+    let last_chain = validator.new_chain().await;
+    last_chain.register_application(application_id).await;
+    let error = last_chain
+        .try_query(application_id, CounterQuery::Value(path))
+        .await
+        .expect_err("Nesting too many remote queries should fail");
+    assert!(matches!(
+        execution_error(error),
+        ExecutionError::ExcessiveRemoteQueryDepth
+    ));
+}
@@ -10,7 +10,9 @@
 
 use futures::FutureExt;
 use linera_sdk::{
-    base::{Amount, ApplicationId, BlockHeight, BytecodeId, ChainId, MessageId, Timestamp},
+    base::{
+        Amount, ApplicationId, BlockHeight, BytecodeId, ChainId, CryptoHash, MessageId, Timestamp,
+    },
     contract, service, test, ContractLogger, ServiceLogger,
 };
 use linera_views::{
@@ -99,6 +101,16 @@ fn mock_system_timestamp() {
     assert_eq!(service::system_api::current_system_time(), timestamp);
 }
 
+/// Test if the random seed getter API is mocked successfully.
+#[webassembly_test]
+fn mock_random_seed() {
+    let random_seed = CryptoHash::from([4, 5, 6, 7]);
+
+    test::mock_random_seed(random_seed);
+
+    assert_eq!(contract::system_api::current_random_seed(), random_seed);
+}
+
 /// Test if messages logged by a contract can be inspected.
 #[webassembly_test]
 fn mock_contract_log() {
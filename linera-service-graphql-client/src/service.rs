@@ -15,8 +15,12 @@ mod types {
     use serde::{Deserialize, Serialize};
     use serde_json::Value;
 
+    pub type CertificateSignatures = Value;
     pub type Epoch = Value;
+    pub type GenericApplicationId = Value;
     pub type Message = Value;
+    pub type MessageAction = Value;
+    pub type MessageKind = Value;
     pub type Operation = Value;
     pub type Event = Value;
     pub type Origin = Value;
@@ -48,9 +52,12 @@ mod types {
 
 #[cfg(not(target_arch = "wasm32"))]
 mod types {
-    pub use linera_chain::data_types::{Event, Origin};
+    pub use linera_chain::data_types::{CertificateSignatures, Event, MessageAction, Origin};
     pub use linera_core::worker::{Notification, Reason};
-    pub use linera_execution::{committee::Epoch, Message, Operation, UserApplicationDescription};
+    pub use linera_execution::{
+        committee::Epoch, GenericApplicationId, Message, MessageKind, Operation,
+        UserApplicationDescription,
+    };
 }
 
 pub use types::*;
@@ -107,12 +114,23 @@ pub struct Transfer;
 #[cfg(not(target_arch = "wasm32"))]
 mod from {
     use super::*;
-    use linera_chain::data_types::{ExecutedBlock, HashedValue, IncomingMessage, OutgoingMessage};
+    use linera_chain::data_types::{
+        ApplicationEvent, ExecutedBlock, FailedOperation, HashedValue, IncomingMessage,
+        OutgoingMessage,
+    };
 
     impl From<block::BlockBlockValueExecutedBlockBlockIncomingMessages> for IncomingMessage {
         fn from(val: block::BlockBlockValueExecutedBlockBlockIncomingMessages) -> Self {
-            let block::BlockBlockValueExecutedBlockBlockIncomingMessages { origin, event } = val;
-            IncomingMessage { origin, event }
+            let block::BlockBlockValueExecutedBlockBlockIncomingMessages {
+                origin,
+                event,
+                action,
+            } = val;
+            IncomingMessage {
+                origin,
+                event,
+                action,
+            }
         }
     }
 
@@ -123,10 +141,12 @@ mod from {
                 epoch,
                 incoming_messages,
                 operations,
+                independent_operations,
                 height,
                 timestamp,
                 authenticated_signer,
                 previous_block_hash,
+                previous_block_signatures,
             } = val;
             let incoming_messages = incoming_messages
                 .into_iter()
@@ -137,10 +157,12 @@ mod from {
                 epoch,
                 incoming_messages,
                 operations,
+                independent_operations,
                 height,
                 timestamp,
                 authenticated_signer,
                 previous_block_hash,
+                previous_block_signatures,
             }
         }
     }
@@ -151,31 +173,72 @@ mod from {
                 destination,
                 authenticated_signer,
                 is_skippable,
+                kind,
+                grant,
                 message,
             } = val;
             OutgoingMessage {
                 destination,
                 authenticated_signer,
                 is_skippable,
+                kind,
+                grant,
                 message,
             }
         }
     }
 
+    impl From<block::BlockBlockValueExecutedBlockEvents> for ApplicationEvent {
+        fn from(val: block::BlockBlockValueExecutedBlockEvents) -> Self {
+            let block::BlockBlockValueExecutedBlockEvents {
+                application_id,
+                transaction_index,
+                topic,
+                value,
+            } = val;
+            ApplicationEvent {
+                application_id,
+                transaction_index: transaction_index as u32,
+                topic: topic.into_iter().map(|byte| byte as u8).collect(),
+                value: value.into_iter().map(|byte| byte as u8).collect(),
+            }
+        }
+    }
+
+    impl From<block::BlockBlockValueExecutedBlockFailedOperations> for FailedOperation {
+        fn from(val: block::BlockBlockValueExecutedBlockFailedOperations) -> Self {
+            let block::BlockBlockValueExecutedBlockFailedOperations { index, error, fees } = val;
+            FailedOperation {
+                index: index as u32,
+                error,
+                fees,
+            }
+        }
+    }
+
     impl From<block::BlockBlockValueExecutedBlock> for ExecutedBlock {
         fn from(val: block::BlockBlockValueExecutedBlock) -> Self {
             let block::BlockBlockValueExecutedBlock {
                 block,
                 messages,
                 message_counts,
+                events,
+                failed_operations,
                 state_hash,
             } = val;
             let messages: Vec<OutgoingMessage> =
                 messages.into_iter().map(OutgoingMessage::from).collect();
+            let events = events.into_iter().map(ApplicationEvent::from).collect();
+            let failed_operations = failed_operations
+                .into_iter()
+                .map(FailedOperation::from)
+                .collect();
             ExecutedBlock {
                 block: block.into(),
                 messages,
                 message_counts: message_counts.into_iter().map(|c| c as u32).collect(),
+                events,
+                failed_operations,
                 state_hash,
             }
         }
@@ -32,8 +32,16 @@ pub struct ChainListenerConfig {
     /// Wait after processing any notification (useful for rate limiting).
     #[structopt(long = "listener-delay-after-ms", default_value = "0")]
     pub delay_after_ms: u64,
+
+    /// Don't request leader timeouts when the round leader of a multi-owner chain doesn't
+    /// propose a block in time.
+    #[structopt(long = "listener-skip-leader-timeouts")]
+    pub skip_leader_timeouts: bool,
 }
 
+/// How long to wait before requesting a leader timeout again, if the previous request failed.
+const LEADER_TIMEOUT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
 #[async_trait]
 pub trait ClientContext<P: ValidatorNodeProvider> {
     fn wallet_state(&self) -> &WalletState;
@@ -147,7 +155,35 @@ where
                 );
             }
         }
-        while let Some(notification) = stream.next().await {
+        let mut next_timeout_request = Timestamp::from(0);
+        loop {
+            let deadline = if config.skip_leader_timeouts {
+                None
+            } else {
+                Self::leader_timeout_deadline(&client)
+                    .await
+                    .map(|deadline| deadline.max(next_timeout_request))
+            };
+            let notification = match deadline {
+                None => stream.next().await,
+                Some(deadline) => {
+                    let delay = deadline.saturating_diff_micros(Timestamp::now());
+                    tokio::select! {
+                        notification = stream.next() => notification,
+                        () = tokio::time::sleep(Duration::from_micros(delay)) => {
+                            if let Err(e) = client.lock().await.request_leader_timeout().await {
+                                warn!("Failed to request a leader timeout: {:?}", e);
+                                next_timeout_request =
+                                    Timestamp::now().saturating_add(LEADER_TIMEOUT_RETRY_DELAY);
+                            }
+                            continue;
+                        }
+                    }
+                }
+            };
+            let Some(notification) = notification else {
+                break;
+            };
             if !tracker.is_new(&notification) {
                 continue;
             }
@@ -201,6 +237,18 @@ where
         Ok(())
     }
 
+    /// Returns when the current round of the chain times out, if we should then request a
+    /// leader timeout.
+    async fn leader_timeout_deadline(client: &Mutex<ChainClient<P, S>>) -> Option<Timestamp> {
+        match client.lock().await.leader_timeout_deadline().await {
+            Ok(deadline) => deadline,
+            Err(e) => {
+                warn!("Failed to read the round timeout: {:?}", e);
+                None
+            }
+        }
+    }
+
     async fn handle_notification(client: &mut ChainClient<P, S>, notification: Notification) {
         match &notification.reason {
             Reason::NewBlock { .. } => {
@@ -16,7 +16,7 @@ use linera_base::{
     data_types::{Amount, RoundNumber},
     identifiers::{ApplicationId, BytecodeId, ChainId, MessageId, Owner},
 };
-use linera_execution::Bytecode;
+use linera_execution::{Bytecode, SystemResponse};
 use serde::{de::DeserializeOwned, ser::Serialize};
 use serde_json::{json, Value};
 use std::{
@@ -309,6 +309,23 @@ impl ClientWrapper {
         bail!("Failed to start faucet");
     }
 
+    /// Runs `linera query-system`.
+    pub async fn query_system(
+        &self,
+        query: &str,
+        chain_id: Option<ChainId>,
+    ) -> Result<SystemResponse> {
+        let mut command = self.command().await?;
+        command.arg("query-system").arg(query);
+        if let Some(chain_id) = chain_id {
+            command.args(["--chain-id", &chain_id.to_string()]);
+        }
+        let stdout = command.spawn_and_wait_for_stdout().await?;
+        let response = serde_json::from_str(&stdout)
+            .context("error while parsing the result of `linera query-system`")?;
+        Ok(response)
+    }
+
     /// Runs `linera query-balance`.
     pub async fn query_balance(&self, chain_id: ChainId) -> Result<Amount> {
         let stdout = self
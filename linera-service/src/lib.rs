@@ -12,5 +12,6 @@ pub mod grpc_proxy;
 pub mod node_service;
 pub mod project;
 pub mod prometheus_server;
+pub mod replay;
 pub mod storage;
 pub mod util;
@@ -8,7 +8,7 @@ use chrono::{DateTime, Utc};
 use colored::Colorize;
 use futures::{lock::Mutex, StreamExt};
 use linera_base::{
-    crypto::{CryptoRng, KeyPair, PublicKey},
+    crypto::{CryptoHash, CryptoRng, KeyPair, PublicKey, SignatureScheme},
     data_types::{Amount, BlockHeight, RoundNumber, Timestamp},
     identifiers::{BytecodeId, ChainDescription, ChainId, MessageId},
 };
@@ -17,16 +17,18 @@ use linera_core::{
     client::{ChainClient, ChainClientBuilder},
     data_types::ChainInfoQuery,
     local_node::LocalNodeClient,
+    message_selection::MessageSelection,
     node::ValidatorNodeProvider,
     notifier::Notifier,
     tracker::NotificationTracker,
     worker::WorkerState,
 };
 use linera_execution::{
-    committee::{Committee, ValidatorName, ValidatorState},
+    committee::{Committee, ValidatorChange, ValidatorName},
     policy::ResourceControlPolicy,
-    system::{Account, UserData},
-    Bytecode, ChainOwnership, UserApplicationId, WasmRuntime, WithWasmDefault,
+    system::{Account, Recipient, UserData},
+    ApprovalScope, ApprovalThreshold, Bytecode, ChainOwnership, GenericApplicationId, SystemQuery,
+    TimeoutBackoff, TimeoutConfig, UserApplicationId, WasmRuntime, WithWasmDefault,
 };
 use linera_rpc::node_provider::{NodeOptions, NodeProvider};
 use linera_service::{
@@ -39,6 +41,7 @@ use linera_service::{
     faucet::FaucetService,
     node_service::NodeService,
     project::{self, Project},
+    replay,
     storage::{full_initialize_storage, run_with_storage, Runnable, StorageConfig},
 };
 use linera_storage::Store;
@@ -83,6 +86,9 @@ struct ClientContext {
     notification_retry_delay: Duration,
     notification_retries: u32,
     wait_for_outgoing_messages: bool,
+    max_stream_queries: usize,
+    independent_operations: bool,
+    message_selection: MessageSelection,
     prng: Box<dyn CryptoRng>,
 }
 
@@ -159,6 +165,18 @@ impl ClientContext {
         let cross_chain_delay = Duration::from_micros(options.cross_chain_delay_ms);
         let notification_retry_delay = Duration::from_micros(options.notification_retry_delay_us);
         let prng = wallet_state.make_prng();
+        let message_selection = if options.prioritized_applications.is_empty() {
+            options.message_selection.clone().unwrap_or_default()
+        } else {
+            MessageSelection::ByApplicationPriority(
+                options
+                    .prioritized_applications
+                    .iter()
+                    .copied()
+                    .map(GenericApplicationId::User)
+                    .collect(),
+            )
+        };
 
         let node_options = NodeOptions {
             send_timeout,
@@ -182,6 +200,9 @@ impl ClientContext {
             notification_retry_delay,
             notification_retries: options.notification_retries,
             wait_for_outgoing_messages: options.wait_for_outgoing_messages,
+            max_stream_queries: options.max_stream_queries,
+            independent_operations: options.independent_operations,
+            message_selection,
             prng,
         }
     }
@@ -258,7 +279,7 @@ impl ClientContext {
             .map(|kp| kp.copy())
             .into_iter()
             .collect();
-        self.chain_client_builder.build(
+        let mut chain_client = self.chain_client_builder.build(
             chain_id,
             known_key_pairs,
             storage,
@@ -266,7 +287,10 @@ impl ClientContext {
             chain.block_hash,
             chain.timestamp,
             chain.next_block_height,
-        )
+        );
+        chain_client.set_independent_operations(self.independent_operations);
+        chain_client.set_message_selection(self.message_selection.clone());
+        chain_client
     }
 
     fn make_node_provider(&self) -> NodeProvider {
@@ -317,7 +341,9 @@ impl ClientContext {
                     amount: Amount::ONE,
                     user_data: UserData::default(),
                 })],
+                independent_operations: self.independent_operations,
                 previous_block_hash: chain.block_hash,
+                previous_block_signatures: None,
                 height: chain.next_block_height,
                 authenticated_signer: None,
                 timestamp: chain.timestamp.max(Timestamp::now()),
@@ -559,6 +585,10 @@ impl ClientContext {
     fn generate_key_pair(&mut self) -> KeyPair {
         KeyPair::generate_from(&mut self.prng)
     }
+
+    fn generate_key_pair_with_scheme(&mut self, scheme: SignatureScheme) -> KeyPair {
+        KeyPair::generate_with_scheme_from(scheme, &mut self.prng)
+    }
 }
 
 #[cfg(feature = "benchmark")]
@@ -646,6 +676,22 @@ struct ClientOptions {
     /// messages have been delivered.
     #[structopt(long)]
     wait_for_outgoing_messages: bool,
+
+    /// Whether the operations of the proposed blocks may fail without making the whole block
+    /// fail. A failing operation is then rolled back and only charged for the resources it
+    /// used.
+    #[structopt(long)]
+    independent_operations: bool,
+
+    /// The order in which the pending messages are included in new blocks: "by-origin",
+    /// "by-timestamp" or "round-robin". Defaults to "by-origin".
+    #[structopt(long)]
+    message_selection: Option<MessageSelection>,
+
+    /// Include the pending messages of these applications first in new blocks, in this order,
+    /// followed by the other messages. Ties are broken by timestamp.
+    #[structopt(long, conflicts_with = "message-selection")]
+    prioritized_applications: Vec<UserApplicationId>,
 }
 
 impl ClientOptions {
@@ -753,13 +799,42 @@ enum ClientCommand {
         /// number in which only a single designated leader is allowed to propose blocks.
         #[structopt(long = "multi-leader-rounds")]
         multi_leader_rounds: Option<RoundNumber>,
+
+        /// The total weight of owners that must approve transfers, ownership changes and user
+        /// operations, including the proposer. By default, any single owner can propose every
+        /// block.
+        #[structopt(long = "approval-threshold")]
+        approval_threshold: Option<u64>,
+
+        /// Require the approval threshold for all blocks, not only for transfers and
+        /// ownership changes.
+        #[structopt(long = "approve-all-blocks", requires = "approval-threshold")]
+        approve_all_blocks: bool,
+
+        /// The duration of the first round, in milliseconds. If no block is confirmed in time,
+        /// the next round begins.
+        #[structopt(long = "base-timeout-ms", default_value = "10000")]
+        base_timeout_ms: u64,
+
+        /// Make each round last this many times as long as the previous one. By default,
+        /// round `n` lasts `n + 1` times as long as the first one.
+        #[structopt(long = "timeout-backoff-factor")]
+        timeout_backoff_factor: Option<u32>,
+
+        /// The maximum duration of a round, in milliseconds.
+        #[structopt(long = "max-timeout-ms")]
+        max_timeout_ms: Option<u64>,
     },
 
-    /// Close (i.e. deactivate) an existing chain.
+    /// Close an existing chain. It then only returns incoming messages to their senders.
     CloseChain {
         /// Chain id (must be one of our chains)
         #[structopt(long = "from")]
         chain_id: ChainId,
+
+        /// Account receiving the remaining balances of the chain (otherwise they are lost)
+        #[structopt(long = "sweep-to")]
+        sweep_to: Option<Account>,
     },
 
     /// Read the balance of the chain from the local state of the client.
@@ -773,6 +848,22 @@ enum ClientCommand {
     SyncBalance {
         /// Chain id
         chain_id: Option<ChainId>,
+
+        /// Start from the latest checkpoint of the chain state certified by the validators,
+        /// instead of executing all the blocks that are missing locally.
+        #[structopt(long)]
+        from_checkpoint: bool,
+    },
+
+    /// Query the system state of a chain from the local state of the client: `balance`,
+    /// `balances`, `ownership`, `committees`, `subscriptions` or `registry`.
+    QuerySystem {
+        /// The part of the system state to query
+        query: SystemQuery,
+
+        /// Chain id
+        #[structopt(long = "chain-id")]
+        chain_id: Option<ChainId>,
     },
 
     /// Show the current set of validators for a chain.
@@ -794,6 +885,10 @@ enum ClientCommand {
         /// Voting power
         #[structopt(long, default_value = "1")]
         votes: u64,
+
+        /// How long the previous committees are still trusted, in milliseconds
+        #[structopt(long = "grace-period-ms", default_value = "0")]
+        grace_period_ms: u64,
     },
 
     /// Remove a validator (admin only)
@@ -801,6 +896,25 @@ enum ClientCommand {
         /// The public key of the validator.
         #[structopt(long)]
         name: ValidatorName,
+
+        /// How long the previous committees are still trusted, in milliseconds
+        #[structopt(long = "grace-period-ms", default_value = "0")]
+        grace_period_ms: u64,
+    },
+
+    /// Replace the public key of a validator (admin only)
+    RotateValidatorKey {
+        /// The current public key of the validator.
+        #[structopt(long)]
+        name: ValidatorName,
+
+        /// The new public key of the validator.
+        #[structopt(long)]
+        new_name: ValidatorName,
+
+        /// How long the previous committees are still trusted, in milliseconds
+        #[structopt(long = "grace-period-ms", default_value = "0")]
+        grace_period_ms: u64,
     },
 
     /// View or update the resource control policy
@@ -833,6 +947,24 @@ enum ClientCommand {
         #[structopt(long)]
         maximum_bytes_written_per_block: Option<u64>,
 
+        /// Set the maximum number of Wasm memory pages used by a contract
+        #[structopt(long)]
+        maximum_wasm_memory_pages: Option<u64>,
+
+        /// Set the maximum depth of nested cross-application calls
+        #[structopt(long)]
+        maximum_call_depth: Option<u64>,
+
+        /// Set the maximum number of sessions alive at the same time during a transaction
+        #[structopt(long)]
+        maximum_live_sessions: Option<u64>,
+
+        /// Set the time after which the messages that their recipients haven't executed
+        /// expire, in milliseconds. Tracked and acknowledged messages are then refused by their
+        /// recipients, and the other ones are abandoned.
+        #[structopt(long)]
+        message_expiry_ms: Option<u64>,
+
         /// Set the price per byte to store and send outgoing cross-chain messages.
         #[structopt(long)]
         messages: Option<Amount>,
@@ -905,6 +1037,24 @@ enum ClientCommand {
         #[structopt(long)]
         maximum_bytes_written_per_block: Option<u64>,
 
+        /// Set the maximum number of Wasm memory pages used by a contract
+        #[structopt(long)]
+        maximum_wasm_memory_pages: Option<u64>,
+
+        /// Set the maximum depth of nested cross-application calls
+        #[structopt(long)]
+        maximum_call_depth: Option<u64>,
+
+        /// Set the maximum number of sessions alive at the same time during a transaction
+        #[structopt(long)]
+        maximum_live_sessions: Option<u64>,
+
+        /// Set the time after which the messages that their recipients haven't executed
+        /// expire, in milliseconds. Tracked and acknowledged messages are then refused by their
+        /// recipients, and the other ones are abandoned.
+        #[structopt(long)]
+        message_expiry_ms: Option<u64>,
+
         /// Set the price per byte to store and send outgoing cross-chain messages.
         #[structopt(long, default_value = "0")]
         messages_price: Amount,
@@ -915,6 +1065,17 @@ enum ClientCommand {
         testing_prng_seed: Option<u64>,
     },
 
+    /// Replay a confirmed block from the local storage against the state of its chain after
+    /// the previous block, and print or export the trace of its execution.
+    ReplayBlock {
+        /// The hash of the certificate confirming the block.
+        certificate_hash: CryptoHash,
+
+        /// Export the trace as JSON to the given file instead of printing it.
+        #[structopt(long)]
+        output: Option<PathBuf>,
+    },
+
     /// Watch the network for notifications.
     Watch {
         /// The chain id to watch.
@@ -991,6 +1152,10 @@ enum ClientCommand {
         /// The list of required dependencies of application, if any.
         #[structopt(long)]
         required_application_ids: Option<Vec<UserApplicationId>>,
+
+        /// The maximum number of bytes the application may store on each chain, if limited.
+        #[structopt(long)]
+        storage_quota: Option<u64>,
     },
 
     /// Create an application, and publish the required bytecode.
@@ -1024,6 +1189,10 @@ enum ClientCommand {
         /// The list of required dependencies of application, if any.
         #[structopt(long)]
         required_application_ids: Option<Vec<UserApplicationId>>,
+
+        /// The maximum number of bytes the application may store on each chain, if limited.
+        #[structopt(long)]
+        storage_quota: Option<u64>,
     },
 
     /// Request an application from another chain, so it can be used on this one.
@@ -1042,7 +1211,16 @@ enum ClientCommand {
     },
 
     /// Create an unassigned key-pair.
-    Keygen,
+    Keygen {
+        /// The signature scheme of the key-pair: `ed25519` or `secp256k1`.
+        #[structopt(long, default_value = "ed25519")]
+        scheme: SignatureScheme,
+
+        /// Import an existing secret key of the given scheme, read as a hexadecimal string from
+        /// this file, instead of generating a new one.
+        #[structopt(long = "import-secret-key")]
+        secret_key_path: Option<PathBuf>,
+    },
 
     /// Link a key owned by the wallet to a chain that was just created for that key.
     Assign {
@@ -1175,6 +1353,10 @@ enum ProjectCommand {
         /// The list of required dependencies of application, if any.
         #[structopt(long)]
         required_application_ids: Option<Vec<UserApplicationId>>,
+
+        /// The maximum number of bytes the application may store on each chain, if limited.
+        #[structopt(long)]
+        storage_quota: Option<u64>,
     },
 }
 
@@ -1264,6 +1446,11 @@ impl Runnable for Job {
                 public_keys,
                 weights,
                 multi_leader_rounds,
+                approval_threshold,
+                approve_all_blocks,
+                base_timeout_ms,
+                timeout_backoff_factor,
+                max_timeout_ms,
             } => {
                 let mut chain_client = context.make_chain_client(storage, chain_id);
                 info!("Starting operation to open a new chain");
@@ -1280,7 +1467,27 @@ impl Runnable for Job {
                     public_keys.into_iter().zip(weights).collect()
                 };
                 let multi_leader_rounds = multi_leader_rounds.unwrap_or(RoundNumber::MAX);
-                let ownership = ChainOwnership::multiple(owners, multi_leader_rounds);
+                let approval_threshold = approval_threshold.map(|weight| ApprovalThreshold {
+                    weight,
+                    scope: if approve_all_blocks {
+                        ApprovalScope::AllBlocks
+                    } else {
+                        ApprovalScope::TransfersAndOwnership
+                    },
+                });
+                let backoff = match timeout_backoff_factor {
+                    None => TimeoutBackoff::Linear,
+                    Some(factor) => TimeoutBackoff::Exponential { factor },
+                };
+                let timeout_config = TimeoutConfig {
+                    base_timeout_micros: base_timeout_ms.saturating_mul(1000),
+                    backoff,
+                    max_timeout_micros: max_timeout_ms
+                        .map_or(u64::MAX, |ms| ms.saturating_mul(1000)),
+                };
+                let ownership = ChainOwnership::multiple(owners, multi_leader_rounds)
+                    .with_approval_threshold(approval_threshold)
+                    .with_timeout_config(timeout_config);
                 let (message_id, certificate) = chain_client.open_chain(ownership).await.unwrap();
                 let time_total = time_start.elapsed().as_micros();
                 info!("Operation confirmed after {} us", time_total);
@@ -1303,11 +1510,14 @@ impl Runnable for Job {
                 context.save_wallet();
             }
 
-            CloseChain { chain_id } => {
+            CloseChain { chain_id, sweep_to } => {
                 let mut chain_client = context.make_chain_client(storage, chain_id);
                 info!("Starting operation to close the chain");
                 let time_start = Instant::now();
-                let certificate = chain_client.close_chain().await.unwrap();
+                let certificate = chain_client
+                    .close_chain(sweep_to.map(Recipient::Account))
+                    .await
+                    .unwrap();
                 let time_total = time_start.elapsed().as_micros();
                 info!("Operation confirmed after {} us", time_total);
                 debug!("{:?}", certificate);
@@ -1330,11 +1540,18 @@ impl Runnable for Job {
                 context.save_wallet();
             }
 
-            SyncBalance { chain_id } => {
+            SyncBalance {
+                chain_id,
+                from_checkpoint,
+            } => {
                 let mut chain_client = context.make_chain_client(storage, chain_id);
                 info!("Synchronize chain information");
                 let time_start = Instant::now();
-                let balance = chain_client.synchronize_from_validators().await.unwrap();
+                let balance = if from_checkpoint {
+                    chain_client.synchronize_from_checkpoint().await.unwrap()
+                } else {
+                    chain_client.synchronize_from_validators().await.unwrap()
+                };
                 let time_total = time_start.elapsed().as_micros();
                 info!("Chain balance synchronized after {} us", time_total);
                 println!("{}", balance);
@@ -1342,6 +1559,18 @@ impl Runnable for Job {
                 context.save_wallet();
             }
 
+            QuerySystem { query, chain_id } => {
+                let mut chain_client = context.make_chain_client(storage, chain_id);
+                info!("Starting system query");
+                let time_start = Instant::now();
+                let response = chain_client.query_system_application(query).await?;
+                let time_total = time_start.elapsed().as_micros();
+                info!("System query answered after {} us", time_total);
+                println!("{}", serde_json::to_string_pretty(&response)?);
+                context.update_wallet_from_client(&mut chain_client).await;
+                context.save_wallet();
+            }
+
             QueryValidators { chain_id } => {
                 let mut chain_client = context.make_chain_client(storage, chain_id);
                 info!("Starting operation to query validators");
@@ -1356,6 +1585,7 @@ impl Runnable for Job {
 
             command @ (SetValidator { .. }
             | RemoveValidator { .. }
+            | RotateValidatorKey { .. }
             | ResourceControlPolicy { .. }) => {
                 info!("Starting operations to change validator set");
                 let time_start = Instant::now();
@@ -1377,28 +1607,59 @@ impl Runnable for Job {
                 info!("Subscribed {} chains to new committees", n);
 
                 // Create the new committee.
-                let mut committee = chain_client.local_committee().await.unwrap();
+                let committee = chain_client.local_committee().await.unwrap();
                 let mut policy = committee.policy().clone();
-                let mut validators = committee.validators().clone();
-                match command {
+                let validators = committee.validators();
+                let (changes, grace_period_ms) = match command {
                     SetValidator {
                         name,
                         address,
                         votes,
+                        grace_period_ms,
                     } => {
-                        validators.insert(
-                            name,
-                            ValidatorState {
+                        let changes = match validators.get(&name) {
+                            None => vec![ValidatorChange::Join {
+                                name,
                                 network_address: address,
                                 votes,
-                            },
-                        );
+                            }],
+                            Some(state) if state.network_address == address => {
+                                vec![ValidatorChange::ChangeVotes { name, votes }]
+                            }
+                            Some(_) => vec![
+                                ValidatorChange::Exit { name },
+                                ValidatorChange::Join {
+                                    name,
+                                    network_address: address,
+                                    votes,
+                                },
+                            ],
+                        };
+                        (changes, grace_period_ms)
                     }
-                    RemoveValidator { name } => {
-                        if validators.remove(&name).is_none() {
+                    RemoveValidator {
+                        name,
+                        grace_period_ms,
+                    } => {
+                        if !validators.contains_key(&name) {
                             warn!("Skipping removal of nonexistent validator");
                             return Ok(());
                         }
+                        (vec![ValidatorChange::Exit { name }], grace_period_ms)
+                    }
+                    RotateValidatorKey {
+                        name,
+                        new_name,
+                        grace_period_ms,
+                    } => {
+                        if !validators.contains_key(&name) {
+                            warn!("Skipping key rotation of nonexistent validator");
+                            return Ok(());
+                        }
+                        (
+                            vec![ValidatorChange::RotateKey { name, new_name }],
+                            grace_period_ms,
+                        )
                     }
                     ResourceControlPolicy {
                         certificate,
@@ -1408,6 +1669,10 @@ impl Runnable for Job {
                         storage_bytes_written,
                         maximum_bytes_read_per_block,
                         maximum_bytes_written_per_block,
+                        maximum_wasm_memory_pages,
+                        maximum_call_depth,
+                        maximum_live_sessions,
+                        message_expiry_ms,
                         messages,
                     } => {
                         if let Some(certificate) = certificate {
@@ -1434,6 +1699,18 @@ impl Runnable for Job {
                             policy.maximum_bytes_written_per_block =
                                 maximum_bytes_written_per_block;
                         }
+                        if let Some(maximum_wasm_memory_pages) = maximum_wasm_memory_pages {
+                            policy.maximum_wasm_memory_pages = maximum_wasm_memory_pages;
+                        }
+                        if let Some(maximum_call_depth) = maximum_call_depth {
+                            policy.maximum_call_depth = maximum_call_depth;
+                        }
+                        if let Some(maximum_live_sessions) = maximum_live_sessions {
+                            policy.maximum_live_sessions = maximum_live_sessions;
+                        }
+                        if let Some(message_expiry_ms) = message_expiry_ms {
+                            policy.message_expiry_micros = message_expiry_ms.saturating_mul(1000);
+                        }
                         if let Some(messages) = messages {
                             policy.messages = messages;
                         }
@@ -1446,7 +1723,11 @@ impl Runnable for Job {
                             {:.2} cost per bytes written\n\
                             {:.2} per byte of outgoing messages\n\
                             {:.2} maximum number bytes read per block\n\
-                            {:.2} maximum number bytes written per block",
+                            {:.2} maximum number bytes written per block\n\
+                            {} maximum number of Wasm memory pages\n\
+                            {} maximum depth of cross-application calls\n\
+                            {} maximum number of live sessions\n\
+                            {} microseconds until unreceived messages expire",
                             policy.certificate,
                             policy.fuel,
                             policy.storage_num_reads,
@@ -1454,7 +1735,11 @@ impl Runnable for Job {
                             policy.storage_bytes_written,
                             policy.messages,
                             policy.maximum_bytes_read_per_block,
-                            policy.maximum_bytes_written_per_block
+                            policy.maximum_bytes_written_per_block,
+                            policy.maximum_wasm_memory_pages,
+                            policy.maximum_call_depth,
+                            policy.maximum_live_sessions,
+                            policy.message_expiry_micros
                         );
                         if certificate.is_none()
                             && fuel.is_none()
@@ -1463,24 +1748,41 @@ impl Runnable for Job {
                             && storage_bytes_written.is_none()
                             && maximum_bytes_read_per_block.is_none()
                             && maximum_bytes_written_per_block.is_none()
+                            && maximum_wasm_memory_pages.is_none()
+                            && maximum_call_depth.is_none()
+                            && maximum_live_sessions.is_none()
+                            && message_expiry_ms.is_none()
                             && messages.is_none()
                         {
                             return Ok(());
                         }
+                        (Vec::new(), 0)
                     }
                     _ => unreachable!(),
-                }
-                committee = Committee::new(validators, policy);
-                let certificate = chain_client.stage_new_committee(committee).await.unwrap();
-                context.update_wallet_from_client(&mut chain_client).await;
-                info!("Staging committee:\n{:?}", certificate);
-                context.push_to_all_chains(&storage, &certificate).await;
+                };
+                if changes.is_empty() {
+                    let committee = Committee::new(validators.clone(), policy);
+                    let certificate = chain_client.stage_new_committee(committee).await.unwrap();
+                    context.update_wallet_from_client(&mut chain_client).await;
+                    info!("Staging committee:\n{:?}", certificate);
+                    context.push_to_all_chains(&storage, &certificate).await;
 
-                // Remove the old committee.
-                let certificate = chain_client.finalize_committee().await.unwrap();
-                context.update_wallet_from_client(&mut chain_client).await;
-                info!("Finalizing committee:\n{:?}", certificate);
-                context.push_to_all_chains(&storage, &certificate).await;
+                    // Remove the old committee.
+                    let certificate = chain_client.finalize_committee().await.unwrap();
+                    context.update_wallet_from_client(&mut chain_client).await;
+                    info!("Finalizing committee:\n{:?}", certificate);
+                    context.push_to_all_chains(&storage, &certificate).await;
+                } else {
+                    // The old committees are removed automatically after the grace period.
+                    let grace_period = Duration::from_millis(grace_period_ms);
+                    let certificate = chain_client
+                        .change_validators(changes, grace_period)
+                        .await
+                        .unwrap();
+                    context.update_wallet_from_client(&mut chain_client).await;
+                    info!("Changing validators:\n{:?}", certificate);
+                    context.push_to_all_chains(&storage, &certificate).await;
+                }
 
                 let time_total = time_start.elapsed().as_micros();
                 info!("Operations confirmed after {} us", time_total);
@@ -1580,6 +1882,32 @@ impl Runnable for Job {
                 context.save_wallet();
             }
 
+            ReplayBlock {
+                certificate_hash,
+                output,
+            } => {
+                info!("Replaying block of certificate {certificate_hash}");
+                let replay = replay::replay_block(
+                    &storage,
+                    context.wallet_state.genesis_config(),
+                    context.max_stream_queries,
+                    certificate_hash,
+                )
+                .await?;
+                match output {
+                    Some(path) => {
+                        fs::write(&path, serde_json::to_vec_pretty(&replay.trace)?)?;
+                        info!("Execution trace exported to {}", path.display());
+                    }
+                    None => print!("{}", replay.trace),
+                }
+                if let Some(error) = &replay.error {
+                    warn!("Block execution failed: {error}");
+                } else if !replay.matches_certificate {
+                    warn!("Replaying the block did not reproduce its certified outcome");
+                }
+            }
+
             Watch { chain_id, raw } => {
                 let chain_client = context.make_chain_client(storage, chain_id);
                 let chain_id = chain_client.chain_id();
@@ -1635,6 +1963,7 @@ impl Runnable for Job {
                 json_argument,
                 json_argument_path,
                 required_application_ids,
+                storage_quota,
             } => {
                 let start_time = Instant::now();
                 let mut chain_client = context.make_chain_client(storage, creator);
@@ -1654,6 +1983,7 @@ impl Runnable for Job {
                         parameters,
                         argument,
                         required_application_ids.unwrap_or_default(),
+                        storage_quota,
                     )
                     .await
                     .context("failed to create application")?;
@@ -1674,6 +2004,7 @@ impl Runnable for Job {
                 json_argument,
                 json_argument_path,
                 required_application_ids,
+                storage_quota,
             } => {
                 let start_time = Instant::now();
                 let mut chain_client = context.make_chain_client(storage, publisher);
@@ -1697,6 +2028,7 @@ impl Runnable for Job {
                         parameters,
                         argument,
                         required_application_ids.unwrap_or_default(),
+                        storage_quota,
                     )
                     .await
                     .context("failed to create application")?;
@@ -1777,6 +2109,7 @@ impl Runnable for Job {
                     json_argument,
                     json_argument_path,
                     required_application_ids,
+                    storage_quota,
                 } => {
                     let start_time = Instant::now();
                     let mut chain_client = context.make_chain_client(storage, publisher);
@@ -1804,6 +2137,7 @@ impl Runnable for Job {
                             parameters,
                             argument,
                             required_application_ids.unwrap_or_default(),
+                            storage_quota,
                         )
                         .await
                         .context("failed to create application")?;
@@ -1817,7 +2151,7 @@ impl Runnable for Job {
                 _ => unreachable!("other project commands do not require storage"),
             },
 
-            CreateGenesisConfig { .. } | Keygen | Net(_) | Wallet(_) => unreachable!(),
+            CreateGenesisConfig { .. } | Keygen { .. } | Net(_) | Wallet(_) => unreachable!(),
         }
         Ok(())
     }
@@ -1849,6 +2183,10 @@ async fn main() -> Result<(), anyhow::Error> {
             storage_bytes_written_price,
             maximum_bytes_read_per_block,
             maximum_bytes_written_per_block,
+            maximum_wasm_memory_pages,
+            maximum_call_depth,
+            maximum_live_sessions,
+            message_expiry_ms,
             messages_price,
             testing_prng_seed,
         } => {
@@ -1870,6 +2208,11 @@ async fn main() -> Result<(), anyhow::Error> {
                 storage_bytes_written: *storage_bytes_written_price,
                 maximum_bytes_read_per_block,
                 maximum_bytes_written_per_block,
+                maximum_wasm_memory_pages: maximum_wasm_memory_pages.unwrap_or(u64::MAX),
+                maximum_call_depth: maximum_call_depth.unwrap_or(u64::MAX),
+                maximum_live_sessions: maximum_live_sessions.unwrap_or(u64::MAX),
+                message_expiry_micros: message_expiry_ms
+                    .map_or(u64::MAX, |millis| millis.saturating_mul(1000)),
                 messages: *messages_price,
             };
             let mut genesis_config =
@@ -1923,9 +2266,19 @@ async fn main() -> Result<(), anyhow::Error> {
             ProjectCommand::PublishAndCreate { .. } => options.run_command_with_storage().await,
         },
 
-        ClientCommand::Keygen => {
+        ClientCommand::Keygen {
+            scheme,
+            secret_key_path,
+        } => {
             let mut context = ClientContext::from_options(&options)?;
-            let key_pair = context.generate_key_pair();
+            let key_pair = match secret_key_path {
+                Some(path) => {
+                    let secret = fs::read_to_string(path)?;
+                    let secret = hex::decode(secret.trim().trim_start_matches("0x"))?;
+                    KeyPair::from_secret_key(*scheme, &secret)?
+                }
+                None => context.generate_key_pair_with_scheme(*scheme),
+            };
             let public = key_pair.public();
             context.wallet_state.add_unassigned_key_pair(key_pair);
             context.save_wallet();
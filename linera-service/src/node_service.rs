@@ -8,7 +8,7 @@ use crate::{
 use async_graphql::{
     futures_util::Stream,
     parser::types::{DocumentOperations, ExecutableDocument, OperationType},
-    Error, MergedObject, Object, Request, ScalarType, Schema, ServerError, SimpleObject,
+    Error, Json, MergedObject, Object, Request, ScalarType, Schema, ServerError, SimpleObject,
     Subscription,
 };
 use async_graphql_axum::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
@@ -18,7 +18,7 @@ use axum::{
 use futures::lock::{Mutex, MutexGuard, OwnedMutexGuard};
 use linera_base::{
     crypto::{CryptoError, CryptoHash, PublicKey},
-    data_types::{Amount, RoundNumber},
+    data_types::{Amount, BlockHeight, RoundNumber},
     identifiers::{ApplicationId, BytecodeId, ChainId, Owner},
     BcsHexParseError,
 };
@@ -29,10 +29,11 @@ use linera_core::{
     worker::Notification,
 };
 use linera_execution::{
-    committee::{Committee, Epoch},
+    committee::{Committee, Epoch, ValidatorChange},
     system::{AdminOperation, Recipient, SystemChannel, UserData},
-    Bytecode, ChainOwnership, Operation, Query, Response, SystemOperation,
-    UserApplicationDescription, UserApplicationId,
+    ApprovalThreshold, Bytecode, ChainOwnership, InboxPolicy, Operation, Query,
+    RemoteQueryResponse, Response, SystemOperation, TimeoutConfig, UserApplicationDescription,
+    UserApplicationId,
 };
 use linera_storage::Store;
 use linera_views::views::ViewError;
@@ -295,6 +296,8 @@ where
         public_keys: Vec<PublicKey>,
         weights: Option<Vec<u64>>,
         multi_leader_rounds: Option<RoundNumber>,
+        approval_threshold: Option<ApprovalThreshold>,
+        timeout_config: Option<TimeoutConfig>,
     ) -> Result<ChainId, Error> {
         let owners: Vec<_> = if let Some(weights) = weights {
             if weights.len() != public_keys.len() {
@@ -309,16 +312,22 @@ where
             public_keys.into_iter().zip(iter::repeat(100)).collect()
         };
         let multi_leader_rounds = multi_leader_rounds.unwrap_or(RoundNumber::MAX);
-        let ownership = ChainOwnership::multiple(owners, multi_leader_rounds);
+        let ownership = ChainOwnership::multiple(owners, multi_leader_rounds)
+            .with_approval_threshold(approval_threshold)
+            .with_timeout_config(timeout_config.unwrap_or_default());
         let mut client = self.clients.try_client_lock(&chain_id).await?;
         let (message_id, _) = client.open_chain(ownership).await?;
         Ok(ChainId::child(message_id))
     }
 
-    /// Closes the chain.
-    async fn close_chain(&self, chain_id: ChainId) -> Result<CryptoHash, Error> {
+    /// Closes the chain. The remaining balances are transferred to the recipient, if any.
+    async fn close_chain(
+        &self,
+        chain_id: ChainId,
+        recipient: Option<Recipient>,
+    ) -> Result<CryptoHash, Error> {
         let mut client = self.clients.try_client_lock(&chain_id).await?;
-        let certificate = client.close_chain().await?;
+        let certificate = client.close_chain(recipient).await?;
         Ok(certificate.hash())
     }
 
@@ -339,14 +348,28 @@ where
         new_public_keys: Vec<PublicKey>,
         new_weights: Vec<u64>,
         multi_leader_rounds: RoundNumber,
+        approval_threshold: Option<ApprovalThreshold>,
+        timeout_config: Option<TimeoutConfig>,
     ) -> Result<CryptoHash, Error> {
         let operation = SystemOperation::ChangeMultipleOwners {
             new_public_keys: new_public_keys.into_iter().zip(new_weights).collect(),
             multi_leader_rounds,
+            approval_threshold,
+            timeout_config: timeout_config.unwrap_or_default(),
         };
         self.execute_system_operation(operation, chain_id).await
     }
 
+    /// Changes the messages that the chain accepts in its inboxes.
+    async fn change_inbox_policy(
+        &self,
+        chain_id: ChainId,
+        policy: InboxPolicy,
+    ) -> Result<CryptoHash, Error> {
+        let operation = SystemOperation::ChangeInboxPolicy { policy };
+        self.execute_system_operation(operation, chain_id).await
+    }
+
     /// (admin chain only) Registers a new committee. This will notify the subscribers of
     /// the admin chain so that they can migrate to the new epoch (by accepting the
     /// notification as an "incoming message" in a next block).
@@ -399,6 +422,22 @@ where
         self.execute_system_operation(operation, chain_id).await
     }
 
+    /// (admin chain only) Starts a new epoch with the committee obtained by applying the
+    /// given changes to the current one. The previous committees are still trusted during
+    /// the grace period, then removed automatically.
+    async fn change_validators(
+        &self,
+        chain_id: ChainId,
+        changes: Vec<ValidatorChange>,
+        grace_period_ms: u64,
+    ) -> Result<CryptoHash, Error> {
+        let operation = SystemOperation::Admin(AdminOperation::ChangeValidators {
+            changes,
+            grace_period_micros: grace_period_ms.saturating_mul(1000),
+        });
+        self.execute_system_operation(operation, chain_id).await
+    }
+
     /// Publishes a new application bytecode.
     async fn publish_bytecode(
         &self,
@@ -419,6 +458,7 @@ where
         parameters: String,
         initialization_argument: String,
         required_application_ids: Vec<UserApplicationId>,
+        storage_quota: Option<u64>,
     ) -> Result<ApplicationId, Error> {
         let mut client = self.clients.try_client_lock(&chain_id).await?;
         let (application_id, _) = client
@@ -427,6 +467,7 @@ where
                 parameters.as_bytes().to_vec(),
                 initialization_argument.as_bytes().to_vec(),
                 required_application_ids,
+                storage_quota,
             )
             .await?;
         Ok(application_id)
@@ -478,6 +519,26 @@ where
         Ok(overviews)
     }
 
+    /// Queries the service of an application on a chain tracked by this node. The response is
+    /// annotated with the height of the next block of the queried chain.
+    async fn query_application(
+        &self,
+        chain_id: ChainId,
+        application_id: UserApplicationId,
+        query: String,
+    ) -> Result<ApplicationQueryResponse, Error> {
+        let client = self.clients.try_client_lock(&chain_id).await?;
+        let bytes = serde_json::to_vec(&Request::new(query))?;
+        let RemoteQueryResponse { response, height } = client
+            .query_remote_application(chain_id, application_id, bytes)
+            .await?;
+        let response = serde_json::from_slice(&response)?;
+        Ok(ApplicationQueryResponse {
+            response: Json(response),
+            height,
+        })
+    }
+
     async fn chains(&self) -> Result<Chains, Error> {
         Ok(Chains {
             list: self.clients.0.lock().await.keys().cloned().collect(),
@@ -560,6 +621,16 @@ where
     }
 }
 
+/// The response of an application queried through [`QueryRoot::query_application`].
+#[derive(SimpleObject)]
+pub struct ApplicationQueryResponse {
+    /// The GraphQL response of the application.
+    response: Json<serde_json::Value>,
+    /// The height of the next block of the queried chain: the response reflects the state of
+    /// the chain after executing all the blocks below this height.
+    height: BlockHeight,
+}
+
 #[derive(SimpleObject)]
 pub struct ApplicationOverview {
     id: UserApplicationId,
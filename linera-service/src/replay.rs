@@ -0,0 +1,223 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deterministic replay of confirmed blocks, used to debug failing applications.
+//!
+//! The chain of the block is re-executed from its initial state in a temporary in-memory
+//! storage, up to the block's parent. The block itself is then executed again with tracing
+//! enabled. The original storage is only read.
+//!
+//! Each block is executed at its own timestamp, so that replaying does not depend on when the
+//! replay is run.
+
+use crate::config::GenesisConfig;
+use anyhow::{bail, ensure, Context as _};
+use linera_base::{
+    crypto::CryptoHash,
+    data_types::{BlockHeight, Timestamp},
+    identifiers::{ChainDescription, ChainId},
+};
+use linera_chain::{data_types::ExecutedBlock, ChainError, ChainStateView};
+use linera_execution::trace::ExecutionTrace;
+use linera_storage::{Clock, MemoryStoreClient, Store};
+use linera_views::views::ViewError;
+
+/// The outcome of replaying a confirmed block.
+#[derive(Debug)]
+pub struct BlockReplay {
+    /// The block that was replayed.
+    pub executed_block: ExecutedBlock,
+    /// The steps taken while executing the block.
+    pub trace: ExecutionTrace,
+    /// The error that interrupted the execution of the block, if any.
+    pub error: Option<ChainError>,
+    /// Whether the replay reproduced the certified outcome of the block.
+    pub matches_certificate: bool,
+}
+
+/// A clock stopped at the timestamp of the replayed block.
+#[derive(Clone)]
+struct ReplayClock(Timestamp);
+
+impl Clock for ReplayClock {
+    fn current_time(&self) -> Timestamp {
+        self.0
+    }
+}
+
+type ReplayStore = MemoryStoreClient<ReplayClock>;
+type ReplayChain = ChainStateView<<ReplayStore as Store>::Context>;
+
+/// Replays the confirmed block certified by the certificate with the given `hash`, against
+/// the state of its chain after the previous block.
+pub async fn replay_block<S>(
+    storage: &S,
+    genesis_config: &GenesisConfig,
+    max_stream_queries: usize,
+    hash: CryptoHash,
+) -> Result<BlockReplay, anyhow::Error>
+where
+    S: Store + Clone + Send + Sync + 'static,
+    ViewError: From<S::ContextError>,
+{
+    let certificate = storage.read_certificate(hash).await?;
+    let value = certificate.value();
+    let Some(executed_block) = value.executed_block().filter(|_| value.is_confirmed()) else {
+        bail!("Certificate {hash} does not confirm a block");
+    };
+    let block = &executed_block.block;
+    let replay_storage = ReplayStore::new(
+        storage.wasm_runtime(),
+        max_stream_queries,
+        ReplayClock(block.timestamp),
+    );
+    copy_bytecodes(storage, &replay_storage, block.chain_id).await?;
+    let mut chain =
+        initialize_chain(storage, &replay_storage, genesis_config, block.chain_id).await?;
+
+    for height in 0..block.height.0 {
+        let height = BlockHeight(height);
+        let previous_block = read_confirmed_block(storage, block.chain_id, height).await?;
+        let outcome = chain
+            .execute_block(&previous_block.block, previous_block.block.timestamp)
+            .await
+            .with_context(|| format!("Failed to replay the block at height {height}"))?;
+        ensure!(
+            outcome.with(previous_block.block.clone()) == previous_block,
+            "Replaying the block at height {height} did not reproduce its certified outcome"
+        );
+    }
+
+    let mut trace = ExecutionTrace::default();
+    let (error, matches_certificate) = match chain
+        .execute_block_with_trace(block, block.timestamp, Some(&mut trace))
+        .await
+    {
+        Ok(outcome) => (None, outcome.with(block.clone()) == *executed_block),
+        Err(error) => (Some(error), false),
+    };
+    Ok(BlockReplay {
+        executed_block: executed_block.clone(),
+        trace,
+        error,
+        matches_certificate,
+    })
+}
+
+/// Copies the bytecodes of the applications known by the chain, so that they can be loaded
+/// from the replay storage.
+async fn copy_bytecodes<S>(
+    storage: &S,
+    replay_storage: &ReplayStore,
+    chain_id: ChainId,
+) -> Result<(), anyhow::Error>
+where
+    S: Store + Clone + Send + Sync + 'static,
+    ViewError: From<S::ContextError>,
+{
+    let chain = storage.load_chain(chain_id).await?;
+    let mut certificate_hashes = Vec::new();
+    chain
+        .execution_state
+        .system
+        .registry
+        .known_applications
+        .for_each_index_value(|_, description| {
+            certificate_hashes.push(description.bytecode_location.certificate_hash);
+            Ok(())
+        })
+        .await?;
+    for hash in certificate_hashes {
+        let value = storage.read_value(hash).await?;
+        replay_storage.write_value(&value).await?;
+    }
+    Ok(())
+}
+
+/// Creates the chain in the replay storage, in the state it had before its first block.
+async fn initialize_chain<S>(
+    storage: &S,
+    replay_storage: &ReplayStore,
+    genesis_config: &GenesisConfig,
+    chain_id: ChainId,
+) -> Result<ReplayChain, anyhow::Error>
+where
+    S: Store + Clone + Send + Sync + 'static,
+    ViewError: From<S::ContextError>,
+{
+    // Release the chain before loading other chains from the storage.
+    let description = *storage
+        .load_chain(chain_id)
+        .await?
+        .execution_state
+        .system
+        .description
+        .get();
+    let Some(description) = description else {
+        bail!("Chain {chain_id} is not active in the local storage");
+    };
+    match description {
+        ChainDescription::Root(_) => {
+            let Some((description, public_key, balance, timestamp)) = genesis_config
+                .chains
+                .iter()
+                .find(|(description, ..)| ChainId::from(*description) == chain_id)
+            else {
+                bail!("Root chain {chain_id} is missing from the genesis configuration");
+            };
+            replay_storage
+                .create_chain(
+                    genesis_config.create_committee(),
+                    genesis_config.admin_id,
+                    *description,
+                    *public_key,
+                    *balance,
+                    *timestamp,
+                )
+                .await?;
+            Ok(replay_storage.load_chain(chain_id).await?)
+        }
+        ChainDescription::Child(message_id) => {
+            let parent_block =
+                read_confirmed_block(storage, message_id.chain_id, message_id.height).await?;
+            let Some(open_chain) = usize::try_from(message_id.index)
+                .ok()
+                .and_then(|index| parent_block.messages.get(index))
+            else {
+                bail!("The message opening chain {chain_id} is missing");
+            };
+            let mut chain = replay_storage.load_chain(chain_id).await?;
+            chain
+                .execute_immediate_message(
+                    message_id,
+                    &open_chain.message,
+                    parent_block.block.timestamp,
+                    parent_block.block.timestamp,
+                )
+                .await?;
+            Ok(chain)
+        }
+    }
+}
+
+/// Reads the block confirmed at the given height of a chain.
+async fn read_confirmed_block<S>(
+    storage: &S,
+    chain_id: ChainId,
+    height: BlockHeight,
+) -> Result<ExecutedBlock, anyhow::Error>
+where
+    S: Store + Clone + Send + Sync + 'static,
+    ViewError: From<S::ContextError>,
+{
+    let chain = storage.load_chain(chain_id).await?;
+    let index = usize::try_from(height.0)?;
+    let Some(hash) = chain.confirmed_log.get(index).await? else {
+        bail!("Missing block at height {height} of chain {chain_id}");
+    };
+    let certificate = storage.read_certificate(hash).await?;
+    match certificate.value().executed_block() {
+        Some(executed_block) => Ok(executed_block.clone()),
+        None => bail!("Certificate {hash} does not contain a block"),
+    }
+}
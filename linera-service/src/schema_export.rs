@@ -81,6 +81,7 @@ fn main() -> std::io::Result<()> {
     let config = ChainListenerConfig {
         delay_before_ms: 0,
         delay_after_ms: 0,
+        skip_leader_timeouts: false,
     };
     let service = NodeService::<DummyValidatorNodeProvider, _>::new(
         config,
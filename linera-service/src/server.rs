@@ -37,6 +37,8 @@ struct ServerContext {
     notification_config: NotificationConfig,
     shard: Option<usize>,
     grace_period_micros: u64,
+    proposal_pipeline_depth: u64,
+    checkpoint_interval: u64,
 }
 
 impl ServerContext {
@@ -58,7 +60,9 @@ impl ServerContext {
         )
         .with_allow_inactive_chains(false)
         .with_allow_messages_from_deprecated_epochs(false)
-        .with_grace_period_micros(self.grace_period_micros);
+        .with_grace_period_micros(self.grace_period_micros)
+        .with_proposal_pipeline_depth(self.proposal_pipeline_depth)
+        .with_checkpoint_interval(self.checkpoint_interval);
         (state, shard_id, shard.clone())
     }
 
@@ -250,6 +254,20 @@ struct ValidatorOptions {
     shards: Vec<ShardConfig>,
 }
 
+#[cfg(any(feature = "wasmer", feature = "wasmtime"))]
+fn enable_wasm_module_cache(directory: PathBuf, max_size_mib: u64) {
+    linera_execution::WasmApplication::enable_disk_module_cache(
+        directory,
+        max_size_mib.saturating_mul(1024 * 1024),
+    )
+    .expect("Failed to enable the on-disk cache of compiled WebAssembly modules");
+}
+
+#[cfg(not(any(feature = "wasmer", feature = "wasmtime")))]
+fn enable_wasm_module_cache(_directory: PathBuf, _max_size_mib: u64) {
+    tracing::warn!("Ignoring the WebAssembly module cache since no WebAssembly runtime is enabled");
+}
+
 fn make_server_config<R: CryptoRng>(
     rng: &mut R,
     options: ValidatorOptions,
@@ -311,6 +329,16 @@ enum ServerCommand {
         #[structopt(long, default_value = "500ms", parse(try_from_str = parse_duration))]
         grace_period: u64,
 
+        /// Accept proposals for up to this many blocks ahead of a single-owner chain, and vote
+        /// for them as soon as the previous block is confirmed.
+        #[structopt(long, default_value = "0")]
+        proposal_pipeline_depth: u64,
+
+        /// Sign a checkpoint of the state of each chain every this many blocks, so that new
+        /// nodes can start from there. Checkpoints are disabled if this is 0.
+        #[structopt(long, default_value = "0")]
+        checkpoint_interval: u64,
+
         /// The WebAssembly runtime to use.
         #[structopt(long)]
         wasm_runtime: Option<WasmRuntime>,
@@ -326,6 +354,14 @@ enum ServerCommand {
         /// The maximal number of entries in the storage cache.
         #[structopt(long, default_value = "1000")]
         cache_size: usize,
+
+        /// Directory where compiled WebAssembly modules are cached across restarts.
+        #[structopt(long)]
+        wasm_module_cache_dir: Option<PathBuf>,
+
+        /// The maximal size of the on-disk cache of compiled WebAssembly modules, in MiB.
+        #[structopt(long, default_value = "1024")]
+        wasm_module_cache_size: u64,
     },
 
     /// Act as a trusted third-party and generate all server configurations
@@ -399,11 +435,18 @@ async fn main() {
             genesis_config_path,
             shard,
             grace_period,
+            proposal_pipeline_depth,
+            checkpoint_interval,
             wasm_runtime,
             max_concurrent_queries,
             max_stream_queries,
             cache_size,
+            wasm_module_cache_dir,
+            wasm_module_cache_size,
         } => {
+            if let Some(directory) = wasm_module_cache_dir {
+                enable_wasm_module_cache(directory, wasm_module_cache_size);
+            }
             let genesis_config = GenesisConfig::read(&genesis_config_path)
                 .expect("Fail to read initial chain config");
             let server_config = ValidatorServerConfig::read(&server_config_path)
@@ -415,6 +458,8 @@ async fn main() {
                 notification_config,
                 shard,
                 grace_period_micros: grace_period,
+                proposal_pipeline_depth,
+                checkpoint_interval,
             };
             let wasm_runtime = wasm_runtime.with_wasm_default();
             let common_config = CommonStoreConfig {
@@ -8,6 +8,7 @@ mod common;
 
 use common::INTEGRATION_TEST_GUARD;
 use linera_base::{data_types::Amount, identifiers::ChainId};
+use linera_execution::{ChainOwnership, SystemResponse};
 use linera_service::{
     cli_wrappers::{
         local_net::{Database, LocalNet, LocalNetConfig, LocalNetTestingConfig},
@@ -606,6 +607,16 @@ async fn test_end_to_end_open_multi_owner_chain(config: impl LineraNetConfig) {
         client2.assign(client2_key, message_id).await.unwrap()
     );
 
+    // Both owners are listed in the ownership of Chain 2.
+    let SystemResponse::Ownership(ChainOwnership::Multi { public_keys, .. }) = client2
+        .query_system("ownership", Some(chain2))
+        .await
+        .unwrap()
+    else {
+        panic!("Chain 2 should have multiple owners");
+    };
+    assert_eq!(public_keys.len(), 2);
+
     // Transfer 6 units from Chain 1 to Chain 2.
     client1
         .transfer(Amount::from_tokens(6), chain1, chain2)
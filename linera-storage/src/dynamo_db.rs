@@ -35,7 +35,9 @@ impl DynamoDbStoreInner {
             client,
             guards: ChainGuards::default(),
             user_applications: Arc::new(DashMap::new()),
+            native_applications: DashMap::new(),
             wasm_runtime,
+            fuel_profiler: None,
         };
         Ok((store, table_status))
     }
@@ -49,7 +51,9 @@ impl DynamoDbStoreInner {
             client,
             guards: ChainGuards::default(),
             user_applications: Arc::new(DashMap::new()),
+            native_applications: DashMap::new(),
             wasm_runtime,
+            fuel_profiler: None,
         };
         Ok(store)
     }
@@ -63,7 +67,9 @@ impl DynamoDbStoreInner {
             client,
             guards: ChainGuards::default(),
             user_applications: Arc::new(DashMap::new()),
+            native_applications: DashMap::new(),
             wasm_runtime,
+            fuel_profiler: None,
         };
         Ok((store, table_status))
     }
@@ -28,16 +28,21 @@ use futures::future;
 use linera_base::{
     crypto::{CryptoHash, PublicKey},
     data_types::{Amount, BlockHeight, Timestamp},
-    identifiers::{ChainDescription, ChainId},
+    identifiers::{BytecodeId, ChainDescription, ChainId},
 };
 use linera_chain::{
-    data_types::{Certificate, CertificateValue, HashedValue, LiteCertificate},
+    data_types::{
+        Certificate, CertificateValue, CheckpointChunk, CheckpointVote, HashedValue,
+        LiteCertificate,
+    },
     ChainError, ChainStateView,
 };
 use linera_execution::{
     committee::{Committee, Epoch},
-    ChainOwnership, ExecutionError, ExecutionRuntimeContext, UserApplicationCode,
-    UserApplicationDescription, UserApplicationId, WasmRuntime,
+    profiler::FuelProfiler,
+    ChainOwnership, ExecutionError, ExecutionRuntimeContext, Query, QueryContext,
+    RemoteQueryResponse, Response, UserApplicationCode, UserApplicationDescription,
+    UserApplicationId, WasmRuntime, MAXIMUM_REMOTE_QUERY_DEPTH,
 };
 use linera_views::{
     batch::Batch,
@@ -48,7 +53,10 @@ use linera_views::{
 use once_cell::sync::Lazy;
 use prometheus::{register_int_counter_vec, IntCounterVec};
 use serde::{Deserialize, Serialize};
-use std::{fmt::Debug, sync::Arc};
+use std::{fmt::Debug, sync::Arc, time::Duration};
+
+/// How long a remote query waits for the queried chain to be unlocked before failing.
+pub const REMOTE_QUERY_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// The metric counting how often a value is read from storage.
 pub static READ_VALUE_COUNTER: Lazy<IntCounterVec> = Lazy::new(|| {
@@ -134,6 +142,25 @@ pub trait Store: Sized {
     /// Writes a vector of certificates.
     async fn write_certificates(&self, certificate: &[Certificate]) -> Result<(), ViewError>;
 
+    /// Reads the latest checkpoint signed by this validator for the given chain, if any.
+    async fn read_checkpoint(&self, chain_id: ChainId)
+        -> Result<Option<CheckpointVote>, ViewError>;
+
+    /// Reads the chunk with the given hash of the latest checkpoint of the given chain.
+    async fn read_checkpoint_chunk(
+        &self,
+        chain_id: ChainId,
+        hash: CryptoHash,
+    ) -> Result<CheckpointChunk, ViewError>;
+
+    /// Writes a checkpoint together with the chunks of state it commits to. This replaces
+    /// the previous checkpoint of the same chain, if any.
+    async fn write_checkpoint(
+        &self,
+        vote: &CheckpointVote,
+        chunks: &[CheckpointChunk],
+    ) -> Result<(), ViewError>;
+
     /// Loads the view of a chain state and checks that it is active.
     async fn load_active_chain(
         &self,
@@ -216,6 +243,29 @@ pub trait Store: Sized {
     /// Selects the WebAssembly runtime to use for applications (if any).
     fn wasm_runtime(&self) -> Option<WasmRuntime>;
 
+    /// Returns the profiler collecting the fuel consumed by the contracts of applications, if
+    /// profiling is enabled.
+    fn fuel_profiler(&self) -> Option<FuelProfiler>;
+
+    /// Returns the application registered to be executed natively instead of the bytecode
+    /// published with `bytecode_id`, if any.
+    fn native_application(&self, bytecode_id: BytecodeId) -> Option<UserApplicationCode>;
+
+    /// Queries an application, or the system, on a chain of this storage, returning the
+    /// response together with the height of the next block of the chain.
+    ///
+    /// The `callers` are the chains whose services are waiting for this query, outermost first.
+    /// Querying one of them again fails instead of deadlocking, and so does nesting more than
+    /// [`MAXIMUM_REMOTE_QUERY_DEPTH`] queries. The chain is locked while it is queried, so
+    /// that the response reflects a consistent state; if it stays locked by another user for
+    /// longer than [`REMOTE_QUERY_LOCK_TIMEOUT`], the query fails.
+    async fn query_remote_application(
+        &self,
+        chain_id: ChainId,
+        query: Query,
+        callers: Vec<ChainId>,
+    ) -> Result<RemoteQueryResponse<Response>, ExecutionError>;
+
     /// Creates a [`linera-sdk::UserApplication`] instance using the bytecode in storage referenced
     /// by the `application_description`.
     #[cfg(any(feature = "wasmer", feature = "wasmtime"))]
@@ -223,6 +273,9 @@ pub trait Store: Sized {
         &self,
         application_description: &UserApplicationDescription,
     ) -> Result<UserApplicationCode, ExecutionError> {
+        if let Some(application) = self.native_application(application_description.bytecode_id) {
+            return Ok(application);
+        }
         let Some(wasm_runtime) = self.wasm_runtime() else {
             panic!("A Wasm runtime is required to load user applications.");
         };
@@ -251,9 +304,22 @@ pub trait Store: Sized {
             .map_err(|_| linera_base::data_types::ArithmeticError::Overflow)?;
         match operations.get(index) {
             Some(Operation::System(SystemOperation::PublishBytecode { contract, service })) => {
-                Ok(Arc::new(
-                    WasmApplication::new(contract.clone(), service.clone(), wasm_runtime).await?,
-                ))
+                let application = match self.fuel_profiler() {
+                    Some(profiler) => {
+                        WasmApplication::new_with_fuel_profiler(
+                            contract.clone(),
+                            service.clone(),
+                            wasm_runtime,
+                            profiler,
+                        )
+                        .await?
+                    }
+                    None => {
+                        WasmApplication::new(contract.clone(), service.clone(), wasm_runtime)
+                            .await?
+                    }
+                };
+                Ok(Arc::new(application))
             }
             _ => Err(ExecutionError::InvalidBytecodeId(*bytecode_id)),
         }
@@ -263,8 +329,11 @@ pub trait Store: Sized {
     #[allow(clippy::diverging_sub_expression)]
     async fn load_application(
         &self,
-        _application_description: &UserApplicationDescription,
+        application_description: &UserApplicationDescription,
     ) -> Result<UserApplicationCode, ExecutionError> {
+        if let Some(application) = self.native_application(application_description.bytecode_id) {
+            return Ok(application);
+        }
         panic!(
             "A Wasm runtime is required to load user applications. \
             Please enable the `wasmer` or the `wasmtime` feature flags \
@@ -278,7 +347,9 @@ pub struct DbStoreInner<Client> {
     client: Client,
     guards: ChainGuards,
     user_applications: Arc<DashMap<UserApplicationId, UserApplicationCode>>,
+    native_applications: DashMap<BytecodeId, UserApplicationCode>,
     wasm_runtime: Option<WasmRuntime>,
+    fuel_profiler: Option<FuelProfiler>,
 }
 
 #[derive(Clone)]
@@ -293,6 +364,8 @@ enum BaseKey {
     ChainState(ChainId),
     Certificate(CryptoHash),
     Value(CryptoHash),
+    Checkpoint(ChainId),
+    CheckpointChunk(ChainId, CryptoHash),
 }
 
 /// A clock that can be used to get the current `Timestamp`.
@@ -370,6 +443,7 @@ where
             chain_id,
             user_applications: self.client.user_applications.clone(),
             chain_guard: Some(Arc::new(guard)),
+            remote_query_callers: Vec::new(),
         };
         let client = self.client.client.clone();
         let base_key = bcs::to_bytes(&BaseKey::ChainState(chain_id))?;
@@ -461,9 +535,120 @@ where
         self.write_batch(batch).await
     }
 
+    async fn read_checkpoint(
+        &self,
+        chain_id: ChainId,
+    ) -> Result<Option<CheckpointVote>, ViewError> {
+        let checkpoint_key = bcs::to_bytes(&BaseKey::Checkpoint(chain_id))?;
+        Ok(self.client.client.read_key(&checkpoint_key).await?)
+    }
+
+    async fn read_checkpoint_chunk(
+        &self,
+        chain_id: ChainId,
+        hash: CryptoHash,
+    ) -> Result<CheckpointChunk, ViewError> {
+        let chunk_key = bcs::to_bytes(&BaseKey::CheckpointChunk(chain_id, hash))?;
+        let maybe_chunk = self.client.client.read_key(&chunk_key).await?;
+        maybe_chunk.ok_or_else(|| ViewError::not_found("checkpoint chunk for hash", hash))
+    }
+
+    async fn write_checkpoint(
+        &self,
+        vote: &CheckpointVote,
+        chunks: &[CheckpointChunk],
+    ) -> Result<(), ViewError> {
+        let chain_id = vote.checkpoint.chain_id;
+        let mut batch = Batch::new();
+        if let Some(previous_vote) = self.read_checkpoint(chain_id).await? {
+            for hash in previous_vote.chunk_hashes {
+                let chunk_key = bcs::to_bytes(&BaseKey::CheckpointChunk(chain_id, hash))?;
+                batch.delete_key(chunk_key);
+            }
+        }
+        for (hash, chunk) in vote.chunk_hashes.iter().zip(chunks) {
+            let chunk_key = bcs::to_bytes(&BaseKey::CheckpointChunk(chain_id, *hash))?;
+            batch.put_key_value(chunk_key, chunk)?;
+        }
+        let checkpoint_key = bcs::to_bytes(&BaseKey::Checkpoint(chain_id))?;
+        batch.put_key_value(checkpoint_key, vote)?;
+        self.write_batch(batch).await
+    }
+
     fn wasm_runtime(&self) -> Option<WasmRuntime> {
         self.client.wasm_runtime
     }
+
+    fn fuel_profiler(&self) -> Option<FuelProfiler> {
+        self.client.fuel_profiler.clone()
+    }
+
+    fn native_application(&self, bytecode_id: BytecodeId) -> Option<UserApplicationCode> {
+        self.client
+            .native_applications
+            .get(&bytecode_id)
+            .map(|application| application.clone())
+    }
+
+    async fn query_remote_application(
+        &self,
+        chain_id: ChainId,
+        query: Query,
+        callers: Vec<ChainId>,
+    ) -> Result<RemoteQueryResponse<Response>, ExecutionError> {
+        if callers.contains(&chain_id) {
+            return Err(ExecutionError::RemoteQueryCycle(chain_id));
+        }
+        if callers.len() > MAXIMUM_REMOTE_QUERY_DEPTH {
+            return Err(ExecutionError::ExcessiveRemoteQueryDepth);
+        }
+        tracing::trace!("Acquiring lock on {:?} for a remote query", chain_id);
+        let guard = tokio::time::timeout(
+            REMOTE_QUERY_LOCK_TIMEOUT,
+            self.client.guards.guard(chain_id),
+        )
+        .await
+        .map_err(|_| ExecutionError::RemoteChainBusy(chain_id))?;
+        let runtime_context = ChainRuntimeContext {
+            store: self.clone(),
+            chain_id,
+            user_applications: self.client.user_applications.clone(),
+            chain_guard: Some(Arc::new(guard)),
+            remote_query_callers: callers,
+        };
+        let client = self.client.client.clone();
+        let base_key =
+            bcs::to_bytes(&BaseKey::ChainState(chain_id)).map_err(ViewError::Serialization)?;
+        let context = ContextFromDb::create(client, base_key, runtime_context)
+            .await
+            .map_err(ViewError::from)?;
+        let mut chain = ChainStateView::load(context).await?;
+        if !chain.is_active() {
+            return Err(ExecutionError::UntrackedChain(chain_id));
+        }
+        let height = chain.tip_state.get().next_block_height;
+        let response = chain
+            .execution_state
+            .query_application(&QueryContext { chain_id }, &query)
+            .await?;
+        Ok(RemoteQueryResponse { response, height })
+    }
+}
+
+impl<Client, C> DbStore<Client, C> {
+    /// Registers an `application` to be executed natively, instead of loading the bytecode
+    /// published with `bytecode_id`.
+    ///
+    /// Only applications created after the registration use the native `application`.
+    pub fn register_native_application(
+        &self,
+        bytecode_id: BytecodeId,
+        application: UserApplicationCode,
+    ) {
+        self.client
+            .native_applications
+            .insert(bytecode_id, application);
+    }
 }
 
 impl<Client, C> DbStore<Client, C>
@@ -508,6 +693,8 @@ pub struct ChainRuntimeContext<StoreClient> {
     pub chain_id: ChainId,
     pub user_applications: Arc<DashMap<UserApplicationId, UserApplicationCode>>,
     pub chain_guard: Option<Arc<ChainGuard>>,
+    /// The chains whose services are waiting for a query to this chain, outermost first.
+    pub remote_query_callers: Vec<ChainId>,
 }
 
 #[async_trait]
@@ -536,4 +723,16 @@ where
             }
         }
     }
+
+    async fn query_remote_application(
+        &self,
+        chain_id: ChainId,
+        query: Query,
+    ) -> Result<RemoteQueryResponse<Response>, ExecutionError> {
+        let mut callers = self.remote_query_callers.clone();
+        callers.push(self.chain_id);
+        self.store
+            .query_remote_application(chain_id, query, callers)
+            .await
+    }
 }
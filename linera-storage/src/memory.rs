@@ -2,20 +2,27 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{chain_guards::ChainGuards, DbStore, DbStoreInner};
-use linera_execution::WasmRuntime;
+use dashmap::DashMap;
+use linera_execution::{profiler::FuelProfiler, WasmRuntime};
 use linera_views::memory::{create_memory_client_stream_queries, MemoryClient};
 use std::sync::Arc;
 
 type MemoryStore = DbStoreInner<MemoryClient>;
 
 impl MemoryStore {
-    pub fn new(wasm_runtime: Option<WasmRuntime>, max_stream_queries: usize) -> Self {
+    pub fn new(
+        wasm_runtime: Option<WasmRuntime>,
+        max_stream_queries: usize,
+        fuel_profiler: Option<FuelProfiler>,
+    ) -> Self {
         let client = create_memory_client_stream_queries(max_stream_queries);
         Self {
             client,
             guards: ChainGuards::default(),
             user_applications: Arc::default(),
+            native_applications: DashMap::new(),
             wasm_runtime,
+            fuel_profiler,
         }
     }
 }
@@ -34,7 +41,22 @@ impl MemoryStoreClient<crate::TestClock> {
 impl<C> MemoryStoreClient<C> {
     pub fn new(wasm_runtime: Option<WasmRuntime>, max_stream_queries: usize, clock: C) -> Self {
         Self {
-            client: Arc::new(MemoryStore::new(wasm_runtime, max_stream_queries)),
+            client: Arc::new(MemoryStore::new(wasm_runtime, max_stream_queries, None)),
+            clock,
+        }
+    }
+
+    /// Creates a new store whose applications are instrumented so that the fuel consumed by
+    /// their contracts is attributed to their functions by the `fuel_profiler`.
+    pub fn new_with_fuel_profiler(
+        wasm_runtime: Option<WasmRuntime>,
+        max_stream_queries: usize,
+        clock: C,
+        fuel_profiler: FuelProfiler,
+    ) -> Self {
+        let store = MemoryStore::new(wasm_runtime, max_stream_queries, Some(fuel_profiler));
+        Self {
+            client: Arc::new(store),
             clock,
         }
     }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{chain_guards::ChainGuards, DbStore, DbStoreInner, WallClock};
+use dashmap::DashMap;
 use linera_execution::WasmRuntime;
 use linera_views::{
     common::TableStatus,
@@ -29,7 +30,9 @@ impl RocksDbStoreInner {
             client,
             guards: ChainGuards::default(),
             user_applications: Arc::default(),
+            native_applications: DashMap::new(),
             wasm_runtime,
+            fuel_profiler: None,
         };
         Ok((store, table_status))
     }
@@ -43,7 +46,9 @@ impl RocksDbStoreInner {
             client,
             guards: ChainGuards::default(),
             user_applications: Arc::default(),
+            native_applications: DashMap::new(),
             wasm_runtime,
+            fuel_profiler: None,
         };
         Ok(store)
     }
@@ -57,7 +62,9 @@ impl RocksDbStoreInner {
             client,
             guards: ChainGuards::default(),
             user_applications: Arc::default(),
+            native_applications: DashMap::new(),
             wasm_runtime,
+            fuel_profiler: None,
         };
         Ok((store, table_status))
     }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{chain_guards::ChainGuards, DbStore, DbStoreInner, WallClock};
+use dashmap::DashMap;
 use linera_execution::WasmRuntime;
 use linera_views::{
     common::TableStatus,
@@ -32,7 +33,9 @@ impl ScyllaDbStoreInner {
             client,
             guards: ChainGuards::default(),
             user_applications: Arc::default(),
+            native_applications: DashMap::new(),
             wasm_runtime,
+            fuel_profiler: None,
         };
         Ok((store, table_status))
     }
@@ -46,7 +49,9 @@ impl ScyllaDbStoreInner {
             client,
             guards: ChainGuards::default(),
             user_applications: Arc::default(),
+            native_applications: DashMap::new(),
             wasm_runtime,
+            fuel_profiler: None,
         };
         Ok(store)
     }
@@ -60,7 +65,9 @@ impl ScyllaDbStoreInner {
             client,
             guards: ChainGuards::default(),
             user_applications: Arc::default(),
+            native_applications: DashMap::new(),
             wasm_runtime,
+            fuel_profiler: None,
         };
         Ok((store, table_status))
     }
@@ -269,6 +269,33 @@ fn generate_hash_view_code(input: ItemStruct) -> TokenStream2 {
     }
 }
 
+fn generate_clonable_view_code(input: ItemStruct) -> TokenStream2 {
+    let struct_name = input.ident;
+    let generics = input.generics;
+    let template_vect = get_seq_parameter(generics.clone());
+
+    let (context, context_constraints) = context_and_constraints(&input.attrs, &template_vect);
+
+    let mut clone_quotes = Vec::new();
+    for e in input.fields {
+        let name = e.clone().ident.unwrap();
+        clone_quotes.push(quote! { #name: self.#name.clone_unchecked()?, });
+    }
+
+    quote! {
+        impl #generics linera_views::views::ClonableView<#context> for #struct_name #generics
+        #context_constraints
+        {
+            fn clone_unchecked(&mut self) -> Result<Self, linera_views::views::ViewError> {
+                use linera_views::views::ClonableView;
+                Ok(Self {
+                    #(#clone_quotes)*
+                })
+            }
+        }
+    }
+}
+
 fn generate_crypto_hash_code(input: ItemStruct) -> TokenStream2 {
     let struct_name = input.ident;
     let generics = input.generics;
@@ -689,6 +716,12 @@ pub fn derive_hashable_root_view(input: TokenStream) -> TokenStream {
     stream.into()
 }
 
+#[proc_macro_derive(ClonableView, attributes(view))]
+pub fn derive_clonable_view(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as ItemStruct);
+    generate_clonable_view_code(input).into()
+}
+
 #[proc_macro_derive(GraphQLView, attributes(view))]
 pub fn derive_graphql_view(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ItemStruct);
@@ -933,6 +966,37 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_generate_clonable_view_code() {
+        for context in SpecificContextInfo::test_cases() {
+            let input = context.test_view_input();
+            let output = generate_clonable_view_code(input);
+
+            let SpecificContextInfo {
+                context,
+                constraints,
+                generics,
+                ..
+            } = context;
+
+            let expected = quote!(
+                impl #generics linera_views::views::ClonableView<#context> for TestView #generics
+                #constraints
+                {
+                    fn clone_unchecked(&mut self) -> Result<Self, linera_views::views::ViewError> {
+                        use linera_views::views::ClonableView;
+                        Ok(Self {
+                            register: self.register.clone_unchecked()?,
+                            collection: self.collection.clone_unchecked()?,
+                        })
+                    }
+                }
+            );
+
+            assert_eq!(output.to_string(), expected.to_string());
+        }
+    }
+
     #[test]
     fn test_generate_graphql_code() {
         for context in SpecificContextInfo::test_cases() {
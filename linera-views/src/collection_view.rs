@@ -4,7 +4,7 @@
 use crate::{
     batch::Batch,
     common::{Context, CustomSerialize, HasherOutput, KeyIterable, Update, MIN_VIEW_TAG},
-    views::{HashableView, Hasher, View, ViewError},
+    views::{ClonableView, HashableView, Hasher, View, ViewError},
 };
 use async_lock::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use async_trait::async_trait;
@@ -141,6 +141,35 @@ where
     }
 }
 
+impl<C, W> ClonableView<C> for ByteCollectionView<C, W>
+where
+    C: Context + Clone + Send + Sync,
+    ViewError: From<C::Error>,
+    W: ClonableView<C> + Send + Sync,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        let updates = self
+            .updates
+            .get_mut()
+            .iter_mut()
+            .map(|(index, update)| {
+                let update = match update {
+                    Update::Removed => Update::Removed,
+                    Update::Set(view) => Update::Set(view.clone_unchecked()?),
+                };
+                Ok((index.clone(), update))
+            })
+            .collect::<Result<_, ViewError>>()?;
+        Ok(ByteCollectionView {
+            context: self.context.clone(),
+            was_cleared: self.was_cleared,
+            updates: RwLock::new(updates),
+            stored_hash: self.stored_hash,
+            hash: Mutex::new(*self.hash.get_mut()),
+        })
+    }
+}
+
 impl<C, W> ByteCollectionView<C, W>
 where
     C: Context + Send,
@@ -584,6 +613,21 @@ where
     }
 }
 
+impl<C, I, W> ClonableView<C> for CollectionView<C, I, W>
+where
+    C: Context + Clone + Send + Sync,
+    ViewError: From<C::Error>,
+    I: Send + Sync + Debug + Serialize + DeserializeOwned,
+    W: ClonableView<C> + Send + Sync,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(CollectionView {
+            collection: self.collection.clone_unchecked()?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
 impl<C, I, W> CollectionView<C, I, W>
 where
     C: Context + Send,
@@ -896,6 +940,21 @@ where
     }
 }
 
+impl<C, I, W> ClonableView<C> for CustomCollectionView<C, I, W>
+where
+    C: Context + Clone + Send + Sync,
+    ViewError: From<C::Error>,
+    I: Send + Sync + Debug,
+    W: ClonableView<C> + Send + Sync,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(CustomCollectionView {
+            collection: self.collection.clone_unchecked()?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
 impl<C, I, W> CustomCollectionView<C, I, W>
 where
     C: Context + Send,
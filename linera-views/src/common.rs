@@ -32,7 +32,7 @@ pub type HasherOutputSize = <sha3::Sha3_256 as sha3::digest::OutputSizeUser>::Ou
 #[doc(hidden)]
 pub type HasherOutput = generic_array::GenericArray<u8, HasherOutputSize>;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub(crate) enum Update<T> {
     Removed,
     Set(T),
@@ -4,7 +4,7 @@
 use crate::{
     batch::Batch,
     common::{Context, MIN_VIEW_TAG},
-    views::{HashableView, Hasher, View, ViewError},
+    views::{ClonableView, HashableView, Hasher, View, ViewError},
 };
 use async_lock::Mutex;
 use async_trait::async_trait;
@@ -84,6 +84,24 @@ where
     }
 }
 
+impl<C, W, O> ClonableView<C> for WrappedHashableContainerView<C, W, O>
+where
+    C: Context + Clone + Send + Sync,
+    ViewError: From<C::Error>,
+    W: HashableView<C> + ClonableView<C>,
+    O: Serialize + DeserializeOwned + Send + Sync + Copy + PartialEq,
+    W::Hasher: Hasher<Output = O>,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(WrappedHashableContainerView {
+            context: self.context.clone(),
+            stored_hash: self.stored_hash,
+            hash: Mutex::new(*self.hash.get_mut()),
+            inner: self.inner.clone_unchecked()?,
+        })
+    }
+}
+
 #[async_trait]
 impl<C, W, O> HashableView<C> for WrappedHashableContainerView<C, W, O>
 where
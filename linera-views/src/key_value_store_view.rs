@@ -7,7 +7,7 @@ use crate::{
         get_interval, get_upper_bound, Context, HasherOutput, KeyIterable, KeyValueIterable,
         Update, MIN_VIEW_TAG,
     },
-    views::{HashableView, Hasher, View, ViewError},
+    views::{ClonableView, HashableView, Hasher, View, ViewError},
 };
 use async_lock::Mutex;
 use async_trait::async_trait;
@@ -149,6 +149,23 @@ where
     }
 }
 
+impl<C> ClonableView<C> for KeyValueStoreView<C>
+where
+    C: Context + Clone + Send + Sync,
+    ViewError: From<C::Error>,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(KeyValueStoreView {
+            context: self.context.clone(),
+            was_cleared: self.was_cleared,
+            updates: self.updates.clone(),
+            deleted_prefixes: self.deleted_prefixes.clone(),
+            stored_hash: self.stored_hash,
+            hash: Mutex::new(*self.hash.get_mut()),
+        })
+    }
+}
+
 impl<'a, C> KeyValueStoreView<C>
 where
     C: Send + Context,
@@ -21,7 +21,7 @@ use crate::{
     common::{
         Context, CustomSerialize, HasherOutput, KeyIterable, KeyValueIterable, Update, MIN_VIEW_TAG,
     },
-    views::{HashableView, Hasher, View, ViewError},
+    views::{ClonableView, HashableView, Hasher, View, ViewError},
 };
 use async_lock::Mutex;
 use async_trait::async_trait;
@@ -118,6 +118,23 @@ where
     }
 }
 
+impl<C, V> ClonableView<C> for ByteMapView<C, V>
+where
+    C: Context + Clone + Send + Sync,
+    ViewError: From<C::Error>,
+    V: Clone + Send + Sync + Serialize,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(ByteMapView {
+            context: self.context.clone(),
+            was_cleared: self.was_cleared,
+            updates: self.updates.clone(),
+            stored_hash: self.stored_hash,
+            hash: Mutex::new(*self.hash.get_mut()),
+        })
+    }
+}
+
 impl<C, V> ByteMapView<C, V>
 where
     C: Context,
@@ -611,6 +628,21 @@ where
     }
 }
 
+impl<C, I, V> ClonableView<C> for MapView<C, I, V>
+where
+    C: Context + Clone + Send + Sync,
+    ViewError: From<C::Error>,
+    I: Send + Sync + Serialize,
+    V: Clone + Send + Sync + Serialize,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(MapView {
+            map: self.map.clone_unchecked()?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
 impl<C, I, V> MapView<C, I, V>
 where
     C: Context + Sync,
@@ -976,6 +1008,21 @@ where
     }
 }
 
+impl<C, I, V> ClonableView<C> for CustomMapView<C, I, V>
+where
+    C: Context + Clone + Send + Sync,
+    ViewError: From<C::Error>,
+    I: Send + Sync + CustomSerialize,
+    V: Clone + Send + Sync + Serialize,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(CustomMapView {
+            map: self.map.clone_unchecked()?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
 impl<C, I, V> CustomMapView<C, I, V>
 where
     C: Context + Sync,
@@ -4,7 +4,7 @@
 use crate::{
     batch::Batch,
     common::{from_bytes_opt, Context, HasherOutput, MIN_VIEW_TAG},
-    views::{HashableView, Hasher, View, ViewError},
+    views::{ClonableView, HashableView, Hasher, View, ViewError},
 };
 use async_lock::Mutex;
 use async_trait::async_trait;
@@ -130,6 +130,25 @@ where
     }
 }
 
+impl<C, T> ClonableView<C> for QueueView<C, T>
+where
+    C: Context + Clone + Send + Sync,
+    ViewError: From<C::Error>,
+    T: Clone + Send + Sync + Serialize,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(QueueView {
+            context: self.context.clone(),
+            stored_indices: self.stored_indices.clone(),
+            front_delete_count: self.front_delete_count,
+            was_cleared: self.was_cleared,
+            new_back_values: self.new_back_values.clone(),
+            stored_hash: self.stored_hash,
+            hash: Mutex::new(*self.hash.get_mut()),
+        })
+    }
+}
+
 impl<C, T> QueueView<C, T> {
     fn stored_count(&self) -> usize {
         if self.was_cleared {
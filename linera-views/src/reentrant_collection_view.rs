@@ -4,7 +4,7 @@
 use crate::{
     batch::Batch,
     common::{Context, CustomSerialize, HasherOutput, KeyIterable, Update, MIN_VIEW_TAG},
-    views::{HashableView, Hasher, View, ViewError},
+    views::{ClonableView, HashableView, Hasher, View, ViewError},
 };
 use async_lock::{Mutex, RwLock, RwLockReadGuardArc, RwLockWriteGuardArc};
 use async_trait::async_trait;
@@ -132,6 +132,40 @@ where
     }
 }
 
+impl<C, W> ClonableView<C> for ReentrantByteCollectionView<C, W>
+where
+    C: Context + Clone + Send + Sync,
+    ViewError: From<C::Error>,
+    W: ClonableView<C> + Send + Sync,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        let updates = self
+            .updates
+            .get_mut()
+            .iter()
+            .map(|(index, update)| {
+                let update = match update {
+                    Update::Removed => Update::Removed,
+                    Update::Set(view) => {
+                        let mut view = view
+                            .try_write()
+                            .ok_or_else(|| ViewError::TryLockError(index.clone()))?;
+                        Update::Set(Arc::new(RwLock::new(view.clone_unchecked()?)))
+                    }
+                };
+                Ok((index.clone(), update))
+            })
+            .collect::<Result<_, ViewError>>()?;
+        Ok(ReentrantByteCollectionView {
+            context: self.context.clone(),
+            was_cleared: self.was_cleared,
+            updates: Mutex::new(updates),
+            stored_hash: self.stored_hash,
+            hash: Mutex::new(*self.hash.get_mut()),
+        })
+    }
+}
+
 impl<C: Context, W> ReentrantByteCollectionView<C, W> {
     fn get_index_key(&self, index: &[u8]) -> Vec<u8> {
         self.context.base_tag_index(KeyTag::Index as u8, index)
@@ -735,6 +769,21 @@ where
     }
 }
 
+impl<C, I, W> ClonableView<C> for ReentrantCollectionView<C, I, W>
+where
+    C: Context + Clone + Send + Sync,
+    ViewError: From<C::Error>,
+    I: Send + Sync + Debug + Serialize + DeserializeOwned,
+    W: ClonableView<C> + Send + Sync,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(ReentrantCollectionView {
+            collection: self.collection.clone_unchecked()?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
 impl<C, I, W> ReentrantCollectionView<C, I, W>
 where
     C: Context + Send,
@@ -1096,6 +1145,21 @@ where
     }
 }
 
+impl<C, I, W> ClonableView<C> for ReentrantCustomCollectionView<C, I, W>
+where
+    C: Context + Clone + Send + Sync,
+    ViewError: From<C::Error>,
+    I: Send + Sync + Debug + CustomSerialize,
+    W: ClonableView<C> + Send + Sync,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(ReentrantCustomCollectionView {
+            collection: self.collection.clone_unchecked()?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
 impl<C, I, W> ReentrantCustomCollectionView<C, I, W>
 where
     C: Context + Send,
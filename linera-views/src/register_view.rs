@@ -4,7 +4,7 @@
 use crate::{
     batch::Batch,
     common::{from_bytes_opt, Context, HasherOutput, MIN_VIEW_TAG},
-    views::{HashableView, Hasher, View, ViewError},
+    views::{ClonableView, HashableView, Hasher, View, ViewError},
 };
 use async_lock::Mutex;
 use async_trait::async_trait;
@@ -90,6 +90,23 @@ where
     }
 }
 
+impl<C, T> ClonableView<C> for RegisterView<C, T>
+where
+    C: Context + Clone + Send + Sync,
+    ViewError: From<C::Error>,
+    T: Clone + Default + Send + Sync + Serialize + DeserializeOwned,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(RegisterView {
+            context: self.context.clone(),
+            stored_value: self.stored_value.clone(),
+            update: self.update.clone(),
+            stored_hash: self.stored_hash,
+            hash: Mutex::new(*self.hash.get_mut()),
+        })
+    }
+}
+
 impl<C, T> RegisterView<C, T>
 where
     C: Context,
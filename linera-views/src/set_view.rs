@@ -4,7 +4,7 @@
 use crate::{
     batch::Batch,
     common::{Context, CustomSerialize, HasherOutput, KeyIterable, Update, MIN_VIEW_TAG},
-    views::{HashableView, Hasher, View, ViewError},
+    views::{ClonableView, HashableView, Hasher, View, ViewError},
 };
 use async_lock::Mutex;
 use async_trait::async_trait;
@@ -100,6 +100,22 @@ where
     }
 }
 
+impl<C> ClonableView<C> for ByteSetView<C>
+where
+    C: Context + Clone + Send + Sync,
+    ViewError: From<C::Error>,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(ByteSetView {
+            context: self.context.clone(),
+            was_cleared: self.was_cleared,
+            updates: self.updates.clone(),
+            stored_hash: self.stored_hash,
+            hash: Mutex::new(*self.hash.get_mut()),
+        })
+    }
+}
+
 impl<C> ByteSetView<C>
 where
     C: Context,
@@ -395,6 +411,20 @@ where
     }
 }
 
+impl<C, I> ClonableView<C> for SetView<C, I>
+where
+    C: Context + Clone + Send + Sync,
+    ViewError: From<C::Error>,
+    I: Send + Sync + Serialize,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(SetView {
+            set: self.set.clone_unchecked()?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
 impl<C, I> SetView<C, I>
 where
     C: Context,
@@ -634,6 +664,20 @@ where
     }
 }
 
+impl<C, I> ClonableView<C> for CustomSetView<C, I>
+where
+    C: Context + Clone + Send + Sync,
+    ViewError: From<C::Error>,
+    I: Send + Sync + CustomSerialize,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(CustomSetView {
+            set: self.set.clone_unchecked()?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
 impl<C, I> CustomSetView<C, I>
 where
     C: Context,
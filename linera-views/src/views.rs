@@ -5,7 +5,7 @@ use crate::{batch::Batch, common::HasherOutput};
 use async_trait::async_trait;
 use linera_base::crypto::CryptoHash;
 pub use linera_views_derive::{
-    CryptoHashRootView, CryptoHashView, GraphQLView, HashableView, RootView, View,
+    ClonableView, CryptoHashRootView, CryptoHashView, GraphQLView, HashableView, RootView, View,
 };
 use serde::Serialize;
 use std::{fmt::Debug, io::Write};
@@ -46,6 +46,15 @@ pub trait View<C>: Sized {
     fn delete(self, batch: &mut Batch);
 }
 
+/// A view that can be cloned together with its staged changes, e.g. to save a point to
+/// return to if later changes must be discarded.
+pub trait ClonableView<C>: View<C> {
+    /// Creates a clone of this view, sharing the underlying storage context and the staged
+    /// changes. Only one of the view and its clone may be flushed to storage, otherwise the
+    /// stored data can become inconsistent.
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError>;
+}
+
 /// Main error type for the crate.
 #[derive(Error, Debug)]
 pub enum ViewError {